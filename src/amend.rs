@@ -0,0 +1,113 @@
+use git2::Commit;
+use git2::Repository;
+use log::info;
+
+/// How far apart, in seconds, the author dates of a commit and its amended
+/// version may be.
+///
+/// `git commit --amend` keeps the author date, so this only matters for
+/// `--reset-author` or `--date`, which move it to the time of the amend.
+pub const AMEND_WINDOW_SECONDS: i64 = 60 * 60;
+
+/// Returns the hash of the commit `commit` replaced with `git commit --amend`.
+///
+/// The `HEAD` reflog tells which commit `HEAD` pointed to before `commit`.
+/// That commit is the amended one when [`is_amend_of()`] recognizes it: a
+/// regular commit has it as its parent instead, and resets, checkouts or
+/// rebases onto other commits do not share its parent chain.
+///
+/// # Returns
+///
+/// `None` when `commit` is not in the reflog (e.g. it is not `HEAD`), the
+/// previous commit is gone, or `commit` is not an amend.
+#[must_use]
+pub fn find_amended_commit(git_repo: &Repository, commit: &Commit) -> Option<String> {
+    let reflog = git_repo.reflog("HEAD").ok()?;
+    let entry = reflog.iter().find(|entry| entry.id_new() == commit.id())?;
+    let previous = git_repo.find_commit(entry.id_old()).ok()?;
+    if !is_amend_of(commit, &previous) {
+        return None;
+    }
+
+    info!(
+        "[find_amended_commit()]: {:} amends {:}.",
+        commit.id(),
+        previous.id()
+    );
+    Some(previous.id().to_string())
+}
+
+/// Checks whether `commit` is an amended version of `previous`.
+///
+/// Both commits must differ, have the same parents, and have author dates
+/// at most [`AMEND_WINDOW_SECONDS`] apart.
+#[must_use]
+pub fn is_amend_of(commit: &Commit, previous: &Commit) -> bool {
+    let author_gap = commit.author().when().seconds() - previous.author().when().seconds();
+    commit.id() != previous.id()
+        && commit.parent_ids().eq(previous.parent_ids())
+        && author_gap.abs() <= AMEND_WINDOW_SECONDS
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod amend_tests {
+    use super::*;
+    use crate::testing::FakeRepo;
+    use crate::testing::FAKE_AUTHOR_EMAIL;
+    use crate::testing::FAKE_AUTHOR_NAME;
+    use chrono::TimeZone;
+    use chrono::Utc;
+    use git2::Signature;
+    use git2::Time;
+    use std::error::Error;
+
+    #[test]
+    fn test_find_amended_commit_after_an_amend() -> Result<(), Box<dyn Error>> {
+        let repo = FakeRepo::new()?;
+        repo.commit_at("first", Utc.timestamp_opt(1_736_848_800, 0).unwrap())?;
+        let typo = repo.commit_at("secnod", Utc.timestamp_opt(1_736_852_400, 0).unwrap())?;
+
+        let typo_commit = repo.repo().find_commit(typo)?;
+        let amended = typo_commit.amend(Some("HEAD"), None, None, None, Some("second"), None)?;
+        let amended_commit = repo.repo().find_commit(amended)?;
+
+        assert_eq!(
+            find_amended_commit(repo.repo(), &amended_commit),
+            Some(typo.to_string())
+        );
+        assert_eq!(find_amended_commit(repo.repo(), &typo_commit), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_amend_of_rejects_regular_and_distant_commits() -> Result<(), Box<dyn Error>> {
+        let repo = FakeRepo::new()?;
+        let first = repo.commit_at("first", Utc.timestamp_opt(1_736_848_800, 0).unwrap())?;
+        let second = repo.commit_at("second", Utc.timestamp_opt(1_736_852_400, 0).unwrap())?;
+        let first = repo.repo().find_commit(first)?;
+        let second = repo.repo().find_commit(second)?;
+
+        assert!(!is_amend_of(&second, &first));
+
+        let late = Signature::new(
+            FAKE_AUTHOR_NAME,
+            FAKE_AUTHOR_EMAIL,
+            &Time::new(1_736_900_000, 0),
+        )?;
+        let rewritten = second.amend(
+            None,
+            Some(&late),
+            None,
+            None,
+            Some("second, much later"),
+            None,
+        )?;
+        let rewritten = repo.repo().find_commit(rewritten)?;
+        assert!(!is_amend_of(&rewritten, &second));
+
+        let reworded = second.amend(None, None, None, None, Some("second, reworded"), None)?;
+        assert!(is_amend_of(&repo.repo().find_commit(reworded)?, &second));
+        Ok(())
+    }
+}
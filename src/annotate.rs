@@ -0,0 +1,348 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use log::info;
+
+use crate::atomic_write::write_atomically;
+use crate::diary_parser::parse_diary_table;
+use crate::diary_parser::split_block_anchor;
+use crate::diary_parser::DiaryTable;
+use crate::hydration::ensure_hydrated;
+use crate::hydration::HYDRATION_ATTEMPTS;
+use crate::hydration::HYDRATION_RETRY_DELAY;
+use crate::state::LoggedEntry;
+use crate::state::StateIndex;
+use crate::trash::keep_copy_in_trash;
+use crate::vim_commit::yaml_quote;
+use crate::vim_commit::TableColumn;
+
+/// Shortest hash prefix `annotate` accepts, like `git`'s abbreviated hashes.
+pub const MIN_HASH_PREFIX: usize = 4;
+
+/// Separator between notes added to the same `NOTE` cell or YAML `note`.
+const NOTE_SEPARATOR: &str = "<br/>";
+
+/// Finds the logged entry of the commit whose hash starts with `hash_prefix`.
+///
+/// A commit logged more than once (e.g. with `--allow-duplicates`) gives its
+/// latest entry.
+///
+/// # Errors
+///
+/// Returns the message to show when the prefix is shorter than
+/// [`MIN_HASH_PREFIX`], matches no logged commit, or matches several.
+pub fn find_logged_entry<'a>(
+    entries: &'a [LoggedEntry],
+    hash_prefix: &str,
+) -> Result<&'a LoggedEntry, String> {
+    let hash_prefix = hash_prefix.trim().to_lowercase();
+    if hash_prefix.len() < MIN_HASH_PREFIX {
+        return Err(format!(
+            "The commit hash needs at least {MIN_HASH_PREFIX:} characters, got '{hash_prefix:}'."
+        ));
+    }
+
+    let matches = entries
+        .iter()
+        .filter(|entry| entry.commit_hash.starts_with(&hash_prefix))
+        .collect::<Vec<_>>();
+    let Some(latest) = matches.last() else {
+        return Err(format!("No logged commit matches '{hash_prefix:}'."));
+    };
+    if matches
+        .iter()
+        .any(|entry| entry.commit_hash != latest.commit_hash)
+    {
+        return Err(format!(
+            "'{hash_prefix:}' matches several logged commits, give more of the hash."
+        ));
+    }
+    Ok(latest)
+}
+
+/// Adds `note` to the entry of `commit_hash` in a diary file's content.
+///
+/// Where the note goes depends on how the entry was written:
+///
+/// - Table row - In its `NOTE` cell; a table without that column gets it
+///   appended to the header and separator (Markdown fills the other rows
+///   with empty cells)
+/// - YAML block - In its `note` key
+/// - Anything else mentioning the full hash (e.g. a templated bullet) - As
+///   a nested bullet below that line
+///
+/// A note added where one already is gets appended to it. Line breaks
+/// become `<br/>`, and pipes are escaped in table cells, like in commit
+/// messages.
+///
+/// # Returns
+///
+/// The rewritten content, or `None` if no entry of `commit_hash` is found.
+#[must_use]
+pub fn annotate_entry(content: &str, commit_hash: &str, note: &str) -> Option<String> {
+    let note = note.trim().replace('\n', NOTE_SEPARATOR);
+    let mut lines = content
+        .split_inclusive('\n')
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+
+    if let Some(table) = parse_diary_table(content) {
+        if let Some(row) = table
+            .rows
+            .iter()
+            .find(|row| table.cell(row, TableColumn::CommitHash) == Some(commit_hash))
+        {
+            annotate_table_row(&mut lines, &table, row.line_index, &note);
+            return Some(lines.concat());
+        }
+    }
+
+    let yaml_line = format!(
+        "{:}: {:}",
+        TableColumn::CommitHash.key(),
+        yaml_quote(commit_hash)
+    );
+    if let Some(line_index) = lines.iter().position(|line| line.trim_end() == yaml_line) {
+        annotate_yaml_block(&mut lines, line_index, &note);
+        return Some(lines.concat());
+    }
+
+    let line_index = lines.iter().position(|line| line.contains(commit_hash))?;
+    let line = &lines[line_index];
+    let indent = line[..line.len() - line.trim_start().len()].to_string();
+    let mut insert_at = line_index + 1;
+    while lines
+        .get(insert_at)
+        .is_some_and(|next| next.starts_with(&format!("{indent:}  ")))
+    {
+        insert_at += 1;
+    }
+    if !lines[line_index].ends_with('\n') {
+        lines[line_index].push('\n');
+    }
+    lines.insert(insert_at, format!("{indent:}  - {note:}\n"));
+    Some(lines.concat())
+}
+
+/// Writes `note` to the `NOTE` cell of the table row at `line_index`.
+fn annotate_table_row(lines: &mut [String], table: &DiaryTable, line_index: usize, note: &str) {
+    let note_index = table.column_index(TableColumn::Note).unwrap_or_else(|| {
+        let header = TableColumn::Note.header();
+        let ending = |line: &str| if line.ends_with('\n') { "\n" } else { "" };
+        for (index, cell) in [
+            (table.header_line_index, format!(" {header:} |")),
+            (
+                table.header_line_index + 1,
+                format!("{:}|", "-".repeat(header.len() + 2)),
+            ),
+        ] {
+            let line = &lines[index];
+            lines[index] = format!("{:}{cell:}{:}", line.trim_end(), ending(line));
+        }
+        table.headers.len()
+    });
+
+    let row = table
+        .rows
+        .iter()
+        .find(|row| row.line_index == line_index)
+        .expect("The annotated row belongs to the table");
+    let mut cells = row.cells.clone();
+    if cells.len() <= note_index {
+        cells.resize(note_index + 1, String::new());
+    }
+    let cell = &mut cells[note_index];
+    if !cell.is_empty() {
+        cell.push_str(NOTE_SEPARATOR);
+    }
+    cell.push_str(&note.replace('|', "\\|"));
+
+    let ending = if lines[line_index].ends_with('\n') {
+        "\n"
+    } else {
+        ""
+    };
+    let (_, anchor) = split_block_anchor(&lines[line_index]);
+    lines[line_index] = format!("| {:} |{anchor:}{ending:}", cells.join(" | "));
+}
+
+/// Writes `note` to the `note` key of the YAML block holding `line_index`.
+fn annotate_yaml_block(lines: &mut Vec<String>, line_index: usize, note: &str) {
+    let key = format!("{:}: ", TableColumn::Note.key());
+    let block_end = (line_index..lines.len())
+        .find(|index| lines[*index].trim_end() == "```")
+        .unwrap_or(lines.len());
+    let block_start = (0..line_index)
+        .rev()
+        .find(|index| lines[*index].trim_start().starts_with("```"))
+        .map_or(0, |index| index + 1);
+
+    let existing = (block_start..block_end).find(|index| lines[*index].starts_with(&key));
+    let note = match existing {
+        Some(index) => {
+            let current = lines.remove(index);
+            let current =
+                serde_json::from_str::<String>(current[key.len()..].trim_end()).unwrap_or_default();
+            if current.is_empty() {
+                note.to_string()
+            } else {
+                format!("{current:}{NOTE_SEPARATOR:}{note:}")
+            }
+        }
+        None => note.to_string(),
+    };
+    let block_end = block_end - usize::from(existing.is_some());
+    lines.insert(block_end, format!("{key:}{:}\n", yaml_quote(&note)));
+}
+
+/// Adds `note` to the diary entry of the logged commit starting with
+/// `hash_prefix` (`annotate`).
+///
+/// The diary file is found through the logged-commits index, hydrated if it
+/// is a cloud placeholder, and a copy is kept in the vault trash first when
+/// `trash_dir` is set.
+///
+/// # Returns
+///
+/// The full hash of the annotated commit and the diary file it is in.
+///
+/// # Errors
+///
+/// Returns an error if the hash matches no single logged commit, or if the
+/// diary file cannot be read, has no entry of the commit, or cannot be
+/// rewritten.
+pub fn annotate_logged_commit(
+    index: &StateIndex,
+    hash_prefix: &str,
+    note: &str,
+    trash_dir: Option<&Path>,
+) -> Result<(String, PathBuf), Box<dyn Error>> {
+    if note.trim().is_empty() {
+        return Err("The note is empty.".into());
+    }
+    let entries = index.entries()?;
+    let entry = find_logged_entry(&entries, hash_prefix)?;
+    let diary_path = PathBuf::from(&entry.diary_path);
+
+    ensure_hydrated(&diary_path, HYDRATION_ATTEMPTS, HYDRATION_RETRY_DELAY)?;
+    let content = fs::read_to_string(&diary_path)?;
+    let annotated = annotate_entry(&content, &entry.commit_hash, note).ok_or_else(|| {
+        format!(
+            "No entry of {:} in {:}; `verify` reports such index entries.",
+            entry.commit_hash,
+            diary_path.display()
+        )
+    })?;
+
+    info!(
+        "[annotate_logged_commit()]: Annotating {:} in: {:}",
+        entry.commit_hash,
+        diary_path.display()
+    );
+    if let Some(trash_dir) = trash_dir {
+        keep_copy_in_trash(trash_dir, &diary_path)?;
+    }
+    write_atomically(&diary_path, annotated)?;
+    Ok((entry.commit_hash.clone(), diary_path))
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod annotate_tests {
+    use super::*;
+    use crate::testing::LoggedEntryBuilder;
+    use tempfile::tempdir;
+
+    const TABLE: &str = "# 2025-01-14\n\n| TIME | COMMIT MESSAGE | COMMIT HASH |\n|------|----------------|-------------|\n| 10:00:00 | feat: api | abc123 |\n| 11:00:00 | fix: bug | def456 |\n";
+
+    #[test]
+    fn test_find_logged_entry() {
+        let diary = Path::new("/vault/2025-01-14.md");
+        let entries = vec![
+            LoggedEntryBuilder::new("abc123").in_diary(diary).build(),
+            LoggedEntryBuilder::new("abd456").in_diary(diary).build(),
+            LoggedEntryBuilder::new("abc123")
+                .in_diary(Path::new("/vault/2025-01-15.md"))
+                .build(),
+        ];
+
+        assert_eq!(
+            find_logged_entry(&entries, "ABC1").unwrap().diary_path,
+            "/vault/2025-01-15.md"
+        );
+        assert!(find_logged_entry(&entries, "abc").is_err());
+        assert!(find_logged_entry(&entries, "abd4").is_ok());
+        assert!(find_logged_entry(&entries, "ab12").is_err());
+        let prefixes =
+            ["abc1", "abc2"].map(|hash| LoggedEntryBuilder::new(hash).in_diary(diary).build());
+        assert!(find_logged_entry(&prefixes, "abc1").is_ok());
+    }
+
+    #[test]
+    fn test_annotate_entry_adds_a_note_column() {
+        let annotated = annotate_entry(TABLE, "def456", "caused the Friday | incident").unwrap();
+        assert_eq!(
+            annotated,
+            "# 2025-01-14\n\n| TIME | COMMIT MESSAGE | COMMIT HASH | NOTE |\n|------|----------------|-------------|------|\n| 10:00:00 | feat: api | abc123 |\n| 11:00:00 | fix: bug | def456 | caused the Friday \\| incident |\n"
+        );
+
+        let twice = annotate_entry(&annotated, "def456", "reverted\nlater").unwrap();
+        assert!(twice.ends_with(
+            "| 11:00:00 | fix: bug | def456 | caused the Friday \\| incident<br/>reverted<br/>later |\n"
+        ));
+        let other = annotate_entry(&annotated, "abc123", "first").unwrap();
+        assert!(other.contains("| 10:00:00 | feat: api | abc123 | first |\n"));
+        assert_eq!(annotate_entry(TABLE, "fff000", "note"), None);
+
+        let anchored = TABLE.replace("def456 |\n", "def456 | ^def456\n");
+        assert!(annotate_entry(&anchored, "def456", "note")
+            .unwrap()
+            .ends_with("| 11:00:00 | fix: bug | def456 | note | ^def456\n"));
+    }
+
+    #[test]
+    fn test_annotate_entry_in_yaml_and_template_entries() {
+        let yaml =
+            "```yaml\ncommit_hash: \"abc123\"\n```\n\n```yaml\ncommit_hash: \"def456\"\n```\n\n";
+        let annotated = annotate_entry(yaml, "abc123", "first").unwrap();
+        assert_eq!(
+            annotated,
+            "```yaml\ncommit_hash: \"abc123\"\nnote: \"first\"\n```\n\n```yaml\ncommit_hash: \"def456\"\n```\n\n"
+        );
+        assert!(annotate_entry(&annotated, "abc123", "second")
+            .unwrap()
+            .starts_with("```yaml\ncommit_hash: \"abc123\"\nnote: \"first<br/>second\"\n```\n"));
+
+        let bullets = "- 10:00 feat: api (abc123)\n- 11:00 fix: bug (def456)\n";
+        let annotated = annotate_entry(bullets, "abc123", "first").unwrap();
+        let annotated = annotate_entry(&annotated, "abc123", "second").unwrap();
+        assert_eq!(
+            annotated,
+            "- 10:00 feat: api (abc123)\n  - first\n  - second\n- 11:00 fix: bug (def456)\n"
+        );
+    }
+
+    #[test]
+    fn test_annotate_logged_commit() {
+        let temp_dir = tempdir().unwrap();
+        let diary = temp_dir.path().join("2025-01-14.md");
+        fs::write(&diary, TABLE).unwrap();
+        let index = StateIndex::new(&temp_dir.path().join("state"));
+        index
+            .append(&LoggedEntryBuilder::new("def456").in_diary(&diary).build())
+            .unwrap();
+
+        let (commit_hash, diary_path) =
+            annotate_logged_commit(&index, "def4", "a note", None).unwrap();
+
+        assert_eq!(commit_hash, "def456");
+        assert_eq!(diary_path, diary);
+        assert!(fs::read_to_string(&diary)
+            .unwrap()
+            .contains("| def456 | a note |"));
+        assert!(annotate_logged_commit(&index, "def4", "  ", None).is_err());
+        assert!(annotate_logged_commit(&index, "abc1", "a note", None).is_err());
+    }
+}
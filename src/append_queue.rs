@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::Instant;
+
+use log::info;
+
+/// Debounce window used when none is configured (`[daemon] debounce_ms`).
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Entries waiting to be saved to one diary note.
+#[derive(Debug)]
+struct PendingNote<T> {
+    entries: Vec<T>,
+    last_queued: Instant,
+}
+
+/// Holds diary entries and hands them out per note once events settle down.
+///
+/// A long-running process logging several commits in quick succession (a
+/// rebase, a pull, commits in several repositories) would otherwise rewrite
+/// the same note as each one lands, and every write wakes up Obsidian Sync
+/// and other file watchers. Entries are grouped per target note and handed
+/// out together once no new entry arrived for that note during the debounce
+/// window.
+///
+/// The queue does not write anything: the caller saves the entries it hands
+/// out the same way as any other (for the daemon, oldest first through the
+/// batch save, so rows are inserted at their place in time and duplicates
+/// are skipped).
+///
+/// Time is passed in explicitly so the caller controls the clock.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::append_queue::AppendQueue;
+/// use std::time::{Duration, Instant};
+///
+/// let mut queue = AppendQueue::new(Duration::from_millis(500));
+/// queue.push(&diary_path, first_commit, Instant::now());
+/// queue.push(&diary_path, second_commit, Instant::now());
+///
+/// // Later, e.g. when `queue.next_deadline()` is reached:
+/// for (note, commits) in queue.take_due(Instant::now()) {
+///     save_commit_batch(commits, ...)?;
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AppendQueue<T> {
+    debounce: Duration,
+    pending: BTreeMap<PathBuf, PendingNote<T>>,
+}
+
+impl<T> AppendQueue<T> {
+    /// Creates an empty queue with the given debounce window.
+    #[must_use]
+    pub fn new(debounce: Duration) -> Self {
+        Self {
+            debounce,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Queues `entry` for `note`, restarting that note's window.
+    pub fn push(&mut self, note: &Path, entry: T, now: Instant) {
+        let pending = self
+            .pending
+            .entry(note.to_path_buf())
+            .or_insert_with(|| PendingNote {
+                entries: Vec::new(),
+                last_queued: now,
+            });
+        pending.entries.push(entry);
+        pending.last_queued = now;
+    }
+
+    /// Returns `true` if no entries are waiting.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Returns the earliest time at which a note becomes due, if any.
+    #[must_use]
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pending
+            .values()
+            .map(|pending| pending.last_queued + self.debounce)
+            .min()
+    }
+
+    /// Removes the entries of every note whose window has elapsed at `now`.
+    ///
+    /// # Returns
+    ///
+    /// Each due note with its entries, in the order they were queued.
+    pub fn take_due(&mut self, now: Instant) -> Vec<(PathBuf, Vec<T>)> {
+        let due = self
+            .pending
+            .iter()
+            .filter(|(_, pending)| now.duration_since(pending.last_queued) >= self.debounce)
+            .map(|(note, _)| note.clone())
+            .collect::<Vec<_>>();
+
+        self.take_notes(due)
+    }
+
+    /// Removes every queued entry right away, e.g. before shutting down.
+    pub fn take_all(&mut self) -> Vec<(PathBuf, Vec<T>)> {
+        let notes = self.pending.keys().cloned().collect::<Vec<_>>();
+        self.take_notes(notes)
+    }
+
+    fn take_notes(&mut self, notes: Vec<PathBuf>) -> Vec<(PathBuf, Vec<T>)> {
+        notes
+            .into_iter()
+            .filter_map(|note| {
+                let pending = self.pending.remove(&note)?;
+                info!(
+                    "[AppendQueue::take_notes()]: {:} entry(ies) due for: {:}",
+                    pending.entries.len(),
+                    note.display()
+                );
+                Some((note, pending.entries))
+            })
+            .collect()
+    }
+}
+
+impl<T> Default for AppendQueue<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEBOUNCE)
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod append_queue_tests {
+    use super::*;
+
+    #[test]
+    fn test_take_due_waits_for_the_window_and_groups_entries() {
+        let note = PathBuf::from("Commits/2025-01-14.md");
+        let start = Instant::now();
+        let mut queue = AppendQueue::new(Duration::from_millis(500));
+
+        queue.push(&note, "first", start);
+        queue.push(&note, "second", start + Duration::from_millis(300));
+        assert_eq!(
+            queue.next_deadline(),
+            Some(start + Duration::from_millis(800))
+        );
+
+        assert!(queue
+            .take_due(start + Duration::from_millis(600))
+            .is_empty());
+        assert!(!queue.is_empty());
+
+        assert_eq!(
+            queue.take_due(start + Duration::from_millis(800)),
+            vec![(note, vec!["first", "second"])]
+        );
+        assert!(queue.is_empty());
+        assert_eq!(queue.next_deadline(), None);
+    }
+
+    #[test]
+    fn test_take_all_hands_out_every_note() {
+        let first = PathBuf::from("Commits/2025-01-14.md");
+        let second = PathBuf::from("Commits/2025-01-15.md");
+        let now = Instant::now();
+        let mut queue = AppendQueue::default();
+
+        queue.push(&second, 2, now);
+        queue.push(&first, 1, now);
+        assert!(queue.take_due(now).is_empty());
+
+        assert_eq!(queue.take_all(), vec![(first, vec![1]), (second, vec![2])]);
+        assert!(queue.is_empty());
+    }
+}
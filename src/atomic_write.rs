@@ -0,0 +1,134 @@
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process;
+
+use log::info;
+
+/// Writes `contents` to `path` through a temporary file in the same
+/// directory, renamed into place once complete.
+///
+/// `fs::write` truncates the file first, so a process killed mid-write (or a
+/// full disk) leaves a truncated note that Obsidian and the sync clients pick
+/// up. With a rename the file either keeps its old content or gets the new
+/// one whole. The temporary file is hidden (`.<name>.<pid>.tmp`), so Obsidian
+/// does not index it, and the permissions of an existing file are kept. A
+/// symbolic link is followed, so the link itself stays in place.
+///
+/// # Errors
+///
+/// Returns an error if the temporary file cannot be written or renamed; the
+/// temporary file is removed then.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::atomic_write::write_atomically;
+///
+/// write_atomically(&diary_path, "---\ndate: 2025-01-14\n---\n")?;
+/// ```
+pub fn write_atomically(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let path = path.as_ref();
+    let path = if path.is_symlink() {
+        fs::canonicalize(path)?
+    } else {
+        path.to_path_buf()
+    };
+    let temporary_path = temporary_path(&path)?;
+
+    info!(
+        "[write_atomically()]: Writing through: {:}",
+        temporary_path.display()
+    );
+    let written = write_and_sync(&temporary_path, contents.as_ref()).and_then(|()| {
+        if let Ok(metadata) = fs::metadata(&path) {
+            fs::set_permissions(&temporary_path, metadata.permissions())?;
+        }
+        fs::rename(&temporary_path, &path)
+    });
+    if written.is_err() {
+        let _ = fs::remove_file(&temporary_path);
+    }
+    written
+}
+
+/// Returns the hidden temporary file `path` is written through.
+fn temporary_path(path: &Path) -> io::Result<PathBuf> {
+    let file_name = path.file_name().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{:} has no file name to write to", path.display()),
+        )
+    })?;
+    Ok(path.with_file_name(format!(
+        ".{:}.{:}.tmp",
+        file_name.to_string_lossy(),
+        process::id()
+    )))
+}
+
+/// Writes and flushes `contents` to disk, so the rename never exposes an
+/// empty file after a crash.
+fn write_and_sync(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod atomic_write_tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_write_atomically_replaces_the_file_whole() {
+        let temp_dir = tempdir().unwrap();
+        let diary = temp_dir.path().join("2025-01-14.md");
+
+        write_atomically(&diary, "first").unwrap();
+        write_atomically(&diary, "second").unwrap();
+
+        assert_eq!(fs::read_to_string(&diary).unwrap(), "second");
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_write_atomically_leaves_the_file_alone_on_failure() {
+        let temp_dir = tempdir().unwrap();
+        let diary = temp_dir.path().join("2025-01-14.md");
+        fs::create_dir(&diary).unwrap();
+
+        assert!(write_atomically(&diary, "rows").is_err());
+
+        assert!(diary.is_dir());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+        assert!(write_atomically(temp_dir.path().join(".."), "rows").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_atomically_keeps_symlinks_and_permissions() {
+        use std::os::unix::fs::symlink;
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempdir().unwrap();
+        let target = temp_dir.path().join("target.md");
+        fs::write(&target, "old").unwrap();
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o640)).unwrap();
+        let link = temp_dir.path().join("2025-01-14.md");
+        symlink(&target, &link).unwrap();
+
+        write_atomically(&link, "new").unwrap();
+
+        assert!(link.is_symlink());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "new");
+        assert_eq!(
+            fs::metadata(&target).unwrap().permissions().mode() & 0o777,
+            0o640
+        );
+    }
+}
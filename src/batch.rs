@@ -0,0 +1,116 @@
+use std::collections::HashSet;
+use std::error::Error;
+
+use git2::Repository;
+use log::info;
+
+use crate::vim_commit::CommitSaver;
+
+/// Parses a list of commit hashes, one per line (e.g. `git rev-list` output).
+///
+/// Only the first word of each line is used, so `git log --format='%H %s'`
+/// works too. Empty lines and `#` comments are skipped, and repeated hashes
+/// are kept once, in order of first appearance.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::batch::parse_commit_list;
+///
+/// assert_eq!(parse_commit_list("abc123 subject\n\nabc123\ndef456\n"), vec!["abc123", "def456"]);
+/// ```
+#[must_use]
+pub fn parse_commit_list(input: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+
+    input
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .filter(|word| !word.starts_with('#'))
+        .filter(|word| seen.insert(word.to_string()))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Builds one [`CommitSaver`] per listed commit, oldest first.
+///
+/// Each revision is resolved in `git_repo` (full or short hashes, or any
+/// other revision Git understands) and recorded on `branch_name`. Entries are
+/// sorted by commit time so the diary rows come out in chronological order,
+/// whatever order the list used (`git rev-list` prints newest first).
+///
+/// # Errors
+///
+/// Returns an error naming the revision if one cannot be resolved to a commit.
+pub fn collect_commit_batch(
+    git_repo: &Repository,
+    revisions: &[String],
+    branch_name: &str,
+) -> Result<Vec<CommitSaver>, Box<dyn Error>> {
+    info!(
+        "[collect_commit_batch()]: Resolving {:} revision(s).",
+        revisions.len()
+    );
+    let mut batch = revisions
+        .iter()
+        .map(|revision| {
+            let commit = git_repo
+                .revparse_single(revision)
+                .and_then(|object| object.peel_to_commit())
+                .map_err(|e| format!("Could not resolve {revision:} to a commit: {e:}"))?;
+            Ok(CommitSaver::from_commit(git_repo, &commit, branch_name)?)
+        })
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+    batch.sort_by_key(|commit_saver| commit_saver.entry.datetime);
+    Ok(batch)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod batch_tests {
+    use super::*;
+    use crate::testing::FakeRepo;
+    use chrono::TimeZone;
+    use chrono::Utc;
+
+    #[test]
+    fn test_parse_commit_list() {
+        let input = "abc123 first subject\n\n  def456\n# comment\nabc123\n";
+
+        assert_eq!(parse_commit_list(input), vec!["abc123", "def456"]);
+        assert!(parse_commit_list("").is_empty());
+    }
+
+    #[test]
+    fn test_collect_commit_batch_sorts_oldest_first() -> Result<(), Box<dyn Error>> {
+        let repo = FakeRepo::new()?;
+        let first = repo
+            .commit_at("first", Utc.timestamp_opt(1_736_848_800, 0).unwrap())?
+            .to_string();
+        let second = repo
+            .commit_at("second", Utc.timestamp_opt(1_736_852_400, 0).unwrap())?
+            .to_string();
+
+        // `git rev-list` order: newest first, abbreviated hashes are fine.
+        let revisions = vec![second[..10].to_string(), first.clone()];
+        let batch = collect_commit_batch(repo.repo(), &revisions, "feature")?;
+
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[0].entry.hash, first);
+        assert_eq!(batch[1].entry.message, "second");
+        assert_eq!(batch[1].entry.branch, "feature");
+        Ok(())
+    }
+
+    #[test]
+    fn test_collect_commit_batch_unknown_revision_errors() -> Result<(), Box<dyn Error>> {
+        let repo = FakeRepo::new()?;
+        repo.commit_at("only", Utc.timestamp_opt(1_736_848_800, 0).unwrap())?;
+
+        let result = collect_commit_batch(repo.repo(), &["deadbeef".to_string()], "main");
+
+        assert!(result.unwrap_err().to_string().contains("deadbeef"));
+        Ok(())
+    }
+}
@@ -0,0 +1,584 @@
+use std::error::Error;
+use std::fs;
+use std::io::BufRead;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use log::info;
+use serde::Deserialize;
+
+use crate::atomic_write::write_atomically;
+use crate::config::set_proper_home_dir;
+use crate::config::InitArgs;
+use crate::config_format::ConfigFormat;
+
+/// Vault root written to a default config when no vault was found.
+pub const PLACEHOLDER_VAULT_ROOT: &str = "~/Documents/Obsidian";
+
+/// Where the diary files go in a new config, relative to the vault root.
+pub const DEFAULT_COMMIT_PATH: &str = "Diaries/Commits";
+
+/// Directories, relative to the home directory, searched for a vault (an
+/// `.obsidian` directory in them or in one of their subdirectories) when
+/// Obsidian's own vault list is not available.
+const VAULT_SEARCH_DIRS: [&str; 4] = [
+    "Documents/Obsidian",
+    "Obsidian",
+    "Documents",
+    "Library/Mobile Documents/iCloud~md~obsidian/Documents",
+];
+
+/// Obsidian's list of known vaults (`obsidian.json` in its config directory).
+#[derive(Deserialize)]
+struct ObsidianRegistry {
+    #[serde(default)]
+    vaults: std::collections::HashMap<String, RegisteredVault>,
+}
+
+#[derive(Deserialize)]
+struct RegisteredVault {
+    path: PathBuf,
+    #[serde(default)]
+    ts: u64,
+    #[serde(default)]
+    open: bool,
+}
+
+/// Picks the vault to log into from Obsidian's `obsidian.json`: the open
+/// vault, else the most recently opened one.
+///
+/// # Returns
+///
+/// `None` if the content is not a vault list or lists no vault.
+#[must_use]
+pub fn vault_from_obsidian_registry(registry_json: &str) -> Option<PathBuf> {
+    let registry: ObsidianRegistry = serde_json::from_str(registry_json).ok()?;
+    registry
+        .vaults
+        .into_values()
+        .max_by_key(|vault| (vault.open, vault.ts))
+        .map(|vault| vault.path)
+}
+
+/// Returns the first directory of `candidates` that is a vault, or that has
+/// a vault as a direct subdirectory (e.g. `~/Documents/Obsidian/Notes`).
+#[must_use]
+pub fn find_vault_in(candidates: &[PathBuf]) -> Option<PathBuf> {
+    candidates.iter().find_map(|candidate| {
+        if candidate.join(".obsidian").is_dir() {
+            return Some(candidate.clone());
+        }
+        let mut vaults = fs::read_dir(candidate)
+            .ok()?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.join(".obsidian").is_dir())
+            .collect::<Vec<_>>();
+        vaults.sort();
+        vaults.into_iter().next()
+    })
+}
+
+/// Looks for the user's Obsidian vault: first in Obsidian's own vault list,
+/// then in the usual vault locations under the home directory.
+#[must_use]
+pub fn detect_vault() -> Option<PathBuf> {
+    let registered = dirs::config_dir()
+        .and_then(|config_dir| fs::read_to_string(config_dir.join("obsidian/obsidian.json")).ok())
+        .and_then(|registry| vault_from_obsidian_registry(&registry))
+        .filter(|vault| vault.is_dir());
+    if registered.is_some() {
+        return registered;
+    }
+    let home = dirs::home_dir()?;
+    let candidates = VAULT_SEARCH_DIRS
+        .iter()
+        .map(|dir| home.join(dir))
+        .collect::<Vec<_>>();
+    find_vault_in(&candidates)
+}
+
+/// Renders a commented default config with the required sections, logging
+/// into `vault_root` (or [`PLACEHOLDER_VAULT_ROOT`], flagged for editing).
+#[must_use]
+pub fn default_config_ini(vault_root: Option<&Path>) -> String {
+    let root_path_dir = vault_root.map_or_else(
+        || format!("{PLACEHOLDER_VAULT_ROOT:}\n; ^ No vault was found: point this at your Obsidian vault."),
+        |vault_root| vault_root.display().to_string(),
+    );
+    render_config_ini(&root_path_dir, DEFAULT_COMMIT_PATH, "created on first run")
+}
+
+/// Renders a commented config with the required sections.
+fn render_config_ini(root_path_dir: &str, commit_path: &str, created_by: &str) -> String {
+    format!(
+        "; rusty-commit-saver configuration, {created_by:}.
+; Optional sections ([table], [goals], [webhook], ...) are described in the README.
+
+[obsidian]
+; The Obsidian vault the diary is written to.
+root_path_dir = {root_path_dir:}
+; Where the diary files go, relative to the vault root.
+commit_path = {commit_path:}
+
+[templates]
+; Path of a day's diary file under commit_path (chrono format).
+commit_date_path = %Y/%m-%B/%F.md
+; Time format of the TIME column (chrono format).
+commit_datetime = %H:%M:%S
+"
+    )
+}
+
+/// Writes the default config to `config_path`, creating its directories.
+///
+/// # Errors
+///
+/// Returns an error if the directories cannot be created, or if the file
+/// cannot be written (including when it already exists).
+pub fn write_default_config(
+    config_path: &Path,
+    vault_root: Option<&Path>,
+) -> Result<String, Box<dyn Error>> {
+    if let Some(parent) = config_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let content = default_config_ini(vault_root);
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(config_path)?;
+    file.write_all(content.as_bytes())?;
+    info!(
+        "[write_default_config()]: Wrote a default config to: {:}",
+        config_path.display()
+    );
+    Ok(content)
+}
+
+/// Creates the missing config file at `config_path` on first run.
+///
+/// Interactively, the user is asked (on `output`, answering on `input`)
+/// whether to create a config logging into the detected `vault`, or for the
+/// vault path when none was detected. Otherwise (e.g. from a Git hook) a
+/// commented default config is written for the user to review, and nothing
+/// is logged this time.
+///
+/// # Returns
+///
+/// - `Ok(content)` - The config was created and can be used right away
+/// - `Err(message)` - Nothing to run with: what happened and what to do next
+///
+/// # Errors
+///
+/// Returns the message to show the user when the config was declined, could
+/// not be written, or was written without being confirmed.
+pub fn bootstrap_config(
+    config_path: &Path,
+    vault: Option<PathBuf>,
+    interactive: bool,
+    input: &mut dyn BufRead,
+    output: &mut dyn Write,
+) -> Result<String, String> {
+    let shown_path = config_path.display();
+    if !interactive {
+        write_default_config(config_path, vault.as_deref()).map_err(|e| {
+            format!("No config at {shown_path:}, and a default one could not be written: {e:}")
+        })?;
+        return Err(format!(
+            "No config was found, so a default one was written to {shown_path:}. Review it, then commit again."
+        ));
+    }
+
+    let read_answer = |input: &mut dyn BufRead| {
+        let mut answer = String::new();
+        input
+            .read_line(&mut answer)
+            .map(|_| answer.trim().to_string())
+    };
+    let vault = if let Some(vault) = vault {
+        let question = format!(
+            "No config at {shown_path:}. Create one logging into {:}?",
+            vault.display()
+        );
+        confirm(&question, true, input, output).then_some(vault)
+    } else {
+        let _ = write!(
+            output,
+            "No config at {shown_path:} and no Obsidian vault found. Path of your vault (empty to cancel): "
+        );
+        let _ = output.flush();
+        read_answer(input)
+            .ok()
+            .filter(|answer| !answer.is_empty())
+            .map(PathBuf::from)
+    };
+    let Some(vault) = vault else {
+        return Err(format!(
+            "No config was created. Run `rusty-commit-saver config init` to create {shown_path:}."
+        ));
+    };
+
+    let content = write_default_config(config_path, Some(&vault))
+        .map_err(|e| format!("Could not write {shown_path:}: {e:}"))?;
+    let _ = writeln!(output, "Created {shown_path:}.");
+    Ok(content)
+}
+
+/// Asks a yes/no `question` on `output` and reads the answer from `input`.
+///
+/// An empty answer (or no answer at all) picks `default`.
+pub fn confirm(
+    question: &str,
+    default: bool,
+    input: &mut dyn BufRead,
+    output: &mut dyn Write,
+) -> bool {
+    let choices = if default { "[Y/n]" } else { "[y/N]" };
+    let _ = write!(output, "{question:} {choices:} ");
+    let _ = output.flush();
+    let mut answer = String::new();
+    let _ = input.read_line(&mut answer);
+    match answer.trim().to_lowercase().as_str() {
+        "" => default,
+        answer => answer == "y" || answer == "yes",
+    }
+}
+
+/// Reads the answer to `prompt`, or `default` when it is empty.
+fn ask(
+    prompt: &str,
+    default: Option<String>,
+    input: &mut dyn BufRead,
+    output: &mut dyn Write,
+) -> Option<String> {
+    match &default {
+        Some(default) => {
+            let _ = write!(output, "{prompt:} [{default:}]: ");
+        }
+        None => {
+            let _ = write!(output, "{prompt:}: ");
+        }
+    }
+    let _ = output.flush();
+    let mut answer = String::new();
+    let _ = input.read_line(&mut answer);
+    let answer = answer.trim();
+    if answer.is_empty() {
+        default
+    } else {
+        Some(answer.to_string())
+    }
+}
+
+/// Writes the config file of `config init` to `config_path`, creating its
+/// directories.
+///
+/// The vault and diary folder come from `args`; in a terminal (`interactive`)
+/// the missing ones are asked for on `output`, offering the `detected_vault`
+/// and [`DEFAULT_COMMIT_PATH`]. Otherwise the detected vault and default
+/// folder are used.
+///
+/// # Returns
+///
+/// The content written.
+///
+/// # Errors
+///
+/// Returns an error if `config_path` is not an INI file, if it exists and
+/// `args.force` is not set, if no vault was given nor found, if the vault is
+/// not a directory, or if the file cannot be written.
+pub fn init_config(
+    config_path: &Path,
+    args: &InitArgs,
+    detected_vault: Option<PathBuf>,
+    interactive: bool,
+    input: &mut dyn BufRead,
+    output: &mut dyn Write,
+) -> Result<String, Box<dyn Error>> {
+    let shown_path = config_path.display();
+    let format = ConfigFormat::from_path(config_path);
+    if format != ConfigFormat::Ini {
+        return Err(format!("`config init` writes INI, not {format:}: {shown_path:}").into());
+    }
+    if config_path.exists() && !args.force {
+        return Err(format!("{shown_path:} already exists, pass --force to replace it").into());
+    }
+
+    let detected_vault = detected_vault.map(|vault| vault.display().to_string());
+    let vault = match &args.vault {
+        Some(vault) => Some(vault.display().to_string()),
+        None if interactive => ask("Path of your Obsidian vault", detected_vault, input, output),
+        None => detected_vault,
+    }
+    .ok_or("No Obsidian vault was found, pass it with --vault")?;
+    let vault = PathBuf::from(if vault.starts_with('~') {
+        set_proper_home_dir(&vault)
+    } else {
+        vault
+    });
+    if !vault.is_dir() {
+        return Err(format!("The vault {:} is not a directory", vault.display()).into());
+    }
+    if !vault.join(".obsidian").is_dir() {
+        let _ = writeln!(
+            output,
+            "Note: {:} has no .obsidian directory, Obsidian has not opened it as a vault yet.",
+            vault.display()
+        );
+    }
+
+    let commit_path = match &args.commit_path {
+        Some(commit_path) => commit_path.clone(),
+        None if interactive => ask(
+            "Folder of the diary files in the vault",
+            Some(DEFAULT_COMMIT_PATH.to_string()),
+            input,
+            output,
+        )
+        .unwrap_or_else(|| DEFAULT_COMMIT_PATH.to_string()),
+        None => DEFAULT_COMMIT_PATH.to_string(),
+    };
+
+    if let Some(parent) = config_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let content = render_config_ini(
+        &vault.display().to_string(),
+        &commit_path,
+        "created by `config init`",
+    );
+    write_atomically(config_path, &content)?;
+    info!(
+        "[init_config()]: Wrote the config to: {:}",
+        config_path.display()
+    );
+    let _ = writeln!(output, "Created {shown_path:}.");
+    Ok(content)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod bootstrap_tests {
+    use super::*;
+    use crate::config::parse_ini_content;
+    use std::io::Cursor;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_vault_from_obsidian_registry() {
+        let registry = r#"{"vaults": {
+            "a": {"path": "/home/me/Old", "ts": 1700000000000},
+            "b": {"path": "/home/me/Notes", "ts": 1600000000000, "open": true},
+            "c": {"path": "/home/me/Recent", "ts": 1800000000000}
+        }}"#;
+
+        assert_eq!(
+            vault_from_obsidian_registry(registry),
+            Some(PathBuf::from("/home/me/Notes"))
+        );
+        assert_eq!(
+            vault_from_obsidian_registry(&registry.replace(", \"open\": true", "")),
+            Some(PathBuf::from("/home/me/Recent"))
+        );
+        assert_eq!(vault_from_obsidian_registry("{}"), None);
+        assert_eq!(vault_from_obsidian_registry("not json"), None);
+    }
+
+    #[test]
+    fn test_find_vault_in() {
+        let home = tempdir().unwrap();
+        let documents = home.path().join("Documents");
+        fs::create_dir_all(documents.join("Notes/.obsidian")).unwrap();
+        fs::create_dir_all(documents.join("Taxes")).unwrap();
+
+        assert_eq!(
+            find_vault_in(&[home.path().join("Obsidian"), documents.clone()]),
+            Some(documents.join("Notes"))
+        );
+        assert_eq!(find_vault_in(&[home.path().to_path_buf()]), None);
+    }
+
+    #[test]
+    fn test_default_config_ini_is_valid() {
+        let content = default_config_ini(Some(Path::new("/home/me/Notes")));
+        let config = parse_ini_content(&content).unwrap();
+
+        assert_eq!(
+            config.get("obsidian", "root_path_dir").as_deref(),
+            Some("/home/me/Notes")
+        );
+        assert_eq!(
+            config.get("templates", "commit_date_path").as_deref(),
+            Some("%Y/%m-%B/%F.md")
+        );
+        let placeholder = parse_ini_content(&default_config_ini(None)).unwrap();
+        assert_eq!(
+            placeholder.get("obsidian", "root_path_dir").as_deref(),
+            Some(PLACEHOLDER_VAULT_ROOT)
+        );
+    }
+
+    #[test]
+    fn test_bootstrap_config_non_interactive_writes_and_stops() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("rcs/rusty-commit-saver.ini");
+
+        let message = bootstrap_config(
+            &config_path,
+            None,
+            false,
+            &mut Cursor::new(""),
+            &mut Vec::new(),
+        )
+        .unwrap_err();
+
+        assert!(message.contains("a default one was written"));
+        assert_eq!(
+            fs::read_to_string(&config_path).unwrap(),
+            default_config_ini(None)
+        );
+    }
+
+    #[test]
+    fn test_bootstrap_config_interactive() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("rusty-commit-saver.ini");
+        let vault = dir.path().join("Notes");
+
+        let declined = bootstrap_config(
+            &config_path,
+            Some(vault.clone()),
+            true,
+            &mut Cursor::new("n\n"),
+            &mut Vec::new(),
+        );
+        assert!(declined.is_err());
+        assert!(!config_path.exists());
+
+        let mut output = Vec::new();
+        let content = bootstrap_config(
+            &config_path,
+            None,
+            true,
+            &mut Cursor::new(format!("{:}\n", vault.display())),
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(content, default_config_ini(Some(&vault)));
+        assert_eq!(fs::read_to_string(&config_path).unwrap(), content);
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains("no Obsidian vault found"));
+    }
+
+    #[test]
+    fn test_init_config_with_flags() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("rcs/rusty-commit-saver.ini");
+        let vault = dir.path().join("Notes");
+        let args = InitArgs {
+            vault: Some(vault.clone()),
+            commit_path: Some("Work/Commits".to_string()),
+            ..InitArgs::default()
+        };
+        let init = |args: &InitArgs| {
+            init_config(
+                &config_path,
+                args,
+                None,
+                false,
+                &mut Cursor::new(""),
+                &mut Vec::new(),
+            )
+        };
+
+        let missing = init(&args).unwrap_err().to_string();
+        assert!(missing.contains("is not a directory"), "{missing:}");
+        assert!(!config_path.exists());
+
+        fs::create_dir_all(vault.join(".obsidian")).unwrap();
+        let content = init(&args).unwrap();
+        let config = parse_ini_content(&fs::read_to_string(&config_path).unwrap()).unwrap();
+        assert_eq!(
+            config.get("obsidian", "root_path_dir"),
+            Some(vault.display().to_string())
+        );
+        assert_eq!(
+            config.get("obsidian", "commit_path").as_deref(),
+            Some("Work/Commits")
+        );
+        assert!(content.contains("created by `config init`"));
+
+        let existing = init(&args).unwrap_err().to_string();
+        assert!(existing.contains("--force"), "{existing:}");
+        init(&InitArgs {
+            force: true,
+            commit_path: None,
+            ..args.clone()
+        })
+        .unwrap();
+        assert!(fs::read_to_string(&config_path)
+            .unwrap()
+            .contains("commit_path = Diaries/Commits"));
+
+        let toml_path = dir.path().join("rusty-commit-saver.toml");
+        assert!(init_config(
+            &toml_path,
+            &args,
+            None,
+            false,
+            &mut Cursor::new(""),
+            &mut Vec::new()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_init_config_asks_for_what_the_flags_do_not_give() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("rusty-commit-saver.ini");
+        let detected = dir.path().join("Detected");
+        fs::create_dir_all(&detected).unwrap();
+
+        let mut output = Vec::new();
+        init_config(
+            &config_path,
+            &InitArgs::default(),
+            Some(detected.clone()),
+            true,
+            &mut Cursor::new("\nJournal\n"),
+            &mut output,
+        )
+        .unwrap();
+
+        let config = parse_ini_content(&fs::read_to_string(&config_path).unwrap()).unwrap();
+        assert_eq!(
+            config.get("obsidian", "root_path_dir"),
+            Some(detected.display().to_string())
+        );
+        assert_eq!(
+            config.get("obsidian", "commit_path").as_deref(),
+            Some("Journal")
+        );
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(&format!(
+            "Path of your Obsidian vault [{:}]: ",
+            detected.display()
+        )));
+        assert!(output.contains("has no .obsidian directory"));
+        assert!(!confirm(
+            "Install?",
+            false,
+            &mut Cursor::new("\n"),
+            &mut Vec::new()
+        ));
+        assert!(confirm(
+            "Install?",
+            false,
+            &mut Cursor::new("yes\n"),
+            &mut Vec::new()
+        ));
+    }
+}
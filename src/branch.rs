@@ -0,0 +1,76 @@
+/// Turns a branch name into a slug usable in Obsidian tags.
+///
+/// `/` starts a nested tag in Obsidian and most punctuation ends a tag, so
+/// every character other than a letter, a digit, `-` or `_` becomes a `-`.
+/// Runs of `-` are collapsed and trimmed from both ends.
+///
+/// # Returns
+///
+/// The slug, or `None` when nothing is left (e.g. an empty branch name).
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::branch::branch_slug;
+///
+/// assert_eq!(branch_slug("feature/login-page").as_deref(), Some("feature-login-page"));
+/// assert_eq!(branch_slug("fix/#42 crash").as_deref(), Some("fix-42-crash"));
+/// assert_eq!(branch_slug("///"), None);
+/// ```
+#[must_use]
+pub fn branch_slug(branch: &str) -> Option<String> {
+    let mut slug = String::with_capacity(branch.len());
+    for character in branch.chars() {
+        let character = if character.is_alphanumeric() || character == '_' {
+            character
+        } else {
+            '-'
+        };
+        if character == '-' && (slug.is_empty() || slug.ends_with('-')) {
+            continue;
+        }
+        slug.push(character);
+    }
+
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        None
+    } else {
+        Some(slug.to_string())
+    }
+}
+
+/// Returns the frontmatter tag of a branch slug, e.g. `#branch/feature-login-page`.
+#[must_use]
+pub fn branch_tag(slug: &str) -> String {
+    format!("#branch/{slug:}")
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod branch_tests {
+    use super::*;
+
+    #[test]
+    fn test_branch_slug() {
+        assert_eq!(
+            branch_slug("feature/login-page").as_deref(),
+            Some("feature-login-page")
+        );
+        assert_eq!(
+            branch_slug("--user/ÉTÉ//hot fix.2--").as_deref(),
+            Some("user-ÉTÉ-hot-fix-2")
+        );
+        assert_eq!(branch_slug("release_1.0").as_deref(), Some("release_1-0"));
+        assert_eq!(branch_slug(""), None);
+        assert_eq!(branch_slug("/-/"), None);
+    }
+
+    #[test]
+    fn test_branch_tag() {
+        assert_eq!(
+            branch_tag("feature-login-page"),
+            "#branch/feature-login-page"
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use chrono::DateTime;
+use chrono::Utc;
+use git2::Commit;
+use git2::Repository;
+use log::info;
+
+use crate::atomic_write::write_atomically;
+use crate::diary_lock::DiaryLock;
+use crate::diary_lock::LOCK_TIMEOUT;
+use crate::diary_parser::parse_diary_table;
+use crate::git_stats::DiffStats;
+use crate::hydration::ensure_hydrated;
+use crate::hydration::HYDRATION_ATTEMPTS;
+use crate::hydration::HYDRATION_RETRY_DELAY;
+use crate::links::wikilink;
+use crate::state::LoggedEntry;
+
+/// The life of a branch, written to the day's diary when a merge closes it
+/// out (`[branches] merge_summary`).
+///
+/// Rendered as an Obsidian callout:
+///
+/// ```text
+/// > [!summary] Branch `feature/login` merged
+/// > - First commit: 2025-01-10
+/// > - Commits: 3
+/// > - Changes: +120/−4 (3 files)
+/// > - Days: [[Commits/2025-01-10]], [[Commits/2025-01-13]]
+/// ```
+///
+/// # Fields
+///
+/// - `branch` - The branch the merged commits were logged on
+/// - `first_commit` - When its first logged commit was made
+/// - `commits` - How many of its commits were logged
+/// - `diff_stats` - What the whole branch changed, from where it forked
+/// - `diary_links` - Links to the diary files holding its commits, oldest first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchSummary {
+    pub branch: String,
+    pub first_commit: DateTime<Utc>,
+    pub commits: usize,
+    pub diff_stats: DiffStats,
+    pub diary_links: Vec<String>,
+}
+
+impl BranchSummary {
+    /// Builds the summary of a merged branch from the logged-commits index.
+    ///
+    /// `merged` are the hashes the merge brought in (see [`merged_commits()`]);
+    /// the summary only covers those in `entries`, each counted once.
+    ///
+    /// # Returns
+    ///
+    /// `None` if none of the merged commits was logged, e.g. for a branch
+    /// made on another machine.
+    #[must_use]
+    pub fn from_index(
+        entries: &[LoggedEntry],
+        merged: &[String],
+        diff_stats: DiffStats,
+        obsidian_root_path_dir: &Path,
+    ) -> Option<Self> {
+        let merged = merged.iter().map(String::as_str).collect::<HashSet<_>>();
+        let mut seen = HashSet::new();
+        let mut logged = entries
+            .iter()
+            .filter(|entry| merged.contains(entry.commit_hash.as_str()))
+            .filter(|entry| seen.insert(entry.commit_hash.as_str()))
+            .collect::<Vec<_>>();
+        logged.sort_by_key(|entry| entry.commit_datetime);
+        let (first, last) = (logged.first()?, logged.last()?);
+
+        let mut diary_paths = Vec::new();
+        for entry in &logged {
+            if !diary_paths.contains(&entry.diary_path) {
+                diary_paths.push(entry.diary_path.clone());
+            }
+        }
+        let diary_links = diary_paths
+            .iter()
+            .map(|diary_path| {
+                wikilink(obsidian_root_path_dir, Path::new(diary_path), None)
+                    .unwrap_or_else(|| diary_path.clone())
+            })
+            .collect();
+
+        Some(BranchSummary {
+            branch: last.branch.clone(),
+            first_commit: first.commit_datetime,
+            commits: logged.len(),
+            diff_stats,
+            diary_links,
+        })
+    }
+
+    /// Renders the summary as the callout block written to the diary, ending
+    /// with a newline.
+    #[must_use]
+    pub fn render(&self) -> String {
+        let mut block = format!("> [!summary] Branch `{:}` merged\n", self.branch);
+        let _ = writeln!(
+            block,
+            "> - First commit: {:}",
+            self.first_commit.format("%Y-%m-%d")
+        );
+        let _ = writeln!(block, "> - Commits: {:}", self.commits);
+        let _ = writeln!(block, "> - Changes: {:}", self.diff_stats);
+        let _ = writeln!(block, "> - Days: {:}", self.diary_links.join(", "));
+        block
+    }
+}
+
+/// Lists the commits a merge brought in: those reachable from its second
+/// parent but not from its first.
+///
+/// # Returns
+///
+/// The hashes, newest first; empty if `merge` is not a merge commit.
+///
+/// # Errors
+///
+/// Returns an error if the history cannot be walked.
+pub fn merged_commits(git_repo: &Repository, merge: &Commit) -> Result<Vec<String>, git2::Error> {
+    if merge.parent_count() < 2 {
+        return Ok(Vec::new());
+    }
+    let mut revwalk = git_repo.revwalk()?;
+    revwalk.push(merge.parent_id(1)?)?;
+    revwalk.hide(merge.parent_id(0)?)?;
+    revwalk.map(|oid| oid.map(|oid| oid.to_string())).collect()
+}
+
+/// Computes what a merged branch changed as a whole: its tip against the
+/// commit it forked from.
+///
+/// # Errors
+///
+/// Returns an error if `merge` is not a merge commit, or if the fork point,
+/// a tree or the diff cannot be read.
+pub fn branch_diff_stats(git_repo: &Repository, merge: &Commit) -> Result<DiffStats, git2::Error> {
+    let tip = merge.parent(1)?;
+    let fork_point = git_repo.merge_base(merge.parent_id(0)?, tip.id())?;
+    let fork_tree = git_repo.find_commit(fork_point)?.tree()?;
+    let diff = git_repo.diff_tree_to_tree(Some(&fork_tree), Some(&tip.tree()?), None)?;
+    let stats = diff.stats()?;
+    Ok(DiffStats {
+        files_changed: stats.files_changed(),
+        insertions: stats.insertions(),
+        deletions: stats.deletions(),
+    })
+}
+
+/// Places a rendered summary `block` in a diary file's content.
+///
+/// In a diary holding a commit table, the block goes right above the table,
+/// so the rows appended later stay in it; summaries of the same day stack
+/// up in merge order. Other diaries (YAML or templated entries) get it at
+/// the end, like an entry.
+#[must_use]
+pub fn insert_branch_summary(content: &str, block: &str) -> String {
+    let Some(table) = parse_diary_table(content) else {
+        let separator = if content.is_empty() || content.ends_with("\n\n") {
+            ""
+        } else if content.ends_with('\n') {
+            "\n"
+        } else {
+            "\n\n"
+        };
+        return format!("{content:}{separator:}{block:}\n");
+    };
+
+    let mut lines = content.split_inclusive('\n').collect::<Vec<_>>();
+    let spaced = format!("{block:}\n");
+    lines.insert(table.header_line_index, &spaced);
+    lines.concat()
+}
+
+/// Writes `summary` to the diary file at `diary_path`, see
+/// [`insert_branch_summary()`].
+///
+/// The file is hydrated if it is a cloud placeholder and rewritten
+/// atomically under the diary lock, kept in `state_dir` (see
+/// [`DiaryLock::acquire()`]).
+///
+/// # Errors
+///
+/// Returns an error if the lock cannot be taken, or if the diary file cannot
+/// be hydrated, read or written.
+pub fn write_branch_summary(
+    state_dir: Option<&Path>,
+    diary_path: &Path,
+    summary: &BranchSummary,
+) -> Result<(), Box<dyn Error>> {
+    let _lock = DiaryLock::acquire(state_dir, diary_path, LOCK_TIMEOUT)?;
+    ensure_hydrated(diary_path, HYDRATION_ATTEMPTS, HYDRATION_RETRY_DELAY)?;
+    let content = fs::read_to_string(diary_path)?;
+
+    info!(
+        "[write_branch_summary()]: Summarizing branch '{:}' in: {:}",
+        summary.branch,
+        diary_path.display()
+    );
+    write_atomically(
+        diary_path,
+        insert_branch_summary(&content, &summary.render()),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod branch_summary_tests {
+    use super::*;
+    use crate::testing::FakeRepo;
+    use crate::testing::LoggedEntryBuilder;
+    use crate::vim_commit::head_branch_name;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_merged_commits_and_branch_diff_stats() -> Result<(), Box<dyn Error>> {
+        let repo = FakeRepo::new()?;
+        repo.commit_file("README.md", "hello\n", "Initial commit")?;
+        let default_branch = head_branch_name(repo.repo());
+        repo.checkout_new_branch("feature")?;
+        let first = repo.commit_file("src/login.rs", "fn login() {}\n", "feat: login")?;
+        let second = repo.commit_file("README.md", "hello\nlogin\n", "docs: login")?;
+        repo.checkout(&default_branch)?;
+        let merge = repo.merge("feature", "Merge branch 'feature'")?;
+        let merge = repo.repo().find_commit(merge)?;
+
+        assert_eq!(
+            merged_commits(repo.repo(), &merge)?,
+            vec![second.to_string(), first.to_string()]
+        );
+        assert_eq!(
+            branch_diff_stats(repo.repo(), &merge)?,
+            DiffStats {
+                files_changed: 2,
+                insertions: 2,
+                deletions: 0,
+            }
+        );
+        assert!(merged_commits(repo.repo(), &merge.parent(0)?)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_index_summarizes_the_logged_commits() {
+        let vault = Path::new("/vault");
+        let entries = [
+            ("ccc333", 13),
+            ("aaa111", 10),
+            ("bbb222", 13),
+            ("aaa111", 10),
+            ("zzz999", 9),
+        ]
+        .map(|(commit_hash, day)| {
+            LoggedEntryBuilder::new(commit_hash)
+                .at(Utc.with_ymd_and_hms(2025, 1, day, 10, 0, 0).unwrap())
+                .on_branch("feature/login")
+                .in_diary(Path::new(&format!("/vault/Commits/2025-01-{day:02}.md")))
+                .build()
+        });
+        let merged = ["aaa111", "bbb222", "ccc333", "ddd444"].map(String::from);
+        let stats = DiffStats {
+            files_changed: 3,
+            insertions: 120,
+            deletions: 4,
+        };
+
+        let summary = BranchSummary::from_index(&entries, &merged, stats, vault).unwrap();
+        assert_eq!(summary.commits, 3);
+        assert_eq!(
+            summary.render(),
+            "> [!summary] Branch `feature/login` merged\n> - First commit: 2025-01-10\n> - Commits: 3\n> - Changes: +120/\u{2212}4 (3 files)\n> - Days: [[Commits/2025-01-10]], [[Commits/2025-01-13]]\n"
+        );
+        assert_eq!(
+            BranchSummary::from_index(&entries, &["fff000".to_string()], stats, vault),
+            None
+        );
+    }
+
+    #[test]
+    fn test_insert_branch_summary_keeps_the_table_last() {
+        let table = "# 2025-01-14\n\n| TIME | COMMIT HASH |\n|---|---|\n| 10:00:00 | abc123 |\n";
+        let inserted = insert_branch_summary(table, "> [!summary] one\n");
+        assert_eq!(
+            inserted,
+            "# 2025-01-14\n\n> [!summary] one\n\n| TIME | COMMIT HASH |\n|---|---|\n| 10:00:00 | abc123 |\n"
+        );
+        assert!(insert_branch_summary(&inserted, "> [!summary] two\n")
+            .contains("> [!summary] one\n\n> [!summary] two\n\n| TIME"));
+
+        let yaml = "```yaml\ncommit_hash: \"abc123\"\n```\n";
+        assert_eq!(
+            insert_branch_summary(yaml, "> [!summary] one\n"),
+            "```yaml\ncommit_hash: \"abc123\"\n```\n\n> [!summary] one\n\n"
+        );
+    }
+}
@@ -0,0 +1,179 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use log::info;
+
+use crate::shell_command::run_with_timeout;
+
+/// How long the build command may run by default before it is killed.
+///
+/// The check runs inside the post-commit hook, so it is meant for quick
+/// commands such as `cargo check`, not full test suites.
+pub const DEFAULT_BUILD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where the build status of the committed state is read from at save time.
+///
+/// # Variants
+///
+/// - `Command` - A shell command run in the repository; exit status 0 passes
+/// - `File` - A file whose first line is the status written by another tool
+///   (e.g. a watcher or CI script): `pass`/`ok`/`success`/`0` or
+///   `fail`/`failed`/`error`/any other exit code
+///
+/// # Configuration
+///
+/// ```text
+/// [build_status]
+/// command = cargo check --quiet
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildStatusSource {
+    Command(String),
+    File(PathBuf),
+}
+
+/// The configured build status check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildStatusCheck {
+    pub source: BuildStatusSource,
+    /// How long the command may run before it is killed and the status left empty.
+    pub timeout: Duration,
+}
+
+/// Whether the committed state built, shown in the `STATUS` column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildStatus {
+    Pass,
+    Fail,
+}
+
+impl BuildStatus {
+    /// Parses a status file line, see [`BuildStatusSource::File`].
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "pass" | "passed" | "ok" | "success" | "0" => Some(BuildStatus::Pass),
+            "fail" | "failed" | "failure" | "error" => Some(BuildStatus::Fail),
+            code if code.parse::<i32>().is_ok() => Some(BuildStatus::Fail),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for BuildStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildStatus::Pass => write!(f, "✅ pass"),
+            BuildStatus::Fail => write!(f, "❌ fail"),
+        }
+    }
+}
+
+/// Checks the build status from the configured source.
+///
+/// A command runs from the current directory (the repository, in a hook)
+/// with its output discarded; it passes when it exits with status 0.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The command cannot be started, or runs longer than the check's timeout
+///   (it is killed then)
+/// - The file cannot be read, or its first line is not a known status
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::build_status::{check_build_status, BuildStatus, BuildStatusCheck, BuildStatusSource, DEFAULT_BUILD_TIMEOUT};
+///
+/// let check = BuildStatusCheck {
+///     source: BuildStatusSource::Command("cargo check --quiet".to_string()),
+///     timeout: DEFAULT_BUILD_TIMEOUT,
+/// };
+/// assert_eq!(check_build_status(&check)?, BuildStatus::Pass);
+/// ```
+pub fn check_build_status(check: &BuildStatusCheck) -> Result<BuildStatus, Box<dyn Error>> {
+    match &check.source {
+        BuildStatusSource::Command(command) => {
+            info!("[check_build_status()]: Running build command: {command:}");
+            let output = run_with_timeout(command, check.timeout)?
+                .ok_or_else(|| format!("build command timed out: {command:}"))?;
+            Ok(if output.status.success() {
+                BuildStatus::Pass
+            } else {
+                BuildStatus::Fail
+            })
+        }
+        BuildStatusSource::File(path) => {
+            info!(
+                "[check_build_status()]: Reading build status file: {:}",
+                path.display()
+            );
+            let content = fs::read_to_string(path)?;
+            let line = content
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .unwrap_or_default();
+            BuildStatus::from_name(line).ok_or_else(|| {
+                format!("Unknown build status in {:}: {line:}", path.display()).into()
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod build_status_tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn command(command: &str, timeout: Duration) -> BuildStatusCheck {
+        BuildStatusCheck {
+            source: BuildStatusSource::Command(command.to_string()),
+            timeout,
+        }
+    }
+
+    #[test]
+    fn test_check_build_status_from_command_exit_status() {
+        assert_eq!(
+            check_build_status(&command("true", DEFAULT_BUILD_TIMEOUT)).unwrap(),
+            BuildStatus::Pass
+        );
+        assert_eq!(
+            check_build_status(&command("echo broken; exit 101", DEFAULT_BUILD_TIMEOUT)).unwrap(),
+            BuildStatus::Fail
+        );
+        assert!(check_build_status(&command("sleep 5", Duration::from_millis(100))).is_err());
+    }
+
+    #[test]
+    fn test_check_build_status_from_file() {
+        let temp_dir = tempdir().unwrap();
+        let file = temp_dir.path().join("status");
+        let check = BuildStatusCheck {
+            source: BuildStatusSource::File(file.clone()),
+            timeout: DEFAULT_BUILD_TIMEOUT,
+        };
+
+        fs::write(&file, "\n  OK \nrest").unwrap();
+        assert_eq!(check_build_status(&check).unwrap(), BuildStatus::Pass);
+
+        fs::write(&file, "2\n").unwrap();
+        assert_eq!(check_build_status(&check).unwrap(), BuildStatus::Fail);
+
+        fs::write(&file, "building\n").unwrap();
+        assert!(check_build_status(&check).is_err());
+    }
+
+    #[test]
+    fn test_build_status_display() {
+        assert_eq!(BuildStatus::Pass.to_string(), "✅ pass");
+        assert_eq!(BuildStatus::Fail.to_string(), "❌ fail");
+        assert_eq!(BuildStatus::from_name("Failed"), Some(BuildStatus::Fail));
+    }
+}
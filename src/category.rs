@@ -0,0 +1,154 @@
+use log::info;
+
+/// A rule assigning a category to the repositories matching a pattern.
+///
+/// Patterns are matched against both the repository remote URL and the
+/// directory the entry is recorded from. `*` matches any run of characters
+/// (including `/`); everything else matches literally.
+///
+/// # Configuration
+///
+/// Each key of the `[categories]` section is a category, its value a
+/// comma-separated list of patterns:
+///
+/// ```text
+/// [categories]
+/// work = *github.com/acme/*, ~/work/*
+/// oss = *github.com/chess-seventh/*
+/// learning = ~/courses/*
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryRule {
+    pub category: String,
+    pub pattern: String,
+}
+
+/// Returns `true` if `category` can be used as a frontmatter value and tag.
+///
+/// Allowed are ASCII letters, digits, `-`, `_` and `/` (nested tags), so the
+/// `#category/<name>` tag stays a single Obsidian tag.
+#[must_use]
+pub fn is_valid_category(category: &str) -> bool {
+    !category.is_empty()
+        && category
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '/'))
+}
+
+/// Finds the category of an entry from its remote URL and working directory.
+///
+/// When several rules match, the longest pattern wins, as it is the most
+/// specific one; ties go to the rule listed first.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::category::{resolve_category, CategoryRule};
+///
+/// let rules = vec![
+///     CategoryRule { category: "oss".into(), pattern: "*github.com/*".into() },
+///     CategoryRule { category: "work".into(), pattern: "*github.com/acme/*".into() },
+/// ];
+/// let category = resolve_category(&rules, "https://github.com/acme/api.git", "/home/me/api");
+/// assert_eq!(category.as_deref(), Some("work"));
+/// ```
+#[must_use]
+pub fn resolve_category(
+    rules: &[CategoryRule],
+    repository_url: &str,
+    folder: &str,
+) -> Option<String> {
+    let mut best: Option<&CategoryRule> = None;
+    for rule in rules {
+        let matches =
+            wildcard_match(&rule.pattern, repository_url) || wildcard_match(&rule.pattern, folder);
+        let more_specific = best.is_none_or(|best| rule.pattern.len() > best.pattern.len());
+        if matches && more_specific {
+            best = Some(rule);
+        }
+    }
+
+    let category = best.map(|rule| rule.category.clone());
+    info!("[resolve_category()]: Category for {repository_url:} in {folder:}: {category:?}");
+    category
+}
+
+/// Matches `text` against a pattern where `*` stands for any run of characters.
+pub(crate) fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let parts = pattern.split('*').collect::<Vec<_>>();
+    let [first, middle @ .., last] = parts.as_slice() else {
+        return pattern == text;
+    };
+
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    for part in middle {
+        let Some(index) = rest.find(part) else {
+            return false;
+        };
+        rest = &rest[index + part.len()..];
+    }
+
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod category_tests {
+    use super::*;
+
+    fn rule(category: &str, pattern: &str) -> CategoryRule {
+        CategoryRule {
+            category: category.to_string(),
+            pattern: pattern.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(!wildcard_match(
+            "*github.com/acme/*",
+            "git@github.com:acme/x.git"
+        ));
+        assert!(wildcard_match(
+            "*github.com/acme/*",
+            "https://github.com/acme/x.git"
+        ));
+        assert!(wildcard_match("/home/me/work/*", "/home/me/work/api"));
+        assert!(wildcard_match("exact", "exact"));
+        assert!(!wildcard_match("exact", "exactly"));
+        assert!(!wildcard_match("a*a", "a"));
+    }
+
+    #[test]
+    fn test_resolve_category_prefers_most_specific_rule() {
+        let rules = vec![
+            rule("oss", "*github.com/*"),
+            rule("work", "*github.com/acme/*"),
+            rule("learning", "/home/me/courses/*"),
+        ];
+
+        assert_eq!(
+            resolve_category(&rules, "https://github.com/acme/api.git", "/src/api"),
+            Some("work".to_string())
+        );
+        assert_eq!(
+            resolve_category(&rules, "https://github.com/me/tool.git", "/src/tool"),
+            Some("oss".to_string())
+        );
+        assert_eq!(
+            resolve_category(&rules, "no_url_set", "/home/me/courses/rust"),
+            Some("learning".to_string())
+        );
+        assert_eq!(resolve_category(&rules, "no_url_set", "/tmp"), None);
+    }
+
+    #[test]
+    fn test_is_valid_category() {
+        assert!(is_valid_category("work"));
+        assert!(is_valid_category("clients/acme-2"));
+        assert!(!is_valid_category("with space"));
+        assert!(!is_valid_category(""));
+    }
+}
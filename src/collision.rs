@@ -0,0 +1,139 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use log::info;
+
+use crate::diary_parser::parse_diary_table;
+use crate::diary_parser::DiaryTable;
+use crate::vim_commit::CommitSaver;
+use crate::vim_commit::TableColumn;
+
+/// What happens to the `TIME` cell of a row logged in the same second as
+/// rows already in the diary table (e.g. commits made by a script).
+///
+/// Rows of the same second are always kept in the order they were appended,
+/// which is the order the commits were made (batch saves log oldest first).
+///
+/// # Variants
+///
+/// - `Keep` - The rows share the same `TIME` cell (default)
+/// - `Sequence` - The second and following rows get a ` #02`, ` #03`, ...
+///   suffix, so rows stay distinguishable and sorting by `TIME` keeps their
+///   order
+///
+/// # Configuration
+///
+/// ```text
+/// [table]
+/// same_second = sequence
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SameSecondPolicy {
+    #[default]
+    Keep,
+    Sequence,
+}
+
+impl SameSecondPolicy {
+    /// Parses the `[table] same_second` value (`keep` or `sequence`, any case).
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "keep" => Some(SameSecondPolicy::Keep),
+            "sequence" => Some(SameSecondPolicy::Sequence),
+            _ => None,
+        }
+    }
+}
+
+/// Appends the sequence suffix to a `TIME` cell, e.g. `10:00:00 #02`.
+///
+/// The number is zero-padded so the suffixed cells sort in order.
+#[must_use]
+pub fn sequenced_time(time: &str, sequence: usize) -> String {
+    format!("{time:} #{sequence:02}")
+}
+
+/// Returns the sequence number of a new row whose `TIME` cell is `time`.
+///
+/// Counts the rows of the table already logged in that second, with or
+/// without a suffix.
+///
+/// # Returns
+///
+/// `None` for the first row of the second, which gets no suffix, and the
+/// row's position in the second (2, 3, ...) otherwise.
+#[must_use]
+pub fn next_time_sequence(table: &DiaryTable, time: &str) -> Option<usize> {
+    let same_second = table
+        .rows
+        .iter()
+        .filter_map(|row| table.cell(row, TableColumn::Time))
+        .filter(|cell| {
+            *cell == time
+                || cell
+                    .strip_prefix(time)
+                    .and_then(|rest| rest.strip_prefix(" #"))
+                    .is_some_and(|sequence| sequence.parse::<usize>().is_ok())
+        })
+        .count();
+    (same_second > 0).then_some(same_second + 1)
+}
+
+/// Numbers the entry among the rows of its second in the diary table of
+/// `diary_path`, setting its [`time_sequence`](CommitSaver::time_sequence).
+///
+/// A missing diary file, or one without a table, leaves the entry unnumbered.
+///
+/// # Errors
+///
+/// Returns an error if the diary file exists but cannot be read.
+pub fn number_same_second_entry(
+    commit_saver: &mut CommitSaver,
+    diary_path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let content = match fs::read_to_string(diary_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    commit_saver.details.time_sequence = parse_diary_table(&content)
+        .and_then(|table| next_time_sequence(&table, &commit_saver.entry_time()));
+    if let Some(sequence) = commit_saver.details.time_sequence {
+        info!("[number_same_second_entry()]: Entry #{sequence:} of its second.");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod collision_tests {
+    use super::*;
+
+    #[test]
+    fn test_next_time_sequence_counts_rows_of_the_same_second() {
+        let table = parse_diary_table(
+            "| TIME | COMMIT HASH |\n|---|---|\n| 10:00:00 | a |\n| 10:00:00 #02 | b |\n| 10:00:01 | c |\n| 10:00:00 #x | d |\n",
+        )
+        .unwrap();
+
+        assert_eq!(next_time_sequence(&table, "10:00:00"), Some(3));
+        assert_eq!(next_time_sequence(&table, "10:00:01"), Some(2));
+        assert_eq!(next_time_sequence(&table, "10:00:02"), None);
+        assert_eq!(sequenced_time("10:00:00 CET", 3), "10:00:00 CET #03");
+    }
+
+    #[test]
+    fn test_same_second_policy_from_name() {
+        assert_eq!(
+            SameSecondPolicy::from_name(" Sequence "),
+            Some(SameSecondPolicy::Sequence)
+        );
+        assert_eq!(
+            SameSecondPolicy::from_name("keep"),
+            Some(SameSecondPolicy::Keep)
+        );
+        assert_eq!(SameSecondPolicy::from_name("suffix"), None);
+    }
+}
@@ -0,0 +1,269 @@
+use std::error::Error;
+
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::git_stats::DiffStats;
+
+/// The data of a logged commit, without how or where it is written.
+///
+/// The entry of a [`CommitSaver`](crate::vim_commit::CommitSaver), which adds what the diary writes about
+/// it. The text fields hold plain text, escaped for Markdown tables only
+/// when rendered, so the entry can be serialized as is (JSON, CSV) or built
+/// by hand in tests, without a repository.
+///
+/// # Fields
+///
+/// - `repository_url` - The remote URL of the repository
+/// - `branch` - The branch the commit was made on
+/// - `hash` - The full commit hash
+/// - `message` - The commit message, see [`normalize_message()`]
+/// - `datetime` - When the commit was made
+/// - `author` / `author_email` - The commit author, if known
+/// - `stats` - The diff stats of the commit, if computed
+/// - `parents` - The full hashes of the parent commits
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::commit_entry::{CommitEntry, JsonRenderer, Renderer};
+/// use rusty_commit_saver::vim_commit::CommitSaver;
+///
+/// println!("{}", JsonRenderer.render(&commit_saver.entry)?);
+/// let row = commit_saver.renderer(&folder).render(&commit_saver.entry)?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitEntry {
+    pub repository_url: String,
+    pub branch: String,
+    pub hash: String,
+    pub message: String,
+    pub datetime: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<DiffStats>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parents: Vec<String>,
+}
+
+impl CommitEntry {
+    /// Returns the message in the one-line form of diary tables, see
+    /// [`table_message()`].
+    #[must_use]
+    pub fn table_message(&self) -> String {
+        table_message(&self.message)
+    }
+
+    /// Whether the commit has more than one parent.
+    ///
+    /// Merge rows get their message prefixed with
+    /// [`MERGE_MARKER`](crate::vim_commit::MERGE_MARKER) and show `merge` in
+    /// the `EVENT` column, so they stand out from regular commits.
+    #[must_use]
+    pub fn is_merge(&self) -> bool {
+        self.parents.len() > 1
+    }
+}
+
+/// Trims the lines of a commit message and drops the empty ones, as the
+/// diary writes it.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::commit_entry::normalize_message;
+///
+/// assert_eq!(normalize_message("Fix a|b\n\n  details\n"), "Fix a|b\ndetails");
+/// ```
+#[must_use]
+pub fn normalize_message(message: &str) -> String {
+    message
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Turns a plain commit message into the one-line form of diary tables:
+/// lines trimmed, empty lines dropped, pipes escaped (`\|`) and lines joined
+/// with `<br/>`.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::commit_entry::table_message;
+///
+/// assert_eq!(table_message("Fix a|b\n\n  details\n"), "Fix a\\|b<br/>details");
+/// ```
+#[must_use]
+pub fn table_message(message: &str) -> String {
+    message
+        .lines()
+        .map(|line| line.trim().replace('|', "\\|"))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("<br/>")
+}
+
+/// Undoes [`table_message()`]: line breaks and pipes are put back.
+#[must_use]
+pub fn plain_message(commit_msg: &str) -> String {
+    commit_msg.replace("<br/>", "\n").replace("\\|", "|")
+}
+
+/// Formats a [`CommitEntry`] for one output (diary row, JSON, CSV, ...).
+///
+/// [`DiaryRenderer`](crate::diary_renderer::DiaryRenderer) renders diary
+/// entries with the settings and details of a
+/// [`CommitSaver`](crate::vim_commit::CommitSaver) (columns, entry
+/// format, timezone, ...), [`JsonRenderer`] and [`CsvRenderer`] render the
+/// entry data alone.
+pub trait Renderer {
+    /// Renders one entry, ending with a newline.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entry cannot be rendered.
+    fn render(&self, entry: &CommitEntry) -> Result<String, Box<dyn Error>>;
+}
+
+/// Renders entries as JSON Lines: one JSON object per entry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JsonRenderer;
+
+impl Renderer for JsonRenderer {
+    fn render(&self, entry: &CommitEntry) -> Result<String, Box<dyn Error>> {
+        Ok(format!("{:}\n", serde_json::to_string(entry)?))
+    }
+}
+
+/// Renders entries as CSV records, in the columns of [`CsvRenderer::HEADER`].
+///
+/// Fields holding a comma, a quote or a line break are quoted, with quotes
+/// doubled, so multi-line messages stay one record.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CsvRenderer;
+
+impl CsvRenderer {
+    /// The header record of the CSV output.
+    pub const HEADER: &'static str =
+        "datetime,repository_url,branch,hash,author,author_email,files_changed,insertions,deletions,message\n";
+}
+
+impl Renderer for CsvRenderer {
+    fn render(&self, entry: &CommitEntry) -> Result<String, Box<dyn Error>> {
+        let stat = |field: fn(&DiffStats) -> usize| {
+            entry
+                .stats
+                .as_ref()
+                .map(|stats| field(stats).to_string())
+                .unwrap_or_default()
+        };
+        let fields = [
+            entry.datetime.to_rfc3339(),
+            entry.repository_url.clone(),
+            entry.branch.clone(),
+            entry.hash.clone(),
+            entry.author.clone().unwrap_or_default(),
+            entry.author_email.clone().unwrap_or_default(),
+            stat(|stats| stats.files_changed),
+            stat(|stats| stats.insertions),
+            stat(|stats| stats.deletions),
+            entry.message.clone(),
+        ];
+        Ok(format!(
+            "{:}\n",
+            fields
+                .iter()
+                .map(|field| csv_field(field))
+                .collect::<Vec<_>>()
+                .join(",")
+        ))
+    }
+}
+
+/// Quotes a CSV field when it holds a comma, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{:}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod commit_entry_tests {
+    use super::*;
+    use crate::vim_commit::CommitSaver;
+    use crate::vim_commit::TableColumn;
+    use chrono::TimeZone;
+    use std::path::Path;
+
+    fn entry() -> CommitEntry {
+        CommitEntry {
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: "abc123def456".to_string(),
+            message: "Fix a|b, c\nWith \"details\"".to_string(),
+            datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 30, 0).unwrap(),
+            author: Some("Ana".to_string()),
+            author_email: None,
+            stats: Some(DiffStats {
+                files_changed: 3,
+                insertions: 120,
+                deletions: 4,
+            }),
+            parents: vec!["fff000".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_commit_entry_table_message_and_merges() {
+        assert_eq!(entry().table_message(), "Fix a\\|b, c<br/>With \"details\"");
+        assert!(!entry().is_merge());
+        assert_eq!(normalize_message("  Fix\n\n  details \n"), "Fix\ndetails");
+    }
+
+    #[test]
+    fn test_json_renderer() -> Result<(), Box<dyn Error>> {
+        let json = JsonRenderer.render(&entry())?;
+        assert!(json.ends_with("}\n"));
+        assert!(!json.contains("author_email"));
+        assert_eq!(serde_json::from_str::<CommitEntry>(&json)?, entry());
+        Ok(())
+    }
+
+    #[test]
+    fn test_csv_renderer() -> Result<(), Box<dyn Error>> {
+        assert_eq!(
+            CsvRenderer.render(&entry())?,
+            "2025-01-14T10:30:00+00:00,https://github.com/test/repo.git,main,abc123def456,Ana,,3,120,4,\"Fix a|b, c\nWith \"\"details\"\"\"\n"
+        );
+        assert_eq!(CsvRenderer::HEADER.matches(',').count(), 9);
+        Ok(())
+    }
+
+    #[test]
+    fn test_diary_renderer_renders_entries_with_the_saver_settings() -> Result<(), Box<dyn Error>> {
+        let mut commit_saver = CommitSaver::from(entry());
+        commit_saver.settings.table_columns = vec![TableColumn::Time, TableColumn::CommitHash];
+        let other = CommitEntry {
+            hash: "0123abcd".to_string(),
+            datetime: Utc.with_ymd_and_hms(2025, 1, 14, 18, 0, 0).unwrap(),
+            ..entry()
+        };
+
+        assert_eq!(
+            commit_saver.renderer(Path::new("/test")).render(&other)?,
+            "| 18:00:00 | 0123abcd |\n"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::io::Write;
+use std::path::PathBuf;
+
+use clap_complete::env::Shells;
+use clap_complete::ArgValueCandidates;
+use clap_complete::CompletionCandidate;
+
+use crate::paths::repository_name_from_url;
+use crate::state::LoggedEntry;
+use crate::state::StateIndex;
+
+/// Environment variable the shell sets when it asks the binary for completions.
+pub const COMPLETE_ENV_VAR: &str = "COMPLETE";
+
+/// Shells `completions` can register the dynamic completions for.
+pub const COMPLETION_SHELLS: [&str; 5] = ["bash", "elvish", "fish", "powershell", "zsh"];
+
+/// Returns the repository names of the logged commits, sorted and deduplicated.
+///
+/// Names are the aliases from [`repository_name_from_url()`] (e.g. `api` for
+/// `git@github.com:acme/api.git`), like in the `Related Repos` note.
+#[must_use]
+pub fn repository_names(entries: &[LoggedEntry]) -> Vec<String> {
+    entries
+        .iter()
+        .map(|entry| repository_name_from_url(&entry.repository_url))
+        .filter(|name| !name.is_empty())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Returns the branch names of the logged commits, sorted and deduplicated.
+#[must_use]
+pub fn branch_names(entries: &[LoggedEntry]) -> Vec<String> {
+    entries
+        .iter()
+        .map(|entry| entry.branch.clone())
+        .filter(|branch| !branch.is_empty())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Completes repository name arguments from the logged-commits index.
+///
+/// Attach it with `#[arg(add = repository_completer(completion_state_dir))]`.
+/// The index is read from the `state_dir` returned by `state_dir` when the
+/// shell asks for candidates, so a missing state directory or an unreadable
+/// index completes nothing.
+#[must_use]
+pub fn repository_completer<F>(state_dir: F) -> ArgValueCandidates
+where
+    F: Fn() -> Option<PathBuf> + Send + Sync + 'static,
+{
+    ArgValueCandidates::new(move || candidates(repository_names(&logged_entries(state_dir()))))
+}
+
+/// Completes branch name arguments from the logged-commits index, like
+/// [`repository_completer()`].
+#[must_use]
+pub fn branch_completer<F>(state_dir: F) -> ArgValueCandidates
+where
+    F: Fn() -> Option<PathBuf> + Send + Sync + 'static,
+{
+    ArgValueCandidates::new(move || candidates(branch_names(&logged_entries(state_dir()))))
+}
+
+/// Writes the script registering the dynamic completions of `completer` (the
+/// path of the binary) in `shell`.
+///
+/// Sourcing the script makes the shell call the binary with
+/// [`COMPLETE_ENV_VAR`] set on every completion, so candidates read from the
+/// index are always current.
+///
+/// # Errors
+///
+/// Returns an error if `shell` is not one of [`COMPLETION_SHELLS`], or if the
+/// script cannot be written.
+pub fn write_registration(
+    shell: &str,
+    completer: &str,
+    buf: &mut dyn Write,
+) -> Result<(), Box<dyn Error>> {
+    let shells = Shells::builtins();
+    let env_completer = shells
+        .completer(shell)
+        .ok_or_else(|| format!("Completions are not available for the shell: {shell:}"))?;
+    let name = env!("CARGO_PKG_NAME");
+    env_completer.write_registration(COMPLETE_ENV_VAR, name, name, completer, buf)?;
+    Ok(())
+}
+
+/// Reads the logged-commits index of `state_dir`.
+fn logged_entries(state_dir: Option<PathBuf>) -> Vec<LoggedEntry> {
+    state_dir
+        .and_then(|state_dir| StateIndex::new(&state_dir).entries().ok())
+        .unwrap_or_default()
+}
+
+fn candidates(values: Vec<String>) -> Vec<CompletionCandidate> {
+    values.into_iter().map(CompletionCandidate::new).collect()
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod completions_tests {
+    use super::*;
+    use crate::config::UserInput;
+    use chrono::TimeZone;
+    use chrono::Utc;
+    use clap::CommandFactory;
+    use clap_complete::engine::complete;
+    use std::ffi::OsString;
+    use tempfile::tempdir;
+
+    fn logged(repository_url: &str, branch: &str) -> LoggedEntry {
+        LoggedEntry {
+            commit_datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+            repository_url: repository_url.to_string(),
+            branch: branch.to_string(),
+            commit_hash: "abc123".to_string(),
+            diary_path: "/vault/Commits/2025-01-14.md".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_repository_and_branch_names() {
+        let entries = [
+            logged("https://github.com/acme/web.git", "main"),
+            logged("git@github.com:acme/api.git", "feature/login"),
+            logged("https://github.com/acme/api.git", "main"),
+            logged("https://github.com/acme/api.git", ""),
+        ];
+
+        assert_eq!(repository_names(&entries), ["api", "web"]);
+        assert_eq!(branch_names(&entries), ["feature/login", "main"]);
+    }
+
+    #[test]
+    fn test_completers_offer_the_logged_names_through_the_command() {
+        let state_dir = tempdir().unwrap();
+        let index = StateIndex::new(state_dir.path());
+        index
+            .append(&logged("git@github.com:acme/api.git", "feature/login"))
+            .unwrap();
+        index
+            .append(&logged("https://github.com/acme/web.git", "main"))
+            .unwrap();
+
+        let mut command = UserInput::command();
+        for (subcommand, arg) in [
+            ("undo", "repo"),
+            ("undo", "branch"),
+            ("stats", "repo"),
+            ("log-recent", "repo"),
+        ] {
+            let arg = command
+                .find_subcommand(subcommand)
+                .and_then(|subcommand| subcommand.get_arguments().find(|a| a.get_id() == arg))
+                .unwrap();
+            assert!(arg.get::<ArgValueCandidates>().is_some());
+        }
+
+        let dir = state_dir.path().to_path_buf();
+        let branch_dir = dir.clone();
+        command = command.mut_subcommand("undo", |undo| {
+            undo.mut_arg("repo", |arg| {
+                arg.add(repository_completer(move || Some(dir.clone())))
+            })
+            .mut_arg("branch", |arg| {
+                arg.add(branch_completer(move || Some(branch_dir.clone())))
+            })
+        });
+        let complete = |command: &mut clap::Command, args: &[&str]| {
+            let args = args.iter().map(OsString::from).collect::<Vec<_>>();
+            let index = args.len() - 1;
+            complete(command, args, index, None)
+                .unwrap()
+                .iter()
+                .map(|candidate| candidate.get_value().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            complete(&mut command, &["rusty-commit-saver", "undo", "--repo", ""]),
+            ["api", "web"]
+        );
+        assert_eq!(
+            complete(
+                &mut command,
+                &["rusty-commit-saver", "undo", "--branch", "f"]
+            ),
+            ["feature/login"]
+        );
+    }
+
+    #[test]
+    fn test_write_registration() {
+        let mut script = Vec::new();
+        write_registration("bash", "/usr/bin/rusty-commit-saver", &mut script).unwrap();
+
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.contains("COMPLETE"));
+        assert!(script.contains("/usr/bin/rusty-commit-saver"));
+        assert!(write_registration("cmd", "rusty-commit-saver", &mut Vec::new()).is_err());
+    }
+}
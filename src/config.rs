@@ -1,15 +1,137 @@
-use log::{error, info};
+use log::{error, info, warn};
 
 use std::{
-    fs,
+    env, fs,
     path::{Path, PathBuf},
+    time::Duration,
 };
 
+use clap::Args;
 use clap::Parser;
+use clap::Subcommand;
+use clap::ValueEnum;
 use configparser::ini::Ini;
 use dirs::home_dir;
+use handlebars::Template;
 use once_cell::sync::OnceCell;
 
+use crate::append_queue::DEFAULT_DEBOUNCE;
+use crate::build_status::BuildStatusCheck;
+use crate::build_status::BuildStatusSource;
+use crate::build_status::DEFAULT_BUILD_TIMEOUT;
+use crate::category::is_valid_category;
+use crate::category::CategoryRule;
+use crate::collision::SameSecondPolicy;
+use crate::completions::branch_completer;
+use crate::completions::repository_completer;
+use crate::completions::COMPLETION_SHELLS;
+use crate::config_format::existing_config_path;
+use crate::config_format::parse_config;
+use crate::config_format::ConfigFormat;
+use crate::daily_note::DailyNote;
+use crate::daily_note::DailyNoteLinkStyle;
+use crate::daily_note::DEFAULT_DAILY_NOTE_TEMPLATE;
+use crate::diary_renderer::EntrySettings;
+use crate::entry_id::EntryIdStyle;
+use crate::existing_note::heading_line;
+use crate::forge::ForgeResolver;
+use crate::forge::ForgeTemplates;
+use crate::git_stats::SizeThresholds;
+use crate::github::DEFAULT_IMPORT_DAYS;
+use crate::goals::WeeklyGoal;
+use crate::goals::DEFAULT_WEEKLY_NOTE_TEMPLATE;
+use crate::heatmap::HeatmapFormat;
+use crate::heatmap::HeatmapSettings;
+use crate::heatmap::DEFAULT_HEATMAP_NOTE;
+use crate::hygiene::MessagePattern;
+use crate::paths::prefix_path_emoji;
+use crate::recent::DEFAULT_LOOKBACK_DAYS;
+use crate::related::DEFAULT_RELATED_REPOS_NOTE;
+use crate::routing::parse_route;
+use crate::routing::Route;
+use crate::sinks::SinkKind;
+use crate::state::default_state_dir;
+use crate::time_tracking::TimeTrackingSource;
+use crate::timezone::DiaryTimezone;
+use crate::timezone::TimezoneStyle;
+use crate::trailers::TagPlacement;
+use crate::trash::VAULT_TRASH_DIR;
+use crate::vault_git::DEFAULT_VAULT_COMMIT_MESSAGE;
+use crate::vaults::RemotePattern;
+use crate::vaults::VaultTarget;
+use crate::vim_commit::EntryFormat;
+use crate::vim_commit::TableColumn;
+use crate::vim_commit::DEFAULT_TABLE_COLUMNS;
+use crate::webhook::Webhook;
+use crate::webhook::DEFAULT_PAYLOAD_TEMPLATE;
+use crate::weekly_log::WeeklyLog;
+use crate::weekly_log::DEFAULT_WEEKLY_ROW_TEMPLATE;
+use crate::wsl::FolderStyle;
+use crate::wsl::PathEnvironment;
+use crate::wsl::WslPaths;
+
+use chrono::NaiveDate;
+use chrono::TimeDelta;
+
+/// INI sections every configuration needs.
+const REQUIRED_SECTIONS: [&str; 2] = ["obsidian", "templates"];
+
+/// INI sections that may be omitted, on top of the [`REQUIRED_SECTIONS`].
+///
+/// Any other section is skipped with a warning, so configurations written
+/// for newer versions (or holding the user's own notes) still load.
+const OPTIONAL_SECTIONS: [&str; 31] = [
+    "events",
+    "duration",
+    "time_tracking",
+    "build_status",
+    "vault_git",
+    "table",
+    "categories",
+    "safety",
+    "routing",
+    "vaults",
+    "forges",
+    "language",
+    "goals",
+    "related_repos",
+    "message_hygiene",
+    "wsl",
+    "webhook",
+    "weekly_log",
+    "size",
+    "release_tags",
+    "branches",
+    "recent",
+    "repositories",
+    "daemon",
+    "metrics",
+    "filters",
+    "conventional_commits",
+    "trailers",
+    "output",
+    "daily_note",
+    "heatmap",
+];
+
+/// Default cap for the `DURATION` column, in minutes.
+const DEFAULT_DURATION_MAX_MINUTES: i64 = 120;
+
+/// Environment variables overriding INI values, with the section and key
+/// each one replaces. See [`apply_env_overrides()`].
+pub const ENV_OVERRIDES: [(&str, &str, &str); 10] = [
+    ("RCS_OBSIDIAN_ROOT", "obsidian", "root_path_dir"),
+    ("RCS_COMMIT_PATH", "obsidian", "commit_path"),
+    ("RCS_DATE_TEMPLATE", "templates", "commit_date_path"),
+    ("RCS_DATETIME_TEMPLATE", "templates", "commit_datetime"),
+    ("RCS_DIARY_TEMPLATE", "templates", "diary_template"),
+    ("RCS_ENTRY_FORMAT", "templates", "entry_format"),
+    ("RCS_TABLE_COLUMNS", "table", "columns"),
+    ("RCS_TIME_ZONE", "table", "time_zone"),
+    ("RCS_REQUIRE_VAULT", "safety", "require_vault"),
+    ("RCS_PROJECTS_DIR", "recent", "projects_dir"),
+];
+
 /// Parses INI file content into a configuration object without file I/O.
 ///
 /// This is a pure function that takes raw INI text and parses it into an `Ini` struct.
@@ -48,7 +170,7 @@ use once_cell::sync::OnceCell;
 ///
 /// [templates]
 /// commit_date_path = %Y/%m-%B/%F.md
-/// commit_datetime = %Y-%m-%d %H:%M:%S
+/// commit_datetime = %H:%M:%S
 /// "#;
 ///
 /// let config = parse_ini_content(ini_content).unwrap();
@@ -92,6 +214,44 @@ pub fn parse_ini_content(content: &str) -> Result<Ini, String> {
     Ok(config)
 }
 
+/// Replaces INI values with the [`ENV_OVERRIDES`] variables that are set.
+///
+/// Precedence is command line > environment > file: the overrides apply to
+/// the parsed file before [`GlobalVars`] reads it, and command-line flags
+/// (e.g. `log-recent --dir`) still win over the resulting values. This makes
+/// headless runs (CI) and per-directory overrides (e.g. with `direnv`)
+/// possible without editing the config file. Empty variables are ignored, so
+/// `RCS_COMMIT_PATH=` does not blank the commit path.
+///
+/// # Returns
+///
+/// The names of the variables that were applied.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::config::{apply_env_overrides, get_ini_file};
+///
+/// // RCS_OBSIDIAN_ROOT=/tmp/ci-vault rusty-commit-saver save
+/// let mut config = get_ini_file();
+/// apply_env_overrides(&mut config, |name| std::env::var(name).ok());
+/// ```
+pub fn apply_env_overrides<F>(config: &mut Ini, lookup: F) -> Vec<&'static str>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut applied = Vec::new();
+    for (variable, section, key) in ENV_OVERRIDES {
+        let Some(value) = lookup(variable).filter(|value| !value.trim().is_empty()) else {
+            continue;
+        };
+        info!("[apply_env_overrides()]: {variable:} overrides [{section:}] {key:}.");
+        config.set(section, key, Some(value));
+        applied.push(variable);
+    }
+    applied
+}
+
 /// Thread-safe global configuration container for Rusty Commit Saver.
 ///
 /// This struct holds all runtime configuration loaded from the INI file,
@@ -171,13 +331,56 @@ pub struct GlobalVars {
     ///
     /// # Configuration
     ///
-    /// Loaded from INI file:
+    /// Loaded from INI file, with an optional emoji put in front of its first
+    /// folder (`📅 Diaries/Commits`):
     /// ```text
     /// [obsidian]
     /// commit_path = Diaries/Commits
+    /// path_prefix_emoji = 📅
     /// ```
     obsidian_commit_path: OnceCell<PathBuf>,
 
+    /// Whether remote URLs are written as HTTPS addresses, see
+    /// [`normalize_remote_url()`](crate::forge::normalize_remote_url).
+    ///
+    /// # Configuration
+    ///
+    /// Optional, loaded from INI file:
+    /// ```text
+    /// [obsidian]
+    /// normalize_remote = true
+    /// ```
+    obsidian_normalize_remote: OnceCell<bool>,
+
+    /// Heading the commit rows go under: they are inserted at the end of the
+    /// table of its section instead of the end of the note, and diary files
+    /// that exist without a table (e.g. notes created by the Obsidian Daily
+    /// Notes plugin) get one there.
+    ///
+    /// # Configuration
+    ///
+    /// Optional, loaded from INI file (defaults to
+    /// [`DEFAULT_TABLE_HEADING`](crate::existing_note::DEFAULT_TABLE_HEADING)),
+    /// see [`heading_line()`]:
+    /// ```text
+    /// [obsidian]
+    /// insert_under = Commits
+    /// ```
+    obsidian_insert_under: OnceCell<String>,
+
+    /// Name of the vault in Obsidian, for the `obsidian://` URIs of
+    /// `save --open`.
+    ///
+    /// # Configuration
+    ///
+    /// Optional, loaded from INI file (defaults to the name of the
+    /// `root_path_dir` directory):
+    /// ```text
+    /// [obsidian]
+    /// vault_name = Notes
+    /// ```
+    obsidian_vault_name: OnceCell<String>,
+
     /// Chrono format string for date-based file paths.
     ///
     /// Controls the directory structure and filename for diary entries.
@@ -212,7 +415,8 @@ pub struct GlobalVars {
 
     /// Chrono format string for datetime display in diary entries.
     ///
-    /// Controls how commit timestamps appear in the diary table's TIME column.
+    /// Controls how commit timestamps appear in the diary table's TIME column
+    /// and the `time` row template variable.
     ///
     /// # Format Specifiers
     ///
@@ -239,1049 +443,6733 @@ pub struct GlobalVars {
     /// Loaded from INI file:
     /// ```text
     /// [templates]
-    /// commit_datetime = %Y-%m-%d %H:%M:%S
+    /// commit_datetime = %H:%M:%S
     /// ```
     template_commit_datetime: OnceCell<String>,
-}
 
-impl GlobalVars {
-    /// Creates a new uninitialized `GlobalVars` instance.
-    ///
-    /// This constructor initializes all fields as empty `OnceCell` values.
-    /// Use [`set_all()`](Self::set_all) to load configuration from the INI file.
-    ///
-    /// # Thread Safety
+    /// The user's daily-note template used for new diary files, if any.
     ///
-    /// `GlobalVars` uses `OnceCell` for thread-safe, lazy initialization.
-    /// Configuration values are set once and cannot be changed afterward.
-    ///
-    /// # Returns
+    /// Relative to the vault root (like Obsidian's template folder setting),
+    /// absolute, or starting with `~`. `.md` is added when the path has no
+    /// extension.
     ///
-    /// A new `GlobalVars` instance with all fields uninitialized
+    /// # Configuration
     ///
-    /// # Fields
+    /// Optional, loaded from INI file:
+    /// ```text
+    /// [templates]
+    /// diary_template = Templates/Daily Note.md
+    /// ```
+    template_diary_file: OnceCell<PathBuf>,
+
+    /// The Handlebars template new diary files are rendered from, if any.
     ///
-    /// - `config` - The parsed INI configuration file
-    /// - `obsidian_root_path_dir` - Root directory of Obsidian vault
-    /// - `obsidian_commit_path` - Subdirectory path for commit entries
-    /// - `template_commit_date_path` - Chrono format for date-based directory structure
-    /// - `template_commit_datetime` - Chrono format for datetime strings
+    /// Resolved like `diary_template`, without adding an extension. Takes
+    /// precedence over `diary_template`.
     ///
-    /// # Examples
+    /// # Configuration
     ///
-    /// ```ignore
-    /// use rusty_commit_saver::config::GlobalVars;
+    /// Optional, loaded from INI file:
+    /// ```text
+    /// [templates]
+    /// diary_file = ~/.config/rusty-commit-saver/diary.md.hbs
+    /// ```
+    template_diary_handlebars: OnceCell<PathBuf>,
+
+    /// Whether diary files go straight into the commit path, without the
+    /// year/month directories of `commit_date_path`.
     ///
-    /// // Create new instance
-    /// let global_vars = GlobalVars::new();
+    /// # Configuration
     ///
-    /// // Now call set_all() to initialize from config file
-    /// // global_vars.set_all();
+    /// Optional, loaded from INI file:
+    /// ```text
+    /// [templates]
+    /// flat_layout = true
     /// ```
-    #[must_use]
-    pub fn new() -> Self {
-        info!("[GlobalVars::new()] Creating new GlobalVars with OnceCell default values.");
-        GlobalVars {
-            config: OnceCell::new(),
-
-            obsidian_root_path_dir: OnceCell::new(),
-            obsidian_commit_path: OnceCell::new(),
-
-            template_commit_date_path: OnceCell::new(),
-            template_commit_datetime: OnceCell::new(),
-        }
-    }
+    template_flat_layout: OnceCell<bool>,
 
-    /// Loads and initializes all configuration from the INI file.
+    /// How entries are written to the diary: table rows or YAML blocks.
     ///
-    /// This is the main entry point for configuration setup. It:
-    /// 1. Reads the INI configuration file from disk (or CLI argument)
-    /// 2. Parses it into the `config` field
-    /// 3. Extracts and initializes all Obsidian and template variables
+    /// # Configuration
     ///
-    /// Configuration is loaded from (in order of preference):
-    /// - `--config-ini <PATH>` CLI argument
-    /// - Default: `~/.config/rusty-commit-saver/rusty-commit-saver.ini`
+    /// Optional, loaded from INI file (table rows by default):
+    /// ```text
+    /// [templates]
+    /// entry_format = yaml
+    /// ```
+    template_entry_format: OnceCell<EntryFormat>,
+
+    /// How the stable entry IDs of row templates are generated.
     ///
-    /// # Panics
+    /// # Configuration
     ///
-    /// Panics if:
-    /// - Configuration file doesn't exist
-    /// - Configuration file cannot be read
-    /// - Configuration file has invalid INI format
-    /// - Required sections or keys are missing
-    /// - Section count is not exactly 2 (obsidian + templates)
+    /// Optional, loaded from INI file (`base32` by default):
+    /// ```text
+    /// [templates]
+    /// entry_id = short_hash
+    /// ```
+    template_entry_id: OnceCell<EntryIdStyle>,
+
+    /// The timezone diary paths, dates and times are written in.
     ///
-    /// # Returns
+    /// # Configuration
     ///
-    /// Returns `self` for method chaining
+    /// Optional, loaded from INI file (UTC by default; `local` for the
+    /// system timezone, or an IANA name):
+    /// ```text
+    /// [templates]
+    /// timezone = Europe/Zurich
+    /// ```
+    template_timezone: OnceCell<DiaryTimezone>,
+
+    /// The Handlebars template each entry is rendered from, instead of a table
+    /// row (bullet lists, callouts, ...).
     ///
-    /// # Required INI Structure
+    /// # Configuration
     ///
+    /// Optional, loaded from INI file (`\n` starts a new line):
     /// ```text
-    /// [obsidian]
-    /// root_path_dir = ~/Documents/Obsidian
-    /// commit_path = Diaries/Commits
-    ///
     /// [templates]
-    /// commit_date_path = %Y/%m-%B/%F.md
-    /// commit_datetime = %Y-%m-%d %H:%M:%S
+    /// row_template = - {{time}} [{{message}}]({{repo_url}}/commit/{{hash}}) on {{branch}}
     /// ```
+    template_row_template: OnceCell<String>,
+
+    /// Whether branch switches reported by the `post-checkout` hook are logged.
     ///
-    /// # Examples
+    /// # Configuration
     ///
-    /// ```ignore
-    /// use rusty_commit_saver::config::GlobalVars;
+    /// Optional, loaded from INI file (defaults to `false`):
+    /// ```text
+    /// [events]
+    /// branch_switches = true
+    /// ```
+    events_branch_switches: OnceCell<bool>,
+
+    /// Whether stash pushes and pops are logged.
     ///
-    /// let global_vars = GlobalVars::new();
-    /// global_vars.set_all(); // Reads from default or CLI config
+    /// # Configuration
     ///
-    /// // Now all getters will return values
-    /// let root_path = global_vars.get_obsidian_root_path_dir();
-    /// let commit_path = global_vars.get_obsidian_commit_path();
+    /// Optional, loaded from INI file (defaults to `false`):
+    /// ```text
+    /// [events]
+    /// stash = true
     /// ```
-    pub fn set_all(&self) -> &Self {
-        info!("[GlobalVars::set_all()] Setting all variables for GlobalVars");
-        let config = get_ini_file();
-
-        info!("[GlobalVars::set_all()]: Setting Config Ini file.");
-        self.config
-            .set(config)
-            .expect("Coulnd't set config in GlobalVars");
+    events_stash: OnceCell<bool>,
 
-        info!("[GlobalVars::set_all()]: Setting Obsidian variables from file.");
-        self.set_obsidian_vars();
+    /// Whether pushes reported by the `pre-push` hook are logged.
+    ///
+    /// # Configuration
+    ///
+    /// Optional, loaded from INI file (defaults to `false`):
+    /// ```text
+    /// [events]
+    /// push = true
+    /// ```
+    events_push: OnceCell<bool>,
 
-        self
-    }
+    /// Whether the `DURATION` column (time since the previous logged commit) is added.
+    ///
+    /// # Configuration
+    ///
+    /// Optional, loaded from INI file (defaults to `false`):
+    /// ```text
+    /// [duration]
+    /// enabled = true
+    /// ```
+    duration_enabled: OnceCell<bool>,
 
-    /// Returns the root directory of the Obsidian vault.
+    /// Upper bound for the estimated duration, in minutes.
     ///
-    /// This is the base directory where all Obsidian vault files are stored.
-    /// All diary entries are created under this directory according to the
-    /// configured subdirectory structure.
+    /// Gaps longer than this (lunch, nights, weekends) are reported as the cap.
     ///
-    /// # Panics
+    /// # Configuration
     ///
-    /// Panics if called before [`set_all()`](Self::set_all) has been invoked
+    /// Optional, loaded from INI file (defaults to `120`):
+    /// ```text
+    /// [duration]
+    /// max_minutes = 90
+    /// ```
+    duration_max_minutes: OnceCell<i64>,
+
+    /// Where the current time-tracking project/tag is read from at save time.
     ///
-    /// # Returns
+    /// When set, a `TRACKING` column is added to the diary table.
     ///
-    /// A `PathBuf` representing the Obsidian vault root directory
+    /// # Configuration
     ///
-    /// # Examples
+    /// Optional, loaded from INI file (exactly one of the two keys):
+    /// ```text
+    /// [time_tracking]
+    /// command = timew get dom.active.tag.1
+    /// # or
+    /// file = ~/.local/share/toggl/current.txt
+    /// ```
+    time_tracking_source: OnceCell<TimeTrackingSource>,
+
+    /// Where the build status of the committed state is read from at save time.
     ///
-    /// ```ignore
-    /// use rusty_commit_saver::config::GlobalVars;
+    /// When set, a `STATUS` column is added to the diary table.
     ///
-    /// let global_vars = GlobalVars::new();
-    /// global_vars.set_all();
+    /// # Configuration
     ///
-    /// let root = global_vars.get_obsidian_root_path_dir();
-    /// println!("Obsidian vault root: {}", root.display());
-    /// // Output: Obsidian vault root: /home/user/Documents/Obsidian
+    /// Optional, loaded from INI file (exactly one of `command` and `file`):
+    /// ```text
+    /// [build_status]
+    /// command = cargo check --quiet
+    /// timeout_seconds = 10
+    /// # or
+    /// file = .build-status
     /// ```
+    build_status_check: OnceCell<BuildStatusCheck>,
+
+    /// Message template of the vault auto-commit, set only when it is enabled.
     ///
-    /// # Configuration Source
+    /// # Configuration
     ///
-    /// Read from INI file:
+    /// Optional, loaded from INI file (`message` defaults to
+    /// [`DEFAULT_VAULT_COMMIT_MESSAGE`]):
     /// ```text
-    /// [obsidian]
-    /// root_path_dir = ~/Documents/Obsidian
+    /// [vault_git]
+    /// auto_commit = true
+    /// message = Log {repo}@{hash} in the commit diary
     /// ```
-    pub fn get_obsidian_root_path_dir(&self) -> PathBuf {
-        info!("[GlobalVars::get_obsidian_root_path_dir()]: Getting obsidian_root_path_dir.");
-        self.obsidian_root_path_dir
-            .get()
-            .expect("Could not get obsidian_root_path_dir")
-            .clone()
-    }
+    vault_commit_message: OnceCell<String>,
 
-    /// Returns the subdirectory path where commits are stored.
-    ///
-    /// This is a relative path under [`get_obsidian_root_path_dir()`](Self::get_obsidian_root_path_dir)
-    /// where commit diary entries will be organized. The full path is constructed by
-    /// combining this with the Obsidian root and the date-based directory structure.
+    /// Maximum length of a diary table row, in characters.
     ///
-    /// # Panics
+    /// Longer rows get a shortened commit message that links to a per-day
+    /// overflow note holding the full text.
     ///
-    /// Panics if called before [`set_all()`](Self::set_all) has been invoked
+    /// # Configuration
     ///
-    /// # Returns
+    /// Optional, loaded from INI file (no limit by default):
+    /// ```text
+    /// [table]
+    /// max_row_length = 500
+    /// ```
+    table_max_row_length: OnceCell<usize>,
+
+    /// The diary table columns, in order, replacing [`DEFAULT_TABLE_COLUMNS`].
     ///
-    /// A `PathBuf` representing the commits subdirectory (relative path)
+    /// # Configuration
     ///
-    /// # Examples
+    /// Optional, loaded from INI file:
+    /// ```text
+    /// [table]
+    /// columns = time, message, branch, hash
+    /// ```
+    table_columns: OnceCell<Vec<TableColumn>>,
+
+    /// How the `TIME` column names the timezone of an entry.
     ///
-    /// ```ignore
-    /// use rusty_commit_saver::config::GlobalVars;
+    /// # Configuration
     ///
-    /// let global_vars = GlobalVars::new();
-    /// global_vars.set_all();
+    /// Optional, loaded from INI file (times are in UTC without it):
+    /// ```text
+    /// [table]
+    /// time_zone = abbreviation
+    /// ```
+    table_time_zone: OnceCell<TimezoneStyle>,
+
+    /// What happens to the `TIME` cell of rows logged in the same second.
     ///
-    /// let commit_path = global_vars.get_obsidian_commit_path();
-    /// println!("Commit subdirectory: {}", commit_path.display());
-    /// // Output: Commit subdirectory: Diaries/Commits
+    /// # Configuration
     ///
-    /// // Full path would be constructed as:
-    /// // /home/user/Documents/Obsidian/Diaries/Commits/2025/01-January/2025-01-14.md
+    /// Optional, loaded from INI file (rows keep the same `TIME` without it):
+    /// ```text
+    /// [table]
+    /// same_second = sequence
     /// ```
+    table_same_second: OnceCell<SameSecondPolicy>,
+
+    /// Whether `COMMIT HASH` cells link to the commit on its web forge.
     ///
-    /// # Configuration Source
+    /// # Configuration
     ///
-    /// Read from INI file:
+    /// Optional, loaded from INI file (plain hashes without it):
     /// ```text
-    /// [obsidian]
-    /// commit_path = Diaries/Commits
+    /// [table]
+    /// forge_links = true
     /// ```
-    pub fn get_obsidian_commit_path(&self) -> PathBuf {
-        info!("[GlobalVars::get_obsidian_commit_path()]: Getting obsidian_commit_path.");
-        self.obsidian_commit_path
-            .get()
-            .expect("Could not get obsidian_commit_path")
-            .clone()
-    }
+    table_forge_links: OnceCell<bool>,
 
-    /// Returns the Chrono format string for diary file date hierarchies.
-    ///
-    /// This format string is used to create the directory structure and filename
-    /// for diary entries based on the commit timestamp. It controls how commits
-    /// are organized by date.
+    /// Whether table rows end with a `^<short-hash>` block anchor.
     ///
-    /// # Chrono Format Specifiers
+    /// # Configuration
     ///
-    /// - `%Y` - Full year (e.g., `2025`)
-    /// - `%m` - Month as zero-padded number (e.g., `01`)
-    /// - `%B` - Full month name (e.g., `January`)
-    /// - `%b` - Abbreviated month (e.g., `Jan`)
-    /// - `%d` - Day of month, zero-padded (e.g., `14`)
-    /// - `%F` - ISO 8601 date (equivalent to `%Y-%m-%d`, e.g., `2025-01-14`)
-    /// - `%H` - Hour in 24-hour format (e.g., `14`)
-    /// - `%M` - Minute (e.g., `30`)
-    /// - `%S` - Second (e.g., `45`)
-    ///
-    /// # Panics
+    /// Optional, loaded from INI file (no anchors without it):
+    /// ```text
+    /// [table]
+    /// block_refs = true
+    /// ```
+    table_block_refs: OnceCell<bool>,
+
+    /// Rules mapping repository URLs/directories to an entry category.
     ///
-    /// Panics if called before [`set_all()`](Self::set_all) has been invoked
+    /// # Configuration
     ///
-    /// # Returns
+    /// Optional, loaded from INI file (one key per category, comma-separated
+    /// `*` patterns):
+    /// ```text
+    /// [categories]
+    /// work = *github.com/acme/*, ~/work/*
+    /// oss = *github.com/chess-seventh/*
+    /// ```
+    category_rules: OnceCell<Vec<CategoryRule>>,
+
+    /// Whether removed or rewritten notes go through the vault `.trash` folder.
     ///
-    /// A `String` containing the Chrono format specifiers
+    /// # Configuration
     ///
-    /// # Examples
+    /// Optional, loaded from INI file (defaults to `true`):
+    /// ```text
+    /// [safety]
+    /// use_trash = true
+    /// ```
+    safety_use_trash: OnceCell<bool>,
+
+    /// Whether saving refuses a vault root without a `.obsidian` directory.
     ///
-    /// ```ignore
-    /// use rusty_commit_saver::config::GlobalVars;
+    /// # Configuration
     ///
-    /// let global_vars = GlobalVars::new();
-    /// global_vars.set_all();
+    /// Optional, loaded from INI file (defaults to `false`):
+    /// ```text
+    /// [safety]
+    /// require_vault = true
+    /// ```
+    safety_require_vault: OnceCell<bool>,
+
+    /// Whether the commit message language is detected for a `#lang/<code>` tag.
     ///
-    /// let date_template = global_vars.get_template_commit_date_path();
-    /// println!("Date format: {}", date_template);
-    /// // Output: Date format: %Y/%m-%B/%F.md
+    /// # Configuration
     ///
-    /// // This creates paths like:
-    /// // /home/user/Obsidian/Diaries/Commits/2025/01-January/2025-01-14.md
+    /// Optional, loaded from INI file (defaults to `false`):
+    /// ```text
+    /// [language]
+    /// detect = true
     /// ```
+    language_detect: OnceCell<bool>,
+
+    /// Whether commit messages are parsed as Conventional Commits.
     ///
-    /// # Configuration Source
+    /// # Configuration
     ///
-    /// Read from INI file:
+    /// Optional, loaded from INI file (defaults to `false`):
     /// ```text
-    /// [templates]
-    /// commit_date_path = %Y/%m-%B/%F.md
+    /// [conventional_commits]
+    /// enabled = true
     /// ```
-    pub fn get_template_commit_date_path(&self) -> String {
-        info!("[GlobalVars::get_template_commit_date_path()]: Getting template_commit_date_path.");
-        self.template_commit_date_path
-            .get()
-            .expect("Could not get template_commit_date_path")
-            .clone()
-    }
+    conventional_commits_enabled: OnceCell<bool>,
 
-    /// Returns the Chrono format string for commit timestamps in diary entries.
+    /// Where the tags of `Obsidian-Tags` commit trailers are written.
     ///
-    /// This format string is used to display the commit time in the diary table.
-    /// It controls how timestamps appear in the commit entry rows.
+    /// # Configuration
     ///
-    /// # Chrono Format Specifiers
+    /// Optional, loaded from INI file (`row`, `frontmatter` or `both`;
+    /// trailers are ignored when unset):
+    /// ```text
+    /// [trailers]
+    /// tags = both
+    /// ```
+    trailers_tags: OnceCell<TagPlacement>,
+
+    /// Outputs commits are written to.
     ///
-    /// - `%Y` - Full year (e.g., `2025`)
-    /// - `%m` - Month as zero-padded number (e.g., `01`)
-    /// - `%B` - Full month name (e.g., `January`)
-    /// - `%d` - Day of month, zero-padded (e.g., `14`)
-    /// - `%H` - Hour in 24-hour format (e.g., `14`)
-    /// - `%M` - Minute, zero-padded (e.g., `30`)
-    /// - `%S` - Second, zero-padded (e.g., `45`)
-    /// - `%T` - Time in HH:MM:SS format (equivalent to `%H:%M:%S`)
+    /// # Configuration
     ///
-    /// # Panics
+    /// Optional, loaded from INI file (defaults to the diary alone):
+    /// ```text
+    /// [output]
+    /// sinks = markdown, jsonl
+    /// jsonl_path = ~/Journal/commits.jsonl
+    /// ```
+    output_sinks: OnceCell<Vec<SinkKind>>,
+
+    /// How entries are written to the diary, overriding
+    /// `[templates] entry_format`.
     ///
-    /// Panics if called before [`set_all()`](Self::set_all) has been invoked
+    /// # Configuration
     ///
-    /// # Returns
+    /// Optional, loaded from INI file (`table`, `yaml` or `dataview`):
+    /// ```text
+    /// [output]
+    /// style = dataview
+    /// ```
+    output_style: OnceCell<EntryFormat>,
+
+    /// Weekly commit goal tracked in the weekly note.
     ///
-    /// A `String` containing the Chrono format specifiers for datetime
+    /// # Configuration
     ///
-    /// # Examples
+    /// Optional, loaded from INI file (`weekly_note` defaults to
+    /// [`DEFAULT_WEEKLY_NOTE_TEMPLATE`]):
+    /// ```text
+    /// [goals]
+    /// weekly_commits = 20
+    /// weekly_note = Diaries/Weekly/%G-W%V.md
+    /// ```
+    weekly_goal: OnceCell<WeeklyGoal>,
+
+    /// Path of the related repositories note, relative to the vault root.
     ///
-    /// ```ignore
-    /// use rusty_commit_saver::config::GlobalVars;
+    /// # Configuration
     ///
-    /// let global_vars = GlobalVars::new();
-    /// global_vars.set_all();
+    /// Optional, loaded from INI file (`note` defaults to
+    /// [`DEFAULT_RELATED_REPOS_NOTE`]):
+    /// ```text
+    /// [related_repos]
+    /// enabled = true
+    /// note = Rollups/Related Repos.md
+    /// ```
+    related_repos_note: OnceCell<PathBuf>,
+
+    /// The regular expression commit message subjects should match.
     ///
-    /// let datetime_template = global_vars.get_template_commit_datetime();
-    /// println!("Datetime format: {}", datetime_template);
-    /// // Output: Datetime format: %Y-%m-%d %H:%M:%S
+    /// # Configuration
     ///
-    /// // This renders timestamps like:
-    /// // 2025-01-14 14:30:45
+    /// Optional, loaded from INI file (messages are not checked without it):
+    /// ```text
+    /// [message_hygiene]
+    /// pattern = ^(feat|fix|docs|refactor|test|chore)(\(.+\))?!?: .+
     /// ```
+    message_pattern: OnceCell<MessagePattern>,
+
+    /// The outbound webhook every logged commit is sent to.
     ///
-    /// # Diary Table Usage
+    /// # Configuration
     ///
-    /// In the diary table, this format appears in the TIME column:
+    /// Optional, loaded from INI file (nothing is sent without it):
     /// ```text
-    /// | FOLDER | TIME | COMMIT MESSAGE | REPOSITORY URL | BRANCH | COMMIT HASH |
-    /// |--------|------|----------------|----------------|--------|-------------|
-    /// | /work/project | 14:30:45 | feat: add feature | https://github.com/... | main | abc123... |
+    /// [webhook]
+    /// url = https://n8n.example.com/webhook/commits
+    /// payload = {"text": "{{repo}}: {{message}}"}
+    /// secret_env = RCS_WEBHOOK_SECRET
     /// ```
+    webhook: OnceCell<Webhook>,
+
+    /// The compact weekly row written next to each daily diary row.
     ///
-    /// # Configuration Source
+    /// # Configuration
     ///
-    /// Read from INI file:
+    /// Optional, loaded from INI file (no weekly row without it):
     /// ```text
-    /// [templates]
-    /// commit_datetime = %Y-%m-%d %H:%M:%S
+    /// [weekly_log]
+    /// enabled = true
+    /// note = Diaries/Weekly/%G-W%V.md
+    /// row_template = - {{date}} {{time}} **{{repo}}** {{message}}
     /// ```
-    pub fn get_template_commit_datetime(&self) -> String {
-        info!("[GlobalVars::get_template_commit_datetime()]: Getting template_commit_datetime.");
-        self.template_commit_datetime
-            .get()
-            .expect("Could not get template_commit_datetime")
-            .clone()
-    }
+    weekly_log: OnceCell<WeeklyLog>,
 
-    /// Retrieves a clone of the parsed INI configuration.
+    /// The daily note the day's diary file is linked from.
     ///
-    /// This is a private helper method that returns a copy of the configuration
-    /// object. Used internally by other helper methods to access sections and keys.
-    ///
-    /// # Panics
+    /// # Configuration
     ///
-    /// Panics if called before [`set_all()`](Self::set_all) has initialized the config.
+    /// Optional, loaded from INI file (only used when `enabled` is on):
+    /// ```text
+    /// [daily_note]
+    /// enabled = true
+    /// note = Journal/%F.md
+    /// link = embed
+    /// ```
+    daily_note: OnceCell<DailyNote>,
+
+    /// The note and format of the `heatmap` subcommand.
     ///
-    /// # Returns
+    /// # Configuration
     ///
-    /// A cloned `Ini` configuration object
-    fn get_config(&self) -> Ini {
-        info!("[GlobalVars::get_config()] Getting config");
-        self.config
-            .get()
-            .expect("Could not get Config. Config not initialized")
-            .clone()
-    }
-
-    fn get_key_from_section_from_ini(&self, section: &str, key: &str) -> Option<String> {
-        info!(
-            "[GlobalVars::get_key_from_section_from_ini()] Getting key: {key:} from section: {section:}."
-        );
-        self.config
-            .get()
-            .expect("Retrieving the config for commit_path")
-            .get(section, key)
-    }
+    /// Optional, loaded from INI file:
+    /// ```text
+    /// [heatmap]
+    /// note = Rollups/Commit Heatmap.md
+    /// format = html
+    /// ```
+    heatmap: OnceCell<HeatmapSettings>,
 
-    fn get_sections_from_config(&self) -> Vec<String> {
-        info!("[GlobalVars::get_sections_from_config()] Getting sections from config");
-        let sections = self.get_config().sections();
-        let sections_len = sections.len(); // Extract to variable
+    /// The size classes of the `#size/<class>` tags, by lines changed.
+    ///
+    /// # Configuration
+    ///
+    /// Optional, loaded from INI file (no size tag without it):
+    /// ```text
+    /// [size]
+    /// enabled = true
+    /// column = true
+    /// xs = 10
+    /// s = 50
+    /// m = 250
+    /// l = 1000
+    /// ```
+    size_thresholds: OnceCell<SizeThresholds>,
 
-        info!("[GlobalVars::get_sections_from_config()] Checking validity of number of sections.");
-        if sections_len == 2 {
-            sections
-        } else {
-            error!(
-                // LCOV_EXCL_START
-                "[GlobalVars::get_sections_from_config()] Sections Len must be 2, we have: {sections_len:?}"
-            );
-            error!(
-                "[GlobalVars::get_sections_from_config()] These are the sections found: {sections:?}"
-            ); // LCOV_EXCL_STOP
-            panic!(
-                "[GlobalVars::get_sections_from_config()] config has the wrong number of sections."
-            )
-        }
-    }
+    /// Whether the `SIZE` column is added to the table (`[size] column`).
+    size_column: OnceCell<bool>,
 
-    /// Loads all configuration variables from the "obsidian" and "templates" sections.
-    ///
-    /// This method iterates through all sections returned by `get_sections_from_config`.
-    /// For each recognized section, it initializes the corresponding runtime variables
-    /// by calling their dedicated setters:
+    /// How many commits after an annotated tag the `TAG` column still names it.
     ///
-    /// - For the **"obsidian"** section: calls `set_obsidian_root_path_dir` and `set_obsidian_commit_path`.
-    /// - For the **"templates"** section: calls `set_templates_commit_date_path` and `set_templates_datetime`.
+    /// # Configuration
     ///
-    /// # Panics
+    /// Optional, loaded from INI file (no `TAG` column without it):
+    /// ```text
+    /// [release_tags]
+    /// enabled = true
+    /// max_distance = 5
+    /// ```
+    release_tag_distance: OnceCell<usize>,
+
+    /// The translation of configured paths between WSL and Windows.
     ///
-    /// Panics if the INI file contains a section other than "obsidian" or "templates", as only these two sections are supported.
+    /// Read from the `[wsl]` section the first time a path needs it, whatever
+    /// the order of the sections.
     ///
-    /// # Logging
+    /// # Configuration
     ///
-    /// - Logs an info message when applying each section.
-    /// - Logs an error right before panicking on unsupported sections.
+    /// Optional, loaded from INI file (paths are not translated without it):
+    /// ```text
+    /// [wsl]
+    /// translate = true
+    /// mount_root = /mnt
+    /// folder_style = windows
+    /// ```
+    wsl_paths: OnceCell<WslPaths>,
+
+    /// Whether entries get a `#branch/<slug>` tag.
     ///
-    /// # Examples
+    /// # Configuration
     ///
-    /// ```ignore
-    /// use rusty_commit_saver::config::GlobalVars;
-    /// let mut config = configparser::ini::Ini::new();
-    /// config.set("obsidian", "root_path_dir", Some("~/Obsidian".to_string()));
-    /// config.set("obsidian", "commit_path", Some("Diary/Commits".to_string()));
-    /// config.set("templates", "commit_date_path", Some("%Y-%m-%d.md".to_string()));
-    /// config.set("templates", "commit_datetime", Some("%Y-%m-%d %H:%M:%S".to_string()));
-    /// let global_vars = GlobalVars::new();
-    /// global_vars.config.set(config).unwrap();
-    /// global_vars.set_obsidian_vars();
+    /// Optional, loaded from INI file (defaults to `false`):
+    /// ```text
+    /// [branches]
+    /// tags = true
     /// ```
-    pub fn set_obsidian_vars(&self) {
-        for section in self.get_sections_from_config() {
-            if section == "obsidian" {
-                info!("[GlobalVars::set_obsidian_vars()] Setting 'obsidian' section variables.");
-                self.set_obsidian_root_path_dir(&section);
-                self.set_obsidian_commit_path(&section);
-            } else if section == "templates" {
-                info!("[GlobalVars::set_obsidian_vars()] Setting 'templates' section variables.");
-                self.set_templates_commit_date_path(&section);
-                self.set_templates_datetime(&section);
-            } else {
-                error!(
-                    "[GlobalVars::set_obsidian_vars()] Trying to set other sections is not supported."
-                );
-                panic!(
-                    "[GlobalVars::set_obsidian_vars()] Trying to set other sections is not supported."
-                )
-            }
-        }
-    }
+    branches_tags: OnceCell<bool>,
 
-    /// Sets the `template_commit_datetime` field from the `[templates]` section.
+    /// Whether the diary table gets a `BRANCH SLUG` column.
     ///
-    /// Reads the `commit_datetime` key from the INI file and stores it in the
-    /// `template_commit_datetime` `OnceCell`.
-    ///
-    /// # Arguments
+    /// # Configuration
     ///
-    /// * `section` - Should be `"templates"` (validated by caller)
+    /// Optional, loaded from INI file (defaults to `false`):
+    /// ```text
+    /// [branches]
+    /// column = true
+    /// ```
+    branches_column: OnceCell<bool>,
+
+    /// Whether a merge closing out a branch gets a branch summary block.
     ///
-    /// # Panics
+    /// # Configuration
     ///
-    /// Panics if:
-    /// - The `commit_datetime` key is missing from the INI section
-    /// - The `OnceCell` has already been set (called multiple times)
+    /// Optional, loaded from INI file (defaults to `false`):
+    /// ```text
+    /// [branches]
+    /// merge_summary = true
+    /// ```
+    branches_merge_summary: OnceCell<bool>,
+
+    /// Branches whose commits are never logged.
     ///
-    /// # Expected INI Key
+    /// # Configuration
     ///
+    /// Optional, loaded from INI file (comma-separated, `*` wildcards):
     /// ```text
-    /// [templates]
-    /// commit_datetime = %Y-%m-%d %H:%M:%S
+    /// [filters]
+    /// ignore_branches = wip/*,tmp/*,dependabot/*
     /// ```
-    fn set_templates_datetime(&self, section: &str) {
-        info!("[GlobalVars::set_templates_datetime()]: Setting the templates_datetime.");
-        let key = self
-            .get_key_from_section_from_ini(section, "commit_datetime")
-            .expect("Could not get the commit_datetime from INI");
-
-        self.template_commit_datetime
-            .set(key)
-            .expect("Could not set the template_commit_datetime GlobalVars");
-    }
+    filters_ignore_branches: OnceCell<Vec<String>>,
 
-    /// Sets the `template_commit_date_path` field from the `[templates]` section.
-    ///
-    /// Reads the `commit_date_path` key from the INI file and stores it in the
-    /// `template_commit_date_path` `OnceCell`.
+    /// Commit messages that are never logged.
     ///
-    /// # Arguments
+    /// # Configuration
     ///
-    /// * `section` - Should be `"templates"` (validated by caller)
+    /// Optional, loaded from INI file (comma-separated regular expressions):
+    /// ```text
+    /// [filters]
+    /// ignore_message_patterns = ^fixup!,^squash!,^Merge branch
+    /// ```
+    filters_ignore_message_patterns: OnceCell<Vec<MessagePattern>>,
+
+    /// Whether commits of dependency update bots are never logged.
     ///
-    /// # Panics
+    /// # Configuration
     ///
-    /// Panics if:
-    /// - The `commit_date_path` key is missing from the INI section
-    /// - The `OnceCell` has already been set (called multiple times)
+    /// Optional, loaded from INI file (defaults to `false`):
+    /// ```text
+    /// [filters]
+    /// ignore_bots = true
+    /// ```
+    filters_ignore_bots: OnceCell<bool>,
+
+    /// Routes sending the commits of matching remotes to another commit path.
     ///
-    /// # Expected INI Key
+    /// # Configuration
     ///
+    /// Optional, loaded from INI file (one key per route):
     /// ```text
-    /// [templates]
-    /// commit_date_path = %Y/%m-%B/%F.md
+    /// [routing]
+    /// work = github.com/acme/* -> Work/Commits
     /// ```
-    fn set_templates_commit_date_path(&self, section: &str) {
-        info!(
-            "[GlobalVars::set_templates_commit_date_path()]: Setting the template_commit_date_path."
-        );
-        let key = self
-            .get_key_from_section_from_ini(section, "commit_date_path")
-            .expect("Could not get the commit_date_path from INI");
+    routes: OnceCell<Vec<Route>>,
 
-        self.template_commit_date_path
-            .set(key)
-            .expect("Could not set the template_commit_date_path in GlobalVars");
-    }
+    /// Extra vaults commits are written to when their rules match.
+    ///
+    /// # Configuration
+    ///
+    /// Optional, loaded from INI file (`<vault>.<key>` keys):
+    /// ```text
+    /// [vaults]
+    /// work.root = ~/Vaults/Work
+    /// work.remote = github\.com[:/]acme/
+    /// ```
+    vaults: OnceCell<Vec<VaultTarget>>,
 
-    /// Sets the `obsidian_commit_path` field from the `[obsidian]` section.
+    /// Link templates of self-hosted forges, on top of the built-in ones.
     ///
-    /// Reads the `commit_path` key, expands tilde (`~`) to the home directory
-    /// if present, splits the path by `/`, and constructs a `PathBuf`.
+    /// # Configuration
     ///
-    /// # Arguments
+    /// Optional, loaded from INI file (a preset name or a template per link kind):
+    /// ```text
+    /// [forges]
+    /// git.example.com = gitea
+    /// code.example.net.commit = https://{host}/browse/{path}/rev/{hash}
+    /// ```
+    forge_resolver: OnceCell<ForgeResolver>,
+
+    /// Directory scanned for repositories by `log-recent`.
     ///
-    /// * `section` - Should be `"obsidian"` (validated by caller)
+    /// # Configuration
     ///
-    /// # Tilde Expansion
+    /// Optional, loaded from INI file:
+    /// ```text
+    /// [recent]
+    /// projects_dir = ~/Projects
+    /// ```
+    recent_projects_dir: OnceCell<PathBuf>,
+
+    /// Repositories watched by the `daemon` subcommand.
     ///
-    /// - `~/Diaries/Commits` → `/home/user/Diaries/Commits`
-    /// - `/absolute/path` → `/absolute/path` (unchanged)
+    /// # Configuration
     ///
-    /// # Panics
+    /// Optional, loaded from INI file:
+    /// ```text
+    /// [repositories]
+    /// api = ~/Projects/api
+    /// web = ~/Projects/web
+    /// ```
+    watched_repositories: OnceCell<Vec<PathBuf>>,
+
+    /// How long the `daemon` subcommand waits for a diary note to stop
+    /// receiving entries before writing them.
     ///
-    /// Panics if:
-    /// - The `commit_path` key is missing from the INI section
-    /// - Home directory cannot be determined (when `~` is used)
-    /// - The `OnceCell` has already been set
+    /// # Configuration
     ///
-    /// # Expected INI Key
+    /// Optional, loaded from INI file:
+    /// ```text
+    /// [daemon]
+    /// debounce_ms = 2000
+    /// ```
+    daemon_debounce: OnceCell<Duration>,
+
+    /// Whether runs are counted in the local usage metrics.
+    ///
+    /// # Configuration
     ///
+    /// Optional, loaded from INI file (off unless enabled):
     /// ```text
-    /// [obsidian]
-    /// commit_path = ~/Documents/Obsidian/Diaries/Commits
+    /// [metrics]
+    /// enabled = true
     /// ```
-    fn set_obsidian_commit_path(&self, section: &str) {
-        let string_path = self
-            .get_key_from_section_from_ini(section, "commit_path")
-            .expect("Could not get commit_path from config");
+    metrics_enabled: OnceCell<bool>,
+}
 
-        let fixed_home = if string_path.contains('~') {
-            info!("[GlobalVars::set_obsidian_commit_path()]: Path does contain: '~'.");
-            set_proper_home_dir(&string_path)
-        } else {
-            info!("[GlobalVars::set_obsidian_commit_path()]: Path does NOT contain: '~'.");
-            string_path
-        };
+/// Settings that shape how entries are written to the diary.
+///
+/// Built from the INI file by [`GlobalVars::get_save_settings()`]; the
+/// [`Default`] value matches a configuration with only the required
+/// `[obsidian]` and `[templates]` sections.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::config::SaveSettings;
+/// use rusty_commit_saver::vim_commit::DEFAULT_TABLE_COLUMNS;
+///
+/// let settings = SaveSettings::default();
+/// assert_eq!(settings.table_columns, DEFAULT_TABLE_COLUMNS.to_vec());
+/// assert!(!settings.log_branch_switches);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveSettings {
+    /// The diary table columns, in order: `[table] columns`, or
+    /// [`DEFAULT_TABLE_COLUMNS`] without it.
+    ///
+    /// An `EVENT` column is appended when any workday event is logged, so
+    /// commits, branch switches and stashes can be told apart. Columns of
+    /// other enabled features are appended the same way, unless already listed.
+    pub table_columns: Vec<TableColumn>,
 
-        let vec_str = fixed_home.split('/');
+    /// Log branch switches from `event checkout` (`[events] branch_switches`).
+    pub log_branch_switches: bool,
 
-        let mut path = PathBuf::new();
+    /// Log stash pushes and pops from `event stash` (`[events] stash`).
+    pub log_stash: bool,
 
-        info!(
-            "[GlobalVars::set_obsidian_commit_path()]: Pushing strings folders to create PathBuf."
-        );
-        for s in vec_str {
-            path.push(s);
+    /// Log one summary row per pushed ref from `event push` (`[events] push`).
+    pub log_pushes: bool,
+
+    /// Cap for the `DURATION` column estimate (`[duration] max_minutes`).
+    pub duration_cap: TimeDelta,
+
+    /// Directory holding the tool's own bookkeeping (the logged-commits index).
+    ///
+    /// `None` disables the index, so nothing is read or written outside the vault.
+    pub state_dir: Option<PathBuf>,
+
+    /// Time tracker queried for the `TRACKING` column (`[time_tracking]`).
+    pub time_tracking: Option<TimeTrackingSource>,
+
+    /// Build check run for the `STATUS` column (`[build_status]`).
+    pub build_status: Option<BuildStatusCheck>,
+
+    /// Message template for committing the diary file in the vault repository
+    /// after each save (`[vault_git]`). `None` disables the auto-commit.
+    pub vault_commit_message: Option<String>,
+
+    /// Maximum row length before the message moves to the overflow note
+    /// (`[table] max_row_length`). `None` means no limit.
+    pub max_row_length: Option<usize>,
+
+    /// How entries name their timezone after the `TIME` and in the frontmatter
+    /// (`[table] time_zone`). `None` writes times without their zone.
+    pub time_zone: Option<TimezoneStyle>,
+
+    /// The timezone diary paths, dates and times are written in
+    /// (`[templates] timezone`). `None` keeps UTC, unless
+    /// [`time_zone`](Self::time_zone) is set, which uses the system timezone.
+    pub timezone: Option<DiaryTimezone>,
+
+    /// Chrono format of the `TIME` cell (`[templates] commit_datetime`).
+    /// `None` is [`DEFAULT_TIME_FORMAT`](crate::vim_commit::DEFAULT_TIME_FORMAT).
+    pub time_format: Option<String>,
+
+    /// Whether rows logged in the same second get a sequence suffix after the
+    /// `TIME` (`[table] same_second`).
+    pub same_second: SameSecondPolicy,
+
+    /// Whether `COMMIT HASH` cells link to the commit on its forge, using
+    /// [`forges`](Self::forges) (`[table] forge_links`).
+    pub forge_links: bool,
+
+    /// Whether table rows end with a `^<short-hash>` block anchor
+    /// (`[table] block_refs`).
+    pub block_refs: bool,
+
+    /// Rules resolving the entry category (`[categories]`). Empty disables categories.
+    pub category_rules: Vec<CategoryRule>,
+
+    /// Vault trash folder receiving removed notes and copies of rewritten ones
+    /// (`[safety] use_trash`). `None` deletes and rewrites in place.
+    pub trash_dir: Option<PathBuf>,
+
+    /// Refuse to write when the vault root has no `.obsidian` directory
+    /// (`[safety] require_vault`), unless `save --force` is given.
+    pub require_vault: bool,
+
+    /// Commit paths by remote URL pattern (`[routing]`). Empty keeps
+    /// `[obsidian] commit_path` for every repository.
+    pub routes: Vec<Route>,
+
+    /// Vaults written to instead of the `[obsidian]` one when their rules
+    /// match the commit (`[vaults]`). Empty writes every commit to the
+    /// `[obsidian]` vault.
+    pub vaults: Vec<VaultTarget>,
+
+    /// Web link builder for commits, branches and pull requests (`[forges]`).
+    pub forges: ForgeResolver,
+
+    /// Write remote URLs as HTTPS addresses (`[obsidian] normalize_remote`),
+    /// see [`normalize_remote_url()`](crate::forge::normalize_remote_url).
+    pub normalize_remote: bool,
+
+    /// Heading whose table the rows are inserted in (`[obsidian]
+    /// insert_under`). `None` appends rows at the end of the note, and adds
+    /// missing tables under
+    /// [`DEFAULT_TABLE_HEADING`](crate::existing_note::DEFAULT_TABLE_HEADING).
+    pub insert_under: Option<String>,
+
+    /// Name of the vault in Obsidian (`[obsidian] vault_name`), for
+    /// `save --open`. `None` uses the name of the vault directory.
+    pub vault_name: Option<String>,
+
+    /// Whether entries get a `#lang/<code>` tag from the detected commit
+    /// message language (`[language] detect`).
+    pub detect_language: bool,
+
+    /// Whether commit messages are parsed as Conventional Commits, for the
+    /// `TYPE`/`SCOPE`/`BREAKING`/`SUBJECT` columns and the `#commit/<type>`
+    /// tag (`[conventional_commits] enabled`).
+    pub conventional_commits: bool,
+
+    /// Where the tags of `Obsidian-Tags` commit trailers are written
+    /// (`[trailers] tags`). `None` ignores the trailers.
+    pub trailer_tags: Option<TagPlacement>,
+
+    /// Outputs commits are written to (`[output] sinks`), the diary
+    /// ([`SinkKind::ObsidianMarkdown`]) by default. Relative file paths are
+    /// resolved against the vault root.
+    pub sinks: Vec<SinkKind>,
+
+    /// Weekly commit goal written to the weekly note (`[goals]`). `None`
+    /// disables goal tracking.
+    pub weekly_goal: Option<WeeklyGoal>,
+
+    /// Note listing which repositories were worked on together, relative to
+    /// the vault root (`[related_repos]`). `None` disables the rollup.
+    pub related_repos_note: Option<PathBuf>,
+
+    /// Pattern commit messages should match (`[message_hygiene] pattern`).
+    /// Other commits get a `#needs-better-message` tag and are listed in the
+    /// weekly note. `None` disables the check.
+    pub message_pattern: Option<MessagePattern>,
+
+    /// Webhook every logged commit is `POST`ed to (`[webhook]`). `None`
+    /// sends nothing.
+    pub webhook: Option<Webhook>,
+
+    /// Weekly note every logged commit also gets a compact row in
+    /// (`[weekly_log]`). `None` only writes the daily row.
+    pub weekly_log: Option<WeeklyLog>,
+
+    /// The daily note the diary file is linked from (`[daily_note]`). `None`
+    /// leaves the daily notes alone.
+    pub daily_note: Option<DailyNote>,
+
+    /// The note and format of `heatmap` (`[heatmap]`). The default only
+    /// prints an HTML heatmap.
+    pub heatmap: HeatmapSettings,
+
+    /// Size classes of the commits (`[size]`), tagged `#size/<class>` in
+    /// the frontmatter and the `SIZE` column. `None` classifies nothing.
+    pub size_thresholds: Option<SizeThresholds>,
+
+    /// Commits after an annotated tag the `TAG` column still names it
+    /// (`[release_tags] max_distance`). `None` looks up no tag.
+    pub release_tag_distance: Option<usize>,
+
+    /// WSL/Windows path translation (`[wsl]`); its `folder_style` decides how
+    /// the `FOLDER` cell is written.
+    pub wsl_paths: WslPaths,
+
+    /// Whether entries get a `#branch/<slug>` tag (`[branches] tags`).
+    pub branch_tags: bool,
+
+    /// Whether a logged merge gets a summary of the branch it merged, read
+    /// from the logged-commits index (`[branches] merge_summary`).
+    pub branch_summary: bool,
+
+    /// Branch patterns whose commits are skipped (`[filters]
+    /// ignore_branches`), see [`is_ignored_branch()`](crate::skip::is_ignored_branch).
+    pub ignore_branches: Vec<String>,
+
+    /// Message patterns of commits that are skipped (`[filters]
+    /// ignore_message_patterns`), see
+    /// [`is_ignored_message()`](crate::skip::is_ignored_message).
+    pub ignore_message_patterns: Vec<MessagePattern>,
+
+    /// Whether commits of dependabot and renovate are skipped (`[filters]
+    /// ignore_bots`), see [`is_bot_commit()`](crate::skip::is_bot_commit).
+    pub ignore_bots: bool,
+
+    /// Daily-note template new diary files are created from
+    /// (`[templates] diary_template`). `None` uses the built-in template.
+    pub diary_template: Option<PathBuf>,
+
+    /// Handlebars template new diary files are rendered from
+    /// (`[templates] diary_file`). Takes precedence over `diary_template`.
+    pub diary_file_template: Option<PathBuf>,
+
+    /// Keep only the file name of `commit_date_path`, so every diary file
+    /// sits directly in the commit path (`[templates] flat_layout`).
+    pub flat_layout: bool,
+
+    /// Write entries as table rows, fenced YAML blocks or Dataview bullets
+    /// (`[output] style`, or `[templates] entry_format`), or from
+    /// [`row_template`](Self::row_template).
+    pub entry_format: EntryFormat,
+
+    /// How the `entry_id` and `block_id` template variables are generated
+    /// (`[templates] entry_id`).
+    pub entry_id_style: EntryIdStyle,
+
+    /// Handlebars template of each entry (`[templates] row_template`). When
+    /// set, [`entry_format`](Self::entry_format) is [`EntryFormat::Template`].
+    pub row_template: Option<String>,
+
+    /// Print the would-be entry instead of writing it (`save --dry-run`).
+    /// Only set from the command line, never from the configuration file.
+    pub dry_run: bool,
+
+    /// Append the entry even when its commit hash is already in the diary
+    /// file (`save --allow-duplicates`). Only set from the command line.
+    pub allow_duplicates: bool,
+}
+
+impl Default for SaveSettings {
+    fn default() -> Self {
+        SaveSettings {
+            table_columns: DEFAULT_TABLE_COLUMNS.to_vec(),
+            log_branch_switches: false,
+            log_stash: false,
+            log_pushes: false,
+            duration_cap: TimeDelta::minutes(DEFAULT_DURATION_MAX_MINUTES),
+            state_dir: None,
+            time_tracking: None,
+            build_status: None,
+            vault_commit_message: None,
+            max_row_length: None,
+            time_zone: None,
+            timezone: None,
+            time_format: None,
+            same_second: SameSecondPolicy::Keep,
+            forge_links: false,
+            block_refs: false,
+            category_rules: Vec::new(),
+            trash_dir: None,
+            require_vault: false,
+            routes: Vec::new(),
+            vaults: Vec::new(),
+            forges: ForgeResolver::default(),
+            detect_language: false,
+            conventional_commits: false,
+            trailer_tags: None,
+            sinks: vec![SinkKind::ObsidianMarkdown],
+            weekly_goal: None,
+            related_repos_note: None,
+            message_pattern: None,
+            webhook: None,
+            weekly_log: None,
+            daily_note: None,
+            heatmap: HeatmapSettings::default(),
+            size_thresholds: None,
+            release_tag_distance: None,
+            wsl_paths: WslPaths::default(),
+            normalize_remote: false,
+            insert_under: None,
+            vault_name: None,
+            branch_tags: false,
+            branch_summary: false,
+            ignore_branches: Vec::new(),
+            ignore_message_patterns: Vec::new(),
+            ignore_bots: false,
+            diary_template: None,
+            diary_file_template: None,
+            flat_layout: false,
+            entry_format: EntryFormat::Table,
+            entry_id_style: EntryIdStyle::Base32,
+            row_template: None,
+            dry_run: false,
+            allow_duplicates: false,
         }
-        self.obsidian_commit_path
-            .set(path)
-            .expect("Could not set the path for obsidian_root_path_dir");
     }
+}
 
-    /// Sets the `obsidian_root_path_dir` field from the `[obsidian]` section.
+impl SaveSettings {
+    /// Returns the settings deciding how entries are written in the diary:
+    /// columns, entry format, row template, time format, `[wsl]` folders,
+    /// forge links (when `[table] forge_links` is on), entry IDs and block
+    /// anchors.
+    #[must_use]
+    pub fn entry_settings(&self) -> EntrySettings {
+        EntrySettings {
+            table_columns: self.table_columns.clone(),
+            entry_format: self.entry_format,
+            row_template: self.row_template.clone(),
+            time_format: self.time_format.clone(),
+            wsl_paths: Some(self.wsl_paths.clone()),
+            forge_links: self.forge_links.then(|| self.forges.clone()),
+            entry_id_style: self.entry_id_style,
+            block_refs: self.block_refs,
+        }
+    }
+}
+
+impl GlobalVars {
+    /// Creates a new uninitialized `GlobalVars` instance.
     ///
-    /// Reads the `root_path_dir` key, expands tilde (`~`) to the home directory
-    /// if present, prepends `/` for absolute paths, and constructs a `PathBuf`.
+    /// This constructor initializes all fields as empty `OnceCell` values.
+    /// Use [`set_all()`](Self::set_all) to load configuration from the INI file.
     ///
-    /// # Arguments
+    /// # Thread Safety
     ///
-    /// * `section` - Should be `"obsidian"` (validated by caller)
+    /// `GlobalVars` uses `OnceCell` for thread-safe, lazy initialization.
+    /// Configuration values are set once and cannot be changed afterward.
     ///
-    /// # Path Construction
+    /// # Returns
     ///
-    /// - Starts with `/` to ensure absolute path
-    /// - Expands `~` to home directory
-    /// - Splits by `/` and constructs `PathBuf`
+    /// A new `GlobalVars` instance with all fields uninitialized
     ///
-    /// # Tilde Expansion Examples
+    /// # Fields
     ///
-    /// - `~/Documents/Obsidian` → `/home/user/Documents/Obsidian`
-    /// - `/absolute/path` → `/absolute/path`
+    /// - `config` - The parsed INI configuration file
+    /// - `obsidian_root_path_dir` - Root directory of Obsidian vault
+    /// - `obsidian_commit_path` - Subdirectory path for commit entries
+    /// - `obsidian_normalize_remote` - Optional SSH to HTTPS rewrite of remote URLs
+    /// - `obsidian_insert_under` - Optional heading the commit rows go under
+    /// - `obsidian_vault_name` - Optional vault name for `obsidian://` URIs
+    /// - `template_commit_date_path` - Chrono format for date-based directory structure
+    /// - `template_commit_datetime` - Chrono format for datetime strings
+    /// - `template_diary_file` - Optional daily-note template for new diary files
+    /// - `template_diary_handlebars` - Optional Handlebars template for new diary files
+    /// - `template_flat_layout` - Optional flat layout, without year/month directories
+    /// - `template_entry_format` - Optional entry format (table rows or YAML blocks)
+    /// - `template_entry_id` - Optional style of the entry IDs of row templates
+    /// - `template_timezone` - Optional timezone of the diary paths, dates and times
+    /// - `template_row_template` - Optional Handlebars template of each entry
+    /// - `events_branch_switches` / `events_stash` / `events_push` - Optional `[events]` toggles
+    /// - `duration_enabled` / `duration_max_minutes` - Optional `[duration]` column settings
+    /// - `time_tracking_source` - Optional `[time_tracking]` command or file
+    /// - `build_status_check` - Optional `[build_status]` command or file
+    /// - `vault_commit_message` - Optional `[vault_git]` auto-commit message template
+    /// - `table_max_row_length` / `table_columns` / `table_time_zone` / `table_same_second` /
+    ///   `table_forge_links` / `table_block_refs` - Optional `[table]` row length limit,
+    ///   column layout, timezone style, same-second policy, forge links and block anchors
+    /// - `category_rules` - Optional `[categories]` rules
+    /// - `safety_use_trash` - Optional `[safety]` trash setting
+    /// - `safety_require_vault` - Optional `[safety]` vault check
+    /// - `language_detect` - Optional `[language]` detection toggle
+    /// - `conventional_commits_enabled` - Optional `[conventional_commits]` parsing toggle
+    /// - `trailers_tags` - Optional `[trailers]` tag placement
+    /// - `output_sinks` - Optional `[output]` sinks
+    /// - `output_style` - Optional `[output]` entry format
+    /// - `weekly_goal` - Optional `[goals]` weekly commit goal
+    /// - `related_repos_note` - Optional `[related_repos]` rollup note
+    /// - `message_pattern` - Optional `[message_hygiene]` commit message pattern
+    /// - `webhook` - Optional `[webhook]` sink of logged commits
+    /// - `weekly_log` - Optional `[weekly_log]` row of the weekly note
+    /// - `daily_note` - Optional `[daily_note]` link from the daily note
+    /// - `heatmap` - Optional `[heatmap]` note and format of `heatmap`
+    /// - `size_thresholds` / `size_column` - Optional `[size]` classes and column
+    /// - `release_tag_distance` - Optional `[release_tags]` reach of the `TAG` column
+    /// - `wsl_paths` - Optional `[wsl]` path translation
+    /// - `branches_tags` / `branches_column` / `branches_merge_summary` - Optional
+    ///   `[branches]` slug tag, column and merge summary
+    /// - `routes` - Optional `[routing]` table
+    /// - `vaults` - Optional `[vaults]` routing to other vaults
+    /// - `forge_resolver` - Optional `[forges]` link templates
+    /// - `recent_projects_dir` - Optional `[recent]` projects directory for `log-recent`
+    /// - `watched_repositories` - Optional `[repositories]` list for `daemon`
+    /// - `daemon_debounce` - Optional `[daemon]` debounce window
+    /// - `metrics_enabled` - Optional `[metrics]` opt-in usage counters
+    /// - `filters_ignore_branches` / `filters_ignore_message_patterns` /
+    ///   `filters_ignore_bots` - Optional `[filters]` branches, messages and
+    ///   bots never logged
     ///
-    /// # Panics
+    /// # Examples
     ///
-    /// Panics if:
-    /// - The `root_path_dir` key is missing from the INI section
-    /// - Home directory cannot be determined (when `~` is used)
-    /// - The `OnceCell` has already been set
+    /// ```ignore
+    /// use rusty_commit_saver::config::GlobalVars;
     ///
-    /// # Expected INI Key
+    /// // Create new instance
+    /// let global_vars = GlobalVars::new();
     ///
-    /// ```text
-    /// [obsidian]
-    /// root_path_dir = ~/Documents/Obsidian
+    /// // Now call set_all() to initialize from config file
+    /// // global_vars.set_all();
     /// ```
-    fn set_obsidian_root_path_dir(&self, section: &str) {
-        let string_path = self
-            .get_key_from_section_from_ini(section, "root_path_dir")
-            .expect("Could not get commit_path from config");
+    #[must_use]
+    pub fn new() -> Self {
+        info!("[GlobalVars::new()] Creating new GlobalVars with OnceCell default values.");
+        GlobalVars {
+            config: OnceCell::new(),
 
-        let fixed_home = if string_path.contains('~') {
-            info!("[GlobalVars::set_obsidian_root_path_dir()]: Does contain ~");
-            set_proper_home_dir(&string_path)
-        } else {
-            info!("[GlobalVars::set_obsidian_root_path_dir()]: Does NOT contain ~");
-            string_path
-        };
+            obsidian_root_path_dir: OnceCell::new(),
+            obsidian_commit_path: OnceCell::new(),
+            obsidian_normalize_remote: OnceCell::new(),
+            obsidian_insert_under: OnceCell::new(),
+            obsidian_vault_name: OnceCell::new(),
 
-        let vec_str = fixed_home.split('/');
-        let mut path = PathBuf::new();
+            template_commit_date_path: OnceCell::new(),
+            template_commit_datetime: OnceCell::new(),
+            template_diary_file: OnceCell::new(),
+            template_diary_handlebars: OnceCell::new(),
+            template_flat_layout: OnceCell::new(),
+            template_entry_format: OnceCell::new(),
+            template_entry_id: OnceCell::new(),
+            template_timezone: OnceCell::new(),
+            template_row_template: OnceCell::new(),
 
-        info!(
-            "[GlobalVars::set_obsidian_root_path_dir()]: Pushing '/' to PathBuf for proper path."
-        );
-        path.push("/");
+            events_branch_switches: OnceCell::new(),
+            events_stash: OnceCell::new(),
+            events_push: OnceCell::new(),
 
-        info!(
-            "[GlobalVars::set_obsidian_root_path_dir()]: Pushing strings folders to create PathBuf."
-        );
-        for s in vec_str {
-            path.push(s);
-        }
+            duration_enabled: OnceCell::new(),
+            duration_max_minutes: OnceCell::new(),
 
-        self.obsidian_root_path_dir
-            .set(path)
-            .expect("Could not set the path for obsidian_root_path_dir");
+            time_tracking_source: OnceCell::new(),
+
+            build_status_check: OnceCell::new(),
+
+            vault_commit_message: OnceCell::new(),
+
+            table_max_row_length: OnceCell::new(),
+            table_columns: OnceCell::new(),
+            table_time_zone: OnceCell::new(),
+            table_same_second: OnceCell::new(),
+            table_forge_links: OnceCell::new(),
+            table_block_refs: OnceCell::new(),
+
+            category_rules: OnceCell::new(),
+
+            safety_use_trash: OnceCell::new(),
+            safety_require_vault: OnceCell::new(),
+
+            language_detect: OnceCell::new(),
+            conventional_commits_enabled: OnceCell::new(),
+            trailers_tags: OnceCell::new(),
+            output_sinks: OnceCell::new(),
+            output_style: OnceCell::new(),
+
+            weekly_goal: OnceCell::new(),
+
+            related_repos_note: OnceCell::new(),
+            message_pattern: OnceCell::new(),
+            webhook: OnceCell::new(),
+            weekly_log: OnceCell::new(),
+            daily_note: OnceCell::new(),
+            heatmap: OnceCell::new(),
+            size_thresholds: OnceCell::new(),
+            size_column: OnceCell::new(),
+            release_tag_distance: OnceCell::new(),
+            wsl_paths: OnceCell::new(),
+
+            branches_tags: OnceCell::new(),
+            branches_column: OnceCell::new(),
+            branches_merge_summary: OnceCell::new(),
+            filters_ignore_branches: OnceCell::new(),
+            filters_ignore_message_patterns: OnceCell::new(),
+            filters_ignore_bots: OnceCell::new(),
+
+            routes: OnceCell::new(),
+            vaults: OnceCell::new(),
+
+            forge_resolver: OnceCell::new(),
+
+            recent_projects_dir: OnceCell::new(),
+
+            watched_repositories: OnceCell::new(),
+
+            daemon_debounce: OnceCell::new(),
+
+            metrics_enabled: OnceCell::new(),
+        }
     }
-}
 
-/// Command-line argument parser for configuration file path.
-///
-/// This struct uses `clap` to parse CLI arguments and provide configuration
-/// options for the application. Currently supports specifying a custom INI
-/// configuration file path.
-///
-/// # CLI Arguments
-///
-/// - `--config-ini <PATH>` - Optional path to a custom configuration file
-///
-/// # Examples
-///
-/// ```text
-/// # Use default config (~/.config/rusty-commit-saver/rusty-commit-saver.ini)
-/// rusty-commit-saver
-///
-/// # Use custom config file
-/// rusty-commit-saver --config-ini /path/to/custom.ini
-/// ```
-///
-/// # See Also
-///
-/// - [`retrieve_config_file_path()`] - Gets the config path from CLI or default
-/// - [`get_ini_file()`] - Loads the INI file from the resolved path
-#[derive(Parser, Debug, Clone)]
-#[command(version, about, long_about = None)]
-#[command(propagate_version = true)]
-#[command(about = "Rusty Commit Saver config", long_about = None)]
-pub struct UserInput {
-    /// Path to a custom INI configuration file.
+    /// Loads and initializes all configuration from the INI file.
     ///
-    /// If not provided, the default configuration file is used:
-    /// `~/.config/rusty-commit-saver/rusty-commit-saver.ini`
+    /// This is the main entry point for configuration setup. It:
+    /// 1. Reads the INI configuration file from disk (or CLI argument)
+    /// 2. Replaces its values with the `RCS_*` environment variables that are
+    ///    set, see [`apply_env_overrides()`]
+    /// 3. Parses it into the `config` field
+    /// 4. Extracts and initializes all Obsidian and template variables
     ///
-    /// # CLI Usage
+    /// Configuration is loaded from (in order of preference):
+    /// - `--config-ini <PATH>` CLI argument
+    /// - Default: `~/.config/rusty-commit-saver/rusty-commit-saver.ini`
     ///
-    /// ```text
-    /// rusty-commit-saver --config-ini /custom/path/config.ini
-    /// ```
+    /// # Panics
     ///
-    /// # Examples
+    /// Panics if:
+    /// - Configuration file doesn't exist
+    /// - Configuration file cannot be read
+    /// - Configuration file has invalid INI format
+    /// - Required sections or keys are missing
+    /// - Required section count is not exactly 2 (obsidian + templates)
+    /// - An unsupported section is present (only `[events]`, `[duration]` and
+    ///   `[time_tracking]` are optional)
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining
+    ///
+    /// # Required INI Structure
+    ///
+    /// ```text
+    /// [obsidian]
+    /// root_path_dir = ~/Documents/Obsidian
+    /// commit_path = Diaries/Commits
+    ///
+    /// [templates]
+    /// commit_date_path = %Y/%m-%B/%F.md
+    /// commit_datetime = %H:%M:%S
+    /// ```
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rusty_commit_saver::config::GlobalVars;
+    ///
+    /// let global_vars = GlobalVars::new();
+    /// global_vars.set_all(); // Reads from default or CLI config
+    ///
+    /// // Now all getters will return values
+    /// let root_path = global_vars.get_obsidian_root_path_dir();
+    /// let commit_path = global_vars.get_obsidian_commit_path();
+    /// ```
+    pub fn set_all(&self) -> &Self {
+        info!("[GlobalVars::set_all()] Setting all variables for GlobalVars");
+        let mut config = get_ini_file();
+        apply_env_overrides(&mut config, |variable| env::var(variable).ok());
+
+        info!("[GlobalVars::set_all()]: Setting Config Ini file.");
+        self.config
+            .set(config)
+            .expect("Coulnd't set config in GlobalVars");
+
+        info!("[GlobalVars::set_all()]: Setting Obsidian variables from file.");
+        self.set_obsidian_vars();
+
+        self
+    }
+
+    /// Returns the root directory of the Obsidian vault.
+    ///
+    /// This is the base directory where all Obsidian vault files are stored.
+    /// All diary entries are created under this directory according to the
+    /// configured subdirectory structure.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called before [`set_all()`](Self::set_all) has been invoked
+    ///
+    /// # Returns
+    ///
+    /// A `PathBuf` representing the Obsidian vault root directory
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rusty_commit_saver::config::GlobalVars;
+    ///
+    /// let global_vars = GlobalVars::new();
+    /// global_vars.set_all();
+    ///
+    /// let root = global_vars.get_obsidian_root_path_dir();
+    /// println!("Obsidian vault root: {}", root.display());
+    /// // Output: Obsidian vault root: /home/user/Documents/Obsidian
+    /// ```
+    ///
+    /// # Configuration Source
+    ///
+    /// Read from INI file:
+    /// ```text
+    /// [obsidian]
+    /// root_path_dir = ~/Documents/Obsidian
+    /// ```
+    pub fn get_obsidian_root_path_dir(&self) -> PathBuf {
+        info!("[GlobalVars::get_obsidian_root_path_dir()]: Getting obsidian_root_path_dir.");
+        self.obsidian_root_path_dir
+            .get()
+            .expect("Could not get obsidian_root_path_dir")
+            .clone()
+    }
+
+    /// Returns the subdirectory path where commits are stored.
+    ///
+    /// This is a relative path under [`get_obsidian_root_path_dir()`](Self::get_obsidian_root_path_dir)
+    /// where commit diary entries will be organized. The full path is constructed by
+    /// combining this with the Obsidian root and the date-based directory structure.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called before [`set_all()`](Self::set_all) has been invoked
+    ///
+    /// # Returns
+    ///
+    /// A `PathBuf` representing the commits subdirectory (relative path)
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rusty_commit_saver::config::GlobalVars;
+    ///
+    /// let global_vars = GlobalVars::new();
+    /// global_vars.set_all();
+    ///
+    /// let commit_path = global_vars.get_obsidian_commit_path();
+    /// println!("Commit subdirectory: {}", commit_path.display());
+    /// // Output: Commit subdirectory: Diaries/Commits
+    ///
+    /// // Full path would be constructed as:
+    /// // /home/user/Documents/Obsidian/Diaries/Commits/2025/01-January/2025-01-14.md
+    /// ```
+    ///
+    /// # Configuration Source
+    ///
+    /// Read from INI file:
+    /// ```text
+    /// [obsidian]
+    /// commit_path = Diaries/Commits
+    /// ```
+    pub fn get_obsidian_commit_path(&self) -> PathBuf {
+        info!("[GlobalVars::get_obsidian_commit_path()]: Getting obsidian_commit_path.");
+        self.obsidian_commit_path
+            .get()
+            .expect("Could not get obsidian_commit_path")
+            .clone()
+    }
+
+    /// Returns the Chrono format string for diary file date hierarchies.
+    ///
+    /// This format string is used to create the directory structure and filename
+    /// for diary entries based on the commit timestamp. It controls how commits
+    /// are organized by date.
+    ///
+    /// # Chrono Format Specifiers
+    ///
+    /// - `%Y` - Full year (e.g., `2025`)
+    /// - `%m` - Month as zero-padded number (e.g., `01`)
+    /// - `%B` - Full month name (e.g., `January`)
+    /// - `%b` - Abbreviated month (e.g., `Jan`)
+    /// - `%d` - Day of month, zero-padded (e.g., `14`)
+    /// - `%F` - ISO 8601 date (equivalent to `%Y-%m-%d`, e.g., `2025-01-14`)
+    /// - `%H` - Hour in 24-hour format (e.g., `14`)
+    /// - `%M` - Minute (e.g., `30`)
+    /// - `%S` - Second (e.g., `45`)
+    ///
+    /// # Panics
+    ///
+    /// Panics if called before [`set_all()`](Self::set_all) has been invoked
+    ///
+    /// # Returns
+    ///
+    /// A `String` containing the Chrono format specifiers
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rusty_commit_saver::config::GlobalVars;
+    ///
+    /// let global_vars = GlobalVars::new();
+    /// global_vars.set_all();
+    ///
+    /// let date_template = global_vars.get_template_commit_date_path();
+    /// println!("Date format: {}", date_template);
+    /// // Output: Date format: %Y/%m-%B/%F.md
+    ///
+    /// // This creates paths like:
+    /// // /home/user/Obsidian/Diaries/Commits/2025/01-January/2025-01-14.md
+    /// ```
+    ///
+    /// # Configuration Source
+    ///
+    /// Read from INI file:
+    /// ```text
+    /// [templates]
+    /// commit_date_path = %Y/%m-%B/%F.md
+    /// ```
+    pub fn get_template_commit_date_path(&self) -> String {
+        info!("[GlobalVars::get_template_commit_date_path()]: Getting template_commit_date_path.");
+        self.template_commit_date_path
+            .get()
+            .expect("Could not get template_commit_date_path")
+            .clone()
+    }
+
+    /// Returns the Chrono format string for commit timestamps in diary entries.
+    ///
+    /// This format string is used to display the commit time in the diary table.
+    /// It controls how timestamps appear in the commit entry rows.
+    ///
+    /// # Chrono Format Specifiers
+    ///
+    /// - `%Y` - Full year (e.g., `2025`)
+    /// - `%m` - Month as zero-padded number (e.g., `01`)
+    /// - `%B` - Full month name (e.g., `January`)
+    /// - `%d` - Day of month, zero-padded (e.g., `14`)
+    /// - `%H` - Hour in 24-hour format (e.g., `14`)
+    /// - `%M` - Minute, zero-padded (e.g., `30`)
+    /// - `%S` - Second, zero-padded (e.g., `45`)
+    /// - `%T` - Time in HH:MM:SS format (equivalent to `%H:%M:%S`)
+    ///
+    /// # Panics
+    ///
+    /// Panics if called before [`set_all()`](Self::set_all) has been invoked
+    ///
+    /// # Returns
+    ///
+    /// A `String` containing the Chrono format specifiers for datetime
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rusty_commit_saver::config::GlobalVars;
+    ///
+    /// let global_vars = GlobalVars::new();
+    /// global_vars.set_all();
+    ///
+    /// let datetime_template = global_vars.get_template_commit_datetime();
+    /// println!("Datetime format: {}", datetime_template);
+    /// // Output: Datetime format: %H:%M:%S
+    ///
+    /// // This renders timestamps like:
+    /// // 14:30:45
+    /// ```
+    ///
+    /// # Diary Table Usage
+    ///
+    /// In the diary table, this format appears in the TIME column:
+    /// ```text
+    /// | FOLDER | TIME | COMMIT MESSAGE | REPOSITORY URL | BRANCH | COMMIT HASH |
+    /// |--------|------|----------------|----------------|--------|-------------|
+    /// | /work/project | 14:30:45 | feat: add feature | https://github.com/... | main | abc123... |
+    /// ```
+    ///
+    /// # Configuration Source
+    ///
+    /// Read from INI file:
+    /// ```text
+    /// [templates]
+    /// commit_datetime = %H:%M:%S
+    /// ```
+    pub fn get_template_commit_datetime(&self) -> String {
+        info!("[GlobalVars::get_template_commit_datetime()]: Getting template_commit_datetime.");
+        self.template_commit_datetime
+            .get()
+            .expect("Could not get template_commit_datetime")
+            .clone()
+    }
+
+    /// Returns the directory `log-recent` scans for repositories, if configured.
+    ///
+    /// # Configuration Source
+    ///
+    /// Read from INI file:
+    /// ```text
+    /// [recent]
+    /// projects_dir = ~/Projects
+    /// ```
+    pub fn get_recent_projects_dir(&self) -> Option<PathBuf> {
+        info!("[GlobalVars::get_recent_projects_dir()]: Getting recent_projects_dir.");
+        self.recent_projects_dir.get().cloned()
+    }
+
+    /// Returns the repositories the `daemon` subcommand watches, sorted by name.
+    ///
+    /// Empty when the `[repositories]` section is missing.
+    ///
+    /// # Configuration Source
+    ///
+    /// Read from INI file:
+    /// ```text
+    /// [repositories]
+    /// api = ~/Projects/api
+    /// ```
+    pub fn get_watched_repositories(&self) -> Vec<PathBuf> {
+        info!("[GlobalVars::get_watched_repositories()]: Getting watched_repositories.");
+        self.watched_repositories.get().cloned().unwrap_or_default()
+    }
+
+    /// Returns how long the `daemon` subcommand lets the entries of a diary
+    /// note settle before writing them, see
+    /// [`AppendQueue`](crate::append_queue::AppendQueue).
+    ///
+    /// Defaults to [`DEFAULT_DEBOUNCE`].
+    ///
+    /// # Configuration Source
+    ///
+    /// Read from INI file:
+    /// ```text
+    /// [daemon]
+    /// debounce_ms = 2000
+    /// ```
+    pub fn get_daemon_debounce(&self) -> Duration {
+        info!("[GlobalVars::get_daemon_debounce()]: Getting daemon_debounce.");
+        self.daemon_debounce
+            .get()
+            .copied()
+            .unwrap_or(DEFAULT_DEBOUNCE)
+    }
+
+    /// Returns `true` if runs are counted in the local usage metrics.
+    ///
+    /// Off unless the user opted in.
+    ///
+    /// # Configuration Source
+    ///
+    /// Read from INI file:
+    /// ```text
+    /// [metrics]
+    /// enabled = true
+    /// ```
+    pub fn get_metrics_enabled(&self) -> bool {
+        info!("[GlobalVars::get_metrics_enabled()]: Getting metrics_enabled.");
+        self.metrics_enabled.get().copied().unwrap_or(false)
+    }
+
+    /// Returns the `[table] columns` (or [`DEFAULT_TABLE_COLUMNS`]), followed by
+    /// the columns of enabled features that are not listed: `DURATION`,
+    /// `TRACKING`, `STATUS`, `BRANCH SLUG` and `EVENT`.
+    fn resolved_table_columns(&self) -> Vec<TableColumn> {
+        let mut table_columns = self
+            .table_columns
+            .get()
+            .cloned()
+            .unwrap_or_else(|| DEFAULT_TABLE_COLUMNS.to_vec());
+        let mut add_column = |column: TableColumn| {
+            if !table_columns.contains(&column) {
+                table_columns.push(column);
+            }
+        };
+        if self.duration_enabled.get().copied().unwrap_or(false) {
+            info!("[GlobalVars::resolved_table_columns()]: Duration enabled, adding the DURATION column.");
+            add_column(TableColumn::Duration);
+        }
+        if self.time_tracking_source.get().is_some() {
+            info!("[GlobalVars::resolved_table_columns()]: Time tracking set, adding the TRACKING column.");
+            add_column(TableColumn::Tracking);
+        }
+        if self.build_status_check.get().is_some() {
+            info!("[GlobalVars::resolved_table_columns()]: Build status set, adding the STATUS column.");
+            add_column(TableColumn::Status);
+        }
+        if self.branches_column.get().copied().unwrap_or(false) {
+            info!("[GlobalVars::resolved_table_columns()]: Branch slugs enabled, adding the BRANCH SLUG column.");
+            add_column(TableColumn::BranchSlug);
+        }
+        if self.size_column.get().copied().unwrap_or(false) {
+            info!("[GlobalVars::resolved_table_columns()]: Size column enabled, adding the SIZE column.");
+            add_column(TableColumn::Size);
+        }
+        if self.release_tag_distance.get().is_some() {
+            info!("[GlobalVars::resolved_table_columns()]: Release tags enabled, adding the TAG column.");
+            add_column(TableColumn::Tag);
+        }
+        let events = [
+            &self.events_branch_switches,
+            &self.events_stash,
+            &self.events_push,
+        ];
+        if events
+            .iter()
+            .any(|event| event.get().copied().unwrap_or(false))
+        {
+            info!(
+                "[GlobalVars::resolved_table_columns()]: Events enabled, adding the EVENT column."
+            );
+            add_column(TableColumn::Event);
+        }
+        table_columns
+    }
+
+    /// Returns the settings used when writing entries to the diary.
+    ///
+    /// Optional sections that are missing from the INI file fall back to the
+    /// [`SaveSettings::default()`] values. The state directory is always the
+    /// platform default from [`default_state_dir()`].
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rusty_commit_saver::config::GlobalVars;
+    ///
+    /// let global_vars = GlobalVars::new();
+    /// global_vars.set_all();
+    ///
+    /// let settings = global_vars.get_save_settings();
+    /// println!("Columns: {:?}", settings.table_columns);
+    /// ```
+    ///
+    /// # Configuration Source
+    ///
+    /// Read from INI file:
+    /// ```text
+    /// [obsidian]
+    /// normalize_remote = true
+    ///
+    /// [templates]
+    /// flat_layout = true
+    /// entry_format = yaml
+    ///
+    /// [events]
+    /// branch_switches = true
+    /// stash = true
+    /// push = true
+    ///
+    /// [duration]
+    /// enabled = true
+    /// max_minutes = 120
+    ///
+    /// [time_tracking]
+    /// command = timew get dom.active.tag.1
+    ///
+    /// [vault_git]
+    /// auto_commit = true
+    ///
+    /// [table]
+    /// max_row_length = 500
+    /// columns = time, message, branch, hash
+    /// time_zone = abbreviation
+    /// same_second = sequence
+    /// forge_links = true
+    /// block_refs = true
+    ///
+    /// [categories]
+    /// work = *github.com/acme/*
+    ///
+    /// [safety]
+    /// use_trash = true
+    /// require_vault = true
+    ///
+    /// [routing]
+    /// work = github.com/acme/* -> Work/Commits
+    ///
+    /// [forges]
+    /// git.example.com = gitea
+    ///
+    /// [language]
+    /// detect = true
+    ///
+    /// [goals]
+    /// weekly_commits = 20
+    ///
+    /// [related_repos]
+    /// enabled = true
+    ///
+    /// [message_hygiene]
+    /// pattern = ^(feat|fix|docs|chore): .+
+    ///
+    /// [webhook]
+    /// url = https://n8n.example.com/webhook/commits
+    ///
+    /// [weekly_log]
+    /// enabled = true
+    ///
+    /// [size]
+    /// enabled = true
+    ///
+    /// [release_tags]
+    /// enabled = true
+    ///
+    /// [wsl]
+    /// translate = true
+    /// folder_style = windows
+    ///
+    /// [branches]
+    /// tags = true
+    /// column = true
+    /// ```
+    ///
+    /// The trash folder is `<root_path_dir>/.trash`, Obsidian's own trash.
+    /// A relative `[templates] diary_template` or `diary_file` is resolved
+    /// against `<root_path_dir>`.
+    pub fn get_save_settings(&self) -> SaveSettings {
+        info!("[GlobalVars::get_save_settings()]: Building the SaveSettings.");
+        let log_branch_switches = self.events_branch_switches.get().copied().unwrap_or(false);
+        let log_stash = self.events_stash.get().copied().unwrap_or(false);
+        let log_pushes = self.events_push.get().copied().unwrap_or(false);
+
+        let duration_max_minutes = self
+            .duration_max_minutes
+            .get()
+            .copied()
+            .unwrap_or(DEFAULT_DURATION_MAX_MINUTES);
+
+        SaveSettings {
+            table_columns: self.resolved_table_columns(),
+            log_branch_switches,
+            log_stash,
+            log_pushes,
+            duration_cap: TimeDelta::minutes(duration_max_minutes),
+            state_dir: default_state_dir(),
+            time_tracking: self.time_tracking_source.get().cloned(),
+            build_status: self.build_status_check.get().cloned(),
+            vault_commit_message: self.vault_commit_message.get().cloned(),
+            max_row_length: self.table_max_row_length.get().copied(),
+            time_zone: self.table_time_zone.get().copied(),
+            timezone: self.template_timezone.get().copied(),
+            time_format: self.template_commit_datetime.get().cloned(),
+            same_second: self.table_same_second.get().copied().unwrap_or_default(),
+            forge_links: self.table_forge_links.get().copied().unwrap_or(false),
+            block_refs: self.table_block_refs.get().copied().unwrap_or(false),
+            category_rules: self.category_rules.get().cloned().unwrap_or_default(),
+            trash_dir: if self.safety_use_trash.get().copied().unwrap_or(true) {
+                self.obsidian_root_path_dir
+                    .get()
+                    .map(|root| root.join(VAULT_TRASH_DIR))
+            } else {
+                None
+            },
+            require_vault: self.safety_require_vault.get().copied().unwrap_or(false),
+            routes: self.routes.get().cloned().unwrap_or_default(),
+            vaults: self.vaults.get().cloned().unwrap_or_default(),
+            forges: self.forge_resolver.get().cloned().unwrap_or_default(),
+            normalize_remote: self.obsidian_normalize_remote.get() == Some(&true),
+            insert_under: self.obsidian_insert_under.get().cloned(),
+            vault_name: self.obsidian_vault_name.get().cloned(),
+            detect_language: self.language_detect.get().copied().unwrap_or(false),
+            conventional_commits: self
+                .conventional_commits_enabled
+                .get()
+                .copied()
+                .unwrap_or(false),
+            trailer_tags: self.trailers_tags.get().copied(),
+            sinks: self.output_sinks(),
+            weekly_goal: self.weekly_goal.get().cloned(),
+            related_repos_note: self.related_repos_note.get().cloned(),
+            message_pattern: self.message_pattern.get().cloned(),
+            webhook: self.webhook.get().cloned(),
+            weekly_log: self.weekly_log.get().cloned(),
+            daily_note: self.daily_note.get().cloned(),
+            heatmap: self.heatmap.get().cloned().unwrap_or_default(),
+            size_thresholds: self.size_thresholds.get().copied(),
+            release_tag_distance: self.release_tag_distance.get().copied(),
+            wsl_paths: self.wsl_paths.get().cloned().unwrap_or_default(),
+            branch_tags: self.branches_tags.get().copied().unwrap_or(false),
+            branch_summary: self.branches_merge_summary.get().copied().unwrap_or(false),
+            ignore_branches: self
+                .filters_ignore_branches
+                .get()
+                .cloned()
+                .unwrap_or_default(),
+            ignore_message_patterns: self
+                .filters_ignore_message_patterns
+                .get()
+                .cloned()
+                .unwrap_or_default(),
+            ignore_bots: self.filters_ignore_bots.get().copied().unwrap_or(false),
+            diary_template: self
+                .template_diary_file
+                .get()
+                .map(|path| self.in_vault(path)),
+            diary_file_template: self
+                .template_diary_handlebars
+                .get()
+                .map(|path| self.in_vault(path)),
+            flat_layout: self.template_flat_layout.get().copied().unwrap_or(false),
+            entry_format: if self.template_row_template.get().is_some() {
+                EntryFormat::Template
+            } else {
+                self.output_style
+                    .get()
+                    .or(self.template_entry_format.get())
+                    .copied()
+                    .unwrap_or_default()
+            },
+            entry_id_style: self.template_entry_id.get().copied().unwrap_or_default(),
+            row_template: self.template_row_template.get().cloned(),
+            dry_run: false,
+            allow_duplicates: false,
+        }
+    }
+
+    /// Returns the `[output]` sinks, with relative file paths resolved
+    /// against the vault root, or the diary alone when unset.
+    fn output_sinks(&self) -> Vec<SinkKind> {
+        let Some(sinks) = self.output_sinks.get() else {
+            return vec![SinkKind::ObsidianMarkdown];
+        };
+        sinks
+            .iter()
+            .map(|sink| match sink {
+                SinkKind::Jsonl(path) => SinkKind::Jsonl(self.in_vault(path)),
+                SinkKind::Sqlite(path) => SinkKind::Sqlite(self.in_vault(path)),
+                other => other.clone(),
+            })
+            .collect()
+    }
+
+    /// Resolves a configured path against the vault root, when it is known.
+    fn in_vault(&self, path: &Path) -> PathBuf {
+        self.obsidian_root_path_dir
+            .get()
+            .map_or_else(|| path.to_path_buf(), |root| root.join(path))
+    }
+
+    /// Retrieves a clone of the parsed INI configuration.
+    ///
+    /// This is a private helper method that returns a copy of the configuration
+    /// object. Used internally by other helper methods to access sections and keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called before [`set_all()`](Self::set_all) has initialized the config.
+    ///
+    /// # Returns
+    ///
+    /// A cloned `Ini` configuration object
+    fn get_config(&self) -> Ini {
+        info!("[GlobalVars::get_config()] Getting config");
+        self.config
+            .get()
+            .expect("Could not get Config. Config not initialized")
+            .clone()
+    }
+
+    fn get_key_from_section_from_ini(&self, section: &str, key: &str) -> Option<String> {
+        info!(
+            "[GlobalVars::get_key_from_section_from_ini()] Getting key: {key:} from section: {section:}."
+        );
+        self.config
+            .get()
+            .expect("Retrieving the config for commit_path")
+            .get(section, key)
+    }
+
+    /// Returns the known sections of the configuration, in file order.
+    ///
+    /// Sections are looked up by name: an unknown section (a typo, one added
+    /// by a newer version, or the user's own notes) is skipped with a warning
+    /// instead of stopping the tool.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the [`REQUIRED_SECTIONS`] is missing.
+    fn get_sections_from_config(&self) -> Vec<String> {
+        info!("[GlobalVars::get_sections_from_config()] Getting sections from config");
+        let sections = self.get_config().sections();
+
+        info!("[GlobalVars::get_sections_from_config()] Checking the required sections.");
+        for required in REQUIRED_SECTIONS {
+            if !sections.iter().any(|section| section == required) {
+                error!(
+                    "[GlobalVars::get_sections_from_config()] These are the sections found: {sections:?}"
+                );
+                panic!(
+                    "[GlobalVars::get_sections_from_config()] config is missing the required '{required:}' section."
+                );
+            }
+        }
+
+        sections
+            .into_iter()
+            .filter(|section| {
+                let known = REQUIRED_SECTIONS.contains(&section.as_str())
+                    || OPTIONAL_SECTIONS.contains(&section.as_str());
+                if !known {
+                    warn!(
+                        "[GlobalVars::get_sections_from_config()] Ignoring the unknown '{section:}' section."
+                    );
+                }
+                known
+            })
+            .collect()
+    }
+
+    /// Loads all configuration variables from the "obsidian" and "templates" sections.
+    ///
+    /// This method iterates through all sections returned by `get_sections_from_config`.
+    /// For each recognized section, it initializes the corresponding runtime variables
+    /// by calling their dedicated setters:
+    ///
+    /// - For the **"obsidian"** section: calls `set_obsidian_root_path_dir`, `set_obsidian_commit_path`,
+    ///   `set_obsidian_normalize_remote`, `set_obsidian_insert_under` and
+    ///   `set_obsidian_vault_name`.
+    /// - For the **"templates"** section: calls `set_templates_commit_date_path`, `set_templates_datetime`,
+    ///   `set_templates_diary_template`, `set_templates_diary_file`, `set_templates_flat_layout`,
+    ///   `set_templates_entry_format`, `set_templates_entry_id`, `set_templates_timezone` and
+    ///   `set_templates_row_template`.
+    /// - For the optional **"events"** section: calls `set_events_vars`.
+    /// - For the optional **"duration"** section: calls `set_duration_vars`.
+    /// - For the optional **`time_tracking`** section: calls `set_time_tracking_source`.
+    /// - For the optional **`build_status`** section: calls `set_build_status_check`.
+    /// - For the optional **`vault_git`** section: calls `set_vault_git_vars`.
+    /// - For the optional **"table"** section: calls `set_table_vars`.
+    /// - For the optional **"categories"** section: calls `set_category_rules`.
+    /// - For the optional **"safety"** section: calls `set_safety_vars`.
+    /// - For the optional **"routing"** section: calls `set_routes`.
+    /// - For the optional **"vaults"** section: calls `set_vaults`.
+    /// - For the optional **"forges"** section: calls `set_forge_resolver`.
+    /// - For the optional **"language"** section: calls `set_language_vars`.
+    /// - For the optional **`conventional_commits`** section: calls `set_conventional_commits_vars`.
+    /// - For the optional **"trailers"** section: calls `set_trailers_vars`.
+    /// - For the optional **"output"** section: calls `set_output_vars` and
+    ///   `set_output_style`.
+    /// - For the optional **"goals"** section: calls `set_goals_vars`.
+    /// - For the optional **`related_repos`** section: calls `set_related_repos_vars`.
+    /// - For the optional **`message_hygiene`** section: calls `set_message_hygiene_vars`.
+    /// - For the optional **"wsl"** section: calls `set_wsl_vars`.
+    /// - For the optional **"webhook"** section: calls `set_webhook_vars`.
+    /// - For the optional **`weekly_log`** section: calls `set_weekly_log_vars`.
+    /// - For the optional **`daily_note`** section: calls `set_daily_note_vars`.
+    /// - For the optional **"heatmap"** section: calls `set_heatmap_vars`.
+    /// - For the optional **"size"** section: calls `set_size_vars`.
+    /// - For the optional **`release_tags`** section: calls `set_release_tags_vars`.
+    /// - For the optional **"branches"** section: calls `set_branches_vars`.
+    /// - For the optional **"recent"** section: calls `set_recent_projects_dir`.
+    /// - For the optional **"repositories"** section: calls `set_watched_repositories`.
+    /// - For the optional **"daemon"** section: calls `set_daemon_vars`.
+    /// - For the optional **"metrics"** section: calls `set_metrics_vars`.
+    /// - For the optional **"filters"** section: calls `set_filters_vars`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the "obsidian" or "templates" section is missing, see
+    /// `get_sections_from_config`. Other sections than the ones above are
+    /// skipped with a warning.
+    ///
+    /// # Logging
+    ///
+    /// - Logs an info message when applying each section.
+    /// - Logs a warning for each unknown section.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rusty_commit_saver::config::GlobalVars;
+    /// let mut config = configparser::ini::Ini::new();
+    /// config.set("obsidian", "root_path_dir", Some("~/Obsidian".to_string()));
+    /// config.set("obsidian", "commit_path", Some("Diary/Commits".to_string()));
+    /// config.set("templates", "commit_date_path", Some("%Y-%m-%d.md".to_string()));
+    /// config.set("templates", "commit_datetime", Some("%H:%M:%S".to_string()));
+    /// let global_vars = GlobalVars::new();
+    /// global_vars.config.set(config).unwrap();
+    /// global_vars.set_obsidian_vars();
+    /// ```
+    pub fn set_obsidian_vars(&self) {
+        for section in self.get_sections_from_config() {
+            info!("[GlobalVars::set_obsidian_vars()] Setting '{section:}' section variables.");
+            match section.as_str() {
+                "obsidian" => {
+                    self.set_obsidian_root_path_dir(&section);
+                    self.set_obsidian_commit_path(&section);
+                    self.set_obsidian_normalize_remote(&section);
+                    self.set_obsidian_insert_under(&section);
+                    self.set_obsidian_vault_name(&section);
+                }
+                "templates" => {
+                    self.set_templates_commit_date_path(&section);
+                    self.set_templates_datetime(&section);
+                    self.set_templates_diary_template(&section);
+                    self.set_templates_diary_file(&section);
+                    self.set_templates_flat_layout(&section);
+                    self.set_templates_entry_format(&section);
+                    self.set_templates_entry_id(&section);
+                    self.set_templates_timezone(&section);
+                    self.set_templates_row_template(&section);
+                }
+                "events" => self.set_events_vars(&section),
+                "duration" => self.set_duration_vars(&section),
+                "time_tracking" => self.set_time_tracking_source(&section),
+                "build_status" => self.set_build_status_check(&section),
+                "vault_git" => self.set_vault_git_vars(&section),
+                "table" => self.set_table_vars(&section),
+                "categories" => self.set_category_rules(&section),
+                "safety" => self.set_safety_vars(&section),
+                "routing" => self.set_routes(&section),
+                "vaults" => self.set_vaults(&section),
+                "forges" => self.set_forge_resolver(&section),
+                "language" => self.set_language_vars(&section),
+                "conventional_commits" => self.set_conventional_commits_vars(&section),
+                "trailers" => self.set_trailers_vars(&section),
+                "output" => {
+                    self.set_output_vars(&section);
+                    self.set_output_style(&section);
+                }
+                "goals" => self.set_goals_vars(&section),
+                "related_repos" => self.set_related_repos_vars(&section),
+                "message_hygiene" => self.set_message_hygiene_vars(&section),
+                "wsl" => self.set_wsl_vars(),
+                "webhook" => self.set_webhook_vars(&section),
+                "weekly_log" => self.set_weekly_log_vars(&section),
+                "daily_note" => self.set_daily_note_vars(&section),
+                "heatmap" => self.set_heatmap_vars(&section),
+                "size" => self.set_size_vars(&section),
+                "release_tags" => self.set_release_tags_vars(&section),
+                "branches" => self.set_branches_vars(&section),
+                "recent" => self.set_recent_projects_dir(&section),
+                "repositories" => self.set_watched_repositories(&section),
+                "daemon" => self.set_daemon_vars(&section),
+                "metrics" => self.set_metrics_vars(&section),
+                "filters" => self.set_filters_vars(&section),
+                _ => unreachable!(
+                    "[GlobalVars::set_obsidian_vars()] Unknown sections are skipped beforehand."
+                ),
+            }
+        }
+    }
+
+    /// Sets the `events_branch_switches`, `events_stash` and `events_push` fields
+    /// from the `[events]` section.
+    ///
+    /// All keys are optional and default to `false`. Values accept the usual
+    /// INI booleans (`true`/`false`, `yes`/`no`, `on`/`off`, `1`/`0`).
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"events"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - A key holds a value that is not a boolean
+    /// - The `OnceCell`s have already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [events]
+    /// branch_switches = true
+    /// stash = false
+    /// push = true
+    /// ```
+    fn set_events_vars(&self, section: &str) {
+        info!("[GlobalVars::set_events_vars()]: Setting the events toggles.");
+        let config = self.get_config();
+        let branch_switches = config
+            .getboolcoerce(section, "branch_switches")
+            .expect("Could not parse branch_switches from INI as a boolean")
+            .unwrap_or(false);
+        let stash = config
+            .getboolcoerce(section, "stash")
+            .expect("Could not parse stash from INI as a boolean")
+            .unwrap_or(false);
+        let push = config
+            .getboolcoerce(section, "push")
+            .expect("Could not parse push from INI as a boolean")
+            .unwrap_or(false);
+
+        self.events_branch_switches
+            .set(branch_switches)
+            .expect("Could not set the events_branch_switches in GlobalVars");
+        self.events_stash
+            .set(stash)
+            .expect("Could not set the events_stash in GlobalVars");
+        self.events_push
+            .set(push)
+            .expect("Could not set the events_push in GlobalVars");
+    }
+
+    /// Sets the `duration_enabled` and `duration_max_minutes` fields from the `[duration]` section.
+    ///
+    /// Both keys are optional: `enabled` defaults to `false` and
+    /// `max_minutes` to `120`.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"duration"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `enabled` is not a boolean
+    /// - `max_minutes` is not a positive integer
+    /// - The `OnceCell`s have already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [duration]
+    /// enabled = true
+    /// max_minutes = 120
+    /// ```
+    fn set_duration_vars(&self, section: &str) {
+        info!("[GlobalVars::set_duration_vars()]: Setting the duration column settings.");
+        let config = self.get_config();
+        let enabled = config
+            .getboolcoerce(section, "enabled")
+            .expect("Could not parse enabled from INI as a boolean")
+            .unwrap_or(false);
+        let max_minutes = config
+            .getint(section, "max_minutes")
+            .expect("Could not parse max_minutes from INI as an integer")
+            .unwrap_or(DEFAULT_DURATION_MAX_MINUTES);
+        assert!(
+            max_minutes > 0,
+            "[GlobalVars::set_duration_vars()] max_minutes must be positive, got: {max_minutes:}"
+        );
+
+        self.duration_enabled
+            .set(enabled)
+            .expect("Could not set the duration_enabled in GlobalVars");
+        self.duration_max_minutes
+            .set(max_minutes)
+            .expect("Could not set the duration_max_minutes in GlobalVars");
+    }
+
+    /// Sets the `time_tracking_source` field from the `[time_tracking]` section.
+    ///
+    /// Reads either the `command` key (run through `sh -c`) or the `file` key
+    /// (tilde is expanded to the home directory).
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"time_tracking"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - Both or neither of `command` and `file` are set
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [time_tracking]
+    /// command = timew get dom.active.tag.1
+    /// ```
+    fn set_time_tracking_source(&self, section: &str) {
+        info!("[GlobalVars::set_time_tracking_source()]: Setting the time tracking source.");
+        let command = self.get_key_from_section_from_ini(section, "command");
+        let file = self.get_key_from_section_from_ini(section, "file");
+
+        let source = match (command, file) {
+            (Some(command), None) => TimeTrackingSource::Command(command),
+            (None, Some(file)) if file.contains('~') => {
+                TimeTrackingSource::File(PathBuf::from(set_proper_home_dir(&file)))
+            }
+            (None, Some(file)) => TimeTrackingSource::File(PathBuf::from(file)),
+            _ => panic!(
+                "[GlobalVars::set_time_tracking_source()] Set exactly one of 'command' or 'file' in [time_tracking]."
+            ),
+        };
+
+        self.time_tracking_source
+            .set(source)
+            .expect("Could not set the time_tracking_source in GlobalVars");
+    }
+
+    /// Sets the `build_status_check` field from the `[build_status]` section.
+    ///
+    /// Reads either the `command` key (run through `sh -c` in the repository)
+    /// or the `file` key (tilde is expanded to the home directory, relative
+    /// paths are relative to the repository). `timeout_seconds` bounds the
+    /// command and defaults to [`DEFAULT_BUILD_TIMEOUT`].
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"build_status"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - Both or neither of `command` and `file` are set
+    /// - `timeout_seconds` is not a positive integer
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [build_status]
+    /// command = cargo check --quiet
+    /// timeout_seconds = 30
+    /// ```
+    fn set_build_status_check(&self, section: &str) {
+        info!("[GlobalVars::set_build_status_check()]: Setting the build status check.");
+        let command = self.get_key_from_section_from_ini(section, "command");
+        let file = self.get_key_from_section_from_ini(section, "file");
+
+        let source = match (command, file) {
+            (Some(command), None) => BuildStatusSource::Command(command),
+            (None, Some(file)) if file.starts_with('~') => {
+                BuildStatusSource::File(PathBuf::from(set_proper_home_dir(&file)))
+            }
+            (None, Some(file)) => BuildStatusSource::File(PathBuf::from(file)),
+            _ => panic!(
+                "[GlobalVars::set_build_status_check()] Set exactly one of 'command' or 'file' in [build_status]."
+            ),
+        };
+        let timeout = match self
+            .get_config()
+            .getuint(section, "timeout_seconds")
+            .expect("Could not parse timeout_seconds from INI as a positive integer")
+        {
+            Some(0) => {
+                panic!("[GlobalVars::set_build_status_check()] timeout_seconds must be positive")
+            }
+            Some(seconds) => Duration::from_secs(seconds),
+            None => DEFAULT_BUILD_TIMEOUT,
+        };
+
+        self.build_status_check
+            .set(BuildStatusCheck { source, timeout })
+            .expect("Could not set the build_status_check in GlobalVars");
+    }
+
+    /// Sets the `vault_commit_message` field from the `[vault_git]` section.
+    ///
+    /// The message template is only stored when `auto_commit` is on, so a
+    /// `[vault_git]` section with `auto_commit = false` keeps the feature off.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"vault_git"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `auto_commit` is not a boolean
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [vault_git]
+    /// auto_commit = true
+    /// message = {date}: log {repo} ({branch})
+    /// ```
+    fn set_vault_git_vars(&self, section: &str) {
+        info!("[GlobalVars::set_vault_git_vars()]: Setting the vault auto-commit.");
+        let auto_commit = self
+            .get_config()
+            .getboolcoerce(section, "auto_commit")
+            .expect("Could not parse auto_commit from INI as a boolean")
+            .unwrap_or(false);
+        if !auto_commit {
+            info!("[GlobalVars::set_vault_git_vars()]: Vault auto-commit is disabled.");
+            return;
+        }
+
+        let message = self
+            .get_key_from_section_from_ini(section, "message")
+            .unwrap_or_else(|| DEFAULT_VAULT_COMMIT_MESSAGE.to_string());
+        self.vault_commit_message
+            .set(message)
+            .expect("Could not set the vault_commit_message in GlobalVars");
+    }
+
+    /// Sets the `routes` field from the `[routing]` section.
+    ///
+    /// Every key names a route and its value is `<URL pattern> -> <commit path>`.
+    /// Routes are sorted by name so that ties between equally long patterns
+    /// resolve the same way on every run.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"routing"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - A value is not a `pattern -> commit_path` pair
+    /// - A commit path is absolute (it must be relative to the vault root)
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [routing]
+    /// work = github.com/acme/* -> Work/Commits
+    /// personal = github.com/me/* -> Personal/Commits
+    /// ```
+    fn set_routes(&self, section: &str) {
+        info!("[GlobalVars::set_routes()]: Setting the routing table.");
+        let config = self.get_config();
+        let mut entries = config
+            .get_map_ref()
+            .get(section)
+            .map(|keys| keys.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+        entries.sort_by_key(|(name, _)| *name);
+
+        let routes = entries
+            .into_iter()
+            .map(|(name, value)| {
+                let route = parse_route(value.as_deref().unwrap_or("")).unwrap_or_else(|| {
+                    panic!("[GlobalVars::set_routes()] Route {name:} must be 'pattern -> commit_path'")
+                });
+                assert!(
+                    route.commit_path.is_relative(),
+                    "[GlobalVars::set_routes()] Route {name:} must use a path relative to the vault root"
+                );
+                route
+            })
+            .collect();
+
+        self.routes
+            .set(routes)
+            .expect("Could not set the routes in GlobalVars");
+    }
+
+    /// Sets the `vaults` field from the `[vaults]` section.
+    ///
+    /// Every `<vault>.<key>` key sets one key of the vault `<vault>`: `root`
+    /// (required), `commit_path`, and the `remote` (regular expression),
+    /// `path` (directory prefix) and `branch` (name, `*` wildcards allowed)
+    /// rules. Vaults are sorted by name, so they are written in the same
+    /// order on every run.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"vaults"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - A key is not `<vault>.<key>` with a known key
+    /// - A vault has no `root`, or no rule at all
+    /// - A `remote` rule is not a valid regular expression
+    /// - A `commit_path` is absolute (it must be relative to the vault root)
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [vaults]
+    /// work.root = ~/Vaults/Work
+    /// work.remote = github\.com[:/]acme/
+    /// work.commit_path = Engineering/Commits
+    /// oss.root = ~/Vaults/Personal
+    /// oss.path = ~/src/oss
+    /// oss.branch = release/*
+    /// ```
+    fn set_vaults(&self, section: &str) {
+        info!("[GlobalVars::set_vaults()]: Setting the vault targets.");
+        let config = self.get_config();
+        let mut entries = config
+            .get_map_ref()
+            .get(section)
+            .map(|keys| keys.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+        entries.sort_by_key(|(key, _)| (*key).rsplit_once('.').map(|(name, _)| name));
+
+        let mut vaults: Vec<(String, Option<PathBuf>, VaultTarget)> = Vec::new();
+        for (key, value) in entries {
+            let value = value.as_deref().unwrap_or("").trim();
+            let (name, field) = key.rsplit_once('.').unwrap_or_else(|| {
+                panic!("[GlobalVars::set_vaults()] Key {key:} must be '<vault>.<key>'")
+            });
+            let index = vaults
+                .iter()
+                .position(|(known, _, _)| known == name)
+                .unwrap_or_else(|| {
+                    vaults.push((
+                        name.to_string(),
+                        None,
+                        VaultTarget::new(name, PathBuf::new()),
+                    ));
+                    vaults.len() - 1
+                });
+            let (_, root, vault) = &mut vaults[index];
+            let path = || {
+                PathBuf::from(if value.starts_with('~') {
+                    set_proper_home_dir(value)
+                } else {
+                    value.to_string()
+                })
+            };
+            match field {
+                "root" => *root = Some(path()),
+                "commit_path" => {
+                    vault.commit_path = Some(PathBuf::from(value));
+                    assert!(
+                        Path::new(value).is_relative(),
+                        "[GlobalVars::set_vaults()] Vault {name:} must use a commit_path relative to its root"
+                    );
+                }
+                "remote" => {
+                    let remote = RemotePattern::new(value).unwrap_or_else(|e| {
+                        panic!("[GlobalVars::set_vaults()] Invalid remote of vault {name:}: {e:}")
+                    });
+                    vault.remote = Some(remote);
+                }
+                "path" => vault.path_prefix = Some(path()),
+                "branch" => vault.branch = Some(value.to_string()),
+                _ => panic!("[GlobalVars::set_vaults()] Unknown key {key:}"),
+            }
+        }
+
+        let vaults = vaults
+            .into_iter()
+            .map(|(name, root, vault)| {
+                let root_path_dir = root.unwrap_or_else(|| {
+                    panic!("[GlobalVars::set_vaults()] Vault {name:} must have a root")
+                });
+                assert!(
+                    vault.has_rules(),
+                    "[GlobalVars::set_vaults()] Vault {name:} must have a remote, path or branch rule"
+                );
+                VaultTarget {
+                    root_path_dir,
+                    ..vault
+                }
+            })
+            .collect();
+
+        self.vaults
+            .set(vaults)
+            .expect("Could not set the vaults in GlobalVars");
+    }
+
+    /// Sets the `forge_resolver` field from the `[forges]` section.
+    ///
+    /// A `<host>` key picks a preset (see [`ForgeTemplates::preset()`]), and
+    /// `<host>.commit`, `<host>.branch` and `<host>.pr` keys set (or override)
+    /// single templates. Hosts are matched without their port, since INI keys
+    /// cannot contain `:`.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"forges"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - A preset name is unknown
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [forges]
+    /// git.example.com = gitea
+    /// review.example.org = gerrit
+    /// code.example.net.commit = https://{host}/browse/{path}/rev/{hash}
+    /// ```
+    fn set_forge_resolver(&self, section: &str) {
+        info!("[GlobalVars::set_forge_resolver()]: Setting the forge link templates.");
+        let config = self.get_config();
+        let mut entries = config
+            .get_map_ref()
+            .get(section)
+            .map(|keys| keys.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+        // Presets first, so single templates can override them.
+        entries.sort_by_key(|(key, _)| (split_template_key(key).is_some(), *key));
+
+        let mut resolver = ForgeResolver::default();
+        for (key, value) in entries {
+            let value = value.as_deref().unwrap_or("").trim().to_string();
+            match split_template_key(key) {
+                Some((host, "commit")) => resolver.host_mut(host).commit = Some(value),
+                Some((host, "branch")) => resolver.host_mut(host).branch = Some(value),
+                Some((host, _)) => resolver.host_mut(host).pull_request = Some(value),
+                None => {
+                    let templates = ForgeTemplates::preset(&value.to_lowercase())
+                        .unwrap_or_else(|| {
+                            panic!("[GlobalVars::set_forge_resolver()] Unknown forge preset for {key:}: {value:}")
+                        });
+                    resolver.set_host(key, templates);
+                }
+            }
+        }
+
+        self.forge_resolver
+            .set(resolver)
+            .expect("Could not set the forge_resolver in GlobalVars");
+    }
+
+    /// Sets the `safety_use_trash` and `safety_require_vault` fields from the
+    /// `[safety]` section.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"safety"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `use_trash` or `require_vault` is not a boolean
+    /// - The `OnceCell`s have already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [safety]
+    /// use_trash = false
+    /// require_vault = true
+    /// ```
+    fn set_safety_vars(&self, section: &str) {
+        info!("[GlobalVars::set_safety_vars()]: Setting the trash usage.");
+        let use_trash = self
+            .get_config()
+            .getboolcoerce(section, "use_trash")
+            .expect("Could not parse use_trash from INI as a boolean")
+            .unwrap_or(true);
+        self.safety_use_trash
+            .set(use_trash)
+            .expect("Could not set the safety_use_trash in GlobalVars");
+
+        info!("[GlobalVars::set_safety_vars()]: Setting the vault check.");
+        let require_vault = self
+            .get_config()
+            .getboolcoerce(section, "require_vault")
+            .expect("Could not parse require_vault from INI as a boolean")
+            .unwrap_or(false);
+        self.safety_require_vault
+            .set(require_vault)
+            .expect("Could not set the safety_require_vault in GlobalVars");
+    }
+
+    /// Sets the `language_detect` field from the `[language]` section.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"language"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `detect` is not a boolean
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [language]
+    /// detect = true
+    /// ```
+    fn set_language_vars(&self, section: &str) {
+        info!("[GlobalVars::set_language_vars()]: Setting the language detection.");
+        let detect = self
+            .get_config()
+            .getboolcoerce(section, "detect")
+            .expect("Could not parse detect from INI as a boolean")
+            .unwrap_or(false);
+        self.language_detect
+            .set(detect)
+            .expect("Could not set the language_detect in GlobalVars");
+    }
+
+    /// Sets the `conventional_commits_enabled` field from the
+    /// `[conventional_commits]` section.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"conventional_commits"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `enabled` is not a boolean
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [conventional_commits]
+    /// enabled = true
+    /// ```
+    fn set_conventional_commits_vars(&self, section: &str) {
+        info!("[GlobalVars::set_conventional_commits_vars()]: Setting the Conventional Commits parsing.");
+        let enabled = self
+            .get_config()
+            .getboolcoerce(section, "enabled")
+            .expect("Could not parse enabled from INI as a boolean")
+            .unwrap_or(false);
+        self.conventional_commits_enabled
+            .set(enabled)
+            .expect("Could not set the conventional_commits_enabled in GlobalVars");
+    }
+
+    /// Sets the `trailers_tags` field from the `[trailers]` section.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"trailers"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `tags` is not `row`, `frontmatter` or `both`
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [trailers]
+    /// tags = both
+    /// ```
+    fn set_trailers_vars(&self, section: &str) {
+        info!("[GlobalVars::set_trailers_vars()]: Setting the trailer tag placement.");
+        if let Some(tags) = self.get_key_from_section_from_ini(section, "tags") {
+            let placement = TagPlacement::from_name(&tags).unwrap_or_else(|| {
+                panic!(
+                    "[GlobalVars::set_trailers_vars()] tags must be 'row', 'frontmatter' or 'both', got: {tags:}"
+                )
+            });
+            self.trailers_tags
+                .set(placement)
+                .expect("Could not set the trailers_tags in GlobalVars");
+        }
+    }
+
+    /// Sets the `output_sinks` field from the `[output]` section.
+    ///
+    /// `sinks` is a comma-separated list of `markdown` (the diary), `jsonl`,
+    /// `sqlite` and `stdout`; the file sinks need their path key. Paths
+    /// starting with `~` are expanded, relative ones are resolved against the
+    /// vault root by [`get_save_settings()`](Self::get_save_settings).
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"output"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `sinks` is empty or names an unknown sink
+    /// - `jsonl` or `sqlite` is listed without `jsonl_path` or `sqlite_path`
+    /// - `sqlite` is listed in a build without the `sqlite` feature
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [output]
+    /// sinks = markdown, jsonl, sqlite
+    /// jsonl_path = ~/Journal/commits.jsonl
+    /// sqlite_path = Data/commits.db
+    /// ```
+    fn set_output_vars(&self, section: &str) {
+        info!("[GlobalVars::set_output_vars()]: Setting the output sinks.");
+        let Some(names) = self.get_key_from_section_from_ini(section, "sinks") else {
+            return;
+        };
+        let sink_path = |key: &str| {
+            let path = self
+                .get_key_from_section_from_ini(section, key)
+                .unwrap_or_else(|| panic!("[GlobalVars::set_output_vars()] {key:} is required"));
+            PathBuf::from(if path.starts_with('~') {
+                set_proper_home_dir(&path)
+            } else {
+                self.native_path(&path)
+            })
+        };
+
+        let mut sinks = Vec::new();
+        for name in names.split(',').map(|name| name.trim().to_lowercase()) {
+            let sink = match name.as_str() {
+                "markdown" => SinkKind::ObsidianMarkdown,
+                "jsonl" => SinkKind::Jsonl(sink_path("jsonl_path")),
+                "sqlite" if !cfg!(feature = "sqlite") => panic!(
+                    "[GlobalVars::set_output_vars()] the sqlite sink needs a build with the 'sqlite' feature"
+                ),
+                "sqlite" => SinkKind::Sqlite(sink_path("sqlite_path")),
+                "stdout" => SinkKind::Stdout,
+                _ => panic!(
+                    "[GlobalVars::set_output_vars()] sinks must be 'markdown', 'jsonl', 'sqlite' or 'stdout', got: {name:}"
+                ),
+            };
+            if !sinks.contains(&sink) {
+                sinks.push(sink);
+            }
+        }
+        self.output_sinks
+            .set(sinks)
+            .expect("Could not set the output_sinks in GlobalVars");
+    }
+
+    /// Sets the `output_style` field from the `[output]` section.
+    ///
+    /// The `style` key is optional and takes precedence over
+    /// `[templates] entry_format`: `table` appends table rows, `yaml` fenced
+    /// YAML blocks and `dataview` bullets with Dataview inline fields.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"output"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `style` is not `table`, `yaml` or `dataview`
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Key
+    ///
+    /// ```text
+    /// [output]
+    /// style = dataview
+    /// ```
+    fn set_output_style(&self, section: &str) {
+        let Some(style) = self.get_key_from_section_from_ini(section, "style") else {
+            return;
+        };
+        info!("[GlobalVars::set_output_style()]: Setting the entry style: {style:}");
+        let format = EntryFormat::from_name(&style).unwrap_or_else(|| {
+            panic!(
+                "[GlobalVars::set_output_style()] style must be 'table', 'yaml' or 'dataview', got: {style:}"
+            )
+        });
+        self.output_style
+            .set(format)
+            .expect("Could not set the output_style in GlobalVars");
+    }
+
+    /// Sets the `branches_tags`, `branches_column` and `branches_merge_summary`
+    /// fields from the `[branches]` section.
+    ///
+    /// All keys are optional and default to `false`.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"branches"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - A key holds a value that is not a boolean
+    /// - The `OnceCell`s have already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [branches]
+    /// tags = true
+    /// column = true
+    /// merge_summary = true
+    /// ```
+    fn set_branches_vars(&self, section: &str) {
+        info!("[GlobalVars::set_branches_vars()]: Setting the branch slug tag, column and merge summary.");
+        let config = self.get_config();
+        let tags = config
+            .getboolcoerce(section, "tags")
+            .expect("Could not parse tags from INI as a boolean")
+            .unwrap_or(false);
+        let column = config
+            .getboolcoerce(section, "column")
+            .expect("Could not parse column from INI as a boolean")
+            .unwrap_or(false);
+        let merge_summary = config
+            .getboolcoerce(section, "merge_summary")
+            .expect("Could not parse merge_summary from INI as a boolean")
+            .unwrap_or(false);
+
+        self.branches_tags
+            .set(tags)
+            .expect("Could not set the branches_tags in GlobalVars");
+        self.branches_column
+            .set(column)
+            .expect("Could not set the branches_column in GlobalVars");
+        self.branches_merge_summary
+            .set(merge_summary)
+            .expect("Could not set the branches_merge_summary in GlobalVars");
+    }
+
+    /// Sets the `recent_projects_dir` field from the `[recent]` section.
+    ///
+    /// The `projects_dir` key is optional; without it `log-recent` needs a
+    /// `--dir`. A leading `~` is expanded to the home directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"recent"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `OnceCell` has already been set (called multiple times).
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [recent]
+    /// projects_dir = ~/Projects
+    /// ```
+    fn set_recent_projects_dir(&self, section: &str) {
+        let Some(projects_dir) = self.get_key_from_section_from_ini(section, "projects_dir") else {
+            return;
+        };
+        info!("[GlobalVars::set_recent_projects_dir()]: Setting the projects directory: {projects_dir:}");
+        let projects_dir = if projects_dir.starts_with('~') {
+            set_proper_home_dir(&projects_dir)
+        } else {
+            projects_dir
+        };
+
+        self.recent_projects_dir
+            .set(PathBuf::from(projects_dir))
+            .expect("Could not set the recent_projects_dir in GlobalVars");
+    }
+
+    /// Sets the `watched_repositories` field from the `[repositories]` section.
+    ///
+    /// Every key names a repository and its value is the path of its working
+    /// directory. A leading `~` is expanded to the home directory. Repositories
+    /// are sorted by name, so the daemon logs them in the same order on every run.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"repositories"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - A repository has an empty path
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [repositories]
+    /// api = ~/Projects/api
+    /// web = /srv/git/web
+    /// ```
+    fn set_watched_repositories(&self, section: &str) {
+        info!("[GlobalVars::set_watched_repositories()]: Setting the watched repositories.");
+        let config = self.get_config();
+        let mut entries = config
+            .get_map_ref()
+            .get(section)
+            .map(|keys| keys.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+        entries.sort_by_key(|(name, _)| *name);
+
+        let repositories = entries
+            .into_iter()
+            .map(|(name, value)| {
+                let path = value.as_deref().unwrap_or("").trim();
+                assert!(
+                    !path.is_empty(),
+                    "[GlobalVars::set_watched_repositories()] Repository {name:} must have a path"
+                );
+                if path.starts_with('~') {
+                    PathBuf::from(set_proper_home_dir(path))
+                } else {
+                    PathBuf::from(path)
+                }
+            })
+            .collect();
+
+        self.watched_repositories
+            .set(repositories)
+            .expect("Could not set the watched_repositories in GlobalVars");
+    }
+
+    /// Sets the `daemon_debounce` field from the `[daemon]` section.
+    ///
+    /// `debounce_ms` is the time, in milliseconds, a diary note must go
+    /// without new entries before the daemon writes them; `0` writes them as
+    /// soon as the repository's refs settle.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"daemon"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `debounce_ms` is not a non-negative integer
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [daemon]
+    /// debounce_ms = 2000
+    /// ```
+    fn set_daemon_vars(&self, section: &str) {
+        let Some(debounce_ms) = self
+            .get_config()
+            .getuint(section, "debounce_ms")
+            .expect("Could not parse debounce_ms from INI as a non-negative integer")
+        else {
+            return;
+        };
+        info!("[GlobalVars::set_daemon_vars()]: Setting the debounce window: {debounce_ms:}ms");
+
+        self.daemon_debounce
+            .set(Duration::from_millis(debounce_ms))
+            .expect("Could not set the daemon_debounce in GlobalVars");
+    }
+
+    /// Sets the `metrics_enabled` field from the `[metrics]` section.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"metrics"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `enabled` is not a boolean
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [metrics]
+    /// enabled = true
+    /// ```
+    fn set_metrics_vars(&self, section: &str) {
+        info!("[GlobalVars::set_metrics_vars()]: Setting the usage metrics opt-in.");
+        let enabled = self
+            .get_config()
+            .getboolcoerce(section, "enabled")
+            .expect("Could not parse enabled from INI as a boolean")
+            .unwrap_or(false);
+        self.metrics_enabled
+            .set(enabled)
+            .expect("Could not set the metrics_enabled in GlobalVars");
+    }
+
+    /// Sets the `filters_ignore_branches`, `filters_ignore_message_patterns`
+    /// and `filters_ignore_bots` fields from the `[filters]` section.
+    ///
+    /// All keys are optional; without them every commit is logged.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"filters"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - A message pattern is not a valid regular expression
+    /// - `ignore_bots` is not a boolean
+    /// - The `OnceCell`s have already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [filters]
+    /// ignore_branches = wip/*,tmp/*,dependabot/*
+    /// ignore_message_patterns = ^fixup!,^squash!,^Merge branch
+    /// ignore_bots = true
+    /// ```
+    fn set_filters_vars(&self, section: &str) {
+        info!("[GlobalVars::set_filters_vars()]: Setting the ignored branches, messages and bots.");
+        let list = |key: &str| {
+            self.get_key_from_section_from_ini(section, key)
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        let ignore_branches = list("ignore_branches");
+        let ignore_message_patterns = list("ignore_message_patterns")
+            .iter()
+            .map(|pattern| {
+                MessagePattern::new(pattern).unwrap_or_else(|e| {
+                    panic!("[GlobalVars::set_filters_vars()] ignore_message_patterns has an invalid regex {pattern:}: {e:}")
+                })
+            })
+            .collect();
+        let ignore_bots = self
+            .get_config()
+            .getboolcoerce(section, "ignore_bots")
+            .expect("Could not parse ignore_bots from INI as a boolean")
+            .unwrap_or(false);
+
+        self.filters_ignore_branches
+            .set(ignore_branches)
+            .expect("Could not set the filters_ignore_branches in GlobalVars");
+        self.filters_ignore_message_patterns
+            .set(ignore_message_patterns)
+            .expect("Could not set the filters_ignore_message_patterns in GlobalVars");
+        self.filters_ignore_bots
+            .set(ignore_bots)
+            .expect("Could not set the filters_ignore_bots in GlobalVars");
+    }
+
+    /// Sets the `weekly_goal` field from the `[goals]` section.
+    ///
+    /// The `weekly_commits` key is optional; without it no goal is tracked.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"goals"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `weekly_commits` is not a positive integer
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [goals]
+    /// weekly_commits = 20
+    /// weekly_note = Diaries/Weekly/%G-W%V.md
+    /// ```
+    fn set_goals_vars(&self, section: &str) {
+        info!("[GlobalVars::set_goals_vars()]: Setting the weekly goal.");
+        let config = self.get_config();
+        let Some(weekly_commits) = config
+            .getuint(section, "weekly_commits")
+            .expect("Could not parse weekly_commits from INI as a positive integer")
+        else {
+            return;
+        };
+        assert!(
+            weekly_commits > 0,
+            "[GlobalVars::set_goals_vars()] weekly_commits must be positive"
+        );
+
+        let weekly_goal = WeeklyGoal {
+            commits: usize::try_from(weekly_commits).expect("weekly_commits is too large"),
+            note_template: config
+                .get(section, "weekly_note")
+                .unwrap_or_else(|| DEFAULT_WEEKLY_NOTE_TEMPLATE.to_string()),
+        };
+        self.weekly_goal
+            .set(weekly_goal)
+            .expect("Could not set the weekly_goal in GlobalVars");
+    }
+
+    /// Sets the `related_repos_note` field from the `[related_repos]` section.
+    ///
+    /// The note is only stored when `enabled` is on; `note` defaults to
+    /// [`DEFAULT_RELATED_REPOS_NOTE`] at the vault root.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"related_repos"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `enabled` is not a boolean
+    /// - `note` is an absolute path (it must be relative to the vault root)
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [related_repos]
+    /// enabled = true
+    /// note = Rollups/Related Repos.md
+    /// ```
+    fn set_related_repos_vars(&self, section: &str) {
+        info!("[GlobalVars::set_related_repos_vars()]: Setting the related repositories rollup.");
+        let enabled = self
+            .get_config()
+            .getboolcoerce(section, "enabled")
+            .expect("Could not parse enabled from INI as a boolean")
+            .unwrap_or(false);
+        if !enabled {
+            return;
+        }
+
+        let note = PathBuf::from(
+            self.get_key_from_section_from_ini(section, "note")
+                .unwrap_or_else(|| DEFAULT_RELATED_REPOS_NOTE.to_string()),
+        );
+        assert!(
+            note.is_relative(),
+            "[GlobalVars::set_related_repos_vars()] note must be relative to the vault root"
+        );
+        self.related_repos_note
+            .set(note)
+            .expect("Could not set the related_repos_note in GlobalVars");
+    }
+
+    /// Sets the `message_pattern` field from the `[message_hygiene]` section.
+    ///
+    /// Commits whose subject does not match the pattern are tagged and listed
+    /// in the weekly note, see [`MessagePattern`]. The weekly note is the one
+    /// of `[goals] weekly_note`, or [`DEFAULT_WEEKLY_NOTE_TEMPLATE`].
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"message_hygiene"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `pattern` is not a valid regular expression
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [message_hygiene]
+    /// pattern = ^(feat|fix|docs|refactor|test|chore)(\(.+\))?!?: .+
+    /// ```
+    fn set_message_hygiene_vars(&self, section: &str) {
+        info!("[GlobalVars::set_message_hygiene_vars()]: Setting the commit message pattern.");
+        let Some(pattern) = self.get_key_from_section_from_ini(section, "pattern") else {
+            return;
+        };
+        let pattern = MessagePattern::new(&pattern).unwrap_or_else(|e| {
+            panic!("[GlobalVars::set_message_hygiene_vars()] pattern is not a valid regex: {e:}")
+        });
+        self.message_pattern
+            .set(pattern)
+            .expect("Could not set the message_pattern in GlobalVars");
+    }
+
+    /// Sets the `webhook` field from the `[webhook]` section.
+    ///
+    /// Without a `url` nothing is sent. The `payload` defaults to
+    /// [`DEFAULT_PAYLOAD_TEMPLATE`]; the signing secret is read from `secret`,
+    /// or from the environment variable named by `secret_env` so it can stay
+    /// out of the config file. An unset `secret_env` variable only logs a
+    /// warning, and payloads go out unsigned.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"webhook"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `payload` is not a valid Handlebars template
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [webhook]
+    /// url = https://n8n.example.com/webhook/commits
+    /// payload = {"text": "{{repo}}: {{message}}", "note": "{{vault_path}}"}
+    /// secret_env = RCS_WEBHOOK_SECRET
+    /// ```
+    fn set_webhook_vars(&self, section: &str) {
+        info!("[GlobalVars::set_webhook_vars()]: Setting the webhook.");
+        let Some(url) = self.get_key_from_section_from_ini(section, "url") else {
+            return;
+        };
+        let payload_template = self
+            .get_key_from_section_from_ini(section, "payload")
+            .unwrap_or_else(|| DEFAULT_PAYLOAD_TEMPLATE.to_string());
+        if let Err(e) = Template::compile(&payload_template) {
+            panic!(
+                "[GlobalVars::set_webhook_vars()] payload is not a valid Handlebars template: {e:}"
+            );
+        }
+        let secret = self
+            .get_key_from_section_from_ini(section, "secret")
+            .or_else(|| {
+                let variable = self.get_key_from_section_from_ini(section, "secret_env")?;
+                let secret = env::var(&variable).ok();
+                if secret.is_none() {
+                    warn!("[GlobalVars::set_webhook_vars()]: {variable:} is not set, payloads are not signed.");
+                }
+                secret
+            });
+
+        self.webhook
+            .set(Webhook {
+                url,
+                payload_template,
+                secret,
+            })
+            .expect("Could not set the webhook in GlobalVars");
+    }
+
+    /// Sets the `weekly_log` field from the `[weekly_log]` section.
+    ///
+    /// The weekly log is only stored when `enabled` is on; `note` defaults to
+    /// [`DEFAULT_WEEKLY_NOTE_TEMPLATE`] (the `[goals]` weekly note) and
+    /// `row_template` to [`DEFAULT_WEEKLY_ROW_TEMPLATE`].
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"weekly_log"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `enabled` is not a boolean
+    /// - `row_template` is not a valid Handlebars template
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [weekly_log]
+    /// enabled = true
+    /// note = Diaries/Weekly/%G-W%V.md
+    /// row_template = - {{date}} {{time}} **{{repo}}** {{message}}
+    /// ```
+    fn set_weekly_log_vars(&self, section: &str) {
+        info!("[GlobalVars::set_weekly_log_vars()]: Setting the weekly log.");
+        let enabled = self
+            .get_config()
+            .getboolcoerce(section, "enabled")
+            .expect("Could not parse enabled from INI as a boolean")
+            .unwrap_or(false);
+        if !enabled {
+            return;
+        }
+        let row_template = self
+            .get_key_from_section_from_ini(section, "row_template")
+            .unwrap_or_else(|| DEFAULT_WEEKLY_ROW_TEMPLATE.to_string());
+        if let Err(e) = Template::compile(&row_template) {
+            panic!(
+                "[GlobalVars::set_weekly_log_vars()] row_template is not a valid Handlebars template: {e:}"
+            );
+        }
+
+        self.weekly_log
+            .set(WeeklyLog {
+                note_template: self
+                    .get_key_from_section_from_ini(section, "note")
+                    .unwrap_or_else(|| DEFAULT_WEEKLY_NOTE_TEMPLATE.to_string()),
+                row_template,
+            })
+            .expect("Could not set the weekly_log in GlobalVars");
+    }
+
+    /// Sets the `daily_note` field from the `[daily_note]` section.
+    ///
+    /// The daily note is only stored when `enabled` is on; `note` defaults to
+    /// [`DEFAULT_DAILY_NOTE_TEMPLATE`], `link` to `embed` and `create` to
+    /// `false`.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"daily_note"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `enabled` or `create` is not a boolean
+    /// - `note` is an absolute path (it must be relative to the vault root)
+    /// - `link` is not `embed` or `wikilink`
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [daily_note]
+    /// enabled = true
+    /// note = Journal/%F.md
+    /// link = wikilink
+    /// create = true
+    /// ```
+    fn set_daily_note_vars(&self, section: &str) {
+        info!("[GlobalVars::set_daily_note_vars()]: Setting the daily note link.");
+        let config = self.get_config();
+        let enabled = config
+            .getboolcoerce(section, "enabled")
+            .expect("Could not parse enabled from INI as a boolean")
+            .unwrap_or(false);
+        if !enabled {
+            return;
+        }
+
+        let note_template = self
+            .get_key_from_section_from_ini(section, "note")
+            .unwrap_or_else(|| DEFAULT_DAILY_NOTE_TEMPLATE.to_string());
+        assert!(
+            Path::new(&note_template).is_relative(),
+            "[GlobalVars::set_daily_note_vars()] note must be relative to the vault root"
+        );
+        let style = self
+            .get_key_from_section_from_ini(section, "link")
+            .map_or(DailyNoteLinkStyle::default(), |link| {
+                DailyNoteLinkStyle::from_name(&link).unwrap_or_else(|| {
+                    panic!(
+                        "[GlobalVars::set_daily_note_vars()] link must be 'embed' or 'wikilink', got: {link:}"
+                    )
+                })
+            });
+        let create = config
+            .getboolcoerce(section, "create")
+            .expect("Could not parse create from INI as a boolean")
+            .unwrap_or(false);
+
+        self.daily_note
+            .set(DailyNote {
+                note_template,
+                style,
+                create,
+            })
+            .expect("Could not set the daily_note in GlobalVars");
+    }
+
+    /// Sets the `heatmap` field from the `[heatmap]` section.
+    ///
+    /// Without `note`, `heatmap` only prints the heatmap (unless given
+    /// `--note`); an empty `note` picks [`DEFAULT_HEATMAP_NOTE`] at the vault
+    /// root. `format` defaults to `html`.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"heatmap"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `note` is an absolute path (it must be relative to the vault root)
+    /// - `format` is not `html` or `text`
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [heatmap]
+    /// note = Rollups/Commit Heatmap.md
+    /// format = text
+    /// ```
+    fn set_heatmap_vars(&self, section: &str) {
+        info!("[GlobalVars::set_heatmap_vars()]: Setting the heatmap note.");
+        let note = self
+            .get_key_from_section_from_ini(section, "note")
+            .map(|note| match note.trim() {
+                "" => PathBuf::from(DEFAULT_HEATMAP_NOTE),
+                note => PathBuf::from(note),
+            });
+        assert!(
+            note.as_ref().is_none_or(|note| note.is_relative()),
+            "[GlobalVars::set_heatmap_vars()] note must be relative to the vault root"
+        );
+        let format = self
+            .get_key_from_section_from_ini(section, "format")
+            .map_or(HeatmapFormat::default(), |format| {
+                HeatmapFormat::from_str(format.trim(), true).unwrap_or_else(|_| {
+                    panic!(
+                        "[GlobalVars::set_heatmap_vars()] format must be 'html' or 'text', got: {format:}"
+                    )
+                })
+            });
+
+        self.heatmap
+            .set(HeatmapSettings { note, format })
+            .expect("Could not set the heatmap in GlobalVars");
+    }
+
+    /// Sets the `size_thresholds` and `size_column` fields from the `[size]` section.
+    ///
+    /// The thresholds are only stored when `enabled` is on. Each of `xs`, `s`,
+    /// `m` and `l` is the largest number of changed lines of its class, and
+    /// defaults to [`SizeThresholds::default()`]; `column` defaults to `false`.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"size"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `enabled` or `column` is not a boolean
+    /// - A threshold is not a positive integer, or is not larger than the
+    ///   previous class's
+    /// - The `OnceCell`s have already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [size]
+    /// enabled = true
+    /// column = true
+    /// xs = 10
+    /// s = 50
+    /// m = 250
+    /// l = 1000
+    /// ```
+    fn set_size_vars(&self, section: &str) {
+        info!("[GlobalVars::set_size_vars()]: Setting the commit size classes.");
+        let config = self.get_config();
+        let enabled = config
+            .getboolcoerce(section, "enabled")
+            .expect("Could not parse enabled from INI as a boolean")
+            .unwrap_or(false);
+        let column = config
+            .getboolcoerce(section, "column")
+            .expect("Could not parse column from INI as a boolean")
+            .unwrap_or(false);
+        self.size_column
+            .set(enabled && column)
+            .expect("Could not set the size_column in GlobalVars");
+        if !enabled {
+            return;
+        }
+
+        let threshold = |key: &str, default: usize| {
+            config
+                .getuint(section, key)
+                .unwrap_or_else(|e| panic!("Could not parse {key:} from INI as a number: {e:}"))
+                .map_or(default, |lines| {
+                    usize::try_from(lines).expect("The size threshold does not fit in usize")
+                })
+        };
+        let defaults = SizeThresholds::default();
+        let thresholds = SizeThresholds {
+            xs: threshold("xs", defaults.xs),
+            s: threshold("s", defaults.s),
+            m: threshold("m", defaults.m),
+            l: threshold("l", defaults.l),
+        };
+        assert!(
+            thresholds.is_ascending(),
+            "[GlobalVars::set_size_vars()] The size thresholds must grow from xs to l: {thresholds:?}"
+        );
+
+        self.size_thresholds
+            .set(thresholds)
+            .expect("Could not set the size_thresholds in GlobalVars");
+    }
+
+    /// Sets the `release_tag_distance` field from the `[release_tags]` section.
+    ///
+    /// The distance is only stored when `enabled` is on; `max_distance`
+    /// defaults to `0` (only commits that are tagged themselves).
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"release_tags"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `enabled` is not a boolean
+    /// - `max_distance` is not a non-negative integer
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [release_tags]
+    /// enabled = true
+    /// max_distance = 5
+    /// ```
+    fn set_release_tags_vars(&self, section: &str) {
+        info!("[GlobalVars::set_release_tags_vars()]: Setting the release tag lookup.");
+        let config = self.get_config();
+        let enabled = config
+            .getboolcoerce(section, "enabled")
+            .expect("Could not parse enabled from INI as a boolean")
+            .unwrap_or(false);
+        if !enabled {
+            return;
+        }
+        let max_distance = config
+            .getuint(section, "max_distance")
+            .expect("Could not parse max_distance from INI as a number")
+            .map_or(0, |distance| {
+                usize::try_from(distance).expect("max_distance does not fit in usize")
+            });
+
+        self.release_tag_distance
+            .set(max_distance)
+            .expect("Could not set the release_tag_distance in GlobalVars");
+    }
+
+    /// Sets the `wsl_paths` field from the `[wsl]` section, unless a path
+    /// already needed it, see [`get_wsl_paths()`](Self::get_wsl_paths).
+    fn set_wsl_vars(&self) {
+        info!("[GlobalVars::set_wsl_vars()]: Setting the WSL path translation.");
+        self.get_wsl_paths();
+    }
+
+    /// Returns the WSL/Windows path translation, reading the `[wsl]` section
+    /// the first time.
+    ///
+    /// Configured paths go through it before they are used, so it has to be
+    /// known before the `[wsl]` section comes up in the INI file.
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `translate` is not a boolean
+    /// - `folder_style` is neither `native` nor `windows`
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [wsl]
+    /// translate = true
+    /// mount_root = /mnt
+    /// folder_style = windows
+    /// ```
+    fn get_wsl_paths(&self) -> &WslPaths {
+        self.wsl_paths.get_or_init(|| {
+            let defaults = WslPaths::default();
+            let translate = self
+                .get_config()
+                .getboolcoerce("wsl", "translate")
+                .expect("Could not parse translate from INI as a boolean")
+                .unwrap_or(defaults.translate);
+            let mount_root = self
+                .get_key_from_section_from_ini("wsl", "mount_root")
+                .unwrap_or(defaults.mount_root);
+            let folder_style = self
+                .get_key_from_section_from_ini("wsl", "folder_style")
+                .map_or(defaults.folder_style, |style| {
+                    FolderStyle::from_name(&style).unwrap_or_else(|| {
+                        panic!("[GlobalVars::get_wsl_paths()] folder_style must be 'native' or 'windows', got: {style:}")
+                    })
+                });
+            WslPaths {
+                translate,
+                mount_root,
+                folder_style,
+            }
+        })
+    }
+
+    /// Translates a configured path for the running environment (WSL or
+    /// Windows), see [`WslPaths::native_path()`].
+    fn native_path(&self, path: &str) -> String {
+        self.get_wsl_paths()
+            .native_path(path, PathEnvironment::current())
+    }
+
+    /// Sets the `table_max_row_length`, `table_columns`, `table_time_zone`,
+    /// `table_same_second`, `table_forge_links` and `table_block_refs` fields
+    /// from the `[table]` section.
+    ///
+    /// All keys are optional; without them rows are not limited, the default
+    /// columns are used, times are written in UTC, rows of the same second
+    /// share their `TIME`, hashes are plain text and rows have no block
+    /// anchor. `columns` is a comma-separated list of names accepted by
+    /// [`TableColumn::from_name()`], `time_zone` is `offset` (`+01:00`) or
+    /// `abbreviation` (`CET`), `same_second` is `keep` or `sequence` (see
+    /// [`SameSecondPolicy`]), and `forge_links` and `block_refs` are booleans.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"table"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `max_row_length` is not a positive integer
+    /// - `columns` is empty, or names an unknown column or the same column twice
+    /// - `time_zone` is neither `offset` nor `abbreviation`
+    /// - `same_second` is neither `keep` nor `sequence`
+    /// - `forge_links` or `block_refs` is not a boolean
+    /// - The `OnceCell`s have already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [table]
+    /// max_row_length = 500
+    /// columns = time, message, branch, hash
+    /// time_zone = abbreviation
+    /// same_second = sequence
+    /// forge_links = true
+    /// block_refs = true
+    /// ```
+    fn set_table_vars(&self, section: &str) {
+        info!("[GlobalVars::set_table_vars()]: Setting the table settings.");
+        if let Some(columns) = self.get_key_from_section_from_ini(section, "columns") {
+            self.table_columns
+                .set(parse_table_columns(&columns))
+                .expect("Could not set the table_columns in GlobalVars");
+        }
+
+        if let Some(time_zone) = self.get_key_from_section_from_ini(section, "time_zone") {
+            let style = TimezoneStyle::from_name(&time_zone).unwrap_or_else(|| {
+                panic!(
+                    "[GlobalVars::set_table_vars()] time_zone must be 'offset' or 'abbreviation', got: {time_zone:}"
+                )
+            });
+            self.table_time_zone
+                .set(style)
+                .expect("Could not set the table_time_zone in GlobalVars");
+        }
+
+        if let Some(same_second) = self.get_key_from_section_from_ini(section, "same_second") {
+            let policy = SameSecondPolicy::from_name(&same_second).unwrap_or_else(|| {
+                panic!(
+                    "[GlobalVars::set_table_vars()] same_second must be 'keep' or 'sequence', got: {same_second:}"
+                )
+            });
+            self.table_same_second
+                .set(policy)
+                .expect("Could not set the table_same_second in GlobalVars");
+        }
+
+        if let Some(forge_links) = self
+            .get_config()
+            .getboolcoerce(section, "forge_links")
+            .expect("Could not parse forge_links from INI as a boolean")
+        {
+            self.table_forge_links
+                .set(forge_links)
+                .expect("Could not set the table_forge_links in GlobalVars");
+        }
+
+        if let Some(block_refs) = self
+            .get_config()
+            .getboolcoerce(section, "block_refs")
+            .expect("Could not parse block_refs from INI as a boolean")
+        {
+            self.table_block_refs
+                .set(block_refs)
+                .expect("Could not set the table_block_refs in GlobalVars");
+        }
+
+        let Some(max_row_length) = self
+            .get_config()
+            .getuint(section, "max_row_length")
+            .expect("Could not parse max_row_length from INI as a positive integer")
+        else {
+            return;
+        };
+        assert!(
+            max_row_length > 0,
+            "[GlobalVars::set_table_vars()] max_row_length must be positive"
+        );
+
+        self.table_max_row_length
+            .set(usize::try_from(max_row_length).expect("max_row_length is too large"))
+            .expect("Could not set the table_max_row_length in GlobalVars");
+    }
+
+    /// Sets the `category_rules` field from the `[categories]` section.
+    ///
+    /// Every key is a category name and its value a comma-separated list of
+    /// patterns (a leading `~` is expanded to the home directory). Keys are
+    /// read in lowercase, and rules are sorted by category so that ties
+    /// between equally long patterns resolve the same way on every run.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"categories"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - A category name is not a valid tag (see [`is_valid_category()`])
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Keys
+    ///
+    /// ```text
+    /// [categories]
+    /// work = *github.com/acme/*, ~/work/*
+    /// learning = ~/courses/*
+    /// ```
+    fn set_category_rules(&self, section: &str) {
+        info!("[GlobalVars::set_category_rules()]: Setting the category rules.");
+        let config = self.get_config();
+        let mut categories = config
+            .get_map_ref()
+            .get(section)
+            .map(|keys| keys.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+        categories.sort_by_key(|(category, _)| *category);
+
+        let mut rules = Vec::new();
+        for (category, patterns) in categories {
+            assert!(
+                is_valid_category(category),
+                "[GlobalVars::set_category_rules()] Invalid category name: {category:}"
+            );
+            for pattern in patterns.as_deref().unwrap_or("").split(',') {
+                let pattern = pattern.trim();
+                if pattern.is_empty() {
+                    continue;
+                }
+                rules.push(CategoryRule {
+                    category: category.clone(),
+                    pattern: if pattern.starts_with('~') {
+                        set_proper_home_dir(pattern)
+                    } else {
+                        pattern.to_string()
+                    },
+                });
+            }
+        }
+
+        self.category_rules
+            .set(rules)
+            .expect("Could not set the category_rules in GlobalVars");
+    }
+
+    /// Sets the `template_commit_datetime` field from the `[templates]` section.
+    ///
+    /// Reads the `commit_datetime` key from the INI file and stores it in the
+    /// `template_commit_datetime` `OnceCell`.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"templates"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - The `commit_datetime` key is missing from the INI section
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Key
+    ///
+    /// ```text
+    /// [templates]
+    /// commit_datetime = %H:%M:%S
+    /// ```
+    fn set_templates_datetime(&self, section: &str) {
+        info!("[GlobalVars::set_templates_datetime()]: Setting the templates_datetime.");
+        let key = self
+            .get_key_from_section_from_ini(section, "commit_datetime")
+            .expect("Could not get the commit_datetime from INI");
+
+        self.template_commit_datetime
+            .set(key)
+            .expect("Could not set the template_commit_datetime GlobalVars");
+    }
+
+    /// Sets the `template_diary_file` field from the `[templates]` section.
+    ///
+    /// The `diary_template` key is optional; without it new diary files use
+    /// the built-in template. A leading `~` is expanded to the home directory
+    /// and `.md` is added when the path has no extension, as Obsidian stores
+    /// template paths without it.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"templates"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `OnceCell` has already been set (called multiple times).
+    ///
+    /// # Expected INI Key
+    ///
+    /// ```text
+    /// [templates]
+    /// diary_template = Templates/Daily Note
+    /// ```
+    fn set_templates_diary_template(&self, section: &str) {
+        let Some(diary_template) = self.get_key_from_section_from_ini(section, "diary_template")
+        else {
+            return;
+        };
+        info!("[GlobalVars::set_templates_diary_template()]: Setting the diary template: {diary_template:}");
+        let diary_template = if diary_template.starts_with('~') {
+            set_proper_home_dir(&diary_template)
+        } else {
+            self.native_path(&diary_template)
+        };
+
+        let mut path = PathBuf::from(diary_template);
+        if path.extension().is_none() {
+            path.set_extension("md");
+        }
+        self.template_diary_file
+            .set(path)
+            .expect("Could not set the template_diary_file in GlobalVars");
+    }
+
+    /// Sets the `template_diary_handlebars` field from the `[templates]` section.
+    ///
+    /// The `diary_file` key is optional; without it new diary files use
+    /// `diary_template`, or the built-in template. A leading `~` is expanded to
+    /// the home directory. See
+    /// [`render_diary_file_from_handlebars()`](crate::templater::render_diary_file_from_handlebars)
+    /// for the template variables.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"templates"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `OnceCell` has already been set (called multiple times).
+    ///
+    /// # Expected INI Key
+    ///
+    /// ```text
+    /// [templates]
+    /// diary_file = Templates/diary.md.hbs
+    /// ```
+    fn set_templates_diary_file(&self, section: &str) {
+        let Some(diary_file) = self.get_key_from_section_from_ini(section, "diary_file") else {
+            return;
+        };
+        info!("[GlobalVars::set_templates_diary_file()]: Setting the diary file template: {diary_file:}");
+        let diary_file = if diary_file.starts_with('~') {
+            set_proper_home_dir(&diary_file)
+        } else {
+            self.native_path(&diary_file)
+        };
+        self.template_diary_handlebars
+            .set(PathBuf::from(diary_file))
+            .expect("Could not set the template_diary_handlebars in GlobalVars");
+    }
+
+    /// Sets the `template_flat_layout` field from the `[templates]` section.
+    ///
+    /// The `flat_layout` key is optional and defaults to `false`, keeping the
+    /// directories of `commit_date_path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"templates"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `flat_layout` is not a boolean
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Key
+    ///
+    /// ```text
+    /// [templates]
+    /// flat_layout = true
+    /// ```
+    fn set_templates_flat_layout(&self, section: &str) {
+        info!("[GlobalVars::set_templates_flat_layout()]: Setting the flat layout.");
+        let flat_layout = self
+            .get_config()
+            .getboolcoerce(section, "flat_layout")
+            .expect("Could not parse flat_layout from INI as a boolean")
+            .unwrap_or(false);
+        self.template_flat_layout
+            .set(flat_layout)
+            .expect("Could not set the template_flat_layout in GlobalVars");
+    }
+
+    /// Sets the `template_entry_format` field from the `[templates]` section.
+    ///
+    /// The `entry_format` key is optional: `table` (default) appends table
+    /// rows, `yaml` appends one fenced YAML block per entry, `dataview` one
+    /// bullet with Dataview inline fields.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"templates"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `entry_format` is not `table`, `yaml` or `dataview`
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Key
+    ///
+    /// ```text
+    /// [templates]
+    /// entry_format = yaml
+    /// ```
+    fn set_templates_entry_format(&self, section: &str) {
+        info!("[GlobalVars::set_templates_entry_format()]: Setting the entry format.");
+        let Some(entry_format) = self.get_key_from_section_from_ini(section, "entry_format") else {
+            return;
+        };
+        let format = EntryFormat::from_name(&entry_format).unwrap_or_else(|| {
+            panic!(
+                "[GlobalVars::set_templates_entry_format()] entry_format must be 'table', 'yaml' or 'dataview', got: {entry_format:}"
+            )
+        });
+        self.template_entry_format
+            .set(format)
+            .expect("Could not set the template_entry_format in GlobalVars");
+    }
+
+    /// Sets the `template_entry_id` field from the `[templates]` section.
+    ///
+    /// The `entry_id` key is optional: `base32` (default) hashes the commit
+    /// hash and time, `short_hash` uses the abbreviated commit hash.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"templates"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `entry_id` is neither `base32` nor `short_hash`
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Key
+    ///
+    /// ```text
+    /// [templates]
+    /// entry_id = short_hash
+    /// ```
+    fn set_templates_entry_id(&self, section: &str) {
+        info!("[GlobalVars::set_templates_entry_id()]: Setting the entry ID style.");
+        let Some(entry_id) = self.get_key_from_section_from_ini(section, "entry_id") else {
+            return;
+        };
+        let style = EntryIdStyle::from_name(&entry_id).unwrap_or_else(|| {
+            panic!(
+                "[GlobalVars::set_templates_entry_id()] entry_id must be 'base32' or 'short_hash', got: {entry_id:}"
+            )
+        });
+        self.template_entry_id
+            .set(style)
+            .expect("Could not set the template_entry_id in GlobalVars");
+    }
+
+    /// Sets the `template_timezone` field from the `[templates]` section.
+    ///
+    /// The `timezone` key is optional: without it, diary paths, dates and
+    /// times are in UTC. `local` uses the system timezone (or `TZ`), any
+    /// other value must be an IANA timezone name.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"templates"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `timezone` is neither `local`, `UTC` nor an IANA timezone name
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Key
+    ///
+    /// ```text
+    /// [templates]
+    /// timezone = Australia/Sydney
+    /// ```
+    fn set_templates_timezone(&self, section: &str) {
+        info!("[GlobalVars::set_templates_timezone()]: Setting the diary timezone.");
+        let Some(timezone) = self.get_key_from_section_from_ini(section, "timezone") else {
+            return;
+        };
+        let timezone = DiaryTimezone::from_name(&timezone).unwrap_or_else(|| {
+            panic!(
+                "[GlobalVars::set_templates_timezone()] timezone must be 'local', 'UTC' or an IANA timezone like 'Europe/Zurich', got: {timezone:}"
+            )
+        });
+        self.template_timezone
+            .set(timezone)
+            .expect("Could not set the template_timezone in GlobalVars");
+    }
+
+    /// Sets the `template_row_template` field from the `[templates]` section.
+    ///
+    /// The `row_template` key is optional; with it, entries are rendered from
+    /// the template instead of table rows or YAML blocks. A literal `\n` in
+    /// the value starts a new line, for multi-line entries such as callouts.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"templates"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `row_template` is not a valid Handlebars template
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Key
+    ///
+    /// ```text
+    /// [templates]
+    /// row_template = - {{time}} [{{message}}]({{repo_url}}/commit/{{hash}}) on {{branch}}
+    /// ```
+    fn set_templates_row_template(&self, section: &str) {
+        let Some(row_template) = self.get_key_from_section_from_ini(section, "row_template") else {
+            return;
+        };
+        info!(
+            "[GlobalVars::set_templates_row_template()]: Setting the row template: {row_template:}"
+        );
+        let row_template = row_template.replace("\\n", "\n");
+        if let Err(e) = Template::compile(&row_template) {
+            panic!("[GlobalVars::set_templates_row_template()] row_template is not a valid Handlebars template: {e:}");
+        }
+        self.template_row_template
+            .set(row_template)
+            .expect("Could not set the template_row_template in GlobalVars");
+    }
+
+    /// Sets the `template_commit_date_path` field from the `[templates]` section.
+    ///
+    /// Reads the `commit_date_path` key from the INI file and stores it in the
+    /// `template_commit_date_path` `OnceCell`.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"templates"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - The `commit_date_path` key is missing from the INI section
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Key
+    ///
+    /// ```text
+    /// [templates]
+    /// commit_date_path = %Y/%m-%B/%F.md
+    /// ```
+    fn set_templates_commit_date_path(&self, section: &str) {
+        info!(
+            "[GlobalVars::set_templates_commit_date_path()]: Setting the template_commit_date_path."
+        );
+        let key = self
+            .get_key_from_section_from_ini(section, "commit_date_path")
+            .expect("Could not get the commit_date_path from INI");
+
+        self.template_commit_date_path
+            .set(key)
+            .expect("Could not set the template_commit_date_path in GlobalVars");
+    }
+
+    /// Sets the `obsidian_commit_path` field from the `[obsidian]` section.
+    ///
+    /// Reads the `commit_path` key, expands tilde (`~`) to the home directory
+    /// if present, puts the optional `path_prefix_emoji` in front of its first
+    /// folder (see [`prefix_path_emoji()`]), splits the path by `/`, and
+    /// constructs a `PathBuf`.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"obsidian"` (validated by caller)
+    ///
+    /// # Tilde Expansion
+    ///
+    /// - `~/Diaries/Commits` → `/home/user/Diaries/Commits`
+    /// - `/absolute/path` → `/absolute/path` (unchanged)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - The `commit_path` key is missing from the INI section
+    /// - Home directory cannot be determined (when `~` is used)
+    /// - The `OnceCell` has already been set
+    ///
+    /// # Expected INI Key
+    ///
+    /// ```text
+    /// [obsidian]
+    /// commit_path = ~/Documents/Obsidian/Diaries/Commits
+    /// path_prefix_emoji = 📅
+    /// ```
+    fn set_obsidian_commit_path(&self, section: &str) {
+        let string_path = self
+            .get_key_from_section_from_ini(section, "commit_path")
+            .expect("Could not get commit_path from config");
+
+        let fixed_home = if string_path.contains('~') {
+            info!("[GlobalVars::set_obsidian_commit_path()]: Path does contain: '~'.");
+            set_proper_home_dir(&string_path)
+        } else {
+            info!("[GlobalVars::set_obsidian_commit_path()]: Path does NOT contain: '~'.");
+            string_path
+        };
+        let fixed_home = match self.get_key_from_section_from_ini(section, "path_prefix_emoji") {
+            Some(emoji) => {
+                info!("[GlobalVars::set_obsidian_commit_path()]: Prefixing the path with: '{emoji:}'.");
+                prefix_path_emoji(&fixed_home, &emoji)
+            }
+            None => fixed_home,
+        };
+
+        let vec_str = fixed_home.split('/');
+
+        let mut path = PathBuf::new();
+
+        info!(
+            "[GlobalVars::set_obsidian_commit_path()]: Pushing strings folders to create PathBuf."
+        );
+        for s in vec_str {
+            path.push(s);
+        }
+        self.obsidian_commit_path
+            .set(path)
+            .expect("Could not set the path for obsidian_root_path_dir");
+    }
+
+    /// Sets the `obsidian_normalize_remote` field from the `[obsidian]` section.
+    ///
+    /// The `normalize_remote` key is optional and defaults to `false`, keeping
+    /// remote URLs as `origin` has them.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"obsidian"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `normalize_remote` is not a boolean
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Key
+    ///
+    /// ```text
+    /// [obsidian]
+    /// normalize_remote = true
+    /// ```
+    fn set_obsidian_normalize_remote(&self, section: &str) {
+        info!("[GlobalVars::set_obsidian_normalize_remote()]: Setting the remote normalization.");
+        let normalize_remote = self
+            .get_config()
+            .getboolcoerce(section, "normalize_remote")
+            .expect("Could not parse normalize_remote from INI as a boolean")
+            .unwrap_or(false);
+        self.obsidian_normalize_remote
+            .set(normalize_remote)
+            .expect("Could not set the obsidian_normalize_remote in GlobalVars");
+    }
+
+    /// Sets the `obsidian_insert_under` field from the `[obsidian]` section.
+    ///
+    /// The `insert_under` key is optional; the value is the heading title,
+    /// turned into a `## Title` heading by [`heading_line()`]. `#` starts a
+    /// comment in INI files, so only TOML and YAML files can give the level.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"obsidian"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `insert_under` is empty
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Key
+    ///
+    /// ```text
+    /// [obsidian]
+    /// insert_under = Commits
+    /// ```
+    fn set_obsidian_insert_under(&self, section: &str) {
+        let Some(heading) = self.get_key_from_section_from_ini(section, "insert_under") else {
+            return;
+        };
+        info!("[GlobalVars::set_obsidian_insert_under()]: Setting the commit table heading.");
+        let heading = heading_line(&heading).unwrap_or_else(|| {
+            panic!(
+                "[GlobalVars::set_obsidian_insert_under()] insert_under must be a heading title like 'Commits' ('#' starts a comment in INI files)"
+            )
+        });
+        self.obsidian_insert_under
+            .set(heading)
+            .expect("Could not set the obsidian_insert_under in GlobalVars");
+    }
+
+    /// Sets the `obsidian_vault_name` field from the `[obsidian]` section.
+    ///
+    /// The `vault_name` key is optional: it is only needed when the vault is
+    /// named differently in Obsidian than its directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"obsidian"` (validated by caller)
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - `vault_name` is empty
+    /// - The `OnceCell` has already been set (called multiple times)
+    ///
+    /// # Expected INI Key
+    ///
+    /// ```text
+    /// [obsidian]
+    /// vault_name = Notes
+    /// ```
+    fn set_obsidian_vault_name(&self, section: &str) {
+        let Some(vault_name) = self.get_key_from_section_from_ini(section, "vault_name") else {
+            return;
+        };
+        info!("[GlobalVars::set_obsidian_vault_name()]: Setting the vault name: {vault_name:}");
+        let vault_name = vault_name.trim();
+        assert!(
+            !vault_name.is_empty(),
+            "[GlobalVars::set_obsidian_vault_name()] vault_name must not be empty"
+        );
+        self.obsidian_vault_name
+            .set(vault_name.to_string())
+            .expect("Could not set the obsidian_vault_name in GlobalVars");
+    }
+
+    /// Sets the `obsidian_root_path_dir` field from the `[obsidian]` section.
+    ///
+    /// Reads the `root_path_dir` key, expands tilde (`~`) to the home directory
+    /// if present, prepends `/` for absolute paths, and constructs a `PathBuf`.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - Should be `"obsidian"` (validated by caller)
+    ///
+    /// # Path Construction
+    ///
+    /// - Starts with `/` to ensure absolute path
+    /// - Expands `~` to home directory
+    /// - Translates between WSL and Windows forms when `[wsl] translate` is on
+    /// - Splits by `/` and constructs `PathBuf`
+    ///
+    /// # Tilde Expansion Examples
+    ///
+    /// - `~/Documents/Obsidian` → `/home/user/Documents/Obsidian`
+    /// - `/absolute/path` → `/absolute/path`
+    ///
+    /// # Panics
+    ///
+    /// Panics if:
+    /// - The `root_path_dir` key is missing from the INI section
+    /// - Home directory cannot be determined (when `~` is used)
+    /// - The `OnceCell` has already been set
+    ///
+    /// # Expected INI Key
+    ///
+    /// ```text
+    /// [obsidian]
+    /// root_path_dir = ~/Documents/Obsidian
+    /// ```
+    fn set_obsidian_root_path_dir(&self, section: &str) {
+        let string_path = self
+            .get_key_from_section_from_ini(section, "root_path_dir")
+            .expect("Could not get commit_path from config");
+
+        let fixed_home = if string_path.contains('~') {
+            info!("[GlobalVars::set_obsidian_root_path_dir()]: Does contain ~");
+            set_proper_home_dir(&string_path)
+        } else {
+            info!("[GlobalVars::set_obsidian_root_path_dir()]: Does NOT contain ~");
+            self.native_path(&string_path)
+        };
+
+        let vec_str = fixed_home.split('/');
+        let mut path = PathBuf::new();
+
+        info!(
+            "[GlobalVars::set_obsidian_root_path_dir()]: Pushing '/' to PathBuf for proper path."
+        );
+        path.push("/");
+
+        info!(
+            "[GlobalVars::set_obsidian_root_path_dir()]: Pushing strings folders to create PathBuf."
+        );
+        for s in vec_str {
+            path.push(s);
+        }
+
+        self.obsidian_root_path_dir
+            .set(path)
+            .expect("Could not set the path for obsidian_root_path_dir");
+    }
+}
+
+/// Command-line argument parser for configuration file path.
+///
+/// This struct uses `clap` to parse CLI arguments and provide configuration
+/// options for the application: a custom INI configuration file path and an
+/// optional subcommand.
+///
+/// # CLI Arguments
+///
+/// - `--config-ini <PATH>` - Optional path to a custom configuration file
+/// - `save [--wip|--stdin]` - Log the HEAD commit, the dirty working tree with
+///   `--wip`, or every commit listed on stdin with `--stdin`
+/// - `event checkout|stash` - Log a branch switch or stash as a lightweight row
+/// - `verify [--fix]` - Cross-check the state index against the vault
+///
+/// # Examples
+///
+/// ```text
+/// # Use default config (~/.config/rusty-commit-saver/rusty-commit-saver.ini)
+/// rusty-commit-saver
+///
+/// # Use custom config file
+/// rusty-commit-saver --config-ini /path/to/custom.ini
+/// ```
+///
+/// # See Also
+///
+/// - [`retrieve_config_file_path()`] - Gets the config path from CLI or default
+/// - [`get_ini_file()`] - Loads the INI file from the resolved path
+#[derive(Parser, Debug, Clone)]
+#[command(version, about, long_about = None)]
+#[command(propagate_version = true)]
+#[command(about = "Rusty Commit Saver config", long_about = None)]
+pub struct UserInput {
+    /// Path to a custom INI configuration file.
+    ///
+    /// A `.toml`, `.yaml` or `.yml` file is read as TOML or YAML instead,
+    /// with the same sections and keys.
+    ///
+    /// If not provided, the default configuration file is used:
+    /// `~/.config/rusty-commit-saver/rusty-commit-saver.ini`
+    ///
+    /// # CLI Usage
+    ///
+    /// ```text
+    /// rusty-commit-saver --config-ini /custom/path/config.ini
+    /// ```
+    ///
+    /// # Examples
     ///
     /// Valid paths:
     /// - `~/my-configs/commit-saver.ini`
     /// - `/etc/rusty-commit-saver/config.ini`
     /// - `./local-config.ini`
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub config_ini: Option<String>,
+
+    /// The action to run.
+    ///
+    /// When omitted, the tool behaves like `save` (the post-commit hook usage).
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Subcommands supported by the CLI.
+///
+/// # Examples
+///
+/// ```text
+/// # Log the HEAD commit (same as running without a subcommand)
+/// rusty-commit-saver save
+///
+/// # Log the current dirty working tree as a WIP row
+/// rusty-commit-saver save --wip
+///
+/// # Log every commit about to be pushed (from .git/hooks/pre-push)
+/// git rev-list origin/main..HEAD | rusty-commit-saver save --stdin
+///
+/// # Same, with one tab-separated record per commit for a script to read
+/// git rev-list origin/main..HEAD | rusty-commit-saver save --stdin --porcelain
+///
+/// # Log a branch switch (from .git/hooks/post-checkout)
+/// rusty-commit-saver event checkout "$1" "$2" "$3"
+///
+/// # Check the vault against the logged-commits index, repairing what can be
+/// rusty-commit-saver verify --fix
+///
+/// # Backfill newly enabled columns into older diary tables
+/// rusty-commit-saver enrich
+///
+/// # Review the day's entries in the terminal
+/// rusty-commit-saver today --date 2025-01-14
+///
+/// # Catch up on repositories where the hook is not installed
+/// rusty-commit-saver log-recent --dir ~/Projects --days 14
+///
+/// # Log new commits of the [repositories] list as they land, without hooks
+/// rusty-commit-saver daemon
+///
+/// # See how much of the vault the commit diary occupies
+/// rusty-commit-saver report --vault
+///
+/// # Set up the post-commit hook of a repository
+/// rusty-commit-saver install-hook --path ~/Projects/api
+///
+/// # Backfill the public commits made on other machines
+/// rusty-commit-saver import --github-user octocat --days 60
+///
+/// # Add retrospective context to a logged commit
+/// rusty-commit-saver annotate 3f2a9c1 "this caused the Friday incident"
+///
+/// # Take back a commit logged by mistake, wherever it is in the diary
+/// rusty-commit-saver undo --hash 3f2a9c1 --dry-run
+///
+/// # Commit totals per month, repository and weekday, also kept in a note
+/// rusty-commit-saver stats --note "Rollups/Commit Stats.md"
+///
+/// # How did this week go compared to the last one?
+/// rusty-commit-saver stats --compare last-week this-week
+///
+/// # Print the opt-in usage counters, to paste into a bug report
+/// rusty-commit-saver metrics show
+///
+/// # Write a config file for the vault and install the hook in this repository
+/// rusty-commit-saver config init --vault ~/Notes --install-hook
+/// ```
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Save the current commit to the Obsidian diary (default).
+    Save(SaveArgs),
+
+    /// Log a workday event (branch switch, stash) as a lightweight diary row.
+    Event(EventArgs),
+
+    /// Check the vault for missing files, unknown commits and duplicate rows.
+    Verify(VerifyArgs),
+
+    /// Backfill newly enabled columns (e.g. `DURATION`, `EVENT`) into the
+    /// tables of already logged diary files.
+    Enrich,
+
+    /// Print the commit table of a day's diary in the terminal.
+    Today(TodayArgs),
+
+    /// Log the recent commits of every repository in the projects directory
+    /// that are not in the logged-commits index yet.
+    LogRecent(LogRecentArgs),
+
+    /// Watch the `[repositories]` list and log new commits as they land,
+    /// without a post-commit hook in each repository.
+    Daemon,
+
+    /// Summarize how much of the vault the commit diary occupies and
+    /// suggest years to archive.
+    Report(ReportArgs),
+
+    /// Backfill diary entries from the user's public GitHub commits, for
+    /// days worked on other machines. Commits already in the diary are skipped.
+    Import(ImportArgs),
+
+    /// Add the `post-commit` hook calling this binary to a repository,
+    /// keeping the hook's other commands.
+    InstallHook(HookArgs),
+
+    /// Remove what `install-hook` added from the repository's `post-commit` hook.
+    UninstallHook(HookArgs),
+
+    /// Print the script registering the shell completions, which complete
+    /// repository and branch names from the logged-commits index.
+    Completions(CompletionsArgs),
+
+    /// Add a freeform note to the diary entry of a logged commit, e.g. to
+    /// record afterwards that it caused an incident.
+    Annotate(AnnotateArgs),
+
+    /// Remove (or strike through) the diary entry of a logged commit, the
+    /// last one by default, and drop it from the logged-commits index.
+    Undo(UndoArgs),
+
+    /// Print commit totals per month, repository and weekday, and the longest
+    /// streak of days with commits, read from the diary files of the vault.
+    Stats(StatsArgs),
+
+    /// Draw a GitHub-style heatmap of the commits per day of a year, with
+    /// the longest and current streaks, and write it to the heatmap note.
+    Heatmap(HeatmapArgs),
+
+    /// Work with the local usage counters kept when `[metrics] enabled = true`.
+    Metrics(MetricsArgs),
+
+    /// Manage the configuration file, e.g. write a first one with `config init`.
+    Config(ConfigArgs),
+}
+
+impl Command {
+    /// Returns the name of the subcommand, as typed on the command line.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Save(_) => "save",
+            Command::Event(_) => "event",
+            Command::Verify(_) => "verify",
+            Command::Enrich => "enrich",
+            Command::Today(_) => "today",
+            Command::LogRecent(_) => "log-recent",
+            Command::Daemon => "daemon",
+            Command::Report(_) => "report",
+            Command::Import(_) => "import",
+            Command::InstallHook(_) => "install-hook",
+            Command::UninstallHook(_) => "uninstall-hook",
+            Command::Completions(_) => "completions",
+            Command::Annotate(_) => "annotate",
+            Command::Undo(_) => "undo",
+            Command::Stats(_) => "stats",
+            Command::Heatmap(_) => "heatmap",
+            Command::Metrics(_) => "metrics",
+            Command::Config(_) => "config",
+        }
+    }
+}
+
+/// Arguments for the `save` subcommand.
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SaveArgs {
+    /// Log the uncommitted working tree as a WIP row instead of the HEAD commit.
+    ///
+    /// Useful to journal progress before a commit exists. The row lists the
+    /// modified, new and deleted files and is marked with `🚧 WIP`.
+    #[arg(long)]
+    pub wip: bool,
+
+    /// Log every commit whose hash is listed on stdin, one per line.
+    ///
+    /// Meant for pre-push hooks: `git rev-list origin/main..HEAD | rusty-commit-saver save --stdin`.
+    /// Commits are logged oldest first; commits already in the logged-commits
+    /// index are skipped.
+    #[arg(long, conflicts_with = "wip")]
+    pub stdin: bool,
+
+    /// Log the commits of every branch updated by a push, read from the
+    /// `post-receive` hook's stdin.
+    ///
+    /// Meant for server-side hooks of bare repositories: each
+    /// `<old hash> <new hash> <ref name>` line logs the commits the push
+    /// brought to that branch, recorded on it. Tags and deletions are skipped.
+    #[arg(long, conflicts_with_all = ["wip", "stdin"])]
+    pub post_receive: bool,
+
+    /// Print one tab-separated `save` record per entry on stdout.
+    ///
+    /// The field order is stable and tabs, newlines and backslashes inside
+    /// fields are escaped, for scripts and editor integrations. See
+    /// [`SaveReport::to_porcelain()`](crate::porcelain::SaveReport::to_porcelain).
+    #[arg(long)]
+    pub porcelain: bool,
+
+    /// Print the diary path, the new file (if any) and the entry that would be
+    /// written, without touching the vault.
+    ///
+    /// Useful to check `commit_date_path` and the row escaping before
+    /// trusting the hook. With `--porcelain`, the records carry the
+    /// `dry-run` status instead.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Write even when `[safety] require_vault` finds no `.obsidian`
+    /// directory in the vault root.
+    #[arg(long)]
+    pub force: bool,
+
+    /// Open the diary file in Obsidian once the entry is saved.
+    ///
+    /// Goes through an `obsidian://open` URI, handed to the system opener
+    /// (`open`, `xdg-open` or `start`). The vault is `[obsidian] vault_name`,
+    /// or the name of the vault directory.
+    #[arg(long, conflicts_with = "dry_run")]
+    pub open: bool,
+
+    /// Log the commit even when its hash is already in the diary file.
+    ///
+    /// By default a commit logged twice (e.g. the hook fired twice, or
+    /// `save` was run by hand after the hook) is skipped and reported as
+    /// `already-logged`.
+    #[arg(long)]
+    pub allow_duplicates: bool,
+}
+
+/// Arguments for the `today` subcommand.
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
+pub struct TodayArgs {
+    /// Day to show (`YYYY-MM-DD`), today (UTC, like the diary file names) by default.
+    #[arg(long)]
+    pub date: Option<NaiveDate>,
+}
+
+/// Arguments for the `log-recent` subcommand.
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
+pub struct LogRecentArgs {
+    /// Directory to scan for repositories, `[recent] projects_dir` by default.
+    #[arg(long)]
+    pub dir: Option<PathBuf>,
+
+    /// How many days back to look in repositories with no logged commit yet.
+    ///
+    /// Repositories with logged commits are scanned from their last logged
+    /// commit, but never further back than this either.
+    #[arg(long, default_value_t = DEFAULT_LOOKBACK_DAYS)]
+    pub days: i64,
+
+    /// Print one tab-separated `save` record per entry on stdout, like `save --porcelain`.
+    #[arg(long)]
+    pub porcelain: bool,
+
+    /// Only scan the repositories with this name (e.g. `api` for
+    /// `git@github.com:acme/api.git`, the directory name without remote).
+    #[arg(long, add = repository_completer(completion_state_dir))]
+    pub repo: Option<String>,
+}
+
+/// Arguments for the `install-hook` and `uninstall-hook` subcommands.
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
+pub struct HookArgs {
+    /// Repository to change, the one containing the current directory by default.
+    #[arg(long)]
+    pub path: Option<PathBuf>,
+}
+
+/// Arguments for the `completions` subcommand.
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
+pub struct CompletionsArgs {
+    /// Shell to print the registration script for.
+    #[arg(value_parser = COMPLETION_SHELLS)]
+    pub shell: String,
+}
+
+/// Arguments for the `annotate` subcommand.
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
+pub struct AnnotateArgs {
+    /// Hash of the logged commit, at least 4 characters of it.
+    pub hash: String,
+
+    /// The note to add. Table rows get it in a `NOTE` column, YAML entries
+    /// in a `note` key and templated entries as a nested bullet.
+    pub note: String,
+}
+
+/// Arguments for the `undo` subcommand.
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
+pub struct UndoArgs {
+    /// Hash of the logged commit to undo, at least 4 characters of it.
+    /// Without it, the last logged entry is undone.
+    #[arg(long)]
+    pub hash: Option<String>,
+
+    /// Strike the entry through instead of removing it, so the diary still
+    /// shows the commit was logged.
+    #[arg(long)]
+    pub strike: bool,
+
+    /// Print the entry that would be undone without changing anything.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Only undo a logged commit of this repository (e.g. `api` for
+    /// `git@github.com:acme/api.git`).
+    #[arg(long, add = repository_completer(completion_state_dir))]
+    pub repo: Option<String>,
+
+    /// Only undo a logged commit of this branch.
+    #[arg(long, add = branch_completer(completion_state_dir))]
+    pub branch: Option<String>,
+}
+
+/// Arguments for the `stats` subcommand.
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatsArgs {
+    /// Also write the stats to this note, relative to the vault root. The
+    /// note is rewritten as a whole on every run.
+    #[arg(long)]
+    pub note: Option<PathBuf>,
+
+    /// Compare two periods instead, with the change per repository and per
+    /// commit type: `today`, `yesterday`, `this-week`, `last-week`,
+    /// `this-month`, `last-month`, `this-year`, `last-year`, a month
+    /// (`2025-01`), a day or a range (`2025-01-06..2025-01-19`).
+    #[arg(long, num_args = 2, value_names = ["BEFORE", "AFTER"])]
+    pub compare: Vec<String>,
+
+    /// Print tab-separated records instead of Markdown, like `save --porcelain`.
+    /// The `--note` still gets the Markdown.
+    #[arg(long)]
+    pub porcelain: bool,
+
+    /// Only count the commits of this repository (e.g. `api` for
+    /// `git@github.com:acme/api.git`).
+    #[arg(long, add = repository_completer(completion_state_dir))]
+    pub repo: Option<String>,
+}
+
+/// Arguments for the `heatmap` subcommand.
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeatmapArgs {
+    /// Year to draw, the current one (UTC) by default.
+    #[arg(long)]
+    pub year: Option<i32>,
+
+    /// Write the heatmap to this note, relative to the vault root, instead
+    /// of `[heatmap] note`. The note is rewritten as a whole on every run.
+    #[arg(long)]
+    pub note: Option<PathBuf>,
+
+    /// How the grid is drawn, `[heatmap] format` by default.
+    #[arg(long, value_enum)]
+    pub format: Option<HeatmapFormat>,
+}
+
+/// Arguments for the `import` subcommand.
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
+pub struct ImportArgs {
+    /// GitHub user whose public commits are imported.
+    ///
+    /// Only reads from the API. Set `GITHUB_TOKEN` to raise the search rate
+    /// limit; no scope is needed.
+    #[arg(long)]
+    pub github_user: String,
+
+    /// How many days back to import.
+    #[arg(long, default_value_t = DEFAULT_IMPORT_DAYS)]
+    pub days: i64,
+}
+
+/// Arguments for the `report` subcommand.
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReportArgs {
+    /// Report file counts and sizes per year, the largest days and archive
+    /// candidates of the commit diary.
+    #[arg(long)]
+    pub vault: bool,
+}
+
+/// Arguments for the `verify` subcommand.
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifyArgs {
+    /// Repair what can be repaired: prune stale index entries and remove
+    /// duplicate rows. Rows of commits missing from the repository are only
+    /// reported.
+    #[arg(long)]
+    pub fix: bool,
+}
+
+/// Arguments for the `event` subcommand.
+///
+/// Events are only written when enabled in the `[events]` INI section, so the
+/// hooks can stay installed while the feature is switched off.
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
+pub struct EventArgs {
+    /// The event to record.
+    #[command(subcommand)]
+    pub event: EventCommand,
+}
+
+/// Workday events that can be logged alongside commits.
+///
+/// # Hook Integration
+///
+/// ```text
+/// # .git/hooks/post-checkout
+/// rusty-commit-saver event checkout "$1" "$2" "$3"
+///
+/// # .git/hooks/pre-push (the pushed refs come on stdin)
+/// rusty-commit-saver event push "$1" "$2"
+///
+/// # Git has no stash hook, wrap the commands with aliases instead
+/// git config alias.spush '!git stash push "$@" && rusty-commit-saver event stash push'
+/// git config alias.spop '!git stash pop "$@" && rusty-commit-saver event stash pop'
+/// ```
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum EventCommand {
+    /// Record a branch switch, taking the `post-checkout` hook arguments.
+    Checkout {
+        /// The ref of the previous `HEAD` (first hook argument).
+        previous_head: Option<String>,
+
+        /// The ref of the new `HEAD` (second hook argument).
+        new_head: Option<String>,
+
+        /// `1` for a branch checkout, `0` for a file checkout (third hook argument).
+        ///
+        /// File checkouts are ignored.
+        checkout_type: Option<String>,
+    },
+
+    /// Record a stash push or pop.
+    Stash {
+        /// Which stash operation just ran.
+        #[arg(value_enum)]
+        action: StashAction,
+    },
+
+    /// Record a push, one row per pushed ref, taking the `pre-push` hook
+    /// arguments and reading the pushed refs from stdin.
+    Push {
+        /// The name of the remote pushed to (first hook argument).
+        remote: Option<String>,
+
+        /// The URL of the remote pushed to (second hook argument).
+        url: Option<String>,
+    },
+}
+
+/// Arguments for the `metrics` subcommand.
+///
+/// Counting is off unless enabled in the `[metrics]` INI section. The
+/// counters stay in the state directory and are never sent anywhere.
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
+pub struct MetricsArgs {
+    /// What to do with the counters.
+    #[command(subcommand)]
+    pub action: MetricsCommand,
+}
+
+/// Actions of the `metrics` subcommand.
+#[derive(Subcommand, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricsCommand {
+    /// Print the counters as a Markdown report, with the version and
+    /// platform, ready to paste into a bug report.
+    Show,
+}
+
+/// Arguments for the `config` subcommand.
+///
+/// Runs before the configuration file is read, so it works without one.
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
+pub struct ConfigArgs {
+    /// What to do with the configuration file.
+    #[command(subcommand)]
+    pub action: ConfigCommand,
+}
+
+/// Actions of the `config` subcommand.
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum ConfigCommand {
+    /// Write a valid INI configuration file, to `--config-ini` or the default
+    /// path, asking for what the flags do not give when run in a terminal.
+    Init(InitArgs),
+}
+
+/// Arguments for `config init`.
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
+pub struct InitArgs {
+    /// The Obsidian vault to log into. Must be an existing directory.
+    ///
+    /// Without it, the vault Obsidian lists (or one found under the home
+    /// directory) is offered.
+    #[arg(long)]
+    pub vault: Option<PathBuf>,
+
+    /// Where the diary files go, relative to the vault root.
+    #[arg(long)]
+    pub commit_path: Option<String>,
+
+    /// Also install the `post-commit` hook, see `install-hook`.
+    #[arg(long)]
+    pub install_hook: bool,
+
+    /// Repository to install the hook in, the one containing the current
+    /// directory by default.
+    #[arg(long, requires = "install_hook")]
+    pub hook_path: Option<PathBuf>,
+
+    /// Replace an existing configuration file.
+    #[arg(long)]
+    pub force: bool,
+}
+
+/// Stash operations recorded by `event stash`.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StashAction {
+    /// Changes were stashed (`git stash push`).
+    Push,
+
+    /// A stash entry was applied and dropped (`git stash pop`).
+    Pop,
+}
+
+/// Retrieves the configuration file path from CLI arguments or returns the default.
+///
+/// This function parses command-line arguments and returns the path to the INI configuration file.
+/// If no `--config-ini` argument is provided, returns the default path.
+///
+/// # Default Path
+///
+/// `~/.config/rusty-commit-saver/rusty-commit-saver.ini`
+///
+/// # Returns
+///
+/// A `String` containing the absolute path to the configuration file.
+///
+/// # CLI Usage
+///
+/// ```text
+/// // Use default config
+/// $ rusty-commit-saver
+/// // Returns: ~/.config/rusty-commit-saver/rusty-commit-saver.ini
+///
+/// // Use custom config
+/// $ rusty-commit-saver --config-ini /custom/path/config.ini
+/// // Returns: /custom/path/config.ini
+/// ```
+///
+/// # Panics
+///
+/// Panics if:
+/// - The resolved configuration file does not exist on the filesystem
+/// - The file cannot be read (permission denied, IO error)
+/// - The file path cannot be converted to a valid string
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::config::retrieve_config_file_path;
+///
+/// let config_path = retrieve_config_file_path();
+/// println!("Using config: {}", config_path);
+/// ```
+///
+/// # See Also
+///
+/// - [`get_or_default_config_ini_path`] - Helper that implements the CLI parsing logic
+/// - [`get_default_ini_path`] - Constructs the default configuration path
+#[must_use]
+pub fn retrieve_config_file_path() -> String {
+    info!(
+        "[UserInput::retrieve_config_file_path()]: retrieving the string path from CLI or default"
+    );
+    let config_path = get_or_default_config_ini_path();
+
+    if Path::new(&config_path).exists() {
+        info!("[UserInput::retrieve_config_file_path()]: config_path exists {config_path:}");
+    } else {
+        error!(
+            "[UserInput::retrieve_config_file_path()]: config_path DOES NOT exists {config_path:}"
+        );
+        panic!(
+            "[UserInput::retrieve_config_file_path()]: config_path DOES NOT exists {config_path:}"
+        );
+    }
+    info!("[UserInput::retrieve_config_file_path()] retrieved config path: {config_path:}");
+    fs::read_to_string(config_path.clone())
+        .unwrap_or_else(|_| panic!("Should have been able to read the file: {config_path:}"))
+}
+
+/// Returns the config path from CLI arguments or the default path.
+///
+/// Internal helper function that parses CLI arguments using `UserInput` and
+/// returns either the provided `--config-ini` path or the default configuration
+/// file location.
+///
+/// # Returns
+///
+/// - CLI path if `--config-ini` was provided
+/// - Default path (`~/.config/rusty-commit-saver/rusty-commit-saver.ini`) otherwise
+///
+/// # Called By
+///
+/// This function is called internally by [`retrieve_config_file_path()`].
+///
+/// # See Also
+///
+/// - [`get_default_ini_path()`] - Constructs the default configuration path
+#[must_use]
+pub fn resolve_config_path(cli_arg: Option<String>, env_var: Option<String>) -> String {
+    // Check env var first
+    if let Some(env_path) = env_var {
+        info!("[resolve_config_path]: Using config from env var.");
+        return if env_path.contains('~') {
+            set_proper_home_dir(&env_path)
+        } else {
+            env_path
+        };
+    }
+
+    // Check CLI arg
+    if let Some(cfg_str) = cli_arg {
+        if cfg_str.contains('~') {
+            info!("[resolve_config_path]: CLI path contains '~'.");
+            set_proper_home_dir(&cfg_str)
+        } else {
+            info!("[resolve_config_path]: CLI path without '~'.");
+            cfg_str
+        }
+    } else {
+        info!("[resolve_config_path]: Using default path.");
+        get_default_ini_path()
+    }
+}
+
+#[must_use]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn get_or_default_config_ini_path() -> String {
+    get_or_default_config_ini_path_with(std::env::var("RUSTY_COMMIT_SAVER_CONFIG").ok(), || {
+        UserInput::parse().config_ini
+    })
+}
+
+#[must_use]
+fn get_or_default_config_ini_path_with<F>(env_var: Option<String>, cli_parser: F) -> String
+where
+    F: FnOnce() -> Option<String>,
+{
+    info!("[get_or_default_config_ini_path()]: Parsing CLI inputs.");
+
+    let cli_arg = if env_var.is_some() {
+        None // Skip parsing if env var is set
+    } else {
+        cli_parser()
+    };
+
+    let config_path = resolve_config_path(cli_arg, env_var);
+    info!("[get_or_default_config_ini_path()]: Config path found: {config_path:}");
+    config_path
+}
+
+/// Constructs the default configuration file path.
+///
+/// Builds the standard XDG configuration path for the application by combining
+/// the user's home directory with the application-specific config directory.
+///
+/// # Returns
+///
+/// A `String` with the default INI file path:
+/// `~/.config/rusty-commit-saver/rusty-commit-saver.ini`, or the
+/// `rusty-commit-saver.toml`, `.yaml` or `.yml` next to it when only that one
+/// exists (see [`existing_config_path()`]).
+///
+/// # Directory Structure
+///
+/// ```text
+/// ~/.config/
+///   └── rusty-commit-saver/
+///       └── rusty-commit-saver.ini
+/// ```
+///
+/// # Panics
+///
+/// Panics if the user's home directory cannot be determined
+/// (via the `dirs::home_dir()` function).
+///
+/// # Examples
+///
+/// ```ignore
+/// // Internal usage
+/// let default_path = get_default_ini_path();
+/// // Returns: "/home/user/.config/rusty-commit-saver/rusty-commit-saver.ini"
+/// ```
+///
+/// # See Also
+///
+/// - [`retrieve_config_file_path()`] - Public API for getting config path
+#[must_use]
+pub fn get_default_ini_path() -> String {
+    info!("[get_default_ini_path()]: Getting default ini file.");
+    let cfg_str = "~/.config/rusty-commit-saver/rusty-commit-saver.ini".to_string();
+    existing_config_path(Path::new(&set_proper_home_dir(&cfg_str)))
+        .display()
+        .to_string()
+}
+
+/// Loads and parses the INI configuration file from disk.
+///
+/// Reads the configuration file (from CLI argument or default location),
+/// parses its contents using [`parse_ini_content()`], and returns the
+/// parsed `Ini` object.
+///
+/// # Returns
+///
+/// A parsed `Ini` configuration object
+///
+/// # Panics
+///
+/// Panics if:
+/// - The configuration file doesn't exist at the resolved path
+/// - The file cannot be read (permission denied, I/O error)
+/// - The file content is not valid UTF-8
+/// - The INI syntax is invalid (malformed sections or key-value pairs)
+/// - A `.toml` or `.yaml` file does not parse, or does not follow the INI
+///   schema (see [`parse_config()`])
+///
+/// # File Resolution Order
+///
+/// 1. Check for `--config-ini <PATH>` CLI argument
+/// 2. Fall back to `~/.config/rusty-commit-saver/rusty-commit-saver.ini`
+///
+/// # Expected INI Structure
+///
+/// ```text
+/// [obsidian]
+/// root_path_dir = ~/Documents/Obsidian
+/// commit_path = Diaries/Commits
+///
+/// [templates]
+/// commit_date_path = %Y/%m-%B/%F.md
+/// commit_datetime = %H:%M:%S
+/// ```
+///
+/// # Called By
+///
+/// This function is called internally by [`GlobalVars::set_all()`].
+///
+/// # See Also
+///
+/// - [`retrieve_config_file_path()`] - Resolves the config file path
+/// - [`parse_ini_content()`] - Parses INI text into `Ini` struct
+#[must_use]
+pub fn get_ini_file() -> Ini {
+    info!("[get_ini_file()]: Retrieving the INI File");
+    let content_ini = retrieve_config_file_path();
+    let config_path = get_or_default_config_ini_path();
+    let format = ConfigFormat::from_path(Path::new(&config_path));
+    let config = parse_config(&content_ini, format).unwrap_or_else(|e| {
+        panic!("Could not read the {format:} config file {config_path:}: {e:}")
+    });
+
+    info!("[get_ini_file()]: This is the INI File:\n\n{config:?}");
+    config
+}
+
+/// Returns the state directory of the [`SaveSettings`], for the shell
+/// completions (see [`repository_completer()`]).
+///
+/// The shell runs the binary with its own arguments when it asks for
+/// candidates, so the config is only looked for through
+/// `RUSTY_COMMIT_SAVER_CONFIG` or at the default path, never `--config-ini`.
+/// Without a readable config there is no state directory, and nothing is
+/// completed.
+#[must_use]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn completion_state_dir() -> Option<PathBuf> {
+    let config_path = resolve_config_path(None, env::var("RUSTY_COMMIT_SAVER_CONFIG").ok());
+    let content = fs::read_to_string(&config_path).ok()?;
+    let format = ConfigFormat::from_path(Path::new(&config_path));
+    let mut config = parse_config(&content, format).ok()?;
+    apply_env_overrides(&mut config, |variable| env::var(variable).ok());
+
+    let global_vars = GlobalVars::new();
+    global_vars.config.set(config).ok()?;
+    global_vars.set_obsidian_vars();
+    global_vars.get_save_settings().state_dir
+}
+
+/// Parses the `[table] columns` list, e.g. `time, message, branch, hash`.
+///
+/// # Panics
+///
+/// Panics if the list is empty, or names an unknown column or the same column twice.
+fn parse_table_columns(value: &str) -> Vec<TableColumn> {
+    let mut columns = Vec::new();
+    for name in value
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        let column = TableColumn::from_name(name)
+            .unwrap_or_else(|| panic!("[parse_table_columns()] Unknown table column: {name:}"));
+        assert!(
+            !columns.contains(&column),
+            "[parse_table_columns()] Table column listed twice: {name:}"
+        );
+        columns.push(column);
+    }
+    assert!(
+        !columns.is_empty(),
+        "[parse_table_columns()] columns must list at least one column"
+    );
+    info!("[parse_table_columns()]: Table columns: {columns:?}");
+    columns
+}
+
+/// Expands the tilde (`~`) character to the user's home directory path.
+///
+/// Replaces the leading `~` in a path string with the absolute path to the
+/// user's home directory. If no `~` is present, returns the string unchanged.
+///
+/// # Arguments
+///
+/// * `cfg_str` - A path string that may contain a leading `~`
+///
+/// # Returns
+///
+/// A `String` with `~` expanded to the full home directory path
+///
+/// # Panics
+///
+/// Panics if the user's home directory cannot be determined
+/// (via the `dirs::home_dir()` function).
+///
+/// # Examples
+///
+/// ```ignore
+/// // On Linux/macOS with home at /home/user
+/// let expanded = set_proper_home_dir("~/Documents/Obsidian");
+/// assert_eq!(expanded, "/home/user/Documents/Obsidian");
+///
+/// // Path without tilde is returned unchanged
+/// let unchanged = set_proper_home_dir("/absolute/path");
+/// assert_eq!(unchanged, "/absolute/path");
+/// ```
+///
+/// # Platform Behavior
+///
+/// - **Linux/macOS**: Expands to `/home/username` or `/Users/username`
+/// - **Windows**: Expands to `C:\Users\username`
+///
+/// # Used By
+///
+/// This function is called by:
+/// - [`GlobalVars::set_obsidian_root_path_dir()`]
+/// - [`GlobalVars::set_obsidian_commit_path()`]
+/// - [`init_config()`](crate::bootstrap::init_config) for the vault path typed in
+pub(crate) fn set_proper_home_dir(cfg_str: &str) -> String {
+    info!("[set_proper_home_dir()]: Changing the '~' to full home directory.");
+    let home_dir = home_dir()
+        .expect("Could not get home_dir")
+        .into_os_string()
+        .into_string()
+        .expect("Could not convert home_dir from OsString to String");
+
+    cfg_str.replace('~', &home_dir)
+}
+
+/// Splits a `[forges]` key like `git.example.com.commit` into host and link kind.
+///
+/// Returns `None` for plain host keys, which select a preset.
+fn split_template_key(key: &str) -> Option<(&str, &str)> {
+    key.rsplit_once('.')
+        .filter(|(_, kind)| matches!(*kind, "commit" | "branch" | "pr"))
 }
 
-/// Retrieves the configuration file path from CLI arguments or returns the default.
-///
-/// This function parses command-line arguments and returns the path to the INI configuration file.
-/// If no `--config-ini` argument is provided, returns the default path.
-///
-/// # Default Path
-///
-/// `~/.config/rusty-commit-saver/rusty-commit-saver.ini`
-///
-/// # Returns
-///
-/// A `String` containing the absolute path to the configuration file.
-///
-/// # CLI Usage
-///
-/// ```text
-/// // Use default config
-/// $ rusty-commit-saver
-/// // Returns: ~/.config/rusty-commit-saver/rusty-commit-saver.ini
-///
-/// // Use custom config
-/// $ rusty-commit-saver --config-ini /custom/path/config.ini
-/// // Returns: /custom/path/config.ini
-/// ```
-///
-/// # Panics
-///
-/// Panics if:
-/// - The resolved configuration file does not exist on the filesystem
-/// - The file cannot be read (permission denied, IO error)
-/// - The file path cannot be converted to a valid string
-///
-/// # Examples
-///
-/// ```ignore
-/// use rusty_commit_saver::config::retrieve_config_file_path;
-///
-/// let config_path = retrieve_config_file_path();
-/// println!("Using config: {}", config_path);
-/// ```
-///
-/// # See Also
-///
-/// - [`get_or_default_config_ini_path`] - Helper that implements the CLI parsing logic
-/// - [`get_default_ini_path`] - Constructs the default configuration path
-#[must_use]
-pub fn retrieve_config_file_path() -> String {
-    info!(
-        "[UserInput::retrieve_config_file_path()]: retrieving the string path from CLI or default"
-    );
-    let config_path = get_or_default_config_ini_path();
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod global_vars_tests {
+    use super::*;
+    use std::panic::{self, AssertUnwindSafe};
+
+    #[test]
+    fn test_global_vars_new() {
+        let global_vars = GlobalVars::new();
+
+        assert!(global_vars.config.get().is_none());
+    }
+
+    #[test]
+    fn test_global_vars_default() {
+        let global_vars = GlobalVars::default();
+
+        assert!(global_vars.config.get().is_none());
+    }
+
+    #[test]
+    fn test_get_sections_from_config_valid() {
+        let mut config = Ini::new();
+        config.set("obsidian", "root_path_dir", Some("/tmp/test".to_string()));
+        config.set(
+            "templates",
+            "commit_date_path",
+            Some("%Y-%m-%d".to_string()),
+        );
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        let sections = global_vars.get_sections_from_config();
+
+        assert_eq!(sections.len(), 2);
+        assert!(sections.contains(&"obsidian".to_string()));
+        assert!(sections.contains(&"templates".to_string()));
+    }
+
+    #[test]
+    fn test_get_sections_from_config_invalid_count() {
+        let mut config = Ini::new();
+        config.set("only_one_section", "key", Some("value".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        let result =
+            panic::catch_unwind(AssertUnwindSafe(|| global_vars.get_sections_from_config()));
+
+        assert!(result.is_err(), "Expected panic for invalid section count");
+
+        // Verify the panic message (panic! with string literal = &str)
+        let panic_info = result.unwrap_err();
+        let msg = panic_info
+            .downcast_ref::<String>()
+            .expect("Panic message should be a String");
+        assert!(
+            msg.contains("missing the required 'obsidian' section"),
+            "Unexpected panic message: {msg}"
+        );
+    }
+
+    #[test]
+    fn test_get_sections_from_config_panics_with_zero_sections() {
+        let config = Ini::new();
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        let result =
+            panic::catch_unwind(AssertUnwindSafe(|| global_vars.get_sections_from_config()));
+
+        assert!(result.is_err(), "Expected panic for zero sections");
+    }
+
+    #[test]
+    fn test_get_sections_from_config_skips_unknown_sections() {
+        let mut config = Ini::new();
+        config.set("obsidian", "root_path_dir", Some("/tmp/test".to_string()));
+        config.set("extra", "key", Some("value".to_string()));
+        config.set("templates", "commit_date_path", Some("%Y.md".to_string()));
+        config.set("events", "branch_switches", Some("true".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        let mut sections = global_vars.get_sections_from_config();
+        sections.sort();
+
+        assert_eq!(sections, vec!["events", "obsidian", "templates"]);
+    }
+
+    #[test]
+    fn test_get_sections_from_config_panics_without_templates() {
+        let mut config = Ini::new();
+        config.set("obsidian", "root_path_dir", Some("/tmp/test".to_string()));
+        config.set("events", "branch_switches", Some("true".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        let result =
+            panic::catch_unwind(AssertUnwindSafe(|| global_vars.get_sections_from_config()));
+
+        assert!(result.is_err(), "Expected panic without [templates]");
+    }
+
+    #[test]
+    fn test_get_sections_from_config_allows_optional_events_section() {
+        let mut config = Ini::new();
+        config.set("obsidian", "root_path_dir", Some("/tmp/test".to_string()));
+        config.set("templates", "commit_date_path", Some("%Y.md".to_string()));
+        config.set("events", "branch_switches", Some("true".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        let sections = global_vars.get_sections_from_config();
+
+        assert_eq!(sections.len(), 3);
+        assert!(sections.contains(&"events".to_string()));
+    }
+
+    #[test]
+    fn test_get_save_settings_defaults_without_optional_sections() {
+        let global_vars = GlobalVars::new();
+
+        let settings = global_vars.get_save_settings();
+
+        assert_eq!(
+            settings,
+            SaveSettings {
+                state_dir: default_state_dir(),
+                ..SaveSettings::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_get_save_settings_with_duration_section() {
+        let mut config = Ini::new();
+        config.set("duration", "enabled", Some("true".to_string()));
+        config.set("duration", "max_minutes", Some("90".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_duration_vars("duration");
+
+        let settings = global_vars.get_save_settings();
+
+        assert_eq!(settings.duration_cap, TimeDelta::minutes(90));
+        assert_eq!(settings.table_columns.last(), Some(&TableColumn::Duration));
+    }
+
+    #[test]
+    fn test_set_time_tracking_source_command_adds_tracking_column() {
+        let mut config = Ini::new();
+        config.set(
+            "time_tracking",
+            "command",
+            Some("timew get dom.active.tag.1".to_string()),
+        );
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_time_tracking_source("time_tracking");
+
+        let settings = global_vars.get_save_settings();
+
+        assert_eq!(
+            settings.time_tracking,
+            Some(TimeTrackingSource::Command(
+                "timew get dom.active.tag.1".to_string()
+            ))
+        );
+        assert_eq!(settings.table_columns.last(), Some(&TableColumn::Tracking));
+    }
+
+    #[test]
+    fn test_set_build_status_check_adds_status_column() {
+        let mut config = Ini::new();
+        config.set(
+            "build_status",
+            "command",
+            Some("cargo check --quiet".to_string()),
+        );
+        config.set("build_status", "timeout_seconds", Some("30".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_build_status_check("build_status");
+
+        let settings = global_vars.get_save_settings();
+        assert_eq!(
+            settings.build_status,
+            Some(BuildStatusCheck {
+                source: BuildStatusSource::Command("cargo check --quiet".to_string()),
+                timeout: Duration::from_secs(30),
+            })
+        );
+        assert_eq!(settings.table_columns.last(), Some(&TableColumn::Status));
+    }
+
+    #[test]
+    fn test_set_build_status_check_file_with_default_timeout() {
+        let mut config = Ini::new();
+        config.set("build_status", "file", Some(".build-status".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_build_status_check("build_status");
+
+        assert_eq!(
+            global_vars.get_save_settings().build_status,
+            Some(BuildStatusCheck {
+                source: BuildStatusSource::File(PathBuf::from(".build-status")),
+                timeout: DEFAULT_BUILD_TIMEOUT,
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Set exactly one of 'command' or 'file' in [build_status]")]
+    fn test_set_build_status_check_requires_exactly_one_key() {
+        let mut config = Ini::new();
+        config.set("build_status", "timeout_seconds", Some("5".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_build_status_check("build_status");
+    }
+
+    #[test]
+    fn test_set_time_tracking_source_file() {
+        let mut config = Ini::new();
+        config.set(
+            "time_tracking",
+            "file",
+            Some("/tmp/toggl/current.txt".to_string()),
+        );
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_time_tracking_source("time_tracking");
+
+        assert_eq!(
+            global_vars.get_save_settings().time_tracking,
+            Some(TimeTrackingSource::File(PathBuf::from(
+                "/tmp/toggl/current.txt"
+            )))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Set exactly one of 'command' or 'file'")]
+    fn test_set_time_tracking_source_requires_exactly_one_key() {
+        let mut config = Ini::new();
+        config.set("time_tracking", "command", Some("echo a".to_string()));
+        config.set("time_tracking", "file", Some("/tmp/a".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_time_tracking_source("time_tracking");
+    }
+
+    #[test]
+    fn test_set_vault_git_vars() {
+        let mut config = Ini::new();
+        config.set("vault_git", "auto_commit", Some("true".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_vault_git_vars("vault_git");
+
+        assert_eq!(
+            global_vars.get_save_settings().vault_commit_message,
+            Some(DEFAULT_VAULT_COMMIT_MESSAGE.to_string())
+        );
+
+        let mut config = Ini::new();
+        config.set("vault_git", "auto_commit", Some("off".to_string()));
+        config.set("vault_git", "message", Some("ignored".to_string()));
+
+        let disabled = GlobalVars::new();
+        disabled.config.set(config).unwrap();
+        disabled.set_vault_git_vars("vault_git");
+
+        assert_eq!(disabled.get_save_settings().vault_commit_message, None);
+    }
+
+    #[test]
+    fn test_set_table_vars_max_row_length() {
+        let mut config = Ini::new();
+        config.set("table", "max_row_length", Some("500".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_table_vars("table");
+
+        assert_eq!(global_vars.get_save_settings().max_row_length, Some(500));
+    }
+
+    #[test]
+    fn test_set_table_vars_time_zone() {
+        let mut config = Ini::new();
+        config.set("table", "time_zone", Some("Abbreviation".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_table_vars("table");
+
+        let save_settings = global_vars.get_save_settings();
+        assert_eq!(save_settings.time_zone, Some(TimezoneStyle::Abbreviation));
+        assert_eq!(save_settings.max_row_length, None);
+        assert_eq!(save_settings.same_second, SameSecondPolicy::Keep);
+    }
+
+    #[test]
+    fn test_set_table_vars_same_second() {
+        let mut config = Ini::new();
+        config.set("table", "same_second", Some("sequence".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_table_vars("table");
+
+        assert_eq!(
+            global_vars.get_save_settings().same_second,
+            SameSecondPolicy::Sequence
+        );
+    }
+
+    #[test]
+    fn test_set_table_vars_forge_links() {
+        let mut config = Ini::new();
+        config.set("table", "forge_links", Some("yes".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        assert!(!global_vars.get_save_settings().forge_links);
+        global_vars.set_table_vars("table");
+
+        assert!(global_vars.get_save_settings().forge_links);
+    }
+
+    #[test]
+    fn test_set_table_vars_block_refs() {
+        let mut config = Ini::new();
+        config.set("table", "block_refs", Some("true".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        assert!(!global_vars.get_save_settings().block_refs);
+        global_vars.set_table_vars("table");
+
+        assert!(global_vars.get_save_settings().block_refs);
+    }
+
+    #[test]
+    #[should_panic(expected = "same_second must be 'keep' or 'sequence'")]
+    fn test_set_table_vars_rejects_unknown_same_second() {
+        let mut config = Ini::new();
+        config.set("table", "same_second", Some("suffix".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_table_vars("table");
+    }
+
+    #[test]
+    fn test_set_table_vars_columns() {
+        let mut config = Ini::new();
+        config.set(
+            "table",
+            "columns",
+            Some("time, message, duration, branch, hash".to_string()),
+        );
+        config.set("duration", "enabled", Some("true".to_string()));
+        config.set("events", "stash", Some("true".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_table_vars("table");
+        global_vars.set_duration_vars("duration");
+        global_vars.set_events_vars("events");
+
+        assert_eq!(
+            global_vars.get_save_settings().table_columns,
+            vec![
+                TableColumn::Time,
+                TableColumn::CommitMessage,
+                TableColumn::Duration,
+                TableColumn::Branch,
+                TableColumn::CommitHash,
+                TableColumn::Event,
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown table column: committer")]
+    fn test_set_table_vars_rejects_unknown_columns() {
+        let mut config = Ini::new();
+        config.set("table", "columns", Some("time, committer".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_table_vars("table");
+    }
+
+    #[test]
+    #[should_panic(expected = "Table column listed twice: commit_hash")]
+    fn test_set_table_vars_rejects_duplicate_columns() {
+        let mut config = Ini::new();
+        config.set(
+            "table",
+            "columns",
+            Some("hash, time, commit_hash".to_string()),
+        );
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_table_vars("table");
+    }
+
+    #[test]
+    #[should_panic(expected = "time_zone must be 'offset' or 'abbreviation'")]
+    fn test_set_table_vars_rejects_unknown_time_zone() {
+        let mut config = Ini::new();
+        config.set("table", "time_zone", Some("Europe/Zurich".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_table_vars("table");
+    }
+
+    #[test]
+    #[should_panic(expected = "max_row_length must be positive")]
+    fn test_set_table_vars_rejects_zero() {
+        let mut config = Ini::new();
+        config.set("table", "max_row_length", Some("0".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_table_vars("table");
+    }
+
+    #[test]
+    fn test_set_category_rules_sorted_and_split() {
+        let mut config = Ini::new();
+        config.set(
+            "categories",
+            "work",
+            Some("*github.com/acme/*, /srv/work/*".to_string()),
+        );
+        config.set("categories", "learning", Some("/courses/*".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_category_rules("categories");
+
+        let rules = global_vars.get_save_settings().category_rules;
+        assert_eq!(
+            rules
+                .iter()
+                .map(|rule| (rule.category.as_str(), rule.pattern.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("learning", "/courses/*"),
+                ("work", "*github.com/acme/*"),
+                ("work", "/srv/work/*"),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid category name")]
+    fn test_set_category_rules_rejects_invalid_names() {
+        let mut config = Ini::new();
+        config.set("categories", "side project", Some("*".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_category_rules("categories");
+    }
+
+    #[test]
+    fn test_get_save_settings_trash_dir_follows_safety_section() {
+        let global_vars = GlobalVars::new();
+        global_vars
+            .obsidian_root_path_dir
+            .set(PathBuf::from("/vault"))
+            .unwrap();
+        assert_eq!(
+            global_vars.get_save_settings().trash_dir,
+            Some(PathBuf::from("/vault/.trash"))
+        );
+
+        assert!(!global_vars.get_save_settings().require_vault);
+
+        let mut config = Ini::new();
+        config.set("safety", "use_trash", Some("false".to_string()));
+        config.set("safety", "require_vault", Some("true".to_string()));
+        global_vars.config.set(config).unwrap();
+        global_vars.set_safety_vars("safety");
+        assert_eq!(global_vars.get_save_settings().trash_dir, None);
+        assert!(global_vars.get_save_settings().require_vault);
+    }
+
+    #[test]
+    fn test_get_save_settings_detect_language_follows_language_section() {
+        let global_vars = GlobalVars::new();
+        assert!(!global_vars.get_save_settings().detect_language);
+
+        let mut config = Ini::new();
+        config.set("language", "detect", Some("true".to_string()));
+        global_vars.config.set(config).unwrap();
+        global_vars.set_language_vars("language");
+        assert!(global_vars.get_save_settings().detect_language);
+    }
+
+    #[test]
+    fn test_get_save_settings_conventional_commits_follows_its_section() {
+        let global_vars = GlobalVars::new();
+        assert!(!global_vars.get_save_settings().conventional_commits);
+
+        let mut config = Ini::new();
+        config.set("conventional_commits", "enabled", Some("on".to_string()));
+        global_vars.config.set(config).unwrap();
+        global_vars.set_conventional_commits_vars("conventional_commits");
+        assert!(global_vars.get_save_settings().conventional_commits);
+    }
+
+    #[test]
+    fn test_set_trailers_vars() {
+        let global_vars = GlobalVars::new();
+        assert_eq!(global_vars.get_save_settings().trailer_tags, None);
+
+        let mut config = Ini::new();
+        config.set("trailers", "tags", Some("Frontmatter".to_string()));
+        global_vars.config.set(config).unwrap();
+        global_vars.set_trailers_vars("trailers");
+        assert_eq!(
+            global_vars.get_save_settings().trailer_tags,
+            Some(TagPlacement::Frontmatter)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "tags must be 'row', 'frontmatter' or 'both'")]
+    fn test_set_trailers_vars_rejects_unknown_placements() {
+        let global_vars = GlobalVars::new();
+        let mut config = Ini::new();
+        config.set("trailers", "tags", Some("body".to_string()));
+        global_vars.config.set(config).unwrap();
+        global_vars.set_trailers_vars("trailers");
+    }
+
+    #[test]
+    fn test_set_output_vars() {
+        let global_vars = GlobalVars::new();
+        assert_eq!(
+            global_vars.get_save_settings().sinks,
+            vec![SinkKind::ObsidianMarkdown]
+        );
+
+        let mut config = Ini::new();
+        config.set("obsidian", "root_path_dir", Some("/vault".to_string()));
+        config.set("output", "sinks", Some("JSONL, stdout, jsonl".to_string()));
+        config.set(
+            "output",
+            "jsonl_path",
+            Some("Data/commits.jsonl".to_string()),
+        );
+        global_vars.config.set(config).unwrap();
+        global_vars.set_obsidian_root_path_dir("obsidian");
+        global_vars.set_output_vars("output");
+        assert_eq!(
+            global_vars.get_save_settings().sinks,
+            vec![
+                SinkKind::Jsonl(PathBuf::from("/vault/Data/commits.jsonl")),
+                SinkKind::Stdout
+            ]
+        );
+    }
+
+    #[test]
+    fn test_set_output_style_overrides_the_entry_format() {
+        let global_vars = GlobalVars::new();
+        let mut config = Ini::new();
+        config.set("templates", "entry_format", Some("yaml".to_string()));
+        config.set("output", "style", Some("Dataview".to_string()));
+        global_vars.config.set(config).unwrap();
+        global_vars.set_templates_entry_format("templates");
+        assert_eq!(
+            global_vars.get_save_settings().entry_format,
+            EntryFormat::Yaml
+        );
+        global_vars.set_output_style("output");
+        assert_eq!(
+            global_vars.get_save_settings().entry_format,
+            EntryFormat::Dataview
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "style must be 'table', 'yaml' or 'dataview'")]
+    fn test_set_output_style_rejects_unknown_styles() {
+        let global_vars = GlobalVars::new();
+        let mut config = Ini::new();
+        config.set("output", "style", Some("json".to_string()));
+        global_vars.config.set(config).unwrap();
+        global_vars.set_output_style("output");
+    }
+
+    #[test]
+    #[should_panic(expected = "sinks must be 'markdown', 'jsonl', 'sqlite' or 'stdout'")]
+    fn test_set_output_vars_rejects_unknown_sinks() {
+        let global_vars = GlobalVars::new();
+        let mut config = Ini::new();
+        config.set("output", "sinks", Some("markdown, csv".to_string()));
+        global_vars.config.set(config).unwrap();
+        global_vars.set_output_vars("output");
+    }
+
+    #[test]
+    #[should_panic(expected = "jsonl_path is required")]
+    fn test_set_output_vars_requires_the_sink_path() {
+        let global_vars = GlobalVars::new();
+        let mut config = Ini::new();
+        config.set("output", "sinks", Some("jsonl".to_string()));
+        global_vars.config.set(config).unwrap();
+        global_vars.set_output_vars("output");
+    }
+
+    #[test]
+    #[cfg(not(feature = "sqlite"))]
+    #[should_panic(expected = "needs a build with the 'sqlite' feature")]
+    fn test_set_output_vars_rejects_sqlite_without_the_feature() {
+        let global_vars = GlobalVars::new();
+        let mut config = Ini::new();
+        config.set("output", "sinks", Some("sqlite".to_string()));
+        config.set("output", "sqlite_path", Some("/tmp/commits.db".to_string()));
+        global_vars.config.set(config).unwrap();
+        global_vars.set_output_vars("output");
+    }
+
+    #[test]
+    fn test_set_branches_vars_enables_the_slug_tag_and_column() {
+        let global_vars = GlobalVars::new();
+        let settings = global_vars.get_save_settings();
+        assert!(!settings.branch_tags);
+        assert!(!settings.table_columns.contains(&TableColumn::BranchSlug));
+
+        let mut config = Ini::new();
+        config.set("branches", "tags", Some("true".to_string()));
+        config.set("branches", "column", Some("on".to_string()));
+        config.set("branches", "merge_summary", Some("yes".to_string()));
+        global_vars.config.set(config).unwrap();
+        global_vars.set_branches_vars("branches");
+
+        let settings = global_vars.get_save_settings();
+        assert!(settings.branch_tags);
+        assert!(settings.branch_summary);
+        assert_eq!(
+            settings.table_columns.last(),
+            Some(&TableColumn::BranchSlug)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Could not parse column from INI as a boolean")]
+    fn test_set_branches_vars_panics_on_invalid_value() {
+        let mut config = Ini::new();
+        config.set("branches", "column", Some("maybe".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        global_vars.set_branches_vars("branches");
+    }
+
+    #[test]
+    fn test_set_recent_projects_dir() {
+        let mut config = Ini::new();
+        config.set(
+            "recent",
+            "projects_dir",
+            Some("/home/me/Projects".to_string()),
+        );
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        assert_eq!(global_vars.get_recent_projects_dir(), None);
+
+        global_vars.set_recent_projects_dir("recent");
+
+        assert_eq!(
+            global_vars.get_recent_projects_dir(),
+            Some(PathBuf::from("/home/me/Projects"))
+        );
+    }
+
+    #[test]
+    fn test_set_metrics_vars() {
+        let mut config = Ini::new();
+        config.set("metrics", "enabled", Some("yes".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        assert!(!global_vars.get_metrics_enabled());
+
+        global_vars.set_metrics_vars("metrics");
+
+        assert!(global_vars.get_metrics_enabled());
+        assert_eq!(Command::Stats(StatsArgs::default()).name(), "stats");
+        assert_eq!(Command::Heatmap(HeatmapArgs::default()).name(), "heatmap");
+    }
+
+    #[test]
+    fn test_set_watched_repositories() {
+        let mut config = Ini::new();
+        config.set("repositories", "web", Some("/srv/git/web".to_string()));
+        config.set("repositories", "api", Some(" /home/me/api ".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        assert!(global_vars.get_watched_repositories().is_empty());
+
+        global_vars.set_watched_repositories("repositories");
+
+        assert_eq!(
+            global_vars.get_watched_repositories(),
+            vec![PathBuf::from("/home/me/api"), PathBuf::from("/srv/git/web")]
+        );
+    }
+
+    #[test]
+    fn test_set_daemon_vars() {
+        let mut config = Ini::new();
+        config.set("daemon", "debounce_ms", Some("2000".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        assert_eq!(global_vars.get_daemon_debounce(), DEFAULT_DEBOUNCE);
+
+        global_vars.set_daemon_vars("daemon");
+
+        assert_eq!(global_vars.get_daemon_debounce(), Duration::from_secs(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Repository api must have a path")]
+    fn test_set_watched_repositories_rejects_empty_paths() {
+        let mut config = Ini::new();
+        config.set("repositories", "api", Some(String::new()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        global_vars.set_watched_repositories("repositories");
+    }
+
+    #[test]
+    fn test_set_related_repos_vars() {
+        let mut config = Ini::new();
+        config.set("related_repos", "enabled", Some("true".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        global_vars.set_related_repos_vars("related_repos");
+
+        assert_eq!(
+            global_vars.get_save_settings().related_repos_note,
+            Some(PathBuf::from(DEFAULT_RELATED_REPOS_NOTE))
+        );
+    }
+
+    #[test]
+    fn test_set_message_hygiene_vars() {
+        let mut config = Ini::new();
+        config.set(
+            "message_hygiene",
+            "pattern",
+            Some("^(feat|fix): .+".to_string()),
+        );
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        global_vars.set_message_hygiene_vars("message_hygiene");
+
+        let pattern = global_vars.get_save_settings().message_pattern.unwrap();
+        assert!(pattern.is_match("fix: typo"));
+        assert!(!pattern.is_match("wip"));
+    }
+
+    #[test]
+    #[should_panic(expected = "pattern is not a valid regex")]
+    fn test_set_message_hygiene_vars_rejects_invalid_patterns() {
+        let mut config = Ini::new();
+        config.set("message_hygiene", "pattern", Some("(feat".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        global_vars.set_message_hygiene_vars("message_hygiene");
+    }
+
+    #[test]
+    fn test_set_webhook_vars() {
+        let mut config = Ini::new();
+        config.set(
+            "webhook",
+            "url",
+            Some("https://hooks.example.com/commits".to_string()),
+        );
+        config.set(
+            "webhook",
+            "secret_env",
+            Some("RCS_TEST_UNSET_WEBHOOK_SECRET".to_string()),
+        );
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        assert_eq!(global_vars.get_save_settings().webhook, None);
+
+        global_vars.set_webhook_vars("webhook");
+
+        let webhook = global_vars.get_save_settings().webhook.unwrap();
+        assert_eq!(webhook.url, "https://hooks.example.com/commits");
+        assert_eq!(webhook.payload_template, DEFAULT_PAYLOAD_TEMPLATE);
+        assert_eq!(webhook.secret, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "payload is not a valid Handlebars template")]
+    fn test_set_webhook_vars_rejects_invalid_payloads() {
+        let mut config = Ini::new();
+        config.set(
+            "webhook",
+            "url",
+            Some("https://hooks.example.com".to_string()),
+        );
+        config.set("webhook", "payload", Some("{{#if}}".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        global_vars.set_webhook_vars("webhook");
+    }
+
+    #[test]
+    fn test_set_daily_note_vars() {
+        let global_vars = GlobalVars::new();
+        let mut config = Ini::new();
+        config.set("daily_note", "enabled", Some("true".to_string()));
+        config.set("daily_note", "link", Some("Wikilink".to_string()));
+        global_vars.config.set(config).unwrap();
+        global_vars.set_daily_note_vars("daily_note");
+        assert_eq!(
+            global_vars.get_save_settings().daily_note,
+            Some(DailyNote {
+                note_template: DEFAULT_DAILY_NOTE_TEMPLATE.to_string(),
+                style: DailyNoteLinkStyle::Wikilink,
+                create: false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_set_heatmap_vars() {
+        let heatmap = |note: Option<&str>, format: &str| {
+            let global_vars = GlobalVars::new();
+            let mut config = Ini::new();
+            config.set("heatmap", "note", note.map(str::to_string));
+            config.set("heatmap", "format", Some(format.to_string()));
+            global_vars.config.set(config).unwrap();
+            global_vars.set_heatmap_vars("heatmap");
+            global_vars.get_save_settings().heatmap
+        };
+
+        assert_eq!(
+            heatmap(Some("Rollups/Heatmap.md"), "Text"),
+            HeatmapSettings {
+                note: Some(PathBuf::from("Rollups/Heatmap.md")),
+                format: HeatmapFormat::Text,
+            }
+        );
+        assert_eq!(
+            heatmap(Some(""), "html").note,
+            Some(PathBuf::from(DEFAULT_HEATMAP_NOTE))
+        );
+        assert_eq!(heatmap(None, "html"), HeatmapSettings::default());
+    }
+
+    #[test]
+    #[should_panic(expected = "format must be 'html' or 'text'")]
+    fn test_set_heatmap_vars_rejects_unknown_formats() {
+        let global_vars = GlobalVars::new();
+        let mut config = Ini::new();
+        config.set("heatmap", "format", Some("mermaid".to_string()));
+        global_vars.config.set(config).unwrap();
+        global_vars.set_heatmap_vars("heatmap");
+    }
+
+    #[test]
+    #[should_panic(expected = "link must be 'embed' or 'wikilink'")]
+    fn test_set_daily_note_vars_rejects_unknown_link_styles() {
+        let global_vars = GlobalVars::new();
+        let mut config = Ini::new();
+        config.set("daily_note", "enabled", Some("true".to_string()));
+        config.set("daily_note", "link", Some("markdown".to_string()));
+        global_vars.config.set(config).unwrap();
+        global_vars.set_daily_note_vars("daily_note");
+    }
+
+    #[test]
+    fn test_set_weekly_log_vars() {
+        let mut config = Ini::new();
+        config.set("weekly_log", "enabled", Some("false".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_weekly_log_vars("weekly_log");
+        assert_eq!(global_vars.get_save_settings().weekly_log, None);
+
+        let mut config = Ini::new();
+        config.set("weekly_log", "enabled", Some("true".to_string()));
+        config.set("weekly_log", "note", Some("Weekly/%G-W%V.md".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_weekly_log_vars("weekly_log");
+        assert_eq!(
+            global_vars.get_save_settings().weekly_log,
+            Some(WeeklyLog {
+                note_template: "Weekly/%G-W%V.md".to_string(),
+                row_template: DEFAULT_WEEKLY_ROW_TEMPLATE.to_string(),
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "row_template is not a valid Handlebars template")]
+    fn test_set_weekly_log_vars_rejects_invalid_templates() {
+        let mut config = Ini::new();
+        config.set("weekly_log", "enabled", Some("true".to_string()));
+        config.set("weekly_log", "row_template", Some("{{#each}}".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
 
-    if Path::new(&config_path).exists() {
-        info!("[UserInput::retrieve_config_file_path()]: config_path exists {config_path:}");
-    } else {
-        error!(
-            "[UserInput::retrieve_config_file_path()]: config_path DOES NOT exists {config_path:}"
+        global_vars.set_weekly_log_vars("weekly_log");
+    }
+
+    #[test]
+    fn test_set_size_vars() {
+        let mut config = Ini::new();
+        config.set("size", "column", Some("true".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_size_vars("size");
+        let settings = global_vars.get_save_settings();
+        assert_eq!(settings.size_thresholds, None);
+        assert!(!settings.table_columns.contains(&TableColumn::Size));
+
+        let mut config = Ini::new();
+        config.set("size", "enabled", Some("true".to_string()));
+        config.set("size", "column", Some("true".to_string()));
+        config.set("size", "xs", Some("5".to_string()));
+        config.set("size", "l", Some("2000".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_size_vars("size");
+        let settings = global_vars.get_save_settings();
+        assert_eq!(
+            settings.size_thresholds,
+            Some(SizeThresholds {
+                xs: 5,
+                s: 50,
+                m: 250,
+                l: 2000,
+            })
+        );
+        assert_eq!(settings.table_columns.last(), Some(&TableColumn::Size));
+    }
+
+    #[test]
+    #[should_panic(expected = "The size thresholds must grow from xs to l")]
+    fn test_set_size_vars_rejects_unordered_thresholds() {
+        let mut config = Ini::new();
+        config.set("size", "enabled", Some("true".to_string()));
+        config.set("size", "m", Some("20".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        global_vars.set_size_vars("size");
+    }
+
+    #[test]
+    fn test_set_release_tags_vars() {
+        let mut config = Ini::new();
+        config.set("release_tags", "max_distance", Some("3".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_release_tags_vars("release_tags");
+        let settings = global_vars.get_save_settings();
+        assert_eq!(settings.release_tag_distance, None);
+        assert!(!settings.table_columns.contains(&TableColumn::Tag));
+
+        let mut config = Ini::new();
+        config.set("release_tags", "enabled", Some("true".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_release_tags_vars("release_tags");
+        let settings = global_vars.get_save_settings();
+        assert_eq!(settings.release_tag_distance, Some(0));
+        assert_eq!(settings.table_columns.last(), Some(&TableColumn::Tag));
+    }
+
+    #[test]
+    fn test_set_wsl_vars() {
+        let mut config = Ini::new();
+        config.set("wsl", "folder_style", Some("Windows".to_string()));
+        config.set("wsl", "mount_root", Some("/win".to_string()));
+        config.set(
+            "templates",
+            "diary_template",
+            Some("/home/me/Daily".to_string()),
+        );
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        assert_eq!(
+            global_vars.get_save_settings().wsl_paths,
+            WslPaths::default()
+        );
+
+        global_vars.set_templates_diary_template("templates");
+        global_vars.set_wsl_vars();
+
+        let save_settings = global_vars.get_save_settings();
+        assert_eq!(
+            save_settings.wsl_paths,
+            WslPaths {
+                translate: false,
+                mount_root: "/win".to_string(),
+                folder_style: FolderStyle::Windows,
+            }
+        );
+        assert_eq!(
+            save_settings.diary_template,
+            Some(PathBuf::from("/home/me/Daily.md"))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "folder_style must be 'native' or 'windows'")]
+    fn test_set_wsl_vars_rejects_unknown_folder_styles() {
+        let mut config = Ini::new();
+        config.set("wsl", "folder_style", Some("posix".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        global_vars.set_wsl_vars();
+    }
+
+    #[test]
+    fn test_set_related_repos_vars_disabled() {
+        let mut config = Ini::new();
+        config.set("related_repos", "enabled", Some("false".to_string()));
+        config.set(
+            "related_repos",
+            "note",
+            Some("Rollups/Related.md".to_string()),
+        );
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        global_vars.set_related_repos_vars("related_repos");
+
+        assert_eq!(global_vars.get_save_settings().related_repos_note, None);
+    }
+
+    #[test]
+    fn test_set_goals_vars_reads_the_weekly_goal() {
+        let mut config = Ini::new();
+        config.set("goals", "weekly_commits", Some("20".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        global_vars.set_goals_vars("goals");
+
+        assert_eq!(
+            global_vars.get_save_settings().weekly_goal,
+            Some(WeeklyGoal {
+                commits: 20,
+                note_template: DEFAULT_WEEKLY_NOTE_TEMPLATE.to_string(),
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "weekly_commits must be positive")]
+    fn test_set_goals_vars_rejects_a_zero_goal() {
+        let mut config = Ini::new();
+        config.set("goals", "weekly_commits", Some("0".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        global_vars.set_goals_vars("goals");
+    }
+
+    #[test]
+    fn test_set_templates_diary_template_resolves_against_the_vault() {
+        let mut config = Ini::new();
+        config.set(
+            "templates",
+            "diary_template",
+            Some("Templates/Daily Note".to_string()),
+        );
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars
+            .obsidian_root_path_dir
+            .set(PathBuf::from("/vault"))
+            .unwrap();
+        assert_eq!(global_vars.get_save_settings().diary_template, None);
+
+        global_vars.set_templates_diary_template("templates");
+
+        assert_eq!(
+            global_vars.get_save_settings().diary_template,
+            Some(PathBuf::from("/vault/Templates/Daily Note.md"))
+        );
+    }
+
+    #[test]
+    fn test_set_templates_diary_file() {
+        let mut config = Ini::new();
+        config.set(
+            "templates",
+            "diary_file",
+            Some("Templates/diary.md.hbs".to_string()),
+        );
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars
+            .obsidian_root_path_dir
+            .set(PathBuf::from("/vault"))
+            .unwrap();
+
+        global_vars.set_templates_diary_file("templates");
+
+        let save_settings = global_vars.get_save_settings();
+        assert_eq!(
+            save_settings.diary_file_template,
+            Some(PathBuf::from("/vault/Templates/diary.md.hbs"))
+        );
+        assert_eq!(save_settings.diary_template, None);
+    }
+
+    #[test]
+    fn test_set_templates_row_template() {
+        let mut config = Ini::new();
+        config.set(
+            "templates",
+            "row_template",
+            Some("> [!note] {{time}}\\n> {{message}}".to_string()),
+        );
+        config.set("templates", "entry_format", Some("yaml".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        global_vars.set_templates_entry_format("templates");
+        global_vars.set_templates_row_template("templates");
+
+        let save_settings = global_vars.get_save_settings();
+        assert_eq!(save_settings.entry_format, EntryFormat::Template);
+        assert_eq!(
+            save_settings.row_template.as_deref(),
+            Some("> [!note] {{time}}\n> {{message}}")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "row_template is not a valid Handlebars template")]
+    fn test_set_templates_row_template_rejects_invalid_templates() {
+        let mut config = Ini::new();
+        config.set("templates", "row_template", Some("{{#each}}".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        global_vars.set_templates_row_template("templates");
+    }
+
+    #[test]
+    fn test_set_templates_flat_layout() {
+        let mut config = Ini::new();
+        config.set("templates", "flat_layout", Some("yes".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        assert!(!global_vars.get_save_settings().flat_layout);
+
+        global_vars.set_templates_flat_layout("templates");
+
+        assert!(global_vars.get_save_settings().flat_layout);
+    }
+
+    #[test]
+    fn test_set_templates_entry_format() {
+        let mut config = Ini::new();
+        config.set("templates", "entry_format", Some("YAML".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        assert_eq!(
+            global_vars.get_save_settings().entry_format,
+            EntryFormat::Table
+        );
+
+        global_vars.set_templates_entry_format("templates");
+
+        assert_eq!(
+            global_vars.get_save_settings().entry_format,
+            EntryFormat::Yaml
+        );
+    }
+
+    #[test]
+    fn test_set_templates_timezone() {
+        let mut config = Ini::new();
+        config.set(
+            "templates",
+            "timezone",
+            Some("Australia/Sydney".to_string()),
+        );
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        assert_eq!(global_vars.get_save_settings().timezone, None);
+
+        global_vars.set_templates_timezone("templates");
+
+        assert_eq!(
+            global_vars.get_save_settings().timezone,
+            Some(DiaryTimezone::Named(chrono_tz::Australia::Sydney))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "timezone must be 'local', 'UTC' or an IANA timezone")]
+    fn test_set_templates_timezone_rejects_unknown_zones() {
+        let mut config = Ini::new();
+        config.set("templates", "timezone", Some("Sydney".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        global_vars.set_templates_timezone("templates");
+    }
+
+    #[test]
+    fn test_set_templates_entry_id() {
+        let mut config = Ini::new();
+        config.set("templates", "entry_id", Some("short_hash".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        assert_eq!(
+            global_vars.get_save_settings().entry_id_style,
+            EntryIdStyle::Base32
+        );
+
+        global_vars.set_templates_entry_id("templates");
+
+        assert_eq!(
+            global_vars.get_save_settings().entry_id_style,
+            EntryIdStyle::ShortHash
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "entry_format must be 'table', 'yaml' or 'dataview'")]
+    fn test_set_templates_entry_format_panics_on_unknown_format() {
+        let mut config = Ini::new();
+        config.set("templates", "entry_format", Some("json".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        global_vars.set_templates_entry_format("templates");
+    }
+
+    #[test]
+    fn test_set_obsidian_vault_name() {
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(Ini::new()).unwrap();
+        global_vars.set_obsidian_vault_name("obsidian");
+        assert_eq!(global_vars.get_save_settings().vault_name, None);
+
+        let mut config = Ini::new();
+        config.set("obsidian", "vault_name", Some(" Work Notes ".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_obsidian_vault_name("obsidian");
+        assert_eq!(
+            global_vars.get_save_settings().vault_name.as_deref(),
+            Some("Work Notes")
         );
-        panic!(
-            "[UserInput::retrieve_config_file_path()]: config_path DOES NOT exists {config_path:}"
+    }
+
+    #[test]
+    fn test_set_obsidian_insert_under() {
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(Ini::new()).unwrap();
+        global_vars.set_obsidian_insert_under("obsidian");
+        assert_eq!(global_vars.get_save_settings().insert_under, None);
+
+        let mut config = Ini::new();
+        config.set("obsidian", "insert_under", Some(" Git log ".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_obsidian_insert_under("obsidian");
+        assert_eq!(
+            global_vars.get_save_settings().insert_under.as_deref(),
+            Some("## Git log")
         );
     }
-    info!("[UserInput::retrieve_config_file_path()] retrieved config path: {config_path:}");
-    fs::read_to_string(config_path.clone())
-        .unwrap_or_else(|_| panic!("Should have been able to read the file: {config_path:}"))
-}
 
-/// Returns the config path from CLI arguments or the default path.
-///
-/// Internal helper function that parses CLI arguments using `UserInput` and
-/// returns either the provided `--config-ini` path or the default configuration
-/// file location.
-///
-/// # Returns
-///
-/// - CLI path if `--config-ini` was provided
-/// - Default path (`~/.config/rusty-commit-saver/rusty-commit-saver.ini`) otherwise
-///
-/// # Called By
-///
-/// This function is called internally by [`retrieve_config_file_path()`].
-///
-/// # See Also
-///
-/// - [`get_default_ini_path()`] - Constructs the default configuration path
-#[must_use]
-pub fn resolve_config_path(cli_arg: Option<String>, env_var: Option<String>) -> String {
-    // Check env var first
-    if let Some(env_path) = env_var {
-        info!("[resolve_config_path]: Using config from env var.");
-        return if env_path.contains('~') {
-            set_proper_home_dir(&env_path)
-        } else {
-            env_path
-        };
+    #[test]
+    #[should_panic(expected = "insert_under must be a heading title")]
+    fn test_set_obsidian_insert_under_rejects_comment_only_values() {
+        let global_vars = GlobalVars::new();
+        global_vars
+            .config
+            .set(parse_ini_content("[obsidian]\ninsert_under = ## Commits\n").unwrap())
+            .unwrap();
+        global_vars.set_obsidian_insert_under("obsidian");
     }
 
-    // Check CLI arg
-    if let Some(cfg_str) = cli_arg {
-        if cfg_str.contains('~') {
-            info!("[resolve_config_path]: CLI path contains '~'.");
-            set_proper_home_dir(&cfg_str)
-        } else {
-            info!("[resolve_config_path]: CLI path without '~'.");
-            cfg_str
-        }
-    } else {
-        info!("[resolve_config_path]: Using default path.");
-        get_default_ini_path()
+    #[test]
+    fn test_set_obsidian_normalize_remote() {
+        let mut config = Ini::new();
+        config.set("obsidian", "normalize_remote", Some("true".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        assert!(!global_vars.get_save_settings().normalize_remote);
+        global_vars.set_obsidian_normalize_remote("obsidian");
+
+        assert!(global_vars.get_save_settings().normalize_remote);
     }
-}
 
-#[must_use]
-#[cfg_attr(coverage_nightly, coverage(off))]
-pub fn get_or_default_config_ini_path() -> String {
-    get_or_default_config_ini_path_with(std::env::var("RUSTY_COMMIT_SAVER_CONFIG").ok(), || {
-        UserInput::parse().config_ini
-    })
-}
+    #[test]
+    #[should_panic(expected = "Could not parse flat_layout from INI as a boolean")]
+    fn test_set_templates_flat_layout_panics_on_invalid_value() {
+        let mut config = Ini::new();
+        config.set("templates", "flat_layout", Some("sometimes".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
 
-#[must_use]
-fn get_or_default_config_ini_path_with<F>(env_var: Option<String>, cli_parser: F) -> String
-where
-    F: FnOnce() -> Option<String>,
-{
-    info!("[get_or_default_config_ini_path()]: Parsing CLI inputs.");
+        global_vars.set_templates_flat_layout("templates");
+    }
 
-    let cli_arg = if env_var.is_some() {
-        None // Skip parsing if env var is set
-    } else {
-        cli_parser()
-    };
+    #[test]
+    fn test_set_routes_parses_the_routing_table() {
+        let mut config = Ini::new();
+        config.set(
+            "routing",
+            "work",
+            Some("github.com/acme/* -> Work/Commits".to_string()),
+        );
+        config.set(
+            "routing",
+            "personal",
+            Some("github.com/me/* -> Personal/Commits".to_string()),
+        );
 
-    let config_path = resolve_config_path(cli_arg, env_var);
-    info!("[get_or_default_config_ini_path()]: Config path found: {config_path:}");
-    config_path
-}
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_routes("routing");
 
-/// Constructs the default configuration file path.
-///
-/// Builds the standard XDG configuration path for the application by combining
-/// the user's home directory with the application-specific config directory.
-///
-/// # Returns
-///
-/// A `String` with the default INI file path:
-/// `~/.config/rusty-commit-saver/rusty-commit-saver.ini`
-///
-/// # Directory Structure
-///
-/// ```text
-/// ~/.config/
-///   └── rusty-commit-saver/
-///       └── rusty-commit-saver.ini
-/// ```
-///
-/// # Panics
-///
-/// Panics if the user's home directory cannot be determined
-/// (via the `dirs::home_dir()` function).
-///
-/// # Examples
-///
-/// ```ignore
-/// // Internal usage
-/// let default_path = get_default_ini_path();
-/// // Returns: "/home/user/.config/rusty-commit-saver/rusty-commit-saver.ini"
-/// ```
-///
-/// # See Also
-///
-/// - [`retrieve_config_file_path()`] - Public API for getting config path
-#[must_use]
-pub fn get_default_ini_path() -> String {
-    info!("[get_default_ini_path()]: Getting default ini file.");
-    let cfg_str = "~/.config/rusty-commit-saver/rusty-commit-saver.ini".to_string();
-    set_proper_home_dir(&cfg_str)
-}
+        let routes = global_vars.get_save_settings().routes;
+        assert_eq!(
+            routes
+                .iter()
+                .map(|route| route.commit_path.display().to_string())
+                .collect::<Vec<_>>(),
+            vec!["Personal/Commits", "Work/Commits"]
+        );
+    }
 
-/// Loads and parses the INI configuration file from disk.
-///
-/// Reads the configuration file (from CLI argument or default location),
-/// parses its contents using [`parse_ini_content()`], and returns the
-/// parsed `Ini` object.
-///
-/// # Returns
-///
-/// A parsed `Ini` configuration object
-///
-/// # Panics
-///
-/// Panics if:
-/// - The configuration file doesn't exist at the resolved path
-/// - The file cannot be read (permission denied, I/O error)
-/// - The file content is not valid UTF-8
-/// - The INI syntax is invalid (malformed sections or key-value pairs)
-///
-/// # File Resolution Order
-///
-/// 1. Check for `--config-ini <PATH>` CLI argument
-/// 2. Fall back to `~/.config/rusty-commit-saver/rusty-commit-saver.ini`
-///
-/// # Expected INI Structure
-///
-/// ```text
-/// [obsidian]
-/// root_path_dir = ~/Documents/Obsidian
-/// commit_path = Diaries/Commits
-///
-/// [templates]
-/// commit_date_path = %Y/%m-%B/%F.md
-/// commit_datetime = %Y-%m-%d %H:%M:%S
-/// ```
-///
-/// # Called By
-///
-/// This function is called internally by [`GlobalVars::set_all()`].
-///
-/// # See Also
-///
-/// - [`retrieve_config_file_path()`] - Resolves the config file path
-/// - [`parse_ini_content()`] - Parses INI text into `Ini` struct
-#[must_use]
-pub fn get_ini_file() -> Ini {
-    info!("[get_ini_file()]: Retrieving the INI File");
-    let content_ini = retrieve_config_file_path();
-    let mut config = Ini::new();
-    config
-        .read(content_ini)
-        .expect("Could not read the INI file!");
+    #[test]
+    #[should_panic(expected = "must be 'pattern -> commit_path'")]
+    fn test_set_routes_rejects_values_without_separator() {
+        let mut config = Ini::new();
+        config.set("routing", "work", Some("Work/Commits".to_string()));
 
-    info!("[get_ini_file()]: This is the INI File:\n\n{config:?}");
-    config
-}
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_routes("routing");
+    }
 
-/// Expands the tilde (`~`) character to the user's home directory path.
-///
-/// Replaces the leading `~` in a path string with the absolute path to the
-/// user's home directory. If no `~` is present, returns the string unchanged.
-///
-/// # Arguments
-///
-/// * `cfg_str` - A path string that may contain a leading `~`
-///
-/// # Returns
-///
-/// A `String` with `~` expanded to the full home directory path
-///
-/// # Panics
-///
-/// Panics if the user's home directory cannot be determined
-/// (via the `dirs::home_dir()` function).
-///
-/// # Examples
-///
-/// ```ignore
-/// // On Linux/macOS with home at /home/user
-/// let expanded = set_proper_home_dir("~/Documents/Obsidian");
-/// assert_eq!(expanded, "/home/user/Documents/Obsidian");
-///
-/// // Path without tilde is returned unchanged
-/// let unchanged = set_proper_home_dir("/absolute/path");
-/// assert_eq!(unchanged, "/absolute/path");
-/// ```
-///
-/// # Platform Behavior
-///
-/// - **Linux/macOS**: Expands to `/home/username` or `/Users/username`
-/// - **Windows**: Expands to `C:\Users\username`
-///
-/// # Used By
-///
-/// This function is called by:
-/// - [`GlobalVars::set_obsidian_root_path_dir()`]
-/// - [`GlobalVars::set_obsidian_commit_path()`]
-fn set_proper_home_dir(cfg_str: &str) -> String {
-    info!("[set_proper_home_dir()]: Changing the '~' to full home directory.");
-    let home_dir = home_dir()
-        .expect("Could not get home_dir")
-        .into_os_string()
-        .into_string()
-        .expect("Could not convert home_dir from OsString to String");
+    #[test]
+    fn test_set_vaults_groups_keys_by_vault() {
+        let mut config = Ini::new();
+        for (key, value) in [
+            ("work.root", "/vaults/work"),
+            ("work.remote", r"github\.com[:/]acme/"),
+            ("work.commit_path", "Engineering/Commits"),
+            ("oss.root", "/vaults/personal"),
+            ("oss.path", "/home/me/src/oss"),
+            ("oss.branch", "release/*"),
+        ] {
+            config.set("vaults", key, Some(value.to_string()));
+        }
 
-    cfg_str.replace('~', &home_dir)
-}
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_vaults("vaults");
 
-#[cfg(test)]
-#[cfg_attr(coverage_nightly, coverage(off))]
-mod global_vars_tests {
-    use super::*;
-    use std::panic::{self, AssertUnwindSafe};
+        let vaults = global_vars.get_save_settings().vaults;
+        assert_eq!(
+            vaults,
+            vec![
+                VaultTarget {
+                    path_prefix: Some(PathBuf::from("/home/me/src/oss")),
+                    branch: Some("release/*".to_string()),
+                    ..VaultTarget::new("oss", PathBuf::from("/vaults/personal"))
+                },
+                VaultTarget {
+                    commit_path: Some(PathBuf::from("Engineering/Commits")),
+                    remote: Some(RemotePattern::new(r"github\.com[:/]acme/").unwrap()),
+                    ..VaultTarget::new("work", PathBuf::from("/vaults/work"))
+                },
+            ]
+        );
+    }
 
     #[test]
-    fn test_global_vars_new() {
+    fn test_set_filters_vars_splits_ignored_branches() {
+        let mut config = Ini::new();
+        config.set(
+            "filters",
+            "ignore_branches",
+            Some(" wip/*, tmp/* ,,dependabot/*".to_string()),
+        );
+
         let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_filters_vars("filters");
 
-        assert!(global_vars.config.get().is_none());
+        let save_settings = global_vars.get_save_settings();
+        assert_eq!(
+            save_settings.ignore_branches,
+            vec!["wip/*", "tmp/*", "dependabot/*"]
+        );
+        assert!(save_settings.ignore_message_patterns.is_empty());
+        assert!(!save_settings.ignore_bots);
     }
 
     #[test]
-    fn test_global_vars_default() {
-        let global_vars = GlobalVars::default();
+    fn test_set_filters_vars_parses_message_patterns_and_bots() {
+        let mut config = Ini::new();
+        config.set(
+            "filters",
+            "ignore_message_patterns",
+            Some("^fixup!, ^squash!,^Merge branch".to_string()),
+        );
+        config.set("filters", "ignore_bots", Some("yes".to_string()));
 
-        assert!(global_vars.config.get().is_none());
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_filters_vars("filters");
+
+        let save_settings = global_vars.get_save_settings();
+        assert_eq!(
+            save_settings
+                .ignore_message_patterns
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["^fixup!", "^squash!", "^Merge branch"]
+        );
+        assert!(save_settings.ignore_bots);
     }
 
     #[test]
-    fn test_get_sections_from_config_valid() {
+    #[should_panic(expected = "ignore_message_patterns has an invalid regex")]
+    fn test_set_filters_vars_rejects_invalid_message_patterns() {
         let mut config = Ini::new();
-        config.set("obsidian", "root_path_dir", Some("/tmp/test".to_string()));
         config.set(
-            "templates",
-            "commit_date_path",
-            Some("%Y-%m-%d".to_string()),
+            "filters",
+            "ignore_message_patterns",
+            Some("^fixup!,(".to_string()),
         );
 
         let global_vars = GlobalVars::new();
         global_vars.config.set(config).unwrap();
+        global_vars.set_filters_vars("filters");
+    }
 
-        let sections = global_vars.get_sections_from_config();
+    #[test]
+    #[should_panic(expected = "must have a remote, path or branch rule")]
+    fn test_set_vaults_rejects_vaults_without_rules() {
+        let mut config = Ini::new();
+        config.set("vaults", "work.root", Some("/vaults/work".to_string()));
 
-        assert_eq!(sections.len(), 2);
-        assert!(sections.contains(&"obsidian".to_string()));
-        assert!(sections.contains(&"templates".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_vaults("vaults");
     }
 
     #[test]
-    fn test_get_sections_from_config_invalid_count() {
+    #[should_panic(expected = "Invalid remote of vault work")]
+    fn test_set_vaults_rejects_invalid_remotes() {
         let mut config = Ini::new();
-        config.set("only_one_section", "key", Some("value".to_string()));
+        config.set("vaults", "work.root", Some("/vaults/work".to_string()));
+        config.set("vaults", "work.remote", Some("acme/(".to_string()));
 
         let global_vars = GlobalVars::new();
         global_vars.config.set(config).unwrap();
+        global_vars.set_vaults("vaults");
+    }
 
-        let result =
-            panic::catch_unwind(AssertUnwindSafe(|| global_vars.get_sections_from_config()));
+    #[test]
+    fn test_set_forge_resolver_presets_and_overrides() {
+        let mut config = Ini::new();
+        config.set("forges", "git.example.com", Some("Gitea".to_string()));
+        config.set(
+            "forges",
+            "git.example.com.commit",
+            Some("https://{host}/{path}/rev/{hash}".to_string()),
+        );
+        config.set(
+            "forges",
+            "code.example.net.pr",
+            Some("https://{host}/{path}/reviews/{pr}".to_string()),
+        );
 
-        assert!(result.is_err(), "Expected panic for invalid section count");
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_forge_resolver("forges");
 
-        // Verify the panic message (panic! with string literal = &str)
-        let panic_info = result.unwrap_err();
-        let msg = panic_info
-            .downcast_ref::<&str>()
-            .expect("Panic message should be &str");
-        assert!(
-            msg.contains("wrong number of sections"),
-            "Unexpected panic message: {msg}"
+        let forges = global_vars.get_save_settings().forges;
+        assert_eq!(
+            forges.commit_url("git@git.example.com:team/repo.git", "abc"),
+            Some("https://git.example.com/team/repo/rev/abc".to_string())
+        );
+        assert_eq!(
+            forges.branch_url("git@git.example.com:team/repo.git", "main"),
+            Some("https://git.example.com/team/repo/src/branch/main".to_string())
+        );
+        assert_eq!(
+            forges.pull_request_url("https://code.example.net/team/repo", 3),
+            Some("https://code.example.net/team/repo/reviews/3".to_string())
+        );
+        assert_eq!(
+            forges.commit_url("https://github.com/org/repo", "abc"),
+            Some("https://github.com/org/repo/commit/abc".to_string())
         );
     }
 
     #[test]
-    fn test_get_sections_from_config_panics_with_zero_sections() {
-        let config = Ini::new();
+    #[should_panic(expected = "Unknown forge preset")]
+    fn test_set_forge_resolver_rejects_unknown_presets() {
+        let mut config = Ini::new();
+        config.set("forges", "git.example.com", Some("sourcehut".to_string()));
 
         let global_vars = GlobalVars::new();
         global_vars.config.set(config).unwrap();
+        global_vars.set_forge_resolver("forges");
+    }
 
-        let result =
-            panic::catch_unwind(AssertUnwindSafe(|| global_vars.get_sections_from_config()));
+    #[test]
+    #[should_panic(expected = "max_minutes must be positive")]
+    fn test_set_duration_vars_rejects_non_positive_cap() {
+        let mut config = Ini::new();
+        config.set("duration", "max_minutes", Some("0".to_string()));
 
-        assert!(result.is_err(), "Expected panic for zero sections");
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_duration_vars("duration");
     }
 
     #[test]
-    fn test_get_sections_from_config_panics_with_three_sections() {
+    fn test_get_save_settings_with_events_adds_event_column() {
         let mut config = Ini::new();
-        config.set("obsidian", "root_path_dir", Some("/tmp/test".to_string()));
-        config.set("templates", "commit_date_path", Some("%Y.md".to_string()));
-        config.set("extra", "key", Some("value".to_string()));
+        config.set("events", "branch_switches", Some("yes".to_string()));
 
         let global_vars = GlobalVars::new();
         global_vars.config.set(config).unwrap();
+        global_vars.set_events_vars("events");
 
-        let result =
-            panic::catch_unwind(AssertUnwindSafe(|| global_vars.get_sections_from_config()));
+        let settings = global_vars.get_save_settings();
+
+        assert!(settings.log_branch_switches);
+        assert!(!settings.log_stash);
+        assert_eq!(settings.table_columns.last(), Some(&TableColumn::Event));
+        assert_eq!(
+            settings.table_columns.len(),
+            DEFAULT_TABLE_COLUMNS.len() + 1
+        );
+    }
+
+    #[test]
+    fn test_get_save_settings_with_push_events() {
+        let mut config = Ini::new();
+        config.set("events", "push", Some("true".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_events_vars("events");
+
+        let settings = global_vars.get_save_settings();
+
+        assert!(settings.log_pushes);
+        assert!(!settings.log_branch_switches);
+        assert_eq!(settings.table_columns.last(), Some(&TableColumn::Event));
+    }
 
-        assert!(result.is_err(), "Expected panic for three sections");
+    #[test]
+    #[should_panic(expected = "Could not parse stash from INI as a boolean")]
+    fn test_set_events_vars_invalid_boolean() {
+        let mut config = Ini::new();
+        config.set("events", "stash", Some("sometimes".to_string()));
+
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+        global_vars.set_events_vars("events");
     }
 
     #[test]
@@ -1411,6 +7299,26 @@ mod global_vars_tests {
         assert!(result.to_string_lossy().ends_with("Commits"));
     }
 
+    #[test]
+    fn test_set_obsidian_commit_path_prefixes_the_emoji() {
+        let mut config = Ini::new();
+        config.set(
+            "obsidian",
+            "commit_path",
+            Some("Diaries/0. Commits".to_string()),
+        );
+        config.set("obsidian", "path_prefix_emoji", Some("📅".to_string()));
+        let global_vars = GlobalVars::new();
+        global_vars.config.set(config).unwrap();
+
+        global_vars.set_obsidian_commit_path("obsidian");
+
+        assert_eq!(
+            global_vars.get_obsidian_commit_path(),
+            PathBuf::from("📅 Diaries/0. Commits")
+        );
+    }
+
     #[test]
     fn test_set_obsidian_commit_path_absolute_path() {
         let mut config = Ini::new();
@@ -1460,11 +7368,7 @@ mod global_vars_tests {
     #[test]
     fn test_set_templates_datetime() {
         let mut config = Ini::new();
-        config.set(
-            "templates",
-            "commit_datetime",
-            Some("%Y-%m-%d %H:%M:%S".to_string()),
-        );
+        config.set("templates", "commit_datetime", Some("%H:%M:%S".to_string()));
 
         let global_vars = GlobalVars::new();
         global_vars.config.set(config).unwrap();
@@ -1472,7 +7376,7 @@ mod global_vars_tests {
 
         let result = global_vars.get_template_commit_datetime();
 
-        assert_eq!(result, "%Y-%m-%d %H:%M:%S");
+        assert_eq!(result, "%H:%M:%S");
     }
 
     #[test]
@@ -1493,11 +7397,7 @@ mod global_vars_tests {
             "commit_date_path",
             Some("%Y-%m-%d.md".to_string()),
         );
-        config.set(
-            "templates",
-            "commit_datetime",
-            Some("%Y-%m-%d %H:%M:%S".to_string()),
-        );
+        config.set("templates", "commit_datetime", Some("%H:%M:%S".to_string()));
 
         let global_vars = GlobalVars::new();
         global_vars.config.set(config).unwrap();
@@ -1514,15 +7414,19 @@ mod global_vars_tests {
         assert!(root_path.to_string_lossy().contains("Obsidian"));
         assert!(commit_path.to_string_lossy().contains("Commits"));
         assert_eq!(date_path, "%Y-%m-%d.md");
-        assert_eq!(datetime, "%Y-%m-%d %H:%M:%S");
+        assert_eq!(datetime, "%H:%M:%S");
+        assert_eq!(
+            global_vars.get_save_settings().time_format.as_deref(),
+            Some("%H:%M:%S")
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Trying to set other sections is not supported")]
-    fn test_set_obsidian_vars_invalid_section() {
+    fn test_set_obsidian_vars_skips_unknown_sections() {
         let mut config = Ini::new();
-        // Add correct number of sections (2) but with wrong name
-        config.set("invalid_section", "key", Some("value".to_string()));
+        config.set("hooks", "pre_save", Some("echo".to_string()));
+        config.set("obsidian", "root_path_dir", Some("/tmp/test".to_string()));
+        config.set("obsidian", "commit_path", Some("Commits".to_string()));
         config.set(
             "templates",
             "commit_date_path",
@@ -1533,12 +7437,15 @@ mod global_vars_tests {
             "commit_datetime",
             Some("%Y-%m-%d %H:%M".to_string()),
         );
+        config.set("my notes", "todo", Some("try the filters".to_string()));
 
         let global_vars = GlobalVars::new();
         global_vars.config.set(config).unwrap();
 
-        // Should panic because "invalid_section" is not "obsidian" or "templates"
+        // Unknown sections only log a warning.
         global_vars.set_obsidian_vars();
+
+        assert_eq!(global_vars.get_template_commit_date_path(), "%Y-%m-%d.md");
     }
 
     #[test]
@@ -1553,7 +7460,7 @@ mod global_vars_tests {
         writeln!(temp_file, "commit_path=TestDiaries/TestCommits").unwrap();
         writeln!(temp_file, "[templates]").unwrap();
         writeln!(temp_file, "commit_date_path=%Y-%m-%d.md").unwrap();
-        writeln!(temp_file, "commit_datetime=%Y-%m-%d %H:%M:%S").unwrap();
+        writeln!(temp_file, "commit_datetime=%H:%M:%S").unwrap();
         temp_file.flush().unwrap();
 
         // Parse the config manually and test set_all
@@ -1573,7 +7480,7 @@ mod global_vars_tests {
         assert!(root.to_string_lossy().contains("test_obsidian"));
         assert!(commit.to_string_lossy().contains("TestCommits"));
         assert_eq!(date, "%Y-%m-%d.md");
-        assert_eq!(datetime, "%Y-%m-%d %H:%M:%S");
+        assert_eq!(datetime, "%H:%M:%S");
     }
 
     #[test]
@@ -1637,7 +7544,7 @@ mod global_vars_tests {
         writeln!(temp_file, "commit_path=TestDiaries/TestCommits").unwrap();
         writeln!(temp_file, "[templates]").unwrap();
         writeln!(temp_file, "commit_date_path=%Y/%m-%B/%F.md").unwrap();
-        writeln!(temp_file, "commit_datetime=%Y-%m-%d %H:%M:%S").unwrap();
+        writeln!(temp_file, "commit_datetime=%H:%M:%S").unwrap();
         temp_file.flush().unwrap();
 
         // Read and parse the config
@@ -1662,7 +7569,7 @@ mod global_vars_tests {
         assert!(root.to_string_lossy().contains("obsidian_test"));
         assert!(commit.to_string_lossy().contains("TestCommits"));
         assert_eq!(date_path, "%Y/%m-%B/%F.md");
-        assert_eq!(datetime, "%Y-%m-%d %H:%M:%S");
+        assert_eq!(datetime, "%H:%M:%S");
     }
 
     #[test]
@@ -1812,7 +7719,7 @@ mod global_vars_tests {
         writeln!(temp_file, "commit_path=FullTest/Commits").unwrap();
         writeln!(temp_file, "[templates]").unwrap();
         writeln!(temp_file, "commit_date_path=%Y/%m/%d.md").unwrap();
-        writeln!(temp_file, "commit_datetime=%Y-%m-%d %H:%M:%S").unwrap();
+        writeln!(temp_file, "commit_datetime=%H:%M:%S").unwrap();
         temp_file.flush().unwrap();
 
         // Parse config manually
@@ -1833,7 +7740,7 @@ mod global_vars_tests {
         assert!(root.to_string_lossy().contains("obsidian_full_test"));
         assert!(commit.to_string_lossy().contains("FullTest"));
         assert_eq!(date, "%Y/%m/%d.md");
-        assert_eq!(datetime, "%Y-%m-%d %H:%M:%S");
+        assert_eq!(datetime, "%H:%M:%S");
     }
 
     #[test]
@@ -1854,11 +7761,7 @@ mod global_vars_tests {
             "commit_date_path",
             Some("%Y/%m/%d.md".to_string()),
         );
-        config.set(
-            "templates",
-            "commit_datetime",
-            Some("%Y-%m-%d %H:%M:%S".to_string()),
-        );
+        config.set("templates", "commit_datetime", Some("%H:%M:%S".to_string()));
 
         let global_vars = GlobalVars::new();
         global_vars.config.set(config).unwrap();
@@ -1914,6 +7817,277 @@ mod user_input_tests {
         );
     }
 
+    #[test]
+    fn test_user_input_parse_without_subcommand() {
+        let user_input = UserInput::try_parse_from(["test_program"]).unwrap();
+
+        assert_eq!(user_input.command, None);
+    }
+
+    #[test]
+    fn test_user_input_parse_save_wip() {
+        let args = ["test_program", "save", "--wip", "--config-ini", "/c.ini"];
+        let user_input = UserInput::try_parse_from(args).unwrap();
+
+        assert_eq!(
+            user_input.command,
+            Some(Command::Save(SaveArgs {
+                wip: true,
+                ..SaveArgs::default()
+            }))
+        );
+        assert_eq!(user_input.config_ini, Some("/c.ini".to_string()));
+    }
+
+    #[test]
+    fn test_user_input_parse_save_defaults() {
+        let user_input = UserInput::try_parse_from(["test_program", "save"]).unwrap();
+
+        assert_eq!(user_input.command, Some(Command::Save(SaveArgs::default())));
+    }
+
+    #[test]
+    fn test_user_input_parse_event_checkout_hook_args() {
+        let user_input = UserInput::try_parse_from([
+            "test_program",
+            "event",
+            "checkout",
+            "1111111",
+            "2222222",
+            "1",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            user_input.command,
+            Some(Command::Event(EventArgs {
+                event: EventCommand::Checkout {
+                    previous_head: Some("1111111".to_string()),
+                    new_head: Some("2222222".to_string()),
+                    checkout_type: Some("1".to_string()),
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn test_user_input_parse_event_push_hook_args() {
+        let user_input = UserInput::try_parse_from([
+            "test_program",
+            "event",
+            "push",
+            "origin",
+            "git@github.com:user/repo.git",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            user_input.command,
+            Some(Command::Event(EventArgs {
+                event: EventCommand::Push {
+                    remote: Some("origin".to_string()),
+                    url: Some("git@github.com:user/repo.git".to_string()),
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn test_user_input_parse_event_stash() {
+        let user_input =
+            UserInput::try_parse_from(["test_program", "event", "stash", "pop"]).unwrap();
+
+        assert_eq!(
+            user_input.command,
+            Some(Command::Event(EventArgs {
+                event: EventCommand::Stash {
+                    action: StashAction::Pop,
+                },
+            }))
+        );
+        assert!(UserInput::try_parse_from(["test_program", "event", "stash", "drop"]).is_err());
+    }
+
+    #[test]
+    fn test_user_input_parse_save_stdin() {
+        let user_input = UserInput::try_parse_from(["test_program", "save", "--stdin"]).unwrap();
+
+        assert_eq!(
+            user_input.command,
+            Some(Command::Save(SaveArgs {
+                stdin: true,
+                ..SaveArgs::default()
+            }))
+        );
+        assert!(UserInput::try_parse_from(["test_program", "save", "--stdin", "--wip"]).is_err());
+    }
+
+    #[test]
+    fn test_user_input_parse_save_porcelain() {
+        let user_input =
+            UserInput::try_parse_from(["test_program", "save", "--stdin", "--porcelain"]).unwrap();
+
+        assert_eq!(
+            user_input.command,
+            Some(Command::Save(SaveArgs {
+                stdin: true,
+                porcelain: true,
+                ..SaveArgs::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn test_user_input_parse_save_open() {
+        let user_input = UserInput::try_parse_from(["test_program", "save", "--open"]).unwrap();
+
+        assert_eq!(
+            user_input.command,
+            Some(Command::Save(SaveArgs {
+                open: true,
+                ..SaveArgs::default()
+            }))
+        );
+        assert!(
+            UserInput::try_parse_from(["test_program", "save", "--open", "--dry-run"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_user_input_parse_log_recent() {
+        let user_input = UserInput::try_parse_from(["test_program", "log-recent"]).unwrap();
+        assert_eq!(
+            user_input.command,
+            Some(Command::LogRecent(LogRecentArgs {
+                dir: None,
+                days: DEFAULT_LOOKBACK_DAYS,
+                porcelain: false,
+                repo: None,
+            }))
+        );
+
+        let user_input = UserInput::try_parse_from([
+            "test_program",
+            "log-recent",
+            "--dir",
+            "/home/me/src",
+            "--days",
+            "30",
+            "--repo",
+            "api",
+        ])
+        .unwrap();
+        assert_eq!(
+            user_input.command,
+            Some(Command::LogRecent(LogRecentArgs {
+                dir: Some(PathBuf::from("/home/me/src")),
+                days: 30,
+                porcelain: false,
+                repo: Some("api".to_string()),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_user_input_parse_verify_fix() {
+        let user_input = UserInput::try_parse_from(["test_program", "verify", "--fix"]).unwrap();
+
+        assert_eq!(
+            user_input.command,
+            Some(Command::Verify(VerifyArgs { fix: true }))
+        );
+    }
+
+    #[test]
+    fn test_user_input_parse_enrich() {
+        let user_input = UserInput::try_parse_from(["test_program", "enrich"]).unwrap();
+
+        assert_eq!(user_input.command, Some(Command::Enrich));
+    }
+
+    #[test]
+    fn test_user_input_parse_daemon() {
+        let user_input = UserInput::try_parse_from(["test_program", "daemon"]).unwrap();
+
+        assert_eq!(user_input.command, Some(Command::Daemon));
+    }
+
+    #[test]
+    fn test_user_input_parse_import_github_user() {
+        let user_input =
+            UserInput::try_parse_from(["test_program", "import", "--github-user", "octocat"])
+                .unwrap();
+
+        assert_eq!(
+            user_input.command,
+            Some(Command::Import(ImportArgs {
+                github_user: "octocat".to_string(),
+                days: DEFAULT_IMPORT_DAYS,
+            }))
+        );
+        assert!(UserInput::try_parse_from(["test_program", "import"]).is_err());
+    }
+
+    #[test]
+    fn test_user_input_parse_install_and_uninstall_hook() {
+        let user_input = UserInput::try_parse_from([
+            "test_program",
+            "install-hook",
+            "--path",
+            "/home/me/src/api",
+        ])
+        .unwrap();
+        assert_eq!(
+            user_input.command,
+            Some(Command::InstallHook(HookArgs {
+                path: Some(PathBuf::from("/home/me/src/api")),
+            }))
+        );
+
+        let user_input = UserInput::try_parse_from(["test_program", "uninstall-hook"]).unwrap();
+        assert_eq!(
+            user_input.command,
+            Some(Command::UninstallHook(HookArgs::default()))
+        );
+    }
+
+    #[test]
+    fn test_user_input_parse_completions() {
+        let user_input = UserInput::try_parse_from(["test_program", "completions", "zsh"]).unwrap();
+        assert_eq!(
+            user_input.command,
+            Some(Command::Completions(CompletionsArgs {
+                shell: "zsh".to_string(),
+            }))
+        );
+
+        assert!(UserInput::try_parse_from(["test_program", "completions", "cmd"]).is_err());
+    }
+
+    #[test]
+    fn test_user_input_parse_report_vault() {
+        let user_input = UserInput::try_parse_from(["test_program", "report", "--vault"]).unwrap();
+
+        assert_eq!(
+            user_input.command,
+            Some(Command::Report(ReportArgs { vault: true }))
+        );
+    }
+
+    #[test]
+    fn test_user_input_parse_today_date() {
+        let user_input =
+            UserInput::try_parse_from(["test_program", "today", "--date", "2025-01-14"]).unwrap();
+
+        assert_eq!(
+            user_input.command,
+            Some(Command::Today(TodayArgs {
+                date: NaiveDate::from_ymd_opt(2025, 1, 14)
+            }))
+        );
+        assert!(UserInput::try_parse_from(["test_program", "today", "--date", "14/01"]).is_err());
+    }
+
     #[test]
     fn test_set_proper_home_dir_with_tilde() {
         let input = "~/test/path/file.ini";
@@ -2024,6 +8198,54 @@ commit_datetime=%Y-%m-%d
         assert!(result.is_ok() || result.is_err());
     }
 
+    #[test]
+    fn test_apply_env_overrides_replaces_ini_values() {
+        let mut config = parse_ini_content(
+            "[obsidian]\nroot_path_dir=~/Documents/Obsidian\ncommit_path=Diaries/Commits\n",
+        )
+        .unwrap();
+        let environment = [
+            ("RCS_OBSIDIAN_ROOT", "/tmp/ci-vault"),
+            ("RCS_COMMIT_PATH", " "),
+            ("RCS_TABLE_COLUMNS", "time, hash"),
+        ];
+
+        let applied = apply_env_overrides(&mut config, |variable| {
+            environment
+                .iter()
+                .find(|(name, _)| *name == variable)
+                .map(|(_, value)| (*value).to_string())
+        });
+
+        assert_eq!(applied, vec!["RCS_OBSIDIAN_ROOT", "RCS_TABLE_COLUMNS"]);
+        assert_eq!(
+            config.get("obsidian", "root_path_dir"),
+            Some("/tmp/ci-vault".to_string())
+        );
+        assert_eq!(
+            config.get("obsidian", "commit_path"),
+            Some("Diaries/Commits".to_string())
+        );
+        assert_eq!(
+            config.get("table", "columns"),
+            Some("time, hash".to_string())
+        );
+
+        let global_vars = GlobalVars::new();
+        config.set("templates", "commit_date_path", Some("%F.md".to_string()));
+        config.set("templates", "commit_datetime", Some("%F".to_string()));
+        global_vars.config.set(config).unwrap();
+        global_vars.set_obsidian_vars();
+        assert_eq!(
+            global_vars.get_obsidian_root_path_dir(),
+            PathBuf::from("/tmp/ci-vault")
+        );
+        assert_eq!(
+            global_vars.get_save_settings().table_columns,
+            vec![TableColumn::Time, TableColumn::CommitHash]
+        );
+    }
+
     #[test]
     fn test_parse_ini_content_empty() {
         let content = "";
@@ -2076,7 +8298,7 @@ commit_path=Diaries/Commits
 
 [templates]
 commit_date_path=%Y/%m-%B/%F.md
-commit_datetime=%Y-%m-%d %H:%M:%S
+commit_datetime=%H:%M:%S
 ";
 
         let ini = parse_ini_content(content).unwrap();
@@ -2132,7 +8354,7 @@ commit_path = Commits
 
 [templates]
 commit_date_path = %Y/%m/%d.md
-commit_datetime = %Y-%m-%d %H:%M:%S
+commit_datetime = %H:%M:%S
 ";
         fs::write(temp_file.path(), config_content).expect("Failed to write temp config");
 
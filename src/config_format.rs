@@ -0,0 +1,244 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+use std::path::PathBuf;
+
+use configparser::ini::Ini;
+use log::info;
+use serde::de;
+use serde::Deserialize;
+use serde::Deserializer;
+
+/// Extensions of the configuration file, in the order the default location
+/// is searched.
+pub const CONFIG_EXTENSIONS: [&str; 4] = ["ini", "toml", "yaml", "yml"];
+
+/// The syntax of a configuration file, detected from its extension.
+///
+/// All formats share the INI schema: one table (or mapping) per section,
+/// holding the same keys with string, number or boolean values.
+///
+/// ```text
+/// # rusty-commit-saver.toml          # rusty-commit-saver.yaml
+/// [obsidian]                         obsidian:
+/// root_path_dir = "~/Obsidian"         root_path_dir: ~/Obsidian
+/// commit_path = "Diaries/Commits"      commit_path: Diaries/Commits
+///
+/// [table]                            table:
+/// block_refs = true                    block_refs: true
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Ini,
+    Toml,
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// Detects the format from the extension of `path` (`.toml`, `.yaml` or
+    /// `.yml`, any case); any other file is read as INI.
+    #[must_use]
+    pub fn from_path(path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase());
+        match extension.as_deref() {
+            Some("toml") => ConfigFormat::Toml,
+            Some("yaml" | "yml") => ConfigFormat::Yaml,
+            _ => ConfigFormat::Ini,
+        }
+    }
+}
+
+impl fmt::Display for ConfigFormat {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            ConfigFormat::Ini => "INI",
+            ConfigFormat::Toml => "TOML",
+            ConfigFormat::Yaml => "YAML",
+        })
+    }
+}
+
+/// A key's value in a TOML or YAML section, as the INI text it stands for.
+///
+/// `None` for an empty YAML value (`key:`), which counts as a missing key.
+struct ConfigValue(Option<String>);
+
+impl<'de> Deserialize<'de> for ConfigValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ConfigValueVisitor)
+    }
+}
+
+struct ConfigValueVisitor;
+
+impl de::Visitor<'_> for ConfigValueVisitor {
+    type Value = ConfigValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a string, number or boolean value")
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<ConfigValue, E> {
+        Ok(ConfigValue(Some(value.to_string())))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<ConfigValue, E> {
+        Ok(ConfigValue(Some(value.to_string())))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<ConfigValue, E> {
+        Ok(ConfigValue(Some(value.to_string())))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<ConfigValue, E> {
+        Ok(ConfigValue(Some(value.to_string())))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<ConfigValue, E> {
+        Ok(ConfigValue(Some(value.to_string())))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<ConfigValue, E> {
+        Ok(ConfigValue(None))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<ConfigValue, E> {
+        Ok(ConfigValue(None))
+    }
+}
+
+/// Sections of a TOML or YAML file, each a table of keys.
+type Sections = BTreeMap<String, BTreeMap<String, ConfigValue>>;
+
+/// Parses the content of a configuration file written in `format`.
+///
+/// TOML and YAML files are parsed strictly against the shared schema: a
+/// syntax error, a key outside any section, or a list or nested table as a
+/// value is an error naming the line, where INI would silently skip the
+/// line. The sections are then loaded into the same [`Ini`] the INI files
+/// give, so the rest of the configuration reads them the same way.
+///
+/// # Errors
+///
+/// Returns an error if the content is not valid for `format`.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::config_format::{parse_config, ConfigFormat};
+///
+/// let config = parse_config("[table]\nblock_refs = true\n", ConfigFormat::Toml)?;
+/// assert_eq!(config.get("table", "block_refs"), Some("true".to_string()));
+/// ```
+pub fn parse_config(content: &str, format: ConfigFormat) -> Result<Ini, Box<dyn Error>> {
+    info!("[parse_config()]: Parsing a {format:} configuration.");
+    let sections: Sections = match format {
+        ConfigFormat::Ini => {
+            let mut config = Ini::new();
+            config.read(content.to_string())?;
+            return Ok(config);
+        }
+        ConfigFormat::Toml => toml::from_str(content)?,
+        ConfigFormat::Yaml if content.trim().is_empty() => Sections::new(),
+        ConfigFormat::Yaml => serde_yaml_ng::from_str(content)?,
+    };
+
+    let mut config = Ini::new();
+    for (section, keys) in sections {
+        for (key, ConfigValue(value)) in keys {
+            config.set(&section, &key, value);
+        }
+    }
+    Ok(config)
+}
+
+/// Returns the configuration file next to `ini_path` with the first
+/// extension of [`CONFIG_EXTENSIONS`] that exists, or `ini_path` when none does.
+///
+/// The default location is searched this way, so a
+/// `rusty-commit-saver.toml` or `.yaml` is picked up without `--config-ini`.
+#[must_use]
+pub fn existing_config_path(ini_path: &Path) -> PathBuf {
+    CONFIG_EXTENSIONS
+        .iter()
+        .map(|extension| ini_path.with_extension(extension))
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| ini_path.to_path_buf())
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod config_format_tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_config_reads_the_same_schema_in_every_format() {
+        let ini = "[obsidian]\nroot_path_dir = ~/Obsidian\n\n[table]\nblock_refs = true\nmax_row_length = 120\n";
+        let toml = "[obsidian]\nroot_path_dir = \"~/Obsidian\"\n\n[table]\nblock_refs = true\nmax_row_length = 120\n";
+        let yaml = "obsidian:\n  root_path_dir: ~/Obsidian\ntable:\n  block_refs: true\n  max_row_length: 120\n  time_zone:\n";
+
+        for (content, format) in [
+            (ini, ConfigFormat::Ini),
+            (toml, ConfigFormat::Toml),
+            (yaml, ConfigFormat::Yaml),
+        ] {
+            let config = parse_config(content, format).unwrap();
+            assert_eq!(
+                config.get("obsidian", "root_path_dir"),
+                Some("~/Obsidian".to_string()),
+                "{format:}"
+            );
+            assert_eq!(config.getboolcoerce("table", "block_refs"), Ok(Some(true)));
+            assert_eq!(config.getuint("table", "max_row_length"), Ok(Some(120)));
+            assert_eq!(config.get("table", "time_zone"), None);
+        }
+    }
+
+    #[test]
+    fn test_parse_config_reports_errors_with_their_line() {
+        let typo = parse_config(
+            "[obsidian]\nroot_path_dir = ~/Obsidian\n",
+            ConfigFormat::Toml,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(typo.contains("line 2"), "{typo:}");
+
+        let list = parse_config("table:\n  columns: [time, hash]\n", ConfigFormat::Yaml)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            list.contains("expected a string, number or boolean value"),
+            "{list:}"
+        );
+        assert!(list.contains("line 2"), "{list:}");
+
+        let outside = parse_config("block_refs = true\n", ConfigFormat::Toml).unwrap_err();
+        assert!(outside.to_string().contains("line 1"), "{outside:}");
+    }
+
+    #[test]
+    fn test_existing_config_path_finds_other_extensions() {
+        let config_dir = tempdir().unwrap();
+        let ini_path = config_dir.path().join("rusty-commit-saver.ini");
+        assert_eq!(existing_config_path(&ini_path), ini_path);
+
+        let yaml_path = config_dir.path().join("rusty-commit-saver.yaml");
+        fs::write(&yaml_path, "obsidian:\n").unwrap();
+        assert_eq!(existing_config_path(&ini_path), yaml_path);
+        assert_eq!(ConfigFormat::from_path(&yaml_path), ConfigFormat::Yaml);
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("config.TOML")),
+            ConfigFormat::Toml
+        );
+        assert_eq!(ConfigFormat::from_path(&ini_path), ConfigFormat::Ini);
+
+        fs::write(&ini_path, "[obsidian]\n").unwrap();
+        assert_eq!(existing_config_path(&ini_path), ini_path);
+    }
+}
@@ -0,0 +1,134 @@
+/// Footer tokens marking a breaking change in the message body.
+const BREAKING_FOOTERS: [&str; 2] = ["BREAKING CHANGE:", "BREAKING-CHANGE:"];
+
+/// The parts of a [Conventional Commits](https://www.conventionalcommits.org)
+/// message, e.g. `feat(api)!: drop the v1 endpoints`.
+///
+/// # Fields
+///
+/// - `kind` - The type, lowercased (`feat`, `fix`, ...)
+/// - `scope` - The scope between parentheses, if any
+/// - `breaking` - Whether the header has a `!` or the body a
+///   `BREAKING CHANGE:` footer
+/// - `subject` - The description after the `: `
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConventionalCommit {
+    pub kind: String,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub subject: String,
+}
+
+/// Parses a commit message, as stored in
+/// [`CommitSaver`](crate::vim_commit::CommitSaver) (lines joined with
+/// `<br/>`, pipes escaped), as a Conventional Commit.
+///
+/// # Returns
+///
+/// `None` if the subject line is not `type(scope)!: description`, with a
+/// type made of letters and an optional scope and `!`.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::conventional::parse_conventional_commit;
+///
+/// let commit = parse_conventional_commit("feat(api)!: drop v1").unwrap();
+/// assert_eq!(commit.kind, "feat");
+/// assert_eq!(commit.scope.as_deref(), Some("api"));
+/// assert!(commit.breaking);
+/// assert!(parse_conventional_commit("Update README").is_none());
+/// ```
+#[must_use]
+pub fn parse_conventional_commit(commit_msg: &str) -> Option<ConventionalCommit> {
+    let mut lines = commit_msg.split("<br/>");
+    let (header, subject) = lines.next()?.split_once(": ")?;
+    let (header, bang) = header
+        .strip_suffix('!')
+        .map_or((header, false), |header| (header, true));
+    let (kind, scope) = match header.split_once('(') {
+        Some((kind, scope)) => (kind, Some(scope.strip_suffix(')')?)),
+        None => (header, None),
+    };
+    let valid_scope = scope.is_none_or(|scope| !scope.is_empty() && !scope.contains(['(', ')']));
+    let subject = subject.trim();
+    if kind.is_empty()
+        || !kind.chars().all(char::is_alphabetic)
+        || !valid_scope
+        || subject.is_empty()
+    {
+        return None;
+    }
+
+    let breaking = bang
+        || lines.any(|line| {
+            BREAKING_FOOTERS
+                .iter()
+                .any(|footer| line.starts_with(footer))
+        });
+    Some(ConventionalCommit {
+        kind: kind.to_lowercase(),
+        scope: scope.map(str::to_string),
+        breaking,
+        subject: subject.to_string(),
+    })
+}
+
+/// Formats the frontmatter tag of a commit type, e.g. `#commit/feat`.
+#[must_use]
+pub fn commit_type_tag(kind: &str) -> String {
+    format!("#commit/{kind:}")
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod conventional_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_conventional_commit() {
+        assert_eq!(
+            parse_conventional_commit("Feat(api/v2)!: drop the v1 endpoints"),
+            Some(ConventionalCommit {
+                kind: "feat".to_string(),
+                scope: Some("api/v2".to_string()),
+                breaking: true,
+                subject: "drop the v1 endpoints".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_conventional_commit("fix: off-by-one<br/><br/>BREAKING CHANGE: pages start at 0"),
+            Some(ConventionalCommit {
+                kind: "fix".to_string(),
+                scope: None,
+                breaking: true,
+                subject: "off-by-one".to_string(),
+            })
+        );
+        assert!(
+            !parse_conventional_commit("docs: mention BREAKING CHANGE: in the guide")
+                .unwrap()
+                .breaking
+        );
+    }
+
+    #[test]
+    fn test_parse_conventional_commit_rejects_other_messages() {
+        for commit_msg in [
+            "Update README",
+            "Merge branch 'main': conflicts",
+            "feat(): empty scope",
+            "feat(api: unclosed scope",
+            "feat:",
+            "feat: ",
+            "wip 2: more",
+        ] {
+            assert_eq!(parse_conventional_commit(commit_msg), None, "{commit_msg:}");
+        }
+    }
+
+    #[test]
+    fn test_commit_type_tag() {
+        assert_eq!(commit_type_tag("feat"), "#commit/feat");
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::path::Path;
+use std::path::PathBuf;
+
+use git2::BranchType;
+use git2::Oid;
+use git2::Repository;
+use git2::Sort;
+use log::info;
+
+/// A repository watched by the `daemon` subcommand.
+///
+/// Remembers the tips of the local branches it last saw, so that a change
+/// under `.git/refs` can be turned into the list of commits that landed.
+#[derive(Debug, Clone)]
+pub struct WatchedRepository {
+    /// Working directory of the repository, as configured in `[repositories]`.
+    pub path: PathBuf,
+    /// The `refs` directory to watch; the common one for worktrees.
+    pub refs_dir: PathBuf,
+    tips: HashSet<Oid>,
+}
+
+impl WatchedRepository {
+    /// Starts watching a repository from its current branch tips.
+    ///
+    /// Commits made before the daemon started are not logged; `log-recent`
+    /// catches up on those.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the branches of the repository cannot be listed.
+    pub fn new(git_repo: &Repository, path: &Path) -> Result<Self, Box<dyn Error>> {
+        let tips = branch_tips(git_repo)?;
+        info!(
+            "[WatchedRepository::new()]: Watching {:} with {:} branch tip(s).",
+            path.display(),
+            tips.len()
+        );
+        Ok(WatchedRepository {
+            path: path.to_path_buf(),
+            refs_dir: git_repo.commondir().join("refs"),
+            tips,
+        })
+    }
+
+    /// Returns `true` if a changed file belongs to this repository's refs.
+    #[must_use]
+    pub fn watches(&self, changed_path: &Path) -> bool {
+        changed_path.starts_with(&self.refs_dir)
+    }
+
+    /// Lists the commits that landed since the branch tips were last seen,
+    /// and remembers the new tips.
+    ///
+    /// Every commit reachable from a moved or new branch tip, but not from a
+    /// previously seen one, is new: a branch created from an existing commit
+    /// adds nothing, a commit or a merge adds its commits. Commits by other
+    /// authors (e.g. brought in by `git pull`) are left out when `user.email`
+    /// is set, since the post-commit hook would only have logged the user's
+    /// own commits.
+    ///
+    /// # Returns
+    ///
+    /// The hashes of the new commits, newest first (the `git rev-list` order
+    /// [`collect_commit_batch()`](crate::batch::collect_commit_batch) expects).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the branches or the history cannot be read.
+    pub fn new_commits(&mut self, git_repo: &Repository) -> Result<Vec<String>, Box<dyn Error>> {
+        let tips = branch_tips(git_repo)?;
+        let moved = tips.difference(&self.tips).copied().collect::<Vec<_>>();
+        if moved.is_empty() {
+            self.tips = tips;
+            return Ok(Vec::new());
+        }
+
+        let mut revwalk = git_repo.revwalk()?;
+        revwalk.set_sorting(Sort::TIME)?;
+        for oid in moved {
+            revwalk.push(oid)?;
+        }
+        for oid in &self.tips {
+            // A tip dropped by a rebase may have been garbage collected since.
+            if git_repo.find_commit(*oid).is_ok() {
+                revwalk.hide(*oid)?;
+            }
+        }
+
+        let author_email = git_repo
+            .config()
+            .and_then(|config| config.get_string("user.email"))
+            .ok();
+        let mut commits = Vec::new();
+        for oid in revwalk {
+            let commit = git_repo.find_commit(oid?)?;
+            let by_user = author_email
+                .as_deref()
+                .is_none_or(|email| commit.author().email().ok() == Some(email));
+            if by_user {
+                commits.push(commit.id().to_string());
+            }
+        }
+
+        info!(
+            "[WatchedRepository::new_commits()]: {:} new commit(s) in {:}",
+            commits.len(),
+            self.path.display()
+        );
+        self.tips = tips;
+        Ok(commits)
+    }
+}
+
+/// Returns the commits the local branches of a repository point to.
+///
+/// # Errors
+///
+/// Returns an error if the branches cannot be listed.
+pub fn branch_tips(git_repo: &Repository) -> Result<HashSet<Oid>, Box<dyn Error>> {
+    let mut tips = HashSet::new();
+    for branch in git_repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = branch?;
+        if let Some(oid) = branch.get().target() {
+            tips.insert(oid);
+        }
+    }
+    Ok(tips)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod daemon_tests {
+    use super::*;
+    use crate::testing::FakeRepo;
+    use chrono::TimeZone;
+    use chrono::Utc;
+
+    fn commit_by(repo: &FakeRepo, email: &str, seconds: i64) -> Result<String, Box<dyn Error>> {
+        let datetime = Utc.timestamp_opt(seconds, 0).unwrap();
+        Ok(repo.commit_by("work", email, datetime)?.to_string())
+    }
+
+    #[test]
+    fn test_new_commits_lists_commits_since_the_last_seen_tips() -> Result<(), Box<dyn Error>> {
+        let repo = FakeRepo::new()?;
+        repo.repo()
+            .config()?
+            .set_str("user.email", "me@example.com")?;
+        let base = 1_736_848_800;
+        commit_by(&repo, "me@example.com", base)?;
+
+        let mut watched = WatchedRepository::new(repo.repo(), repo.path())?;
+        assert!(watched.new_commits(repo.repo())?.is_empty());
+
+        let first = commit_by(&repo, "me@example.com", base + 60)?;
+        commit_by(&repo, "someone@example.com", base + 120)?;
+        let third = commit_by(&repo, "me@example.com", base + 180)?;
+
+        assert_eq!(watched.new_commits(repo.repo())?, vec![third, first]);
+        assert!(watched.new_commits(repo.repo())?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_commits_ignores_new_branches_at_existing_commits() -> Result<(), Box<dyn Error>> {
+        let repo = FakeRepo::new()?;
+        repo.commit("work")?;
+        let mut watched = WatchedRepository::new(repo.repo(), repo.path())?;
+
+        repo.checkout_new_branch("feature")?;
+
+        assert!(watched.new_commits(repo.repo())?.is_empty());
+        assert!(watched.watches(&repo.repo().path().join("refs/heads/feature")));
+        assert!(!watched.watches(&repo.path().join("src/main.rs")));
+        Ok(())
+    }
+}
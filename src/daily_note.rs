@@ -0,0 +1,250 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::DateTime;
+use chrono::FixedOffset;
+use log::info;
+
+use crate::atomic_write::write_atomically;
+use crate::hydration::ensure_hydrated;
+use crate::hydration::HYDRATION_ATTEMPTS;
+use crate::hydration::HYDRATION_RETRY_DELAY;
+use crate::links::wikilink;
+
+/// Default daily note path: the Obsidian Daily Notes default, a
+/// `YYYY-MM-DD.md` note at the vault root.
+pub const DEFAULT_DAILY_NOTE_TEMPLATE: &str = "%F.md";
+
+/// How the diary file is linked from the daily note (`[daily_note] link`).
+///
+/// # Variants
+///
+/// - `Embed` - `![[📅 Diaries/0. Commits/2025-01-14]]`, showing the commit
+///   table inside the daily note
+/// - `Wikilink` - `[[📅 Diaries/0. Commits/2025-01-14]]`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DailyNoteLinkStyle {
+    #[default]
+    Embed,
+    Wikilink,
+}
+
+impl DailyNoteLinkStyle {
+    /// Parses a `[daily_note] link` value: `embed` or `wikilink`, in any case.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "embed" => Some(DailyNoteLinkStyle::Embed),
+            "wikilink" => Some(DailyNoteLinkStyle::Wikilink),
+            _ => None,
+        }
+    }
+}
+
+/// A link to the day's diary file added to the user's own daily note, so
+/// commits show up in their journal.
+///
+/// Each diary file is linked once: later commits of the day find the link
+/// and leave the note alone.
+///
+/// # Configuration
+///
+/// ```text
+/// [daily_note]
+/// enabled = true
+/// note = Journal/%F.md
+/// link = embed
+/// create = false
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyNote {
+    /// Chrono format of the daily note path, relative to the vault root.
+    pub note_template: String,
+
+    /// How the diary file is linked.
+    pub style: DailyNoteLinkStyle,
+
+    /// Whether a missing daily note is created. When off, the link waits for
+    /// the note, e.g. created from its template by Obsidian, and is added by
+    /// the next commit of the day.
+    pub create: bool,
+}
+
+impl DailyNote {
+    /// Returns the daily note of the day `datetime` falls in.
+    #[must_use]
+    pub fn note_path(
+        &self,
+        obsidian_root_path_dir: &Path,
+        datetime: DateTime<FixedOffset>,
+    ) -> PathBuf {
+        obsidian_root_path_dir.join(datetime.format(&self.note_template).to_string())
+    }
+
+    /// Builds the link to a diary file, in the [`style`](Self::style) of the
+    /// daily note.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the diary file is not inside the vault.
+    #[must_use]
+    pub fn diary_link(&self, obsidian_root_path_dir: &Path, diary_path: &Path) -> Option<String> {
+        let link = wikilink(obsidian_root_path_dir, diary_path, None)?;
+        Some(match self.style {
+            DailyNoteLinkStyle::Embed => format!("!{link:}"),
+            DailyNoteLinkStyle::Wikilink => link,
+        })
+    }
+
+    /// Adds the link to a diary file at the end of the daily note of
+    /// `datetime`, unless the note already links to it (embedded or not,
+    /// with an alias or a heading).
+    ///
+    /// # Returns
+    ///
+    /// `true` if the link was added; `false` if the note already has it, is
+    /// the diary file itself, or is missing and [`create`](Self::create) is
+    /// off.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the diary file is not inside the vault, if the
+    /// note is a cloud placeholder that cannot be hydrated, or if it cannot
+    /// be read or written.
+    pub fn link_diary(
+        &self,
+        obsidian_root_path_dir: &Path,
+        diary_path: &Path,
+        datetime: DateTime<FixedOffset>,
+    ) -> Result<bool, Box<dyn Error>> {
+        let note_path = self.note_path(obsidian_root_path_dir, datetime);
+        if note_path == diary_path {
+            return Ok(false);
+        }
+        let link = self
+            .diary_link(obsidian_root_path_dir, diary_path)
+            .ok_or("the diary file is not inside the vault")?;
+
+        ensure_hydrated(&note_path, HYDRATION_ATTEMPTS, HYDRATION_RETRY_DELAY)?;
+        let content = if note_path.exists() {
+            fs::read_to_string(&note_path)?
+        } else if self.create {
+            String::new()
+        } else {
+            info!(
+                "[DailyNote::link_diary()]: No daily note yet at: {:}",
+                note_path.display()
+            );
+            return Ok(false);
+        };
+        if links_to(&content, &link) {
+            return Ok(false);
+        }
+
+        let separator = if content.is_empty() || content.ends_with('\n') {
+            ""
+        } else {
+            "\n"
+        };
+        if let Some(parent) = note_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        info!(
+            "[DailyNote::link_diary()]: Adding {link:} to: {:}",
+            note_path.display()
+        );
+        write_atomically(&note_path, format!("{content:}{separator:}{link:}\n"))?;
+        Ok(true)
+    }
+}
+
+/// Whether a note holds a link to the target of `link` (`[[target]]`,
+/// `[[target|alias]]` or `[[target#heading]]`, embedded or not).
+fn links_to(content: &str, link: &str) -> bool {
+    let target = link.trim_start_matches('!').trim_end_matches("]]");
+    ["]]", "|", "#"]
+        .iter()
+        .any(|end| content.contains(&format!("{target:}{end:}")))
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod daily_note_tests {
+    use super::*;
+    use crate::testing::TempVault;
+    use chrono::TimeZone;
+
+    fn daily_note(create: bool) -> DailyNote {
+        DailyNote {
+            note_template: "Journal/%F.md".to_string(),
+            style: DailyNoteLinkStyle::Embed,
+            create,
+        }
+    }
+
+    fn tuesday() -> DateTime<FixedOffset> {
+        FixedOffset::east_opt(3600)
+            .unwrap()
+            .with_ymd_and_hms(2025, 1, 14, 10, 30, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_link_diary_adds_the_link_once() -> Result<(), Box<dyn Error>> {
+        let vault = TempVault::new()?;
+        vault.write_note("Journal/2025-01-14.md", "# Tuesday\n\nGym.")?;
+        let diary_path = vault.root().join("📅 Diaries/0. Commits/2025-01-14.md");
+
+        assert!(daily_note(false).link_diary(vault.root(), &diary_path, tuesday())?);
+        assert!(!daily_note(false).link_diary(vault.root(), &diary_path, tuesday())?);
+
+        assert_eq!(
+            vault.read_note("Journal/2025-01-14.md")?,
+            "# Tuesday\n\nGym.\n![[📅 Diaries/0. Commits/2025-01-14]]\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_link_diary_creates_missing_notes_only_when_asked() -> Result<(), Box<dyn Error>> {
+        let vault = TempVault::new()?;
+        let diary_path = vault.root().join("Commits/2025-01-14.md");
+
+        assert!(!daily_note(false).link_diary(vault.root(), &diary_path, tuesday())?);
+        assert!(!vault.root().join("Journal/2025-01-14.md").exists());
+
+        let wikilink = DailyNote {
+            style: DailyNoteLinkStyle::Wikilink,
+            ..daily_note(true)
+        };
+        assert!(wikilink.link_diary(vault.root(), &diary_path, tuesday())?);
+        assert_eq!(
+            vault.read_note("Journal/2025-01-14.md")?,
+            "[[Commits/2025-01-14]]\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_links_to() {
+        let link = "![[Commits/2025-01-14]]";
+        assert!(links_to("See [[Commits/2025-01-14|commits]].", link));
+        assert!(links_to("![[Commits/2025-01-14#Commits]]", link));
+        assert!(!links_to("[[Commits/2025-01-14 notes]]", link));
+    }
+
+    #[test]
+    fn test_daily_note_link_style_from_name() {
+        assert_eq!(
+            DailyNoteLinkStyle::from_name(" Embed "),
+            Some(DailyNoteLinkStyle::Embed)
+        );
+        assert_eq!(
+            DailyNoteLinkStyle::from_name("WIKILINK"),
+            Some(DailyNoteLinkStyle::Wikilink)
+        );
+        assert_eq!(DailyNoteLinkStyle::from_name("markdown"), None);
+    }
+}
@@ -0,0 +1,220 @@
+use std::env;
+use std::error::Error;
+use std::fmt::Write as _;
+use std::fs;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+use log::info;
+use log::warn;
+use sha2::Digest;
+use sha2::Sha256;
+
+/// Name of the lock file earlier versions created in the directory of each
+/// diary file and in the state directory.
+///
+/// Only removed from the state directory by the version 3 migration, see
+/// [`STATE_SCHEMA_VERSION`](crate::state_dir::STATE_SCHEMA_VERSION); the
+/// ones left in vaults are harmless and can be deleted.
+pub const LOCK_FILE_NAME: &str = ".rusty-commit-saver.lock";
+
+/// Name of the directory holding the lock files inside the state directory.
+pub const LOCKS_DIR_NAME: &str = "locks";
+
+/// Name of the directory holding the lock files in the temporary directory,
+/// for runs without a state directory.
+const FALLBACK_LOCKS_DIR_NAME: &str = "rusty-commit-saver-locks";
+
+/// How long a save waits for another one to release the lock.
+pub const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Delay between two attempts to take the lock.
+const LOCK_RETRY_DELAY: Duration = Duration::from_millis(50);
+
+/// An exclusive advisory lock on a note, released when dropped.
+///
+/// Post-commit hooks of several repositories can fire at the same time; each
+/// save holds this lock from the duplicate check to the appended row, so two
+/// processes never create the same diary file twice or interleave their
+/// rows. The lock files live in the `locks` directory of the state directory,
+/// one per note (see [`lock_path()`]), so nothing is added to the vault. The
+/// lock file is left in place: removing it while another process waits on it
+/// would let a third one lock a new file.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::diary_lock::{DiaryLock, LOCK_TIMEOUT};
+///
+/// let _lock = DiaryLock::acquire(state_dir.as_deref(), &diary_path, LOCK_TIMEOUT)?;
+/// // create the diary file if missing and append the row
+/// ```
+#[derive(Debug)]
+pub struct DiaryLock {
+    file: Option<File>,
+    path: PathBuf,
+}
+
+impl DiaryLock {
+    /// Takes the lock of `note_path`, creating its lock file (see
+    /// [`lock_path()`]) and the locks directory when missing.
+    ///
+    /// On file systems without advisory locks (some network shares), a
+    /// warning is logged and the save goes on unlocked.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lock file cannot be created, or if another
+    /// process still holds the lock after `timeout`.
+    pub fn acquire(
+        state_dir: Option<&Path>,
+        note_path: &Path,
+        timeout: Duration,
+    ) -> Result<Self, Box<dyn Error>> {
+        let path = lock_path(state_dir, note_path);
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(false)
+            .open(&path)?;
+
+        let started = Instant::now();
+        loop {
+            match file.try_lock() {
+                Ok(()) => {
+                    info!("[DiaryLock::acquire()]: Locked: {:}", path.display());
+                    return Ok(DiaryLock {
+                        file: Some(file),
+                        path,
+                    });
+                }
+                Err(fs::TryLockError::WouldBlock) if started.elapsed() < timeout => {
+                    thread::sleep(LOCK_RETRY_DELAY);
+                }
+                Err(fs::TryLockError::WouldBlock) => {
+                    return Err(format!(
+                        "Timed out after {:}s waiting for another save to release {:}",
+                        timeout.as_secs(),
+                        path.display()
+                    )
+                    .into());
+                }
+                Err(fs::TryLockError::Error(e)) if e.kind() == ErrorKind::Unsupported => {
+                    warn!(
+                        "[DiaryLock::acquire()]: File locks are not supported here, saving without one: {:}",
+                        path.display()
+                    );
+                    return Ok(DiaryLock { file: None, path });
+                }
+                Err(fs::TryLockError::Error(e)) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+/// Returns the lock file of `note_path`: a file of the `locks` directory of
+/// `state_dir`, named after the note and a hash of its absolute path.
+///
+/// Without a state directory, the locks go to the temporary directory, where
+/// every run of the same user still finds them.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::diary_lock::lock_path;
+///
+/// let path = lock_path(Some(Path::new("/state")), Path::new("/vault/2025-01-14.md"));
+/// // /state/locks/2025-01-14.md-<hash>.lock
+/// ```
+#[must_use]
+pub fn lock_path(state_dir: Option<&Path>, note_path: &Path) -> PathBuf {
+    let locks_dir = match state_dir {
+        Some(state_dir) => state_dir.join(LOCKS_DIR_NAME),
+        None => env::temp_dir().join(FALLBACK_LOCKS_DIR_NAME),
+    };
+    let note_path = std::path::absolute(note_path).unwrap_or_else(|_| note_path.to_path_buf());
+    let digest = Sha256::digest(note_path.as_os_str().as_encoded_bytes());
+    let mut file_name = note_path
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    file_name.push('-');
+    for byte in &digest[..8] {
+        let _ = write!(file_name, "{byte:02x}");
+    }
+    file_name.push_str(".lock");
+    locks_dir.join(file_name)
+}
+
+impl Drop for DiaryLock {
+    fn drop(&mut self) {
+        if let Some(file) = &self.file {
+            if let Err(e) = file.unlock() {
+                warn!(
+                    "[DiaryLock::drop()]: Could not release {:}: {e:}",
+                    self.path.display()
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod diary_lock_tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_acquire_waits_for_the_lock_to_be_released() {
+        let state_dir = tempdir().unwrap();
+        let vault = tempdir().unwrap();
+        let diary = vault.path().join("2025/01-January/2025-01-14.md");
+        let state = state_dir.path().to_path_buf();
+
+        let lock = DiaryLock::acquire(Some(&state), &diary, LOCK_TIMEOUT).unwrap();
+        assert!(lock_path(Some(&state), &diary).is_file());
+        assert!(!vault.path().join("2025/01-January").exists());
+        let error =
+            DiaryLock::acquire(Some(&state), &diary, Duration::from_millis(100)).unwrap_err();
+        assert!(error.to_string().starts_with("Timed out after 0s"));
+
+        let waiting =
+            thread::spawn(move || DiaryLock::acquire(Some(&state), &diary, LOCK_TIMEOUT).is_ok());
+        thread::sleep(Duration::from_millis(100));
+        drop(lock);
+        assert!(waiting.join().unwrap());
+    }
+
+    #[test]
+    fn test_lock_path_is_one_file_per_note_in_the_state_directory() {
+        let state_dir = Path::new("/state");
+        let first = lock_path(Some(state_dir), Path::new("/vault/2025-01-14.md"));
+        let other_vault = lock_path(Some(state_dir), Path::new("/other/2025-01-14.md"));
+
+        assert_eq!(first.parent(), Some(Path::new("/state/locks")));
+        assert!(first
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("2025-01-14.md-"));
+        assert_ne!(first, other_vault);
+        assert_eq!(
+            first,
+            lock_path(Some(state_dir), Path::new("/vault/2025-01-14.md"))
+        );
+        assert_eq!(
+            lock_path(None, Path::new("/vault/2025-01-14.md")).parent(),
+            Some(env::temp_dir().join(FALLBACK_LOCKS_DIR_NAME).as_path())
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use std::error::Error;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use log::info;
+use notify::event::ModifyKind;
+use notify::event::RenameMode;
+use notify::Event;
+use notify::EventKind;
+
+use crate::state::StateIndex;
+
+/// File name of the moved-diaries record inside the state directory.
+pub(crate) const MOVES_FILE_NAME: &str = "moved-diaries.tsv";
+
+/// Diary files the user renamed or moved after rows were logged to them.
+///
+/// Stored next to the logged-commits index as one `<old path>\t<new path>`
+/// line per move. A save whose diary path no longer exists looks it up with
+/// [`resolve()`](Self::resolve) and appends to the moved file, instead of
+/// recreating a duplicate diary at the old path.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::diary_moves::DiaryMoves;
+///
+/// let moves = DiaryMoves::new(&state_dir);
+/// if let Some(moved) = moves.resolve(&diary_path)? {
+///     println!("Appending to {}", moved.display());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DiaryMoves {
+    moves_path: PathBuf,
+}
+
+impl DiaryMoves {
+    /// Creates a handle on the moved-diaries record inside `state_dir`.
+    ///
+    /// Nothing is created on disk until the first [`record()`](Self::record).
+    #[must_use]
+    pub fn new(state_dir: &Path) -> Self {
+        DiaryMoves {
+            moves_path: state_dir.join(MOVES_FILE_NAME),
+        }
+    }
+
+    /// Reads every recorded move, oldest first. A missing file has none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the record exists but cannot be read.
+    pub fn moves(&self) -> Result<Vec<(PathBuf, PathBuf)>, Box<dyn Error>> {
+        if !self.moves_path.exists() {
+            return Ok(Vec::new());
+        }
+        Ok(fs::read_to_string(&self.moves_path)?
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .map(|(from, to)| (PathBuf::from(from), PathBuf::from(to)))
+            .collect())
+    }
+
+    /// Records that the diary at `from` now lives at `to`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state directory cannot be created or the
+    /// record cannot be written.
+    pub fn record(&self, from: &Path, to: &Path) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = self.moves_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        info!(
+            "[DiaryMoves::record()]: {:} moved to {:}",
+            from.display(),
+            to.display()
+        );
+        let mut file_ref = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.moves_path)?;
+        file_ref.write_all(format!("{:}\t{:}\n", from.display(), to.display()).as_bytes())?;
+
+        Ok(())
+    }
+
+    /// Returns where the diary of `diary_path` was moved to, following
+    /// successive moves.
+    ///
+    /// # Returns
+    ///
+    /// `None` when the diary was never moved, or when the file it was last
+    /// moved to is gone too.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the record cannot be read.
+    pub fn resolve(&self, diary_path: &Path) -> Result<Option<PathBuf>, Box<dyn Error>> {
+        let moves = self.moves()?;
+        let mut current = diary_path.to_path_buf();
+        // Each move is followed at most once, so a file moved back and forth
+        // cannot loop.
+        for _ in 0..moves.len() {
+            match moves.iter().rev().find(|(from, _)| *from == current) {
+                Some((_, to)) => current.clone_from(to),
+                None => break,
+            }
+        }
+        Ok((current != diary_path && current.is_file()).then_some(current))
+    }
+}
+
+/// Returns the old and new paths of a file renamed within the watched tree.
+///
+/// Only events carrying both paths count: a file moved out of the watched
+/// tree, or platforms reporting the two halves of a rename separately, give
+/// `None`.
+#[must_use]
+pub fn rename_event(event: &Event) -> Option<(&Path, &Path)> {
+    match (&event.kind, event.paths.as_slice()) {
+        (EventKind::Modify(ModifyKind::Name(RenameMode::Both)), [from, to]) => {
+            Some((from.as_path(), to.as_path()))
+        }
+        _ => None,
+    }
+}
+
+/// Follows a diary renamed or moved by the user from `from` to `to`.
+///
+/// The logged-commits index tells whether `from` was a diary: when rows were
+/// logged to it, its entries are pointed at `to` and the move is recorded in
+/// [`DiaryMoves`], so the next saves of the day append to the moved file.
+/// Any other rename (notes, the temporary files of atomic writes) is ignored.
+///
+/// # Returns
+///
+/// `true` if `from` was a diary and the move was recorded.
+///
+/// # Errors
+///
+/// Returns an error if the index or the moved-diaries record cannot be read
+/// or written.
+pub fn follow_diary_move(state_dir: &Path, from: &Path, to: &Path) -> Result<bool, Box<dyn Error>> {
+    let state_index = StateIndex::new(state_dir);
+    let mut entries = state_index.entries()?;
+    let from_path = from.display().to_string();
+    let moved = entries
+        .iter_mut()
+        .filter(|entry| entry.diary_path == from_path)
+        .map(|entry| entry.diary_path = to.display().to_string())
+        .count();
+    if moved == 0 {
+        return Ok(false);
+    }
+
+    info!(
+        "[follow_diary_move()]: Following {:} logged entries to: {:}",
+        moved,
+        to.display()
+    );
+    state_index.replace_entries(&entries)?;
+    DiaryMoves::new(state_dir).record(from, to)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod diary_moves_tests {
+    use super::*;
+    use crate::state::LoggedEntry;
+    use chrono::TimeZone;
+    use chrono::Utc;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_follow_diary_move_points_the_index_at_the_moved_diary() {
+        let state_dir = tempdir().unwrap();
+        let vault_dir = tempdir().unwrap();
+        let old_path = vault_dir.path().join("2025-01-14.md");
+        let new_path = vault_dir.path().join("Refiled/2025-01-14 sprint.md");
+        let last_path = vault_dir.path().join("Archive/2025-01-14.md");
+        StateIndex::new(state_dir.path())
+            .append(&LoggedEntry {
+                commit_datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+                repository_url: "https://github.com/user/repo.git".to_string(),
+                branch: "main".to_string(),
+                commit_hash: "abc123".to_string(),
+                diary_path: old_path.display().to_string(),
+            })
+            .unwrap();
+        fs::create_dir_all(last_path.parent().unwrap()).unwrap();
+        fs::write(&last_path, "rows").unwrap();
+
+        assert!(!follow_diary_move(
+            state_dir.path(),
+            &vault_dir.path().join("note.md"),
+            &old_path
+        )
+        .unwrap());
+        assert!(follow_diary_move(state_dir.path(), &old_path, &new_path).unwrap());
+        assert!(follow_diary_move(state_dir.path(), &new_path, &last_path).unwrap());
+
+        let moves = DiaryMoves::new(state_dir.path());
+        assert_eq!(moves.moves().unwrap().len(), 2);
+        assert_eq!(moves.resolve(&old_path).unwrap(), Some(last_path.clone()));
+        assert_eq!(moves.resolve(&last_path).unwrap(), None);
+        let entries = StateIndex::new(state_dir.path()).entries().unwrap();
+        assert_eq!(entries[0].diary_path, last_path.display().to_string());
+
+        fs::remove_file(&last_path).unwrap();
+        assert_eq!(moves.resolve(&old_path).unwrap(), None);
+    }
+
+    #[test]
+    fn test_rename_event_needs_both_paths() {
+        let both = Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::Both)))
+            .add_path(PathBuf::from("/vault/a.md"))
+            .add_path(PathBuf::from("/vault/b.md"));
+        let half = Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::From)))
+            .add_path(PathBuf::from("/vault/a.md"));
+
+        assert_eq!(
+            rename_event(&both),
+            Some((Path::new("/vault/a.md"), Path::new("/vault/b.md")))
+        );
+        assert_eq!(rename_event(&half), None);
+    }
+}
@@ -0,0 +1,305 @@
+use crate::vim_commit::TableColumn;
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use log::info;
+
+/// One data row of a diary commit table.
+///
+/// # Fields
+///
+/// - `line_index` - Zero-based line of the row inside the diary file
+/// - `cells` - Cell contents, trimmed, with `\|` escapes left untouched
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiaryRow {
+    pub line_index: usize,
+    pub cells: Vec<String>,
+}
+
+/// The commit table of a diary file, as written by
+/// [`create_diary_file()`](crate::vim_commit::create_diary_file) and
+/// [`CommitSaver::append_entry_to_diary()`](crate::vim_commit::CommitSaver::append_entry_to_diary).
+///
+/// Columns are looked up by their header title, so diaries written with a
+/// different [`TableColumn`] order (or an older column set) still parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiaryTable {
+    pub header_line_index: usize,
+    pub headers: Vec<String>,
+    pub rows: Vec<DiaryRow>,
+}
+
+impl DiaryTable {
+    /// Returns the position of `column` in this table, if the header has it.
+    #[must_use]
+    pub fn column_index(&self, column: TableColumn) -> Option<usize> {
+        self.headers
+            .iter()
+            .position(|header| header == column.header())
+    }
+
+    /// Returns the content of `column` in `row`, if both exist.
+    ///
+    /// A `COMMIT HASH` cell written as a forge link (`[<hash>](<url>)`) gives
+    /// the hash alone, so linked and plain tables read the same.
+    #[must_use]
+    pub fn cell<'a>(&self, row: &'a DiaryRow, column: TableColumn) -> Option<&'a str> {
+        let index = self.column_index(column)?;
+        let cell = row.cells.get(index).map(String::as_str)?;
+        Some(match column {
+            TableColumn::CommitHash => link_text(cell),
+            _ => cell,
+        })
+    }
+}
+
+/// Parses the commit table out of a diary file.
+///
+/// The table starts at the first line that begins with `|` and is directly
+/// followed by a `|---|` separator line. Every following line that begins
+/// with `|` is a data row; the table ends at the first line that does not.
+/// Frontmatter and any text around the table are ignored.
+///
+/// # Returns
+///
+/// `None` if the content holds no table.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::diary_parser::parse_diary_table;
+/// use rusty_commit_saver::vim_commit::TableColumn;
+///
+/// let table = parse_diary_table("| TIME | COMMIT HASH |\n|---|---|\n| 10:00:00 | abc123 |\n").unwrap();
+/// assert_eq!(table.cell(&table.rows[0], TableColumn::CommitHash), Some("abc123"));
+/// ```
+#[must_use]
+pub fn parse_diary_table(content: &str) -> Option<DiaryTable> {
+    let lines = content.lines().collect::<Vec<_>>();
+
+    let header_index = lines
+        .windows(2)
+        .position(|pair| pair[0].trim_start().starts_with('|') && is_separator_line(pair[1]))?;
+
+    let rows = lines
+        .iter()
+        .enumerate()
+        .skip(header_index + 2)
+        .take_while(|(_, line)| line.trim_start().starts_with('|'))
+        .map(|(line_index, line)| DiaryRow {
+            line_index,
+            cells: split_table_row(line),
+        })
+        .collect();
+
+    Some(DiaryTable {
+        header_line_index: header_index,
+        headers: split_table_row(lines[header_index]),
+        rows,
+    })
+}
+
+/// Reads a diary file for parsing, without locking it.
+///
+/// Rows are appended with a single write, so a snapshot read while the daemon
+/// or a hook is writing sees at most one row without its trailing newline and
+/// closing `|`; that row is left out until the write lands.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read.
+pub fn read_diary_snapshot(path: &Path) -> io::Result<String> {
+    let mut content = String::from_utf8_lossy(&fs::read(path)?).into_owned();
+    if content.ends_with('\n') {
+        return Ok(content);
+    }
+
+    let last_line_start = content.rfind('\n').map_or(0, |index| index + 1);
+    let (last_line, _) = split_block_anchor(&content[last_line_start..]);
+    let last_line = last_line.trim();
+    if last_line.starts_with('|') && !(last_line.len() > 1 && last_line.ends_with('|')) {
+        info!(
+            "[read_diary_snapshot()]: Skipping the row still being appended to: {:}",
+            path.display()
+        );
+        content.truncate(last_line_start);
+    }
+    Ok(content)
+}
+
+/// Splits the block anchor (`[table] block_refs`) off the end of a table line.
+///
+/// # Returns
+///
+/// The line up to its closing `|`, and the anchor with its leading space
+/// (` ^abc1234`), or the whole line (without trailing whitespace) and `""`
+/// when it has no anchor.
+#[must_use]
+pub fn split_block_anchor(line: &str) -> (&str, &str) {
+    let trimmed = line.trim_end();
+    let Some(pipe_index) = trimmed.rfind('|') else {
+        return (trimmed, "");
+    };
+    let is_anchor = trimmed[pipe_index + 1..]
+        .trim_start()
+        .strip_prefix('^')
+        .is_some_and(|id| {
+            !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    if is_anchor {
+        trimmed.split_at(pipe_index + 1)
+    } else {
+        (trimmed, "")
+    }
+}
+
+/// Splits a Markdown table line into trimmed cells.
+///
+/// Pipes escaped as `\|` (as done for commit messages) stay inside their
+/// cell, and a trailing block anchor is left out (see [`split_block_anchor()`]).
+#[must_use]
+pub fn split_table_row(line: &str) -> Vec<String> {
+    let trimmed = split_block_anchor(line).0.trim();
+    let inner = trimmed.strip_prefix('|').unwrap_or(trimmed);
+    let inner = inner.strip_suffix('|').unwrap_or(inner);
+
+    let mut cells = Vec::new();
+    let mut current = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                current.push(c);
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            }
+            '|' => cells.push(std::mem::take(&mut current).trim().to_string()),
+            _ => current.push(c),
+        }
+    }
+    cells.push(current.trim().to_string());
+
+    cells
+}
+
+/// Returns the text of a cell holding a single Markdown link (`[text](url)`),
+/// and any other cell as it is.
+#[must_use]
+pub fn link_text(cell: &str) -> &str {
+    cell.strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(')'))
+        .and_then(|rest| rest.split_once("]("))
+        .map_or(cell, |(text, _)| text)
+}
+
+/// Returns `true` for a Markdown table separator line such as `|----|:---:|`.
+fn is_separator_line(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.starts_with('|')
+        && trimmed.contains('-')
+        && trimmed.chars().all(|c| matches!(c, '|' | '-' | ':' | ' '))
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod diary_parser_tests {
+    use super::*;
+    use crate::vim_commit::render_table_header;
+    use crate::vim_commit::DEFAULT_TABLE_COLUMNS;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_read_diary_snapshot_leaves_out_a_row_being_appended() {
+        let temp_dir = tempdir().unwrap();
+        let diary = temp_dir.path().join("2025-01-14.md");
+        let table = "| TIME | COMMIT HASH |\n|---|---|\n| 10:00:00 | abc123 |\n";
+
+        fs::write(&diary, format!("{table:}| 11:00:00 | de")).unwrap();
+        let content = read_diary_snapshot(&diary).unwrap();
+        assert_eq!(content, table);
+        assert_eq!(parse_diary_table(&content).unwrap().rows.len(), 1);
+
+        fs::write(&diary, format!("{table:}| 11:00:00 | def456 |")).unwrap();
+        assert_eq!(
+            parse_diary_table(&read_diary_snapshot(&diary).unwrap())
+                .unwrap()
+                .rows
+                .len(),
+            2
+        );
+
+        fs::write(&diary, "# Notes\nno trailing newline").unwrap();
+        assert_eq!(
+            read_diary_snapshot(&diary).unwrap(),
+            "# Notes\nno trailing newline"
+        );
+    }
+
+    #[test]
+    fn test_cell_reads_the_hash_of_forge_links() {
+        let table = parse_diary_table(
+            "| COMMIT MESSAGE | COMMIT HASH |\n|---|---|\n| [x](y) | [abc123](https://github.com/u/r/commit/abc123) |\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            table.cell(&table.rows[0], TableColumn::CommitHash),
+            Some("abc123")
+        );
+        assert_eq!(
+            table.cell(&table.rows[0], TableColumn::CommitMessage),
+            Some("[x](y)")
+        );
+        assert_eq!(link_text("[[note\\|alias]]"), "[[note\\|alias]]");
+    }
+
+    #[test]
+    fn test_parse_diary_table_reads_rows_after_frontmatter() {
+        let content = format!(
+            "---\ncategory: diary\n---\n# 2025-01-14\n\n{:}| /src | 10:00:00 | fix a \\| b | https://github.com/u/r.git | main | abc123 |\n| /src | 11:00:00 | more | https://github.com/u/r.git | main | def456 |\n",
+            render_table_header(&DEFAULT_TABLE_COLUMNS)
+        );
+
+        let table = parse_diary_table(&content).unwrap();
+
+        assert_eq!(table.header_line_index, 5);
+        assert_eq!(table.headers.len(), 6);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0].line_index, 7);
+        assert_eq!(
+            table.cell(&table.rows[0], TableColumn::CommitMessage),
+            Some("fix a \\| b")
+        );
+        assert_eq!(
+            table.cell(&table.rows[1], TableColumn::CommitHash),
+            Some("def456")
+        );
+        assert_eq!(table.cell(&table.rows[1], TableColumn::Event), None);
+    }
+
+    #[test]
+    fn test_parse_diary_table_without_table_is_none() {
+        assert!(parse_diary_table("# Just a note\n\n| not a table\n").is_none());
+        assert!(parse_diary_table("").is_none());
+    }
+
+    #[test]
+    fn test_split_block_anchor() {
+        assert_eq!(
+            split_block_anchor("| a | b | ^abc1234\n"),
+            ("| a | b |", " ^abc1234")
+        );
+        assert_eq!(split_block_anchor("| a | b |\n"), ("| a | b |", ""));
+        assert_eq!(split_block_anchor("| a | b ^c |"), ("| a | b ^c |", ""));
+        assert_eq!(split_block_anchor("| a | ^ |"), ("| a | ^ |", ""));
+        assert_eq!(split_table_row("| a | b | ^abc1234"), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_split_table_row_keeps_empty_cells() {
+        assert_eq!(split_table_row("| a |  | c |"), vec!["a", "", "c"]);
+    }
+}
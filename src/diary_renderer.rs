@@ -0,0 +1,579 @@
+use chrono::DateTime;
+use chrono::FixedOffset;
+use handlebars::no_escape;
+use handlebars::Handlebars;
+use serde_json::json;
+
+use std::error::Error;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::branch::branch_slug;
+use crate::collision::sequenced_time;
+use crate::commit_entry::CommitEntry;
+use crate::commit_entry::Renderer;
+use crate::entry_id::EntryIdStyle;
+use crate::forge::parse_remote;
+use crate::forge::pull_request_number;
+use crate::forge::ForgeResolver;
+use crate::git_stats::CommitSize;
+use crate::git_tags::NearestTag;
+use crate::paths::repository_name_from_url;
+use crate::trailers::strip_tags_trailers;
+use crate::vim_commit::format_duration;
+use crate::vim_commit::yaml_quote;
+use crate::vim_commit::EntryDetails;
+use crate::vim_commit::EntryFormat;
+use crate::vim_commit::EntryKind;
+use crate::vim_commit::TableColumn;
+use crate::vim_commit::DEFAULT_TABLE_COLUMNS;
+use crate::vim_commit::DEFAULT_TIME_FORMAT;
+use crate::vim_commit::MERGE_EVENT_LABEL;
+use crate::vim_commit::MERGE_MARKER;
+use crate::wsl::WslPaths;
+
+use log::warn;
+
+/// How entries are written in the diary.
+///
+/// Copied from [`SaveSettings`](crate::config::SaveSettings) with
+/// [`SaveSettings::entry_settings()`](crate::config::SaveSettings::entry_settings)
+/// before an entry is saved; the defaults write the
+/// [`DEFAULT_TABLE_COLUMNS`] as a table row.
+///
+/// # Fields
+///
+/// - `table_columns` - The diary table columns, in order. Used both for the
+///   table header of a new diary file and for every row appended to it, and
+///   as the keys of YAML entries
+/// - `entry_format` - Whether the entry is a table row, a YAML block, a
+///   Dataview bullet or a templated row
+/// - `row_template` - The `[templates] row_template` entries are rendered
+///   from, with [`EntryFormat::Template`]
+/// - `time_format` - Chrono format of the `TIME` cell
+///   (`[templates] commit_datetime`), e.g. `%H:%M`; `None` is
+///   [`DEFAULT_TIME_FORMAT`]
+/// - `wsl_paths` - The `[wsl]` settings deciding how the `FOLDER` cell is
+///   written, see [`FolderStyle`](crate::wsl::FolderStyle). `None` keeps
+///   native paths
+/// - `forge_links` - The forges `COMMIT HASH` cells link to
+///   (`[table] forge_links`), see [`ForgeResolver::commit_url()`]. `None`
+///   keeps plain hashes
+/// - `entry_id_style` - How the `entry_id` and `block_id` template variables
+///   are generated (`[templates] entry_id`)
+/// - `block_refs` - Whether table rows end with a `^<short-hash>` block
+///   anchor (`[table] block_refs`), see [`DiaryRenderer::block_anchor()`]
+#[derive(Debug, Clone)]
+pub struct EntrySettings {
+    pub table_columns: Vec<TableColumn>,
+    pub entry_format: EntryFormat,
+    pub row_template: Option<String>,
+    pub time_format: Option<String>,
+    pub wsl_paths: Option<WslPaths>,
+    pub forge_links: Option<ForgeResolver>,
+    pub entry_id_style: EntryIdStyle,
+    pub block_refs: bool,
+}
+
+impl Default for EntrySettings {
+    fn default() -> Self {
+        EntrySettings {
+            table_columns: DEFAULT_TABLE_COLUMNS.to_vec(),
+            entry_format: EntryFormat::Table,
+            row_template: None,
+            time_format: None,
+            wsl_paths: None,
+            forge_links: None,
+            entry_id_style: EntryIdStyle::Base32,
+            block_refs: false,
+        }
+    }
+}
+
+impl EntrySettings {
+    /// Returns the chrono format of the `TIME` cells, the `time_format`
+    /// field or [`DEFAULT_TIME_FORMAT`].
+    #[must_use]
+    pub fn time_format_or_default(&self) -> &str {
+        self.time_format.as_deref().unwrap_or(DEFAULT_TIME_FORMAT)
+    }
+}
+
+/// Renders the diary entry of a commit: a table row, a fenced YAML block, a
+/// Dataview bullet or a templated row, depending on the
+/// [`entry_format`](EntrySettings::entry_format).
+///
+/// The entry data comes from the rendered [`CommitEntry`], the annotations
+/// (duration, build status, timezone, ...) from the [`EntryDetails`] worked
+/// out at save time, and `folder` is the directory the entry was logged
+/// from, written in the `FOLDER` column.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::commit_entry::Renderer;
+/// use rusty_commit_saver::diary_renderer::DiaryRenderer;
+///
+/// let renderer = DiaryRenderer {
+///     settings: &commit_saver.settings,
+///     details: &commit_saver.details,
+///     folder: Path::new("/home/user/api"),
+/// };
+/// let row = renderer.render(&commit_saver.entry)?;
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct DiaryRenderer<'a> {
+    pub settings: &'a EntrySettings,
+    pub details: &'a EntryDetails,
+    pub folder: &'a Path,
+}
+
+impl Renderer for DiaryRenderer<'_> {
+    fn render(&self, entry: &CommitEntry) -> Result<String, Box<dyn Error>> {
+        Ok(self.render_entry(entry))
+    }
+}
+
+impl DiaryRenderer<'_> {
+    /// Formats the entry as the diary writes it.
+    ///
+    /// Table rows hold one cell per entry of
+    /// [`table_columns`](EntrySettings::table_columns). The default columns are:
+    /// 1. **FOLDER** - The directory the entry was logged from
+    /// 2. **TIME** - Commit timestamp (HH:MM:SS format)
+    /// 3. **COMMIT MESSAGE** - Escaped and formatted commit message
+    /// 4. **REPOSITORY URL** - Git remote origin URL
+    /// 5. **BRANCH** - Current branch name
+    /// 6. **COMMIT HASH** - Full SHA-1 commit hash
+    ///
+    /// # Format
+    ///
+    /// ```text
+    /// | /path/to/repo | 14:30:45 | feat: add feature | https://github.com/user/repo.git | main | abc123... |
+    /// ```
+    #[must_use]
+    pub fn render_entry(&self, entry: &CommitEntry) -> String {
+        match self.settings.entry_format {
+            EntryFormat::Yaml => return self.render_yaml(entry),
+            EntryFormat::Template => return self.render_template(entry),
+            EntryFormat::Dataview => return self.render_dataview(entry),
+            EntryFormat::Table => {}
+        }
+
+        let columns = self
+            .settings
+            .table_columns
+            .iter()
+            .copied()
+            .map(Some)
+            .collect::<Vec<_>>();
+        self.table_row(entry, &columns)
+    }
+
+    /// Formats the entry as a row of a table whose header is `headers`, such
+    /// as a day file written before `[table] columns` last changed, so the
+    /// row lines up with the rows already there.
+    ///
+    /// Each header gets the cell of its column (see
+    /// [`TableColumn::from_name()`]); headers that are no column get an
+    /// empty cell.
+    #[must_use]
+    pub fn render_row(&self, entry: &CommitEntry, headers: &[String]) -> String {
+        let columns = headers
+            .iter()
+            .map(|header| TableColumn::from_name(header))
+            .collect::<Vec<_>>();
+        self.table_row(entry, &columns)
+    }
+
+    /// Formats the table row of the entry, one cell per column, empty for
+    /// `None`.
+    fn table_row(&self, entry: &CommitEntry, columns: &[Option<TableColumn>]) -> String {
+        let cells = columns
+            .iter()
+            .map(|column| {
+                column
+                    .map(|column| {
+                        self.link_to_forge(entry, column, self.column_value(entry, column))
+                    })
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+
+        format!(
+            "| {:} |{:}\n",
+            cells.join(" | "),
+            self.block_anchor(entry)
+                .map(|anchor| format!(" {anchor:}"))
+                .unwrap_or_default()
+        )
+    }
+
+    /// Returns the block anchor ending the table row, e.g. `^abc1234`.
+    ///
+    /// Obsidian turns the row into a block other notes can embed with
+    /// `![[2025-01-14#^abc1234]]`. The anchor is the abbreviated commit hash,
+    /// so it is stable across re-renders.
+    ///
+    /// # Returns
+    ///
+    /// `None` unless [`block_refs`](EntrySettings::block_refs) is on, and for
+    /// entries without a commit hash (WIP rows, workday events).
+    #[must_use]
+    pub fn block_anchor(&self, entry: &CommitEntry) -> Option<String> {
+        if !self.settings.block_refs || entry.hash.is_empty() {
+            return None;
+        }
+        let short_hash = entry.hash.get(..7).unwrap_or(&entry.hash);
+        Some(format!("^{:}", short_hash.to_lowercase()))
+    }
+
+    /// Renders the entry from the [`row_template`](EntrySettings::row_template)
+    /// with Handlebars.
+    ///
+    /// # Template Variables
+    ///
+    /// - `time` - The `TIME` cell (with the timezone and sequence suffixes)
+    /// - `date` - The entry date, `YYYY-MM-DD`
+    /// - `message` - The subject (first line) of the commit message
+    /// - `full_message` - The whole commit message, with its line breaks
+    /// - `repo_url` - The web URL of the repository (`https://host/path`),
+    ///   or the remote URL when it has no host
+    /// - `repo` - The repository name, e.g. `api`
+    /// - `hash`, `short_hash` - The full and 7-character commit hash
+    /// - `entry_id`, `block_id` - The stable ID of the entry (`rcs-k3xq9amd`)
+    ///   and the Obsidian block ID made of it (`^rcs-k3xq9amd`), see
+    ///   [`EntryIdStyle`]
+    /// - `commit_url`, `branch_url` - The forge links to the commit and its
+    ///   branch, with `[table] forge_links` on and a known forge (empty
+    ///   otherwise)
+    /// - `pull_request_url` - The forge link to the pull request the message
+    ///   refers to, see [`pull_request_number()`] (empty otherwise)
+    /// - `author`, `author_email` - The commit author, empty for other entries
+    /// - `is_merge` - Whether the commit is a merge, for `{{#if is_merge}}`
+    /// - `parents` - The short parent hashes, space-separated
+    /// - `stats` - The `STATS` cell, e.g. `+120/−4 (3 files)`
+    /// - `size` - The size class, e.g. `m` (null without `[size]`)
+    /// - `tag` - The `TAG` cell, e.g. `v1.2.0` or `v1.2.0+3`
+    /// - `type`, `scope`, `subject` - The Conventional Commits parts of the
+    ///   message (null without `[conventional_commits] enabled` or for other
+    ///   messages), and `breaking` for `{{#if breaking}}`
+    /// - `tags` - The `Obsidian-Tags` trailer tags, space-separated (empty
+    ///   without `[trailers] tags`)
+    /// - `branch`, `folder`, `event`, `duration`, `tracking`, `status` - The
+    ///   values of the matching columns
+    ///
+    /// Values are inserted as is, without HTML escaping, and a newline is
+    /// added when the rendered entry does not end with one. A template that
+    /// fails to render logs a warning and falls back to `- {time} {message}`.
+    ///
+    /// # Format
+    ///
+    /// ```text
+    /// row_template = - {{time}} [{{message}}]({{repo_url}}/commit/{{hash}}) on {{branch}}
+    /// ```
+    fn render_template(&self, entry: &CommitEntry) -> String {
+        let message = entry.message.lines().next().unwrap_or_default().to_string();
+        let time = self.column_value(entry, TableColumn::Time);
+        let repo_url = parse_remote(&entry.repository_url).map_or_else(
+            || entry.repository_url.clone(),
+            |remote| format!("https://{:}/{:}", remote.host, remote.path),
+        );
+        let entry_id = self
+            .settings
+            .entry_id_style
+            .entry_id(&entry.hash, entry.datetime);
+        let conventional = self.details.conventional.as_ref();
+        let variables = json!({
+            "time": time,
+            "date": self.local_datetime(entry).format("%Y-%m-%d").to_string(),
+            "message": message,
+            "full_message": entry.message,
+            "repo_url": repo_url,
+            "repo": repository_name_from_url(&entry.repository_url),
+            "hash": entry.hash,
+            "short_hash": entry.hash.get(..7).unwrap_or(&entry.hash),
+            "entry_id": entry_id,
+            "block_id": format!("^{entry_id:}"),
+            "commit_url": self.commit_url(entry).unwrap_or_default(),
+            "branch_url": self.branch_url(entry).unwrap_or_default(),
+            "pull_request_url": self.pull_request_url(entry).unwrap_or_default(),
+            "branch": entry.branch,
+            "author": entry.author.as_deref().unwrap_or_default(),
+            "author_email": entry.author_email.as_deref().unwrap_or_default(),
+            "is_merge": entry.is_merge(),
+            "parents": self.column_value(entry, TableColumn::Parents),
+            "stats": self.column_value(entry, TableColumn::Stats),
+            "size": self.details.commit_size.map(CommitSize::as_str),
+            "tag": self.column_value(entry, TableColumn::Tag),
+            "folder": self.column_value(entry, TableColumn::Folder),
+            "event": self.column_value(entry, TableColumn::Event),
+            "duration": self.column_value(entry, TableColumn::Duration),
+            "tracking": self.column_value(entry, TableColumn::Tracking),
+            "status": self.column_value(entry, TableColumn::Status),
+            "type": conventional.map(|conventional| &conventional.kind),
+            "scope": conventional.and_then(|conventional| conventional.scope.as_ref()),
+            "breaking": conventional.is_some_and(|conventional| conventional.breaking),
+            "subject": conventional.map(|conventional| conventional.subject.replace("\\|", "|")),
+            "tags": self
+                .details
+                .trailer_tags
+                .as_ref()
+                .map(|trailer_tags| trailer_tags.tags.join(" "))
+                .unwrap_or_default(),
+        });
+
+        let mut handlebars = Handlebars::new();
+        handlebars.register_escape_fn(no_escape);
+        let template = self.settings.row_template.as_deref().unwrap_or_default();
+        let mut rendered = handlebars
+            .render_template(template, &variables)
+            .unwrap_or_else(|e| {
+                warn!(
+                    "[DiaryRenderer::render_template()]: Could not render the row template: {e:}"
+                );
+                format!("- {time:} {message:}")
+            });
+        if !rendered.ends_with('\n') {
+            rendered.push('\n');
+        }
+        rendered
+    }
+
+    /// Formats the entry as a bullet with Dataview inline fields, followed by
+    /// the commit message.
+    ///
+    /// The fields are `time` (the `TIME` cell), `repo` (the repository
+    /// name), `branch` and `hash` (the full commit hash, left out for entries
+    /// without one). The message lines are joined with `<br/>`. With
+    /// [`block_refs`](EntrySettings::block_refs), the block anchor ends the
+    /// bullet.
+    ///
+    /// # Format
+    ///
+    /// ```text
+    /// - [time:: 14:30:45] [repo:: api] [branch:: main] [hash:: abc123...] feat: add feature
+    /// ```
+    fn render_dataview(&self, entry: &CommitEntry) -> String {
+        let mut fields = vec![
+            ("time", self.column_value(entry, TableColumn::Time)),
+            ("repo", repository_name_from_url(&entry.repository_url)),
+            ("branch", entry.branch.clone()),
+        ];
+        if !entry.hash.is_empty() {
+            fields.push(("hash", entry.hash.clone()));
+        }
+        let fields = fields
+            .iter()
+            .map(|(key, value)| format!("[{key:}:: {value:}]"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!(
+            "- {fields:} {:}{:}\n",
+            entry.table_message().replace("\\|", "|"),
+            self.block_anchor(entry)
+                .map(|anchor| format!(" {anchor:}"))
+                .unwrap_or_default()
+        )
+    }
+
+    /// Formats the entry as a fenced YAML block, one key per table column.
+    ///
+    /// Values are double-quoted YAML strings. The commit message keeps its
+    /// line breaks and pipes, and the time is a full RFC 3339 timestamp, in
+    /// the entry timezone when one is set.
+    ///
+    /// # Format
+    ///
+    /// ````text
+    /// ```yaml
+    /// datetime: "2025-01-14T10:30:45+00:00"
+    /// commit_message: "feat: add feature\nWith details"
+    /// commit_hash: "abc123..."
+    /// ```
+    ///
+    /// ````
+    fn render_yaml(&self, entry: &CommitEntry) -> String {
+        let mut block = String::from("```yaml\n");
+        for column in &self.settings.table_columns {
+            let value = match column {
+                TableColumn::Time => self.local_datetime(entry).to_rfc3339(),
+                TableColumn::CommitMessage => entry.message.clone(),
+                _ => self.column_value(entry, *column),
+            };
+            let _ = writeln!(block, "{:}: {:}", column.key(), yaml_quote(&value));
+        }
+        block.push_str("```\n\n");
+        block
+    }
+
+    /// Returns the commit timestamp of the entry in the timezone of its
+    /// details, see [`EntryDetails::local_datetime()`].
+    fn local_datetime(&self, entry: &CommitEntry) -> DateTime<FixedOffset> {
+        self.details.local_datetime(entry.datetime)
+    }
+
+    /// Returns the `TIME` cell of the entry, without a sequence suffix, see
+    /// [`EntryDetails::entry_time()`].
+    #[must_use]
+    pub fn entry_time(&self, entry: &CommitEntry) -> String {
+        self.details
+            .entry_time(entry.datetime, self.settings.time_format_or_default())
+    }
+
+    /// Returns the cell content of a single table column for the entry.
+    ///
+    /// The message and author are escaped for the table: pipes become `\|`
+    /// and the message lines are joined with `<br/>`.
+    fn column_value(&self, entry: &CommitEntry, column: TableColumn) -> String {
+        let details = self.details;
+        let escape = |text: &Option<String>| {
+            text.as_deref()
+                .map(|text| text.replace('|', "\\|"))
+                .unwrap_or_default()
+        };
+        match column {
+            TableColumn::Folder => {
+                let folder = self.folder.display().to_string();
+                match &self.settings.wsl_paths {
+                    Some(wsl_paths) => wsl_paths
+                        .folder_style
+                        .folder(&folder, &wsl_paths.mount_root),
+                    None => folder,
+                }
+            }
+            TableColumn::Time => match details.time_sequence {
+                Some(sequence) => sequenced_time(&self.entry_time(entry), sequence),
+                None => self.entry_time(entry),
+            },
+            TableColumn::CommitMessage => {
+                let commit_msg = entry.table_message();
+                let tags = self.row_tags();
+                let message = if tags.is_empty() {
+                    commit_msg
+                } else {
+                    format!("{:} {:}", strip_tags_trailers(&commit_msg), tags.join(" "))
+                };
+                if entry.is_merge() {
+                    format!("{MERGE_MARKER:} {message:}")
+                } else {
+                    message
+                }
+            }
+            TableColumn::RepositoryUrl => entry.repository_url.clone(),
+            TableColumn::Branch => entry.branch.clone(),
+            TableColumn::CommitHash => entry.hash.clone(),
+            TableColumn::Event if entry.is_merge() => MERGE_EVENT_LABEL.to_string(),
+            TableColumn::Event => details.entry_kind.label().to_string(),
+            TableColumn::Duration => details.duration.map(format_duration).unwrap_or_default(),
+            TableColumn::Tracking => details.tracked_activity.clone().unwrap_or_default(),
+            TableColumn::BranchSlug => branch_slug(&entry.branch).unwrap_or_default(),
+            TableColumn::Status => details
+                .build_status
+                .map(|status| status.to_string())
+                .unwrap_or_default(),
+            TableColumn::Author => escape(&entry.author),
+            TableColumn::AuthorEmail => escape(&entry.author_email),
+            TableColumn::Parents => entry
+                .parents
+                .iter()
+                .map(|hash| hash.get(..7).unwrap_or(hash))
+                .collect::<Vec<_>>()
+                .join(" "),
+            TableColumn::Stats => entry
+                .stats
+                .map(|stats| stats.to_string())
+                .unwrap_or_default(),
+            TableColumn::Size => details.commit_size.map(CommitSize::tag).unwrap_or_default(),
+            TableColumn::Tag => details
+                .release_tag
+                .as_ref()
+                .map(NearestTag::to_string)
+                .unwrap_or_default(),
+            TableColumn::Note => String::new(),
+            TableColumn::CommitType => details
+                .conventional
+                .as_ref()
+                .map(|conventional| conventional.kind.clone())
+                .unwrap_or_default(),
+            TableColumn::Scope => details
+                .conventional
+                .as_ref()
+                .and_then(|conventional| conventional.scope.clone())
+                .unwrap_or_default(),
+            TableColumn::Breaking => details
+                .conventional
+                .as_ref()
+                .filter(|conventional| conventional.breaking)
+                .map(|_| "yes".to_string())
+                .unwrap_or_default(),
+            TableColumn::Subject => details
+                .conventional
+                .as_ref()
+                .map(|conventional| conventional.subject.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Returns the trailer tags written at the end of the `COMMIT MESSAGE`
+    /// cell, in place of their trailers, empty unless their placement
+    /// includes the row.
+    fn row_tags(&self) -> &[String] {
+        self.details
+            .trailer_tags
+            .as_ref()
+            .filter(|trailer_tags| trailer_tags.placement.in_row())
+            .map_or(&[], |trailer_tags| trailer_tags.tags.as_slice())
+    }
+
+    /// Turns the `COMMIT HASH` cell of a commit into a link to the commit on
+    /// its forge, e.g. `[abc123](https://github.com/org/repo/commit/abc123)`.
+    ///
+    /// Other columns, other entry kinds, remotes of unknown forges, and
+    /// settings without [`forge_links`](EntrySettings::forge_links) keep
+    /// `value`.
+    fn link_to_forge(&self, entry: &CommitEntry, column: TableColumn, value: String) -> String {
+        if column != TableColumn::CommitHash || self.details.entry_kind != EntryKind::Commit {
+            return value;
+        }
+        self.commit_url(entry)
+            .map_or(value, |url| format!("[{:}]({url:})", entry.hash))
+    }
+
+    /// Returns the web link to the commit when
+    /// [`forge_links`](EntrySettings::forge_links) is set and the remote's
+    /// forge is known.
+    fn commit_url(&self, entry: &CommitEntry) -> Option<String> {
+        if entry.hash.is_empty() {
+            return None;
+        }
+        self.settings
+            .forge_links
+            .as_ref()?
+            .commit_url(&entry.repository_url, &entry.hash)
+    }
+
+    /// Returns the web link to the branch of the entry when
+    /// [`forge_links`](EntrySettings::forge_links) is set and the remote's
+    /// forge is known.
+    fn branch_url(&self, entry: &CommitEntry) -> Option<String> {
+        if entry.branch.is_empty() {
+            return None;
+        }
+        self.settings
+            .forge_links
+            .as_ref()?
+            .branch_url(&entry.repository_url, &entry.branch)
+    }
+
+    /// Returns the web link to the pull request the commit message refers
+    /// to when [`forge_links`](EntrySettings::forge_links) is set and the
+    /// remote's forge has pull requests.
+    fn pull_request_url(&self, entry: &CommitEntry) -> Option<String> {
+        let number = pull_request_number(&entry.message)?;
+        self.settings
+            .forge_links
+            .as_ref()?
+            .pull_request_url(&entry.repository_url, number)
+    }
+}
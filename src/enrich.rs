@@ -0,0 +1,356 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs;
+use std::hash::BuildHasher;
+use std::path::Path;
+
+use chrono::TimeDelta;
+use log::info;
+
+use crate::atomic_write::write_atomically;
+use crate::branch::branch_slug;
+use crate::diary_parser::parse_diary_table;
+use crate::diary_parser::split_block_anchor;
+use crate::diary_parser::DiaryRow;
+use crate::diary_parser::DiaryTable;
+use crate::hydration::ensure_hydrated;
+use crate::hydration::HYDRATION_ATTEMPTS;
+use crate::hydration::HYDRATION_RETRY_DELAY;
+use crate::state::LoggedEntry;
+use crate::state::StateIndex;
+use crate::trash::keep_copy_in_trash;
+use crate::verify::is_commit_row;
+use crate::vim_commit::format_duration;
+use crate::vim_commit::EntryKind;
+use crate::vim_commit::TableColumn;
+
+/// Estimates the `DURATION` of every logged commit from the state index.
+///
+/// Same estimate as [`CommitSaver::set_duration_since()`](crate::vim_commit::CommitSaver::set_duration_since):
+/// the time since the previous logged commit of the same repository, capped
+/// at `max_duration`. The first commit of each repository gets none.
+///
+/// # Returns
+///
+/// The durations, by commit hash.
+#[must_use]
+pub fn estimate_durations(
+    entries: &[LoggedEntry],
+    max_duration: TimeDelta,
+) -> HashMap<String, TimeDelta> {
+    let mut by_repository: HashMap<&str, Vec<&LoggedEntry>> = HashMap::new();
+    for entry in entries {
+        by_repository
+            .entry(entry.repository_url.as_str())
+            .or_default()
+            .push(entry);
+    }
+
+    let mut durations = HashMap::new();
+    for repository_entries in by_repository.values_mut() {
+        repository_entries.sort_by_key(|entry| entry.commit_datetime);
+        for pair in repository_entries.windows(2) {
+            let elapsed = pair[1].commit_datetime - pair[0].commit_datetime;
+            if elapsed > TimeDelta::zero() {
+                durations.insert(pair[1].commit_hash.clone(), elapsed.min(max_duration));
+            }
+        }
+    }
+    durations
+}
+
+/// Adds the configured columns an older diary table is missing.
+///
+/// Missing columns are appended to the header and separator in the order of
+/// `columns`, and filled on every row written with the old column set. Rows
+/// are identified by their `COMMIT HASH`:
+///
+/// - `DURATION` - From `durations` (see [`estimate_durations()`]), commits only
+/// - `EVENT` - `commit` or `wip`, the only rows older tables hold
+/// - Anything else (e.g. `TRACKING`, which cannot be queried after the fact) - Left empty
+///
+/// Rows that already have as many cells as the new header (appended after
+/// the configuration changed) are left alone.
+///
+/// # Returns
+///
+/// - `Some((content, rows))` - The rewritten diary and the number of enriched rows
+/// - `None` - No table, or nothing is missing
+#[must_use]
+pub fn add_missing_columns<S: BuildHasher>(
+    content: &str,
+    columns: &[TableColumn],
+    durations: &HashMap<String, TimeDelta, S>,
+) -> Option<(String, usize)> {
+    let table = parse_diary_table(content)?;
+    let missing = columns
+        .iter()
+        .copied()
+        .filter(|column| table.column_index(*column).is_none())
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        return None;
+    }
+
+    let mut lines = content
+        .split_inclusive('\n')
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    let mut extend_line = |line_index: usize, cells: &[String]| {
+        let line = &mut lines[line_index];
+        let ending = if line.ends_with('\n') { "\n" } else { "" };
+        let (row, anchor) = split_block_anchor(line);
+        let mut extended = row.to_string();
+        for cell in cells {
+            extended.push_str(cell);
+            extended.push('|');
+        }
+        extended.push_str(anchor);
+        extended.push_str(ending);
+        *line = extended;
+    };
+
+    let headers = missing
+        .iter()
+        .map(|column| format!(" {:} ", column.header()))
+        .collect::<Vec<_>>();
+    let separators = missing
+        .iter()
+        .map(|column| "-".repeat(column.header().chars().count() + 2))
+        .collect::<Vec<_>>();
+    extend_line(table.header_line_index, &headers);
+    extend_line(table.header_line_index + 1, &separators);
+
+    let mut enriched = 0;
+    for row in &table.rows {
+        if row.cells.len() != table.headers.len() {
+            continue;
+        }
+        let cells = missing
+            .iter()
+            .map(|column| format!(" {:} ", backfill_cell(&table, row, *column, durations)))
+            .collect::<Vec<_>>();
+        extend_line(row.line_index, &cells);
+        enriched += 1;
+    }
+
+    Some((lines.concat(), enriched))
+}
+
+/// Returns the value backfilled into `column` for an existing row.
+fn backfill_cell<S: BuildHasher>(
+    table: &DiaryTable,
+    row: &DiaryRow,
+    column: TableColumn,
+    durations: &HashMap<String, TimeDelta, S>,
+) -> String {
+    let is_commit = is_commit_row(table, row);
+    match column {
+        TableColumn::Event if is_commit => EntryKind::Commit.label().to_string(),
+        TableColumn::Event => EntryKind::Wip.label().to_string(),
+        TableColumn::Duration if is_commit => table
+            .cell(row, TableColumn::CommitHash)
+            .and_then(|commit_hash| durations.get(commit_hash))
+            .map(|duration| format_duration(*duration))
+            .unwrap_or_default(),
+        TableColumn::BranchSlug => table
+            .cell(row, TableColumn::Branch)
+            .and_then(branch_slug)
+            .unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+/// Backfills the configured columns into every diary file of the state index.
+///
+/// Diary files are hydrated before they are rewritten (see [`ensure_hydrated()`]),
+/// and a copy is kept in the vault trash first when `trash_dir` is set.
+/// Files that no longer exist are skipped; `verify` reports them.
+///
+/// # Returns
+///
+/// The number of enriched rows.
+///
+/// # Errors
+///
+/// Returns an error if the index or a diary file cannot be read or rewritten.
+pub fn enrich_diary_files(
+    index: &StateIndex,
+    columns: &[TableColumn],
+    max_duration: TimeDelta,
+    trash_dir: Option<&Path>,
+) -> Result<usize, Box<dyn Error>> {
+    let entries = index.entries()?;
+    let durations = estimate_durations(&entries, max_duration);
+
+    let mut seen = HashSet::new();
+    let mut enriched = 0;
+    for entry in &entries {
+        if !seen.insert(entry.diary_path.as_str()) {
+            continue;
+        }
+        let diary_path = Path::new(&entry.diary_path);
+        ensure_hydrated(diary_path, HYDRATION_ATTEMPTS, HYDRATION_RETRY_DELAY)?;
+        if !diary_path.exists() {
+            info!(
+                "[enrich_diary_files()]: Missing, skipping: {:}",
+                entry.diary_path
+            );
+            continue;
+        }
+
+        let content = fs::read_to_string(diary_path)?;
+        let Some((updated, rows)) = add_missing_columns(&content, columns, &durations) else {
+            continue;
+        };
+        info!(
+            "[enrich_diary_files()]: Enriching {rows:} row(s) in: {:}",
+            entry.diary_path
+        );
+        if let Some(trash_dir) = trash_dir {
+            keep_copy_in_trash(trash_dir, diary_path)?;
+        }
+        write_atomically(diary_path, updated)?;
+        enriched += rows;
+    }
+
+    Ok(enriched)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod enrich_tests {
+    use super::*;
+    use crate::testing::LoggedEntryBuilder;
+    use crate::vim_commit::render_table_header;
+    use crate::vim_commit::DEFAULT_TABLE_COLUMNS;
+    use chrono::TimeZone;
+    use chrono::Utc;
+    use tempfile::tempdir;
+
+    const REPO_URL: &str = "https://github.com/user/repo.git";
+
+    fn row(message: &str, commit_hash: &str) -> String {
+        format!("| /src | 10:00:00 | {message:} | {REPO_URL:} | main | {commit_hash:} |\n")
+    }
+
+    #[test]
+    fn test_estimate_durations_per_repository() {
+        let diary = Path::new("/vault/2025-01-14.md");
+        let at = |hour, minute| Utc.with_ymd_and_hms(2025, 1, 14, hour, minute, 0).unwrap();
+        let entries = vec![
+            LoggedEntryBuilder::new("ccc")
+                .at(at(13, 0))
+                .in_diary(diary)
+                .build(),
+            LoggedEntryBuilder::new("aaa")
+                .at(at(9, 0))
+                .in_diary(diary)
+                .build(),
+            LoggedEntryBuilder::new("other")
+                .in_repository("https://github.com/user/other.git")
+                .at(at(9, 30))
+                .in_diary(diary)
+                .build(),
+            LoggedEntryBuilder::new("bbb")
+                .at(at(9, 45))
+                .in_diary(diary)
+                .build(),
+        ];
+
+        let durations = estimate_durations(&entries, TimeDelta::hours(2));
+
+        assert_eq!(durations.get("bbb"), Some(&TimeDelta::minutes(45)));
+        assert_eq!(durations.get("ccc"), Some(&TimeDelta::hours(2)));
+        assert_eq!(durations.get("aaa"), None);
+        assert_eq!(durations.get("other"), None);
+    }
+
+    #[test]
+    fn test_add_missing_columns_backfills_old_rows() {
+        let content = format!(
+            "# 2025-01-14\n\n{:}{:}{:}",
+            render_table_header(&DEFAULT_TABLE_COLUMNS),
+            row("first", "aaa"),
+            row("🚧 WIP on main: aaa", "aaa"),
+        );
+        let mut columns = DEFAULT_TABLE_COLUMNS.to_vec();
+        columns.extend([
+            TableColumn::Duration,
+            TableColumn::BranchSlug,
+            TableColumn::Event,
+        ]);
+        let durations = HashMap::from([("aaa".to_string(), TimeDelta::minutes(45))]);
+
+        let (updated, rows) = add_missing_columns(&content, &columns, &durations).unwrap();
+
+        assert_eq!(rows, 2);
+        let table = parse_diary_table(&updated).unwrap();
+        assert_eq!(table.headers.len(), 9);
+        assert_eq!(
+            table.cell(&table.rows[0], TableColumn::Duration),
+            Some("45m")
+        );
+        assert_eq!(
+            table.cell(&table.rows[0], TableColumn::Event),
+            Some("commit")
+        );
+        assert_eq!(table.cell(&table.rows[1], TableColumn::Duration), Some(""));
+        assert_eq!(table.cell(&table.rows[1], TableColumn::Event), Some("wip"));
+        assert_eq!(
+            table.cell(&table.rows[1], TableColumn::BranchSlug),
+            Some("main")
+        );
+        assert!(updated.starts_with("# 2025-01-14\n\n| FOLDER |"));
+        assert!(updated.contains("| DURATION | BRANCH SLUG | EVENT |\n|"));
+
+        assert_eq!(add_missing_columns(&updated, &columns, &durations), None);
+    }
+
+    #[test]
+    fn test_enrich_diary_files_rewrites_indexed_diaries() {
+        let temp_dir = tempdir().unwrap();
+        let diary = temp_dir.path().join("2025-01-14.md");
+        fs::write(
+            &diary,
+            format!(
+                "{:}{:}{:}",
+                render_table_header(&DEFAULT_TABLE_COLUMNS),
+                row("first", "aaa"),
+                row("second", "bbb"),
+            ),
+        )
+        .unwrap();
+        let index = StateIndex::new(&temp_dir.path().join("state"));
+        for (commit_hash, hour, minute, diary_path) in [
+            ("aaa", 9, 0, diary.clone()),
+            ("bbb", 10, 30, diary.clone()),
+            ("gone", 11, 0, temp_dir.path().join("gone.md")),
+        ] {
+            let entry = LoggedEntryBuilder::new(commit_hash)
+                .at(Utc.with_ymd_and_hms(2025, 1, 14, hour, minute, 0).unwrap())
+                .in_diary(&diary_path)
+                .build();
+            index.append(&entry).unwrap();
+        }
+        let trash_dir = temp_dir.path().join(".trash");
+
+        let mut columns = DEFAULT_TABLE_COLUMNS.to_vec();
+        columns.push(TableColumn::Duration);
+        let enriched =
+            enrich_diary_files(&index, &columns, TimeDelta::hours(2), Some(&trash_dir)).unwrap();
+
+        assert_eq!(enriched, 2);
+        let table = parse_diary_table(&fs::read_to_string(&diary).unwrap()).unwrap();
+        assert_eq!(
+            table.cell(&table.rows[1], TableColumn::Duration),
+            Some("1h 30m")
+        );
+        assert!(trash_dir.join("2025-01-14.md").exists());
+        assert_eq!(
+            enrich_diary_files(&index, &columns, TimeDelta::hours(2), None).unwrap(),
+            0
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use chrono::DateTime;
+use chrono::Utc;
+use sha2::Digest;
+use sha2::Sha256;
+
+/// Prefix of every entry ID, so the block IDs of the diary stand apart from
+/// hand-written ones.
+pub const ENTRY_ID_PREFIX: &str = "rcs-";
+
+/// Characters of a [`EntryIdStyle::Base32`] ID, after the prefix.
+const BASE32_ID_LENGTH: usize = 8;
+
+/// Characters of a [`EntryIdStyle::ShortHash`] ID, after the prefix.
+const SHORT_HASH_ID_LENGTH: usize = 7;
+
+/// RFC 4648 base32 alphabet, lowercased: block IDs only allow letters,
+/// digits and `-`.
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+/// How the short, stable ID of an entry is generated.
+///
+/// The ID is exposed to row templates as `entry_id` (`rcs-k3xq9amd`) and
+/// `block_id` (`^rcs-k3xq9amd`): ending a templated entry with `{{block_id}}`
+/// makes it an Obsidian block other notes can link to with
+/// `[[2025-01-14#^rcs-k3xq9amd]]`. The same entry always gets the same ID.
+///
+/// # Variants
+///
+/// - `Base32` - Base32 of the SHA-256 of the commit hash and time (default);
+///   also unique for WIP entries, which have no hash
+/// - `ShortHash` - The abbreviated commit hash, readable but empty-ish for
+///   WIP entries
+///
+/// # Configuration
+///
+/// ```text
+/// [templates]
+/// entry_id = short_hash
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EntryIdStyle {
+    #[default]
+    Base32,
+    ShortHash,
+}
+
+impl EntryIdStyle {
+    /// Parses the `[templates] entry_id` value (`base32` or `short_hash`, any case).
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "base32" => Some(EntryIdStyle::Base32),
+            "short_hash" | "hash" => Some(EntryIdStyle::ShortHash),
+            _ => None,
+        }
+    }
+
+    /// Returns the ID of the entry of `commit_hash` made at `commit_datetime`,
+    /// with its [`ENTRY_ID_PREFIX`].
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rusty_commit_saver::entry_id::EntryIdStyle;
+    ///
+    /// let id = EntryIdStyle::ShortHash.entry_id("abc1234def", commit_datetime);
+    /// assert_eq!(id, "rcs-abc1234");
+    /// ```
+    #[must_use]
+    pub fn entry_id(self, commit_hash: &str, commit_datetime: DateTime<Utc>) -> String {
+        let id = match self {
+            EntryIdStyle::Base32 => {
+                let digest = Sha256::digest(
+                    format!("{commit_hash:}{:}", commit_datetime.to_rfc3339()).as_bytes(),
+                );
+                base32(&digest)[..BASE32_ID_LENGTH].to_string()
+            }
+            EntryIdStyle::ShortHash => commit_hash
+                .get(..SHORT_HASH_ID_LENGTH)
+                .unwrap_or(commit_hash)
+                .to_lowercase(),
+        };
+        format!("{ENTRY_ID_PREFIX:}{id:}")
+    }
+}
+
+/// Encodes `bytes` in lowercase RFC 4648 base32, without padding.
+#[must_use]
+pub fn base32(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let mut buffer: u16 = 0;
+    let mut bits = 0;
+    for byte in bytes {
+        buffer = (buffer << 8) | u16::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(char::from(
+                BASE32_ALPHABET[usize::from((buffer >> bits) & 31)],
+            ));
+        }
+    }
+    if bits > 0 {
+        encoded.push(char::from(
+            BASE32_ALPHABET[usize::from((buffer << (5 - bits)) & 31)],
+        ));
+    }
+    encoded
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod entry_id_tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_base32_matches_rfc_4648() {
+        assert_eq!(base32(b""), "");
+        assert_eq!(base32(b"f"), "my");
+        assert_eq!(base32(b"fo"), "mzxq");
+        assert_eq!(base32(b"foo"), "mzxw6");
+        assert_eq!(base32(b"foob"), "mzxw6yq");
+        assert_eq!(base32(b"fooba"), "mzxw6ytb");
+        assert_eq!(base32(b"foobar"), "mzxw6ytboi");
+    }
+
+    #[test]
+    fn test_entry_id() {
+        let datetime = Utc.with_ymd_and_hms(2025, 1, 14, 10, 30, 0).unwrap();
+        let hash = "ABC1234def5678";
+
+        let id = EntryIdStyle::Base32.entry_id(hash, datetime);
+        assert_eq!(id.len(), ENTRY_ID_PREFIX.len() + BASE32_ID_LENGTH);
+        assert!(id.starts_with(ENTRY_ID_PREFIX));
+        assert!(id
+            .chars()
+            .all(|c| c == '-' || c.is_ascii_lowercase() || c.is_ascii_digit()));
+        assert_eq!(EntryIdStyle::Base32.entry_id(hash, datetime), id);
+        assert_ne!(
+            EntryIdStyle::Base32.entry_id("", datetime),
+            EntryIdStyle::Base32.entry_id("", datetime + chrono::TimeDelta::seconds(1))
+        );
+
+        assert_eq!(
+            EntryIdStyle::ShortHash.entry_id(hash, datetime),
+            "rcs-abc1234"
+        );
+        assert_eq!(
+            EntryIdStyle::from_name(" Short_Hash "),
+            Some(EntryIdStyle::ShortHash)
+        );
+        assert_eq!(EntryIdStyle::from_name("uuid"), None);
+    }
+}
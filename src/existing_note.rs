@@ -0,0 +1,416 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use log::info;
+
+use crate::atomic_write::write_atomically;
+use crate::diary_parser::parse_diary_table;
+use crate::diary_parser::DiaryTable;
+use crate::frontmatter::merge_frontmatter_list;
+use crate::frontmatter::split_frontmatter;
+use crate::vim_commit::CommitSaver;
+use crate::vim_commit::EntryFormat;
+
+/// Heading the commit table is added under when `[obsidian] insert_under`
+/// is not set.
+pub const DEFAULT_TABLE_HEADING: &str = "## Commits";
+
+/// Turns an `[obsidian] insert_under` value into a Markdown heading line.
+///
+/// A bare title becomes a level 2 heading (`Commits` → `## Commits`); a value
+/// that already starts with `#` marks keeps its level.
+///
+/// # Returns
+///
+/// `None` if the value has no title.
+#[must_use]
+pub fn heading_line(value: &str) -> Option<String> {
+    let value = value.trim();
+    let title = value.trim_start_matches('#').trim();
+    if title.is_empty() {
+        return None;
+    }
+    if value.starts_with('#') {
+        let marks = value.len() - value.trim_start_matches('#').len();
+        return Some(format!("{:} {title:}", "#".repeat(marks)));
+    }
+    Some(format!("## {title:}"))
+}
+
+/// Adds the commit table to a note that has none, under `heading`.
+///
+/// When the note already ends with `heading` (e.g. a Daily Notes template
+/// with an empty `## Commits` section), the table goes right below it;
+/// otherwise the heading and the table are added at the end of the note, so
+/// the rows appended afterwards land in the table.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::existing_note::add_commit_table;
+///
+/// let note = add_commit_table("# Tuesday\n\nGym.\n", "## Commits", "| TIME |\n|------|\n");
+/// assert_eq!(note, "# Tuesday\n\nGym.\n\n## Commits\n\n| TIME |\n|------|\n");
+/// ```
+#[must_use]
+pub fn add_commit_table(content: &str, heading: &str, table_header: &str) -> String {
+    let heading = heading.trim();
+    let ends_with_heading = content
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .is_some_and(|line| line.trim() == heading);
+    let content = if ends_with_heading {
+        content.trim_end()
+    } else {
+        content
+    };
+    let separator = if content.is_empty() || content.ends_with("\n\n") {
+        ""
+    } else if content.ends_with('\n') {
+        "\n"
+    } else {
+        "\n\n"
+    };
+
+    if ends_with_heading {
+        format!("{content:}\n\n{table_header:}")
+    } else {
+        format!("{content:}{separator:}{heading:}\n\n{table_header:}")
+    }
+}
+
+/// Returns the Markdown heading level of a line (`## Commits` is 2), or
+/// `None` for other lines.
+fn heading_level(line: &str) -> Option<usize> {
+    let line = line.trim_end();
+    let marks = line.len() - line.trim_start_matches('#').len();
+    let title = &line[marks..];
+    ((1..=6).contains(&marks) && (title.is_empty() || title.starts_with(' '))).then_some(marks)
+}
+
+/// Returns the lines of the section under `heading`: from the line after
+/// the heading to the next heading of the same or a higher level (or the
+/// end of the note). Lines inside code fences are never headings.
+fn heading_section(lines: &[&str], heading: &str) -> Option<(usize, usize)> {
+    let heading = heading.trim();
+    let level = heading_level(heading)?;
+    let mut in_fence = false;
+    let mut start = None;
+    for (index, line) in lines.iter().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        match start {
+            None if line.trim() == heading => start = Some(index + 1),
+            Some(start) if heading_level(line).is_some_and(|found| found <= level) => {
+                return Some((start, index));
+            }
+            _ => {}
+        }
+    }
+    start.map(|start| (start, lines.len()))
+}
+
+/// Adds the commit table at the end of the section under `heading`, before
+/// whatever follows it, or the heading and the table at the end of the note
+/// when it has no such heading (see [`add_commit_table()`]).
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::existing_note::add_table_under_heading;
+///
+/// let note = add_table_under_heading("## Commits\n\n## Notes\n", "## Commits", "| TIME |\n|------|\n");
+/// assert_eq!(note, "## Commits\n\n| TIME |\n|------|\n\n## Notes\n");
+/// ```
+#[must_use]
+pub fn add_table_under_heading(content: &str, heading: &str, table_header: &str) -> String {
+    let lines = content.split_inclusive('\n').collect::<Vec<_>>();
+    let Some((start, end)) = heading_section(&lines, heading) else {
+        return add_commit_table(content, heading, table_header);
+    };
+    let mut insert_at = end;
+    while insert_at > start && lines[insert_at - 1].trim().is_empty() {
+        insert_at -= 1;
+    }
+
+    let before = lines[..insert_at].concat();
+    let after = lines[insert_at..].concat();
+    let newline = if before.ends_with('\n') { "" } else { "\n" };
+    let spacing = if after.is_empty() || after.starts_with(['\n', '\r']) {
+        ""
+    } else {
+        "\n"
+    };
+    format!("{before:}{newline:}\n{table_header:}{spacing:}{after:}")
+}
+
+/// Parses the table rows go to: the first table of the section under
+/// `heading`, or of the whole note when `heading` is `None` or the note has
+/// no such heading.
+///
+/// # Returns
+///
+/// The index of the first line of the section, which the line indexes of
+/// the table are relative to, and the table; `None` if there is no table.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::existing_note::section_table;
+///
+/// let note = "| A |\n|---|\n\n## Commits\n\n| TIME |\n|------|\n| 10:00:00 |\n";
+/// let (start, table) = section_table(note, Some("## Commits")).unwrap();
+/// assert_eq!(start + table.rows[0].line_index, 7);
+/// ```
+#[must_use]
+pub fn section_table(content: &str, heading: Option<&str>) -> Option<(usize, DiaryTable)> {
+    let lines = content.split_inclusive('\n').collect::<Vec<_>>();
+    let (start, end) = heading
+        .and_then(|heading| heading_section(&lines, heading))
+        .unwrap_or((0, lines.len()));
+    parse_diary_table(&lines[start..end].concat()).map(|table| (start, table))
+}
+
+/// Returns the line right after the last row of the table rows go to, see
+/// [`section_table()`].
+fn table_end(lines: &[&str], heading: &str) -> Option<usize> {
+    let (start, table) = section_table(&lines.concat(), Some(heading))?;
+    let last = table
+        .rows
+        .last()
+        .map_or(table.header_line_index + 1, |row| row.line_index);
+    Some(start + last + 1)
+}
+
+/// Inserts a table `row` at the end of the commit table under `heading`
+/// (`[obsidian] insert_under`), so text after the table stays after it.
+///
+/// A note whose heading has no table gets one at the end of the section,
+/// and a note without the heading gets the heading and the table at its end
+/// (see [`add_table_under_heading()`]), unless it already has a table
+/// elsewhere, which then gets the row.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::existing_note::insert_row_under_heading;
+///
+/// let note = "## Commits\n\n| TIME |\n|------|\n| 10:00:00 |\n\n## Notes\n";
+/// let updated = insert_row_under_heading(note, "## Commits", "| TIME |\n|------|\n", "| 11:00:00 |\n");
+/// assert_eq!(updated, "## Commits\n\n| TIME |\n|------|\n| 10:00:00 |\n| 11:00:00 |\n\n## Notes\n");
+/// ```
+#[must_use]
+pub fn insert_row_under_heading(
+    content: &str,
+    heading: &str,
+    table_header: &str,
+    row: &str,
+) -> String {
+    let lines = content.split_inclusive('\n').collect::<Vec<_>>();
+    let with_table;
+    let (lines, index) = if let Some(index) = table_end(&lines, heading) {
+        (lines, index)
+    } else {
+        with_table = add_table_under_heading(content, heading, table_header);
+        let lines = with_table.split_inclusive('\n').collect::<Vec<_>>();
+        let index = table_end(&lines, heading).unwrap_or(lines.len());
+        (lines, index)
+    };
+
+    let mut updated = lines[..index].concat();
+    if !updated.is_empty() && !updated.ends_with('\n') {
+        updated.push('\n');
+    }
+    updated.push_str(row);
+    updated.push_str(&lines[index..].concat());
+    updated
+}
+
+/// Merges `tags` into the `tags` list of the note frontmatter, adding a
+/// frontmatter block to notes without one.
+///
+/// # Returns
+///
+/// The updated note, or `None` when it already has every tag.
+#[must_use]
+pub fn merge_note_tags(content: &str, tags: &[String]) -> Option<String> {
+    if split_frontmatter(content).is_some() {
+        return merge_frontmatter_list(content, "tags", tags);
+    }
+    merge_frontmatter_list(&format!("---\n---\n{content:}"), "tags", tags)
+}
+
+/// Prepares a diary file that exists but was not created by the tool, e.g.
+/// by the Obsidian Daily Notes plugin, for the commit rows.
+///
+/// A note without a commit table gets one, under `insert_under` (see
+/// [`add_table_under_heading()`]) or at its end under
+/// [`DEFAULT_TABLE_HEADING`] (see [`add_commit_table()`]), and
+/// the diary tags of [`CommitSaver::prepare_frontmatter_tags()`] merged into
+/// its frontmatter (see [`merge_note_tags()`]). Notes that already have a
+/// table, and entries not written as table rows, are left alone.
+///
+/// The caller must hold the [`DiaryLock`](crate::diary_lock::DiaryLock) of
+/// the note, as it is read then rewritten.
+///
+/// # Returns
+///
+/// `true` if the note was updated.
+///
+/// # Errors
+///
+/// Returns an error if the note cannot be read or written.
+pub fn adopt_existing_note(
+    commit_saver_struct: &mut CommitSaver,
+    full_path: &Path,
+    insert_under: Option<&str>,
+) -> Result<bool, Box<dyn Error>> {
+    if commit_saver_struct.settings.entry_format != EntryFormat::Table {
+        return Ok(false);
+    }
+    let content = fs::read_to_string(full_path)?;
+    if parse_diary_table(&content).is_some() {
+        return Ok(false);
+    }
+
+    let heading = insert_under.unwrap_or(DEFAULT_TABLE_HEADING);
+    info!(
+        "[adopt_existing_note()]: Adding the commit table under '{heading:}' to: {:}",
+        full_path.display()
+    );
+    let table_header = commit_saver_struct
+        .settings
+        .entry_format
+        .header(&commit_saver_struct.settings.table_columns);
+    let with_table = match insert_under {
+        Some(heading) => add_table_under_heading(&content, heading, &table_header),
+        None => add_commit_table(&content, heading, &table_header),
+    };
+    let tags = commit_saver_struct.prepare_frontmatter_tags();
+    let adopted = merge_note_tags(&with_table, &tags).unwrap_or(with_table);
+    write_atomically(full_path, adopted)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod existing_note_tests {
+    use super::*;
+
+    const HEADER: &str = "| TIME |\n|------|\n";
+
+    #[test]
+    fn test_heading_line() {
+        assert_eq!(heading_line(" Commits ").as_deref(), Some("## Commits"));
+        assert_eq!(heading_line("###Git log").as_deref(), Some("### Git log"));
+        assert_eq!(heading_line("# Commits").as_deref(), Some("# Commits"));
+        assert_eq!(heading_line("##"), None);
+        assert_eq!(heading_line(""), None);
+    }
+
+    #[test]
+    fn test_add_commit_table_appends_the_heading() {
+        assert_eq!(
+            add_commit_table("# Tuesday\n\nGym.\n", "## Commits", HEADER),
+            "# Tuesday\n\nGym.\n\n## Commits\n\n| TIME |\n|------|\n"
+        );
+        assert_eq!(
+            add_commit_table("Gym.", "## Commits", HEADER),
+            "Gym.\n\n## Commits\n\n| TIME |\n|------|\n"
+        );
+        assert_eq!(
+            add_commit_table("", "## Commits", HEADER),
+            "## Commits\n\n| TIME |\n|------|\n"
+        );
+    }
+
+    #[test]
+    fn test_add_commit_table_reuses_a_trailing_heading() {
+        assert_eq!(
+            add_commit_table("# Tuesday\n\n## Commits \n\n\n", "## Commits", HEADER),
+            "# Tuesday\n\n## Commits\n\n| TIME |\n|------|\n"
+        );
+    }
+
+    #[test]
+    fn test_add_table_under_heading() {
+        let note = "# Tuesday\n\n## Commits\nToday's work:\n\n\n## Notes\nGym.\n";
+        assert_eq!(
+            add_table_under_heading(note, "## Commits", HEADER),
+            "# Tuesday\n\n## Commits\nToday's work:\n\n| TIME |\n|------|\n\n\n## Notes\nGym.\n"
+        );
+        assert_eq!(
+            add_table_under_heading("## Commits\n### Morning\n## Notes", "## Commits", HEADER),
+            "## Commits\n### Morning\n\n| TIME |\n|------|\n\n## Notes"
+        );
+        assert_eq!(
+            add_table_under_heading("```sh\n## Commits\n```\n", "## Commits", HEADER),
+            "```sh\n## Commits\n```\n\n## Commits\n\n| TIME |\n|------|\n"
+        );
+    }
+
+    #[test]
+    fn test_section_table() {
+        let note = "| A |\n|---|\n\n## Commits\n\n| TIME |\n|------|\n| 10:00:00 |\n";
+        let (start, table) = section_table(note, Some("## Commits")).unwrap();
+        assert_eq!((start, table.headers), (4, vec!["TIME".to_string()]));
+        assert_eq!(start + table.rows[0].line_index, 7);
+        assert_eq!(section_table(note, None).unwrap().0, 0);
+        assert_eq!(section_table(note, Some("## Other")).unwrap().0, 0);
+        assert!(section_table("## Commits\n", Some("## Commits")).is_none());
+    }
+
+    #[test]
+    fn test_insert_row_under_heading_keeps_the_text_after_the_table() {
+        let note = "## Commits\n\n| TIME |\n|------|\n| 10:00:00 |\n\n## Notes\nGym.\n";
+        assert_eq!(
+            insert_row_under_heading(note, "## Commits", HEADER, "| 11:00:00 |\n"),
+            "## Commits\n\n| TIME |\n|------|\n| 10:00:00 |\n| 11:00:00 |\n\n## Notes\nGym.\n"
+        );
+        assert_eq!(
+            insert_row_under_heading("| TIME |\n|------|", "## Commits", HEADER, "| 11:00:00 |\n"),
+            "| TIME |\n|------|\n| 11:00:00 |\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_row_under_heading_creates_the_table() {
+        assert_eq!(
+            insert_row_under_heading(
+                "## Commits\n\n## Notes\n",
+                "## Commits",
+                HEADER,
+                "| 11:00:00 |\n"
+            ),
+            "## Commits\n\n| TIME |\n|------|\n| 11:00:00 |\n\n## Notes\n"
+        );
+        assert_eq!(
+            insert_row_under_heading("# Tuesday\n", "## Commits", HEADER, "| 11:00:00 |\n"),
+            "# Tuesday\n\n## Commits\n\n| TIME |\n|------|\n| 11:00:00 |\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_note_tags() {
+        let tags = ["#diary/commits".to_string()];
+        assert_eq!(
+            merge_note_tags("# Tuesday\n", &tags).unwrap(),
+            "---\ntags:\n- '#diary/commits'\n---\n# Tuesday\n"
+        );
+        assert_eq!(
+            merge_note_tags("---\ntags:\n  - daily\n---\n# Tuesday\n", &tags).unwrap(),
+            "---\ntags:\n  - daily\n  - '#diary/commits'\n---\n# Tuesday\n"
+        );
+        assert_eq!(
+            merge_note_tags("---\ntags:\n- '#diary/commits'\n---\n", &tags),
+            None
+        );
+    }
+}
@@ -0,0 +1,469 @@
+use std::error::Error;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::path::PathBuf;
+
+use log::info;
+use log::warn;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::atomic_write::write_atomically;
+use crate::commit_entry::CommitEntry;
+use crate::diary_lock::DiaryLock;
+use crate::diary_lock::LOCK_TIMEOUT;
+use crate::hydration::ensure_hydrated;
+use crate::hydration::HYDRATION_ATTEMPTS;
+use crate::hydration::HYDRATION_RETRY_DELAY;
+use crate::vim_commit::CommitSaver;
+use crate::vim_commit::EntryKind;
+
+/// File name of the spool of unwritten appends inside the state directory.
+const SPOOL_FILE_NAME: &str = "spool.jsonl";
+
+/// File name of the spool of unwritten diary rows inside the state directory.
+const ROW_SPOOL_FILE_NAME: &str = "spool-rows.jsonl";
+
+/// Text staged for the end of a note other than the diary, e.g. the row of
+/// the weekly note (`[weekly_log]`).
+///
+/// Staging renders every target of a save before anything is written, so an
+/// entry that cannot be rendered for one target is written to none.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingAppend {
+    /// The note to append to.
+    pub note_path: PathBuf,
+
+    /// What a missing note is created with, before the text is appended.
+    pub header: String,
+
+    /// The text appended to the note.
+    pub text: String,
+}
+
+impl PendingAppend {
+    /// Appends the text to the note, creating the note (and its directories)
+    /// with its header when missing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the note is a cloud placeholder that cannot be
+    /// hydrated, or if it cannot be created or written.
+    pub fn write(&self) -> Result<(), Box<dyn Error>> {
+        ensure_hydrated(&self.note_path, HYDRATION_ATTEMPTS, HYDRATION_RETRY_DELAY)?;
+        if !self.note_path.exists() {
+            info!(
+                "[PendingAppend::write()]: Creating the note: {:}",
+                self.note_path.display()
+            );
+            if let Some(parent) = self.note_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&self.note_path, &self.header)?;
+        }
+
+        let mut note = OpenOptions::new().append(true).open(&self.note_path)?;
+        note.write_all(self.text.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// A diary row that could not be written to one of the vaults of its entry
+/// (`[vaults]`), while the others took it.
+///
+/// Kept in the row spool (see [`Spool::rows()`]) with what it takes to save
+/// the entry again, so the next save writes it through the regular save path:
+/// inserted at its place in time, and skipped if the diary already has it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingRow {
+    /// The root of the vault the row is for.
+    pub vault_root: PathBuf,
+
+    /// The commit path of the vault, relative to its root.
+    pub commit_path: PathBuf,
+
+    /// The diary path template of the save (`[obsidian] commit_date_path`).
+    pub date_template: String,
+
+    /// The entry of the row.
+    pub entry: CommitEntry,
+
+    /// What the row records.
+    pub entry_kind: EntryKind,
+
+    /// The Git directory of the repository of the entry, see
+    /// [`EntryDetails::git_dir`](crate::vim_commit::EntryDetails::git_dir).
+    pub git_dir: Option<PathBuf>,
+
+    /// Its working directory, see
+    /// [`EntryDetails::workdir`](crate::vim_commit::EntryDetails::workdir).
+    pub workdir: Option<PathBuf>,
+}
+
+impl PendingRow {
+    /// Records the row of `commit_saver` for the vault at `vault_root`.
+    #[must_use]
+    pub fn new(
+        commit_saver: &CommitSaver,
+        vault_root: &Path,
+        commit_path: &Path,
+        date_template: &str,
+    ) -> Self {
+        PendingRow {
+            vault_root: vault_root.to_path_buf(),
+            commit_path: commit_path.to_path_buf(),
+            date_template: date_template.to_string(),
+            entry: commit_saver.entry.clone(),
+            entry_kind: commit_saver.details.entry_kind,
+            git_dir: commit_saver.details.git_dir.clone(),
+            workdir: commit_saver.details.workdir.clone(),
+        }
+    }
+
+    /// Rebuilds the `CommitSaver` of the row, for the save to apply its
+    /// settings again.
+    #[must_use]
+    pub fn commit_saver(&self) -> CommitSaver {
+        let mut commit_saver = CommitSaver::with_kind(self.entry.clone(), self.entry_kind);
+        commit_saver.details.git_dir.clone_from(&self.git_dir);
+        commit_saver.details.workdir.clone_from(&self.workdir);
+        commit_saver
+    }
+}
+
+/// Writes that could not be done when their entry was saved, kept for the
+/// next save to retry: appends ([`PendingAppend`], the default) or diary rows
+/// ([`PendingRow`]).
+///
+/// Stored in the state directory as one JSON object per line.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::fan_out::{commit_appends, Spool};
+///
+/// let spool = Spool::new(&state_dir);
+/// let spooled = commit_appends(&staged, Some(&spool));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Spool<T = PendingAppend> {
+    spool_path: PathBuf,
+    pending: PhantomData<T>,
+}
+
+impl Spool {
+    /// Creates a handle on the spool of appends inside `state_dir`.
+    ///
+    /// Nothing is created on disk until an append is spooled.
+    #[must_use]
+    pub fn new(state_dir: &Path) -> Self {
+        Spool {
+            spool_path: state_dir.join(SPOOL_FILE_NAME),
+            pending: PhantomData,
+        }
+    }
+}
+
+impl Spool<PendingRow> {
+    /// Creates a handle on the spool of diary rows inside `state_dir`.
+    ///
+    /// Nothing is created on disk until a row is spooled.
+    #[must_use]
+    pub fn rows(state_dir: &Path) -> Self {
+        Spool {
+            spool_path: state_dir.join(ROW_SPOOL_FILE_NAME),
+            pending: PhantomData,
+        }
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> Spool<T> {
+    /// Reads the spooled writes, oldest first. A missing spool has none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the spool exists but cannot be read, or if one of
+    /// its lines cannot be parsed.
+    pub fn pending(&self) -> Result<Vec<T>, Box<dyn Error>> {
+        if !self.spool_path.exists() {
+            return Ok(Vec::new());
+        }
+        fs::read_to_string(&self.spool_path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(Into::into))
+            .collect()
+    }
+
+    /// Replaces the spooled writes with `appends`, removing the spool when
+    /// there are none left.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state directory cannot be created or the
+    /// spool cannot be written or removed.
+    pub fn replace(&self, appends: &[T]) -> Result<(), Box<dyn Error>> {
+        if appends.is_empty() {
+            if self.spool_path.exists() {
+                fs::remove_file(&self.spool_path)?;
+            }
+            return Ok(());
+        }
+        if let Some(parent) = self.spool_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut content = String::new();
+        for append in appends {
+            content.push_str(&serde_json::to_string(append)?);
+            content.push('\n');
+        }
+        write_atomically(&self.spool_path, content)?;
+        Ok(())
+    }
+}
+
+/// Writes the staged appends of a save whose diary row is written, after
+/// retrying the ones spooled by earlier saves.
+///
+/// The diary row is the entry: once it is written, the other targets must
+/// follow rather than roll it back. An append that fails is kept in the
+/// `spool` (when there is a state directory) and retried by the next save,
+/// so the other notes catch up in order instead of silently missing rows.
+/// The spool is locked meanwhile, so concurrent saves do not retry the same
+/// appends twice.
+///
+/// # Returns
+///
+/// The number of appends left in the spool.
+pub fn commit_appends(staged: &[PendingAppend], spool: Option<&Spool>) -> usize {
+    let lock = spool.map(|spool| {
+        DiaryLock::acquire(spool.spool_path.parent(), &spool.spool_path, LOCK_TIMEOUT)
+    });
+    if let Some(Err(e)) = &lock {
+        warn!("[commit_appends()]: Could not lock the spool, not retrying it: {e:}");
+        return spool_appends(staged, None);
+    }
+    let mut appends = match spool.map(Spool::pending).transpose() {
+        Ok(spooled) => spooled.unwrap_or_default(),
+        Err(e) => {
+            warn!("[commit_appends()]: Could not read the spool, not retrying it: {e:}");
+            return spool_appends(staged, None);
+        }
+    };
+    if !appends.is_empty() {
+        info!(
+            "[commit_appends()]: Retrying {:} spooled append(s).",
+            appends.len()
+        );
+    }
+    appends.extend(staged.iter().cloned());
+    let unwritten = spool_appends(&appends, spool);
+    drop(lock);
+    unwritten
+}
+
+/// Saves the diary rows spooled by earlier saves again with `save`, then
+/// spools `failed`, the rows of this save that could not be written.
+///
+/// A row whose vault is still unavailable stays in the spool for the next
+/// save. The spool is locked meanwhile, so concurrent saves do not write the
+/// same rows twice.
+///
+/// # Returns
+///
+/// The number of rows left in the spool.
+pub fn commit_rows<F>(spool: &Spool<PendingRow>, failed: &[PendingRow], mut save: F) -> usize
+where
+    F: FnMut(&PendingRow) -> Result<(), Box<dyn Error>>,
+{
+    let _lock = match DiaryLock::acquire(spool.spool_path.parent(), &spool.spool_path, LOCK_TIMEOUT)
+    {
+        Ok(lock) => lock,
+        Err(e) => {
+            warn!(
+                "[commit_rows()]: Could not lock the row spool, dropping {:} row(s): {e:}",
+                failed.len()
+            );
+            return failed.len();
+        }
+    };
+    let spooled = match spool.pending() {
+        Ok(spooled) => spooled,
+        Err(e) => {
+            warn!(
+                "[commit_rows()]: Could not read the row spool, dropping {:} row(s): {e:}",
+                failed.len()
+            );
+            return failed.len();
+        }
+    };
+    if spooled.is_empty() && failed.is_empty() {
+        return 0;
+    }
+
+    let mut unwritten = Vec::new();
+    for row in spooled {
+        info!(
+            "[commit_rows()]: Retrying the row of {:} in: {:}",
+            row.entry.hash,
+            row.vault_root.display()
+        );
+        if let Err(e) = save(&row) {
+            warn!(
+                "[commit_rows()]: Could not write to {:} yet: {e:}",
+                row.vault_root.display()
+            );
+            unwritten.push(row);
+        }
+    }
+    unwritten.extend(failed.iter().cloned());
+    if let Err(e) = spool.replace(&unwritten) {
+        warn!("[commit_rows()]: Could not update the row spool: {e:}");
+    }
+    unwritten.len()
+}
+
+/// Writes `appends` in order, spooling the ones that fail.
+///
+/// Once an append to a note fails, the later ones to the same note are
+/// spooled too, so its rows keep their order.
+fn spool_appends(appends: &[PendingAppend], spool: Option<&Spool>) -> usize {
+    let mut unwritten: Vec<PendingAppend> = Vec::new();
+    for append in appends {
+        let blocked = unwritten
+            .iter()
+            .any(|failed| failed.note_path == append.note_path);
+        if blocked {
+            unwritten.push(append.clone());
+            continue;
+        }
+        if let Err(e) = append.write() {
+            warn!(
+                "[spool_appends()]: Could not write to {:}: {e:}",
+                append.note_path.display()
+            );
+            unwritten.push(append.clone());
+        }
+    }
+
+    match spool {
+        Some(spool) => {
+            if let Err(e) = spool.replace(&unwritten) {
+                warn!("[spool_appends()]: Could not update the spool: {e:}");
+            }
+        }
+        None if !unwritten.is_empty() => {
+            warn!(
+                "[spool_appends()]: No state directory to spool {:} unwritten append(s).",
+                unwritten.len()
+            );
+        }
+        None => (),
+    }
+    unwritten.len()
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod fan_out_tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn weekly_append(vault: &Path, text: &str) -> PendingAppend {
+        PendingAppend {
+            note_path: vault.join("Weekly/2025-W03.md"),
+            header: "# 2025-W03\n\n".to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_commit_appends_spools_failed_appends_until_they_succeed() {
+        let state_dir = tempdir().unwrap();
+        let vault = tempdir().unwrap();
+        let spool = Spool::new(state_dir.path());
+        // A file where the weekly directory should be makes the appends fail.
+        fs::write(vault.path().join("Weekly"), "not a directory").unwrap();
+
+        let first = weekly_append(vault.path(), "- first\n");
+        assert_eq!(
+            commit_appends(std::slice::from_ref(&first), Some(&spool)),
+            1
+        );
+        let second = weekly_append(vault.path(), "- second\n");
+        assert_eq!(
+            commit_appends(std::slice::from_ref(&second), Some(&spool)),
+            2
+        );
+        assert_eq!(spool.pending().unwrap(), vec![first, second]);
+
+        fs::remove_file(vault.path().join("Weekly")).unwrap();
+        let third = weekly_append(vault.path(), "- third\n");
+        assert_eq!(commit_appends(&[third], Some(&spool)), 0);
+
+        assert_eq!(
+            fs::read_to_string(vault.path().join("Weekly/2025-W03.md")).unwrap(),
+            "# 2025-W03\n\n- first\n- second\n- third\n"
+        );
+        assert!(spool.pending().unwrap().is_empty());
+        assert!(!state_dir.path().join(SPOOL_FILE_NAME).exists());
+    }
+
+    #[test]
+    fn test_commit_rows_retries_spooled_rows_until_they_are_saved() {
+        let state_dir = tempdir().unwrap();
+        let spool = Spool::rows(state_dir.path());
+        let mut commit_saver = CommitSaver::from(CommitEntry {
+            repository_url: "https://github.com/user/repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: "abc123".to_string(),
+            message: "feat: add login".to_string(),
+            datetime: chrono::Utc::now(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        });
+        commit_saver.details.workdir = Some(PathBuf::from("/src/repo"));
+        let row = PendingRow::new(
+            &commit_saver,
+            Path::new("/vaults/work"),
+            Path::new("Commits"),
+            "%Y/%m-%B/%F.md",
+        );
+        assert_eq!(
+            row.commit_saver().details.workdir,
+            commit_saver.details.workdir
+        );
+
+        let fail = |_: &PendingRow| Err("vault unavailable".into());
+        assert_eq!(commit_rows(&spool, std::slice::from_ref(&row), fail), 1);
+        assert_eq!(commit_rows(&spool, &[], fail), 1);
+        assert_eq!(spool.pending().unwrap(), vec![row.clone()]);
+
+        let mut saved = Vec::new();
+        let succeed = |row: &PendingRow| {
+            saved.push(row.entry.hash.clone());
+            Ok(())
+        };
+        assert_eq!(commit_rows(&spool, &[], succeed), 0);
+        assert_eq!(saved, ["abc123"]);
+        assert!(!state_dir.path().join(ROW_SPOOL_FILE_NAME).exists());
+    }
+
+    #[test]
+    fn test_commit_appends_without_spool_reports_unwritten_appends() {
+        let vault = tempdir().unwrap();
+        fs::write(vault.path().join("Weekly"), "not a directory").unwrap();
+
+        assert_eq!(
+            commit_appends(&[weekly_append(vault.path(), "- row\n")], None),
+            1
+        );
+    }
+}
@@ -0,0 +1,478 @@
+use std::error::Error;
+use std::path::Path;
+
+use log::info;
+
+use crate::atomic_write::write_atomically;
+
+/// Byte order mark some editors put in front of a note's first line.
+const BYTE_ORDER_MARK: char = '\u{feff}';
+
+/// Adds values to a YAML list in the frontmatter of a note.
+///
+/// Diary files are created once per day, but later entries of the same day
+/// may bring new values (e.g. another category). This merges them into the
+/// `key:` list of the leading `---` frontmatter block, written in the same
+/// `- 'value'` style as the diary template. Values already present (quoted or
+/// not) are kept once, and everything after the closing `---` is left
+/// byte-for-byte untouched.
+///
+/// Lists indented the way Obsidian writes them (`  - daily`) keep their
+/// indentation, and inline values (`tags: daily`, `tags: [daily, work]`) are
+/// turned into a block list holding the old values first. When the key does
+/// not exist yet, it is added after the last property.
+///
+/// # Returns
+///
+/// - `Some(content)` - The updated note
+/// - `None` - Nothing to add, or the note has no frontmatter block
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::frontmatter::merge_frontmatter_list;
+///
+/// let note = "---\ntags:\n- '#diary/commits'\n---\n# 2025-01-14\n";
+/// let merged = merge_frontmatter_list(note, "tags", &["#category/work".to_string()]).unwrap();
+/// assert_eq!(merged, "---\ntags:\n- '#diary/commits'\n- '#category/work'\n---\n# 2025-01-14\n");
+/// ```
+#[must_use]
+pub fn merge_frontmatter_list(content: &str, key: &str, values: &[String]) -> Option<String> {
+    let (front, body) = split_frontmatter(content)?;
+    let lines = front.split_inclusive('\n').collect::<Vec<_>>();
+    let newline = line_ending(lines[0]);
+    let block = &lines[1..lines.len() - 1];
+
+    let key_line = format!("{key:}:");
+    let key_index = block.iter().position(|line| {
+        line.split_once(':')
+            .is_some_and(|(line_key, _)| line_key == key)
+    });
+    let inline = key_index.map_or("", |index| block[index].trim_end()[key_line.len()..].trim());
+    let items = key_index.map_or(&[][..], |index| {
+        let count = block[index + 1..]
+            .iter()
+            .take_while(|line| line.trim_start().starts_with("- "))
+            .count();
+        &block[index + 1..index + 1 + count]
+    });
+    let indent = items
+        .first()
+        .map_or("", |line| &line[..line.len() - line.trim_start().len()]);
+    let existing = if inline.is_empty() {
+        items
+            .iter()
+            .map(|line| unquote(line.trim()[2..].trim()))
+            .collect::<Vec<_>>()
+    } else {
+        inline_list(inline)
+    };
+
+    let mut missing = Vec::new();
+    for value in values {
+        if !existing.contains(&value.as_str()) && !missing.contains(value) {
+            missing.push(value.clone());
+        }
+    }
+    if missing.is_empty() {
+        return None;
+    }
+
+    let mut inserted = String::new();
+    let mut push_item = |value: &str, quote: bool| {
+        inserted.push_str(indent);
+        inserted.push_str("- ");
+        if quote {
+            inserted.push('\'');
+            inserted.push_str(value);
+            inserted.push('\'');
+        } else {
+            inserted.push_str(value);
+        }
+        inserted.push_str(newline);
+    };
+    if !inline.is_empty() {
+        for value in &existing {
+            push_item(value, value.starts_with('#'));
+        }
+    }
+    for value in &missing {
+        push_item(value, true);
+    }
+    let (insert_at, resume_at) = match key_index {
+        Some(index) if !inline.is_empty() => {
+            inserted.insert_str(0, &format!("{key_line:}{newline:}"));
+            (1 + index, 1 + index + 1)
+        }
+        Some(index) => {
+            let end = 1 + index + 1 + items.len();
+            (end, end)
+        }
+        None => {
+            inserted.insert_str(0, &format!("{key_line:}{newline:}"));
+            let end = 1 + block
+                .iter()
+                .rposition(|line| !line.trim().is_empty())
+                .map_or(0, |index| index + 1);
+            (end, end)
+        }
+    };
+
+    let mut merged = String::with_capacity(content.len() + inserted.len());
+    for line in &lines[..insert_at] {
+        merged.push_str(line);
+    }
+    merged.push_str(&inserted);
+    for line in &lines[resume_at..] {
+        merged.push_str(line);
+    }
+    merged.push_str(body);
+
+    Some(merged)
+}
+
+/// Splits an inline YAML list value (`[daily, work]`, or a bare `daily`)
+/// into its unquoted values.
+fn inline_list(value: &str) -> Vec<&str> {
+    let inner = value
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .unwrap_or(value);
+    inner
+        .split(',')
+        .map(unquote)
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+/// Returns the value of a `key: value` property of the leading frontmatter block.
+///
+/// Quotes around the value are stripped; list properties have no inline value
+/// and return `Some("")`.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::frontmatter::frontmatter_property;
+///
+/// assert_eq!(frontmatter_property("---\ndone: 13\n---\n", "done"), Some("13"));
+/// ```
+#[must_use]
+pub fn frontmatter_property<'a>(content: &'a str, key: &str) -> Option<&'a str> {
+    let block = frontmatter_block(content)?;
+    block.iter().find_map(|line| {
+        let (line_key, value) = line.split_once(':')?;
+        (line_key == key).then(|| unquote(value))
+    })
+}
+
+/// Sets a `key: value` property in the leading frontmatter block of a note.
+///
+/// An existing property is replaced in place, a new one is added after the
+/// last property. A note without frontmatter gets a new block holding only
+/// this property; either way the rest of the note is left byte-for-byte
+/// untouched.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::frontmatter::set_frontmatter_property;
+///
+/// let note = set_frontmatter_property("# Week 3\n", "goal", "20");
+/// assert_eq!(set_frontmatter_property(&note, "goal", "25"), "---\ngoal: 25\n---\n# Week 3\n");
+/// ```
+#[must_use]
+pub fn set_frontmatter_property(content: &str, key: &str, value: &str) -> String {
+    let Some((front, body)) = split_frontmatter(content) else {
+        let (bom, rest) = match content.strip_prefix(BYTE_ORDER_MARK) {
+            Some(rest) => (&content[..BYTE_ORDER_MARK.len_utf8()], rest),
+            None => ("", content),
+        };
+        return format!("{bom:}---\n{key:}: {value:}\n---\n{rest:}");
+    };
+    let lines = front.split_inclusive('\n').collect::<Vec<_>>();
+    let property = format!("{key:}: {value:}{:}", line_ending(lines[0]));
+    let block = &lines[1..lines.len() - 1];
+
+    let key_prefix = format!("{key:}:");
+    let key_index = block.iter().position(|line| line.starts_with(&key_prefix));
+    let insert_at = 1 + key_index.unwrap_or_else(|| {
+        block
+            .iter()
+            .rposition(|line| !line.trim().is_empty())
+            .map_or(0, |index| index + 1)
+    });
+    let resume_at = insert_at + usize::from(key_index.is_some());
+
+    let mut updated = String::with_capacity(content.len() + property.len());
+    for line in &lines[..insert_at] {
+        updated.push_str(line);
+    }
+    updated.push_str(&property);
+    for line in &lines[resume_at..] {
+        updated.push_str(line);
+    }
+    updated.push_str(body);
+    updated
+}
+
+/// Splits a note into its leading frontmatter block and the rest of it.
+///
+/// The first part runs from the start of the note (byte order mark included)
+/// through the line ending of the closing `---`; the second part is every
+/// byte after it, so `front + body` is always the original note.
+///
+/// # Returns
+///
+/// `None` if the note does not start with a closed `---` block.
+#[must_use]
+pub fn split_frontmatter(content: &str) -> Option<(&str, &str)> {
+    let start = if content.starts_with(BYTE_ORDER_MARK) {
+        BYTE_ORDER_MARK.len_utf8()
+    } else {
+        0
+    };
+    let mut lines = content[start..].split_inclusive('\n');
+    let opening = lines.next()?;
+    if opening.trim_end() != "---" {
+        return None;
+    }
+
+    let mut end = start + opening.len();
+    for line in lines {
+        end += line.len();
+        if line.trim_end() == "---" {
+            return Some(content.split_at(end));
+        }
+    }
+    None
+}
+
+/// Writes a frontmatter update of the note at `path`, for vaults kept in
+/// sync (Obsidian Sync, iCloud, Syncthing).
+///
+/// `updated` is expected to come from `original` through
+/// [`merge_frontmatter_list()`] or [`set_frontmatter_property()`]: nothing is
+/// written when it is unchanged, and it is refused when the bytes after the
+/// frontmatter differ, so a sync service never sees an edit to the note body.
+///
+/// # Returns
+///
+/// `true` if the note was written.
+///
+/// # Errors
+///
+/// Returns an error if `updated` changes the note body, or if the note cannot
+/// be written.
+pub fn write_frontmatter_update(
+    path: &Path,
+    original: &str,
+    updated: &str,
+) -> Result<bool, Box<dyn Error>> {
+    if updated == original {
+        return Ok(false);
+    }
+    if note_body(updated) != note_body(original) {
+        return Err(format!(
+            "Refusing to update the frontmatter of {:}: the note body would change",
+            path.display()
+        )
+        .into());
+    }
+
+    info!(
+        "[write_frontmatter_update()]: Updating the frontmatter of: {:}",
+        path.display()
+    );
+    write_atomically(path, updated)?;
+    Ok(true)
+}
+
+/// Returns the part of a note after its frontmatter (the whole note, less a
+/// byte order mark, when it has none).
+fn note_body(content: &str) -> &str {
+    split_frontmatter(content).map_or_else(
+        || content.strip_prefix(BYTE_ORDER_MARK).unwrap_or(content),
+        |(_, body)| body,
+    )
+}
+
+/// Returns the line ending of `line` (`\r\n` or `\n`), so inserted
+/// properties match the ones around them.
+fn line_ending(line: &str) -> &'static str {
+    if line.ends_with("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+/// Returns the lines inside the leading `---` frontmatter block, without line endings.
+fn frontmatter_block(content: &str) -> Option<Vec<&str>> {
+    let (front, _) = split_frontmatter(content)?;
+    let lines = front.lines().collect::<Vec<_>>();
+    Some(lines[1..lines.len() - 1].to_vec())
+}
+
+/// Strips one pair of matching single or double quotes around a YAML scalar.
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    for quote in ['\'', '"'] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod frontmatter_tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    const NOTE: &str = "---\ncategory: diary\n\ntags:\n- '#diary/commits'\n- \"#category/work\"\ndate: 2025-01-14\n\n---\n\n# 2025-01-14\n| row |\n";
+
+    #[test]
+    fn test_merge_frontmatter_list_appends_to_existing_list() {
+        let merged = merge_frontmatter_list(
+            NOTE,
+            "tags",
+            &["#category/work".to_string(), "#category/oss".to_string()],
+        )
+        .unwrap();
+
+        assert!(merged.contains("- \"#category/work\"\n- '#category/oss'\ndate: 2025-01-14"));
+        assert!(merged.ends_with("\n---\n\n# 2025-01-14\n| row |\n"));
+    }
+
+    #[test]
+    fn test_merge_frontmatter_list_adds_missing_key() {
+        let merged = merge_frontmatter_list(NOTE, "categories", &["oss".to_string()]).unwrap();
+
+        assert!(merged.contains("date: 2025-01-14\ncategories:\n- 'oss'\n\n---\n"));
+    }
+
+    #[test]
+    fn test_merge_frontmatter_list_keeps_obsidian_indentation() {
+        let note = "---\ntags:\n  - daily\n  - \"journal\"\ncreated: 2025-01-14\n---\nBody\n";
+        let merged = merge_frontmatter_list(
+            note,
+            "tags",
+            &["journal".to_string(), "#diary/commits".to_string()],
+        )
+        .unwrap();
+
+        assert_eq!(
+            merged,
+            "---\ntags:\n  - daily\n  - \"journal\"\n  - '#diary/commits'\ncreated: 2025-01-14\n---\nBody\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_frontmatter_list_expands_inline_values() {
+        let values = ["#diary/commits".to_string()];
+        assert_eq!(
+            merge_frontmatter_list("---\ntags: [daily, 'work']\n---\n", "tags", &values).unwrap(),
+            "---\ntags:\n- daily\n- work\n- '#diary/commits'\n---\n"
+        );
+        assert_eq!(
+            merge_frontmatter_list("---\ntags: daily\nday: 1\n---\n", "tags", &values).unwrap(),
+            "---\ntags:\n- daily\n- '#diary/commits'\nday: 1\n---\n"
+        );
+        assert_eq!(
+            merge_frontmatter_list("---\ntags: [daily]\n---\n", "tags", &["daily".to_string()]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_frontmatter_property() {
+        assert_eq!(frontmatter_property(NOTE, "date"), Some("2025-01-14"));
+        assert_eq!(frontmatter_property(NOTE, "tags"), Some(""));
+        assert_eq!(frontmatter_property(NOTE, "goal"), None);
+        assert_eq!(frontmatter_property("# No frontmatter\n", "date"), None);
+    }
+
+    #[test]
+    fn test_set_frontmatter_property() {
+        let replaced = set_frontmatter_property(NOTE, "date", "2025-01-15");
+        assert_eq!(
+            replaced,
+            NOTE.replace("2025-01-14\n\n---", "2025-01-15\n\n---")
+        );
+
+        let added = set_frontmatter_property(NOTE, "done", "13");
+        assert!(added.contains("date: 2025-01-14\ndone: 13\n\n---\n\n# 2025-01-14\n"));
+
+        assert_eq!(
+            set_frontmatter_property("# Week\n", "goal", "20"),
+            "---\ngoal: 20\n---\n# Week\n"
+        );
+    }
+
+    #[test]
+    fn test_frontmatter_updates_keep_body_bytes() {
+        let body = "\r\n# 2025-01-14  \r\n| row |\n\n---\nnot: frontmatter\n\t\u{e9}\u{0}";
+        let note = format!("\u{feff}---\r\ntags:\r\n- '#diary/commits'\r\n---\r\n{body:}");
+
+        let merged =
+            merge_frontmatter_list(&note, "tags", &["#category/work".to_string()]).unwrap();
+        assert!(merged.ends_with(body));
+        assert_eq!(
+            split_frontmatter(&merged).unwrap().0,
+            "\u{feff}---\r\ntags:\r\n- '#diary/commits'\r\n- '#category/work'\r\n---\r\n"
+        );
+
+        let updated = set_frontmatter_property(&merged, "done", "3");
+        assert_eq!(
+            split_frontmatter(&updated).unwrap().1.as_bytes(),
+            body.as_bytes()
+        );
+        assert!(updated.contains("- '#category/work'\r\ndone: 3\r\n---\r\n"));
+
+        let created = set_frontmatter_property("\u{feff}# Week\n", "goal", "20");
+        assert_eq!(created, "\u{feff}---\ngoal: 20\n---\n# Week\n");
+        assert_eq!(split_frontmatter("---\nno: end\n"), None);
+        assert_eq!(
+            split_frontmatter("---\na: 1\n---"),
+            Some(("---\na: 1\n---", ""))
+        );
+    }
+
+    #[test]
+    fn test_write_frontmatter_update_only_touches_frontmatter() {
+        let temp_dir = tempdir().unwrap();
+        let note = temp_dir.path().join("2025-01-14.md");
+        fs::write(&note, NOTE).unwrap();
+
+        assert!(!write_frontmatter_update(&note, NOTE, NOTE).unwrap());
+
+        let merged = merge_frontmatter_list(NOTE, "tags", &["#lang/rust".to_string()]).unwrap();
+        assert!(write_frontmatter_update(&note, NOTE, &merged).unwrap());
+        assert_eq!(fs::read_to_string(&note).unwrap(), merged);
+
+        let edited_body = merged.replace("| row |", "| row edited |");
+        assert!(write_frontmatter_update(&note, &merged, &edited_body).is_err());
+        assert_eq!(fs::read_to_string(&note).unwrap(), merged);
+
+        let created = set_frontmatter_property("# Week\n", "goal", "20");
+        assert!(write_frontmatter_update(&note, "# Week\n", &created).unwrap());
+    }
+
+    #[test]
+    fn test_merge_frontmatter_list_without_changes_is_none() {
+        assert_eq!(
+            merge_frontmatter_list(NOTE, "tags", &["#diary/commits".to_string()]),
+            None
+        );
+        assert_eq!(
+            merge_frontmatter_list("# No frontmatter\n", "tags", &["a".to_string()]),
+            None
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use std::env;
+use std::error::Error;
+use std::ffi::OsString;
+use std::path::Path;
+
+use git2::BranchType;
+use git2::Oid;
+use git2::Repository;
+use git2::Sort;
+use log::info;
+
+use crate::push::ZERO_HASH;
+
+/// Opens the repository a Git hook runs for.
+///
+/// Git exports `GIT_DIR` (and `GIT_WORK_TREE` when it was given one) to its
+/// hooks. When set, the repository is opened from them directly instead of
+/// being discovered from the current directory: it is faster, and right in
+/// the cases discovery gets wrong (server-side hooks of bare repositories,
+/// worktrees, `git --git-dir=... commit`). Without them, the repository is
+/// discovered from the current directory as before.
+///
+/// # Errors
+///
+/// Returns an error if the repository cannot be opened or discovered.
+pub fn hook_repository() -> Result<Repository, git2::Error> {
+    hook_repository_with(
+        env::var_os("GIT_DIR"),
+        env::var_os("GIT_WORK_TREE"),
+        Path::new("./"),
+    )
+}
+
+/// Opens the repository of `git_dir` (with `work_tree` as its working
+/// directory when given), or discovers the one around `path`.
+///
+/// This is [`hook_repository()`] with the environment passed in, for tests.
+///
+/// # Errors
+///
+/// Returns an error if the repository cannot be opened or discovered.
+pub fn hook_repository_with(
+    git_dir: Option<OsString>,
+    work_tree: Option<OsString>,
+    path: &Path,
+) -> Result<Repository, git2::Error> {
+    let Some(git_dir) = git_dir.filter(|git_dir| !git_dir.is_empty()) else {
+        info!("[hook_repository()]: No GIT_DIR, discovering the repository.");
+        return Repository::discover(path);
+    };
+
+    info!(
+        "[hook_repository()]: Opening GIT_DIR: {:}",
+        Path::new(&git_dir).display()
+    );
+    let git_repo = Repository::open(&git_dir)?;
+    if let Some(work_tree) = work_tree.filter(|work_tree| !work_tree.is_empty()) {
+        git_repo.set_workdir(Path::new(&work_tree), false)?;
+    }
+    Ok(git_repo)
+}
+
+/// One ref updated by a push, as read from the `post-receive` hook input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceivedRef {
+    pub old_hash: String,
+    pub new_hash: String,
+    pub ref_name: String,
+}
+
+impl ReceivedRef {
+    /// Returns the branch the push updated, `None` for tags and other refs.
+    #[must_use]
+    pub fn branch(&self) -> Option<&str> {
+        self.ref_name.strip_prefix("refs/heads/")
+    }
+
+    /// Returns `true` if the push deleted the ref.
+    #[must_use]
+    pub fn is_deletion(&self) -> bool {
+        self.new_hash == ZERO_HASH
+    }
+}
+
+/// Parses the lines Git writes to the `post-receive` hook's stdin.
+///
+/// Each line reads `<old hash> <new hash> <ref name>`; lines with another
+/// number of fields are skipped.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::git_env::parse_post_receive_input;
+///
+/// let received = parse_post_receive_input("abc123 def456 refs/heads/main\n");
+/// assert_eq!(received[0].branch(), Some("main"));
+/// ```
+#[must_use]
+pub fn parse_post_receive_input(input: &str) -> Vec<ReceivedRef> {
+    input
+        .lines()
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            match fields[..] {
+                [old_hash, new_hash, ref_name] => Some(ReceivedRef {
+                    old_hash: old_hash.to_string(),
+                    new_hash: new_hash.to_string(),
+                    ref_name: ref_name.to_string(),
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Lists the commits a push brought to a branch.
+///
+/// For an updated branch this is `<old hash>..<new hash>`. For a new branch,
+/// the commits already on another branch before the push are left out, so
+/// pushing a branch made from `main` only logs its own commits. `pushed` is
+/// every ref of the push: the other branches it updated only hide their old
+/// commits, since Git has already moved them when the hook runs.
+///
+/// # Returns
+///
+/// The hashes of the pushed commits, newest first (the `git rev-list` order
+/// [`collect_commit_batch()`](crate::batch::collect_commit_batch) expects);
+/// empty for a deleted ref.
+///
+/// # Errors
+///
+/// Returns an error if the new hash is not a commit of the repository, or if
+/// the branches cannot be listed.
+pub fn received_commits(
+    git_repo: &Repository,
+    received: &ReceivedRef,
+    pushed: &[ReceivedRef],
+) -> Result<Vec<String>, Box<dyn Error>> {
+    if received.is_deletion() {
+        return Ok(Vec::new());
+    }
+
+    let mut revwalk = git_repo.revwalk()?;
+    revwalk.set_sorting(Sort::TIME)?;
+    revwalk.push(Oid::from_str(&received.new_hash)?)?;
+    let old_commit = |received: &ReceivedRef| {
+        Oid::from_str(&received.old_hash)
+            .ok()
+            .filter(|_| received.old_hash != ZERO_HASH)
+            .filter(|oid| git_repo.find_commit(*oid).is_ok())
+    };
+    if let Some(oid) = old_commit(received) {
+        revwalk.hide(oid)?;
+    } else {
+        for branch in git_repo.branches(Some(BranchType::Local))? {
+            let (branch, _) = branch?;
+            let ref_name = branch.get().name().ok();
+            if pushed
+                .iter()
+                .any(|pushed_ref| Some(pushed_ref.ref_name.as_str()) == ref_name)
+            {
+                continue;
+            }
+            if let Some(oid) = branch.get().target() {
+                revwalk.hide(oid)?;
+            }
+        }
+        for oid in pushed.iter().filter_map(old_commit) {
+            revwalk.hide(oid)?;
+        }
+    }
+
+    Ok(revwalk
+        .map(|oid| oid.map(|oid| oid.to_string()))
+        .collect::<Result<Vec<_>, _>>()?)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod git_env_tests {
+    use super::*;
+    use crate::testing::FakeRepo;
+    use chrono::TimeZone;
+    use chrono::Utc;
+    use tempfile::tempdir;
+
+    fn commit_on(repo: &FakeRepo, update_ref: &str, seconds: i64) -> Result<Oid, Box<dyn Error>> {
+        repo.commit_on(update_ref, "work", Utc.timestamp_opt(seconds, 0).unwrap())
+    }
+
+    #[test]
+    fn test_hook_repository_with_prefers_git_dir() {
+        let hooked = tempdir().unwrap();
+        let elsewhere = tempdir().unwrap();
+        Repository::init(hooked.path()).unwrap();
+        Repository::init(elsewhere.path()).unwrap();
+        let work_tree = tempdir().unwrap();
+
+        let git_repo = hook_repository_with(
+            Some(hooked.path().join(".git").into_os_string()),
+            Some(work_tree.path().as_os_str().to_os_string()),
+            elsewhere.path(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            git_repo.path().canonicalize().unwrap(),
+            hooked.path().join(".git").canonicalize().unwrap()
+        );
+        assert_eq!(
+            git_repo.workdir().unwrap().canonicalize().unwrap(),
+            work_tree.path().canonicalize().unwrap()
+        );
+
+        let discovered =
+            hook_repository_with(Some(OsString::new()), None, elsewhere.path()).unwrap();
+        assert_eq!(
+            discovered.workdir().unwrap().canonicalize().unwrap(),
+            elsewhere.path().canonicalize().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_received_commits_lists_the_pushed_commits() -> Result<(), Box<dyn Error>> {
+        let repo = FakeRepo::new()?;
+        let git_repo = repo.repo();
+        let base = 1_736_848_800;
+        let first = commit_on(&repo, "refs/heads/main", base)?;
+        let second = commit_on(&repo, "refs/heads/main", base + 60)?;
+        let third = commit_on(&repo, "refs/heads/main", base + 120)?;
+        git_repo.reference("refs/heads/feature", third, false, "branch")?;
+        let feature = commit_on(&repo, "refs/heads/feature", base + 180)?;
+
+        let input = format!(
+            "{first:} {third:} refs/heads/main\n{ZERO_HASH:} {feature:} refs/heads/feature\n{third:} {ZERO_HASH:} refs/heads/gone\nnot an update line\n"
+        );
+        let received = parse_post_receive_input(&input);
+
+        assert_eq!(received.len(), 3);
+        assert_eq!(received[0].branch(), Some("main"));
+        assert_eq!(
+            received_commits(git_repo, &received[0], &received)?,
+            vec![third.to_string(), second.to_string()]
+        );
+        assert_eq!(
+            received_commits(git_repo, &received[1], &received)?,
+            vec![feature.to_string()]
+        );
+        assert!(received[2].is_deletion());
+        assert!(received_commits(git_repo, &received[2], &received)?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,236 @@
+use std::fmt;
+
+use git2::Commit;
+use git2::Repository;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// How much a commit changed, compared to its first parent.
+///
+/// Rendered in the `STATS` column as `+X/−Y (N files)`, e.g.
+/// `+120/−4 (3 files)`. Root commits are compared to the empty tree; merges
+/// to the branch they were merged into, so only what the merge brought in
+/// counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffStats {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+impl fmt::Display for DiffStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let files = if self.files_changed == 1 {
+            "file"
+        } else {
+            "files"
+        };
+        write!(
+            f,
+            "+{:}/\u{2212}{:} ({:} {files:})",
+            self.insertions, self.deletions, self.files_changed
+        )
+    }
+}
+
+impl DiffStats {
+    /// Lines added plus lines removed.
+    #[must_use]
+    pub fn lines_changed(&self) -> usize {
+        self.insertions + self.deletions
+    }
+}
+
+/// Size class of a commit, from the lines it changed, see [`SizeThresholds`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CommitSize {
+    Xs,
+    S,
+    M,
+    L,
+    Xl,
+}
+
+impl CommitSize {
+    /// Returns the class name, e.g. `m`.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CommitSize::Xs => "xs",
+            CommitSize::S => "s",
+            CommitSize::M => "m",
+            CommitSize::L => "l",
+            CommitSize::Xl => "xl",
+        }
+    }
+
+    /// Returns the tag of the class, e.g. `#size/m`, written to the `SIZE`
+    /// column and the diary frontmatter.
+    #[must_use]
+    pub fn tag(self) -> String {
+        format!("#size/{:}", self.as_str())
+    }
+}
+
+/// Largest number of changed lines (see [`DiffStats::lines_changed()`]) of
+/// each [`CommitSize`] class; larger commits are [`CommitSize::Xl`].
+///
+/// # Configuration
+///
+/// ```text
+/// [size]
+/// enabled = true
+/// xs = 10
+/// s = 50
+/// m = 250
+/// l = 1000
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeThresholds {
+    pub xs: usize,
+    pub s: usize,
+    pub m: usize,
+    pub l: usize,
+}
+
+impl Default for SizeThresholds {
+    fn default() -> Self {
+        SizeThresholds {
+            xs: 10,
+            s: 50,
+            m: 250,
+            l: 1000,
+        }
+    }
+}
+
+impl SizeThresholds {
+    /// Whether every class allows more lines than the previous one.
+    #[must_use]
+    pub fn is_ascending(&self) -> bool {
+        self.xs < self.s && self.s < self.m && self.m < self.l
+    }
+
+    /// Returns the size class of a commit with `stats`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rusty_commit_saver::git_stats::{CommitSize, DiffStats, SizeThresholds};
+    ///
+    /// let stats = DiffStats { files_changed: 2, insertions: 40, deletions: 5 };
+    /// assert_eq!(SizeThresholds::default().classify(stats), CommitSize::S);
+    /// ```
+    #[must_use]
+    pub fn classify(&self, stats: DiffStats) -> CommitSize {
+        let lines = stats.lines_changed();
+        if lines <= self.xs {
+            CommitSize::Xs
+        } else if lines <= self.s {
+            CommitSize::S
+        } else if lines <= self.m {
+            CommitSize::M
+        } else if lines <= self.l {
+            CommitSize::L
+        } else {
+            CommitSize::Xl
+        }
+    }
+}
+
+/// Computes the [`DiffStats`] of `commit` against its first parent.
+///
+/// # Errors
+///
+/// Returns an error if a tree cannot be read or the diff cannot be computed.
+pub fn commit_diff_stats(git_repo: &Repository, commit: &Commit) -> Result<DiffStats, git2::Error> {
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    let diff = git_repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+    let stats = diff.stats()?;
+    Ok(DiffStats {
+        files_changed: stats.files_changed(),
+        insertions: stats.insertions(),
+        deletions: stats.deletions(),
+    })
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod git_stats_tests {
+    use super::*;
+    use crate::testing::FakeRepo;
+    use std::error::Error;
+
+    #[test]
+    fn test_diff_stats_display() {
+        let stats = DiffStats {
+            files_changed: 3,
+            insertions: 120,
+            deletions: 4,
+        };
+        assert_eq!(stats.to_string(), "+120/\u{2212}4 (3 files)");
+        assert_eq!(
+            DiffStats {
+                files_changed: 1,
+                ..stats
+            }
+            .to_string(),
+            "+120/\u{2212}4 (1 file)"
+        );
+    }
+
+    #[test]
+    fn test_size_thresholds_classify() {
+        let thresholds = SizeThresholds::default();
+        let stats = |insertions, deletions| DiffStats {
+            files_changed: 1,
+            insertions,
+            deletions,
+        };
+
+        assert_eq!(thresholds.classify(stats(0, 0)), CommitSize::Xs);
+        assert_eq!(thresholds.classify(stats(6, 4)), CommitSize::Xs);
+        assert_eq!(thresholds.classify(stats(6, 5)), CommitSize::S);
+        assert_eq!(thresholds.classify(stats(200, 50)), CommitSize::M);
+        assert_eq!(thresholds.classify(stats(1000, 0)), CommitSize::L);
+        assert_eq!(thresholds.classify(stats(900, 101)), CommitSize::Xl);
+        assert_eq!(CommitSize::Xl.tag(), "#size/xl");
+        assert!(thresholds.is_ascending());
+        assert!(!SizeThresholds {
+            s: 10,
+            ..thresholds
+        }
+        .is_ascending());
+    }
+
+    #[test]
+    fn test_commit_diff_stats() -> Result<(), Box<dyn Error>> {
+        let repo = FakeRepo::new()?;
+        let root = repo.commit_file("src/lib.rs", "a\nb\nc\n", "Initial commit")?;
+        let root = repo.repo().find_commit(root)?;
+        assert_eq!(
+            commit_diff_stats(repo.repo(), &root)?,
+            DiffStats {
+                files_changed: 1,
+                insertions: 3,
+                deletions: 0,
+            }
+        );
+
+        repo.commit_file("README.md", "# Title\n", "docs: add readme")?;
+        let second = repo.commit_file("src/lib.rs", "a\nB\n", "refactor: shrink")?;
+        let second = repo.repo().find_commit(second)?;
+        assert_eq!(
+            commit_diff_stats(repo.repo(), &second)?,
+            DiffStats {
+                files_changed: 1,
+                insertions: 1,
+                deletions: 2,
+            }
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+use std::fmt;
+
+use git2::ObjectType;
+use git2::Oid;
+use git2::Repository;
+use log::info;
+
+/// The annotated tag closest to a commit, shown in the `TAG` column.
+///
+/// Rendered as the tag name when the commit is the tagged one (`v1.2.0`), or
+/// followed by the number of commits since the tag (`v1.2.0+3`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NearestTag {
+    /// Tag name, without `refs/tags/`.
+    pub name: String,
+
+    /// Commits between the tagged commit and the logged one, `0` when the
+    /// logged commit is the tagged one.
+    pub distance: usize,
+}
+
+impl fmt::Display for NearestTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.distance == 0 {
+            write!(f, "{:}", self.name)
+        } else {
+            write!(f, "{:}+{:}", self.name, self.distance)
+        }
+    }
+}
+
+/// Finds the annotated tag closest to `commit_id` among its ancestors at most
+/// `max_distance` commits away.
+///
+/// Lightweight tags are ignored: they are often local bookmarks rather than
+/// releases. Ties are broken by tag name, so the result does not depend on
+/// the order of the tag list.
+///
+/// # Returns
+///
+/// `None` if no annotated tag points at `commit_id` or at an ancestor close
+/// enough.
+///
+/// # Errors
+///
+/// Returns an error if the tag list cannot be read.
+pub fn nearest_annotated_tag(
+    git_repo: &Repository,
+    commit_id: Oid,
+    max_distance: usize,
+) -> Result<Option<NearestTag>, git2::Error> {
+    let mut nearest: Option<NearestTag> = None;
+    let tag_names = git_repo.tag_names(None)?;
+    for name in tag_names.iter().filter_map(Result::ok).flatten() {
+        let Ok(tag) = git_repo
+            .revparse_single(&format!("refs/tags/{name:}"))
+            .and_then(|object| object.peel(ObjectType::Tag))
+        else {
+            continue;
+        };
+        let Ok(tagged) = tag.peel(ObjectType::Commit) else {
+            continue;
+        };
+
+        let distance = if tagged.id() == commit_id {
+            0
+        } else {
+            match git_repo.graph_ahead_behind(commit_id, tagged.id()) {
+                Ok((ahead, 0)) => ahead,
+                _ => continue,
+            }
+        };
+        if distance > max_distance {
+            continue;
+        }
+
+        let candidate = NearestTag {
+            name: name.to_string(),
+            distance,
+        };
+        if nearest.as_ref().is_none_or(|current| {
+            (candidate.distance, &candidate.name) < (current.distance, &current.name)
+        }) {
+            nearest = Some(candidate);
+        }
+    }
+
+    if let Some(tag) = &nearest {
+        info!("[nearest_annotated_tag()]: Found the tag {tag:} for {commit_id:}");
+    }
+    Ok(nearest)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod git_tags_tests {
+    use super::*;
+    use crate::testing::FakeRepo;
+    use std::error::Error;
+
+    #[test]
+    fn test_nearest_tag_display() {
+        let tag = NearestTag {
+            name: "v1.2.0".to_string(),
+            distance: 0,
+        };
+        assert_eq!(tag.to_string(), "v1.2.0");
+        assert_eq!(NearestTag { distance: 3, ..tag }.to_string(), "v1.2.0+3");
+    }
+
+    #[test]
+    fn test_nearest_annotated_tag() -> Result<(), Box<dyn Error>> {
+        let repo = FakeRepo::new()?;
+        repo.commit("Initial commit")?;
+        repo.tag("v0.1.0")?;
+        let release = repo.commit("chore: release 1.0.0")?;
+        repo.tag("v1.0.0")?;
+        repo.lightweight_tag("bookmark")?;
+        let first = repo.commit("fix: bug")?;
+        let second = repo.commit("fix: another bug")?;
+
+        assert_eq!(
+            nearest_annotated_tag(repo.repo(), release, 0)?,
+            Some(NearestTag {
+                name: "v1.0.0".to_string(),
+                distance: 0,
+            })
+        );
+        assert_eq!(
+            nearest_annotated_tag(repo.repo(), second, 2)?,
+            Some(NearestTag {
+                name: "v1.0.0".to_string(),
+                distance: 2,
+            })
+        );
+        assert_eq!(nearest_annotated_tag(repo.repo(), first, 0)?, None);
+        Ok(())
+    }
+}
@@ -0,0 +1,342 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::DateTime;
+use chrono::NaiveDate;
+use chrono::Utc;
+use log::info;
+use serde::Deserialize;
+
+use crate::commit_entry::normalize_message;
+use crate::commit_entry::CommitEntry;
+use crate::diary_parser::parse_diary_table;
+use crate::same_day::diary_files_by_date;
+use crate::state::LoggedEntry;
+use crate::vim_commit::CommitSaver;
+use crate::vim_commit::TableColumn;
+
+/// Base URL of the GitHub REST API.
+pub const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// Environment variable holding an optional GitHub token.
+///
+/// Public commits are found without one; a token only raises the search rate
+/// limit. It is sent as is and needs no scope.
+pub const GITHUB_TOKEN_ENV_VAR: &str = "GITHUB_TOKEN";
+
+/// How many days back `import --github-user` looks by default.
+pub const DEFAULT_IMPORT_DAYS: i64 = 30;
+
+/// Results per search page, the maximum the API allows.
+const PER_PAGE: usize = 100;
+
+/// The commit search API never returns more than 1000 results.
+const MAX_PAGES: usize = 10;
+
+/// A public commit of the user, as found by the GitHub commit search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GithubCommit {
+    pub sha: String,
+    pub message: String,
+    /// The committer date, like the post-commit hook would have logged.
+    pub datetime: DateTime<Utc>,
+    /// The clone URL of the repository (`https://github.com/<owner>/<repo>.git`).
+    pub repository_url: String,
+}
+
+impl GithubCommit {
+    /// Builds the diary entry of the commit.
+    ///
+    /// The search API does not tell which branch a commit was made on, so the
+    /// `BRANCH` cell stays empty.
+    #[must_use]
+    pub fn to_commit_saver(&self) -> CommitSaver {
+        CommitSaver::from(CommitEntry {
+            repository_url: self.repository_url.clone(),
+            branch: String::new(),
+            hash: self.sha.clone(),
+            message: normalize_message(&self.message),
+            datetime: self.datetime,
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct SearchPage {
+    items: Vec<SearchItem>,
+}
+
+#[derive(Deserialize)]
+struct SearchItem {
+    sha: String,
+    commit: SearchCommit,
+    repository: SearchRepository,
+}
+
+#[derive(Deserialize)]
+struct SearchCommit {
+    message: String,
+    committer: SearchSignature,
+}
+
+#[derive(Deserialize)]
+struct SearchSignature {
+    date: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+struct SearchRepository {
+    html_url: String,
+}
+
+/// Returns the commit search URL of one results page.
+///
+/// Commits authored by `user` and committed on or after `since` are asked
+/// for, oldest first.
+#[must_use]
+pub fn search_url(api_url: &str, user: &str, since: NaiveDate, page: usize) -> String {
+    format!(
+        "{:}/search/commits?q=author:{user:}+committer-date:%3E%3D{:}&sort=committer-date&order=asc&per_page={PER_PAGE:}&page={page:}",
+        api_url.trim_end_matches('/'),
+        since.format("%F")
+    )
+}
+
+/// Parses one page of commit search results.
+///
+/// # Errors
+///
+/// Returns an error if the body is not a commit search response.
+pub fn parse_search_page(body: &str) -> Result<Vec<GithubCommit>, Box<dyn Error>> {
+    let page: SearchPage = serde_json::from_str(body)?;
+    Ok(page
+        .items
+        .into_iter()
+        .map(|item| GithubCommit {
+            sha: item.sha,
+            message: item.commit.message,
+            datetime: item.commit.committer.date,
+            repository_url: format!("{:}.git", item.repository.html_url),
+        })
+        .collect())
+}
+
+/// Fetches the public commits `user` authored since `since`.
+///
+/// Pages through the commit search API until a page comes back short. A
+/// commit found in several repositories (e.g. in forks) is kept once, in
+/// the first repository it was found in.
+///
+/// # Returns
+///
+/// The commits, oldest first.
+///
+/// # Errors
+///
+/// Returns an error if a request fails (e.g. the rate limit is hit) or a
+/// response cannot be parsed.
+pub fn fetch_github_commits(
+    api_url: &str,
+    user: &str,
+    since: NaiveDate,
+    token: Option<&str>,
+) -> Result<Vec<GithubCommit>, Box<dyn Error>> {
+    let mut commits = Vec::new();
+    let mut seen = HashSet::new();
+    for page in 1..=MAX_PAGES {
+        let url = search_url(api_url, user, since, page);
+        info!("[fetch_github_commits()]: Fetching: {url:}");
+        let mut request = ureq::get(&url)
+            .header("Accept", "application/vnd.github+json")
+            .header("User-Agent", env!("CARGO_PKG_NAME"));
+        if let Some(token) = token {
+            request = request.header("Authorization", &format!("Bearer {token:}"));
+        }
+        let body = request.call()?.body_mut().read_to_string()?;
+
+        let page_commits = parse_search_page(&body)?;
+        let count = page_commits.len();
+        commits.extend(
+            page_commits
+                .into_iter()
+                .filter(|commit| seen.insert(commit.sha.clone())),
+        );
+        if count < PER_PAGE {
+            break;
+        }
+    }
+
+    commits.sort_by_key(|commit| commit.datetime);
+    info!(
+        "[fetch_github_commits()]: Found {:} commit(s) of {user:}.",
+        commits.len()
+    );
+    Ok(commits)
+}
+
+/// Collects the commit hashes already in the diary: those of the
+/// logged-commits index and the `COMMIT HASH` cells of every diary table under
+/// the commits roots.
+///
+/// The diary tables are read too, since commits logged on other machines
+/// (or before the index existed) are only there.
+///
+/// # Errors
+///
+/// Returns an error if a commits root or a diary file cannot be read.
+pub fn existing_hashes(
+    commits_roots: &[PathBuf],
+    logged_entries: &[LoggedEntry],
+) -> Result<HashSet<String>, Box<dyn Error>> {
+    let mut hashes = logged_entries
+        .iter()
+        .map(|entry| entry.commit_hash.clone())
+        .collect::<HashSet<_>>();
+    for commits_root in commits_roots {
+        for path in diary_files_by_date(commits_root)?.into_values().flatten() {
+            let Some(table) = parse_diary_table(&fs::read_to_string(&path)?) else {
+                continue;
+            };
+            hashes.extend(
+                table
+                    .rows
+                    .iter()
+                    .filter_map(|row| table.cell(row, TableColumn::CommitHash))
+                    .filter(|hash| !hash.is_empty())
+                    .map(ToString::to_string),
+            );
+        }
+    }
+    Ok(hashes)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod github_tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::io::Read;
+    use std::io::Write;
+    use std::net::TcpListener;
+    use std::thread;
+    use tempfile::tempdir;
+
+    const SEARCH_PAGE: &str = r#"{
+        "total_count": 3,
+        "items": [
+            {
+                "sha": "b2",
+                "commit": {
+                    "message": "fix: a | b\n\nDetails",
+                    "committer": {"date": "2025-01-14T11:00:00.000+01:00"}
+                },
+                "repository": {"html_url": "https://github.com/user/api"}
+            },
+            {
+                "sha": "a1",
+                "commit": {
+                    "message": "feat: start",
+                    "committer": {"date": "2025-01-13T09:00:00Z"}
+                },
+                "repository": {"html_url": "https://github.com/user/web"}
+            },
+            {
+                "sha": "b2",
+                "commit": {
+                    "message": "fix: a | b",
+                    "committer": {"date": "2025-01-14T10:00:00Z"}
+                },
+                "repository": {"html_url": "https://github.com/fork/api"}
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_search_url() {
+        let since = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+
+        assert_eq!(
+            search_url("https://api.github.com/", "octocat", since, 2),
+            "https://api.github.com/search/commits?q=author:octocat+committer-date:%3E%3D2025-01-01&sort=committer-date&order=asc&per_page=100&page=2"
+        );
+    }
+
+    #[test]
+    fn test_parse_search_page_and_commit_saver() {
+        let commits = parse_search_page(SEARCH_PAGE).unwrap();
+
+        assert_eq!(commits.len(), 3);
+        assert_eq!(
+            commits[0].datetime,
+            Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap()
+        );
+        assert_eq!(commits[0].repository_url, "https://github.com/user/api.git");
+
+        let saver = commits[0].to_commit_saver();
+        assert_eq!(saver.entry.message, "fix: a | b\nDetails");
+        assert_eq!(saver.entry.table_message(), "fix: a \\| b<br/>Details");
+        assert_eq!(saver.entry.hash, "b2");
+        assert!(saver.entry.branch.is_empty());
+        assert!(parse_search_page("{\"message\": \"API rate limit exceeded\"}").is_err());
+    }
+
+    #[test]
+    fn test_fetch_github_commits_from_a_local_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let api_url = format!("http://{:}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 4096];
+            let read = stream.read(&mut request).unwrap();
+            let request = String::from_utf8_lossy(&request[..read]).to_string();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {:}\r\nConnection: close\r\n\r\n{SEARCH_PAGE:}",
+                SEARCH_PAGE.len()
+            )
+            .unwrap();
+            request
+        });
+
+        let since = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let commits = fetch_github_commits(&api_url, "octocat", since, Some("secret")).unwrap();
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /search/commits?q=author:octocat+"));
+        assert!(request
+            .to_lowercase()
+            .contains("authorization: bearer secret"));
+        let shas = commits.iter().map(|c| c.sha.as_str()).collect::<Vec<_>>();
+        assert_eq!(shas, ["a1", "b2"]);
+        assert_eq!(commits[1].repository_url, "https://github.com/user/api.git");
+    }
+
+    #[test]
+    fn test_existing_hashes_reads_the_index_and_the_diary_tables() {
+        let vault = tempdir().unwrap();
+        let root = vault.path().join("Commits");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(
+            root.join("2025-01-14.md"),
+            "---\ndate: 2025-01-14\n---\n| TIME | COMMIT HASH |\n|---|---|\n| 10:00:00 | b2 |\n| 11:00:00 |  |\n",
+        )
+        .unwrap();
+        let logged = LoggedEntry {
+            commit_datetime: Utc.with_ymd_and_hms(2025, 1, 13, 9, 0, 0).unwrap(),
+            repository_url: "https://github.com/user/web.git".to_string(),
+            branch: "main".to_string(),
+            commit_hash: "a1".to_string(),
+            diary_path: "/vault/Commits/2025-01-13.md".to_string(),
+        };
+
+        let hashes = existing_hashes(&[root], &[logged]).unwrap();
+
+        assert_eq!(hashes, HashSet::from(["a1".to_string(), "b2".to_string()]));
+    }
+}
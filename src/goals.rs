@@ -0,0 +1,260 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::DateTime;
+use chrono::Datelike;
+use chrono::NaiveDate;
+use chrono::Utc;
+use log::info;
+
+use crate::frontmatter::frontmatter_property;
+use crate::frontmatter::set_frontmatter_property;
+use crate::frontmatter::write_frontmatter_update;
+use crate::hydration::ensure_hydrated;
+use crate::hydration::HYDRATION_ATTEMPTS;
+use crate::hydration::HYDRATION_RETRY_DELAY;
+use crate::porcelain::porcelain_line;
+use crate::state::LoggedEntry;
+use crate::vault_stats::DiaryCommit;
+
+/// Default weekly note path, relative to the vault root (ISO week, e.g. `2025-W03.md`).
+pub const DEFAULT_WEEKLY_NOTE_TEMPLATE: &str = "Diaries/Weekly/%G-W%V.md";
+
+/// Frontmatter property holding the weekly commit goal.
+const GOAL_PROPERTY: &str = "goal";
+
+/// Frontmatter property holding the number of commits logged this week.
+const DONE_PROPERTY: &str = "done";
+
+/// A weekly commit goal, tracked in the frontmatter of a weekly note.
+///
+/// # Configuration
+///
+/// ```text
+/// [goals]
+/// weekly_commits = 20
+/// weekly_note = Diaries/Weekly/%G-W%V.md
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeeklyGoal {
+    /// Number of commits to log per ISO week.
+    pub commits: usize,
+
+    /// Chrono format of the weekly note path, relative to the vault root.
+    pub note_template: String,
+}
+
+/// Progress towards a [`WeeklyGoal`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeeklyProgress {
+    pub goal: usize,
+    pub done: usize,
+}
+
+impl WeeklyProgress {
+    /// Renders the progress of the ISO week `today` falls in, as appended to
+    /// the `stats` output.
+    #[must_use]
+    pub fn render(&self, today: NaiveDate) -> String {
+        format!(
+            "\nWeekly goal ({:}): {:}/{:} commits.\n",
+            today.format("%G-W%V"),
+            self.done,
+            self.goal
+        )
+    }
+
+    /// Renders the progress as a porcelain record (`stats --porcelain`):
+    ///
+    /// ```text
+    /// goal <ISO week> <done> <goal>
+    /// ```
+    #[must_use]
+    pub fn to_porcelain(&self, today: NaiveDate) -> String {
+        porcelain_line(&[
+            "goal",
+            &today.format("%G-W%V").to_string(),
+            &self.done.to_string(),
+            &self.goal.to_string(),
+        ])
+    }
+}
+
+impl WeeklyGoal {
+    /// Returns the weekly note of the ISO week `datetime` falls in.
+    #[must_use]
+    pub fn note_path(&self, obsidian_root_path_dir: &Path, datetime: DateTime<Utc>) -> PathBuf {
+        weekly_note_path(obsidian_root_path_dir, &self.note_template, datetime)
+    }
+}
+
+/// Returns the weekly note of the ISO week `datetime` falls in, with
+/// `note_template` the chrono format of its path relative to the vault root.
+#[must_use]
+pub fn weekly_note_path(
+    obsidian_root_path_dir: &Path,
+    note_template: &str,
+    datetime: DateTime<Utc>,
+) -> PathBuf {
+    let mut note_path = obsidian_root_path_dir.to_path_buf();
+    for directory in datetime.format(note_template).to_string().split('/') {
+        note_path.push(directory);
+    }
+    note_path
+}
+
+/// Counts the logged commits of the ISO week `datetime` falls in.
+///
+/// Weeks follow the UTC dates the diary files are named after. A commit
+/// logged more than once (e.g. saved again by hand) counts once.
+#[must_use]
+pub fn commits_in_week(entries: &[LoggedEntry], datetime: DateTime<Utc>) -> usize {
+    let week = datetime.iso_week();
+    entries
+        .iter()
+        .filter(|entry| entry.commit_datetime.iso_week() == week)
+        .map(|entry| entry.commit_hash.as_str())
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// Counts the diary commits (see
+/// [`collect_diary_commits()`](crate::vault_stats::collect_diary_commits)) of
+/// the ISO week `today` falls in.
+#[must_use]
+pub fn diary_commits_in_week(commits: &[DiaryCommit], today: NaiveDate) -> usize {
+    let week = today.iso_week();
+    commits
+        .iter()
+        .filter(|commit| commit.date.iso_week() == week)
+        .count()
+}
+
+/// Writes the weekly progress into the frontmatter of the weekly note.
+///
+/// Sets the `goal` and `done` properties, creating the note (and its
+/// directories) when it does not exist yet. The rest of the note is left
+/// untouched, so it can hold the user's own weekly review.
+///
+/// When `done` is `None` (no logged-commits index to count from), the `done`
+/// property already in the note is incremented instead.
+///
+/// # Returns
+///
+/// The progress written to the note.
+///
+/// # Errors
+///
+/// Returns an error if the note is a cloud placeholder that cannot be
+/// hydrated, or if it cannot be read or written.
+pub fn update_weekly_note(
+    note_path: &Path,
+    goal: usize,
+    done: Option<usize>,
+) -> Result<WeeklyProgress, Box<dyn Error>> {
+    ensure_hydrated(note_path, HYDRATION_ATTEMPTS, HYDRATION_RETRY_DELAY)?;
+    let content = if note_path.exists() {
+        fs::read_to_string(note_path)?
+    } else {
+        String::new()
+    };
+
+    let done = done.unwrap_or_else(|| {
+        frontmatter_property(&content, DONE_PROPERTY)
+            .and_then(|done| done.parse::<usize>().ok())
+            .unwrap_or_default()
+            + 1
+    });
+    let progress = WeeklyProgress { goal, done };
+
+    let updated = set_frontmatter_property(&content, GOAL_PROPERTY, &goal.to_string());
+    let updated = set_frontmatter_property(&updated, DONE_PROPERTY, &done.to_string());
+    if let Some(parent) = note_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    info!(
+        "[update_weekly_note()]: Weekly progress {done:}/{goal:} in: {:}",
+        note_path.display()
+    );
+    write_frontmatter_update(note_path, &content, &updated)?;
+
+    Ok(progress)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod goals_tests {
+    use super::*;
+    use crate::testing::LoggedEntryBuilder;
+    use chrono::TimeZone;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_note_path_uses_the_iso_week() {
+        let goal = WeeklyGoal {
+            commits: 20,
+            note_template: DEFAULT_WEEKLY_NOTE_TEMPLATE.to_string(),
+        };
+        let sunday = Utc.with_ymd_and_hms(2025, 1, 19, 23, 0, 0).unwrap();
+
+        assert_eq!(
+            goal.note_path(Path::new("/vault"), sunday),
+            PathBuf::from("/vault/Diaries/Weekly/2025-W03.md")
+        );
+    }
+
+    #[test]
+    fn test_commits_in_week() {
+        let entries = [12, 13, 15, 15, 19, 20].map(|day| {
+            LoggedEntryBuilder::new(&format!("abc{day:}"))
+                .at(Utc.with_ymd_and_hms(2025, 1, day, 10, 0, 0).unwrap())
+                .build()
+        });
+        let wednesday = Utc.with_ymd_and_hms(2025, 1, 15, 8, 0, 0).unwrap();
+
+        assert_eq!(commits_in_week(&entries, wednesday), 3);
+    }
+
+    #[test]
+    fn test_weekly_progress_of_diary_commits() {
+        let day = |day| NaiveDate::from_ymd_opt(2025, 1, day).unwrap();
+        let commits = [12, 13, 15, 15, 20]
+            .map(|d| DiaryCommit::new(day(d), "git@github.com:user/api.git", "feat: a"));
+        let progress = WeeklyProgress {
+            goal: 20,
+            done: diary_commits_in_week(&commits, day(15)),
+        };
+
+        assert_eq!(progress.done, 3);
+        assert_eq!(
+            progress.render(day(15)),
+            "\nWeekly goal (2025-W03): 3/20 commits.\n"
+        );
+        assert_eq!(progress.to_porcelain(day(15)), "goal\t2025-W03\t3\t20\n");
+    }
+
+    #[test]
+    fn test_update_weekly_note_keeps_the_rest_of_the_note() {
+        let temp_dir = tempdir().unwrap();
+        let note_path = temp_dir.path().join("Weekly/2025-W03.md");
+
+        assert_eq!(
+            update_weekly_note(&note_path, 20, None).unwrap(),
+            WeeklyProgress { goal: 20, done: 1 }
+        );
+        let mut content = fs::read_to_string(&note_path).unwrap();
+        assert_eq!(content, "---\ngoal: 20\ndone: 1\n---\n");
+
+        content.push_str("# Review\n");
+        fs::write(&note_path, content).unwrap();
+        update_weekly_note(&note_path, 25, None).unwrap();
+        update_weekly_note(&note_path, 25, Some(13)).unwrap();
+        assert_eq!(
+            fs::read_to_string(&note_path).unwrap(),
+            "---\ngoal: 25\ndone: 13\n---\n# Review\n"
+        );
+    }
+}
@@ -0,0 +1,374 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use chrono::Datelike;
+use chrono::Days;
+use chrono::NaiveDate;
+use clap::ValueEnum;
+
+use crate::vault_stats::DiaryCommit;
+use crate::vault_stats::Streak;
+
+/// Default heatmap note, at the vault root.
+pub const DEFAULT_HEATMAP_NOTE: &str = "Commit Heatmap.md";
+
+/// Cell colors of the HTML heatmap, from days without commits to the
+/// busiest ones: the GitHub contribution graph palette.
+const LEVEL_COLORS: [&str; 5] = ["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"];
+
+/// Cell characters of the text heatmap, same levels as [`LEVEL_COLORS`].
+const LEVEL_CHARS: [char; 5] = ['·', '░', '▒', '▓', '█'];
+
+/// Row labels of the heatmap, Monday first like `stats`.
+const WEEKDAY_LABELS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// How the heatmap grid is drawn (`[heatmap] format`, `heatmap --format`).
+///
+/// # Variants
+///
+/// - `Html` - An HTML table with colored cells and a tooltip per day, as
+///   rendered by Obsidian in reading and live preview modes
+/// - `Text` - A code block of shaded characters, readable anywhere
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HeatmapFormat {
+    #[default]
+    Html,
+    Text,
+}
+
+/// Where and how `heatmap` writes its note (`[heatmap]`).
+///
+/// # Configuration
+///
+/// ```text
+/// [heatmap]
+/// note = Rollups/Commit Heatmap.md
+/// format = html
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeatmapSettings {
+    /// Note the heatmap is written to, relative to the vault root. `None`
+    /// only prints it, unless `--note` is given.
+    pub note: Option<PathBuf>,
+
+    /// How the grid is drawn, unless `--format` is given.
+    pub format: HeatmapFormat,
+}
+
+/// Logged commits per day of one year, as drawn by `heatmap`.
+///
+/// Like [`VaultStats`](crate::vault_stats::VaultStats), commits are dated by
+/// the `date` of their diary file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YearActivity {
+    pub year: i32,
+
+    /// Logged commits per day of the year; days without commits are left out.
+    pub per_day: BTreeMap<NaiveDate, usize>,
+}
+
+impl YearActivity {
+    /// Counts the `commits` dated in `year`.
+    #[must_use]
+    pub fn new(year: i32, commits: &[DiaryCommit]) -> Self {
+        let mut per_day = BTreeMap::new();
+        for commit in commits.iter().filter(|commit| commit.date.year() == year) {
+            *per_day.entry(commit.date).or_default() += 1;
+        }
+        YearActivity { year, per_day }
+    }
+
+    /// Total number of logged commits of the year.
+    #[must_use]
+    pub fn commits(&self) -> usize {
+        self.per_day.values().sum()
+    }
+
+    /// Shade of a day, from 0 (no commits) to 4, relative to the busiest
+    /// day of the year like the GitHub contribution graph.
+    #[must_use]
+    pub fn level(&self, day: NaiveDate) -> usize {
+        let commits = self.per_day.get(&day).copied().unwrap_or_default();
+        let busiest = self.per_day.values().copied().max().unwrap_or_default();
+        if commits == 0 {
+            return 0;
+        }
+        (commits * 4).div_ceil(busiest).clamp(1, 4)
+    }
+
+    /// The longest run of consecutive days with commits within the year.
+    #[must_use]
+    pub fn longest_streak(&self) -> Option<Streak> {
+        Streak::longest(self.per_day.keys().copied())
+    }
+
+    /// The run of consecutive days with commits ending `today`, or yesterday
+    /// when nothing was logged today yet, so a streak is not reported broken
+    /// before the day is over.
+    ///
+    /// # Returns
+    ///
+    /// `None` if neither day has commits.
+    #[must_use]
+    pub fn current_streak(&self, today: NaiveDate) -> Option<Streak> {
+        let end = [Some(today), today.pred_opt()]
+            .into_iter()
+            .flatten()
+            .find(|day| self.per_day.contains_key(day))?;
+        let mut start = end;
+        while let Some(day) = start
+            .pred_opt()
+            .filter(|day| self.per_day.contains_key(day))
+        {
+            start = day;
+        }
+        Some(Streak { start, end })
+    }
+
+    /// The columns of the grid: the weeks touching the year, Monday first.
+    /// Days of the neighbouring years are `None`.
+    fn weeks(&self) -> Vec<[Option<NaiveDate>; 7]> {
+        let Some(first) = NaiveDate::from_ymd_opt(self.year, 1, 1) else {
+            return Vec::new();
+        };
+        let mut monday = first - Days::new(u64::from(first.weekday().num_days_from_monday()));
+        let mut weeks = Vec::new();
+        while monday.year() <= self.year {
+            let mut week = [None; 7];
+            for (offset, cell) in (0u64..).zip(week.iter_mut()) {
+                *cell = monday
+                    .checked_add_days(Days::new(offset))
+                    .filter(|day| day.year() == self.year);
+            }
+            weeks.push(week);
+            let Some(next) = monday.checked_add_days(Days::new(7)) else {
+                break;
+            };
+            monday = next;
+        }
+        weeks
+    }
+}
+
+/// Renders the `heatmap` output: the commit totals and streaks of the year,
+/// then the grid of days in `format`.
+///
+/// The current streak is only shown for the year of `today`.
+///
+/// # Output Format
+///
+/// ```text
+/// # Commit Heatmap 2025
+///
+/// 412 commits over 180 days. Longest streak: 21 days (2025-02-03 to 2025-02-23). Current streak: 4 days.
+///
+/// <table>...</table>
+/// ```
+#[must_use]
+pub fn render_heatmap(activity: &YearActivity, format: HeatmapFormat, today: NaiveDate) -> String {
+    let mut output = format!("# Commit Heatmap {:}\n\n", activity.year);
+    if let Some(streak) = activity.longest_streak() {
+        let _ = write!(
+            output,
+            "{:} commits over {:} days. Longest streak: {:} days ({:} to {:}).",
+            activity.commits(),
+            activity.per_day.len(),
+            streak.days(),
+            streak.start,
+            streak.end
+        );
+    } else {
+        let _ = write!(output, "No commits in {:}.", activity.year);
+    }
+    if today.year() == activity.year {
+        let current = activity
+            .current_streak(today)
+            .map_or(0, |streak| streak.days());
+        let _ = write!(output, " Current streak: {current:} days.");
+    }
+    output.push_str("\n\n");
+
+    match format {
+        HeatmapFormat::Html => render_html_grid(activity, &mut output),
+        HeatmapFormat::Text => render_text_grid(activity, &mut output),
+    }
+    output
+}
+
+/// Returns the column of the grid holding the 1st of each month, with the
+/// month's short name.
+fn month_columns(weeks: &[[Option<NaiveDate>; 7]]) -> Vec<(usize, String)> {
+    weeks
+        .iter()
+        .enumerate()
+        .filter_map(|(column, week)| {
+            week.iter()
+                .flatten()
+                .find(|day| day.day() == 1)
+                .map(|day| (column, day.format("%b").to_string()))
+        })
+        .collect()
+}
+
+/// Appends the grid as an HTML table, one line per row so Obsidian keeps it
+/// a single HTML block.
+fn render_html_grid(activity: &YearActivity, output: &mut String) {
+    let weeks = activity.weeks();
+    let months = month_columns(&weeks);
+
+    output.push_str("<table>\n<tr><th></th>");
+    for (index, (column, month)) in months.iter().enumerate() {
+        let next = months.get(index + 1).map_or(weeks.len(), |(next, _)| *next);
+        let _ = write!(output, "<th colspan=\"{:}\">{month}</th>", next - column);
+    }
+    output.push_str("</tr>\n");
+
+    for (row, label) in WEEKDAY_LABELS.iter().enumerate() {
+        let _ = write!(output, "<tr><th>{label}</th>");
+        for week in &weeks {
+            let Some(day) = week[row] else {
+                output.push_str("<td></td>");
+                continue;
+            };
+            let _ = write!(
+                output,
+                "<td title=\"{day:}: {:} commits\" style=\"width:10px;height:10px;padding:0;background:{:}\"></td>",
+                activity.per_day.get(&day).copied().unwrap_or_default(),
+                LEVEL_COLORS[activity.level(day)]
+            );
+        }
+        output.push_str("</tr>\n");
+    }
+    output.push_str("</table>\n");
+}
+
+/// Appends the grid as a code block of [`LEVEL_CHARS`], one character per
+/// day, with a legend.
+fn render_text_grid(activity: &YearActivity, output: &mut String) {
+    let weeks = activity.weeks();
+    let mut header = vec![' '; weeks.len() + 3];
+    for (column, month) in month_columns(&weeks) {
+        for (offset, c) in month.chars().enumerate() {
+            header[column + offset] = c;
+        }
+    }
+
+    output.push_str("```text\n    ");
+    output.push_str(header.iter().collect::<String>().trim_end());
+    output.push('\n');
+    for (row, label) in WEEKDAY_LABELS.iter().enumerate() {
+        let cells = weeks
+            .iter()
+            .map(|week| week[row].map_or(' ', |day| LEVEL_CHARS[activity.level(day)]))
+            .collect::<String>();
+        let _ = writeln!(output, "{label:} {:}", cells.trim_end());
+    }
+    let _ = writeln!(
+        output,
+        "\n    Less {:} More\n```",
+        LEVEL_CHARS.iter().collect::<String>()
+    );
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod heatmap_tests {
+    use super::*;
+
+    fn day(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, month, day).unwrap()
+    }
+
+    fn activity(days: &[(u32, u32, usize)]) -> YearActivity {
+        let mut commits = vec![DiaryCommit::new(
+            NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+            "",
+            "fix: last year",
+        )];
+        for &(month, d, count) in days {
+            for _ in 0..count {
+                commits.push(DiaryCommit::new(day(month, d), "", "feat: search"));
+            }
+        }
+        YearActivity::new(2025, &commits)
+    }
+
+    #[test]
+    fn test_year_activity_levels_and_streaks() {
+        let activity = activity(&[(1, 13, 1), (1, 14, 8), (1, 15, 3), (3, 1, 2), (3, 2, 1)]);
+
+        assert_eq!(activity.commits(), 15);
+        assert_eq!(activity.level(day(1, 12)), 0);
+        assert_eq!(activity.level(day(1, 13)), 1);
+        assert_eq!(activity.level(day(1, 15)), 2);
+        assert_eq!(activity.level(day(1, 14)), 4);
+        assert_eq!(
+            activity.longest_streak(),
+            Some(Streak {
+                start: day(1, 13),
+                end: day(1, 15),
+            })
+        );
+        assert_eq!(
+            activity.current_streak(day(3, 3)),
+            Some(Streak {
+                start: day(3, 1),
+                end: day(3, 2),
+            })
+        );
+        assert_eq!(activity.current_streak(day(3, 4)), None);
+    }
+
+    #[test]
+    fn test_weeks_cover_the_year_monday_first() {
+        let weeks = activity(&[]).weeks();
+
+        // 2025-01-01 is a Wednesday, 2025-12-31 too
+        assert_eq!(weeks.len(), 53);
+        assert_eq!(weeks[0][..3], [None, None, Some(day(1, 1))]);
+        assert_eq!(weeks[52][2..4], [Some(day(12, 31)), None]);
+        assert_eq!(month_columns(&weeks)[1], (4, "Feb".to_string()));
+    }
+
+    #[test]
+    fn test_render_heatmap_as_html() {
+        let output = render_heatmap(
+            &activity(&[(1, 13, 1), (1, 14, 2)]),
+            HeatmapFormat::Html,
+            day(1, 14),
+        );
+
+        assert!(output.starts_with(
+            "# Commit Heatmap 2025\n\n3 commits over 2 days. Longest streak: 2 days (2025-01-13 to 2025-01-14). Current streak: 2 days.\n\n<table>\n<tr><th></th><th colspan=\"4\">Jan</th><th colspan=\"4\">Feb</th>"
+        ));
+        assert!(output.contains(
+            "<tr><th>Tue</th><td></td><td title=\"2025-01-07: 0 commits\" style=\"width:10px;height:10px;padding:0;background:#ebedf0\"></td><td title=\"2025-01-14: 2 commits\" style=\"width:10px;height:10px;padding:0;background:#216e39\"></td>"
+        ));
+        assert!(output.ends_with("</tr>\n</table>\n"));
+        assert_eq!(output.matches("<tr>").count(), 8);
+    }
+
+    #[test]
+    fn test_render_heatmap_as_text() {
+        let output = render_heatmap(
+            &activity(&[(1, 6, 4), (1, 7, 1)]),
+            HeatmapFormat::Text,
+            day(6, 1),
+        );
+
+        assert!(output.starts_with(
+            "# Commit Heatmap 2025\n\n5 commits over 2 days. Longest streak: 2 days (2025-01-06 to 2025-01-07). Current streak: 0 days.\n\n```text\n    Jan Feb"
+        ));
+        assert!(output.contains("\nMon  █··"));
+        assert!(output.contains("\nTue  ░··"));
+        assert!(output.contains("\nWed ··"));
+        assert!(output.ends_with("\n\n    Less ·░▒▓█ More\n```\n"));
+        assert_eq!(
+            render_heatmap(&activity(&[]), HeatmapFormat::Text, day(6, 1))
+                .lines()
+                .nth(2),
+            Some("No commits in 2025. Current streak: 0 days.")
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use git2::Repository;
+use log::info;
+
+/// The Git hook `install-hook` writes to.
+pub const HOOK_NAME: &str = "post-commit";
+
+/// First line of the block `install-hook` adds to the hook script.
+pub const HOOK_BLOCK_START: &str = "# >>> rusty-commit-saver >>>";
+
+/// Last line of the block `install-hook` adds to the hook script.
+pub const HOOK_BLOCK_END: &str = "# <<< rusty-commit-saver <<<";
+
+/// What `install-hook` or `uninstall-hook` did to the hook script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookChange {
+    /// A new hook script was written.
+    Created,
+    /// The block was added to, or removed from, an existing hook script.
+    Updated,
+    /// The hook script already was as asked.
+    Unchanged,
+    /// The hook script only held the block and was deleted.
+    Removed,
+}
+
+/// Returns the `post-commit` hook script of a repository.
+///
+/// Follows `core.hooksPath` when it is set (relative paths are resolved from
+/// the working directory, like Git does), and the common `hooks` directory
+/// otherwise, so worktrees share the hook of their main repository.
+#[must_use]
+pub fn hook_path(git_repo: &Repository) -> PathBuf {
+    let hooks_dir = git_repo
+        .config()
+        .and_then(|config| config.get_path("core.hooksPath"))
+        .map_or_else(
+            |_| git_repo.commondir().join("hooks"),
+            |hooks_path| match git_repo.workdir() {
+                Some(workdir) if hooks_path.is_relative() => workdir.join(hooks_path),
+                _ => hooks_path,
+            },
+        );
+    hooks_dir.join(HOOK_NAME)
+}
+
+/// Renders the block calling `binary` from the hook script.
+///
+/// The path is single-quoted for `sh`, so spaces and `$` are kept as is.
+#[must_use]
+pub fn hook_block(binary: &Path) -> String {
+    let quoted = binary.display().to_string().replace('\'', "'\\''");
+    format!("{HOOK_BLOCK_START:}\n'{quoted:}' save\n{HOOK_BLOCK_END:}\n")
+}
+
+/// Installs the hook block calling `binary` into the hook script.
+///
+/// A missing script is created with a `#!/bin/sh` line. An existing one keeps
+/// its other commands: the block is appended, or replaced when it is already
+/// there (e.g. after the binary moved). The script is made executable.
+///
+/// # Errors
+///
+/// Returns an error if the script cannot be read or written.
+pub fn install_hook(hook_path: &Path, binary: &Path) -> Result<HookChange, Box<dyn Error>> {
+    let block = hook_block(binary);
+    let (content, change) = match fs::read_to_string(hook_path) {
+        Ok(existing) => {
+            let rest = remove_hook_block(&existing).unwrap_or_else(|| existing.clone());
+            let rest = match rest.trim_end() {
+                "" => "#!/bin/sh",
+                rest => rest,
+            };
+            let content = format!("{rest:}\n\n{block:}");
+            if content == existing {
+                (content, HookChange::Unchanged)
+            } else {
+                (content, HookChange::Updated)
+            }
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            (format!("#!/bin/sh\n\n{block:}"), HookChange::Created)
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    if change != HookChange::Unchanged {
+        info!(
+            "[install_hook()]: Writing the hook block to: {:}",
+            hook_path.display()
+        );
+        if let Some(parent) = hook_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(hook_path, content)?;
+    }
+    make_executable(hook_path)?;
+    Ok(change)
+}
+
+/// Removes the hook block from the hook script.
+///
+/// The script is deleted when nothing but the `#!` line is left, so an
+/// install followed by an uninstall leaves the repository as it was.
+///
+/// # Errors
+///
+/// Returns an error if the script cannot be read, written or deleted.
+pub fn uninstall_hook(hook_path: &Path) -> Result<HookChange, Box<dyn Error>> {
+    let existing = match fs::read_to_string(hook_path) {
+        Ok(existing) => existing,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HookChange::Unchanged),
+        Err(e) => return Err(e.into()),
+    };
+    let Some(rest) = remove_hook_block(&existing) else {
+        return Ok(HookChange::Unchanged);
+    };
+
+    let has_commands = rest
+        .lines()
+        .any(|line| !line.trim().is_empty() && !line.starts_with("#!"));
+    if has_commands {
+        info!(
+            "[uninstall_hook()]: Removing the hook block from: {:}",
+            hook_path.display()
+        );
+        fs::write(hook_path, format!("{:}\n", rest.trim_end()))?;
+        Ok(HookChange::Updated)
+    } else {
+        info!(
+            "[uninstall_hook()]: Deleting the hook script: {:}",
+            hook_path.display()
+        );
+        fs::remove_file(hook_path)?;
+        Ok(HookChange::Removed)
+    }
+}
+
+/// Returns the script without the hook block, or `None` if it has none.
+fn remove_hook_block(script: &str) -> Option<String> {
+    let start = script.find(HOOK_BLOCK_START)?;
+    let end = script[start..].find(HOOK_BLOCK_END)? + start + HOOK_BLOCK_END.len();
+    let after = script[end..].strip_prefix('\n').unwrap_or(&script[end..]);
+    Some(format!("{:}{after:}", &script[..start]))
+}
+
+/// Sets the executable bits of the hook script, which Git requires.
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<(), Box<dyn Error>> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut permissions = fs::metadata(path)?.permissions();
+    permissions.set_mode(permissions.mode() | 0o755);
+    fs::set_permissions(path, permissions)?;
+    Ok(())
+}
+
+/// Git for Windows runs hooks without an executable bit.
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<(), Box<dyn Error>> {
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod hook_tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_install_and_uninstall_a_new_hook() {
+        let temp_dir = tempdir().unwrap();
+        let git_repo = Repository::init(temp_dir.path()).unwrap();
+        let hook = hook_path(&git_repo);
+        assert_eq!(hook, git_repo.path().join("hooks/post-commit"));
+        let binary = Path::new("/opt/my tools/rusty-commit-saver");
+
+        assert_eq!(install_hook(&hook, binary).unwrap(), HookChange::Created);
+        assert_eq!(
+            fs::read_to_string(&hook).unwrap(),
+            format!(
+                "#!/bin/sh\n\n{HOOK_BLOCK_START:}\n'/opt/my tools/rusty-commit-saver' save\n{HOOK_BLOCK_END:}\n"
+            )
+        );
+        assert_eq!(
+            fs::metadata(&hook).unwrap().permissions().mode() & 0o111,
+            0o111
+        );
+        assert_eq!(install_hook(&hook, binary).unwrap(), HookChange::Unchanged);
+
+        assert_eq!(uninstall_hook(&hook).unwrap(), HookChange::Removed);
+        assert!(!hook.exists());
+        assert_eq!(uninstall_hook(&hook).unwrap(), HookChange::Unchanged);
+    }
+
+    #[test]
+    fn test_install_hook_keeps_existing_commands() {
+        let temp_dir = tempdir().unwrap();
+        let hook = temp_dir.path().join("post-commit");
+        fs::write(&hook, "#!/bin/sh\nnotify-send committed\n").unwrap();
+
+        install_hook(&hook, Path::new("/old/rusty-commit-saver")).unwrap();
+        assert_eq!(
+            install_hook(&hook, Path::new("/new/rusty-commit-saver")).unwrap(),
+            HookChange::Updated
+        );
+        let script = fs::read_to_string(&hook).unwrap();
+        assert!(script.starts_with("#!/bin/sh\nnotify-send committed\n\n# >>>"));
+        assert!(script.contains("'/new/rusty-commit-saver' save\n"));
+        assert!(!script.contains("/old/"));
+
+        assert_eq!(uninstall_hook(&hook).unwrap(), HookChange::Updated);
+        assert_eq!(
+            fs::read_to_string(&hook).unwrap(),
+            "#!/bin/sh\nnotify-send committed\n"
+        );
+    }
+
+    #[test]
+    fn test_hook_path_follows_core_hooks_path() {
+        let temp_dir = tempdir().unwrap();
+        let git_repo = Repository::init(temp_dir.path()).unwrap();
+        git_repo
+            .config()
+            .unwrap()
+            .set_str("core.hooksPath", ".githooks")
+            .unwrap();
+
+        assert_eq!(
+            hook_path(&git_repo),
+            git_repo.workdir().unwrap().join(".githooks/post-commit")
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use log::info;
+use log::warn;
+
+/// How many times a placeholder diary file is re-checked before giving up.
+pub const HYDRATION_ATTEMPTS: u32 = 5;
+
+/// Pause between two hydration checks, giving the sync client time to download.
+pub const HYDRATION_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Windows attributes set on cloud files whose content is not on disk.
+#[cfg(windows)]
+const WINDOWS_PLACEHOLDER_ATTRIBUTES: u32 = 0x0000_1000 // FILE_ATTRIBUTE_OFFLINE
+    | 0x0004_0000 // FILE_ATTRIBUTE_RECALL_ON_OPEN
+    | 0x0040_0000; // FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS
+
+/// Returns the iCloud stub that stands in for an evicted file.
+///
+/// When iCloud Drive evicts `2025-01-14.md`, the file disappears and a hidden
+/// `.2025-01-14.md.icloud` stub is left next to it.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::hydration::icloud_stub_path;
+/// use std::path::Path;
+///
+/// assert_eq!(
+///     icloud_stub_path(Path::new("/vault/2025-01-14.md")).unwrap(),
+///     Path::new("/vault/.2025-01-14.md.icloud")
+/// );
+/// ```
+#[must_use]
+pub fn icloud_stub_path(path: &Path) -> Option<PathBuf> {
+    let file_name = path.file_name()?.to_str()?;
+    Some(path.with_file_name(format!(".{file_name:}.icloud")))
+}
+
+/// Makes sure an existing diary file is fully present on disk before it is written.
+///
+/// Vaults synced by iCloud Drive or `OneDrive` may hold placeholder files whose
+/// content lives only in the cloud. Appending to (or re-creating) such a file
+/// can corrupt the note once the sync client reconciles it. This function:
+///
+/// 1. Waits for an evicted iCloud file (only the `.icloud` stub exists) to be
+///    downloaded again, instead of letting a fresh diary file replace it
+/// 2. Reads existing files in full, which makes the sync client hydrate them,
+///    and retries while the read fails or the file still looks like a placeholder
+///
+/// A path with neither a file nor a stub is a new diary and passes right away.
+///
+/// # Arguments
+///
+/// * `path` - The diary file about to be written
+/// * `attempts` - How many checks to run before giving up (at least one)
+/// * `retry_delay` - Pause between two checks
+///
+/// # Errors
+///
+/// Returns an error if the file is still a placeholder, or cannot be read,
+/// after all attempts. Nothing is written in that case.
+pub fn ensure_hydrated(
+    path: &Path,
+    attempts: u32,
+    retry_delay: Duration,
+) -> Result<(), Box<dyn Error>> {
+    let attempts = attempts.max(1);
+
+    for attempt in 1..=attempts {
+        match check_hydrated(path) {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt < attempts => {
+                warn!(
+                    "[ensure_hydrated()]: Attempt {attempt:}/{attempts:} for {:}: {e:}",
+                    path.display()
+                );
+                thread::sleep(retry_delay);
+            }
+            Err(e) => {
+                return Err(format!(
+                    "{:} is a cloud placeholder that could not be hydrated: {e:}",
+                    path.display()
+                )
+                .into());
+            }
+        }
+    }
+
+    unreachable!("ensure_hydrated() returns from its last attempt")
+}
+
+/// Runs a single hydration check, see [`ensure_hydrated()`].
+fn check_hydrated(path: &Path) -> Result<(), Box<dyn Error>> {
+    if !path.exists() {
+        return match icloud_stub_path(path) {
+            Some(stub) if stub.exists() => {
+                request_download(path);
+                Err("only the iCloud stub is present".into())
+            }
+            _ => Ok(()),
+        };
+    }
+
+    let metadata = fs::metadata(path)?;
+    if !metadata.is_file() {
+        return Err("not a regular file".into());
+    }
+
+    info!(
+        "[check_hydrated()]: Reading {:} to force hydration.",
+        path.display()
+    );
+    let content = fs::read(path)?;
+    if content.len() as u64 != metadata.len() {
+        return Err(format!(
+            "read {:} bytes but the file reports {:}",
+            content.len(),
+            metadata.len()
+        )
+        .into());
+    }
+
+    if has_placeholder_attributes(&fs::metadata(path)?) {
+        return Err("the file is still marked as a placeholder".into());
+    }
+
+    Ok(())
+}
+
+/// Returns `true` when the platform marks the file content as not on disk.
+///
+/// - macOS: dataless files report content but no allocated blocks
+/// - Windows: offline / recall-on-access attributes
+/// - Elsewhere: no attribute check, the read in [`check_hydrated()`] is used
+#[cfg(target_os = "macos")]
+fn has_placeholder_attributes(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    metadata.len() > 0 && metadata.blocks() == 0
+}
+
+#[cfg(windows)]
+fn has_placeholder_attributes(metadata: &fs::Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+
+    metadata.file_attributes() & WINDOWS_PLACEHOLDER_ATTRIBUTES != 0
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn has_placeholder_attributes(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
+/// Asks iCloud Drive to download an evicted file (macOS only).
+#[cfg(target_os = "macos")]
+fn request_download(path: &Path) {
+    info!(
+        "[request_download()]: Asking iCloud to download {:}",
+        path.display()
+    );
+    if let Err(e) = std::process::Command::new("brctl")
+        .arg("download")
+        .arg(path)
+        .status()
+    {
+        warn!("[request_download()]: brctl download failed: {e:}");
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn request_download(path: &Path) {
+    info!(
+        "[request_download()]: Waiting for the sync client to restore {:}",
+        path.display()
+    );
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod hydration_tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_icloud_stub_path() {
+        assert_eq!(
+            icloud_stub_path(Path::new("/vault/2025-01-14.md")).unwrap(),
+            PathBuf::from("/vault/.2025-01-14.md.icloud")
+        );
+        assert!(icloud_stub_path(Path::new("/")).is_none());
+    }
+
+    #[test]
+    fn test_ensure_hydrated_new_file_passes() {
+        let temp_dir = tempdir().unwrap();
+
+        let result = ensure_hydrated(&temp_dir.path().join("new.md"), 3, Duration::ZERO);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_ensure_hydrated_regular_file_passes() {
+        let temp_dir = tempdir().unwrap();
+        let diary = temp_dir.path().join("diary.md");
+        fs::write(&diary, "| row |\n").unwrap();
+
+        assert!(ensure_hydrated(&diary, 3, Duration::ZERO).is_ok());
+    }
+
+    #[test]
+    fn test_ensure_hydrated_evicted_icloud_file_errors() {
+        let temp_dir = tempdir().unwrap();
+        let diary = temp_dir.path().join("2025-01-14.md");
+        fs::write(icloud_stub_path(&diary).unwrap(), "bplist00").unwrap();
+
+        let result = ensure_hydrated(&diary, 2, Duration::ZERO);
+
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("could not be hydrated"), "{message}");
+        assert!(!diary.exists());
+    }
+
+    #[test]
+    fn test_ensure_hydrated_waits_for_download() {
+        let temp_dir = tempdir().unwrap();
+        let diary = temp_dir.path().join("2025-01-14.md");
+        let stub = icloud_stub_path(&diary).unwrap();
+        fs::write(&stub, "bplist00").unwrap();
+
+        let restored = diary.clone();
+        let sync_client = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            fs::write(&restored, "# 2025-01-14\n").unwrap();
+            fs::remove_file(stub).unwrap();
+        });
+
+        let result = ensure_hydrated(&diary, 50, Duration::from_millis(20));
+        sync_client.join().unwrap();
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_ensure_hydrated_unreadable_entry_errors() {
+        let temp_dir = tempdir().unwrap();
+        let diary = temp_dir.path().join("diary.md");
+        fs::create_dir(&diary).unwrap();
+
+        assert!(ensure_hydrated(&diary, 2, Duration::ZERO).is_err());
+    }
+}
@@ -0,0 +1,227 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use log::info;
+use regex::Regex;
+
+use crate::atomic_write::write_atomically;
+use crate::hydration::ensure_hydrated;
+use crate::hydration::HYDRATION_ATTEMPTS;
+use crate::hydration::HYDRATION_RETRY_DELAY;
+use crate::paths::repository_name_from_url;
+use crate::vim_commit::CommitSaver;
+
+/// Frontmatter tag of diary files with a commit message not matching the
+/// `[message_hygiene] pattern`.
+pub const NEEDS_BETTER_MESSAGE_TAG: &str = "#needs-better-message";
+
+/// Heading of the weekly note section listing those commits.
+pub const MESSAGE_HYGIENE_HEADING: &str = "## Message hygiene";
+
+/// The regular expression commit messages should match (team conventions,
+/// e.g. Conventional Commits).
+///
+/// # Configuration
+///
+/// ```text
+/// [message_hygiene]
+/// pattern = ^(feat|fix|docs|refactor|test|chore)(\(.+\))?!?: .+
+/// ```
+#[derive(Debug, Clone)]
+pub struct MessagePattern(Regex);
+
+impl MessagePattern {
+    /// Compiles the pattern.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `pattern` is not a valid regular expression.
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Regex::new(pattern).map(MessagePattern)
+    }
+
+    /// Checks a commit message, as stored in
+    /// [`CommitSaver`](crate::vim_commit::CommitSaver), against the pattern.
+    ///
+    /// Only the subject (the first line) is checked, with its `\|` escapes
+    /// undone, so `^` and `$` anchor to the subject.
+    #[must_use]
+    pub fn is_match(&self, commit_msg: &str) -> bool {
+        let subject = commit_msg.split("<br/>").next().unwrap_or_default();
+        self.0.is_match(&subject.replace("\\|", "|"))
+    }
+}
+
+impl PartialEq for MessagePattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Eq for MessagePattern {}
+
+impl fmt::Display for MessagePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0.as_str())
+    }
+}
+
+/// Formats the line listing a commit in the message hygiene section, e.g.
+/// ``- [[Commits/2025/01-January/2025-01-14|2025-01-14]] api `abc1234` wip``.
+///
+/// # Arguments
+///
+/// * `commit_saver` - The commit with the non-matching message
+/// * `diary_link` - Link to (or date of) the diary file it is logged in
+#[must_use]
+pub fn message_hygiene_item(commit_saver: &CommitSaver, diary_link: &str) -> String {
+    let short_hash = commit_saver
+        .entry
+        .hash
+        .get(..7)
+        .unwrap_or(&commit_saver.entry.hash);
+    let subject = commit_saver
+        .entry
+        .message
+        .lines()
+        .next()
+        .unwrap_or_default();
+    format!(
+        "- {diary_link:} {:} `{short_hash:}` {subject:}",
+        repository_name_from_url(&commit_saver.entry.repository_url)
+    )
+}
+
+/// Adds a commit to the message hygiene section of the weekly note.
+///
+/// The section is added at the end of the note when missing. `item` is
+/// appended at the end of the section, unless a line of the section already
+/// mentions `commit_hash` (the commit was saved again). The rest of the note
+/// is left untouched.
+///
+/// # Returns
+///
+/// Whether the note was changed.
+///
+/// # Errors
+///
+/// Returns an error if the note is a cloud placeholder that cannot be
+/// hydrated, or if it cannot be read or written.
+pub fn record_message_hygiene(
+    note_path: &Path,
+    commit_hash: &str,
+    item: &str,
+) -> Result<bool, Box<dyn Error>> {
+    ensure_hydrated(note_path, HYDRATION_ATTEMPTS, HYDRATION_RETRY_DELAY)?;
+    let content = if note_path.exists() {
+        fs::read_to_string(note_path)?
+    } else {
+        String::new()
+    };
+
+    let Some(updated) = add_to_hygiene_section(&content, commit_hash, item) else {
+        return Ok(false);
+    };
+    if let Some(parent) = note_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    info!(
+        "[record_message_hygiene()]: Listing {commit_hash:} in: {:}",
+        note_path.display()
+    );
+    write_atomically(note_path, updated)?;
+    Ok(true)
+}
+
+/// Returns the note with `item` at the end of its message hygiene section,
+/// or `None` when the section already mentions `commit_hash`.
+fn add_to_hygiene_section(content: &str, commit_hash: &str, item: &str) -> Option<String> {
+    let mut lines = content.lines().map(str::to_string).collect::<Vec<_>>();
+    let Some(heading) = lines
+        .iter()
+        .position(|line| line.trim_end() == MESSAGE_HYGIENE_HEADING)
+    else {
+        let separator = match content {
+            "" => "",
+            content if content.ends_with("\n\n") => "",
+            content if content.ends_with('\n') => "\n",
+            _ => "\n\n",
+        };
+        return Some(format!(
+            "{content:}{separator:}{MESSAGE_HYGIENE_HEADING:}\n\n{item:}\n"
+        ));
+    };
+
+    let section_end = lines[heading + 1..]
+        .iter()
+        .position(|line| line.starts_with('#') && line.trim_start_matches('#').starts_with(' '))
+        .map_or(lines.len(), |offset| heading + 1 + offset);
+    if lines[heading + 1..section_end]
+        .iter()
+        .any(|line| line.contains(commit_hash))
+    {
+        return None;
+    }
+
+    let mut insert_at = section_end;
+    while insert_at > heading + 1 && lines[insert_at - 1].trim().is_empty() {
+        insert_at -= 1;
+    }
+    if insert_at == heading + 1 {
+        lines.insert(insert_at, String::new());
+        insert_at += 1;
+    }
+    lines.insert(insert_at, item.to_string());
+    Some(format!("{:}\n", lines.join("\n")))
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod hygiene_tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_message_pattern_checks_the_subject() {
+        let pattern = MessagePattern::new(r"^(feat|fix)(\(.+\))?: .+$").unwrap();
+
+        assert!(pattern.is_match("feat(api): add login<br/>wip in the body"));
+        assert!(pattern.is_match("fix: a \\| b"));
+        assert!(!pattern.is_match("wip<br/>feat: add login"));
+        assert!(MessagePattern::new("(unclosed").is_err());
+        assert_eq!(pattern.to_string(), r"^(feat|fix)(\(.+\))?: .+$");
+    }
+
+    #[test]
+    fn test_record_message_hygiene_adds_the_section_once() {
+        let temp_dir = tempdir().unwrap();
+        let note_path = temp_dir.path().join("Weekly/2025-W03.md");
+        fs::create_dir_all(note_path.parent().unwrap()).unwrap();
+        fs::write(&note_path, "---\ngoal: 20\n---\n# Review\n").unwrap();
+
+        assert!(record_message_hygiene(&note_path, "aaa111", "- aaa111 wip").unwrap());
+        assert!(record_message_hygiene(&note_path, "bbb222", "- bbb222 stuff").unwrap());
+        assert!(!record_message_hygiene(&note_path, "aaa111", "- aaa111 wip").unwrap());
+
+        assert_eq!(
+            fs::read_to_string(&note_path).unwrap(),
+            "---\ngoal: 20\n---\n# Review\n\n## Message hygiene\n\n- aaa111 wip\n- bbb222 stuff\n"
+        );
+    }
+
+    #[test]
+    fn test_add_to_hygiene_section_keeps_the_following_sections() {
+        let content = "## Message hygiene\n\n- aaa111 wip\n#not-a-heading\n\n## Notes\nkeep me\n";
+
+        assert_eq!(
+            add_to_hygiene_section(content, "bbb222", "- bbb222 stuff").unwrap(),
+            "## Message hygiene\n\n- aaa111 wip\n#not-a-heading\n- bbb222 stuff\n\n## Notes\nkeep me\n"
+        );
+        assert_eq!(
+            add_to_hygiene_section("## Message hygiene\n", "aaa111", "- aaa111 wip").unwrap(),
+            "## Message hygiene\n\n- aaa111 wip\n"
+        );
+    }
+}
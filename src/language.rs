@@ -0,0 +1,72 @@
+use log::info;
+
+/// Detects the language of a commit message.
+///
+/// Works on the message as stored in [`CommitSaver`](crate::vim_commit::CommitSaver),
+/// whose lines are joined with `<br/>`. Detection is done by
+/// [`whatlang`](https://docs.rs/whatlang) and only kept when it is reliable,
+/// so short messages like `wip` or `fix typo` get no language.
+///
+/// # Returns
+///
+/// The ISO 639-3 code of the language (e.g. `eng`, `deu`, `fra`), or `None`.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::language::detect_language;
+///
+/// let language = detect_language("Corrige le plantage au démarrage lorsque le fichier de configuration est absent");
+/// assert_eq!(language.as_deref(), Some("fra"));
+/// assert_eq!(detect_language("wip"), None);
+/// ```
+#[must_use]
+pub fn detect_language(commit_msg: &str) -> Option<String> {
+    let text = commit_msg.replace("<br/>", "\n");
+    let language = whatlang::detect(&text)
+        .filter(whatlang::Info::is_reliable)
+        .map(|detected| detected.lang().code().to_string());
+    info!("[detect_language()]: Detected language: {language:?}");
+    language
+}
+
+/// Returns the frontmatter tag of a commit message language, e.g. `#lang/eng`.
+#[must_use]
+pub fn language_tag(language: &str) -> String {
+    format!("#lang/{language:}")
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod language_tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language_of_long_messages() {
+        assert_eq!(
+            detect_language(
+                "Fix the crash on startup when the configuration file is missing<br/><br/>Show a clear error message instead."
+            )
+            .as_deref(),
+            Some("eng")
+        );
+        assert_eq!(
+            detect_language(
+                "Behebt den Absturz beim Start, wenn die Konfigurationsdatei fehlt, und zeigt stattdessen eine verständliche Fehlermeldung"
+            )
+            .as_deref(),
+            Some("deu")
+        );
+    }
+
+    #[test]
+    fn test_detect_language_skips_unreliable_messages() {
+        assert_eq!(detect_language("wip"), None);
+        assert_eq!(detect_language(""), None);
+    }
+
+    #[test]
+    fn test_language_tag() {
+        assert_eq!(language_tag("deu"), "#lang/deu");
+    }
+}
@@ -29,8 +29,10 @@
 //! let commit_path = global_vars.get_obsidian_commit_path();
 //! let date_template = global_vars.get_template_commit_date_path();
 //!
+//! let save_settings = global_vars.get_save_settings();
+//!
 //! // Save the commit
-//! run_commit_saver(obsidian_root, &commit_path, &date_template).unwrap();
+//! run_commit_saver(&obsidian_root, &commit_path, &date_template, &save_settings).unwrap();
 //! ```
 //!
 //! ## Configuration
@@ -47,13 +49,82 @@
 //!
 //! [templates]
 //! commit_date_path = %Y/%m-%B/%F.md
-//! commit_datetime = %Y-%m-%d %H:%M:%S
+//! commit_datetime = %H:%M:%S
 //! ```
 //!
 //! ## Modules
 //!
 //! - [`vim_commit`] - Core commit processing and diary file operations
 //! - [`config`] - Configuration management and INI file parsing
+//! - [`paths`] - Path placeholder expansion and segment sanitization
+//! - [`porcelain`] - Stable, line-oriented output for scripts (`--porcelain`)
+//! - [`recent`] - Finding repositories with unlogged recent commits (`log-recent`)
+//! - [`daemon`] - Watching repositories for new commits without hooks (`daemon`)
+//! - [`github`] - Backfilling public commits from the GitHub commit search (`import`)
+//! - [`related`] - Repositories worked on together, for the `Related Repos.md` rollup
+//! - [`report`] - Vault usage report of the commit diary (`report --vault`)
+//! - [`push`] - Push summary rows from the `pre-push` hook (`event push`)
+//! - [`quarantine`] - Moving malformed diary files aside instead of failing
+//! - [`hydration`] - Cloud placeholder (iCloud/OneDrive) detection before writes
+//! - [`language`] - Commit message language detection for `#lang/<code>` tags
+//! - [`links`] - Vault-relative wikilinks and Markdown links to notes
+//! - [`routing`] - Per-repository commit paths from remote URL patterns
+//! - [`same_day`] - Diary files sharing a day across `commit_date_path` templates
+//! - [`skip`] - Per-commit opt-out through a trailer or environment variable
+//! - [`state`] - Index of logged commits kept outside the vault
+//! - [`templater`] - New diary files from the user's daily-note template
+//! - [`today`] - Terminal view of a day's diary tables
+//! - [`time_tracking`] - Current project/tag lookup from an external time tracker
+//! - [`timezone`] - Timezone annotations for the `TIME` column and frontmatter
+//! - [`amend`] - Recognizing `git commit --amend` to replace the amended row
+//! - [`append_queue`] - Debounced diary entries of the `daemon`, handed out per note
+//! - [`batch`] - Logging a list of commits in one run (`save --stdin`)
+//! - [`branch`] - Branch name slugs for `#branch/<slug>` tags and the `BRANCH SLUG` column
+//! - [`build_status`] - Build/test status of the committed state for the `STATUS` column
+//! - [`category`] - Entry categories resolved from path/remote rules
+//! - [`completions`] - Dynamic shell completions of repository and branch names (`completions`)
+//! - [`collision`] - Sequence suffixes for rows logged in the same second
+//! - [`forge`] - Commit, branch and pull request links for known and self-hosted forges
+//! - [`frontmatter`] - Merging values into the YAML frontmatter of existing notes
+//! - [`goals`] - Weekly commit goal progress in the weekly note
+//! - [`hygiene`] - Commit message pattern checks and the weekly message hygiene section
+//! - [`hook`] - Installing and removing the `post-commit` hook (`install-hook`)
+//! - [`diary_parser`] - Parsing of the commit table in existing diary files
+//! - [`enrich`] - Backfilling newly enabled columns into older diary tables
+//! - [`verify`] - Consistency checks between the state index and the vault
+//! - [`trash`] - Moving removed or rewritten notes to the vault `.trash` folder
+//! - [`vault_git`] - Auto-commit of diary files when the vault is a Git repository
+//! - [`wsl`] - Path translation between WSL and Windows for shared config files
+//! - [`webhook`] - Templated, HMAC-signed webhook payloads of logged commits
+//! - [`weekly_log`] - Compact weekly-note rows written next to the daily diary row
+//! - [`bootstrap`] - Creating a default config on first run, pointing at a detected vault
+//! - [`git_stats`] - Per-commit diff stats (`+X/−Y (N files)`) for the `STATS` column
+//! - [`git_tags`] - The annotated tag at or near a commit, for the `TAG` column
+//! - [`annotate`] - Freeform notes added to logged entries after the fact (`annotate`)
+//! - [`entry_id`] - Short, stable entry IDs for Obsidian block links (`^rcs-k3xq9amd`)
+//! - [`vault_stats`] - Commit totals per month, repository and weekday, and the longest streak
+//! - [`diary_lock`] - Advisory lock keeping concurrent saves from interleaving their rows
+//! - [`atomic_write`] - Writes through a temporary file renamed into place, never leaving a truncated note
+//! - [`stats_compare`] - Commit counts of two periods side by side (`stats --compare`)
+//! - [`diary_moves`] - Following diary files the user renamed or moved (`daemon`)
+//! - [`git_env`] - The repository and pushed refs Git hands to hooks (`GIT_DIR`, `post-receive`)
+//! - [`metrics`] - Opt-in local usage counters for bug reports (`metrics show`)
+//! - [`config_format`] - TOML and YAML configuration files, parsed into the INI schema
+//! - [`fan_out`] - Notes written next to the diary, staged first and spooled for retry on failure
+//! - [`undo`] - Removing or striking through the entry of any logged commit (`undo --hash`)
+//! - [`branch_summary`] - Branch lifecycle summaries written when a merge is logged
+//! - [`state_dir`] - Schema version of the state directory and migrations between versions
+//! - [`conventional`] - Conventional Commits type, scope and breaking flag of messages
+//! - [`vaults`] - Extra vaults commits are routed to by remote, path or branch
+//! - [`trailers`] - Commit trailers, and the Obsidian tags read from them
+//! - [`existing_note`] - Commit table and diary tags added to notes created outside the tool
+//! - [`commit_entry`] - Serializable commit data, and the renderers turning it into rows, JSON or CSV
+//! - [`diary_renderer`] - Diary rows, YAML blocks and Dataview bullets rendered from commit data
+//! - [`daily_note`] - A link to the day's diary file added to the user's own daily note
+//! - [`sinks`] - Outputs besides the diary commits are written to: JSON Lines, `SQLite`, stdout
+//! - [`heatmap`] - A GitHub-style heatmap of the commits per day of a year, and its streaks
+//! - [`obsidian_uri`] - `obsidian://open` URIs of diary files, handed to the system opener (`save --open`)
+//! - `testing` - Fake repositories, temporary vaults and logged-commit entries for tests (`testing` feature)
 //!
 //! ## Features
 //!
@@ -62,5 +133,76 @@
 //! - ✅ Customizable storage path with date-based organization
 //! - ✅ Pipe escaping in commit messages for Markdown table safety
 //! - ✅ Thread-safe configuration with `OnceCell`
+pub mod amend;
+pub mod annotate;
+pub mod append_queue;
+pub mod atomic_write;
+pub mod batch;
+pub mod bootstrap;
+pub mod branch;
+pub mod branch_summary;
+pub mod build_status;
+pub mod category;
+pub mod collision;
+pub mod commit_entry;
+pub mod completions;
 pub mod config;
+pub mod config_format;
+pub mod conventional;
+pub mod daemon;
+pub mod daily_note;
+pub mod diary_lock;
+pub mod diary_moves;
+pub mod diary_parser;
+pub mod diary_renderer;
+pub mod enrich;
+pub mod entry_id;
+pub mod existing_note;
+pub mod fan_out;
+pub mod forge;
+pub mod frontmatter;
+pub mod git_env;
+pub mod git_stats;
+pub mod git_tags;
+pub mod github;
+pub mod goals;
+pub mod heatmap;
+pub mod hook;
+pub mod hydration;
+pub mod hygiene;
+pub mod language;
+pub mod links;
+pub mod metrics;
+pub mod obsidian_uri;
+pub mod paths;
+pub mod porcelain;
+pub mod push;
+pub mod quarantine;
+pub mod recent;
+pub mod related;
+pub mod report;
+pub mod routing;
+pub mod same_day;
+pub mod shell_command;
+pub mod sinks;
+pub mod skip;
+pub mod state;
+pub mod state_dir;
+pub mod stats_compare;
+pub mod templater;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod time_tracking;
+pub mod timezone;
+pub mod today;
+pub mod trailers;
+pub mod trash;
+pub mod undo;
+pub mod vault_git;
+pub mod vault_stats;
+pub mod vaults;
+pub mod verify;
 pub mod vim_commit;
+pub mod webhook;
+pub mod weekly_log;
+pub mod wsl;
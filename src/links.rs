@@ -0,0 +1,149 @@
+use std::fmt::Write;
+use std::path::Component;
+use std::path::Path;
+
+/// Returns the path of a note relative to the vault root, with `/` separators.
+///
+/// This is the form Obsidian uses in links, whatever the platform. The path
+/// is compared component by component, so a trailing slash on the root or a
+/// `./` segment makes no difference.
+///
+/// # Returns
+///
+/// `None` if `path` is not inside `obsidian_root_path_dir`, or is the root itself.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::links::vault_relative_path;
+/// use std::path::Path;
+///
+/// assert_eq!(
+///     vault_relative_path(
+///         Path::new("/vault/"),
+///         Path::new("/vault/📅 Diaries/0. Commits/2025-01-14.md")
+///     )
+///     .as_deref(),
+///     Some("📅 Diaries/0. Commits/2025-01-14.md")
+/// );
+/// assert_eq!(vault_relative_path(Path::new("/vault"), Path::new("/tmp/note.md")), None);
+/// ```
+#[must_use]
+pub fn vault_relative_path(obsidian_root_path_dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(obsidian_root_path_dir).ok()?;
+    let segments = relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(segment) => Some(segment.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if segments.is_empty() {
+        return None;
+    }
+    Some(segments.join("/"))
+}
+
+/// Builds an Obsidian wikilink to a note, e.g. `[[📅 Diaries/2025-01-14|Tuesday]]`.
+///
+/// The target is the vault-relative path without its `.md` extension, so the
+/// link stays unambiguous when several folders hold a note with the same name.
+/// Spaces and emoji are kept as is, which is what Obsidian expects inside
+/// `[[...]]`.
+///
+/// In a Markdown table cell, the `|` before the alias must be escaped as `\|`.
+///
+/// # Arguments
+///
+/// * `obsidian_root_path_dir` - The vault root
+/// * `path` - Absolute path of the note
+/// * `alias` - Text shown instead of the note name
+///
+/// # Returns
+///
+/// `None` if the note is not inside the vault.
+#[must_use]
+pub fn wikilink(obsidian_root_path_dir: &Path, path: &Path, alias: Option<&str>) -> Option<String> {
+    let relative = vault_relative_path(obsidian_root_path_dir, path)?;
+    let target = relative.strip_suffix(".md").unwrap_or(&relative);
+
+    Some(match alias {
+        Some(alias) => format!("[[{target:}|{alias:}]]"),
+        None => format!("[[{target:}]]"),
+    })
+}
+
+/// Percent-encodes a vault-relative path for use as a Markdown link target.
+///
+/// Every byte other than ASCII letters, digits, `-`, `.`, `_`, `~` and the `/`
+/// separators is written as `%XX`, so spaces, parentheses, `#` and the UTF-8
+/// bytes of emoji or accented folder names are all encoded the same way.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::links::encode_link_target;
+///
+/// assert_eq!(encode_link_target("📅 Diaries/a (b).md"), "%F0%9F%93%85%20Diaries/a%20%28b%29.md");
+/// ```
+#[must_use]
+pub fn encode_link_target(relative_path: &str) -> String {
+    let mut encoded = String::with_capacity(relative_path.len());
+    for byte in relative_path.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~' | b'/') {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod links_tests {
+    use super::*;
+
+    const DIARY: &str = "/vault/📅 Diaries/0. Commits/2025/01-January/2025-01-14.md";
+
+    #[test]
+    fn test_vault_relative_path() {
+        assert_eq!(
+            vault_relative_path(Path::new("/vault/"), Path::new(DIARY)).as_deref(),
+            Some("📅 Diaries/0. Commits/2025/01-January/2025-01-14.md")
+        );
+        assert_eq!(
+            vault_relative_path(Path::new("/vault"), Path::new("/vaults/note.md")),
+            None
+        );
+        assert_eq!(
+            vault_relative_path(Path::new("/vault"), Path::new("/vault")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_wikilink() {
+        assert_eq!(
+            wikilink(Path::new("/vault"), Path::new(DIARY), None).as_deref(),
+            Some("[[📅 Diaries/0. Commits/2025/01-January/2025-01-14]]")
+        );
+        assert_eq!(
+            wikilink(Path::new("/vault"), Path::new(DIARY), Some("Tuesday")).as_deref(),
+            Some("[[📅 Diaries/0. Commits/2025/01-January/2025-01-14|Tuesday]]")
+        );
+        assert_eq!(
+            wikilink(Path::new("/vault"), Path::new("/elsewhere/note.md"), None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_encode_link_target() {
+        assert_eq!(
+            encode_link_target("Projets/Été (2025)/#1.md"),
+            "Projets/%C3%89t%C3%A9%20%282025%29/%231.md"
+        );
+    }
+}
@@ -3,18 +3,210 @@
 //!
 #![cfg_attr(coverage_nightly, feature(coverage_attribute))]
 
+use rusty_commit_saver::vim_commit::category_tag;
 use rusty_commit_saver::vim_commit::check_diary_path_exists;
-use rusty_commit_saver::vim_commit::create_diary_file;
 use rusty_commit_saver::vim_commit::create_directories_for_new_entry;
+use rusty_commit_saver::vim_commit::head_branch_name;
+use rusty_commit_saver::vim_commit::overflow_note_path;
+use rusty_commit_saver::vim_commit::render_diary_file;
+use rusty_commit_saver::vim_commit::CommitError;
 use rusty_commit_saver::vim_commit::CommitSaver;
+use rusty_commit_saver::vim_commit::EntryFormat;
+use rusty_commit_saver::vim_commit::EntryKind;
+use rusty_commit_saver::vim_commit::TableColumn;
+use rusty_commit_saver::webhook::Webhook;
 
+use rusty_commit_saver::annotate::annotate_logged_commit;
+use rusty_commit_saver::append_queue::AppendQueue;
+use rusty_commit_saver::atomic_write::write_atomically;
+use rusty_commit_saver::batch::collect_commit_batch;
+use rusty_commit_saver::batch::parse_commit_list;
+use rusty_commit_saver::branch::branch_slug;
+use rusty_commit_saver::branch::branch_tag;
+use rusty_commit_saver::build_status::check_build_status;
+use rusty_commit_saver::category::resolve_category;
+use rusty_commit_saver::collision::number_same_second_entry;
+use rusty_commit_saver::collision::SameSecondPolicy;
+use rusty_commit_saver::commit_entry::CommitEntry;
+use rusty_commit_saver::conventional::commit_type_tag;
+use rusty_commit_saver::conventional::parse_conventional_commit;
+use rusty_commit_saver::diary_lock::DiaryLock;
+use rusty_commit_saver::diary_lock::LOCK_TIMEOUT;
+use rusty_commit_saver::diary_moves::follow_diary_move;
+use rusty_commit_saver::diary_moves::rename_event;
+use rusty_commit_saver::diary_moves::DiaryMoves;
+use rusty_commit_saver::diary_parser::parse_diary_table;
+use rusty_commit_saver::diary_parser::read_diary_snapshot;
+use rusty_commit_saver::enrich::enrich_diary_files;
+use rusty_commit_saver::existing_note::adopt_existing_note;
+use rusty_commit_saver::fan_out::commit_appends;
+use rusty_commit_saver::fan_out::commit_rows;
+use rusty_commit_saver::fan_out::PendingAppend;
+use rusty_commit_saver::fan_out::PendingRow;
+use rusty_commit_saver::fan_out::Spool;
+use rusty_commit_saver::forge::normalize_remote_url;
+use rusty_commit_saver::frontmatter::merge_frontmatter_list;
+use rusty_commit_saver::frontmatter::write_frontmatter_update;
+use rusty_commit_saver::git_env::hook_repository;
+use rusty_commit_saver::git_env::parse_post_receive_input;
+use rusty_commit_saver::git_env::received_commits;
+use rusty_commit_saver::git_stats::CommitSize;
+use rusty_commit_saver::git_tags::nearest_annotated_tag;
+use rusty_commit_saver::github::existing_hashes;
+use rusty_commit_saver::github::fetch_github_commits;
+use rusty_commit_saver::github::GithubCommit;
+use rusty_commit_saver::github::GITHUB_API_URL;
+use rusty_commit_saver::github::GITHUB_TOKEN_ENV_VAR;
+use rusty_commit_saver::goals::commits_in_week;
+use rusty_commit_saver::goals::diary_commits_in_week;
+use rusty_commit_saver::goals::update_weekly_note;
+use rusty_commit_saver::goals::weekly_note_path;
+use rusty_commit_saver::goals::WeeklyGoal;
+use rusty_commit_saver::goals::WeeklyProgress;
+use rusty_commit_saver::goals::DEFAULT_WEEKLY_NOTE_TEMPLATE;
+use rusty_commit_saver::heatmap::render_heatmap;
+use rusty_commit_saver::heatmap::YearActivity;
+use rusty_commit_saver::hook::hook_path;
+use rusty_commit_saver::hook::install_hook;
+use rusty_commit_saver::hook::uninstall_hook;
+use rusty_commit_saver::hook::HookChange;
+use rusty_commit_saver::hydration::ensure_hydrated;
+use rusty_commit_saver::hydration::HYDRATION_ATTEMPTS;
+use rusty_commit_saver::hydration::HYDRATION_RETRY_DELAY;
+use rusty_commit_saver::hygiene::message_hygiene_item;
+use rusty_commit_saver::hygiene::record_message_hygiene;
+use rusty_commit_saver::hygiene::NEEDS_BETTER_MESSAGE_TAG;
+use rusty_commit_saver::language::detect_language;
+use rusty_commit_saver::language::language_tag;
+use rusty_commit_saver::links::vault_relative_path;
+use rusty_commit_saver::links::wikilink;
+use rusty_commit_saver::metrics::enabled_features;
+use rusty_commit_saver::metrics::MetricsStore;
+use rusty_commit_saver::obsidian_uri::default_vault_name;
+use rusty_commit_saver::obsidian_uri::open_note_uri;
+use rusty_commit_saver::obsidian_uri::open_uri;
+use rusty_commit_saver::paths::check_obsidian_vault;
+use rusty_commit_saver::paths::check_vault_writable;
+use rusty_commit_saver::paths::flat_date_path_template;
+use rusty_commit_saver::paths::repository_name_from_url;
+use rusty_commit_saver::paths::ReadOnlyVault;
+use rusty_commit_saver::paths::READ_ONLY_VAULT_EXIT_CODE;
+use rusty_commit_saver::porcelain::SaveReport;
+use rusty_commit_saver::porcelain::SaveStatus;
+use rusty_commit_saver::push::parse_pre_push_input;
+use rusty_commit_saver::push::push_entries;
+use rusty_commit_saver::quarantine::quarantine_if_malformed;
+use rusty_commit_saver::recent::find_repositories;
+use rusty_commit_saver::recent::recent_unlogged_commits;
+use rusty_commit_saver::related::update_related_repos_note;
+use rusty_commit_saver::report::collect_vault_report;
+use rusty_commit_saver::report::render_vault_report;
+use rusty_commit_saver::routing::route_commit_path;
+use rusty_commit_saver::same_day::other_diary_files_for_day;
+use rusty_commit_saver::sinks::open_sinks;
+use rusty_commit_saver::sinks::EntrySink;
+use rusty_commit_saver::sinks::SinkKind;
+use rusty_commit_saver::skip::is_bot_commit;
+use rusty_commit_saver::skip::is_ignored_branch;
+use rusty_commit_saver::skip::is_ignored_message;
+use rusty_commit_saver::skip::should_skip_commit;
+use rusty_commit_saver::skip::SKIP_ENV_VAR;
+use rusty_commit_saver::state::EntryFilter;
+use rusty_commit_saver::state::LoggedEntry;
+use rusty_commit_saver::state::StateIndex;
+use rusty_commit_saver::state_dir::upgrade_state_dir;
+use rusty_commit_saver::state_dir::NewerStateSchema;
+use rusty_commit_saver::stats_compare::Period;
+use rusty_commit_saver::stats_compare::PeriodComparison;
+use rusty_commit_saver::templater::render_diary_file_from_handlebars;
+use rusty_commit_saver::templater::render_diary_file_from_template;
+use rusty_commit_saver::time_tracking::query_current_activity;
+use rusty_commit_saver::timezone::entry_timezone;
+use rusty_commit_saver::timezone::system_timezone;
+use rusty_commit_saver::timezone::DiaryTimezone;
+use rusty_commit_saver::today::day_diary_paths;
+use rusty_commit_saver::today::render_day_table;
+use rusty_commit_saver::trailers::trailer_tags;
+use rusty_commit_saver::trailers::TrailerTags;
+use rusty_commit_saver::undo::undo_logged_commit;
+use rusty_commit_saver::undo::UndoMode;
+use rusty_commit_saver::undo::UndoneEntry;
+use rusty_commit_saver::vault_git::commit_diary_file;
+use rusty_commit_saver::vault_git::is_vault_sync_message;
+use rusty_commit_saver::vault_git::render_vault_commit_message;
+use rusty_commit_saver::vault_stats::collect_diary_commits;
+use rusty_commit_saver::vault_stats::render_vault_stats;
+use rusty_commit_saver::vault_stats::render_vault_stats_porcelain;
+use rusty_commit_saver::vault_stats::write_stats_note;
+use rusty_commit_saver::vault_stats::VaultStats;
+use rusty_commit_saver::vaults::matching_vaults;
+use rusty_commit_saver::verify::find_duplicate_days;
+use rusty_commit_saver::verify::fix_issues;
+use rusty_commit_saver::verify::verify_vault;
+use rusty_commit_saver::verify::VerifyIssue;
+
+use rusty_commit_saver::bootstrap::bootstrap_config;
+use rusty_commit_saver::bootstrap::confirm;
+use rusty_commit_saver::bootstrap::detect_vault;
+use rusty_commit_saver::bootstrap::init_config;
+use rusty_commit_saver::branch_summary::branch_diff_stats;
+use rusty_commit_saver::branch_summary::merged_commits;
+use rusty_commit_saver::branch_summary::write_branch_summary;
+use rusty_commit_saver::branch_summary::BranchSummary;
+use rusty_commit_saver::completions::write_registration;
+use rusty_commit_saver::config::get_or_default_config_ini_path;
+use rusty_commit_saver::config::AnnotateArgs;
+use rusty_commit_saver::config::Command;
+use rusty_commit_saver::config::CompletionsArgs;
+use rusty_commit_saver::config::ConfigArgs;
+use rusty_commit_saver::config::ConfigCommand;
+use rusty_commit_saver::config::EventArgs;
+use rusty_commit_saver::config::EventCommand;
 use rusty_commit_saver::config::GlobalVars;
+use rusty_commit_saver::config::HeatmapArgs;
+use rusty_commit_saver::config::HookArgs;
+use rusty_commit_saver::config::ImportArgs;
+use rusty_commit_saver::config::LogRecentArgs;
+use rusty_commit_saver::config::MetricsArgs;
+use rusty_commit_saver::config::MetricsCommand;
+use rusty_commit_saver::config::ReportArgs;
+use rusty_commit_saver::config::SaveArgs;
+use rusty_commit_saver::config::SaveSettings;
+use rusty_commit_saver::config::StashAction;
+use rusty_commit_saver::config::StatsArgs;
+use rusty_commit_saver::config::UndoArgs;
+use rusty_commit_saver::config::UserInput;
+use rusty_commit_saver::daemon::WatchedRepository;
 
+use chrono::DateTime;
+use chrono::Datelike;
+use chrono::NaiveDate;
+use chrono::TimeDelta;
+use chrono::Utc;
+use clap::CommandFactory;
+use clap::Parser;
+use clap_complete::CompleteEnv;
+use git2::Oid;
+use git2::Repository;
 use log::error;
 use log::info;
+use log::warn;
+use notify::Event;
+use notify::RecursiveMode;
+use notify::Watcher;
+use std::collections::HashSet;
+use std::env;
 use std::error::Error;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::IsTerminal;
+use std::io::Read;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::mpsc;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::Duration;
+use std::time::Instant;
 
 /// Core logic for saving a commit to an Obsidian diary file.
 ///
@@ -26,16 +218,35 @@ use std::path::PathBuf;
 ///
 /// This function is extracted for testability and is called by `main()`.
 ///
+/// With [`SaveSettings::dry_run`] (`save --dry-run`), steps 3 and 4 are only
+/// rendered into the returned report's preview; nothing is written.
+///
+/// A commit whose hash is already in the diary file is not appended again
+/// and reported as [`SaveStatus::AlreadyLogged`], unless
+/// [`SaveSettings::allow_duplicates`] is set (`save --allow-duplicates`).
+///
+/// When [`SaveSettings::vaults`] (`[vaults]`) match the commit's remote,
+/// repository directory or branch, the entry is written to each of them
+/// instead of `obsidian_root_path_dir`, see [`matching_vaults()`]. A vault
+/// that cannot be written only logs a warning, as long as another one is.
+///
+/// The diary is the default output of [`SaveSettings::sinks`] (`[output]`);
+/// a logged commit is then written to the other sinks, see
+/// [`write_to_sinks()`]. Without the `markdown` sink no diary is written.
+///
 /// # Arguments
 ///
 /// * `obsidian_root_path_dir` - Base directory for Obsidian vault (e.g., `/home/user/Obsidian`)
 /// * `obsidian_commit_path` - Subdirectory for commits (e.g., `Diaries/Commits`)
 /// * `template_commit_date_path` - Chrono format for date hierarchy (e.g., `%Y/%m-%B/%F.md`)
+/// * `save_settings` - Table columns and other write-time settings (see [`SaveSettings`])
 ///
 /// # Returns
 ///
-/// - `Ok(())` - Commit was successfully saved to the diary
-/// - `Err(Box<dyn Error>)` - Any step in the process failed
+/// - `Ok(Vec<SaveReport>)` - What happened to the commit in each vault it
+///   was written to (see [`SaveReport`]); [`SaveStatus::NoCommit`] in a
+///   repository without commits
+/// - `Err(Box<dyn Error>)` - Any step in the process failed (in every vault)
 ///
 /// # Errors
 ///
@@ -50,14 +261,15 @@ use std::path::PathBuf;
 ///
 /// ```ignore
 /// use rusty_commit_saver::run_commit_saver;
+/// use rusty_commit_saver::config::SaveSettings;
 /// use std::path::PathBuf;
 ///
 /// let obsidian_root = PathBuf::from("/home/user/Obsidian");
 /// let commit_path = PathBuf::from("Diaries/Commits");
 /// let date_template = "%Y/%m-%B/%F.md"; // YYYY/MM-MonthName/YYYY-MM-DD.md
 ///
-/// match run_commit_saver(obsidian_root, &commit_path, date_template) {
-///     Ok(()) => println!("✓ Commit successfully logged!"),
+/// match run_commit_saver(&obsidian_root, &commit_path, date_template, &SaveSettings::default()) {
+///     Ok(reports) => println!("✓ Commit successfully logged in {} vault(s)!", reports.len()),
 ///     Err(e) => eprintln!("✗ Failed to log commit: {}", e),
 /// }
 /// ```
@@ -88,279 +300,6137 @@ use std::path::PathBuf;
 ///   │ Append commit row to table │
 ///   └──────┬─────────────────────┘
 ///          │
-///   ┌──────▼──────────────────┐
-///   │ Return Ok(())           │
-///   └─────────────────────────┘
+///   ┌──────▼───────────────────────┐
+///   │ Return Ok(Vec<SaveReport>)   │
+///   └──────────────────────────────┘
 /// ```
 pub fn run_commit_saver(
+    obsidian_root_path_dir: &Path,
+    obsidian_commit_path: &Path,
+    template_commit_date_path: &str,
+    save_settings: &SaveSettings,
+) -> Result<Vec<SaveReport>, Box<dyn Error>> {
+    info!("[run_commit_saver()]: Instanciating CommitSaver Struct");
+    let mut commit_saver_struct = match CommitSaver::try_new() {
+        Ok(commit_saver_struct) => commit_saver_struct,
+        Err(CommitError::EmptyRepository) => {
+            info!("[run_commit_saver()]: The repository has no commit yet, nothing to log.");
+            return Ok(vec![empty_repository_report()]);
+        }
+        Err(e) => return Err(e.into()),
+    };
+    if let Some(status) = not_logged_status(&commit_saver_struct, save_settings) {
+        return Ok(vec![SaveReport::new(status, &commit_saver_struct, None)]);
+    }
+    apply_build_status(&mut commit_saver_struct, save_settings);
+    apply_release_tag(&mut commit_saver_struct, save_settings);
+
+    save_entry(
+        &mut commit_saver_struct,
+        obsidian_root_path_dir,
+        obsidian_commit_path,
+        template_commit_date_path,
+        save_settings,
+    )
+}
+
+/// Saves one entry to the outputs of `[output] sinks`: the diary (see
+/// [`DiarySink`]), then the other sinks, see [`open_sinks()`].
+///
+/// Every way of logging an entry (`save`, `--wip`, `--stdin`,
+/// `--post-receive`, events, imports) goes through here. The other sinks get
+/// the entry as it was before the diary wrote it, with its full message, and
+/// only when the diary logged it. Rows that are not commits (WIP,
+/// checkouts, stashes, pushes) are diary-only.
+///
+/// # Returns
+///
+/// The reports of the diary, one per vault written; a single report without
+/// a diary path when the diary is not a sink.
+///
+/// # Errors
+///
+/// Returns the error of the first sink, which nothing was written to yet.
+fn save_entry(
+    commit_saver_struct: &mut CommitSaver,
+    obsidian_root_path_dir: &Path,
+    obsidian_commit_path: &Path,
+    template_commit_date_path: &str,
+    save_settings: &SaveSettings,
+) -> Result<Vec<SaveReport>, Box<dyn Error>> {
+    if let Some(status) = not_logged_status(commit_saver_struct, save_settings) {
+        return Ok(vec![SaveReport::new(status, commit_saver_struct, None)]);
+    }
+    let kinds = save_settings
+        .sinks
+        .iter()
+        .filter(|kind| {
+            **kind == SinkKind::ObsidianMarkdown
+                || commit_saver_struct.details.entry_kind == EntryKind::Commit
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    if save_settings.dry_run && !kinds.contains(&SinkKind::ObsidianMarkdown) {
+        return Ok(vec![SaveReport::new(
+            SaveStatus::DryRun,
+            commit_saver_struct,
+            None,
+        )]);
+    }
+
+    let entry = commit_saver_struct.entry.clone();
+    let mut diary = DiarySink {
+        commit_saver_struct,
+        obsidian_root_path_dir,
+        obsidian_commit_path,
+        template_commit_date_path,
+        save_settings,
+        staged: None,
+        reports: Vec::new(),
+    };
+    write_to_sinks(open_sinks(&kinds, Box::new(&mut diary)), &entry)?;
+
+    if diary.reports.is_empty() {
+        return Ok(vec![SaveReport::new(
+            SaveStatus::Logged,
+            diary.commit_saver_struct,
+            None,
+        )]);
+    }
+    Ok(diary.reports)
+}
+
+/// The Obsidian diary as an [`EntrySink`] (`markdown` in `[output] sinks`):
+/// stages the entry in its vaults with [`stage_vaults()`], writes them with
+/// [`commit_vaults()`] and keeps their reports.
+struct DiarySink<'a> {
+    commit_saver_struct: &'a mut CommitSaver,
+    obsidian_root_path_dir: &'a Path,
+    obsidian_commit_path: &'a Path,
+    template_commit_date_path: &'a str,
+    save_settings: &'a SaveSettings,
+    staged: Option<StagedVaults>,
+    reports: Vec<SaveReport>,
+}
+
+impl EntrySink for DiarySink<'_> {
+    fn name(&self) -> &'static str {
+        "markdown"
+    }
+
+    fn stage_entry(&mut self, entry: &CommitEntry) -> Result<(), Box<dyn Error>> {
+        self.commit_saver_struct.entry.clone_from(entry);
+        self.staged = Some(stage_vaults(
+            self.commit_saver_struct,
+            self.obsidian_root_path_dir.to_path_buf(),
+            self.obsidian_commit_path,
+            self.template_commit_date_path,
+            self.save_settings,
+        )?);
+        Ok(())
+    }
+
+    fn write_entry(&mut self, entry: &CommitEntry) -> Result<bool, Box<dyn Error>> {
+        self.reports = if let Some(staged) = self.staged.take() {
+            commit_vaults(staged, self.save_settings)?
+        } else {
+            self.commit_saver_struct.entry.clone_from(entry);
+            save_to_vaults(
+                self.commit_saver_struct,
+                self.obsidian_root_path_dir.to_path_buf(),
+                self.obsidian_commit_path,
+                self.template_commit_date_path,
+                self.save_settings,
+            )?
+        };
+        Ok(self
+            .reports
+            .iter()
+            .any(|report| report.status == SaveStatus::Logged))
+    }
+}
+
+/// Saves the commit in the vaults of [`matching_vaults()`], or in
+/// `obsidian_root_path_dir` when none matches: every vault is staged with
+/// [`stage_vaults()`] before any is written with [`commit_vaults()`].
+///
+/// Every way of logging an entry goes through here, see [`save_entry()`],
+/// so `[vaults]` routing applies to all of them. The `path` rules match the
+/// working directory of the entry's own repository (see
+/// [`CommitSaver::in_repository()`]), never the current directory; entries
+/// without one (GitHub imports) only match vaults without a `path` rule.
+///
+/// # Returns
+///
+/// One [`SaveReport`] per vault written.
+///
+/// # Errors
+///
+/// Returns the error of [`stage_vaults()`] or [`commit_vaults()`].
+fn save_to_vaults(
+    commit_saver_struct: &mut CommitSaver,
+    obsidian_root_path_dir: PathBuf,
+    obsidian_commit_path: &Path,
+    template_commit_date_path: &str,
+    save_settings: &SaveSettings,
+) -> Result<Vec<SaveReport>, Box<dyn Error>> {
+    let staged = stage_vaults(
+        commit_saver_struct,
+        obsidian_root_path_dir,
+        obsidian_commit_path,
+        template_commit_date_path,
+        save_settings,
+    )?;
+    commit_vaults(staged, save_settings)
+}
+
+/// The vaults of an entry staged by [`stage_vaults()`], for
+/// [`commit_vaults()`] to write.
+struct StagedVaults {
+    /// The staged entries, with the row to spool if writing one fails.
+    staged: Vec<(PendingRow, Box<StagedEntry>)>,
+    /// The reports of the vaults with nothing to write.
+    reports: Vec<SaveReport>,
+    /// The rows of the vaults that could not be staged.
+    failed: Vec<PendingRow>,
+    first_error: Option<Box<dyn Error>>,
+}
+
+/// Stages the commit in every vault it goes to with [`stage_commit_entry()`],
+/// without writing any of them.
+///
+/// # Errors
+///
+/// Returns the first staging error when no vault could be staged.
+fn stage_vaults(
+    commit_saver_struct: &mut CommitSaver,
     obsidian_root_path_dir: PathBuf,
     obsidian_commit_path: &Path,
     template_commit_date_path: &str,
+    save_settings: &SaveSettings,
+) -> Result<StagedVaults, Box<dyn Error>> {
+    let vaults = matching_vaults(
+        &save_settings.vaults,
+        &commit_saver_struct.entry.repository_url,
+        commit_saver_struct.details.workdir.as_deref(),
+        &commit_saver_struct.entry.branch,
+    );
+    let targets = if vaults.is_empty() {
+        vec![("default", obsidian_root_path_dir, obsidian_commit_path)]
+    } else {
+        vaults
+            .into_iter()
+            .map(|vault| {
+                (
+                    vault.name.as_str(),
+                    vault.root_path_dir.clone(),
+                    vault.commit_path(obsidian_commit_path),
+                )
+            })
+            .collect()
+    };
+
+    let mut vaults = StagedVaults {
+        staged: Vec::new(),
+        reports: Vec::new(),
+        failed: Vec::new(),
+        first_error: None,
+    };
+    let pristine = commit_saver_struct.clone();
+    for (name, vault_root, commit_path) in targets {
+        info!("[stage_vaults()]: Staging the entry in the vault: {name:}");
+        let row = PendingRow::new(
+            &pristine,
+            &vault_root,
+            commit_path,
+            template_commit_date_path,
+        );
+        let staged = stage_commit_entry(
+            commit_saver_struct,
+            vault_root,
+            commit_path,
+            template_commit_date_path,
+            save_settings,
+        );
+        *commit_saver_struct = pristine.clone();
+        match staged {
+            Ok(StagedSave::Staged(staged)) => vaults.staged.push((row, staged)),
+            Ok(StagedSave::Done(report)) => vaults.reports.push(report),
+            Err(e) => {
+                warn!("[stage_vaults()]: Could not stage the entry in the vault {name:}: {e:}");
+                vaults.failed.push(row);
+                vaults.first_error.get_or_insert(e);
+            }
+        }
+    }
+    match vaults.first_error.take() {
+        Some(e) if vaults.staged.is_empty() && vaults.reports.is_empty() => Err(e),
+        first_error => Ok(StagedVaults {
+            first_error,
+            ..vaults
+        }),
+    }
+}
+
+/// Writes the vaults staged by [`stage_vaults()`] with [`commit_to_vault()`].
+///
+/// A vault that could not be staged or written while others took the row is
+/// spooled in the state directory, and the rows spooled by earlier saves are
+/// written again, see [`save_spooled_rows()`].
+///
+/// # Returns
+///
+/// One [`SaveReport`] per vault written.
+///
+/// # Errors
+///
+/// Returns the first error when no vault could be written.
+fn commit_vaults(
+    staged_vaults: StagedVaults,
+    save_settings: &SaveSettings,
+) -> Result<Vec<SaveReport>, Box<dyn Error>> {
+    let StagedVaults {
+        staged,
+        mut reports,
+        mut failed,
+        mut first_error,
+    } = staged_vaults;
+    for (row, mut staged) in staged {
+        match commit_to_vault(&mut staged, save_settings) {
+            Ok(report) => reports.push(report),
+            Err(e) => {
+                warn!(
+                    "[commit_vaults()]: Could not write to the vault {:}: {e:}",
+                    row.vault_root.display()
+                );
+                failed.push(row);
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    match first_error {
+        Some(e) if reports.is_empty() => Err(e),
+        _ => {
+            save_spooled_rows(&failed, save_settings);
+            Ok(reports)
+        }
+    }
+}
+
+/// Writes the diary rows spooled by earlier saves, then spools `failed`, the
+/// rows of this save that some vault did not take, see [`commit_rows()`].
+///
+/// Spooled rows go through [`save_to_vault()`] again, so they are inserted at
+/// their place in time and skipped when the diary already has them. Without
+/// a state directory the failed rows are only reported; a dry run retries
+/// nothing.
+fn save_spooled_rows(failed: &[PendingRow], save_settings: &SaveSettings) {
+    if save_settings.dry_run {
+        return;
+    }
+    let Some(state_dir) = save_settings.state_dir.as_deref() else {
+        if !failed.is_empty() {
+            warn!(
+                "[save_spooled_rows()]: No state directory to spool {:} unwritten row(s).",
+                failed.len()
+            );
+        }
+        return;
+    };
+
+    let spooled = commit_rows(&Spool::rows(state_dir), failed, |row| {
+        save_to_vault(
+            &mut row.commit_saver(),
+            row.vault_root.clone(),
+            &row.commit_path,
+            &row.date_template,
+            save_settings,
+        )?;
+        Ok(())
+    });
+    if spooled > 0 {
+        warn!("[save_spooled_rows()]: {spooled:} row(s) left to retry on the next save.");
+    }
+}
+
+/// Writes an entry to the sinks of [`open_sinks()`], in order, stopping at
+/// the first one that passes on it.
+///
+/// Every sink stages the entry first (see [`EntrySink::stage_entry()`]), so
+/// a sink that cannot take it stops the save before any is written. A sink
+/// failing once an earlier one wrote the entry only logs a warning: the
+/// entry is already logged.
+///
+/// # Errors
+///
+/// Returns the error of the first sink that cannot stage the entry, or the
+/// write error of the first sink.
+fn write_to_sinks(
+    mut sinks: Vec<Box<dyn EntrySink + '_>>,
+    entry: &CommitEntry,
 ) -> Result<(), Box<dyn Error>> {
-    info!("[run_commit_saver()]: Instanciating CommitSaver Struct");
-    let mut commit_saver_struct = CommitSaver::new();
+    for sink in &mut sinks {
+        info!(
+            "[write_to_sinks()]: Staging the entry in the {:} sink.",
+            sink.name()
+        );
+        sink.stage_entry(entry)?;
+    }
+
+    let mut logged = false;
+    for mut sink in sinks {
+        match sink.write_entry(entry) {
+            Ok(true) => logged = true,
+            Ok(false) => {
+                info!(
+                    "[write_to_sinks()]: Not logged by the {:} sink, skipping the others.",
+                    sink.name()
+                );
+                break;
+            }
+            Err(e) if !logged => return Err(e),
+            Err(e) => warn!(
+                "[write_to_sinks()]: Could not write to the {:} sink: {e:}",
+                sink.name()
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// Saves the commit in one vault with [`save_commit_entry()`], then
+/// summarizes the merged branch there, see [`commit_to_vault()`].
+///
+/// # Errors
+///
+/// Returns the error of [`save_commit_entry()`].
+fn save_to_vault(
+    commit_saver_struct: &mut CommitSaver,
+    obsidian_root_path_dir: PathBuf,
+    obsidian_commit_path: &Path,
+    template_commit_date_path: &str,
+    save_settings: &SaveSettings,
+) -> Result<SaveReport, Box<dyn Error>> {
+    match stage_commit_entry(
+        commit_saver_struct,
+        obsidian_root_path_dir,
+        obsidian_commit_path,
+        template_commit_date_path,
+        save_settings,
+    )? {
+        StagedSave::Done(report) => Ok(report),
+        StagedSave::Staged(mut staged) => {
+            let report = commit_to_vault(&mut staged, save_settings);
+            *commit_saver_struct = staged.commit_saver_struct;
+            report
+        }
+    }
+}
+
+/// Writes an entry staged in one vault with [`commit_staged_entry()`], then
+/// summarizes the merged branch there when the commit is a merge and
+/// `[branches] merge_summary` is on.
+///
+/// # Errors
+///
+/// Returns the error of [`commit_staged_entry()`].
+fn commit_to_vault(
+    staged: &mut StagedEntry,
+    save_settings: &SaveSettings,
+) -> Result<SaveReport, Box<dyn Error>> {
+    let report = commit_staged_entry(staged, save_settings)?;
+    if save_settings.branch_summary && staged.commit_saver_struct.entry.is_merge() {
+        summarize_merged_branch(
+            &staged.commit_saver_struct,
+            save_settings,
+            &staged.vault_root,
+            &staged.full_path,
+        );
+    }
+    Ok(report)
+}
+
+/// Writes the summary of the branch a logged merge closed out to its diary
+/// file (`[branches] merge_summary`), see [`BranchSummary`].
+///
+/// The branch's commits are found in the repository the entry was read from
+/// (see [`entry_repository()`]) and looked up in the logged-commits index.
+/// Failures only log a warning: the merge is already in the diary.
+fn summarize_merged_branch(
+    commit_saver_struct: &CommitSaver,
+    save_settings: &SaveSettings,
+    vault_root: &Path,
+    diary_path: &Path,
+) {
+    let Some(state_index) = save_settings.state_dir.as_deref().map(StateIndex::new) else {
+        warn!("[summarize_merged_branch()]: No state directory to read the branch's commits from.");
+        return;
+    };
+
+    info!("[summarize_merged_branch()]: Summarizing the branch the merge closed out.");
+    let result = entry_repository(commit_saver_struct).and_then(|git_repo| {
+        let merge = git_repo.find_commit(Oid::from_str(&commit_saver_struct.entry.hash)?)?;
+        let merged = merged_commits(&git_repo, &merge)?;
+        let diff_stats = branch_diff_stats(&git_repo, &merge)?;
+        let entries = state_index.entries()?;
+        let Some(summary) = BranchSummary::from_index(&entries, &merged, diff_stats, vault_root)
+        else {
+            info!("[summarize_merged_branch()]: None of the merged commits was logged.");
+            return Ok(());
+        };
+        write_branch_summary(save_settings.state_dir.as_deref(), diary_path, &summary)
+    });
+    if let Err(e) = result {
+        warn!("[summarize_merged_branch()]: Could not write the branch summary: {e:}");
+    }
+}
+
+/// Opens the repository an entry was read from, its
+/// [`git_dir`](rusty_commit_saver::vim_commit::EntryDetails::git_dir).
+///
+/// # Errors
+///
+/// Returns an error if the entry was not read from a local repository, or
+/// the repository cannot be opened.
+fn entry_repository(commit_saver_struct: &CommitSaver) -> Result<Repository, Box<dyn Error>> {
+    let git_dir = commit_saver_struct
+        .details
+        .git_dir
+        .as_deref()
+        .ok_or("the entry was not read from a local repository")?;
+    Ok(Repository::open(git_dir)?)
+}
+
+/// Report of a save in a repository without commits ([`SaveStatus::NoCommit`]).
+fn empty_repository_report() -> SaveReport {
+    let branch = hook_repository()
+        .map(|git_repo| head_branch_name(&git_repo))
+        .unwrap_or_default();
+    SaveReport {
+        status: SaveStatus::NoCommit,
+        entry_kind: EntryKind::Commit,
+        commit_hash: String::new(),
+        repository_url: String::new(),
+        branch,
+        diary_path: None,
+        preview: None,
+    }
+}
+
+/// Logs the dirty working tree as a WIP row (`save --wip`).
+///
+/// Builds a WIP [`CommitSaver`] from the repository discovered in the current
+/// directory (see [`CommitSaver::wip_from_repo()`]) and writes it through the
+/// same path as regular commits, see [`save_entry()`].
+///
+/// # Errors
+///
+/// Returns an error if no repository is found, the working tree is clean, or
+/// any step of [`save_commit_entry()`] fails.
+pub fn run_wip_saver(
+    obsidian_root_path_dir: &Path,
+    obsidian_commit_path: &Path,
+    template_commit_date_path: &str,
+    save_settings: &SaveSettings,
+) -> Result<Vec<SaveReport>, Box<dyn Error>> {
+    info!("[run_wip_saver()]: Instanciating WIP CommitSaver Struct");
+    let mut commit_saver_struct = CommitSaver::try_new_wip()?;
+    apply_build_status(&mut commit_saver_struct, save_settings);
+
+    save_entry(
+        &mut commit_saver_struct,
+        obsidian_root_path_dir,
+        obsidian_commit_path,
+        template_commit_date_path,
+        save_settings,
+    )
+}
+
+/// Logs every commit listed in `commit_list` in one run (`save --stdin`).
+///
+/// The list is parsed with [`parse_commit_list()`] and resolved with
+/// [`collect_commit_batch()`], so rows are written oldest first and recorded
+/// on the branch `HEAD` points to. Commits already in the logged-commits
+/// index are skipped, which makes re-running a pre-push hook harmless.
+/// A failing commit does not stop the batch: the remaining commits are still
+/// logged and the failures are reported together. The rows are inserted at
+/// their place in time ([`CommitSaver::insert_entry_in_diary()`]), so batches
+/// of several repositories run at once into the same vault keep every diary
+/// in order.
+///
+/// # Returns
+///
+/// One [`SaveReport`] per listed commit and vault, in the order they were
+/// processed.
+///
+/// # Errors
+///
+/// Returns an error if a listed revision cannot be resolved (nothing is
+/// logged then), or if saving one or more commits failed.
+pub fn run_batch_saver(
+    git_repo: &Repository,
+    commit_list: &str,
+    obsidian_root_path_dir: &Path,
+    obsidian_commit_path: &Path,
+    template_commit_date_path: &str,
+    save_settings: &SaveSettings,
+) -> Result<Vec<SaveReport>, Box<dyn Error>> {
+    let revisions = parse_commit_list(commit_list);
+    let batch = collect_commit_batch(git_repo, &revisions, &head_branch_name(git_repo))?;
+    save_commit_batch(
+        batch,
+        obsidian_root_path_dir,
+        obsidian_commit_path,
+        template_commit_date_path,
+        save_settings,
+    )
+}
+
+/// Logs the commits a push brought to a server-side repository
+/// (`save --post-receive`).
+///
+/// `post_receive_input` is what Git writes to the `post-receive` hook's
+/// stdin, one `<old hash> <new hash> <ref name>` line per updated ref. The
+/// commits of each updated branch are found with [`received_commits()`] and
+/// recorded on that branch, so no `HEAD` or working tree is needed; tags and
+/// deleted branches log nothing. The commits are then logged like
+/// [`run_batch_saver()`] does, oldest first.
+///
+/// # Returns
+///
+/// One [`SaveReport`] per pushed commit and vault, in the order they were
+/// processed.
+///
+/// # Errors
+///
+/// Returns an error if the pushed commits cannot be read (nothing is logged
+/// then), or if saving one or more commits failed.
+pub fn run_received_saver(
+    git_repo: &Repository,
+    post_receive_input: &str,
+    obsidian_root_path_dir: &Path,
+    obsidian_commit_path: &Path,
+    template_commit_date_path: &str,
+    save_settings: &SaveSettings,
+) -> Result<Vec<SaveReport>, Box<dyn Error>> {
+    let pushed = parse_post_receive_input(post_receive_input);
+    let mut batch = Vec::new();
+    for received in &pushed {
+        let Some(branch) = received.branch() else {
+            info!(
+                "[run_received_saver()]: Not a branch, skipping: {:}",
+                received.ref_name
+            );
+            continue;
+        };
+        let revisions = received_commits(git_repo, received, &pushed)?;
+        batch.extend(collect_commit_batch(git_repo, &revisions, branch)?);
+    }
+    batch.sort_by_key(|commit_saver_struct| commit_saver_struct.entry.datetime);
+
+    save_commit_batch(
+        batch,
+        obsidian_root_path_dir,
+        obsidian_commit_path,
+        template_commit_date_path,
+        save_settings,
+    )
+}
+
+/// Logs a batch of commits, skipping those already in the logged-commits
+/// index, for [`run_batch_saver()`] and [`run_received_saver()`].
+///
+/// # Errors
+///
+/// Returns an error if the index cannot be read, or if saving one or more
+/// commits failed.
+fn save_commit_batch(
+    batch: Vec<CommitSaver>,
+    obsidian_root_path_dir: &Path,
+    obsidian_commit_path: &Path,
+    template_commit_date_path: &str,
+    save_settings: &SaveSettings,
+) -> Result<Vec<SaveReport>, Box<dyn Error>> {
+    let already_logged = match save_settings.state_dir.as_deref().map(StateIndex::new) {
+        Some(state_index) => state_index
+            .entries()?
+            .into_iter()
+            .map(|entry| entry.commit_hash)
+            .collect::<HashSet<_>>(),
+        None => HashSet::new(),
+    };
+
+    let mut reports = Vec::new();
+    let mut failures = Vec::new();
+    for mut commit_saver_struct in batch {
+        if already_logged.contains(&commit_saver_struct.entry.hash) {
+            info!(
+                "[save_commit_batch()]: Already logged, skipping: {:}",
+                commit_saver_struct.entry.hash
+            );
+            reports.push(SaveReport::new(
+                SaveStatus::AlreadyLogged,
+                &commit_saver_struct,
+                None,
+            ));
+            continue;
+        }
+
+        match save_entry(
+            &mut commit_saver_struct,
+            obsidian_root_path_dir,
+            obsidian_commit_path,
+            template_commit_date_path,
+            save_settings,
+        ) {
+            Ok(saved) => reports.extend(saved),
+            Err(e) => {
+                warn!(
+                    "[save_commit_batch()]: Could not log {:}: {e:}",
+                    commit_saver_struct.entry.hash
+                );
+                failures.push(format!("{:}: {e:}", commit_saver_struct.entry.hash));
+            }
+        }
+    }
+
+    info!(
+        "[save_commit_batch()]: Logged {:} commit(s).",
+        reports
+            .iter()
+            .filter(|report| report.status == SaveStatus::Logged)
+            .count()
+    );
+    if failures.is_empty() {
+        Ok(reports)
+    } else {
+        Err(format!(
+            "Could not log {:} commit(s): {:}",
+            failures.len(),
+            failures.join("; ")
+        )
+        .into())
+    }
+}
+
+/// Backfills diary entries from public GitHub commits (`import --github-user`).
+///
+/// Commits whose hash is already in the diary, in the logged-commits index or
+/// in a diary table under a commits root (see [`existing_hashes()`]), are
+/// skipped, so re-running the import is harmless and commits logged by the
+/// hook on this machine are not repeated. Like [`run_batch_saver()`], a
+/// failing commit does not stop the import, and rows are inserted at their
+/// place in time.
+///
+/// # Returns
+///
+/// One [`SaveReport`] per commit and vault, in the order of `commits` (oldest
+/// first).
+///
+/// # Errors
+///
+/// Returns an error if the existing hashes cannot be read (nothing is logged
+/// then), or if saving one or more commits failed.
+pub fn run_github_import(
+    commits: &[GithubCommit],
+    obsidian_root_path_dir: &Path,
+    obsidian_commit_path: &Path,
+    template_commit_date_path: &str,
+    save_settings: &SaveSettings,
+) -> Result<Vec<SaveReport>, Box<dyn Error>> {
+    let logged_entries = match save_settings.state_dir.as_deref().map(StateIndex::new) {
+        Some(state_index) => state_index.entries()?,
+        None => Vec::new(),
+    };
+    let roots = commits_roots(obsidian_root_path_dir, obsidian_commit_path, save_settings);
+    let mut already_logged = existing_hashes(&roots, &logged_entries)?;
+
+    let mut reports = Vec::new();
+    let mut failures = Vec::new();
+    for commit in commits {
+        let mut commit_saver_struct = commit.to_commit_saver();
+        if !already_logged.insert(commit.sha.clone()) {
+            info!(
+                "[run_github_import()]: Already in the diary, skipping: {:}",
+                commit.sha
+            );
+            reports.push(SaveReport::new(
+                SaveStatus::AlreadyLogged,
+                &commit_saver_struct,
+                None,
+            ));
+            continue;
+        }
+
+        match save_entry(
+            &mut commit_saver_struct,
+            obsidian_root_path_dir,
+            obsidian_commit_path,
+            template_commit_date_path,
+            save_settings,
+        ) {
+            Ok(saved) => reports.extend(saved),
+            Err(e) => {
+                warn!("[run_github_import()]: Could not log {:}: {e:}", commit.sha);
+                failures.push(format!("{:}: {e:}", commit.sha));
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(reports)
+    } else {
+        Err(format!(
+            "Could not import {:} commit(s): {:}",
+            failures.len(),
+            failures.join("; ")
+        )
+        .into())
+    }
+}
+
+/// Logs a workday event (`event checkout` / `event stash` / `event push`) as a diary row.
+///
+/// Events are opt-in: when the matching `[events]` toggle in
+/// [`SaveSettings`] is off, nothing is written. Branch checkouts are only
+/// logged when the `post-checkout` hook reports a branch checkout (`1`) and
+/// the last `HEAD` move was a switch between two refs, see
+/// [`CommitSaver::checkout_from_repo()`]. Pushes read the pushed refs from
+/// stdin and are logged by [`run_push_saver()`].
+///
+/// # Errors
+///
+/// Returns an error if the repository or its reflogs cannot be read, or any
+/// step of [`save_commit_entry()`] fails.
+pub fn run_event_saver(
+    git_repo: &Repository,
+    event: &EventCommand,
+    obsidian_root_path_dir: &Path,
+    obsidian_commit_path: &Path,
+    template_commit_date_path: &str,
+    save_settings: &SaveSettings,
+) -> Result<(), Box<dyn Error>> {
+    let commit_saver_struct = match event {
+        EventCommand::Checkout { checkout_type, .. } => {
+            if !save_settings.log_branch_switches {
+                info!("[run_event_saver()]: Branch switches are disabled, skipping.");
+                return Ok(());
+            }
+            if checkout_type.as_deref() == Some("0") {
+                info!("[run_event_saver()]: File checkout, skipping.");
+                return Ok(());
+            }
+            CommitSaver::checkout_from_repo(git_repo)?
+        }
+        EventCommand::Stash { action } => {
+            if !save_settings.log_stash {
+                info!("[run_event_saver()]: Stash events are disabled, skipping.");
+                return Ok(());
+            }
+            Some(match action {
+                StashAction::Push => CommitSaver::stash_push_from_repo(git_repo)?,
+                StashAction::Pop => CommitSaver::stash_pop_from_repo(git_repo)?,
+            })
+        }
+        EventCommand::Push { remote, .. } => {
+            if !save_settings.log_pushes {
+                info!("[run_event_saver()]: Push events are disabled, skipping.");
+                return Ok(());
+            }
+            let mut push_input = String::new();
+            std::io::stdin().read_to_string(&mut push_input)?;
+            return run_push_saver(
+                git_repo,
+                remote.as_deref().unwrap_or("origin"),
+                &push_input,
+                obsidian_root_path_dir,
+                obsidian_commit_path,
+                template_commit_date_path,
+                save_settings,
+            )
+            .map(|_| ());
+        }
+    };
+
+    let Some(mut commit_saver_struct) = commit_saver_struct else {
+        info!("[run_event_saver()]: Nothing to log for this event.");
+        return Ok(());
+    };
+
+    save_entry(
+        &mut commit_saver_struct,
+        obsidian_root_path_dir,
+        obsidian_commit_path,
+        template_commit_date_path,
+        save_settings,
+    )
+    .map(|_| ())
+}
+
+/// Logs one summary row per ref of the `pre-push` hook input (`event push`).
+///
+/// The refs are parsed with [`parse_pre_push_input()`] and summarized by
+/// [`push_entries()`] (`main: 5 commits, abc1234..def5678`), for users who
+/// journal pushes rather than individual commits. Deleted refs are skipped.
+///
+/// # Returns
+///
+/// One [`SaveReport`] per logged row.
+///
+/// # Errors
+///
+/// Returns an error if a pushed commit cannot be found in the repository, or
+/// any step of [`save_commit_entry()`] fails.
+pub fn run_push_saver(
+    git_repo: &Repository,
+    remote: &str,
+    push_input: &str,
+    obsidian_root_path_dir: &Path,
+    obsidian_commit_path: &Path,
+    template_commit_date_path: &str,
+    save_settings: &SaveSettings,
+) -> Result<Vec<SaveReport>, Box<dyn Error>> {
+    let pushed_refs = parse_pre_push_input(push_input);
+    info!(
+        "[run_push_saver()]: Logging {:} pushed ref(s) to {remote:}.",
+        pushed_refs.len()
+    );
+
+    let mut reports = Vec::new();
+    for mut commit_saver_struct in push_entries(git_repo, remote, &pushed_refs)? {
+        reports.extend(save_entry(
+            &mut commit_saver_struct,
+            obsidian_root_path_dir,
+            obsidian_commit_path,
+            template_commit_date_path,
+            save_settings,
+        )?);
+    }
+    Ok(reports)
+}
+
+/// Writes an already-built [`CommitSaver`] into its dated diary file.
+///
+/// Applies the [`SaveSettings`] to the entry, resolves the diary path from the
+/// entry timestamp and the `[routing]` table (see [`route_commit_path()`]), creates the directories and the diary template when the
+/// file is missing, then appends the row. Shared by [`run_commit_saver()`],
+/// [`run_wip_saver()`] and [`run_event_saver()`].
+///
+/// When a state directory is configured, the duration estimate is read from
+/// the logged-commits [`StateIndex`] and regular commits are recorded in it.
+/// When a time tracker is configured, its current activity is recorded too.
+/// When `[categories]` rules match, the entry category is added to the
+/// frontmatter of the diary file, including an already existing one; so is
+/// the `#lang/<code>` tag when `[language] detect` is on.
+/// New diary files are created from the user's Handlebars template when
+/// `[templates] diary_file` is set, or from their daily-note template when
+/// `[templates] diary_template` is set, see [`render_new_diary_file()`].
+/// Existing notes without a commit table (e.g. made by the Daily Notes
+/// plugin) get one under the `[obsidian] insert_under` heading, and the diary
+/// tags, see [`adopt_existing_note()`].
+/// The save is staged first, see [`stage_commit_entry()`]: nothing is written
+/// until the diary is locked and checked and the rows of the other notes are
+/// rendered. [`commit_staged_entry()`] then writes them.
+/// Other saves to the same diary wait for this one: the [`DiaryLock`] is
+/// held from the duplicate check until the row is in the diary and the index.
+/// Before touching the diary file, cloud placeholders (iCloud/OneDrive) are
+/// hydrated, see [`ensure_hydrated()`].
+/// Rows longer than the configured maximum get a shortened message and the
+/// full text goes to the per-day overflow note, see [`overflow_note_path()`].
+/// With a weekly goal, the `goal`/`done` properties of the weekly note are
+/// updated after each commit, and with `[related_repos]` on and a state index
+/// the related repositories note is rewritten, see [`update_rollup_notes()`].
+/// When the vault auto-commit is on, the diary file is then committed in the
+/// vault repository, see [`commit_diary_file()`]. Commits made by that
+/// auto-commit are never logged themselves, so a hook in the vault repository
+/// cannot loop. Commits with an `Rcs-Skip: true` trailer, or saved while
+/// `RCS_SKIP` is set, are skipped too, see [`should_skip_commit()`].
+/// Index, tracker, weekly note, related repos note and vault commit failures only log a warning:
+/// the diary row is what matters.
+///
+/// # Returns
+///
+/// A [`SaveReport`] telling whether the entry was logged (and where) or why it
+/// was not, as printed by `save --porcelain`.
+///
+/// # Errors
+///
+/// Returns an error if `[safety] require_vault` is on and the vault root has
+/// no `.obsidian` directory (see [`check_obsidian_vault()`]), if the diary
+/// path is not valid UTF-8, if the diary file is a cloud placeholder that
+/// cannot be hydrated, if another save holds the diary lock for longer than
+/// [`LOCK_TIMEOUT`], or if creating the directories, the diary file, or
+/// appending the row fails.
+pub fn save_commit_entry(
+    commit_saver_struct: &mut CommitSaver,
+    obsidian_root_path_dir: PathBuf,
+    obsidian_commit_path: &Path,
+    template_commit_date_path: &str,
+    save_settings: &SaveSettings,
+) -> Result<SaveReport, Box<dyn Error>> {
+    match stage_commit_entry(
+        commit_saver_struct,
+        obsidian_root_path_dir,
+        obsidian_commit_path,
+        template_commit_date_path,
+        save_settings,
+    )? {
+        StagedSave::Done(report) => Ok(report),
+        StagedSave::Staged(mut staged) => {
+            let report = commit_staged_entry(&mut staged, save_settings);
+            *commit_saver_struct = staged.commit_saver_struct;
+            report
+        }
+    }
+}
+
+/// A save staged by [`stage_commit_entry()`], for [`commit_staged_entry()`]
+/// to write: the diary file is locked, hydrated and checked for the entry,
+/// and the appends to the other notes are rendered.
+struct StagedEntry {
+    commit_saver_struct: CommitSaver,
+    vault_root: PathBuf,
+    commits_root: PathBuf,
+    full_path: PathBuf,
+    state_index: Option<StateIndex>,
+    diary_lock: Option<DiaryLock>,
+    appends: Vec<PendingAppend>,
+}
+
+/// What [`stage_commit_entry()`] made of an entry.
+enum StagedSave {
+    /// The entry is ready to be written.
+    Staged(Box<StagedEntry>),
+    /// Nothing to write; the report tells why (skipped, already logged, dry
+    /// run).
+    Done(SaveReport),
+}
+
+/// Prepares the save of [`save_commit_entry()`] without writing anything.
+///
+/// Applies the settings to the entry, resolves its diary path, checks that
+/// the vault can be written, takes the diary lock, hydrates the diary file,
+/// checks it for the entry and renders the appends to the other notes (see
+/// [`stage_fan_out()`]). A dry run ends here with its preview.
+///
+/// # Errors
+///
+/// Returns the errors of [`save_commit_entry()`] raised before anything is
+/// written: a missing `.obsidian` directory, a read-only vault, a diary path
+/// that is not valid UTF-8, a placeholder that cannot be hydrated, a lock
+/// timeout or an append that cannot be rendered.
+fn stage_commit_entry(
+    commit_saver_struct: &mut CommitSaver,
+    obsidian_root_path_dir: PathBuf,
+    obsidian_commit_path: &Path,
+    template_commit_date_path: &str,
+    save_settings: &SaveSettings,
+) -> Result<StagedSave, Box<dyn Error>> {
+    if let Some(status) = not_logged_status(commit_saver_struct, save_settings) {
+        return Ok(StagedSave::Done(SaveReport::new(
+            status,
+            commit_saver_struct,
+            None,
+        )));
+    }
+    if save_settings.require_vault {
+        check_obsidian_vault(&obsidian_root_path_dir)?;
+    }
+
+    let state_index = save_settings.state_dir.as_deref().map(StateIndex::new);
+    apply_save_settings(commit_saver_struct, state_index.as_ref(), save_settings);
+
+    info!("[stage_commit_entry()]: Preparing the diary entry path to the new commit.");
+    let commit_path = route_commit_path(
+        &save_settings.routes,
+        &commit_saver_struct.entry.repository_url,
+    )
+    .unwrap_or(obsidian_commit_path);
+    let date_path_template = if save_settings.flat_layout {
+        flat_date_path_template(template_commit_date_path)
+    } else {
+        template_commit_date_path
+    };
+    let diary_entry_path =
+        commit_saver_struct.prepare_path_for_commit(commit_path, date_path_template);
+
+    let vault_root = obsidian_root_path_dir.clone();
+    let commits_root = obsidian_root_path_dir.join(commit_path);
+    let mut full_path = obsidian_root_path_dir;
+    for directory in diary_entry_path.split('/') {
+        full_path.push(directory);
+    }
+    let full_path = follow_moved_diary(full_path, save_settings);
+    full_path
+        .to_str()
+        .ok_or("Could not convert path to string")?;
+    let diary_lock = lock_diary(&full_path, save_settings)?;
+
+    if is_duplicate_entry(commit_saver_struct, &full_path, save_settings)? {
+        return Ok(StagedSave::Done(SaveReport::new(
+            SaveStatus::AlreadyLogged,
+            commit_saver_struct,
+            None,
+        )));
+    }
+    if save_settings.dry_run {
+        return preview_commit_entry(commit_saver_struct, full_path, save_settings)
+            .map(StagedSave::Done);
+    }
+    let appends = stage_fan_out(commit_saver_struct, save_settings, &vault_root)?;
+
+    info!("[stage_commit_entry()]: Making sure the diary file is not a cloud placeholder.");
+    ensure_hydrated(&full_path, HYDRATION_ATTEMPTS, HYDRATION_RETRY_DELAY)?;
+
+    Ok(StagedSave::Staged(Box::new(StagedEntry {
+        commit_saver_struct: commit_saver_struct.clone(),
+        vault_root,
+        commits_root,
+        full_path,
+        state_index,
+        diary_lock,
+        appends,
+    })))
+}
+
+/// Writes a save staged by [`stage_commit_entry()`]: the diary row (creating
+/// the diary file when missing), the state index entry, then, with the lock
+/// released, the notes summarizing the commits (see
+/// [`update_rollup_notes()`]), the webhook and the vault commit.
+///
+/// # Errors
+///
+/// Returns an error if the diary file cannot be quarantined, created or
+/// written; the other notes are then not written either.
+fn commit_staged_entry(
+    staged: &mut StagedEntry,
+    save_settings: &SaveSettings,
+) -> Result<SaveReport, Box<dyn Error>> {
+    let StagedEntry {
+        ref mut commit_saver_struct,
+        ref vault_root,
+        ref commits_root,
+        ref full_path,
+        ref state_index,
+        ref mut diary_lock,
+        ref appends,
+    } = *staged;
+    let stringed_root_path_dir = full_path.display();
+    quarantine_if_malformed(full_path, Utc::now())?;
+
+    info!("[commit_staged_entry()]: Checking if Diary file and/or path exists.");
+    if check_diary_path_exists(full_path).is_ok() {
+        info!("[commit_staged_entry()]: Diary file and path exists: {stringed_root_path_dir:}");
+        update_existing_diary(commit_saver_struct, full_path, save_settings)?;
+    } else {
+        info!("[commit_staged_entry()]: Diary file and or path DO NOT exist.");
+        warn_about_same_day_files(commits_root, full_path, commit_saver_struct);
+        info!("[commit_staged_entry()]: Creating the directories for the new entry.");
+        create_directories_for_new_entry(full_path)?;
+
+        info!("[commit_staged_entry()]: Creating the files for the new entry.");
+        let content = render_new_diary_file(full_path, commit_saver_struct, save_settings);
+        info!("[commit_staged_entry()]: Writing the new diary file: {stringed_root_path_dir:}");
+        write_atomically(full_path, content)?;
+    }
+
+    fit_entry_row(commit_saver_struct, full_path, save_settings)?;
+
+    info!("[commit_staged_entry()]: Writing the commit in the file.");
+    write_entry(commit_saver_struct, full_path, save_settings)?;
+    info!("[commit_staged_entry()]: Commit logged in ");
+
+    if let Some(state_index) = &state_index {
+        if commit_saver_struct.details.entry_kind == EntryKind::Commit {
+            info!("[commit_staged_entry()]: Recording the commit in the state index.");
+            if let Err(e) = state_index.append(&logged_entry(commit_saver_struct, full_path)) {
+                warn!("[commit_staged_entry()]: Could not update the state index: {e:}");
+            }
+        }
+    }
+    drop(diary_lock.take());
+
+    if commit_saver_struct.details.entry_kind == EntryKind::Commit {
+        update_rollup_notes(
+            commit_saver_struct,
+            save_settings,
+            state_index.as_ref(),
+            vault_root,
+            full_path,
+            appends,
+        );
+    }
+
+    if let Some(webhook) = &save_settings.webhook {
+        send_to_webhook(webhook, commit_saver_struct, vault_root, full_path);
+    }
+
+    if let Some(message_template) = &save_settings.vault_commit_message {
+        commit_in_vault(message_template, commit_saver_struct, full_path);
+    }
+
+    Ok(SaveReport::new(
+        SaveStatus::Logged,
+        commit_saver_struct,
+        Some(full_path.clone()),
+    ))
+}
+
+/// Checks that the diary file of `full_path` can be written, then takes its
+/// lock (kept in the state directory, see [`DiaryLock`]). A dry run writes
+/// nothing and takes no lock.
+///
+/// # Errors
+///
+/// Returns a [`ReadOnlyVault`] error if the vault cannot be written to, and
+/// an error if another save holds the lock for longer than [`LOCK_TIMEOUT`].
+fn lock_diary(
+    full_path: &Path,
+    save_settings: &SaveSettings,
+) -> Result<Option<DiaryLock>, Box<dyn Error>> {
+    if save_settings.dry_run {
+        return Ok(None);
+    }
+    check_vault_writable(full_path)?;
+    Ok(Some(DiaryLock::acquire(
+        save_settings.state_dir.as_deref(),
+        full_path,
+        LOCK_TIMEOUT,
+    )?))
+}
+
+/// Commits the diary file in the vault's own repository (`[vault_git]`), see
+/// [`commit_diary_file()`].
+///
+/// A failing commit only logs a warning: the entry is already in the diary.
+fn commit_in_vault(message_template: &str, commit_saver_struct: &CommitSaver, full_path: &Path) {
+    info!("[commit_in_vault()]: Committing the diary file in the vault repository.");
+    let message = render_vault_commit_message(message_template, commit_saver_struct);
+    if let Err(e) = commit_diary_file(full_path, &message) {
+        warn!("[commit_in_vault()]: Could not commit the diary file in the vault: {e:}");
+    }
+}
+
+/// Sends the logged entry to the `[webhook]`, see [`Webhook::send()`].
+///
+/// Only commit entries are sent. A failing webhook only logs a warning: the
+/// entry is already in the diary.
+fn send_to_webhook(
+    webhook: &Webhook,
+    commit_saver_struct: &CommitSaver,
+    vault_root: &Path,
+    full_path: &Path,
+) {
+    if commit_saver_struct.details.entry_kind != EntryKind::Commit {
+        return;
+    }
+    info!("[send_to_webhook()]: Sending the entry to the webhook.");
+    let sent = webhook
+        .render_payload(commit_saver_struct, vault_root, full_path)
+        .and_then(|payload| webhook.send(&payload));
+    if let Err(e) = sent {
+        warn!("[send_to_webhook()]: Could not send the entry to the webhook: {e:}");
+    }
+}
+
+/// Checks whether the commit is already logged in the diary file of
+/// `full_path`, e.g. because the hook fired twice.
+///
+/// Only commit entries are checked, and never with
+/// [`SaveSettings::allow_duplicates`] (`save --allow-duplicates`).
+///
+/// # Errors
+///
+/// Returns an error if the diary file exists but cannot be read.
+fn is_duplicate_entry(
+    commit_saver_struct: &CommitSaver,
+    full_path: &Path,
+    save_settings: &SaveSettings,
+) -> Result<bool, Box<dyn Error>> {
+    if save_settings.allow_duplicates || commit_saver_struct.details.entry_kind != EntryKind::Commit
+    {
+        return Ok(false);
+    }
+    let duplicate = commit_saver_struct.is_logged_in(full_path)?;
+    if duplicate {
+        info!(
+            "[is_duplicate_entry()]: Commit already in {:}, not logging it again: {:}",
+            full_path.display(),
+            commit_saver_struct.entry.hash
+        );
+    }
+    Ok(duplicate)
+}
+
+/// Returns the file a missing diary was moved to, or `full_path` itself.
+///
+/// The moves are the ones the daemon followed (see [`follow_vault_rename()`]),
+/// so a diary refiled by the user mid-day keeps getting the rows of the day
+/// instead of a duplicate being created at the old path. A moved-diaries
+/// record that cannot be read only logs a warning.
+fn follow_moved_diary(full_path: PathBuf, save_settings: &SaveSettings) -> PathBuf {
+    let Some(state_dir) = save_settings.state_dir.as_deref() else {
+        return full_path;
+    };
+    if full_path.exists() {
+        return full_path;
+    }
+    match DiaryMoves::new(state_dir).resolve(&full_path) {
+        Ok(Some(moved)) => {
+            info!(
+                "[follow_moved_diary()]: {:} was moved to: {:}",
+                full_path.display(),
+                moved.display()
+            );
+            moved
+        }
+        Ok(None) => full_path,
+        Err(e) => {
+            warn!("[follow_moved_diary()]: Could not read the moved diaries: {e:}");
+            full_path
+        }
+    }
+}
+
+/// Writes the entry to the diary file of `full_path`.
+///
+/// An amend ([`CommitSaver::amends`]) replaces the row of the amended commit
+/// when the diary has one, and the amended commit is dropped from the state
+/// index. Any other entry, or an amend of a commit logged elsewhere, is
+/// inserted at its place in time in the table under
+/// [`SaveSettings::insert_under`], or in the first table of the diary (see
+/// [`CommitSaver::insert_entry_in_diary()`]): after the last row for a new
+/// commit, before later rows for an older one (backfills, cherry-picks,
+/// rebases).
+///
+/// # Errors
+///
+/// Returns an error if the diary file cannot be read or written. A state
+/// index that cannot be updated only logs a warning.
+fn write_entry(
+    commit_saver_struct: &mut CommitSaver,
+    full_path: &PathBuf,
+    save_settings: &SaveSettings,
+) -> Result<(), Box<dyn Error>> {
+    let add_entry = |commit_saver_struct: &mut CommitSaver| {
+        commit_saver_struct.insert_entry_in_diary(full_path, save_settings.insert_under.as_deref())
+    };
+    let Some(stale_hash) = commit_saver_struct.details.amends.clone() else {
+        return add_entry(commit_saver_struct);
+    };
+    if !commit_saver_struct.replace_entry_in_diary(full_path, &stale_hash)? {
+        return add_entry(commit_saver_struct);
+    }
+
+    info!("[write_entry()]: Replaced the row of the amended commit {stale_hash:}.");
+    if let Some(state_index) = save_settings.state_dir.as_deref().map(StateIndex::new) {
+        let forgotten = state_index.entries().and_then(|entries| {
+            let kept = entries
+                .into_iter()
+                .filter(|entry| entry.commit_hash != stale_hash)
+                .collect::<Vec<_>>();
+            state_index.replace_entries(&kept)
+        });
+        if let Err(e) = forgotten {
+            warn!("[write_entry()]: Could not drop the amended commit from the state index: {e:}");
+        }
+    }
+    Ok(())
+}
+
+/// Prepares the table row of the entry for the diary table of `full_path`.
+///
+/// With `[table] same_second = sequence`, the entry is numbered among the rows
+/// already logged in its second. Then a row longer than `[table]
+/// max_row_length` has its message moved to the overflow note. YAML entries
+/// are left as they are.
+///
+/// # Errors
+///
+/// Returns an error if the diary file cannot be read, or if the overflow note
+/// cannot be written.
+fn fit_entry_row(
+    commit_saver_struct: &mut CommitSaver,
+    full_path: &Path,
+    save_settings: &SaveSettings,
+) -> Result<(), Box<dyn Error>> {
+    if save_settings.entry_format != EntryFormat::Table {
+        return Ok(());
+    }
+    if save_settings.same_second == SameSecondPolicy::Sequence {
+        number_same_second_entry(commit_saver_struct, full_path)?;
+    }
+    if let Some(max_row_length) = save_settings.max_row_length {
+        move_long_message_to_overflow(commit_saver_struct, full_path, max_row_length)?;
+    }
+    Ok(())
+}
+
+/// Renders what [`save_commit_entry()`] would write to `full_path`, without
+/// touching the vault (`save --dry-run`).
+///
+/// The preview shows the whole new diary file when it does not exist yet
+/// (from the `[templates] diary_file` or `diary_template` when set), then the entry appended
+/// to it. A row longer than `[table] max_row_length` is shown shortened, and a
+/// row of an already logged second is numbered, as it would be written.
+///
+/// # Returns
+///
+/// A [`SaveStatus::DryRun`] report carrying the preview.
+///
+/// # Errors
+///
+/// Returns an error if the diary file exists but cannot be read, or if the
+/// folder of the entry cannot be determined.
+fn preview_commit_entry(
+    commit_saver_struct: &mut CommitSaver,
+    full_path: PathBuf,
+    save_settings: &SaveSettings,
+) -> Result<SaveReport, Box<dyn Error>> {
+    info!("[preview_commit_entry()]: Dry run, rendering the entry without writing it.");
+    let mut preview = String::new();
+    if check_diary_path_exists(&full_path).is_ok() {
+        let _ = writeln!(preview, "Would append to {:}:\n", full_path.display());
+    } else {
+        let content = render_new_diary_file(&full_path, commit_saver_struct, save_settings);
+        let _ = writeln!(
+            preview,
+            "Would create {:}:\n\n{content:}\nWould append:\n",
+            full_path.display()
+        );
+    }
+
+    if save_settings.same_second == SameSecondPolicy::Sequence
+        && save_settings.entry_format == EntryFormat::Table
+    {
+        number_same_second_entry(commit_saver_struct, &full_path)?;
+    }
+    if let Some(max_row_length) = save_settings
+        .max_row_length
+        .filter(|_| save_settings.entry_format == EntryFormat::Table)
+    {
+        let overflow_path = overflow_note_path(&full_path);
+        let overflow_note_name = overflow_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if commit_saver_struct
+            .fit_row_length(max_row_length, &overflow_note_name)?
+            .is_some()
+        {
+            let _ = writeln!(
+                preview,
+                "(the full message would go to {:})\n",
+                overflow_path.display()
+            );
+        }
+    }
+    preview.push_str(&commit_saver_struct.render_entry()?);
+
+    let mut report = SaveReport::new(SaveStatus::DryRun, commit_saver_struct, Some(full_path));
+    report.preview = Some(preview);
+    Ok(report)
+}
+
+/// Warns when the day of a new diary file already has one elsewhere under the
+/// commits root, e.g. because `commit_date_path` changed since it was written.
+///
+/// The new file is still created; `verify` keeps reporting the pair until the
+/// files are merged by hand. A commits root that cannot be read only logs a
+/// warning too.
+fn warn_about_same_day_files(
+    commits_root: &Path,
+    full_path: &Path,
+    commit_saver_struct: &CommitSaver,
+) {
+    let date = commit_saver_struct
+        .local_datetime()
+        .format("%Y-%m-%d")
+        .to_string();
+    match other_diary_files_for_day(commits_root, &date, full_path) {
+        Ok(others) => {
+            for other in others {
+                warn!(
+                    "[warn_about_same_day_files()]: {:} already holds the diary of {date:}, creating another one: {:}",
+                    other.display(),
+                    full_path.display()
+                );
+            }
+        }
+        Err(e) => {
+            warn!("[warn_about_same_day_files()]: Could not look for other diary files: {e:}");
+        }
+    }
+}
+
+/// Tells why an entry must not be logged: vault auto-commits, commits asking
+/// to be skipped and commits matching a `[filters]` rule (branch, message
+/// pattern or bot author). Returns `None` for entries to log.
+fn not_logged_status(
+    commit_saver_struct: &CommitSaver,
+    save_settings: &SaveSettings,
+) -> Option<SaveStatus> {
+    if commit_saver_struct.details.entry_kind != EntryKind::Commit {
+        return None;
+    }
+    let commit_msg = commit_saver_struct.entry.table_message();
+    if is_vault_sync_message(&commit_msg) {
+        info!("[not_logged_status()]: Vault auto-commit, not logging it.");
+        return Some(SaveStatus::VaultSync);
+    }
+    if should_skip_commit(&commit_msg, env::var(SKIP_ENV_VAR).ok().as_deref()) {
+        info!(
+            "[not_logged_status()]: Skip requested, not logging: {:}",
+            commit_saver_struct.entry.hash
+        );
+        return Some(SaveStatus::Skipped);
+    }
+    let is_bot = save_settings.ignore_bots
+        && is_bot_commit(
+            commit_saver_struct.entry.author.as_deref(),
+            commit_saver_struct.entry.author_email.as_deref(),
+        );
+    if is_bot
+        || is_ignored_branch(
+            &save_settings.ignore_branches,
+            &commit_saver_struct.entry.branch,
+        )
+        || is_ignored_message(&save_settings.ignore_message_patterns, &commit_msg)
+    {
+        return Some(SaveStatus::Skipped);
+    }
+    None
+}
+
+/// Renders a new diary file: from the `[templates] diary_file` Handlebars
+/// template when set (see [`render_diary_file_from_handlebars()`]), else from
+/// the `[templates] diary_template` daily note (see
+/// [`render_diary_file_from_template()`]), else from the built-in template.
+///
+/// A template that cannot be read or rendered only logs a warning, and the
+/// next one is used instead.
+fn render_new_diary_file(
+    full_path: &Path,
+    commit_saver_struct: &mut CommitSaver,
+    save_settings: &SaveSettings,
+) -> String {
+    if let Some(template) = read_template_file(save_settings.diary_file_template.as_deref()) {
+        match render_diary_file_from_handlebars(full_path, commit_saver_struct, &template) {
+            Ok(content) => return content,
+            Err(e) => {
+                warn!("[render_new_diary_file()]: Could not render the diary file template: {e:}");
+            }
+        }
+    }
+    match read_template_file(save_settings.diary_template.as_deref()) {
+        Some(template) => {
+            render_diary_file_from_template(full_path, commit_saver_struct, &template)
+        }
+        None => render_diary_file(commit_saver_struct),
+    }
+}
+
+/// Reads a configured template file (`[templates] diary_file` or
+/// `diary_template`), if any.
+///
+/// A template that cannot be read only logs a warning, and the next template
+/// is used instead.
+fn read_template_file(template_path: Option<&Path>) -> Option<String> {
+    let template_path = template_path?;
+    let template = ensure_hydrated(template_path, HYDRATION_ATTEMPTS, HYDRATION_RETRY_DELAY)
+        .and_then(|()| fs::read_to_string(template_path).map_err(Into::into));
+    match template {
+        Ok(template) => Some(template),
+        Err(e) => {
+            warn!(
+                "[read_template_file()]: Could not read {:}, using the next template: {e:}",
+                template_path.display()
+            );
+            None
+        }
+    }
+}
+
+/// Builds the logged-commits index entry of a commit written to `full_path`.
+fn logged_entry(commit_saver_struct: &CommitSaver, full_path: &Path) -> LoggedEntry {
+    LoggedEntry {
+        commit_datetime: commit_saver_struct.entry.datetime,
+        repository_url: commit_saver_struct.entry.repository_url.clone(),
+        branch: commit_saver_struct.entry.branch.clone(),
+        commit_hash: commit_saver_struct.entry.hash.clone(),
+        diary_path: full_path.display().to_string(),
+    }
+}
+
+/// Writes the progress towards the weekly goal into the weekly note of the
+/// commit's ISO week, see [`update_weekly_note()`].
+///
+/// The commits of the week are counted from the logged-commits index when
+/// there is one, so the count repairs itself; otherwise the note's own count
+/// is incremented.
+///
+/// # Errors
+///
+/// Returns an error if the index cannot be read or the weekly note cannot be
+/// written.
+fn record_weekly_progress(
+    weekly_goal: &WeeklyGoal,
+    weekly_note_path: &Path,
+    commit_saver_struct: &CommitSaver,
+    state_index: Option<&StateIndex>,
+) -> Result<(), Box<dyn Error>> {
+    let done = state_index
+        .map(StateIndex::entries)
+        .transpose()?
+        .map(|entries| commits_in_week(&entries, commit_saver_struct.entry.datetime));
+    update_weekly_note(weekly_note_path, weekly_goal.commits, done)?;
+    Ok(())
+}
+
+/// Renders what a save appends to notes other than the diary, while the
+/// save is staged (see [`stage_commit_entry()`]): for now the weekly row of a
+/// commit (`[weekly_log]`).
+///
+/// # Errors
+///
+/// Returns an error if an append cannot be rendered; the entry is then
+/// written nowhere.
+fn stage_fan_out(
+    commit_saver_struct: &CommitSaver,
+    save_settings: &SaveSettings,
+    vault_root: &Path,
+) -> Result<Vec<PendingAppend>, Box<dyn Error>> {
+    let mut staged = Vec::new();
+    if commit_saver_struct.details.entry_kind != EntryKind::Commit {
+        return Ok(staged);
+    }
+    if let Some(weekly_log) = &save_settings.weekly_log {
+        info!("[stage_fan_out()]: Staging the weekly row of the commit.");
+        staged.push(weekly_log.stage_row(vault_root, commit_saver_struct)?);
+    }
+    Ok(staged)
+}
+
+/// Updates the notes summarizing the logged commits after a commit was saved:
+/// the weekly goal progress (`[goals]`), the `staged` appends such as the
+/// weekly row (`[weekly_log]`), the message hygiene section of the weekly
+/// note (`[message_hygiene]`), the link to the diary file in the daily note
+/// (`[daily_note]`) and, with a state index, the related repositories note
+/// (`[related_repos]`).
+///
+/// Failures only log a warning: the diary row is already written. Staged
+/// appends that fail are spooled in the state directory and retried by the
+/// next save, see [`commit_appends()`].
+fn update_rollup_notes(
+    commit_saver_struct: &CommitSaver,
+    save_settings: &SaveSettings,
+    state_index: Option<&StateIndex>,
+    vault_root: &Path,
+    full_path: &Path,
+    staged: &[PendingAppend],
+) {
+    if let Some(weekly_goal) = &save_settings.weekly_goal {
+        info!("[update_rollup_notes()]: Updating the weekly goal progress.");
+        let weekly_note_path =
+            weekly_goal.note_path(vault_root, commit_saver_struct.entry.datetime);
+        if let Err(e) = record_weekly_progress(
+            weekly_goal,
+            &weekly_note_path,
+            commit_saver_struct,
+            state_index,
+        ) {
+            warn!("[update_rollup_notes()]: Could not update the weekly note: {e:}");
+        }
+    }
+
+    if !staged.is_empty() {
+        info!("[update_rollup_notes()]: Writing the staged appends of the commit.");
+        let spool = save_settings.state_dir.as_deref().map(Spool::new);
+        let spooled = commit_appends(staged, spool.as_ref());
+        if spooled > 0 {
+            warn!("[update_rollup_notes()]: {spooled:} append(s) left to retry on the next save.");
+        }
+    }
+
+    if commit_saver_struct.details.needs_better_message {
+        info!("[update_rollup_notes()]: Listing the commit in the message hygiene section.");
+        if let Err(e) =
+            record_needs_better_message(commit_saver_struct, save_settings, vault_root, full_path)
+        {
+            warn!("[update_rollup_notes()]: Could not update the message hygiene section: {e:}");
+        }
+    }
+
+    if let Some(daily_note) = &save_settings.daily_note {
+        info!("[update_rollup_notes()]: Linking the diary file from the daily note.");
+        if let Err(e) =
+            daily_note.link_diary(vault_root, full_path, commit_saver_struct.local_datetime())
+        {
+            warn!("[update_rollup_notes()]: Could not update the daily note: {e:}");
+        }
+    }
+
+    if let (Some(note), Some(state_index)) = (&save_settings.related_repos_note, state_index) {
+        info!("[update_rollup_notes()]: Updating the related repositories note.");
+        if let Err(e) = record_related_repos(&vault_root.join(note), state_index) {
+            warn!("[update_rollup_notes()]: Could not update the related repos note: {e:}");
+        }
+    }
+}
+
+/// Lists a commit whose message does not match `[message_hygiene] pattern` in
+/// the message hygiene section of its weekly note, see
+/// [`record_message_hygiene()`].
+///
+/// The weekly note is the one of `[goals] weekly_note` when set, and
+/// [`DEFAULT_WEEKLY_NOTE_TEMPLATE`] otherwise.
+///
+/// # Errors
+///
+/// Returns an error if the weekly note cannot be read or written.
+fn record_needs_better_message(
+    commit_saver_struct: &CommitSaver,
+    save_settings: &SaveSettings,
+    vault_root: &Path,
+    full_path: &Path,
+) -> Result<bool, Box<dyn Error>> {
+    let note_template = save_settings
+        .weekly_goal
+        .as_ref()
+        .map_or(DEFAULT_WEEKLY_NOTE_TEMPLATE, |goal| {
+            goal.note_template.as_str()
+        });
+    let note_path = weekly_note_path(
+        vault_root,
+        note_template,
+        commit_saver_struct.entry.datetime,
+    );
+    let date = commit_saver_struct
+        .local_datetime()
+        .format("%Y-%m-%d")
+        .to_string();
+    let diary_link = wikilink(vault_root, full_path, Some(&date)).unwrap_or(date);
+    record_message_hygiene(
+        &note_path,
+        &commit_saver_struct.entry.hash,
+        &message_hygiene_item(commit_saver_struct, &diary_link),
+    )
+}
+
+/// Rewrites the related repositories note from every commit in the
+/// logged-commits index, see [`update_related_repos_note()`].
+///
+/// # Errors
+///
+/// Returns an error if the index cannot be read or the note cannot be
+/// written.
+fn record_related_repos(
+    related_repos_path: &Path,
+    state_index: &StateIndex,
+) -> Result<(), Box<dyn Error>> {
+    let entries = state_index.entries()?;
+    update_related_repos_note(related_repos_path, &entries, Utc::now())?;
+    Ok(())
+}
+
+/// Copies the [`SaveSettings`] entry settings (see [`SaveSettings::entry_settings()`]) onto the
+/// entry, resolves its category (from the remote URL as `origin` has it), then
+/// normalizes the remote URL, resolves its language, message check, branch slug, Conventional
+/// Commits parts, trailer tags, size class and timezone, and fills the optional `DURATION` and `TRACKING` values.
+///
+/// Failures to read the state index or to query the time tracker only log a
+/// warning and leave the value empty.
+fn apply_save_settings(
+    commit_saver_struct: &mut CommitSaver,
+    state_index: Option<&StateIndex>,
+    save_settings: &SaveSettings,
+) {
+    info!("[apply_save_settings()]: Applying the save settings.");
+    commit_saver_struct.settings = save_settings.entry_settings();
+    let commit_msg = commit_saver_struct.entry.table_message();
+
+    if !save_settings.category_rules.is_empty() {
+        let folder = commit_saver_struct
+            .folder()
+            .map(|dir| dir.display().to_string())
+            .unwrap_or_default();
+        commit_saver_struct.details.category = resolve_category(
+            &save_settings.category_rules,
+            &commit_saver_struct.entry.repository_url,
+            &folder,
+        );
+    }
+
+    if save_settings.normalize_remote {
+        commit_saver_struct.entry.repository_url =
+            normalize_remote_url(&commit_saver_struct.entry.repository_url);
+    }
+
+    if save_settings.detect_language && commit_saver_struct.details.entry_kind == EntryKind::Commit
+    {
+        commit_saver_struct.details.language = detect_language(&commit_msg);
+    }
+
+    if let Some(pattern) = &save_settings.message_pattern {
+        if commit_saver_struct.details.entry_kind == EntryKind::Commit {
+            commit_saver_struct.details.needs_better_message = !pattern.is_match(&commit_msg);
+        }
+    }
+
+    if save_settings.branch_tags {
+        commit_saver_struct.details.branch_slug = branch_slug(&commit_saver_struct.entry.branch);
+    }
+
+    if save_settings.conventional_commits
+        && commit_saver_struct.details.entry_kind == EntryKind::Commit
+    {
+        commit_saver_struct.details.conventional = parse_conventional_commit(&commit_msg);
+    }
+
+    if let Some(placement) = save_settings.trailer_tags {
+        let tags = trailer_tags(&commit_msg);
+        if !tags.is_empty() {
+            commit_saver_struct.details.trailer_tags = Some(TrailerTags { tags, placement });
+        }
+    }
+
+    if let Some(thresholds) = save_settings.size_thresholds {
+        commit_saver_struct.details.commit_size = commit_saver_struct
+            .entry
+            .stats
+            .map(|stats| thresholds.classify(stats));
+    }
+
+    if save_settings.time_zone.is_some() || save_settings.timezone.is_some() {
+        let zone = save_settings
+            .timezone
+            .map_or_else(system_timezone, DiaryTimezone::zone);
+        commit_saver_struct.details.timezone = Some(entry_timezone(
+            save_settings.time_zone,
+            zone,
+            commit_saver_struct.entry.datetime,
+        ));
+    }
+
+    if let Some(state_index) = state_index {
+        if commit_saver_struct
+            .settings
+            .table_columns
+            .contains(&TableColumn::Duration)
+        {
+            info!("[apply_save_settings()]: Estimating the duration from the state index.");
+            match state_index.last_entry_for_repository(&commit_saver_struct.entry.repository_url) {
+                Ok(previous) => commit_saver_struct.set_duration_since(
+                    previous.map(|entry| entry.commit_datetime),
+                    save_settings.duration_cap,
+                ),
+                Err(e) => warn!("[apply_save_settings()]: Could not read the state index: {e:}"),
+            }
+        }
+    }
+
+    if let Some(source) = &save_settings.time_tracking {
+        if commit_saver_struct
+            .settings
+            .table_columns
+            .contains(&TableColumn::Tracking)
+        {
+            info!("[apply_save_settings()]: Querying the time tracker.");
+            match query_current_activity(source) {
+                Ok(activity) => commit_saver_struct.details.tracked_activity = activity,
+                Err(e) => warn!("[apply_save_settings()]: Could not query the time tracker: {e:}"),
+            }
+        }
+    }
+}
+
+/// Checks the build status of the working tree for the `STATUS` column.
+///
+/// Only entries logged from their own working tree get a status (the HEAD
+/// commit from the hook, `save --wip`): batch, push and backfilled entries
+/// keep an empty cell, since their state is not checked out. A check that
+/// fails to run only logs a warning and leaves the status empty.
+fn apply_build_status(commit_saver_struct: &mut CommitSaver, save_settings: &SaveSettings) {
+    let Some(check) = &save_settings.build_status else {
+        return;
+    };
+    if !save_settings.table_columns.contains(&TableColumn::Status) {
+        return;
+    }
+
+    info!("[apply_build_status()]: Checking the build status.");
+    match check_build_status(check) {
+        Ok(status) => commit_saver_struct.details.build_status = Some(status),
+        Err(e) => warn!("[apply_build_status()]: Could not check the build status: {e:}"),
+    }
+}
+
+/// Looks up the annotated tag at or near the logged commit for the `TAG` column.
+///
+/// Like the build status, only the `HEAD` commit logged from its repository
+/// gets a tag; batch, push and backfilled entries keep an empty cell. A
+/// failed lookup only logs a warning.
+fn apply_release_tag(commit_saver_struct: &mut CommitSaver, save_settings: &SaveSettings) {
+    let Some(max_distance) = save_settings.release_tag_distance else {
+        return;
+    };
+    if !save_settings.table_columns.contains(&TableColumn::Tag) {
+        return;
+    }
+
+    info!("[apply_release_tag()]: Looking for an annotated tag near the commit.");
+    let result = entry_repository(commit_saver_struct).and_then(|git_repo| {
+        let commit_id = Oid::from_str(&commit_saver_struct.entry.hash)?;
+        Ok(nearest_annotated_tag(&git_repo, commit_id, max_distance)?)
+    });
+    match result {
+        Ok(tag) => commit_saver_struct.details.release_tag = tag,
+        Err(e) => warn!("[apply_release_tag()]: Could not look up the tags: {e:}"),
+    }
+}
+
+/// Prepares an existing diary file for the row: a note without a commit
+/// table gets one and the diary tags, see [`adopt_existing_note()`], then
+/// the tags of the entry are merged into its frontmatter, see
+/// [`merge_entry_into_frontmatter()`].
+///
+/// # Errors
+///
+/// Returns an error if the diary file cannot be read or written.
+fn update_existing_diary(
+    commit_saver_struct: &mut CommitSaver,
+    full_path: &Path,
+    save_settings: &SaveSettings,
+) -> Result<(), Box<dyn Error>> {
+    adopt_existing_note(
+        commit_saver_struct,
+        full_path,
+        save_settings.insert_under.as_deref(),
+    )?;
+    merge_entry_into_frontmatter(commit_saver_struct, full_path)
+}
+
+/// Adds the entry category, language, branch slug, message hygiene, size, commit type and trailer tags to
+/// the frontmatter of an existing diary file: their tags to `tags`, and the category to `categories`, see
+/// [`merge_frontmatter_list()`]. Only the frontmatter bytes change, see
+/// [`write_frontmatter_update()`].
+///
+/// Files created for this entry already get them from the diary template.
+///
+/// # Errors
+///
+/// Returns an error if the diary file cannot be read or written, or if the
+/// update would change the note body.
+fn merge_entry_into_frontmatter(
+    commit_saver_struct: &CommitSaver,
+    full_path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let category = commit_saver_struct.details.category.as_ref();
+    let tags = category
+        .map(|category| category_tag(category))
+        .into_iter()
+        .chain(
+            commit_saver_struct
+                .details
+                .language
+                .as_deref()
+                .map(language_tag),
+        )
+        .chain(
+            commit_saver_struct
+                .details
+                .branch_slug
+                .as_deref()
+                .map(branch_tag),
+        )
+        .chain(
+            commit_saver_struct
+                .details
+                .needs_better_message
+                .then(|| NEEDS_BETTER_MESSAGE_TAG.to_string()),
+        )
+        .chain(commit_saver_struct.details.commit_size.map(CommitSize::tag))
+        .chain(
+            commit_saver_struct
+                .details
+                .conventional
+                .as_ref()
+                .map(|conventional| commit_type_tag(&conventional.kind)),
+        )
+        .chain(
+            commit_saver_struct
+                .details
+                .trailer_tags
+                .iter()
+                .filter(|trailer_tags| trailer_tags.placement.in_frontmatter())
+                .flat_map(|trailer_tags| trailer_tags.tags.iter().cloned()),
+        )
+        .collect::<Vec<_>>();
+    if tags.is_empty() {
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(full_path)?;
+    let with_tags = merge_frontmatter_list(&content, "tags", &tags);
+    let merged = match category {
+        Some(category) => merge_frontmatter_list(
+            with_tags.as_deref().unwrap_or(&content),
+            "categories",
+            std::slice::from_ref(category),
+        )
+        .or(with_tags),
+        None => with_tags,
+    };
+
+    if let Some(merged) = merged {
+        info!("[merge_entry_into_frontmatter()]: Adding {tags:?} to the frontmatter.");
+        write_frontmatter_update(full_path, &content, &merged)?;
+    }
+
+    Ok(())
+}
+
+/// Shortens a row longer than `max_row_length` and writes the full message to
+/// the overflow note next to the diary file, see [`CommitSaver::fit_row_length()`].
+///
+/// # Errors
+///
+/// Returns an error if the overflow note is a cloud placeholder that cannot be
+/// hydrated, or if it cannot be written.
+fn move_long_message_to_overflow(
+    commit_saver_struct: &mut CommitSaver,
+    full_path: &Path,
+    max_row_length: usize,
+) -> Result<(), Box<dyn Error>> {
+    let overflow_path = overflow_note_path(full_path);
+    let overflow_note_name = overflow_path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or("Could not convert the overflow note name to string")?;
+
+    if let Some(full_message) =
+        commit_saver_struct.fit_row_length(max_row_length, overflow_note_name)?
+    {
+        info!("[move_long_message_to_overflow()]: Row too long, writing the overflow note.");
+        ensure_hydrated(&overflow_path, HYDRATION_ATTEMPTS, HYDRATION_RETRY_DELAY)?;
+        commit_saver_struct.append_overflow_note(&overflow_path, &full_message)?;
+    }
+
+    Ok(())
+}
+
+/// Checks the vault against the logged-commits index (`verify [--fix]`).
+///
+/// Runs [`verify_vault()`] on the index in the configured state directory.
+/// Commit hashes are checked against `git_repo` when one is given (the
+/// repository the command runs in), and every commits root is checked for
+/// days with more than one diary file, see [`find_duplicate_days()`]. With
+/// `fix`, the fixable issues are repaired with [`fix_issues()`], keeping
+/// copies of the rewritten diary files in the vault trash unless
+/// `[safety] use_trash` is off.
+///
+/// # Returns
+///
+/// The issues that remain after the run: everything found without `fix`,
+/// or only the unfixable ones with it.
+///
+/// # Errors
+///
+/// Returns an error if no state directory is configured, or if the index or
+/// a diary file cannot be read or rewritten.
+pub fn run_verify(
+    git_repo: Option<&Repository>,
+    save_settings: &SaveSettings,
+    commits_roots: &[PathBuf],
+    fix: bool,
+) -> Result<Vec<VerifyIssue>, Box<dyn Error>> {
+    let state_dir = save_settings
+        .state_dir
+        .as_deref()
+        .ok_or("No state directory available to read the logged-commits index from")?;
+    let state_index = StateIndex::new(state_dir);
+
+    info!("[run_verify()]: Verifying the vault against the state index.");
+    let mut issues = verify_vault(&state_index, git_repo)?;
+    issues.extend(find_duplicate_days(commits_roots)?);
+    if !fix {
+        return Ok(issues);
+    }
+
+    let fixed = fix_issues(&state_index, &issues, save_settings.trash_dir.as_deref())?;
+    info!("[run_verify()]: Fixed {fixed:} issue(s).");
+    Ok(issues
+        .into_iter()
+        .filter(|issue| !issue.is_fixable())
+        .collect())
+}
+
+/// Returns the directories diary files are written under: the commit path and
+/// the commit path of every `[routing]` route.
+fn commits_roots(
+    obsidian_root_path_dir: &Path,
+    obsidian_commit_path: &Path,
+    save_settings: &SaveSettings,
+) -> Vec<PathBuf> {
+    let mut roots = vec![obsidian_root_path_dir.join(obsidian_commit_path)];
+    for route in &save_settings.routes {
+        let root = obsidian_root_path_dir.join(&route.commit_path);
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+    roots
+}
+
+/// Renders the `report` subcommand.
+///
+/// `--vault` measures the diary files under every commits root (the commit
+/// path and the `[routing]` commit paths) with [`collect_vault_report()`].
+///
+/// # Errors
+///
+/// Returns an error if no report is picked, or if the diary cannot be read.
+pub fn run_report(
+    args: &ReportArgs,
+    today: NaiveDate,
+    obsidian_root_path_dir: &Path,
+    obsidian_commit_path: &Path,
+    save_settings: &SaveSettings,
+) -> Result<String, Box<dyn Error>> {
+    if !args.vault {
+        return Err("Pick a report to show: --vault".into());
+    }
+
+    let roots = commits_roots(obsidian_root_path_dir, obsidian_commit_path, save_settings);
+    let report = collect_vault_report(&roots)?;
+    Ok(render_vault_report(&report, obsidian_root_path_dir, today))
+}
+
+/// Renders the `stats` subcommand.
+///
+/// The commits are read with [`collect_diary_commits()`] from every commits
+/// root (the commit path and the `[routing]` commit paths). They are summed
+/// up in [`VaultStats`], or with `--compare` counted per period in a
+/// [`PeriodComparison`], the periods being relative to the day of `now`.
+/// With `--repo`, only the commits of that repository are counted.
+///
+/// With a `[goals]` weekly goal, the stats end with the progress of the week
+/// of `now`, counted over every repository. `--porcelain` returns the
+/// porcelain records instead of the Markdown. With `--note`, the Markdown
+/// is also written to that note of the vault.
+///
+/// # Errors
+///
+/// Returns an error if a `--compare` period is unknown, if the diary cannot
+/// be read or if the note cannot be written.
+pub fn run_stats(
+    args: &StatsArgs,
+    now: DateTime<Utc>,
+    obsidian_root_path_dir: &Path,
+    obsidian_commit_path: &Path,
+    save_settings: &SaveSettings,
+) -> Result<String, Box<dyn Error>> {
+    let roots = commits_roots(obsidian_root_path_dir, obsidian_commit_path, save_settings);
+    let filter = EntryFilter {
+        repository: args.repo.clone(),
+        branch: None,
+    };
+    let all_commits = collect_diary_commits(&roots)?;
+    let commits = all_commits
+        .iter()
+        .filter(|commit| filter.matches_repository(&commit.repository))
+        .cloned()
+        .collect::<Vec<_>>();
+    let today = now.date_naive();
+    let (output, porcelain) = if let [before, after] = args.compare.as_slice() {
+        let comparison = PeriodComparison::new(
+            &commits,
+            Period::parse(before, today)?,
+            Period::parse(after, today)?,
+        );
+        (comparison.render(), comparison.to_porcelain())
+    } else {
+        let mut stats = VaultStats::default();
+        for commit in &commits {
+            stats.add_commit(commit);
+        }
+        let mut output = render_vault_stats(&stats);
+        let mut porcelain = render_vault_stats_porcelain(&stats);
+        if let Some(weekly_goal) = &save_settings.weekly_goal {
+            let progress = WeeklyProgress {
+                goal: weekly_goal.commits,
+                done: diary_commits_in_week(&all_commits, today),
+            };
+            output.push_str(&progress.render(today));
+            porcelain.push_str(&progress.to_porcelain(today));
+        }
+        (output, porcelain)
+    };
+
+    if let Some(note) = &args.note {
+        write_stats_note(&obsidian_root_path_dir.join(note), &output, now)?;
+    }
+    Ok(if args.porcelain { porcelain } else { output })
+}
+
+/// Renders the `heatmap` subcommand.
+///
+/// The commits are read with [`collect_diary_commits()`] from every commits
+/// root (the commit path and the `[routing]` commit paths), counted per day
+/// of `--year` (the year of `now` by default) in a [`YearActivity`] and drawn
+/// with [`render_heatmap()`]. The output is also written to the `--note`, or
+/// to `[heatmap] note` when set.
+///
+/// # Errors
+///
+/// Returns an error if the diary cannot be read or if the note cannot be
+/// written.
+pub fn run_heatmap(
+    args: &HeatmapArgs,
+    now: DateTime<Utc>,
+    obsidian_root_path_dir: &Path,
+    obsidian_commit_path: &Path,
+    save_settings: &SaveSettings,
+) -> Result<String, Box<dyn Error>> {
+    let roots = commits_roots(obsidian_root_path_dir, obsidian_commit_path, save_settings);
+    let today = now.date_naive();
+    let activity = YearActivity::new(
+        args.year.unwrap_or(today.year()),
+        &collect_diary_commits(&roots)?,
+    );
+    let output = render_heatmap(
+        &activity,
+        args.format.unwrap_or(save_settings.heatmap.format),
+        today,
+    );
+
+    if let Some(note) = args.note.as_ref().or(save_settings.heatmap.note.as_ref()) {
+        write_stats_note(&obsidian_root_path_dir.join(note), &output, now)?;
+    }
+    Ok(output)
+}
+
+/// Prints the commit tables of a day's diary files (`today [--date]`).
+///
+/// The diary files are found with [`day_diary_paths()`] and rendered with
+/// [`render_day_table()`], in color when stdout is a terminal and `NO_COLOR`
+/// is not set.
+///
+/// # Returns
+///
+/// The text to print, or a notice when the day has no diary.
+///
+/// # Errors
+///
+/// Returns an error if the state index or a diary file cannot be read, or if a
+/// diary file is a cloud placeholder that cannot be hydrated.
+pub fn run_today(
+    date: NaiveDate,
+    git_repo: Option<&Repository>,
+    obsidian_root_path_dir: &Path,
+    obsidian_commit_path: &Path,
+    template_commit_date_path: &str,
+    save_settings: &SaveSettings,
+) -> Result<String, Box<dyn Error>> {
+    let color = std::io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none();
+    let diary_paths = day_diary_paths(
+        date,
+        git_repo,
+        obsidian_root_path_dir,
+        obsidian_commit_path,
+        template_commit_date_path,
+        save_settings,
+    )?;
+
+    let mut output = String::new();
+    for diary_path in &diary_paths {
+        ensure_hydrated(diary_path, HYDRATION_ATTEMPTS, HYDRATION_RETRY_DELAY)?;
+        let Some(table) = parse_diary_table(&read_diary_snapshot(diary_path)?) else {
+            continue;
+        };
+        if diary_paths.len() > 1 {
+            output.push_str(&diary_path.display().to_string());
+            output.push('\n');
+        }
+        output.push_str(&render_day_table(&table, color));
+        output.push('\n');
+    }
+
+    if output.is_empty() {
+        output = format!("No diary entries for {date:}.\n");
+    }
+    Ok(output)
+}
+
+/// Backfills the configured columns into the logged diary files (`enrich`).
+///
+/// Runs [`enrich_diary_files()`] on the diary files of the logged-commits
+/// index, with the columns and duration cap of the [`SaveSettings`]. Copies
+/// of the rewritten files go to the vault trash unless `[safety] use_trash`
+/// is off.
+///
+/// # Returns
+///
+/// The number of enriched rows.
+///
+/// # Errors
+///
+/// Returns an error if no state directory is available, or if the index or a
+/// diary file cannot be read or rewritten.
+pub fn run_enrich(save_settings: &SaveSettings) -> Result<usize, Box<dyn Error>> {
+    let state_dir = save_settings
+        .state_dir
+        .as_deref()
+        .ok_or("No state directory available to read the logged-commits index from")?;
+
+    info!("[run_enrich()]: Backfilling the configured columns into the diary files.");
+    enrich_diary_files(
+        &StateIndex::new(state_dir),
+        &save_settings.table_columns,
+        save_settings.duration_cap,
+        save_settings.trash_dir.as_deref(),
+    )
+}
+
+/// Adds a note to the diary entry of a logged commit (`annotate`).
+///
+/// Runs [`annotate_logged_commit()`] on the logged-commits index. A copy of
+/// the rewritten diary file goes to the vault trash unless `[safety]
+/// use_trash` is off.
+///
+/// # Returns
+///
+/// The full hash of the annotated commit and its diary file.
+///
+/// # Errors
+///
+/// Returns an error if no state directory is available, if the hash matches
+/// no single logged commit, or if the diary file cannot be rewritten.
+pub fn run_annotate(
+    args: &AnnotateArgs,
+    save_settings: &SaveSettings,
+) -> Result<(String, PathBuf), Box<dyn Error>> {
+    let state_dir = save_settings
+        .state_dir
+        .as_deref()
+        .ok_or("No state directory available to read the logged-commits index from")?;
+
+    info!("[run_annotate()]: Annotating the entry of {:}", args.hash);
+    annotate_logged_commit(
+        &StateIndex::new(state_dir),
+        &args.hash,
+        &args.note,
+        save_settings.trash_dir.as_deref(),
+    )
+}
+
+/// Removes or strikes through the diary entry of a logged commit (`undo`).
+///
+/// Runs [`undo_logged_commit()`] on the logged-commits index, for the commit
+/// of `--hash` or the last logged one, among the commits of `--repo` and
+/// `--branch` when given. A copy of the rewritten diary file
+/// goes to the vault trash unless `[safety] use_trash` is off. Once the
+/// commit is out of the index, the counts derived from it are rewritten:
+/// the weekly goal progress of its week (`[goals]`) and the related
+/// repositories note (`[related_repos]`). A `--dry-run` changes nothing.
+///
+/// # Returns
+///
+/// The undone index entry and its diary lines as they were.
+///
+/// # Errors
+///
+/// Returns an error if no state directory is available, if the hash matches
+/// no single logged commit, or if the diary file, the index or a rollup note
+/// cannot be rewritten.
+pub fn run_undo(
+    args: &UndoArgs,
+    save_settings: &SaveSettings,
+    vault_root: &Path,
+) -> Result<UndoneEntry, Box<dyn Error>> {
+    let state_dir = save_settings
+        .state_dir
+        .as_deref()
+        .ok_or("No state directory available to read the logged-commits index from")?;
+    let state_index = StateIndex::new(state_dir);
+    let mode = if args.strike {
+        UndoMode::Strike
+    } else {
+        UndoMode::Remove
+    };
+
+    info!(
+        "[run_undo()]: Undoing the entry of {:}",
+        args.hash.as_deref().unwrap_or("the last logged commit")
+    );
+    let filter = EntryFilter {
+        repository: args.repo.clone(),
+        branch: args.branch.clone(),
+    };
+    let undone = undo_logged_commit(
+        &state_index,
+        args.hash.as_deref(),
+        &filter,
+        mode,
+        args.dry_run,
+        save_settings.trash_dir.as_deref(),
+    )?;
+    if args.dry_run {
+        return Ok(undone);
+    }
+
+    if let Some(weekly_goal) = &save_settings.weekly_goal {
+        info!("[run_undo()]: Recounting the weekly goal progress.");
+        let commit_datetime = undone.entry.commit_datetime;
+        let done = commits_in_week(&state_index.entries()?, commit_datetime);
+        update_weekly_note(
+            &weekly_goal.note_path(vault_root, commit_datetime),
+            weekly_goal.commits,
+            Some(done),
+        )?;
+    }
+    if let Some(note) = &save_settings.related_repos_note {
+        info!("[run_undo()]: Rewriting the related repositories note.");
+        record_related_repos(&vault_root.join(note), &state_index)?;
+    }
+    Ok(undone)
+}
+
+/// Logs the commits of `git_repo` made since its last logged commit (`log-recent`).
+///
+/// The commits are found with [`recent_unlogged_commits()`] from the
+/// logged-commits index, looking back at most `lookback` from `now`, and
+/// logged with [`run_batch_saver()`], oldest first.
+///
+/// # Returns
+///
+/// One [`SaveReport`] per logged commit; empty when the repository is up to date.
+///
+/// # Errors
+///
+/// Returns an error if no state directory is available, if the index or the
+/// repository history cannot be read, or if saving a commit failed.
+pub fn run_recent_saver(
+    git_repo: &Repository,
+    now: DateTime<Utc>,
+    lookback: TimeDelta,
+    obsidian_root_path_dir: &Path,
+    obsidian_commit_path: &Path,
+    template_commit_date_path: &str,
+    save_settings: &SaveSettings,
+) -> Result<Vec<SaveReport>, Box<dyn Error>> {
+    let state_dir = save_settings
+        .state_dir
+        .as_deref()
+        .ok_or("No state directory available to read the logged-commits index from")?;
+    let logged_entries = StateIndex::new(state_dir).entries()?;
+
+    let revisions = recent_unlogged_commits(git_repo, &logged_entries, now, lookback)?;
+    if revisions.is_empty() {
+        info!("[run_recent_saver()]: No unlogged recent commit.");
+        return Ok(Vec::new());
+    }
+    run_batch_saver(
+        git_repo,
+        &revisions.join("\n"),
+        obsidian_root_path_dir,
+        obsidian_commit_path,
+        template_commit_date_path,
+        save_settings,
+    )
+}
+
+/// Runs the `log-recent` subcommand on every repository of the projects directory.
+///
+/// Each repository is handled by [`run_recent_saver()`]; its entries carry
+/// the repository's working directory (see [`CommitSaver::in_repository()`]),
+/// so the `FOLDER` column and path-based categories match the repository
+/// without changing the current directory. With `--repo`, the other
+/// repositories are skipped. A failing repository is reported and the others
+/// still run.
+///
+/// # Errors
+///
+/// Returns an error if no projects directory is given or configured, if it
+/// cannot be scanned, or if one or more repositories failed.
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn run_log_recent(
+    args: &LogRecentArgs,
+    projects_dir: Option<PathBuf>,
+    obsidian_root_path_dir: &Path,
+    obsidian_commit_path: &Path,
+    template_commit_date_path: &str,
+    save_settings: &SaveSettings,
+) -> Result<Vec<SaveReport>, Box<dyn Error>> {
+    // LCOV_EXCL_START
+    let projects_dir = args
+        .dir
+        .clone()
+        .or(projects_dir)
+        .ok_or("No projects directory: pass --dir or set [recent] projects_dir")?;
+    let now = Utc::now();
+    let filter = EntryFilter {
+        repository: args.repo.clone(),
+        branch: None,
+    };
+
+    let mut reports = Vec::new();
+    let mut failures = Vec::new();
+    for repository_dir in find_repositories(&projects_dir)? {
+        info!(
+            "[run_log_recent()]: Checking: {:}",
+            repository_dir.display()
+        );
+        let result = Repository::open(&repository_dir)
+            .map_err(Into::into)
+            .and_then(|git_repo| {
+                if !filter.matches_repository(&repository_name(&git_repo, &repository_dir)) {
+                    info!("[run_log_recent()]: Skipping, not the --repo repository.");
+                    return Ok(Vec::new());
+                }
+                run_recent_saver(
+                    &git_repo,
+                    now,
+                    TimeDelta::days(args.days),
+                    obsidian_root_path_dir,
+                    obsidian_commit_path,
+                    template_commit_date_path,
+                    save_settings,
+                )
+            });
+        match result {
+            Ok(repository_reports) => reports.extend(repository_reports),
+            Err(e) => {
+                warn!(
+                    "[run_log_recent()]: Could not log {:}: {e:}",
+                    repository_dir.display()
+                );
+                failures.push(format!("{:}: {e:}", repository_dir.display()));
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(reports)
+    } else {
+        Err(format!(
+            "Could not log {:} repositories:\n{:}",
+            failures.len(),
+            failures.join("\n")
+        )
+        .into())
+    }
+    // LCOV_EXCL_STOP
+}
+
+/// Returns the name `--repo` matches a repository by: the name of its
+/// `origin` remote (see [`repository_name_from_url()`]), or the name of its
+/// directory without one.
+fn repository_name(git_repo: &Repository, repository_dir: &Path) -> String {
+    git_repo
+        .find_remote("origin")
+        .ok()
+        .and_then(|remote| remote.url().ok().map(repository_name_from_url))
+        .unwrap_or_else(|| {
+            repository_dir
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default()
+        })
+}
+
+/// Queues the commits that landed in a watched repository since it was last
+/// checked.
+///
+/// The commits are found with [`WatchedRepository::new_commits()`] and read
+/// like [`run_batch_saver()`] does, with [`collect_commit_batch()`]. Each one
+/// is queued for its diary note (see
+/// [`CommitSaver::prepare_path_for_commit()`]) until the note goes quiet,
+/// then saved by [`save_queued_entries()`].
+///
+/// # Returns
+///
+/// The number of commits queued; 0 when no branch moved.
+///
+/// # Errors
+///
+/// Returns an error if the repository history cannot be read.
+pub fn run_watched_saver(
+    watched: &mut WatchedRepository,
+    git_repo: &Repository,
+    queue: &mut AppendQueue<CommitSaver>,
+    obsidian_commit_path: &Path,
+    template_commit_date_path: &str,
+    now: Instant,
+) -> Result<usize, Box<dyn Error>> {
+    let revisions = watched.new_commits(git_repo)?;
+    if revisions.is_empty() {
+        info!("[run_watched_saver()]: No new commit.");
+        return Ok(0);
+    }
+    let batch = collect_commit_batch(git_repo, &revisions, &head_branch_name(git_repo))?;
+    let queued = batch.len();
+    for commit_saver_struct in batch {
+        let note = commit_saver_struct
+            .clone()
+            .prepare_path_for_commit(obsidian_commit_path, template_commit_date_path);
+        queue.push(Path::new(&note), commit_saver_struct, now);
+    }
+    Ok(queued)
+}
+
+/// The outcome of saving the entries queued for one note: their reports, or
+/// the error of saving them.
+type NoteSave = Result<Vec<SaveReport>, Box<dyn Error>>;
+
+/// Saves the entries of the notes that went quiet at `now`, see
+/// [`AppendQueue::take_due()`].
+///
+/// The entries of a note are logged oldest first with [`save_commit_batch()`],
+/// like [`run_batch_saver()`] does: each row is inserted at its place in time
+/// and commits already logged are skipped.
+///
+/// # Returns
+///
+/// One result per note: the reports of its entries, or the error of saving
+/// them.
+pub fn save_queued_entries(
+    queue: &mut AppendQueue<CommitSaver>,
+    now: Instant,
+    obsidian_root_path_dir: &Path,
+    obsidian_commit_path: &Path,
+    template_commit_date_path: &str,
+    save_settings: &SaveSettings,
+) -> Vec<(PathBuf, NoteSave)> {
+    queue
+        .take_due(now)
+        .into_iter()
+        .map(|(note, mut batch)| {
+            batch.sort_by_key(|commit_saver_struct| commit_saver_struct.entry.datetime);
+            let saved = save_commit_batch(
+                batch,
+                obsidian_root_path_dir,
+                obsidian_commit_path,
+                template_commit_date_path,
+                save_settings,
+            );
+            (note, saved)
+        })
+        .collect()
+}
+
+/// Follows a diary file the user renamed or moved inside the vault.
+///
+/// Renames of files that are not diaries are ignored, see
+/// [`follow_diary_move()`]. Without a state directory there is no index to
+/// tell diaries apart, and nothing is followed.
+///
+/// # Returns
+///
+/// The old and new paths when a diary was followed.
+///
+/// # Errors
+///
+/// Returns an error if the index or the moved-diaries record cannot be
+/// updated.
+pub fn follow_vault_rename(
+    event: &Event,
+    save_settings: &SaveSettings,
+) -> Result<Option<(PathBuf, PathBuf)>, Box<dyn Error>> {
+    let (Some(state_dir), Some((from, to))) =
+        (save_settings.state_dir.as_deref(), rename_event(event))
+    else {
+        return Ok(None);
+    };
+    Ok(follow_diary_move(state_dir, from, to)?.then(|| (from.to_path_buf(), to.to_path_buf())))
+}
+
+/// Runs the `daemon` subcommand until it is interrupted.
+///
+/// Watches the `refs` directory of every `[repositories]` entry for changes.
+/// The new commits of a repository whose refs changed are queued per diary
+/// note by [`run_watched_saver()`], and a note's entries are saved by
+/// [`save_queued_entries()`] once it received none for `debounce`
+/// (`[daemon] debounce_ms`). Git touches several ref files per commit, and a
+/// rebase or a pull lands many commits at once: each note is then written in
+/// one go instead of once per commit. The entries are built from the
+/// repository handle (see [`CommitSaver::from_commit()`]) and carry its
+/// working directory, so the daemon never changes its current directory. A
+/// failing repository or note is reported and the daemon keeps running.
+///
+/// With a state directory, the vault is watched too: a diary file renamed or
+/// moved by the user is followed with [`follow_vault_rename()`], so the next
+/// rows of the day go to it instead of a new file at the old path.
+///
+/// # Errors
+///
+/// Returns an error if no repository is configured, if one cannot be opened
+/// or watched, or if the file watcher stops.
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn run_daemon(
+    repositories: &[PathBuf],
+    debounce: Duration,
+    obsidian_root_path_dir: &Path,
+    obsidian_commit_path: &Path,
+    template_commit_date_path: &str,
+    save_settings: &SaveSettings,
+) -> Result<(), Box<dyn Error>> {
+    // LCOV_EXCL_START
+    if repositories.is_empty() {
+        return Err("No repository to watch: add them to the [repositories] section".into());
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    let mut notifier = notify::recommended_watcher(sender)?;
+    let mut watched = Vec::new();
+    for path in repositories {
+        let git_repo = Repository::open(path)
+            .map_err(|e| format!("Could not open {:}: {e:}", path.display()))?;
+        let repository = WatchedRepository::new(&git_repo, path)?;
+        notifier.watch(&repository.refs_dir, RecursiveMode::Recursive)?;
+        watched.push(repository);
+    }
+    if save_settings.state_dir.is_some() {
+        notifier.watch(obsidian_root_path_dir, RecursiveMode::Recursive)?;
+    }
+    println!(
+        "👀 Watching {:} repositories for new commits.",
+        watched.len()
+    );
+
+    let mut queue = AppendQueue::new(debounce);
+    loop {
+        let timeout = queue
+            .next_deadline()
+            .map_or(Duration::from_secs(3_600), |deadline| {
+                deadline.saturating_duration_since(Instant::now())
+            });
+        match receiver.recv_timeout(timeout) {
+            Ok(Ok(event)) => {
+                match follow_vault_rename(&event, save_settings) {
+                    Ok(Some((from, to))) => {
+                        println!("↪ Following {:} to {:}", from.display(), to.display());
+                    }
+                    Ok(None) => (),
+                    Err(e) => eprintln!("✗ Could not follow a moved diary: {e:}"),
+                }
+                for repository in &mut watched {
+                    if !event.paths.iter().any(|path| repository.watches(path)) {
+                        continue;
+                    }
+                    let queued = Repository::open(&repository.path)
+                        .map_err(Into::into)
+                        .and_then(|git_repo| {
+                            run_watched_saver(
+                                repository,
+                                &git_repo,
+                                &mut queue,
+                                obsidian_commit_path,
+                                template_commit_date_path,
+                                Instant::now(),
+                            )
+                        });
+                    if let Err(e) = queued {
+                        eprintln!("✗ Could not read {:}: {e:}", repository.path.display());
+                    }
+                }
+            }
+            Ok(Err(e)) => warn!("[run_daemon()]: Watch error: {e:}"),
+            Err(RecvTimeoutError::Timeout) => (),
+            Err(RecvTimeoutError::Disconnected) => return Err("The file watcher stopped".into()),
+        }
+
+        for (note, saved) in save_queued_entries(
+            &mut queue,
+            Instant::now(),
+            obsidian_root_path_dir,
+            obsidian_commit_path,
+            template_commit_date_path,
+            save_settings,
+        ) {
+            match saved {
+                Ok(reports) => println!(
+                    "✓ Logged {:} commit(s) for {:}",
+                    reports.len(),
+                    note.display()
+                ),
+                Err(e) => eprintln!("✗ Could not log the commits for {:}: {e:}", note.display()),
+            }
+        }
+    }
+    // LCOV_EXCL_STOP
+}
+
+/// Runs the `save` subcommand, picking the saver from its flags.
+///
+/// `--post-receive` reads the pushed refs from stdin for
+/// [`run_received_saver()`], `--stdin` reads the commit list from stdin for
+/// [`run_batch_saver()`], `--wip` runs [`run_wip_saver()`], anything else
+/// [`run_commit_saver()`]. The repository is the hook's, see
+/// [`hook_repository()`].
+/// `--dry-run` and `--allow-duplicates` are passed to them through
+/// [`SaveSettings::dry_run`] and [`SaveSettings::allow_duplicates`], and
+/// `--force` turns off [`SaveSettings::require_vault`].
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn run_save(
+    args: &SaveArgs,
+    obsidian_root_path_dir: &Path,
+    obsidian_commit_path: &Path,
+    template_commit_date_path: &str,
+    save_settings: &SaveSettings,
+) -> Result<Vec<SaveReport>, Box<dyn Error>> {
+    // LCOV_EXCL_START
+    let save_settings = &SaveSettings {
+        dry_run: args.dry_run,
+        allow_duplicates: args.allow_duplicates,
+        require_vault: save_settings.require_vault && !args.force,
+        ..save_settings.clone()
+    };
+    if args.post_receive {
+        let mut post_receive_input = String::new();
+        std::io::stdin().read_to_string(&mut post_receive_input)?;
+        return run_received_saver(
+            &hook_repository()?,
+            &post_receive_input,
+            obsidian_root_path_dir,
+            obsidian_commit_path,
+            template_commit_date_path,
+            save_settings,
+        );
+    }
+    if args.stdin {
+        let mut commit_list = String::new();
+        std::io::stdin().read_to_string(&mut commit_list)?;
+        let git_repo = hook_repository()?;
+        return run_batch_saver(
+            &git_repo,
+            &commit_list,
+            obsidian_root_path_dir,
+            obsidian_commit_path,
+            template_commit_date_path,
+            save_settings,
+        );
+    }
+
+    if args.wip {
+        return run_wip_saver(
+            obsidian_root_path_dir,
+            obsidian_commit_path,
+            template_commit_date_path,
+            save_settings,
+        );
+    }
+    run_commit_saver(
+        obsidian_root_path_dir,
+        obsidian_commit_path,
+        template_commit_date_path,
+        save_settings,
+    )
+    // LCOV_EXCL_STOP
+}
+
+/// Prints the outcome of `save`: the porcelain records with `--porcelain`,
+/// otherwise the previews of `--dry-run`.
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn print_save_reports(args: &SaveArgs, reports: &[SaveReport]) {
+    // LCOV_EXCL_START
+    if args.porcelain {
+        print_porcelain(reports);
+        return;
+    }
+    for preview in reports.iter().filter_map(|report| report.preview.as_ref()) {
+        println!("{preview:}");
+    }
+    // LCOV_EXCL_STOP
+}
+
+/// Opens the diary file of `save --open` in Obsidian, see [`saved_diary_uri()`].
+///
+/// Failures only print a warning: the entry is already saved.
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn open_saved_diary(
+    reports: &[SaveReport],
+    obsidian_root_path_dir: &Path,
+    save_settings: &SaveSettings,
+) {
+    // LCOV_EXCL_START
+    let Some(uri) = saved_diary_uri(reports, obsidian_root_path_dir, save_settings) else {
+        eprintln!("✗ No diary file to open.");
+        return;
+    };
+    if let Err(e) = open_uri(&uri) {
+        eprintln!("✗ Could not open {uri:} in Obsidian: {e:}");
+    }
+    // LCOV_EXCL_STOP
+}
+
+/// Returns the `obsidian://open` URI of the diary file a save wrote to (or
+/// found the commit in): the last one, when the save wrote several.
+///
+/// The vault is the `[vaults]` entry holding the diary file, named after its
+/// directory, or the configured vault, named `[obsidian] vault_name` when set.
+///
+/// # Returns
+///
+/// `None` if no report has a diary file inside a vault.
+fn saved_diary_uri(
+    reports: &[SaveReport],
+    obsidian_root_path_dir: &Path,
+    save_settings: &SaveSettings,
+) -> Option<String> {
+    let diary_path = reports
+        .iter()
+        .rev()
+        .filter(|report| {
+            matches!(
+                report.status,
+                SaveStatus::Logged | SaveStatus::AlreadyLogged
+            )
+        })
+        .find_map(|report| report.diary_path.as_deref())?;
+    let (vault_root, vault_name) = match save_settings
+        .vaults
+        .iter()
+        .find(|vault| diary_path.starts_with(&vault.root_path_dir))
+    {
+        Some(vault) => (
+            vault.root_path_dir.as_path(),
+            default_vault_name(&vault.root_path_dir)?,
+        ),
+        None => (
+            obsidian_root_path_dir,
+            save_settings
+                .vault_name
+                .clone()
+                .or_else(|| default_vault_name(obsidian_root_path_dir))?,
+        ),
+    };
+    let relative_path = vault_relative_path(vault_root, diary_path)?;
+    Some(open_note_uri(&vault_name, &relative_path))
+}
+
+/// Prints the porcelain `save` record of each report on stdout.
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn print_porcelain(reports: &[SaveReport]) {
+    // LCOV_EXCL_START
+    for report in reports {
+        print!("{:}", report.to_porcelain());
+    }
+    // LCOV_EXCL_STOP
+}
+
+/// Prints the `report` subcommand output for the configured vault, see [`run_report()`].
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn print_report(args: &ReportArgs, global_vars: &GlobalVars) -> Result<(), Box<dyn Error>> {
+    // LCOV_EXCL_START
+    let output = run_report(
+        args,
+        Utc::now().date_naive(),
+        &global_vars.get_obsidian_root_path_dir(),
+        &global_vars.get_obsidian_commit_path(),
+        &global_vars.get_save_settings(),
+    )?;
+    print!("{output:}");
+    Ok(())
+    // LCOV_EXCL_STOP
+}
+
+/// Prints the `stats` subcommand output for the configured vault, see [`run_stats()`].
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn print_stats(args: &StatsArgs, global_vars: &GlobalVars) -> Result<(), Box<dyn Error>> {
+    // LCOV_EXCL_START
+    let output = run_stats(
+        args,
+        Utc::now(),
+        &global_vars.get_obsidian_root_path_dir(),
+        &global_vars.get_obsidian_commit_path(),
+        &global_vars.get_save_settings(),
+    )?;
+    print!("{output:}");
+    Ok(())
+    // LCOV_EXCL_STOP
+}
+
+/// Runs the `heatmap` subcommand and prints the heatmap, see [`run_heatmap()`].
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn print_heatmap(args: &HeatmapArgs, global_vars: &GlobalVars) -> Result<(), Box<dyn Error>> {
+    // LCOV_EXCL_START
+    let output = run_heatmap(
+        args,
+        Utc::now(),
+        &global_vars.get_obsidian_root_path_dir(),
+        &global_vars.get_obsidian_commit_path(),
+        &global_vars.get_save_settings(),
+    )?;
+    print!("{output:}");
+    Ok(())
+    // LCOV_EXCL_STOP
+}
+
+/// Runs the `annotate` subcommand and prints where the note went, see
+/// [`run_annotate()`].
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn annotate_commit(
+    args: &AnnotateArgs,
+    save_settings: &SaveSettings,
+) -> Result<(), Box<dyn Error>> {
+    // LCOV_EXCL_START
+    let (hash, path) = run_annotate(args, save_settings)?;
+    println!(
+        "✓ Annotated {:} in {:}",
+        hash.get(..7).unwrap_or(&hash),
+        path.display()
+    );
+    Ok(())
+    // LCOV_EXCL_STOP
+}
+
+/// Runs the `undo` subcommand and prints the entry it undid, or would undo
+/// with `--dry-run`, see [`run_undo()`].
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn undo_commit(args: &UndoArgs, global_vars: &GlobalVars) -> Result<(), Box<dyn Error>> {
+    // LCOV_EXCL_START
+    let undone = run_undo(
+        args,
+        &global_vars.get_save_settings(),
+        &global_vars.get_obsidian_root_path_dir(),
+    )?;
+    let hash = &undone.entry.commit_hash;
+    let verb = match (args.dry_run, args.strike) {
+        (true, _) => "Would undo",
+        (false, true) => "Struck through",
+        (false, false) => "Removed",
+    };
+    println!(
+        "{:}{verb:} {:} in {:}:",
+        if args.dry_run { "" } else { "✓ " },
+        hash.get(..7).unwrap_or(hash),
+        undone.entry.diary_path
+    );
+    for line in &undone.lines {
+        print!("  {line:}");
+    }
+    Ok(())
+    // LCOV_EXCL_STOP
+}
+
+/// Prints the local usage counters for `metrics show`, see [`MetricsStore`].
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn print_metrics(args: &MetricsArgs, global_vars: &GlobalVars) -> Result<(), Box<dyn Error>> {
+    // LCOV_EXCL_START
+    match args.action {
+        MetricsCommand::Show => {
+            let state_dir = global_vars
+                .get_save_settings()
+                .state_dir
+                .ok_or("No state directory to read the usage metrics from")?;
+            print!("{:}", MetricsStore::new(&state_dir).load()?.render());
+            if !global_vars.get_metrics_enabled() {
+                println!("\nCounting is off, enable it with `[metrics] enabled = true`.");
+            }
+        }
+    }
+    Ok(())
+    // LCOV_EXCL_STOP
+}
+
+/// Brings the state directory to the current schema version, see
+/// [`upgrade_state_dir()`].
+///
+/// Only a state directory written by a newer version stops the run, since
+/// this one could misread it; other failures are logged and the run goes on
+/// as it would without a state directory.
+///
+/// # Errors
+///
+/// Returns a [`NewerStateSchema`] if the state directory is too new.
+fn upgrade_state(save_settings: &SaveSettings) -> Result<(), Box<dyn Error>> {
+    let Some(state_dir) = save_settings.state_dir.as_deref() else {
+        return Ok(());
+    };
+    match upgrade_state_dir(state_dir) {
+        Ok(Some(from)) => {
+            info!("[upgrade_state()]: Migrated the state directory from version {from:}.");
+            Ok(())
+        }
+        Ok(None) => Ok(()),
+        Err(e) if e.is::<NewerStateSchema>() => Err(e),
+        Err(e) => {
+            warn!("[upgrade_state()]: Could not check the state directory: {e:}");
+            Ok(())
+        }
+    }
+}
+
+/// Ends the run when `result` is an error, with the error on stderr: with
+/// [`READ_ONLY_VAULT_EXIT_CODE`] for a read-only vault, and with `1`
+/// otherwise.
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn exit_on_error(result: Result<(), Box<dyn Error>>) {
+    // LCOV_EXCL_START
+    let Err(e) = result else {
+        return;
+    };
+    error!("[main]: {e:}");
+    eprintln!("✗ {e:}");
+    if e.downcast_ref::<ReadOnlyVault>().is_some() {
+        std::process::exit(READ_ONLY_VAULT_EXIT_CODE);
+    }
+    std::process::exit(1);
+    // LCOV_EXCL_STOP
+}
+
+/// Counts a run of `command` in the local usage metrics, when the user
+/// opted in with `[metrics] enabled = true`.
+///
+/// Counting never fails the run: errors are only logged.
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn record_usage(command: &str, succeeded: bool, global_vars: &GlobalVars) {
+    // LCOV_EXCL_START
+    if !global_vars.get_metrics_enabled() {
+        return;
+    }
+    let save_settings = global_vars.get_save_settings();
+    let Some(state_dir) = &save_settings.state_dir else {
+        return;
+    };
+    let features = enabled_features(&save_settings);
+    if let Err(e) = MetricsStore::new(state_dir).record(command, succeeded, &features, Utc::now()) {
+        warn!("[record_usage()]: Could not count the run of '{command:}': {e:}");
+    }
+    // LCOV_EXCL_STOP
+}
+
+/// Runs the `import` subcommand: fetches the user's public GitHub commits and
+/// logs the new ones, see [`run_github_import()`].
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn import_from_github(args: &ImportArgs, global_vars: &GlobalVars) -> Result<(), Box<dyn Error>> {
+    // LCOV_EXCL_START
+    let since = (Utc::now() - TimeDelta::days(args.days)).date_naive();
+    let token = env::var(GITHUB_TOKEN_ENV_VAR).ok();
+    let commits = fetch_github_commits(GITHUB_API_URL, &args.github_user, since, token.as_deref())?;
+
+    let reports = run_github_import(
+        &commits,
+        &global_vars.get_obsidian_root_path_dir(),
+        &global_vars.get_obsidian_commit_path(),
+        &global_vars.get_template_commit_date_path(),
+        &global_vars.get_save_settings(),
+    )?;
+    let imported = reports
+        .iter()
+        .filter(|report| report.status == SaveStatus::Logged)
+        .count();
+    println!(
+        "✓ Imported {imported:} of {:} public commit(s) of {:}.",
+        commits.len(),
+        args.github_user
+    );
+    Ok(())
+    // LCOV_EXCL_STOP
+}
+
+/// Runs `install-hook` (`install`) or `uninstall-hook` on the repository at
+/// `--path` or around the current directory, see [`install_hook()`] and
+/// [`uninstall_hook()`]. The hook calls the binary currently running.
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn run_hook_command(args: &HookArgs, install: bool) -> Result<(), Box<dyn Error>> {
+    // LCOV_EXCL_START
+    let git_repo = Repository::discover(args.path.as_deref().unwrap_or(Path::new("./")))?;
+    let hook = hook_path(&git_repo);
+    let change = if install {
+        install_hook(&hook, &env::current_exe()?)?
+    } else {
+        uninstall_hook(&hook)?
+    };
+    match change {
+        HookChange::Created | HookChange::Updated if install => {
+            println!("✓ Installed the hook: {:}", hook.display());
+        }
+        HookChange::Updated | HookChange::Removed => {
+            println!("✓ Removed the hook: {:}", hook.display());
+        }
+        _ => println!("✓ Nothing to change: {:}", hook.display()),
+    }
+    Ok(())
+    // LCOV_EXCL_STOP
+}
+
+/// Runs the `event` subcommand in the repository around the current
+/// directory, see [`run_event_saver()`].
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn log_event(args: &EventArgs, global_vars: &GlobalVars) -> Result<(), Box<dyn Error>> {
+    // LCOV_EXCL_START
+    let git_repo = hook_repository()?;
+    run_event_saver(
+        &git_repo,
+        &args.event,
+        &global_vars.get_obsidian_root_path_dir(),
+        &global_vars.get_obsidian_commit_path(),
+        &global_vars.get_template_commit_date_path(),
+        &global_vars.get_save_settings(),
+    )
+    // LCOV_EXCL_STOP
+}
+
+/// Prints the `completions` registration script for the binary currently
+/// running, see [`write_registration()`].
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn print_completions(args: &CompletionsArgs) -> Result<(), Box<dyn Error>> {
+    // LCOV_EXCL_START
+    let completer = env::current_exe()?;
+    write_registration(
+        &args.shell,
+        &completer.to_string_lossy(),
+        &mut std::io::stdout(),
+    )
+    // LCOV_EXCL_STOP
+}
+
+/// Prints the issues found by `verify`, and exits with status 1 if there are any.
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn print_verify_issues(issues: &[VerifyIssue]) {
+    // LCOV_EXCL_START
+    if issues.is_empty() {
+        println!("✓ The vault is consistent with the logged-commits index.");
+        return;
+    }
+    for issue in issues {
+        println!("{issue:}");
+    }
+    std::process::exit(1);
+    // LCOV_EXCL_STOP
+}
+
+/// Creates the config file on first run, see [`bootstrap_config()`]: asks the
+/// user on a terminal, or writes a commented default file otherwise. Exits
+/// with its message when there is nothing to run with yet.
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn bootstrap_missing_config() {
+    // LCOV_EXCL_START
+    let config_path = PathBuf::from(get_or_default_config_ini_path());
+    if config_path.exists() {
+        return;
+    }
+    info!("[bootstrap_missing_config()]: No config file, creating one.");
+    let interactive = std::io::stdin().is_terminal() && std::io::stderr().is_terminal();
+    if let Err(message) = bootstrap_config(
+        &config_path,
+        detect_vault(),
+        interactive,
+        &mut std::io::stdin().lock(),
+        &mut std::io::stderr(),
+    ) {
+        eprintln!("{message:}");
+        std::process::exit(1);
+    }
+    // LCOV_EXCL_STOP
+}
+
+/// Runs the `config` subcommand, before (and without) reading the config
+/// file. Errors are printed and exit with status 1.
+///
+/// `config init` writes the config file, see [`init_config()`], then installs
+/// the `post-commit` hook when asked to: with `--install-hook`, or by
+/// answering yes in a terminal opened in a repository.
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn run_config_command(args: &ConfigArgs) {
+    // LCOV_EXCL_START
+    let ConfigCommand::Init(init_args) = &args.action;
+    let interactive = std::io::stdin().is_terminal() && std::io::stderr().is_terminal();
+    let result = init_config(
+        Path::new(&get_or_default_config_ini_path()),
+        init_args,
+        detect_vault(),
+        interactive,
+        &mut std::io::stdin().lock(),
+        &mut std::io::stderr(),
+    )
+    .and_then(|_| {
+        let install = init_args.install_hook
+            || (interactive
+                && Repository::discover("./").is_ok()
+                && confirm(
+                    "Install the post-commit hook in this repository?",
+                    false,
+                    &mut std::io::stdin().lock(),
+                    &mut std::io::stderr(),
+                ));
+        if !install {
+            return Ok(());
+        }
+        let hook_args = HookArgs {
+            path: init_args.hook_path.clone(),
+        };
+        run_hook_command(&hook_args, true)
+    });
+    if let Err(e) = result {
+        eprintln!("{e:}");
+        std::process::exit(1);
+    }
+    // LCOV_EXCL_STOP
+}
+
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn main() {
+    // LCOV_EXCL_START
+    env_logger::init();
+    CompleteEnv::with_factory(UserInput::command).complete();
+    let command = UserInput::parse()
+        .command
+        .unwrap_or_else(|| Command::Save(SaveArgs::default()));
+    if let Command::Config(args) = &command {
+        return run_config_command(args);
+    }
+    bootstrap_missing_config();
+    info!("[main()]: Instanciating GlobalVars Struct.");
+    let global_vars = GlobalVars::new();
+    global_vars.set_all();
+
+    info!("[main()]: Retrieving values from GlobalVars Struct.");
+    let obsidian_root_path_dir = global_vars.get_obsidian_root_path_dir();
+    let obsidian_commit_path = global_vars.get_obsidian_commit_path();
+    let template_commit_date_path = global_vars.get_template_commit_date_path();
+    let save_settings = global_vars.get_save_settings();
+    exit_on_error(upgrade_state(&save_settings));
+
+    let command_name = command.name();
+    let result = match command {
+        Command::Save(args) => run_save(
+            &args,
+            &obsidian_root_path_dir,
+            &obsidian_commit_path,
+            &template_commit_date_path,
+            &save_settings,
+        )
+        .map(|reports| {
+            print_save_reports(&args, &reports);
+            if args.open {
+                open_saved_diary(&reports, &obsidian_root_path_dir, &save_settings);
+            }
+        }),
+        Command::Verify(args) => {
+            let git_repo = Repository::discover("./").ok();
+            let roots = commits_roots(
+                &obsidian_root_path_dir,
+                &obsidian_commit_path,
+                &save_settings,
+            );
+            run_verify(git_repo.as_ref(), &save_settings, &roots, args.fix)
+                .map(|issues| print_verify_issues(&issues))
+        }
+        Command::Today(args) => {
+            let git_repo = Repository::discover("./").ok();
+            run_today(
+                args.date.unwrap_or_else(|| Utc::now().date_naive()),
+                git_repo.as_ref(),
+                &obsidian_root_path_dir,
+                &obsidian_commit_path,
+                &template_commit_date_path,
+                &save_settings,
+            )
+            .map(|output| print!("{output:}"))
+        }
+        Command::LogRecent(args) => run_log_recent(
+            &args,
+            global_vars.get_recent_projects_dir(),
+            &obsidian_root_path_dir,
+            &obsidian_commit_path,
+            &template_commit_date_path,
+            &save_settings,
+        )
+        .map(|reports| {
+            if args.porcelain {
+                print_porcelain(&reports);
+            } else {
+                println!("✓ Logged {:} recent commit(s).", reports.len());
+            }
+        }),
+        Command::Daemon => run_daemon(
+            &global_vars.get_watched_repositories(),
+            global_vars.get_daemon_debounce(),
+            &obsidian_root_path_dir,
+            &obsidian_commit_path,
+            &template_commit_date_path,
+            &save_settings,
+        ),
+        Command::Report(args) => print_report(&args, &global_vars),
+        Command::Import(args) => import_from_github(&args, &global_vars),
+        Command::InstallHook(args) => run_hook_command(&args, true),
+        Command::UninstallHook(args) => run_hook_command(&args, false),
+        Command::Enrich => run_enrich(&save_settings).map(|enriched| {
+            println!("✓ Enriched {enriched:} row(s).");
+        }),
+        Command::Completions(args) => print_completions(&args),
+        Command::Event(args) => log_event(&args, &global_vars),
+        Command::Annotate(args) => annotate_commit(&args, &save_settings),
+        Command::Undo(args) => undo_commit(&args, &global_vars),
+        Command::Stats(args) => print_stats(&args, &global_vars),
+        Command::Heatmap(args) => print_heatmap(&args, &global_vars),
+        Command::Metrics(args) => print_metrics(&args, &global_vars),
+        Command::Config(_) => unreachable!("[main]: config runs before the config is read"),
+    };
+    record_usage(command_name, result.is_ok(), &global_vars);
+    exit_on_error(result);
+    // LCOV_EXCL_STOP
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod main_tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use git2::Repository;
+    use rusty_commit_saver::build_status::BuildStatus;
+    use rusty_commit_saver::build_status::BuildStatusCheck;
+    use rusty_commit_saver::build_status::BuildStatusSource;
+    use rusty_commit_saver::build_status::DEFAULT_BUILD_TIMEOUT;
+    use rusty_commit_saver::category::CategoryRule;
+    use rusty_commit_saver::daily_note::DailyNote;
+    use rusty_commit_saver::daily_note::DailyNoteLinkStyle;
+    use rusty_commit_saver::daily_note::DEFAULT_DAILY_NOTE_TEMPLATE;
+    use rusty_commit_saver::heatmap::HeatmapFormat;
+    use rusty_commit_saver::heatmap::HeatmapSettings;
+    use rusty_commit_saver::hygiene::MessagePattern;
+    use rusty_commit_saver::push::ZERO_HASH;
+    use rusty_commit_saver::routing::parse_route;
+    use rusty_commit_saver::testing::FakeRepo;
+    use rusty_commit_saver::testing::TempVault;
+    use rusty_commit_saver::testing::TEMP_VAULT_COMMIT_PATH;
+    use rusty_commit_saver::time_tracking::TimeTrackingSource;
+    use rusty_commit_saver::timezone::TimezoneStyle;
+    use rusty_commit_saver::trailers::TagPlacement;
+    use rusty_commit_saver::vaults::VaultTarget;
+    use rusty_commit_saver::vim_commit::check_diary_path_exists;
+    use rusty_commit_saver::vim_commit::create_diary_file;
+    use rusty_commit_saver::vim_commit::create_directories_for_new_entry;
+    use rusty_commit_saver::vim_commit::get_parent_from_full_path;
+    use rusty_commit_saver::vim_commit::EntryDetails;
+    use rusty_commit_saver::vim_commit::DEFAULT_TABLE_COLUMNS;
+    use rusty_commit_saver::weekly_log::WeeklyLog;
+    use std::fs;
+    use std::fs::File;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    /// Saves the `HEAD` commit of `repo` into the default commit path of
+    /// `obsidian_root`, like [`run_commit_saver()`] does for the current repository.
+    fn save_fake_head(
+        repo: &FakeRepo,
+        obsidian_root: PathBuf,
+        date_template: &str,
+    ) -> Result<SaveReport, Box<dyn std::error::Error>> {
+        let mut commit_saver = repo.commit_saver()?;
+        save_commit_entry(
+            &mut commit_saver,
+            obsidian_root,
+            Path::new(TEMP_VAULT_COMMIT_PATH),
+            date_template,
+            &SaveSettings::default(),
+        )
+    }
+
+    #[test]
+    fn test_get_parent_from_full_path() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = tempdir()?;
+        let file_path = temp_dir.path().join("subdir").join("file.txt");
+
+        let parent = get_parent_from_full_path(&file_path)?;
+
+        assert_eq!(parent, temp_dir.path().join("subdir"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_parent_from_full_path_root() {
+        let root_path = PathBuf::from("/");
+
+        let result = get_parent_from_full_path(&root_path);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_check_diary_path_exists_true() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = tempdir()?;
+        let file_path = temp_dir.path().join("test_file.md");
+        File::create(&file_path)?;
+
+        let result = check_diary_path_exists(&file_path);
+
+        assert!(result.is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_diary_path_exists_false() {
+        let non_existent_path = PathBuf::from("/non/existent/path");
+
+        let result = check_diary_path_exists(&non_existent_path);
+
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().to_string(), "Path does not exist!");
+    }
+
+    #[test]
+    fn test_create_diary_file() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = tempdir()?;
+        let file_path = temp_dir.path().join("diary.md");
+        let mut commit_saver = CommitSaver::from(CommitEntry {
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: "abc123".to_string(),
+            message: "Test".to_string(),
+            datetime: Utc.with_ymd_and_hms(2023, 12, 25, 10, 30, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        });
+
+        let result = create_diary_file(file_path.to_str().unwrap(), &mut commit_saver);
+
+        assert!(result.is_ok());
+        assert!(file_path.exists());
+
+        let content = fs::read_to_string(&file_path)?;
+        assert!(content.contains("category: diary"));
+        assert!(content.contains("section: commits"));
+        assert!(content.contains("2023-12-25"));
+        assert!(content.contains("| FOLDER | TIME | COMMIT MESSAGE"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_directories_for_new_entry() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = tempdir()?;
+        let nested_path = temp_dir.path().join("deep").join("nested").join("file.md");
+
+        let result = create_directories_for_new_entry(&nested_path);
+
+        assert!(result.is_ok());
+        assert!(temp_dir.path().join("deep").join("nested").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_directories_for_new_entry_invalid_path() {
+        // Test with a path that can't be created (e.g., on a read-only filesystem)
+        let invalid_path = PathBuf::from("/proc/invalid/path/file.md");
+
+        let result = create_directories_for_new_entry(&invalid_path);
+
+        // This should fail on most systems as /proc is not writable
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_run_commit_saver_creates_new_diary() -> Result<(), Box<dyn std::error::Error>> {
+        let vault = TempVault::new()?;
+        let repo = FakeRepo::new()?.with_origin("git@github.com:user/repo.git")?;
+        let oid = repo.commit("feat: first")?;
+
+        let report = save_fake_head(&repo, vault.root().to_path_buf(), "%Y/%m-%B/%F.md")?;
+
+        assert_eq!(report.status, SaveStatus::Logged);
+        let diary_files = vault.diary_files()?;
+        assert_eq!(diary_files.len(), 1);
+        assert!(fs::read_to_string(&diary_files[0])?.contains(&oid.to_string()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_commit_saver_missing_directory_creates_it() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let temp_dir = tempdir()?;
+        let obsidian_root = temp_dir.path().join("non_existent_path");
+        let repo = FakeRepo::new()?;
+        repo.commit("Initial commit")?;
+
+        save_fake_head(&repo, obsidian_root.clone(), "%Y/%m-%B/%F.md")?;
+
+        assert!(obsidian_root.join(TEMP_VAULT_COMMIT_PATH).is_dir());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_commit_saver_append_to_existing_diary() -> Result<(), Box<dyn std::error::Error>> {
+        let vault = TempVault::new()?;
+        let repo = FakeRepo::new()?;
+        let day = Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap();
+
+        let first = repo.commit_at("First", day)?;
+        save_fake_head(&repo, vault.root().to_path_buf(), "%Y/%m-%B/%F.md")?;
+        let second = repo.commit_at("Second", day + chrono::TimeDelta::hours(1))?;
+        save_fake_head(&repo, vault.root().to_path_buf(), "%Y/%m-%B/%F.md")?;
+
+        let content = vault.read_note("Diaries/Commits/2025/01-January/2025-01-14.md")?;
+        assert!(content.contains(&first.to_string()));
+        assert!(content.contains(&second.to_string()));
+        assert_eq!(vault.diary_files()?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_commit_saver_handles_file_write_errors() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let vault = TempVault::new().unwrap();
+        let repo = FakeRepo::new().unwrap();
+        repo.commit("Initial commit").unwrap();
+
+        // Create directory structure first
+        let result = save_fake_head(&repo, vault.root().to_path_buf(), "%Y/%m-%B/%F.md");
+        assert!(result.is_ok());
+
+        // Now make the directory read-only to trigger write errors on second run
+        let diary_dir = vault.commits_dir();
+        let mut perms = fs::metadata(&diary_dir).unwrap().permissions();
+        perms.set_mode(0o444); // Read-only
+        fs::set_permissions(&diary_dir, perms.clone()).ok();
+
+        repo.commit("Second commit").unwrap();
+        let _ = save_fake_head(&repo, vault.root().to_path_buf(), "%Y/%m-%B/%F.md");
+
+        perms.set_mode(0o755);
+        fs::set_permissions(&diary_dir, perms).ok();
+    }
+
+    #[test]
+    fn test_save_commit_entry_writes_wip_row() -> Result<(), Box<dyn std::error::Error>> {
+        let repo_dir = tempdir()?;
+        let repo = Repository::init(repo_dir.path())?;
+        fs::write(repo_dir.path().join("draft.md"), "work in progress")?;
+        let mut wip_saver = CommitSaver::wip_from_repo(&repo)?;
+
+        let vault_dir = tempdir()?;
+        let commit_path = PathBuf::from("Diaries/Commits");
+        save_commit_entry(
+            &mut wip_saver,
+            vault_dir.path().to_path_buf(),
+            &commit_path,
+            "wip.md",
+            &SaveSettings::default(),
+        )?;
+
+        let content = fs::read_to_string(vault_dir.path().join("Diaries/Commits/wip.md"))?;
+        assert!(content.contains("| FOLDER | TIME | COMMIT MESSAGE"));
+        assert!(content.contains("🚧 WIP on "));
+        assert!(content.contains("1 new: draft.md"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_estimates_duration_from_state_index(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let state_dir = tempdir()?;
+        let settings = SaveSettings {
+            table_columns: vec![TableColumn::CommitHash, TableColumn::Duration],
+            state_dir: Some(state_dir.path().to_path_buf()),
+            ..SaveSettings::default()
+        };
+
+        for (hash, minute) in [("first", 0), ("second", 45)] {
+            let mut commit_saver = CommitSaver::from(CommitEntry {
+                repository_url: "https://github.com/test/repo.git".to_string(),
+                branch: "main".to_string(),
+                hash: hash.to_string(),
+                message: "work".to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, minute, 0).unwrap(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            });
+            save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                &settings,
+            )?;
+        }
+
+        let content = fs::read_to_string(vault_dir.path().join("Commits/2025-01-14.md"))?;
+        assert!(content.contains("| COMMIT HASH | DURATION |"));
+        assert!(content.contains("| first |  |\n"));
+        assert!(content.contains("| second | 45m |\n"));
+        let logged = StateIndex::new(state_dir.path()).entries()?;
+        assert_eq!(logged.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_verify_reports_and_fixes_duplicates() -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let state_dir = tempdir()?;
+        let settings = SaveSettings {
+            state_dir: Some(state_dir.path().to_path_buf()),
+            allow_duplicates: true,
+            ..SaveSettings::default()
+        };
+
+        for _ in 0..2 {
+            let mut commit_saver = CommitSaver::from(CommitEntry {
+                repository_url: "https://github.com/test/repo.git".to_string(),
+                branch: "main".to_string(),
+                hash: "same-hash".to_string(),
+                message: "logged twice".to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            });
+            save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                &settings,
+            )?;
+        }
+
+        let commits_roots = [vault_dir.path().join("Commits")];
+        assert_eq!(run_verify(None, &settings, &commits_roots, false)?.len(), 1);
+        assert!(run_verify(None, &settings, &commits_roots, true)?.is_empty());
+        assert!(run_verify(None, &settings, &commits_roots, false)?.is_empty());
+
+        let content = fs::read_to_string(vault_dir.path().join("Commits/2025-01-14.md"))?;
+        assert_eq!(content.matches("same-hash").count(), 1);
+
+        let moved = vault_dir
+            .path()
+            .join("Commits/2025/01-January/2025-01-14.md");
+        fs::create_dir_all(moved.parent().unwrap())?;
+        fs::write(&moved, &content)?;
+        let issues = run_verify(None, &settings, &commits_roots, true)?;
+        assert!(matches!(
+            issues.as_slice(),
+            [VerifyIssue::DuplicateDay { date, .. }] if date == "2025-01-14"
+        ));
+        assert!(run_verify(None, &SaveSettings::default(), &[], false).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_commits_vault_without_looping(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let vault_repo = Repository::init(vault_dir.path())?;
+        let settings = SaveSettings {
+            vault_commit_message: Some("Log {repo} {hash}".to_string()),
+            ..SaveSettings::default()
+        };
+        let mut commit_saver = CommitSaver::from(CommitEntry {
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: "abc123def456".to_string(),
+            message: "work".to_string(),
+            datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        });
+
+        save_commit_entry(
+            &mut commit_saver,
+            vault_dir.path().to_path_buf(),
+            Path::new("Commits"),
+            "%F.md",
+            &settings,
+        )?;
+
+        let vault_commit = vault_repo.head()?.peel_to_commit()?;
+        let vault_message = vault_commit.message()?.to_string();
+        assert!(vault_message.starts_with("Log repo abc123d\n"));
+
+        // The post-commit hook of the vault sees its own auto-commit: skip it.
+        let mut vault_saver = CommitSaver::from_repo(&vault_repo)?;
+        let report = save_commit_entry(
+            &mut vault_saver,
+            vault_dir.path().to_path_buf(),
+            Path::new("Commits"),
+            "%F.md",
+            &settings,
+        )?;
+        assert_eq!(report.status, SaveStatus::VaultSync);
+
+        assert_eq!(vault_repo.head()?.peel_to_commit()?.id(), vault_commit.id());
+        let content = fs::read_to_string(vault_dir.path().join("Commits/2025-01-14.md"))?;
+        assert_eq!(content.matches("| main |").count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_skips_commits_with_skip_trailer(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let repo_dir = tempdir()?;
+        let vault_dir = tempdir()?;
+        let git_repo = Repository::init(repo_dir.path())?;
+        let signature = git2::Signature::now("Test", "test@example.com")?;
+        let tree = git_repo.find_tree(git_repo.index()?.write_tree()?)?;
+        git_repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "Rotate the API token\n\nRcs-Skip: true\n",
+            &tree,
+            &[],
+        )?;
+
+        let mut commit_saver = CommitSaver::from_repo(&git_repo)?;
+        let report = save_commit_entry(
+            &mut commit_saver,
+            vault_dir.path().to_path_buf(),
+            Path::new("Commits"),
+            "%F.md",
+            &SaveSettings::default(),
+        )?;
+
+        assert_eq!(report.status, SaveStatus::Skipped);
+        assert!(!vault_dir.path().join("Commits").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_skips_commits_matching_filters(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let repo = FakeRepo::new()?;
+        repo.commit_file("README.md", "hello\n", "Initial commit")?;
+        repo.checkout_new_branch("dependabot/cargo/serde")?;
+        repo.commit_file("Cargo.lock", "serde\n", "Bump serde")?;
+        let vault_dir = tempdir()?;
+        let settings = SaveSettings {
+            ignore_branches: vec!["wip/*".to_string(), "dependabot/*".to_string()],
+            ..SaveSettings::default()
+        };
+
+        let mut commit_saver = CommitSaver::from_repo(repo.repo())?;
+        let report = save_commit_entry(
+            &mut commit_saver,
+            vault_dir.path().to_path_buf(),
+            Path::new("Commits"),
+            "%F.md",
+            &settings,
+        )?;
+
+        assert_eq!(report.status, SaveStatus::Skipped);
+        assert!(!vault_dir.path().join("Commits").exists());
+
+        let message_filter = SaveSettings {
+            ignore_message_patterns: vec![MessagePattern::new("^Bump ").unwrap()],
+            ..SaveSettings::default()
+        };
+        let bot_filter = SaveSettings {
+            ignore_bots: true,
+            ..SaveSettings::default()
+        };
+        let on_main = CommitSaver {
+            entry: CommitEntry {
+                branch: "main".to_string(),
+                ..commit_saver.entry
+            },
+            ..commit_saver
+        };
+        let by_bot = CommitSaver {
+            entry: CommitEntry {
+                author: Some("dependabot[bot]".to_string()),
+                ..on_main.entry.clone()
+            },
+            ..on_main.clone()
+        };
+        for (mut commit_saver, settings) in [(on_main, message_filter), (by_bot, bot_filter)] {
+            let report = save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                &settings,
+            )?;
+            assert_eq!(report.status, SaveStatus::Skipped);
+        }
+        assert!(!vault_dir.path().join("Commits").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_follows_the_routing_table() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let vault_dir = tempdir()?;
+        let settings = SaveSettings {
+            routes: vec![parse_route("github.com/acme/* -> Work/Commits").unwrap()],
+            ..SaveSettings::default()
+        };
+
+        for repository_url in [
+            "git@github.com:acme/api.git",
+            "https://github.com/me/tool.git",
+        ] {
+            let mut commit_saver = CommitSaver::from(CommitEntry {
+                repository_url: repository_url.to_string(),
+                branch: "main".to_string(),
+                hash: "abc123def456".to_string(),
+                message: "work".to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            });
+            save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                &settings,
+            )?;
+        }
+
+        assert!(vault_dir.path().join("Work/Commits/2025-01-14.md").exists());
+        assert!(vault_dir.path().join("Commits/2025-01-14.md").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_writes_overflow_note_for_long_rows(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let settings = SaveSettings {
+            max_row_length: Some(300),
+            ..SaveSettings::default()
+        };
+        let mut commit_saver = CommitSaver::from(CommitEntry {
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: "abc123def456".to_string(),
+            message: format!("chore: regenerate\n{:}", "lockfile ".repeat(200)),
+            datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        });
+
+        save_commit_entry(
+            &mut commit_saver,
+            vault_dir.path().to_path_buf(),
+            Path::new("Commits"),
+            "%F.md",
+            &settings,
+        )?;
+
+        let diary = fs::read_to_string(vault_dir.path().join("Commits/2025-01-14.md"))?;
+        let row = diary.lines().last().unwrap();
+        assert!(row.chars().count() <= 300);
+        assert!(row.contains("[[2025-01-14.overflow#10-00-00 abc123d\\|more]]"));
+        let overflow = fs::read_to_string(vault_dir.path().join("Commits/2025-01-14.overflow.md"))?;
+        assert!(overflow.contains("## 10-00-00 abc123d\n\nchore: regenerate\nlockfile"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_numbers_rows_of_the_same_second(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let settings = SaveSettings {
+            same_second: SameSecondPolicy::Sequence,
+            ..SaveSettings::default()
+        };
+
+        for hash in ["aaa111", "bbb222", "ccc333"] {
+            let mut commit_saver = CommitSaver::from(CommitEntry {
+                repository_url: "https://github.com/test/repo.git".to_string(),
+                branch: "main".to_string(),
+                hash: hash.to_string(),
+                message: "chore: scripted".to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            });
+            save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                &settings,
+            )?;
+        }
+
+        let diary = fs::read_to_string(vault_dir.path().join("Commits/2025-01-14.md"))?;
+        let times = diary
+            .lines()
+            .filter(|line| line.contains("chore: scripted"))
+            .map(|line| line.split('|').nth(2).unwrap().trim())
+            .collect::<Vec<_>>();
+        assert_eq!(times, ["10:00:00", "10:00:00 #02", "10:00:00 #03"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_skips_commits_already_in_the_diary(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let mut commit_saver = CommitSaver::from(CommitEntry {
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: "abc123def456".to_string(),
+            message: "fix: hook fired twice".to_string(),
+            datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        });
+        let mut save = |settings: &SaveSettings| {
+            save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                settings,
+            )
+            .map(|report| report.status)
+        };
+
+        assert_eq!(save(&SaveSettings::default())?, SaveStatus::Logged);
+        assert_eq!(save(&SaveSettings::default())?, SaveStatus::AlreadyLogged);
+        let allow_duplicates = SaveSettings {
+            allow_duplicates: true,
+            ..SaveSettings::default()
+        };
+        assert_eq!(save(&allow_duplicates)?, SaveStatus::Logged);
+
+        let diary = fs::read_to_string(vault_dir.path().join("Commits/2025-01-14.md"))?;
+        assert_eq!(diary.matches("| abc123def456 |").count(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_spools_the_weekly_row_until_it_can_be_written(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let state_dir = tempdir()?;
+        let settings = SaveSettings {
+            state_dir: Some(state_dir.path().to_path_buf()),
+            weekly_log: Some(WeeklyLog {
+                note_template: "Weekly/%G-W%V.md".to_string(),
+                row_template: "- {{time}} {{message}}".to_string(),
+            }),
+            ..SaveSettings::default()
+        };
+        let mut morning = CommitSaver::from(CommitEntry {
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: "abc123def456".to_string(),
+            message: "feat: morning".to_string(),
+            datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        });
+        let mut afternoon = CommitSaver {
+            entry: CommitEntry {
+                hash: "fed654cba321".to_string(),
+                message: "fix: afternoon".to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, 14, 15, 0, 0).unwrap(),
+                ..morning.entry.clone()
+            },
+            ..morning.clone()
+        };
+        let save = |commit_saver: &mut CommitSaver| {
+            save_commit_entry(
+                commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                &settings,
+            )
+        };
+
+        // A file in the way of the weekly notes directory.
+        fs::write(vault_dir.path().join("Weekly"), "")?;
+        assert_eq!(save(&mut morning)?.status, SaveStatus::Logged);
+        assert!(
+            fs::read_to_string(vault_dir.path().join("Commits/2025-01-14.md"))?
+                .contains("feat: morning")
+        );
+        assert_eq!(
+            Spool::new(state_dir.path()).pending()?[0].text,
+            "- 10:00:00 feat: morning\n"
+        );
+
+        fs::remove_file(vault_dir.path().join("Weekly"))?;
+        save(&mut afternoon)?;
+
+        assert_eq!(
+            fs::read_to_string(vault_dir.path().join("Weekly/2025-W03.md"))?,
+            "# 2025-W03\n\n- 10:00:00 feat: morning\n- 15:00:00 fix: afternoon\n"
+        );
+        assert!(Spool::new(state_dir.path()).pending()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_follows_a_diary_moved_by_the_user(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let state_dir = tempdir()?;
+        let settings = SaveSettings {
+            state_dir: Some(state_dir.path().to_path_buf()),
+            ..SaveSettings::default()
+        };
+        let mut morning = CommitSaver::from(CommitEntry {
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: "abc123def456".to_string(),
+            message: "feat: morning".to_string(),
+            datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        });
+        let mut afternoon = CommitSaver {
+            entry: CommitEntry {
+                hash: "fed654cba321".to_string(),
+                message: "fix: afternoon".to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, 14, 15, 0, 0).unwrap(),
+                ..morning.entry.clone()
+            },
+            ..morning.clone()
+        };
+        let save = |commit_saver: &mut CommitSaver| {
+            save_commit_entry(
+                commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                &settings,
+            )
+        };
+
+        save(&mut morning)?;
+        let old_path = vault_dir.path().join("Commits/2025-01-14.md");
+        let new_path = vault_dir.path().join("Projects/Sprint 3.md");
+        fs::create_dir_all(new_path.parent().unwrap())?;
+        fs::rename(&old_path, &new_path)?;
+        let renamed = Event::new(notify::EventKind::Modify(notify::event::ModifyKind::Name(
+            notify::event::RenameMode::Both,
+        )))
+        .add_path(old_path.clone())
+        .add_path(new_path.clone());
+        assert_eq!(
+            follow_vault_rename(&renamed, &settings)?,
+            Some((old_path.clone(), new_path.clone()))
+        );
+        let report = save(&mut afternoon)?;
+
+        assert!(!old_path.exists());
+        assert_eq!(report.diary_path, Some(new_path.clone()));
+        let diary = fs::read_to_string(&new_path)?;
+        assert!(diary.contains("| abc123def456 |"));
+        assert!(diary.contains("| fed654cba321 |"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_replaces_the_row_of_an_amended_commit(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let state_dir = tempdir()?;
+        let settings = SaveSettings {
+            state_dir: Some(state_dir.path().to_path_buf()),
+            ..SaveSettings::default()
+        };
+        let saver = |hash: &str, message: &str, amends: Option<&str>| CommitSaver {
+            details: EntryDetails {
+                amends: amends.map(ToString::to_string),
+                ..EntryDetails::default()
+            },
+            ..CommitSaver::from(CommitEntry {
+                repository_url: "https://github.com/test/repo.git".to_string(),
+                branch: "main".to_string(),
+                hash: hash.to_string(),
+                message: message.to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            })
+        };
+
+        for mut commit_saver in [
+            saver("typo111", "fix: tpyo", None),
+            saver("next222", "feat: next", None),
+            saver("fixed333", "fix: typo", Some("typo111")),
+        ] {
+            save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                &settings,
+            )?;
+        }
+
+        let diary = fs::read_to_string(vault_dir.path().join("Commits/2025-01-14.md"))?;
+        assert!(!diary.contains("typo111"));
+        assert!(diary.find("fixed333").unwrap() < diary.find("next222").unwrap());
+        let hashes = StateIndex::new(state_dir.path())
+            .entries()?
+            .into_iter()
+            .map(|entry| entry.commit_hash)
+            .collect::<Vec<_>>();
+        assert_eq!(hashes, ["next222", "fixed333"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_merges_categories_into_existing_diary(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let settings = SaveSettings {
+            category_rules: vec![
+                CategoryRule {
+                    category: "oss".to_string(),
+                    pattern: "*github.com/*".to_string(),
+                },
+                CategoryRule {
+                    category: "work".to_string(),
+                    pattern: "*github.com/acme/*".to_string(),
+                },
+            ],
+            ..SaveSettings::default()
+        };
+
+        for (hour, repository_url) in [
+            (10, "https://github.com/acme/api.git"),
+            (11, "https://github.com/me/tool.git"),
+            (12, "https://github.com/acme/web.git"),
+        ] {
+            let mut commit_saver = CommitSaver::from(CommitEntry {
+                repository_url: repository_url.to_string(),
+                branch: "main".to_string(),
+                hash: format!("abc{hour:}"),
+                message: "work".to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, 14, hour, 0, 0).unwrap(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            });
+            save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                &settings,
+            )?;
+        }
+
+        let diary = fs::read_to_string(vault_dir.path().join("Commits/2025-01-14.md"))?;
+        let (frontmatter, table) = diary.split_once("\n---\n").unwrap();
+        assert_eq!(frontmatter.matches("- '#category/work'").count(), 1);
+        assert!(frontmatter.contains("- '#category/oss'"));
+        assert!(frontmatter.contains("categories:\n- 'work'\n- 'oss'\n"));
+        assert_eq!(
+            table.lines().filter(|line| line.contains("abc1")).count(),
+            3
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_tags_detected_languages() -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let settings = SaveSettings {
+            detect_language: true,
+            ..SaveSettings::default()
+        };
+
+        for (hour, commit_msg) in [
+            (10, "wip"),
+            (
+                11,
+                "Fix the crash on startup when the configuration file is missing",
+            ),
+            (
+                12,
+                "Behebt den Absturz beim Start, wenn die Konfigurationsdatei fehlt, und zeigt stattdessen eine verständliche Fehlermeldung",
+            ),
+        ] {
+            let mut commit_saver = CommitSaver::from(CommitEntry {
+                repository_url: "https://github.com/test/repo.git".to_string(),
+                branch: "main".to_string(),
+                hash: format!("abc{hour:}"),
+                message: commit_msg.to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, 14, hour, 0, 0).unwrap(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            });
+            save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                &settings,
+            )?;
+        }
+
+        let diary = fs::read_to_string(vault_dir.path().join("Commits/2025-01-14.md"))?;
+        let (frontmatter, _) = diary.split_once("\n---\n").unwrap();
+        assert!(frontmatter.contains("- '#lang/eng'"));
+        assert!(frontmatter.contains("- '#lang/deu'"));
+        assert!(!frontmatter.contains("categories:\n-"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_tags_and_shows_branch_slugs() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let vault_dir = tempdir()?;
+        let mut table_columns = DEFAULT_TABLE_COLUMNS.to_vec();
+        table_columns.push(TableColumn::BranchSlug);
+        let settings = SaveSettings {
+            table_columns,
+            branch_tags: true,
+            ..SaveSettings::default()
+        };
+
+        for (hour, branch) in [(10, "main"), (11, "feature/login-page")] {
+            let mut commit_saver = CommitSaver::from(CommitEntry {
+                repository_url: "https://github.com/test/repo.git".to_string(),
+                branch: branch.to_string(),
+                hash: format!("abc{hour:}"),
+                message: "work".to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, 14, hour, 0, 0).unwrap(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            });
+            save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                &settings,
+            )?;
+        }
+
+        let diary = fs::read_to_string(vault_dir.path().join("Commits/2025-01-14.md"))?;
+        let (frontmatter, table) = diary.split_once("\n---\n").unwrap();
+        assert!(frontmatter.contains("- '#branch/main'"));
+        assert!(frontmatter.contains("- '#branch/feature-login-page'"));
+        assert!(table.contains("| BRANCH SLUG |"));
+        assert!(table.contains("| feature/login-page | abc11 | feature-login-page |"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_parses_conventional_commits() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let vault_dir = tempdir()?;
+        let table_columns = ["time", "type", "scope", "breaking", "subject", "hash"]
+            .map(|name| TableColumn::from_name(name).unwrap())
+            .to_vec();
+        let settings = SaveSettings {
+            table_columns,
+            conventional_commits: true,
+            ..SaveSettings::default()
+        };
+
+        for (hour, message) in [(10, "feat(api)!: drop v1"), (11, "Update README")] {
+            let mut commit_saver = CommitSaver::from(CommitEntry {
+                repository_url: "https://github.com/test/repo.git".to_string(),
+                branch: "main".to_string(),
+                hash: format!("abc{hour:}"),
+                message: message.to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, 14, hour, 0, 0).unwrap(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            });
+            save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                &settings,
+            )?;
+        }
+
+        let diary = fs::read_to_string(vault_dir.path().join("Commits/2025-01-14.md"))?;
+        let (frontmatter, table) = diary.split_once("\n---\n").unwrap();
+        assert!(frontmatter.contains("- '#commit/feat'"));
+        assert!(table.contains("| TIME | TYPE | SCOPE | BREAKING | SUBJECT | COMMIT HASH |"));
+        assert!(table.contains("| 10:00:00 | feat | api | yes | drop v1 | abc10 |"));
+        assert!(table.contains("| 11:00:00 |  |  |  |  | abc11 |"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_adopts_an_existing_daily_note(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let diary_path = vault_dir.path().join("Commits/2025-01-14.md");
+        fs::create_dir_all(diary_path.parent().unwrap())?;
+        fs::write(
+            &diary_path,
+            "---\ntags:\n  - daily\n---\n# Tuesday\n\nGym at 7.\n",
+        )?;
+        let settings = SaveSettings {
+            table_columns: vec![TableColumn::Time, TableColumn::CommitHash],
+            insert_under: Some("### Git".to_string()),
+            ..SaveSettings::default()
+        };
+
+        for hour in [10, 11] {
+            let mut commit_saver = CommitSaver::from(CommitEntry {
+                repository_url: "https://github.com/test/repo.git".to_string(),
+                branch: "main".to_string(),
+                hash: format!("abc{hour:}"),
+                message: "Commit".to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, 14, hour, 0, 0).unwrap(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            });
+            save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                &settings,
+            )?;
+        }
+
+        assert_eq!(
+            fs::read_to_string(&diary_path)?,
+            "---\ntags:\n  - daily\n  - '#datetime/week/02'\n  - '#datetime/days/Tuesday'\n  - '#diary/commits'\n---\n# Tuesday\n\nGym at 7.\n\n### Git\n\n| TIME | COMMIT HASH |\n|------|-------------|\n| 10:00:00 | abc10 |\n| 11:00:00 | abc11 |\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_reads_trailer_tags() -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let settings = SaveSettings {
+            table_columns: vec![TableColumn::Time, TableColumn::CommitMessage],
+            trailer_tags: Some(TagPlacement::Both),
+            ..SaveSettings::default()
+        };
+
+        for (hour, message) in [
+            (10, "Refactor auth\nObsidian-Tags: project-x, deep-work"),
+            (11, "Fix typo\nObsidian-Tags: #errands"),
+        ] {
+            let mut commit_saver = CommitSaver::from(CommitEntry {
+                repository_url: "https://github.com/test/repo.git".to_string(),
+                branch: "main".to_string(),
+                hash: format!("abc{hour:}"),
+                message: message.to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, 14, hour, 0, 0).unwrap(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            });
+            save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                &settings,
+            )?;
+        }
+
+        let diary = fs::read_to_string(vault_dir.path().join("Commits/2025-01-14.md"))?;
+        let (frontmatter, table) = diary.split_once("\n---\n").unwrap();
+        assert!(frontmatter.contains("- '#project-x'\n- '#deep-work'"));
+        assert!(frontmatter.contains("- '#errands'"));
+        assert!(table.contains("| 10:00:00 | Refactor auth #project-x #deep-work |"));
+        assert!(table.contains("| 11:00:00 | Fix typo #errands |"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_tags_messages_not_matching_the_pattern(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let settings = SaveSettings {
+            message_pattern: Some(MessagePattern::new(r"^(feat|fix): .+")?),
+            ..SaveSettings::default()
+        };
+
+        for (hour, message) in [(10, "feat: add login"), (11, "wip"), (12, "fix stuff")] {
+            let mut commit_saver = CommitSaver::from(CommitEntry {
+                repository_url: "https://github.com/acme/api.git".to_string(),
+                branch: "main".to_string(),
+                hash: format!("{hour:}abcdef0"),
+                message: message.to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, 14, hour, 0, 0).unwrap(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            });
+            save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                &settings,
+            )?;
+            assert_eq!(commit_saver.details.needs_better_message, hour != 10);
+        }
+
+        let diary = fs::read_to_string(vault_dir.path().join("Commits/2025-01-14.md"))?;
+        let (frontmatter, _) = diary.split_once("\n---\n").unwrap();
+        assert_eq!(frontmatter.matches("- '#needs-better-message'").count(), 1);
+
+        let weekly = fs::read_to_string(vault_dir.path().join("Diaries/Weekly/2025-W03.md"))?;
+        assert_eq!(
+            weekly,
+            "## Message hygiene\n\n\
+             - [[Commits/2025-01-14|2025-01-14]] api `11abcde` wip\n\
+             - [[Commits/2025-01-14|2025-01-14]] api `12abcde` fix stuff\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_updates_the_weekly_goal() -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let state_dir = tempdir()?;
+        let settings = SaveSettings {
+            state_dir: Some(state_dir.path().to_path_buf()),
+            weekly_goal: Some(WeeklyGoal {
+                commits: 20,
+                note_template: "Weekly/%G-W%V.md".to_string(),
+            }),
+            ..SaveSettings::default()
+        };
+
+        for (day, entry_kind) in [
+            (13, EntryKind::Commit),
+            (14, EntryKind::Wip),
+            (15, EntryKind::Commit),
+            (20, EntryKind::Commit),
+        ] {
+            let mut commit_saver = CommitSaver {
+                details: EntryDetails {
+                    entry_kind,
+                    ..EntryDetails::default()
+                },
+                ..CommitSaver::from(CommitEntry {
+                    repository_url: "https://github.com/test/repo.git".to_string(),
+                    branch: "main".to_string(),
+                    hash: format!("abc{day:}"),
+                    message: "work".to_string(),
+                    datetime: Utc.with_ymd_and_hms(2025, 1, day, 10, 0, 0).unwrap(),
+                    author: None,
+                    author_email: None,
+                    stats: None,
+                    parents: Vec::new(),
+                })
+            };
+            save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                &settings,
+            )?;
+        }
+
+        assert_eq!(
+            fs::read_to_string(vault_dir.path().join("Weekly/2025-W03.md"))?,
+            "---\ngoal: 20\ndone: 2\n---\n"
+        );
+        assert_eq!(
+            fs::read_to_string(vault_dir.path().join("Weekly/2025-W04.md"))?,
+            "---\ngoal: 20\ndone: 1\n---\n"
+        );
+
+        let undo = UndoArgs {
+            hash: Some("abc13".to_string()),
+            ..UndoArgs::default()
+        };
+        let preview = run_undo(
+            &UndoArgs {
+                dry_run: true,
+                ..undo.clone()
+            },
+            &settings,
+            vault_dir.path(),
+        )?;
+        assert_eq!(preview.entry.commit_hash, "abc13");
+        assert!(fs::read_to_string(&preview.entry.diary_path)?.contains("abc13"));
+
+        let undone = run_undo(&undo, &settings, vault_dir.path())?;
+        assert!(!fs::read_to_string(&undone.entry.diary_path)?.contains("abc13"));
+        assert_eq!(
+            fs::read_to_string(vault_dir.path().join("Weekly/2025-W03.md"))?,
+            "---\ngoal: 20\ndone: 1\n---\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_github_import_skips_commits_already_in_the_diary(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let diary_path = vault_dir.path().join("Commits/2025-01-14.md");
+        fs::create_dir_all(diary_path.parent().unwrap())?;
+        fs::write(
+            &diary_path,
+            "---\ndate: 2025-01-14\n---\n| TIME | COMMIT HASH |\n|---|---|\n| 10:00:00 | b2 |\n",
+        )?;
+        let commit = |sha: &str, day: u32| GithubCommit {
+            sha: sha.to_string(),
+            message: "work".to_string(),
+            datetime: Utc.with_ymd_and_hms(2025, 1, day, 9, 0, 0).unwrap(),
+            repository_url: "https://github.com/user/api.git".to_string(),
+        };
+
+        let reports = run_github_import(
+            &[commit("a1", 13), commit("b2", 14), commit("a1", 13)],
+            vault_dir.path(),
+            Path::new("Commits"),
+            "%F.md",
+            &SaveSettings::default(),
+        )?;
+
+        let statuses = reports.iter().map(|r| r.status).collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            [
+                SaveStatus::Logged,
+                SaveStatus::AlreadyLogged,
+                SaveStatus::AlreadyLogged
+            ]
+        );
+        let imported = fs::read_to_string(vault_dir.path().join("Commits/2025-01-13.md"))?;
+        assert!(imported.contains("| work | https://github.com/user/api.git |  | a1 |"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_requires_an_obsidian_vault() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let vault_dir = tempdir()?;
+        let settings = SaveSettings {
+            require_vault: true,
+            ..SaveSettings::default()
+        };
+        let mut commit_saver = CommitSaver::from(CommitEntry {
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: "abc123".to_string(),
+            message: "work".to_string(),
+            datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        });
+        let mut save = |settings: &SaveSettings| {
+            save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                settings,
+            )
+        };
+
+        assert!(save(&settings).is_err());
+        assert!(!vault_dir.path().join("Commits").exists());
+
+        fs::create_dir(vault_dir.path().join(".obsidian"))?;
+        assert_eq!(save(&settings)?.status, SaveStatus::Logged);
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_dry_run_writes_nothing() -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let settings = SaveSettings {
+            dry_run: true,
+            ..SaveSettings::default()
+        };
+        let mut commit_saver = CommitSaver::from(CommitEntry {
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: "abc123".to_string(),
+            message: "fix: a | b".to_string(),
+            datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        });
+
+        let report = save_commit_entry(
+            &mut commit_saver,
+            vault_dir.path().to_path_buf(),
+            Path::new("Commits"),
+            "%Y/%F.md",
+            &settings,
+        )?;
+
+        let diary_path = vault_dir.path().join("Commits/2025/2025-01-14.md");
+        assert_eq!(report.status, SaveStatus::DryRun);
+        assert_eq!(report.diary_path.as_ref(), Some(&diary_path));
+        let preview = report.preview.unwrap();
+        assert!(preview.starts_with(&format!("Would create {:}:\n\n---\n", diary_path.display())));
+        assert!(preview.contains("# 2025-01-14"));
+        assert!(preview.contains("\nWould append:\n\n| "));
+        assert!(preview
+            .ends_with("| fix: a \\| b | https://github.com/test/repo.git | main | abc123 |\n"));
+        assert!(!vault_dir.path().join("Commits").exists());
+
+        fs::create_dir_all(diary_path.parent().unwrap())?;
+        fs::write(&diary_path, "existing\n")?;
+        let report = save_commit_entry(
+            &mut commit_saver,
+            vault_dir.path().to_path_buf(),
+            Path::new("Commits"),
+            "%Y/%F.md",
+            &settings,
+        )?;
+        assert!(report
+            .preview
+            .unwrap()
+            .starts_with(&format!("Would append to {:}:\n\n| ", diary_path.display())));
+        assert_eq!(fs::read_to_string(&diary_path)?, "existing\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_updates_the_related_repos_note(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let state_dir = tempdir()?;
+        let settings = SaveSettings {
+            state_dir: Some(state_dir.path().to_path_buf()),
+            related_repos_note: Some(PathBuf::from("Rollups/Related Repos.md")),
+            ..SaveSettings::default()
+        };
+
+        for (repository, hour) in [("api", 9), ("web", 9), ("web", 15)] {
+            let mut commit_saver = CommitSaver::from(CommitEntry {
+                repository_url: format!("https://github.com/test/{repository:}.git"),
+                branch: "main".to_string(),
+                hash: format!("{repository:}{hour:}"),
+                message: "work".to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, 14, hour, 0, 0).unwrap(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            });
+            save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                &settings,
+            )?;
+        }
+
+        let note = fs::read_to_string(vault_dir.path().join("Rollups/Related Repos.md"))?;
+        assert!(note.contains("1 of 1 days with logged commits touched more than one repository."));
+        assert!(note.contains("| [[api]] | [[web]] | 1 | 1 |\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_creates_diaries_from_the_user_template(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let template = vault_dir.path().join("Templates/Daily.md");
+        fs::create_dir_all(template.parent().unwrap())?;
+        fs::write(&template, "# {{title}}\n\nPlans for {{date}}:\n")?;
+        let settings = SaveSettings {
+            diary_template: Some(template),
+            ..SaveSettings::default()
+        };
+
+        for (template_settings, day) in [(&settings, 14), (&SaveSettings::default(), 15)] {
+            let mut commit_saver = CommitSaver::from(CommitEntry {
+                repository_url: "https://github.com/test/repo.git".to_string(),
+                branch: "main".to_string(),
+                hash: format!("abc{day:}"),
+                message: "work".to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, day, 10, 0, 0).unwrap(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            });
+            save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                template_settings,
+            )?;
+        }
+
+        let templated = fs::read_to_string(vault_dir.path().join("Commits/2025-01-14.md"))?;
+        assert!(templated.starts_with("# 2025-01-14\n\nPlans for 2025-01-14:\n\n| FOLDER |"));
+        assert!(templated.contains("| abc14 |"));
+        let built_in = fs::read_to_string(vault_dir.path().join("Commits/2025-01-15.md"))?;
+        assert!(built_in.starts_with("---\ncategory: diary"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_renders_the_handlebars_diary_file(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let daily = vault_dir.path().join("Templates/Daily.md");
+        let handlebars = vault_dir.path().join("Templates/diary.md.hbs");
+        let broken = vault_dir.path().join("Templates/broken.md.hbs");
+        fs::create_dir_all(daily.parent().unwrap())?;
+        fs::write(&daily, "# Daily {{date}}\n")?;
+        fs::write(
+            &handlebars,
+            "# {{date}}, week {{week_number}}\n\n{{table_header}}",
+        )?;
+        fs::write(&broken, "{{#if}}")?;
+
+        for (template, day) in [(&handlebars, 14), (&broken, 15)] {
+            let settings = SaveSettings {
+                diary_template: Some(daily.clone()),
+                diary_file_template: Some(template.clone()),
+                ..SaveSettings::default()
+            };
+            let mut commit_saver = CommitSaver::from(CommitEntry {
+                repository_url: "https://github.com/test/repo.git".to_string(),
+                branch: "main".to_string(),
+                hash: format!("abc{day:}"),
+                message: "work".to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, day, 10, 0, 0).unwrap(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            });
+            save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                &settings,
+            )?;
+        }
+
+        let rendered = fs::read_to_string(vault_dir.path().join("Commits/2025-01-14.md"))?;
+        assert!(rendered.starts_with("# 2025-01-14, week 02\n\n| FOLDER |"));
+        assert!(rendered.contains("| abc14 |"));
+        let fallback = fs::read_to_string(vault_dir.path().join("Commits/2025-01-15.md"))?;
+        assert!(fallback.starts_with("# Daily 2025-01-15\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_entry_yaml_format_appends_blocks() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let vault_dir = tempdir()?;
+        let settings = SaveSettings {
+            table_columns: vec![TableColumn::CommitMessage, TableColumn::CommitHash],
+            entry_format: EntryFormat::Yaml,
+            max_row_length: Some(10),
+            ..SaveSettings::default()
+        };
+        for (commit_hash, commit_msg) in [("abc123", "first"), ("def456", "a much longer message")]
+        {
+            let mut commit_saver = CommitSaver::from(CommitEntry {
+                repository_url: "https://github.com/test/repo.git".to_string(),
+                branch: "main".to_string(),
+                hash: commit_hash.to_string(),
+                message: commit_msg.to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            });
+            save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                &settings,
+            )?;
+        }
 
-    info!("[run_commit_saver()]: Preparing the diary entry path to the new commit.");
-    let diary_entry_path = commit_saver_struct
-        .prepare_path_for_commit(obsidian_commit_path, template_commit_date_path);
+        let commits_dir = vault_dir.path().join("Commits");
+        let content = fs::read_to_string(commits_dir.join("2025-01-14.md"))?;
+        assert!(!content.contains("| COMMIT MESSAGE |"));
+        assert!(content.ends_with(
+            "# 2025-01-14\n\n\
+             ```yaml\ncommit_message: \"first\"\ncommit_hash: \"abc123\"\n```\n\n\
+             ```yaml\ncommit_message: \"a much longer message\"\ncommit_hash: \"def456\"\n```\n\n"
+        ));
+        assert!(!commits_dir.join("2025-01-14.overflow.md").exists());
+        Ok(())
+    }
 
-    let mut full_path = obsidian_root_path_dir;
-    for directory in diary_entry_path.split('/') {
-        full_path.push(directory);
+    #[test]
+    fn test_save_commit_entry_annotates_the_timezone() -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let commit_datetime = Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap();
+        let mut commit_saver = CommitSaver::from(CommitEntry {
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: "abc123".to_string(),
+            message: "work".to_string(),
+            datetime: commit_datetime,
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        });
+        let settings = SaveSettings {
+            table_columns: vec![TableColumn::Time, TableColumn::CommitHash],
+            time_zone: Some(TimezoneStyle::Offset),
+            ..SaveSettings::default()
+        };
+
+        save_commit_entry(
+            &mut commit_saver,
+            vault_dir.path().to_path_buf(),
+            Path::new("Commits"),
+            "%F.md",
+            &settings,
+        )?;
+
+        let expected = entry_timezone(
+            Some(TimezoneStyle::Offset),
+            system_timezone(),
+            commit_datetime,
+        );
+        let content = fs::read_to_string(vault_dir.path().join("Commits/2025-01-14.md"))?;
+        assert!(content.contains(&format!("timezone: '{:}'\n", expected.label)));
+        assert!(content.contains(&format!(
+            "| {:} | abc123 |\n",
+            expected.format_time(commit_datetime, "%H:%M:%S")
+        )));
+        Ok(())
     }
 
-    let stringed_root_path_dir = full_path
-        .as_os_str()
-        .to_str()
-        .ok_or("Could not convert path to string")?;
+    #[test]
+    fn test_save_commit_entry_writes_the_local_day_of_the_timezone(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let commit_datetime = Utc.with_ymd_and_hms(2025, 1, 14, 14, 30, 0).unwrap();
+        let mut commit_saver = CommitSaver::from(CommitEntry {
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: "abc123".to_string(),
+            message: "work".to_string(),
+            datetime: commit_datetime,
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        });
+        let settings = SaveSettings {
+            table_columns: vec![TableColumn::Time, TableColumn::CommitHash],
+            timezone: Some(DiaryTimezone::Named(chrono_tz::Australia::Sydney)),
+            ..SaveSettings::default()
+        };
 
-    info!("[run_commit_saver()]: Checking if Diary file and/or path exists.");
-    if check_diary_path_exists(&full_path).is_ok() {
-        info!("[run_commit_saver()]: Diary file and path exists: {stringed_root_path_dir:}");
-    } else {
-        info!("[run_commit_saver()]: Diary file and or path DO NOT exist.");
-        info!("[run_commit_saver()]: Creating the directories for the new entry.");
-        create_directories_for_new_entry(&full_path)?;
+        save_commit_entry(
+            &mut commit_saver,
+            vault_dir.path().to_path_buf(),
+            Path::new("Commits"),
+            "%F.md",
+            &settings,
+        )?;
 
-        info!("[run_commit_saver()]: Creating the files for the new entry.");
-        create_diary_file(stringed_root_path_dir, &mut commit_saver_struct)?;
+        assert!(!vault_dir.path().join("Commits/2025-01-14.md").exists());
+        let content = fs::read_to_string(vault_dir.path().join("Commits/2025-01-15.md"))?;
+        assert!(content.contains("# 2025-01-15\n"));
+        assert!(content.contains("#datetime/days/Wednesday"));
+        assert!(!content.contains("timezone:"));
+        assert!(content.ends_with("| 01:30:00 | abc123 |\n"));
+        Ok(())
     }
 
-    info!("[run_commit_saver()]: Writing the commit in the file.");
-    commit_saver_struct.append_entry_to_diary(&full_path)?;
-    info!("[run_commit_saver]: Commit logged in ");
+    #[test]
+    fn test_save_entry_writes_the_full_entry_to_the_sinks_once_logged(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let temp_dir = tempdir()?;
+        let entry = CommitEntry {
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: "abc123".to_string(),
+            message: format!("chore: regenerate\n{:}", "lockfile ".repeat(200)),
+            datetime: Utc.with_ymd_and_hms(2025, 1, 14, 14, 30, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        };
+        let jsonl_path = temp_dir.path().join("commits.jsonl");
+        let settings = SaveSettings {
+            sinks: vec![
+                SinkKind::ObsidianMarkdown,
+                SinkKind::Jsonl(jsonl_path.clone()),
+            ],
+            max_row_length: Some(200),
+            ..SaveSettings::default()
+        };
+        let save = || {
+            save_entry(
+                &mut CommitSaver::from(entry.clone()),
+                vault_dir.path(),
+                Path::new("Commits"),
+                "%F.md",
+                &settings,
+            )
+        };
 
-    Ok(())
-}
+        assert_eq!(save()?[0].status, SaveStatus::Logged);
+        assert_eq!(save()?[0].status, SaveStatus::AlreadyLogged);
 
-#[cfg_attr(coverage_nightly, coverage(off))]
-fn main() {
-    // LCOV_EXCL_START
-    env_logger::init();
-    info!("[main()]: Instanciating GlobalVars Struct.");
-    let global_vars = GlobalVars::new();
-    global_vars.set_all();
+        let written = fs::read_to_string(&jsonl_path)?
+            .lines()
+            .map(serde_json::from_str::<CommitEntry>)
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(written, vec![entry]);
+        assert!(
+            fs::read_to_string(vault_dir.path().join("Commits/2025-01-14.md"))?
+                .contains("\\|more]]")
+        );
+        assert_eq!(fs::read_dir(temp_dir.path())?.count(), 1);
+        Ok(())
+    }
 
-    info!("[main()]: Retrieving values from GlobalVars Struct.");
-    let obsidian_root_path_dir = global_vars.get_obsidian_root_path_dir();
-    let obsidian_commit_path = global_vars.get_obsidian_commit_path();
-    let template_commit_date_path = global_vars.get_template_commit_date_path();
+    #[test]
+    fn test_save_entry_writes_nothing_when_a_sink_cannot_take_the_entry(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let temp_dir = tempdir()?;
+        let jsonl_path = temp_dir.path().join("commits.jsonl");
+        let settings = SaveSettings {
+            sinks: vec![
+                SinkKind::ObsidianMarkdown,
+                SinkKind::Jsonl(jsonl_path.clone()),
+                SinkKind::Jsonl(temp_dir.path().to_path_buf()),
+            ],
+            ..SaveSettings::default()
+        };
+        let mut commit_saver = CommitSaver::from(CommitEntry {
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: "abc123".to_string(),
+            message: "feat: add feature".to_string(),
+            datetime: Utc.with_ymd_and_hms(2025, 1, 14, 14, 30, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        });
 
-    match run_commit_saver(
-        obsidian_root_path_dir,
-        &obsidian_commit_path,
-        &template_commit_date_path,
-    ) {
-        Ok(()) => (),
-        Err(e) => {
-            error!("[main]: {e:}");
-            panic!("[main]: Something went wrong when writing the commit to the file");
-        }
+        assert!(save_entry(
+            &mut commit_saver,
+            vault_dir.path(),
+            Path::new("Commits"),
+            "%F.md",
+            &settings,
+        )
+        .is_err());
+        assert!(!vault_dir.path().join("Commits/2025-01-14.md").exists());
+        assert_eq!(fs::read_to_string(&jsonl_path)?, "");
+        Ok(())
     }
-    // LCOV_EXCL_STOP
-}
 
-#[cfg(test)]
-#[cfg_attr(coverage_nightly, coverage(off))]
-mod main_tests {
-    use super::*;
-    use chrono::{TimeZone, Utc};
-    use git2::Repository;
-    use rusty_commit_saver::vim_commit::check_diary_path_exists;
-    use rusty_commit_saver::vim_commit::create_diary_file;
-    use rusty_commit_saver::vim_commit::create_directories_for_new_entry;
-    use rusty_commit_saver::vim_commit::get_parent_from_full_path;
-    use std::fs;
-    use std::fs::File;
-    use std::path::PathBuf;
-    use tempfile::tempdir;
+    #[test]
+    fn test_saved_diary_uri_opens_the_last_diary_in_its_vault() {
+        let commit_saver = CommitSaver::from(CommitEntry {
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: "abc123".to_string(),
+            message: "work".to_string(),
+            datetime: Utc.with_ymd_and_hms(2025, 1, 14, 14, 30, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        });
+        let report = |status, diary_path: &str| {
+            SaveReport::new(status, &commit_saver, Some(PathBuf::from(diary_path)))
+        };
+        let root = Path::new("/home/me/Notes");
+        let settings = SaveSettings {
+            vaults: vec![VaultTarget::new("work", PathBuf::from("/home/me/Work"))],
+            ..SaveSettings::default()
+        };
+
+        assert_eq!(
+            saved_diary_uri(
+                &[
+                    report(SaveStatus::Logged, "/home/me/Notes/Commits/2025-01-14.md"),
+                    report(
+                        SaveStatus::AlreadyLogged,
+                        "/home/me/Work/Commits/2025-01-14.md"
+                    ),
+                    report(SaveStatus::DryRun, "/home/me/Notes/Commits/2025-01-15.md"),
+                ],
+                root,
+                &settings
+            )
+            .as_deref(),
+            Some("obsidian://open?vault=Work&file=Commits%2F2025-01-14.md")
+        );
+        let named = SaveSettings {
+            vault_name: Some("Journal".to_string()),
+            ..settings
+        };
+        assert_eq!(
+            saved_diary_uri(
+                &[report(
+                    SaveStatus::Logged,
+                    "/home/me/Notes/Commits/2025-01-14.md"
+                )],
+                root,
+                &named
+            )
+            .as_deref(),
+            Some("obsidian://open?vault=Journal&file=Commits%2F2025-01-14.md")
+        );
+        assert_eq!(
+            saved_diary_uri(&[report(SaveStatus::Skipped, "/tmp/x.md")], root, &named),
+            None
+        );
+    }
 
     #[test]
-    fn test_get_parent_from_full_path() -> Result<(), Box<dyn std::error::Error>> {
-        let temp_dir = tempdir()?;
-        let file_path = temp_dir.path().join("subdir").join("file.txt");
+    fn test_save_commit_entry_links_the_diary_from_the_daily_note(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let settings = SaveSettings {
+            daily_note: Some(DailyNote {
+                note_template: DEFAULT_DAILY_NOTE_TEMPLATE.to_string(),
+                style: DailyNoteLinkStyle::Embed,
+                create: true,
+            }),
+            ..SaveSettings::default()
+        };
+        for hash in ["abc123", "def456"] {
+            let mut commit_saver = CommitSaver::from(CommitEntry {
+                repository_url: "https://github.com/test/repo.git".to_string(),
+                branch: "main".to_string(),
+                hash: hash.to_string(),
+                message: "work".to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, 14, 14, 30, 0).unwrap(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            });
+            save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                "%F.md",
+                &settings,
+            )?;
+        }
 
-        let parent = get_parent_from_full_path(&file_path)?;
+        assert_eq!(
+            fs::read_to_string(vault_dir.path().join("2025-01-14.md"))?,
+            "![[Commits/2025-01-14]]\n"
+        );
+        Ok(())
+    }
 
-        assert_eq!(parent, temp_dir.path().join("subdir"));
+    #[test]
+    fn test_save_commit_entry_flat_layout_creates_no_intermediate_directories(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let flat = SaveSettings {
+            flat_layout: true,
+            ..SaveSettings::default()
+        };
+        for (date_template, settings) in [
+            ("%F.md", &SaveSettings::default()),
+            ("%Y/%m-%B/%F.md", &flat),
+        ] {
+            let vault_dir = tempdir()?;
+            let mut commit_saver = CommitSaver::from(CommitEntry {
+                repository_url: "https://github.com/test/repo.git".to_string(),
+                branch: "main".to_string(),
+                hash: "abc123".to_string(),
+                message: "work".to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            });
+
+            let report = save_commit_entry(
+                &mut commit_saver,
+                vault_dir.path().to_path_buf(),
+                Path::new("Commits"),
+                date_template,
+                settings,
+            )?;
+
+            let commits_dir = vault_dir.path().join("Commits");
+            assert_eq!(
+                report.diary_path,
+                Some(commits_dir.join("2025-01-14.md")),
+                "{date_template:}"
+            );
+            let entries = fs::read_dir(&commits_dir)?
+                .map(|entry| entry.map(|entry| entry.file_name()))
+                .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(entries, ["2025-01-14.md"], "{date_template:}");
+        }
         Ok(())
     }
 
     #[test]
-    fn test_get_parent_from_full_path_root() {
-        let root_path = PathBuf::from("/");
+    fn test_save_commit_entry_quarantines_malformed_diaries(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let diary = vault_dir.path().join("Commits/2025-01-14.md");
+        fs::create_dir_all(diary.parent().unwrap())?;
+        fs::write(&diary, [0xff, 0xfe, 0x00, 0x01])?;
+        let mut commit_saver = CommitSaver::from(CommitEntry {
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: "abc123".to_string(),
+            message: "work".to_string(),
+            datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        });
 
-        let result = get_parent_from_full_path(&root_path);
+        save_commit_entry(
+            &mut commit_saver,
+            vault_dir.path().to_path_buf(),
+            Path::new("Commits"),
+            "%F.md",
+            &SaveSettings::default(),
+        )?;
 
-        assert!(result.is_err());
+        let content = fs::read_to_string(&diary)?;
+        assert!(content.starts_with("---\ncategory: diary"));
+        assert!(content.contains("| abc123 |"));
+        let backups = fs::read_dir(diary.parent().unwrap())?
+            .filter_map(Result::ok)
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|name| name.starts_with("2025-01-14.md.bak-"))
+            .collect::<Vec<_>>();
+        assert_eq!(backups.len(), 1);
+        Ok(())
     }
 
     #[test]
-    fn test_check_diary_path_exists_true() -> Result<(), Box<dyn std::error::Error>> {
-        let temp_dir = tempdir()?;
-        let file_path = temp_dir.path().join("test_file.md");
-        File::create(&file_path)?;
+    fn test_run_stats_counts_the_routed_diaries_and_writes_the_note(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let routed = vault_dir.path().join("Work/2025-01-14.md");
+        fs::create_dir_all(routed.parent().unwrap())?;
+        fs::write(
+            &routed,
+            "---\ndate: 2025-01-14\n---\n| TIME | REPOSITORY URL | COMMIT HASH |\n|---|---|---|\n| 10:00:00 | git@github.com:acme/api.git | abc123 |\n",
+        )?;
+        let settings = SaveSettings {
+            routes: vec![parse_route("github.com/acme/* -> Work").unwrap()],
+            ..SaveSettings::default()
+        };
+        let now = Utc.with_ymd_and_hms(2025, 1, 16, 18, 0, 0).unwrap();
 
-        let result = check_diary_path_exists(&file_path);
+        let output = run_stats(
+            &StatsArgs {
+                note: Some(PathBuf::from("Rollups/Commit Stats.md")),
+                ..StatsArgs::default()
+            },
+            now,
+            vault_dir.path(),
+            Path::new("Commits"),
+            &settings,
+        )?;
+        assert!(output.contains("1 commits over 1 days."));
+        assert!(output.contains("| api | 1 |"));
+        assert!(
+            fs::read_to_string(vault_dir.path().join("Rollups/Commit Stats.md"))?
+                .ends_with(&output)
+        );
 
-        assert!(result.is_ok());
+        let compare = |before: &str, after: &str| {
+            run_stats(
+                &StatsArgs {
+                    compare: vec![before.to_string(), after.to_string()],
+                    ..StatsArgs::default()
+                },
+                now,
+                vault_dir.path(),
+                Path::new("Commits"),
+                &settings,
+            )
+        };
+        let output = compare("last-week", "this-week")?;
+        assert!(output.starts_with("# Commit Stats: last-week vs this-week\n"));
+        assert!(output.contains("| api | 0 | 1 | +1 |"));
+        assert!(compare("last-week", "someday").is_err());
+
+        let settings = SaveSettings {
+            weekly_goal: Some(WeeklyGoal {
+                commits: 20,
+                note_template: DEFAULT_WEEKLY_NOTE_TEMPLATE.to_string(),
+            }),
+            ..settings
+        };
+        let stats = |args: &StatsArgs| {
+            run_stats(args, now, vault_dir.path(), Path::new("Commits"), &settings)
+        };
+        assert!(
+            stats(&StatsArgs::default())?.ends_with("\nWeekly goal (2025-W03): 1/20 commits.\n")
+        );
+        let porcelain = stats(&StatsArgs {
+            porcelain: true,
+            ..StatsArgs::default()
+        })?;
+        assert!(porcelain.starts_with("total\t1\t1\t1\t2025-01-14\t2025-01-14\n"));
+        assert!(porcelain.contains("repository\tapi\t1\n"));
+        assert!(porcelain.ends_with("goal\t2025-W03\t1\t20\n"));
+
+        let other_repository = stats(&StatsArgs {
+            repo: Some("web".to_string()),
+            porcelain: true,
+            ..StatsArgs::default()
+        })?;
+        assert!(other_repository.starts_with("total\t0\t0\t"));
+        assert!(other_repository.ends_with("goal\t2025-W03\t1\t20\n"));
         Ok(())
     }
 
     #[test]
-    fn test_check_diary_path_exists_false() {
-        let non_existent_path = PathBuf::from("/non/existent/path");
+    fn test_run_heatmap_writes_the_configured_note() -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let diary = vault_dir.path().join("Commits/2025-01-14.md");
+        fs::create_dir_all(diary.parent().unwrap())?;
+        fs::write(
+            &diary,
+            "---\ndate: 2025-01-14\n---\n| TIME | REPOSITORY URL | COMMIT HASH |\n|---|---|---|\n| 10:00:00 | git@github.com:acme/api.git | abc123 |\n",
+        )?;
+        let settings = SaveSettings {
+            heatmap: HeatmapSettings {
+                note: Some(PathBuf::from("Rollups/Commit Heatmap.md")),
+                format: HeatmapFormat::Text,
+            },
+            ..SaveSettings::default()
+        };
+        let now = Utc.with_ymd_and_hms(2025, 1, 16, 18, 0, 0).unwrap();
+        let heatmap = |args: &HeatmapArgs| {
+            run_heatmap(args, now, vault_dir.path(), Path::new("Commits"), &settings)
+        };
 
-        let result = check_diary_path_exists(&non_existent_path);
+        let output = heatmap(&HeatmapArgs::default())?;
+        assert!(output.contains("1 commits over 1 days."));
+        assert!(output.contains("```text\n"));
+        assert!(
+            fs::read_to_string(vault_dir.path().join("Rollups/Commit Heatmap.md"))?
+                .ends_with(&output)
+        );
 
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err().to_string(), "Path does not exist!");
+        let output = heatmap(&HeatmapArgs {
+            year: Some(2024),
+            note: Some(PathBuf::from("Heatmap 2024.md")),
+            format: Some(HeatmapFormat::Html),
+        })?;
+        assert!(output.starts_with("# Commit Heatmap 2024\n\nNo commits in 2024.\n\n<table>"));
+        assert!(vault_dir.path().join("Heatmap 2024.md").exists());
+        Ok(())
     }
 
     #[test]
-    fn test_create_diary_file() -> Result<(), Box<dyn std::error::Error>> {
-        let temp_dir = tempdir()?;
-        let file_path = temp_dir.path().join("diary.md");
-        let mut commit_saver = CommitSaver {
-            repository_url: "https://github.com/test/repo.git".to_string(),
-            commit_branch_name: "main".to_string(),
-            commit_hash: "abc123".to_string(),
-            commit_msg: "Test".to_string(),
-            commit_datetime: Utc.with_ymd_and_hms(2023, 12, 25, 10, 30, 0).unwrap(),
+    fn test_run_report_measures_the_routed_diaries() -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let routed = vault_dir.path().join("Work/2025-01-14.md");
+        fs::create_dir_all(routed.parent().unwrap())?;
+        fs::write(
+            &routed,
+            "---\ndate: 2025-01-14\n---\n| TIME |\n|---|\n| 10:00:00 |\n",
+        )?;
+        let settings = SaveSettings {
+            routes: vec![parse_route("github.com/acme/* -> Work").unwrap()],
+            ..SaveSettings::default()
         };
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
 
-        let result = create_diary_file(file_path.to_str().unwrap(), &mut commit_saver);
+        let output = run_report(
+            &ReportArgs { vault: true },
+            today,
+            vault_dir.path(),
+            Path::new("Commits"),
+            &settings,
+        )?;
+        assert!(output.starts_with("Commit diary: 1 files, "));
+        assert!(output.contains("Work/2025-01-14.md"));
 
-        assert!(result.is_ok());
-        assert!(file_path.exists());
+        assert!(run_report(
+            &ReportArgs::default(),
+            today,
+            vault_dir.path(),
+            Path::new("Commits"),
+            &settings
+        )
+        .is_err());
+        Ok(())
+    }
 
-        let content = fs::read_to_string(&file_path)?;
-        assert!(content.contains("category: diary"));
-        assert!(content.contains("section: commits"));
-        assert!(content.contains("2023-12-25"));
-        assert!(content.contains("| FOLDER | TIME | COMMIT MESSAGE"));
+    #[test]
+    fn test_run_today_prints_the_day_table() -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let mut commit_saver = CommitSaver::from(CommitEntry {
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: "abc123def456".to_string(),
+            message: "feat: today view".to_string(),
+            datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        });
+        let settings = SaveSettings::default();
+        save_commit_entry(
+            &mut commit_saver,
+            vault_dir.path().to_path_buf(),
+            Path::new("Commits"),
+            "%F.md",
+            &settings,
+        )?;
 
+        let day = |day| NaiveDate::from_ymd_opt(2025, 1, day).unwrap();
+        let output = run_today(
+            day(14),
+            None,
+            vault_dir.path(),
+            Path::new("Commits"),
+            "%F.md",
+            &settings,
+        )?;
+        assert!(output.starts_with("TIME "));
+        assert!(output.contains("10:00:00  feat: today view  repo"));
+
+        let output = run_today(
+            day(15),
+            None,
+            vault_dir.path(),
+            Path::new("Commits"),
+            "%F.md",
+            &settings,
+        )?;
+        assert_eq!(output, "No diary entries for 2025-01-15.\n");
         Ok(())
     }
 
     #[test]
-    fn test_create_directories_for_new_entry() -> Result<(), Box<dyn std::error::Error>> {
-        let temp_dir = tempdir()?;
-        let nested_path = temp_dir.path().join("deep").join("nested").join("file.md");
+    fn test_run_watched_saver_queues_the_commits_that_landed(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let repo_dir = tempdir()?;
+        let vault_dir = tempdir()?;
+        let git_repo = Repository::init(repo_dir.path())?;
+        let tree = git_repo.find_tree(git_repo.index()?.write_tree()?)?;
+        let signature = git2::Signature::new(
+            "Test",
+            "test@example.com",
+            &git2::Time::new(1_736_848_800, 0),
+        )?;
+        let first = git_repo.commit(Some("HEAD"), &signature, &signature, "first", &tree, &[])?;
+        let mut watched = WatchedRepository::new(&git_repo, repo_dir.path())?;
 
-        let result = create_directories_for_new_entry(&nested_path);
+        let parent = git_repo.find_commit(first)?;
+        let second = git_repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "second",
+            &tree,
+            &[&parent],
+        )?;
 
-        assert!(result.is_ok());
-        assert!(temp_dir.path().join("deep").join("nested").exists());
+        let start = Instant::now();
+        let mut queue = AppendQueue::new(Duration::from_millis(500));
+        let mut run = |queue: &mut AppendQueue<CommitSaver>| {
+            run_watched_saver(
+                &mut watched,
+                &git_repo,
+                queue,
+                Path::new("Commits"),
+                "log.md",
+                start,
+            )
+        };
+        assert_eq!(run(&mut queue)?, 1);
+        assert_eq!(run(&mut queue)?, 0);
+        let mut save = |now: Instant| {
+            save_queued_entries(
+                &mut queue,
+                now,
+                vault_dir.path(),
+                Path::new("Commits"),
+                "log.md",
+                &SaveSettings::default(),
+            )
+        };
+        assert!(save(start + Duration::from_millis(200)).is_empty());
+        assert!(!vault_dir.path().join("Commits/log.md").exists());
 
+        let saved = save(start + Duration::from_millis(500));
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].0, PathBuf::from("/Commits/log.md"));
+        let reports = saved[0].1.as_ref().map_err(ToString::to_string)?;
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].commit_hash, second.to_string());
+
+        let diary = fs::read_to_string(vault_dir.path().join("Commits/log.md"))?;
+        let workdir: PathBuf = repo_dir.path().components().collect();
+        assert!(diary.contains(&format!("| {:} |", workdir.display())));
         Ok(())
     }
 
     #[test]
-    fn test_create_directories_for_new_entry_invalid_path() {
-        // Test with a path that can't be created (e.g., on a read-only filesystem)
-        let invalid_path = PathBuf::from("/proc/invalid/path/file.md");
+    fn test_run_recent_saver_logs_commits_since_the_last_logged_one(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let repo_dir = tempdir()?;
+        let vault_dir = tempdir()?;
+        let state_dir = tempdir()?;
+        let git_repo = Repository::init(repo_dir.path())?;
+        git_repo
+            .config()?
+            .set_str("user.email", "test@example.com")?;
+        let tree = git_repo.find_tree(git_repo.index()?.write_tree()?)?;
+        let now = Utc.with_ymd_and_hms(2025, 1, 14, 18, 0, 0).unwrap();
+        let signature = |hours_ago: i64| {
+            git2::Signature::new(
+                "Test",
+                "test@example.com",
+                &git2::Time::new(now.timestamp() - hours_ago * 3_600, 0),
+            )
+        };
+        let first = git_repo.commit(
+            Some("HEAD"),
+            &signature(3)?,
+            &signature(3)?,
+            "first",
+            &tree,
+            &[],
+        )?;
+        let parent = git_repo.find_commit(first)?;
+        let second = git_repo.commit(
+            Some("HEAD"),
+            &signature(1)?,
+            &signature(1)?,
+            "second",
+            &tree,
+            &[&parent],
+        )?;
+        let settings = SaveSettings {
+            state_dir: Some(state_dir.path().to_path_buf()),
+            ..SaveSettings::default()
+        };
+        run_batch_saver(
+            &git_repo,
+            &first.to_string(),
+            vault_dir.path(),
+            Path::new("Commits"),
+            "log.md",
+            &settings,
+        )?;
 
-        let result = create_directories_for_new_entry(&invalid_path);
+        let run = || {
+            run_recent_saver(
+                &git_repo,
+                now,
+                TimeDelta::days(7),
+                vault_dir.path(),
+                Path::new("Commits"),
+                "log.md",
+                &settings,
+            )
+        };
+        let reports = run()?;
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].commit_hash, second.to_string());
+        assert_eq!(reports[0].status, SaveStatus::Logged);
+        assert!(run()?.is_empty());
 
-        // This should fail on most systems as /proc is not writable
-        assert!(result.is_err());
+        let without_state = SaveSettings::default();
+        assert!(run_recent_saver(
+            &git_repo,
+            now,
+            TimeDelta::days(7),
+            vault_dir.path(),
+            Path::new("Commits"),
+            "log.md",
+            &without_state,
+        )
+        .is_err());
+        Ok(())
     }
 
     #[test]
-    fn test_run_commit_saver_creates_new_diary() -> Result<(), Box<dyn std::error::Error>> {
-        let temp_dir = tempdir()?;
-        let obsidian_root = temp_dir.path().to_path_buf();
-        let commit_path = PathBuf::from("Diaries/Commits");
-        let date_template = "%Y/%m-%B/%F.md";
+    fn test_run_batch_saver_logs_listed_commits_once() -> Result<(), Box<dyn std::error::Error>> {
+        let repo_dir = tempdir()?;
+        let vault_dir = tempdir()?;
+        let state_dir = tempdir()?;
+        let git_repo = Repository::init(repo_dir.path())?;
+        let tree = git_repo.find_tree(git_repo.index()?.write_tree()?)?;
+        let morning = git2::Signature::new(
+            "Test",
+            "test@example.com",
+            &git2::Time::new(1_736_848_800, 0),
+        )?;
+        let noon = git2::Signature::new(
+            "Test",
+            "test@example.com",
+            &git2::Time::new(1_736_856_000, 0),
+        )?;
+        let first = git_repo.commit(Some("HEAD"), &morning, &morning, "first", &tree, &[])?;
+        let parent = git_repo.find_commit(first)?;
+        let second = git_repo.commit(Some("HEAD"), &noon, &noon, "second", &tree, &[&parent])?;
+        let settings = SaveSettings {
+            state_dir: Some(state_dir.path().to_path_buf()),
+            ..SaveSettings::default()
+        };
+        let commit_list = format!("{second:}\n{first:}\n");
 
-        // This assumes we're in a git repo for CommitSaver::new() to work
-        if Repository::discover("./").is_ok() {
-            let result = run_commit_saver(obsidian_root.clone(), &commit_path, date_template);
+        let logged = run_batch_saver(
+            &git_repo,
+            &commit_list,
+            vault_dir.path(),
+            Path::new("Commits"),
+            "log.md",
+            &settings,
+        )?;
+        let logged_again = run_batch_saver(
+            &git_repo,
+            &commit_list,
+            vault_dir.path(),
+            Path::new("Commits"),
+            "log.md",
+            &settings,
+        )?;
 
-            // Should succeed and create diary file
-            assert!(result.is_ok());
+        let statuses = |reports: &[SaveReport]| {
+            reports
+                .iter()
+                .map(|report| report.status)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(statuses(&logged), vec![SaveStatus::Logged; 2]);
+        assert_eq!(statuses(&logged_again), vec![SaveStatus::AlreadyLogged; 2]);
+        assert_eq!(logged[0].commit_hash, first.to_string());
+        assert_eq!(
+            logged[1].diary_path,
+            Some(vault_dir.path().join("Commits/log.md"))
+        );
+        let content = fs::read_to_string(vault_dir.path().join("Commits/log.md"))?;
+        let first_row = content.find(&first.to_string()).unwrap();
+        let second_row = content.find(&second.to_string()).unwrap();
+        assert!(first_row < second_row);
+        assert!(run_batch_saver(
+            &git_repo,
+            "not-a-commit\n",
+            vault_dir.path(),
+            Path::new("Commits"),
+            "log.md",
+            &settings,
+        )
+        .is_err());
+        Ok(())
+    }
 
-            // Verify directory structure was created
-            assert!(obsidian_root.exists());
-        }
+    #[test]
+    fn test_run_received_saver_logs_pushed_branches() -> Result<(), Box<dyn std::error::Error>> {
+        let repo_dir = tempdir()?;
+        let vault_dir = tempdir()?;
+        let git_repo = Repository::init_bare(repo_dir.path())?;
+        let tree = git_repo.find_tree(git_repo.index()?.write_tree()?)?;
+        let commit = |update_ref: &str, seconds, message: &str, parents: &[&git2::Commit]| {
+            let signature =
+                git2::Signature::new("Test", "test@example.com", &git2::Time::new(seconds, 0))?;
+            git_repo.commit(
+                Some(update_ref),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                parents,
+            )
+        };
+        let first = commit("refs/heads/main", 1_736_848_800, "first", &[])?;
+        let first_commit = git_repo.find_commit(first)?;
+        let second = commit("refs/heads/main", 1_736_856_000, "second", &[&first_commit])?;
+        let third = commit(
+            "refs/heads/feature/login",
+            1_736_859_600,
+            "third",
+            &[&first_commit],
+        )?;
+        let post_receive_input = format!(
+            "{first:} {second:} refs/heads/main\n{ZERO_HASH:} {third:} refs/heads/feature/login\n{ZERO_HASH:} {first:} refs/tags/v1.0\n"
+        );
+
+        let reports = run_received_saver(
+            &git_repo,
+            &post_receive_input,
+            vault_dir.path(),
+            Path::new("Commits"),
+            "log.md",
+            &SaveSettings::default(),
+        )?;
 
+        let logged = reports
+            .iter()
+            .map(|report| (report.commit_hash.clone(), report.branch.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            logged,
+            vec![
+                (second.to_string(), "main"),
+                (third.to_string(), "feature/login")
+            ]
+        );
+        let content = fs::read_to_string(vault_dir.path().join("Commits/log.md"))?;
+        assert!(content.contains(&format!("| feature/login | {third:} |")));
         Ok(())
     }
 
     #[test]
-    fn test_run_commit_saver_missing_directory_creates_it() -> Result<(), Box<dyn std::error::Error>>
-    {
-        use git2::Repository;
+    fn test_run_received_saver_routes_branches_to_their_vaults(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let repo_dir = tempdir()?;
+        let vault_dir = tempdir()?;
+        let feature_vault_dir = tempdir()?;
+        let git_repo = Repository::init_bare(repo_dir.path())?;
+        let tree = git_repo.find_tree(git_repo.index()?.write_tree()?)?;
+        let signature = git2::Signature::new(
+            "Test",
+            "test@example.com",
+            &git2::Time::new(1_736_848_800, 0),
+        )?;
+        let commit = |update_ref: &str, message: &str, parents: &[&git2::Commit]| {
+            git_repo.commit(
+                Some(update_ref),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                parents,
+            )
+        };
+        let first = commit("refs/heads/main", "first", &[])?;
+        let first_commit = git_repo.find_commit(first)?;
+        let second = commit("refs/heads/main", "second", &[&first_commit])?;
+        let third = commit("refs/heads/feature/login", "third", &[&first_commit])?;
+        let settings = SaveSettings {
+            vaults: vec![VaultTarget {
+                branch: Some("feature/*".to_string()),
+                ..VaultTarget::new("features", feature_vault_dir.path().to_path_buf())
+            }],
+            ..SaveSettings::default()
+        };
 
-        let temp_dir = tempdir()?;
-        let obsidian_root = temp_dir.path().join("non_existent_path");
-        let commit_path = PathBuf::from("Diaries/Commits");
-        let date_template = "%Y/%m-%B/%F.md";
+        let reports = run_received_saver(
+            &git_repo,
+            &format!(
+                "{first:} {second:} refs/heads/main\n{first:} {third:} refs/heads/feature/login\n"
+            ),
+            vault_dir.path(),
+            Path::new("Commits"),
+            "log.md",
+            &settings,
+        )?;
 
-        // Only run if we're in a git repo
-        if Repository::discover("./").is_ok() {
-            let result = run_commit_saver(obsidian_root.clone(), &commit_path, date_template);
+        assert_eq!(
+            reports
+                .iter()
+                .map(|report| report.diary_path.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                Some(vault_dir.path().join("Commits/log.md")),
+                Some(feature_vault_dir.path().join("Commits/log.md")),
+            ]
+        );
+        assert!(
+            !fs::read_to_string(vault_dir.path().join("Commits/log.md"))?
+                .contains(&third.to_string())
+        );
+        Ok(())
+    }
 
-            // Should succeed and create the missing directories
-            assert!(result.is_ok());
-        }
+    #[test]
+    fn test_save_to_vaults_spools_the_rows_of_a_vault_it_cannot_write(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let repo = FakeRepo::new()?;
+        let state_dir = tempdir()?;
+        let work_vault = tempdir()?;
+        let backup_vault = tempdir()?;
+        // A file where the commits directory should be makes the backup vault fail.
+        fs::write(backup_vault.path().join("Commits"), "not a directory")?;
+        let vault = |name: &str, root: &Path| VaultTarget {
+            path_prefix: Some(repo.path().to_path_buf()),
+            ..VaultTarget::new(name, root.to_path_buf())
+        };
+        let settings = SaveSettings {
+            vaults: vec![
+                vault("work", work_vault.path()),
+                vault("backup", backup_vault.path()),
+            ],
+            state_dir: Some(state_dir.path().to_path_buf()),
+            ..SaveSettings::default()
+        };
+        let save = |commit_saver: &mut CommitSaver| {
+            save_to_vaults(
+                commit_saver,
+                PathBuf::from("/no/default/vault"),
+                Path::new("Commits"),
+                "log.md",
+                &settings,
+            )
+        };
+
+        let first = repo.commit("first")?;
+        assert_eq!(save(&mut repo.commit_saver()?)?.len(), 1);
+        assert_eq!(Spool::rows(state_dir.path()).pending()?.len(), 1);
 
+        fs::remove_file(backup_vault.path().join("Commits"))?;
+        let second = repo.commit("second")?;
+        assert_eq!(save(&mut repo.commit_saver()?)?.len(), 2);
+
+        let backup = fs::read_to_string(backup_vault.path().join("Commits/log.md"))?;
+        assert!(backup.contains(&first.to_string()));
+        assert!(backup.contains(&second.to_string()));
+        assert!(Spool::rows(state_dir.path()).pending()?.is_empty());
         Ok(())
     }
 
     #[test]
-    fn test_run_commit_saver_append_to_existing_diary() -> Result<(), Box<dyn std::error::Error>> {
-        use git2::Repository;
+    fn test_run_batch_saver_merges_concurrent_backfills_chronologically(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let repo_dirs = [tempdir()?, tempdir()?];
+        let hashes = [vec![9, 11, 13], vec![10, 12, 14]]
+            .into_iter()
+            .zip(&repo_dirs)
+            .map(|(hours, repo_dir)| {
+                let git_repo = Repository::init(repo_dir.path())?;
+                let tree = git_repo.find_tree(git_repo.index()?.write_tree()?)?;
+                let mut parents = Vec::new();
+                let mut hashes = Vec::new();
+                for hour in hours {
+                    let when = git2::Time::new(1_736_812_800 + hour * 3600, 0);
+                    let signature = git2::Signature::new("Test", "test@example.com", &when)?;
+                    let parent_refs = parents.iter().collect::<Vec<_>>();
+                    let oid = git_repo.commit(
+                        Some("HEAD"),
+                        &signature,
+                        &signature,
+                        &format!("commit at {hour:}"),
+                        &tree,
+                        &parent_refs,
+                    )?;
+                    parents = vec![git_repo.find_commit(oid)?];
+                    hashes.push(oid.to_string());
+                }
+                Ok(hashes)
+            })
+            .collect::<Result<Vec<_>, git2::Error>>()?;
+
+        let start = std::sync::Barrier::new(repo_dirs.len());
+        std::thread::scope(|scope| {
+            for (repo_dir, hashes) in repo_dirs.iter().zip(&hashes) {
+                let (start, vault) = (&start, vault_dir.path());
+                scope.spawn(move || {
+                    let git_repo = Repository::open(repo_dir.path()).unwrap();
+                    start.wait();
+                    for hash in hashes.iter().rev() {
+                        run_batch_saver(
+                            &git_repo,
+                            hash,
+                            vault,
+                            Path::new("Commits"),
+                            "log.md",
+                            &SaveSettings::default(),
+                        )
+                        .unwrap();
+                    }
+                });
+            }
+        });
+
+        let content = fs::read_to_string(vault_dir.path().join("Commits/log.md"))?;
+        let table = parse_diary_table(&content).unwrap();
+        let times = table
+            .rows
+            .iter()
+            .filter_map(|row| table.cell(row, TableColumn::Time))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            times,
+            vec!["09:00:00", "10:00:00", "11:00:00", "12:00:00", "13:00:00", "14:00:00"]
+        );
+        assert_eq!(content.matches("| TIME |").count(), 1);
+        Ok(())
+    }
 
+    #[test]
+    fn test_apply_build_status_reads_the_status_file() -> Result<(), Box<dyn std::error::Error>> {
         let temp_dir = tempdir()?;
-        let obsidian_root = temp_dir.path().to_path_buf();
-        let commit_path = PathBuf::from("Diaries/Commits");
-        let date_template = "%Y/%m-%B/%F.md";
+        let status_file = temp_dir.path().join(".build-status");
+        fs::write(&status_file, "failed\n")?;
+        let mut settings = SaveSettings {
+            table_columns: vec![TableColumn::CommitHash, TableColumn::Status],
+            build_status: Some(BuildStatusCheck {
+                source: BuildStatusSource::File(status_file),
+                timeout: DEFAULT_BUILD_TIMEOUT,
+            }),
+            ..SaveSettings::default()
+        };
+        let mut commit_saver = CommitSaver::from(CommitEntry {
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: "abc123".to_string(),
+            message: "work".to_string(),
+            datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        });
 
-        // Only run if in a git repo
-        if Repository::discover("./").is_ok() {
-            // First run - creates the file
-            run_commit_saver(obsidian_root.clone(), &commit_path, date_template)?;
+        apply_build_status(&mut commit_saver, &settings);
+        assert_eq!(commit_saver.details.build_status, Some(BuildStatus::Fail));
 
-            // Second run - should append to existing file
-            let result = run_commit_saver(obsidian_root.clone(), &commit_path, date_template);
-            assert!(result.is_ok());
+        commit_saver.details.build_status = None;
+        settings.table_columns = DEFAULT_TABLE_COLUMNS.to_vec();
+        apply_build_status(&mut commit_saver, &settings);
+        assert_eq!(commit_saver.details.build_status, None);
+        Ok(())
+    }
 
-            // Verify file exists and has multiple entries
-            // (The exact path depends on current date, so we check the directory exists)
-            assert!(obsidian_root.join("Diaries").exists());
-        }
+    #[test]
+    fn test_save_commit_entry_records_tracked_activity() -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let tracking_file = vault_dir.path().join("current.txt");
+        fs::write(&tracking_file, "client-a\n")?;
+        let settings = SaveSettings {
+            table_columns: vec![TableColumn::CommitHash, TableColumn::Tracking],
+            time_tracking: Some(TimeTrackingSource::File(tracking_file)),
+            ..SaveSettings::default()
+        };
+        let mut commit_saver = CommitSaver::from(CommitEntry {
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: "abc123".to_string(),
+            message: "work".to_string(),
+            datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        });
+
+        save_commit_entry(
+            &mut commit_saver,
+            vault_dir.path().to_path_buf(),
+            Path::new("Commits"),
+            "%F.md",
+            &settings,
+        )?;
 
+        let content = fs::read_to_string(vault_dir.path().join("Commits/2025-01-14.md"))?;
+        assert!(content.contains("| COMMIT HASH | TRACKING |"));
+        assert!(content.contains("| abc123 | client-a |\n"));
         Ok(())
     }
 
     #[test]
-    fn test_run_commit_saver_handles_file_write_errors() {
-        use git2::Repository;
-        use std::fs;
-        use std::os::unix::fs::PermissionsExt;
+    fn test_save_commit_entry_refuses_evicted_icloud_diary(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let vault_dir = tempdir()?;
+        let commits_dir = vault_dir.path().join("Commits");
+        fs::create_dir_all(&commits_dir)?;
+        fs::write(commits_dir.join(".2025-01-14.md.icloud"), "bplist00")?;
+        let mut commit_saver = CommitSaver::from(CommitEntry {
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: "abc123".to_string(),
+            message: "work".to_string(),
+            datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        });
 
-        let temp_dir = tempdir().unwrap();
-        let obsidian_root = temp_dir.path().to_path_buf();
-        let commit_path = PathBuf::from("Diaries/Commits");
-        let date_template = "%Y/%m-%B/%F.md";
+        let result = save_commit_entry(
+            &mut commit_saver,
+            vault_dir.path().to_path_buf(),
+            Path::new("Commits"),
+            "%F.md",
+            &SaveSettings::default(),
+        );
 
-        // Only run if in a git repo
-        if Repository::discover("./").is_ok() {
-            // Create directory structure first
-            let result = run_commit_saver(obsidian_root.clone(), &commit_path, date_template);
-            assert!(result.is_ok());
+        assert!(result.is_err());
+        assert!(!commits_dir.join("2025-01-14.md").exists());
+        Ok(())
+    }
 
-            // Now make the directory read-only to trigger write errors on second run
-            let diary_dir = obsidian_root.join("Diaries");
-            if diary_dir.exists() {
-                let metadata = fs::metadata(&diary_dir).unwrap();
-                let mut perms = metadata.permissions();
-                perms.set_mode(0o444); // Read-only
-                fs::set_permissions(&diary_dir, perms).ok();
-            }
+    fn init_repo_on_feature_branch(path: &Path) -> Result<Repository, Box<dyn std::error::Error>> {
+        let repo = Repository::init(path)?;
+        {
+            let sig = git2::Signature::now("Test User", "test@example.com")?;
+            let tree_id = repo.index()?.write_tree()?;
+            let tree = repo.find_tree(tree_id)?;
+            let commit_id = repo.commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[])?;
+            let commit = repo.find_commit(commit_id)?;
+            repo.branch("feature", &commit, false)?;
+        }
+        repo.set_head("refs/heads/feature")?;
+        Ok(repo)
+    }
+
+    #[test]
+    fn test_run_event_saver_logs_checkout_with_event_column(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let repo_dir = tempdir()?;
+        let repo = init_repo_on_feature_branch(repo_dir.path())?;
+        let settings = SaveSettings {
+            table_columns: vec![TableColumn::CommitMessage, TableColumn::Event],
+            log_branch_switches: true,
+            ..SaveSettings::default()
+        };
+        let event = EventCommand::Checkout {
+            previous_head: None,
+            new_head: None,
+            checkout_type: Some("1".to_string()),
+        };
+
+        let vault_dir = tempdir()?;
+        run_event_saver(
+            &repo,
+            &event,
+            vault_dir.path(),
+            Path::new("Commits"),
+            "events.md",
+            &settings,
+        )?;
+
+        let content = fs::read_to_string(vault_dir.path().join("Commits/events.md"))?;
+        assert!(content.contains("| COMMIT MESSAGE | EVENT |\n|----------------|-------|\n"));
+        assert!(content.contains(" to feature | checkout |\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_event_saver_skips_disabled_and_file_checkouts(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let repo_dir = tempdir()?;
+        let repo = init_repo_on_feature_branch(repo_dir.path())?;
+        let vault_dir = tempdir()?;
+
+        let checkout = |checkout_type: &str| EventCommand::Checkout {
+            previous_head: None,
+            new_head: None,
+            checkout_type: Some(checkout_type.to_string()),
+        };
+        let enabled = SaveSettings {
+            log_branch_switches: true,
+            log_stash: true,
+            ..SaveSettings::default()
+        };
+
+        for (event, settings) in [
+            (checkout("1"), SaveSettings::default()),
+            (
+                EventCommand::Stash {
+                    action: StashAction::Pop,
+                },
+                SaveSettings::default(),
+            ),
+            (checkout("0"), enabled),
+        ] {
+            run_event_saver(
+                &repo,
+                &event,
+                vault_dir.path(),
+                Path::new("Commits"),
+                "events.md",
+                &settings,
+            )?;
         }
+
+        assert!(!vault_dir.path().join("Commits").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_push_saver_logs_one_row_per_pushed_ref() -> Result<(), Box<dyn std::error::Error>> {
+        let repo_dir = tempdir()?;
+        let repo = init_repo_on_feature_branch(repo_dir.path())?;
+        let head = repo.head()?.peel_to_commit()?.id().to_string();
+        let zero = "0".repeat(40);
+        let push_input = format!(
+            "refs/heads/feature {head:} refs/heads/feature {zero:}\n(delete) {zero:} refs/heads/old {head:}\n"
+        );
+        let settings = SaveSettings {
+            table_columns: vec![TableColumn::CommitMessage, TableColumn::Event],
+            log_pushes: true,
+            ..SaveSettings::default()
+        };
+
+        let vault_dir = tempdir()?;
+        let reports = run_push_saver(
+            &repo,
+            "origin",
+            &push_input,
+            vault_dir.path(),
+            Path::new("Commits"),
+            "events.md",
+            &settings,
+        )?;
+
+        assert_eq!(reports.len(), 1);
+        let content = fs::read_to_string(vault_dir.path().join("Commits/events.md"))?;
+        assert!(content.contains(&format!(
+            "| 🚀 Pushed to origin: feature (new): 1 commit, up to {:} | push |\n",
+            &head[..7]
+        )));
+        Ok(())
     }
 
     #[test]
@@ -391,13 +6461,17 @@ mod main_tests {
         let temp_dir = tempdir().unwrap();
         let file_path = temp_dir.path().join("test.md");
 
-        let mut commit_saver = CommitSaver {
+        let mut commit_saver = CommitSaver::from(CommitEntry {
             repository_url: "test".to_string(),
-            commit_branch_name: "main".to_string(),
-            commit_hash: "abc123".to_string(),
-            commit_msg: "test".to_string(),
-            commit_datetime: Utc.with_ymd_and_hms(2023, 12, 25, 10, 30, 0).unwrap(),
-        };
+            branch: "main".to_string(),
+            hash: "abc123".to_string(),
+            message: "test".to_string(),
+            datetime: Utc.with_ymd_and_hms(2023, 12, 25, 10, 30, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        });
 
         // Test that create_diary_file handles edge cases
         let result = create_diary_file(file_path.to_str().unwrap(), &mut commit_saver);
@@ -429,21 +6503,25 @@ mod main_tests {
 
     #[test]
     fn test_run_commit_saver_idempotent() -> Result<(), Box<dyn std::error::Error>> {
-        use git2::Repository;
-
-        let temp_dir = tempdir()?;
-        let obsidian_root = temp_dir.path().to_path_buf();
-        let commit_path = PathBuf::from("Diaries/Commits");
-        let date_template = "%Y/%m-%B/%F.md";
+        let vault = TempVault::new()?;
+        let repo = FakeRepo::new()?;
+        let oid = repo.commit("Initial commit")?;
 
-        if Repository::discover("./").is_ok() {
-            // Run three times - should be idempotent
-            for _ in 0..3 {
-                let result = run_commit_saver(obsidian_root.clone(), &commit_path, date_template);
-                assert!(result.is_ok());
-            }
+        // Run three times - should be idempotent
+        for _ in 0..3 {
+            let result = save_fake_head(&repo, vault.root().to_path_buf(), "%Y/%m-%B/%F.md");
+            assert!(result.is_ok());
         }
 
+        let diary_files = vault.diary_files()?;
+        assert_eq!(diary_files.len(), 1);
+        assert_eq!(
+            fs::read_to_string(&diary_files[0])?
+                .matches(&oid.to_string())
+                .count(),
+            1
+        );
+
         Ok(())
     }
 
@@ -459,20 +6537,20 @@ mod main_tests {
 
     #[test]
     fn test_run_commit_saver_with_complex_path() -> Result<(), Box<dyn std::error::Error>> {
-        use git2::Repository;
-
         let temp_dir = tempdir()?;
         let complex_root = temp_dir.path().join("level1").join("level2").join("level3");
-        let commit_path = PathBuf::from("Deep/Nested/Commits");
-        let date_template = "%Y/%m-%B/%d/%F.md";
+        let repo = FakeRepo::new()?;
+        repo.commit_at(
+            "Initial commit",
+            Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+        )?;
 
-        if Repository::discover("./").is_ok() {
-            let result = run_commit_saver(complex_root.clone(), &commit_path, date_template);
-            assert!(result.is_ok());
+        save_fake_head(&repo, complex_root.clone(), "%Y/%m-%B/%d/%F.md")?;
 
-            // Verify deep directory structure was created
-            assert!(complex_root.exists() || temp_dir.path().join("level1").exists());
-        }
+        // Verify deep directory structure was created
+        assert!(complex_root
+            .join("Diaries/Commits/2025/01-January/14/2025-01-14.md")
+            .is_file());
 
         Ok(())
     }
@@ -0,0 +1,334 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::DateTime;
+use chrono::Utc;
+use log::info;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::atomic_write::write_atomically;
+use crate::config::SaveSettings;
+use crate::diary_lock::DiaryLock;
+use crate::sinks::SinkKind;
+use crate::vim_commit::EntryFormat;
+
+/// File name of the usage counters inside the state directory.
+const METRICS_FILE_NAME: &str = "metrics.json";
+
+/// How long a run waits for another one to finish updating the counters.
+///
+/// Counting is not worth delaying a commit hook: a run that cannot take the
+/// lock in time is not counted.
+const METRICS_LOCK_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Local usage counters, kept when `[metrics] enabled = true`.
+///
+/// Only counts are stored: runs and failures per subcommand, and how many
+/// runs had each optional feature enabled. No paths, repository names,
+/// commit messages or hashes are kept, and nothing is ever sent anywhere;
+/// `metrics show` prints them so they can be pasted into a bug report.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::metrics::UsageMetrics;
+///
+/// let mut metrics = UsageMetrics::default();
+/// metrics.count_run("save", true, &["block_refs"], chrono::Utc::now());
+/// print!("{}", metrics.render());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageMetrics {
+    /// When the first run was counted.
+    #[serde(default)]
+    pub first_run: Option<DateTime<Utc>>,
+
+    /// When the last run was counted.
+    #[serde(default)]
+    pub last_run: Option<DateTime<Utc>>,
+
+    /// Runs per subcommand.
+    #[serde(default)]
+    pub runs: BTreeMap<String, u64>,
+
+    /// Failed runs per subcommand.
+    #[serde(default)]
+    pub failures: BTreeMap<String, u64>,
+
+    /// Runs per enabled optional feature.
+    #[serde(default)]
+    pub features: BTreeMap<String, u64>,
+}
+
+impl UsageMetrics {
+    /// Counts one run of `command`, with the optional `features` it had enabled.
+    pub fn count_run(
+        &mut self,
+        command: &str,
+        succeeded: bool,
+        features: &[&str],
+        now: DateTime<Utc>,
+    ) {
+        self.first_run.get_or_insert(now);
+        self.last_run = Some(now);
+        *self.runs.entry(command.to_string()).or_default() += 1;
+        if !succeeded {
+            *self.failures.entry(command.to_string()).or_default() += 1;
+        }
+        for feature in features {
+            *self.features.entry((*feature).to_string()).or_default() += 1;
+        }
+    }
+
+    /// Renders the counters as the Markdown report printed by `metrics show`.
+    ///
+    /// The report starts with the version and platform of the binary, so a
+    /// pasted report tells maintainers what it was recorded with.
+    #[must_use]
+    pub fn render(&self) -> String {
+        let mut output = format!(
+            "rusty-commit-saver {:} ({:} {:})\n",
+            env!("CARGO_PKG_VERSION"),
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        );
+        let (Some(first_run), Some(last_run)) = (self.first_run, self.last_run) else {
+            output.push_str("\nNo runs counted yet.\n");
+            return output;
+        };
+        let _ = writeln!(
+            output,
+            "Counted from {:} to {:}",
+            first_run.format("%Y-%m-%d"),
+            last_run.format("%Y-%m-%d")
+        );
+
+        output.push_str("\n| COMMAND | RUNS | FAILURES |\n| --- | --- | --- |\n");
+        for (command, runs) in &self.runs {
+            let failures = self.failures.get(command).copied().unwrap_or(0);
+            let _ = writeln!(output, "| {command:} | {runs:} | {failures:} |");
+        }
+
+        if !self.features.is_empty() {
+            output.push_str("\n| FEATURE | RUNS |\n| --- | --- |\n");
+            for (feature, runs) in &self.features {
+                let _ = writeln!(output, "| {feature:} | {runs:} |");
+            }
+        }
+        output
+    }
+}
+
+/// The usage counters file inside the state directory.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::metrics::MetricsStore;
+///
+/// let store = MetricsStore::new(&state_dir);
+/// store.record("save", true, &[], chrono::Utc::now())?;
+/// print!("{}", store.load()?.render());
+/// ```
+#[derive(Debug, Clone)]
+pub struct MetricsStore {
+    metrics_path: PathBuf,
+}
+
+impl MetricsStore {
+    /// Creates a handle on the counters file inside `state_dir`.
+    ///
+    /// Nothing is created on disk until the first [`record()`](Self::record).
+    #[must_use]
+    pub fn new(state_dir: &Path) -> Self {
+        MetricsStore {
+            metrics_path: state_dir.join(METRICS_FILE_NAME),
+        }
+    }
+
+    /// Reads the counters. A missing file has none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or parsed.
+    pub fn load(&self) -> Result<UsageMetrics, Box<dyn Error>> {
+        if !self.metrics_path.exists() {
+            return Ok(UsageMetrics::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(
+            &self.metrics_path,
+        )?)?)
+    }
+
+    /// Counts one run of `command`, see [`UsageMetrics::count_run()`].
+    ///
+    /// The counters are updated under the lock of the metrics file, so
+    /// hooks firing together do not lose each other's counts.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lock cannot be taken within a second, or if
+    /// the file cannot be read, parsed or written.
+    pub fn record(
+        &self,
+        command: &str,
+        succeeded: bool,
+        features: &[&str],
+        now: DateTime<Utc>,
+    ) -> Result<(), Box<dyn Error>> {
+        let _lock = DiaryLock::acquire(
+            self.metrics_path.parent(),
+            &self.metrics_path,
+            METRICS_LOCK_TIMEOUT,
+        )?;
+        let mut metrics = self.load()?;
+        metrics.count_run(command, succeeded, features, now);
+
+        info!(
+            "[MetricsStore::record()]: Counting a run of '{command:}' in: {:}",
+            self.metrics_path.display()
+        );
+        write_atomically(&self.metrics_path, serde_json::to_string_pretty(&metrics)?)?;
+        Ok(())
+    }
+}
+
+/// Lists the optional features `save_settings` has enabled, by name.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::config::SaveSettings;
+/// use rusty_commit_saver::metrics::enabled_features;
+///
+/// assert!(enabled_features(&SaveSettings::default()).is_empty());
+/// ```
+#[must_use]
+pub fn enabled_features(save_settings: &SaveSettings) -> Vec<&'static str> {
+    [
+        (
+            "entry_format_yaml",
+            save_settings.entry_format == EntryFormat::Yaml,
+        ),
+        (
+            "entry_format_template",
+            save_settings.entry_format == EntryFormat::Template,
+        ),
+        (
+            "entry_format_dataview",
+            save_settings.entry_format == EntryFormat::Dataview,
+        ),
+        ("flat_layout", save_settings.flat_layout),
+        ("diary_template", save_settings.diary_template.is_some()),
+        ("branch_switches", save_settings.log_branch_switches),
+        ("stash", save_settings.log_stash),
+        ("pushes", save_settings.log_pushes),
+        ("time_tracking", save_settings.time_tracking.is_some()),
+        ("build_status", save_settings.build_status.is_some()),
+        ("vault_git", save_settings.vault_commit_message.is_some()),
+        ("max_row_length", save_settings.max_row_length.is_some()),
+        ("time_zone", save_settings.time_zone.is_some()),
+        ("timezone", save_settings.timezone.is_some()),
+        ("forge_links", save_settings.forge_links),
+        ("block_refs", save_settings.block_refs),
+        ("categories", !save_settings.category_rules.is_empty()),
+        ("trash", save_settings.trash_dir.is_some()),
+        ("require_vault", save_settings.require_vault),
+        ("routing", !save_settings.routes.is_empty()),
+        ("vaults", !save_settings.vaults.is_empty()),
+        ("normalize_remote", save_settings.normalize_remote),
+        ("insert_under", save_settings.insert_under.is_some()),
+        ("language", save_settings.detect_language),
+        ("conventional_commits", save_settings.conventional_commits),
+        ("trailers", save_settings.trailer_tags.is_some()),
+        (
+            "output_sinks",
+            save_settings.sinks != [SinkKind::ObsidianMarkdown],
+        ),
+        ("goals", save_settings.weekly_goal.is_some()),
+        ("related_repos", save_settings.related_repos_note.is_some()),
+        ("message_hygiene", save_settings.message_pattern.is_some()),
+        ("webhook", save_settings.webhook.is_some()),
+        ("weekly_log", save_settings.weekly_log.is_some()),
+        ("daily_note", save_settings.daily_note.is_some()),
+        ("heatmap_note", save_settings.heatmap.note.is_some()),
+        ("size", save_settings.size_thresholds.is_some()),
+        ("release_tags", save_settings.release_tag_distance.is_some()),
+        ("wsl", save_settings.wsl_paths.translate),
+        ("branch_tags", save_settings.branch_tags),
+        ("branch_summary", save_settings.branch_summary),
+        ("ignore_branches", !save_settings.ignore_branches.is_empty()),
+        (
+            "ignore_message_patterns",
+            !save_settings.ignore_message_patterns.is_empty(),
+        ),
+        ("ignore_bots", save_settings.ignore_bots),
+    ]
+    .into_iter()
+    .filter_map(|(feature, enabled)| enabled.then_some(feature))
+    .collect()
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod metrics_tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_record_counts_runs_failures_and_features() {
+        let state_dir = tempdir().unwrap();
+        let store = MetricsStore::new(state_dir.path());
+        assert_eq!(store.load().unwrap(), UsageMetrics::default());
+
+        let first = Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap();
+        let last = Utc.with_ymd_and_hms(2025, 1, 16, 18, 0, 0).unwrap();
+        store.record("save", true, &["block_refs"], first).unwrap();
+        store.record("save", false, &["block_refs"], last).unwrap();
+        store.record("verify", true, &[], last).unwrap();
+
+        let metrics = store.load().unwrap();
+        assert_eq!(metrics.first_run, Some(first));
+        assert_eq!(metrics.last_run, Some(last));
+        assert_eq!(metrics.runs["save"], 2);
+        assert_eq!(metrics.failures["save"], 1);
+        assert!(!metrics.failures.contains_key("verify"));
+        assert_eq!(metrics.features["block_refs"], 2);
+
+        let report = metrics.render();
+        assert!(report.starts_with(&format!(
+            "rusty-commit-saver {:}",
+            env!("CARGO_PKG_VERSION")
+        )));
+        assert!(report.contains("Counted from 2025-01-14 to 2025-01-16"));
+        assert!(report.contains("| save | 2 | 1 |"));
+        assert!(report.contains("| verify | 1 | 0 |"));
+        assert!(report.contains("| block_refs | 2 |"));
+    }
+
+    #[test]
+    fn test_enabled_features_lists_only_enabled_ones() {
+        assert!(enabled_features(&SaveSettings::default()).is_empty());
+        assert!(UsageMetrics::default()
+            .render()
+            .contains("No runs counted yet."));
+
+        let save_settings = SaveSettings {
+            entry_format: EntryFormat::Yaml,
+            block_refs: true,
+            detect_language: true,
+            ..SaveSettings::default()
+        };
+        assert_eq!(
+            enabled_features(&save_settings),
+            vec!["entry_format_yaml", "block_refs", "language"]
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use std::error::Error;
+use std::path::Path;
+use std::process::Command;
+
+use log::info;
+
+use crate::links::encode_link_target;
+
+/// Builds the `obsidian://open` URI of a note, which opens it in Obsidian.
+///
+/// Both values are percent-encoded as URI components: `/` separators
+/// included, so the query stays unambiguous.
+///
+/// # Arguments
+///
+/// * `vault_name` - The name of the vault in Obsidian
+/// * `relative_path` - The note path relative to the vault root, as returned
+///   by [`vault_relative_path()`](crate::links::vault_relative_path)
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::obsidian_uri::open_note_uri;
+///
+/// assert_eq!(
+///     open_note_uri("My Notes", "Commits/2025-01-14.md"),
+///     "obsidian://open?vault=My%20Notes&file=Commits%2F2025-01-14.md"
+/// );
+/// ```
+#[must_use]
+pub fn open_note_uri(vault_name: &str, relative_path: &str) -> String {
+    format!(
+        "obsidian://open?vault={:}&file={:}",
+        encode_uri_component(vault_name),
+        encode_uri_component(relative_path)
+    )
+}
+
+/// Percent-encodes a URI query value, see [`encode_link_target()`].
+fn encode_uri_component(value: &str) -> String {
+    encode_link_target(value).replace('/', "%2F")
+}
+
+/// Returns the name Obsidian gives a vault by default: the name of its
+/// directory.
+#[must_use]
+pub fn default_vault_name(obsidian_root_path_dir: &Path) -> Option<String> {
+    obsidian_root_path_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+/// Builds the command handing a URI to the system opener: `open` on macOS,
+/// `url.dll` on Windows (which, unlike `start`, needs no shell quoting of
+/// the `&` in the query) and `xdg-open` elsewhere.
+#[must_use]
+pub fn opener_command(uri: &str) -> Command {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    command.arg(uri);
+    command
+}
+
+/// Opens a URI with the system opener, see [`opener_command()`].
+///
+/// # Errors
+///
+/// Returns an error if the opener cannot be run or exits with a failure.
+pub fn open_uri(uri: &str) -> Result<(), Box<dyn Error>> {
+    info!("[open_uri()]: Opening: {uri:}");
+    let status = opener_command(uri).status()?;
+    if !status.success() {
+        return Err(format!("the system opener failed ({status:})").into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod obsidian_uri_tests {
+    use super::*;
+
+    #[test]
+    fn test_open_note_uri_encodes_the_vault_and_path() {
+        assert_eq!(
+            open_note_uri("Work & Notes", "📅 Diaries/0. Commits/2025-01-14.md"),
+            "obsidian://open?vault=Work%20%26%20Notes&file=%F0%9F%93%85%20Diaries%2F0.%20Commits%2F2025-01-14.md"
+        );
+    }
+
+    #[test]
+    fn test_default_vault_name() {
+        assert_eq!(
+            default_vault_name(Path::new("/home/me/Notes")).as_deref(),
+            Some("Notes")
+        );
+        assert_eq!(default_vault_name(Path::new("/")), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_opener_command() {
+        let command = opener_command("obsidian://open?vault=Notes");
+        assert_eq!(command.get_program(), "xdg-open");
+        assert_eq!(
+            command.get_args().collect::<Vec<_>>(),
+            vec!["obsidian://open?vault=Notes"]
+        );
+    }
+}
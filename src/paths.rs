@@ -0,0 +1,569 @@
+use log::info;
+use log::warn;
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use unicode_normalization::is_nfc;
+use unicode_normalization::UnicodeNormalization;
+
+/// Placeholder replaced by the repository name when building diary paths.
+pub const REPO_PLACEHOLDER: &str = "{repo}";
+
+/// Placeholder replaced by the branch name when building diary paths.
+pub const BRANCH_PLACEHOLDER: &str = "{branch}";
+
+/// Directory Obsidian keeps its settings in, at the root of every vault.
+pub const OBSIDIAN_CONFIG_DIR: &str = ".obsidian";
+
+/// Exit status when the vault cannot be written to (`EX_CANTCREAT` of
+/// `sysexits.h`), so hooks and scripts can tell a read-only mount apart from
+/// other failures.
+pub const READ_ONLY_VAULT_EXIT_CODE: i32 = 73;
+
+/// Name of the empty file created to probe a directory for write access.
+const WRITE_PROBE_FILE: &str = ".rusty-commit-saver-write-probe";
+
+/// Fallback segment used when a value sanitizes down to nothing.
+const EMPTY_SEGMENT: &str = "_";
+
+/// Upper bound for a single sanitized segment, well below common filesystem limits.
+const MAX_SEGMENT_LEN: usize = 100;
+
+/// Turns an arbitrary string into a single, safe path segment.
+///
+/// Branch names and repository names are user-controlled: `feature/login`,
+/// `../../etc`, or `CON:` must never escape the configured commit directory
+/// or split into several directories. The returned value always parses as
+/// exactly one normal path component.
+///
+/// # Rules
+///
+/// - Path separators (`/`, `\`) and characters reserved on Windows
+///   (`<>:"|?*`) become `-`
+/// - Control characters are dropped
+/// - Runs of `-` are collapsed and leading/trailing `-`, `.` and whitespace are trimmed,
+///   so `..` and `.` can never survive as a segment
+/// - The result is truncated to 100 characters
+/// - An empty result becomes `_`
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::paths::sanitize_path_segment;
+///
+/// assert_eq!(sanitize_path_segment("feature/login-page"), "feature-login-page");
+/// assert_eq!(sanitize_path_segment("../../etc/passwd"), "etc-passwd");
+/// assert_eq!(sanitize_path_segment(".."), "_");
+/// ```
+#[must_use]
+pub fn sanitize_path_segment(raw: &str) -> String {
+    let mut segment = String::with_capacity(raw.len());
+
+    for c in raw.chars() {
+        if c.is_control() {
+            continue;
+        }
+        let c = match c {
+            '/' | '\\' | '<' | '>' | ':' | '"' | '|' | '?' | '*' => '-',
+            other => other,
+        };
+        if c == '-' && segment.ends_with('-') {
+            continue;
+        }
+        segment.push(c);
+    }
+
+    let trimmed: String = segment
+        .trim_matches(|c: char| c == '-' || c == '.' || c.is_whitespace())
+        .chars()
+        .take(MAX_SEGMENT_LEN)
+        .collect();
+
+    // Truncation may expose a trailing dot or dash again.
+    let trimmed = trimmed.trim_end_matches(|c: char| c == '-' || c == '.' || c.is_whitespace());
+
+    if trimmed.is_empty() {
+        EMPTY_SEGMENT.to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Derives a short repository name from a remote URL.
+///
+/// Takes the last path segment of the URL and strips a trailing `.git`, so
+/// both HTTPS and SCP-like SSH remotes resolve to the same name.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::paths::repository_name_from_url;
+///
+/// assert_eq!(repository_name_from_url("https://github.com/user/repo.git"), "repo");
+/// assert_eq!(repository_name_from_url("git@github.com:user/repo.git"), "repo");
+/// assert_eq!(repository_name_from_url("https://git.sr.ht/~user/repo"), "repo");
+/// ```
+#[must_use]
+pub fn repository_name_from_url(url: &str) -> String {
+    let last = url
+        .trim_end_matches('/')
+        .rsplit(['/', ':'])
+        .next()
+        .unwrap_or(url);
+
+    last.strip_suffix(".git").unwrap_or(last).to_string()
+}
+
+/// Replaces the `{repo}` and `{branch}` placeholders in a path template.
+///
+/// Both values are passed through [`sanitize_path_segment()`] first, so a
+/// hostile branch name like `../../outside` can only ever produce a single
+/// directory or file name inside the configured commit path.
+///
+/// # Arguments
+///
+/// * `template` - A path (or path fragment) possibly containing placeholders
+/// * `repo` - The repository name substituted for `{repo}`
+/// * `branch` - The branch name substituted for `{branch}`
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::paths::expand_path_placeholders;
+///
+/// let path = expand_path_placeholders("Commits/{repo}/{branch}", "my-repo", "feature/x");
+/// assert_eq!(path, "Commits/my-repo/feature-x");
+/// ```
+#[must_use]
+pub fn expand_path_placeholders(template: &str, repo: &str, branch: &str) -> String {
+    if !template.contains(REPO_PLACEHOLDER) && !template.contains(BRANCH_PLACEHOLDER) {
+        return template.to_string();
+    }
+
+    info!("[expand_path_placeholders()]: Expanding placeholders in: {template:}");
+    template
+        .replace(REPO_PLACEHOLDER, &sanitize_path_segment(repo))
+        .replace(BRANCH_PLACEHOLDER, &sanitize_path_segment(branch))
+}
+
+/// Keeps only the file name part of a `commit_date_path` template.
+///
+/// Used for the flat layout (`[templates] flat_layout`), where every diary
+/// file sits directly in the commit path: `%Y/%m-%B/%F.md` becomes `%F.md`,
+/// so no year or month directory is ever created. The file name must then
+/// be unique on its own, which `%F` (the full date) is.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::paths::flat_date_path_template;
+///
+/// assert_eq!(flat_date_path_template("%Y/%m-%B/%F.md"), "%F.md");
+/// assert_eq!(flat_date_path_template("%F.md"), "%F.md");
+/// ```
+#[must_use]
+pub fn flat_date_path_template(template: &str) -> &str {
+    template.rsplit('/').next().unwrap_or(template)
+}
+
+/// Puts an emoji in front of the first folder of a relative commit path
+/// (`[obsidian] path_prefix_emoji`), e.g. `Diaries/Commits` becomes
+/// `📅 Diaries/Commits`.
+///
+/// Absolute paths, and paths whose first folder already starts with the
+/// emoji, are returned unchanged.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::paths::prefix_path_emoji;
+///
+/// assert_eq!(prefix_path_emoji("Diaries/Commits", "📅"), "📅 Diaries/Commits");
+/// assert_eq!(prefix_path_emoji("📅 Diaries/Commits", "📅"), "📅 Diaries/Commits");
+/// assert_eq!(prefix_path_emoji("/vault/Diaries", "📅"), "/vault/Diaries");
+/// ```
+#[must_use]
+pub fn prefix_path_emoji(path: &str, emoji: &str) -> String {
+    let emoji = emoji.trim();
+    if emoji.is_empty() || path.is_empty() || path.starts_with('/') || path.starts_with(emoji) {
+        return path.to_string();
+    }
+
+    format!("{emoji:} {path:}")
+}
+
+/// Normalizes a generated path to Unicode NFC (composed form).
+///
+/// macOS tools often produce decomposed (NFD) text, e.g. `Ma\u{308}rz` for
+/// `März`, while Linux keeps bytes as-is. Without normalization the same
+/// month name, repository name or emoji folder can end up as two
+/// duplicate-looking directories once the vault is synced between machines.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::paths::normalize_nfc;
+///
+/// assert_eq!(normalize_nfc("Diaries/Ma\u{308}rz"), "Diaries/M\u{e4}rz");
+/// ```
+#[must_use]
+pub fn normalize_nfc(path: &str) -> String {
+    if is_nfc(path) {
+        return path.to_string();
+    }
+
+    info!("[normalize_nfc()]: Normalizing path to NFC: {path:}");
+    path.nfc().collect()
+}
+
+/// Checks that a directory is an Obsidian vault, i.e. holds a `.obsidian` directory.
+///
+/// Used by `[safety] require_vault`, so a typo in `root_path_dir` fails
+/// instead of silently growing a vault tree in the wrong place.
+///
+/// # Errors
+///
+/// Returns an error naming the directory if it has no `.obsidian` directory.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::paths::check_obsidian_vault;
+/// use std::path::Path;
+///
+/// check_obsidian_vault(Path::new("/home/user/Obsidian"))?;
+/// ```
+pub fn check_obsidian_vault(root: &Path) -> Result<(), Box<dyn Error>> {
+    if root.join(OBSIDIAN_CONFIG_DIR).is_dir() {
+        return Ok(());
+    }
+    Err(format!(
+        "{:} is not an Obsidian vault (no {OBSIDIAN_CONFIG_DIR:} directory); check root_path_dir or pass --force",
+        root.display()
+    )
+    .into())
+}
+
+/// The vault (or the part of it an entry goes to) cannot be written to: a
+/// read-only mount (`EROFS`) or missing permissions.
+#[derive(Debug)]
+pub struct ReadOnlyVault {
+    /// The file or directory that could not be written.
+    pub path: PathBuf,
+
+    /// The error writing to it gave.
+    pub source: io::Error,
+}
+
+impl fmt::Display for ReadOnlyVault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The vault is not writable at {:} ({:}); nothing was logged. Remount it read-write or fix its permissions, then run `save` again.",
+            self.path.display(),
+            self.source
+        )
+    }
+}
+
+impl Error for ReadOnlyVault {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Returns `true` for the errors of a read-only filesystem or of missing
+/// write permissions.
+#[must_use]
+pub fn is_read_only_error(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::ReadOnlyFilesystem | io::ErrorKind::PermissionDenied
+    )
+}
+
+/// Checks, before anything is created, that a diary file can be written.
+///
+/// An existing file is opened for appending (without changing it). For a
+/// new file, an empty probe file is created and removed in the deepest
+/// existing directory on its path, where the missing directories would be
+/// created. Saving then fails up front, instead of half-way through with
+/// some of the directories created.
+///
+/// Errors other than a read-only filesystem or missing permissions (e.g. a
+/// vanished mount) only log a warning: the write itself reports them.
+///
+/// # Errors
+///
+/// Returns a [`ReadOnlyVault`] if the file or directory cannot be written to.
+pub fn check_vault_writable(diary_path: &Path) -> Result<(), ReadOnlyVault> {
+    let result = if diary_path.exists() {
+        OpenOptions::new()
+            .append(true)
+            .open(diary_path)
+            .map(drop)
+            .map_err(|e| (diary_path.to_path_buf(), e))
+    } else {
+        let Some(existing) = diary_path.ancestors().skip(1).find(|dir| dir.is_dir()) else {
+            return Ok(());
+        };
+        let probe = existing.join(WRITE_PROBE_FILE);
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&probe)
+            .and_then(|_| fs::remove_file(&probe))
+            .map_err(|e| (existing.to_path_buf(), e))
+    };
+
+    match result {
+        Ok(()) => Ok(()),
+        Err((path, source)) if is_read_only_error(&source) => Err(ReadOnlyVault { path, source }),
+        Err((path, e)) => {
+            warn!(
+                "[check_vault_writable()]: Could not probe {:} for write access: {e:}",
+                path.display()
+            );
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod paths_tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::path::Component;
+
+    /// Whether the given string parses as exactly one normal path component:
+    /// it cannot traverse upwards (`..`), reference the root, or span several
+    /// directories.
+    fn is_single_normal_segment(segment: &str) -> bool {
+        let mut components = Path::new(segment).components();
+        matches!(
+            (components.next(), components.next()),
+            (Some(Component::Normal(_)), None)
+        )
+    }
+
+    #[test]
+    fn test_prefix_path_emoji() {
+        assert_eq!(
+            prefix_path_emoji("Diaries/0. Commits", " 📅 "),
+            "📅 Diaries/0. Commits"
+        );
+        assert_eq!(
+            prefix_path_emoji("📅 Diaries/Commits", "📅"),
+            "📅 Diaries/Commits"
+        );
+        assert_eq!(
+            prefix_path_emoji("/home/me/Diaries", "📅"),
+            "/home/me/Diaries"
+        );
+        assert_eq!(prefix_path_emoji("Diaries", ""), "Diaries");
+    }
+
+    #[test]
+    fn test_check_obsidian_vault() {
+        let vault = tempfile::tempdir().unwrap();
+        let error = check_obsidian_vault(vault.path()).unwrap_err().to_string();
+        assert!(error.contains("is not an Obsidian vault"));
+
+        std::fs::create_dir(vault.path().join(OBSIDIAN_CONFIG_DIR)).unwrap();
+        assert!(check_obsidian_vault(vault.path()).is_ok());
+    }
+
+    #[test]
+    fn test_check_vault_writable() {
+        let vault = tempfile::tempdir().unwrap();
+        let diary = vault.path().join("Diaries/2025/2025-01-14.md");
+
+        assert!(check_vault_writable(&diary).is_ok());
+        assert!(!vault.path().join(WRITE_PROBE_FILE).exists());
+        assert!(!vault.path().join("Diaries").exists());
+
+        fs::create_dir_all(diary.parent().unwrap()).unwrap();
+        fs::write(&diary, "# 2025-01-14\n").unwrap();
+        assert!(check_vault_writable(&diary).is_ok());
+        assert_eq!(fs::read_to_string(&diary).unwrap(), "# 2025-01-14\n");
+    }
+
+    #[test]
+    fn test_read_only_vault_error() {
+        let error = io::Error::from(io::ErrorKind::ReadOnlyFilesystem);
+        assert!(is_read_only_error(&error));
+        assert!(is_read_only_error(&io::Error::from(
+            io::ErrorKind::PermissionDenied
+        )));
+        assert!(!is_read_only_error(&io::Error::from(
+            io::ErrorKind::NotFound
+        )));
+
+        let message = ReadOnlyVault {
+            path: PathBuf::from("/vault/Diaries"),
+            source: error,
+        }
+        .to_string();
+        assert!(message.contains("not writable at /vault/Diaries"));
+        assert!(message.contains("nothing was logged"));
+    }
+
+    #[test]
+    fn test_sanitize_path_segment_slash_branch() {
+        assert_eq!(
+            sanitize_path_segment("feature/login-page"),
+            "feature-login-page"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_path_segment_traversal() {
+        assert_eq!(sanitize_path_segment(".."), "_");
+        assert_eq!(sanitize_path_segment("."), "_");
+        assert_eq!(sanitize_path_segment("../../etc/passwd"), "etc-passwd");
+        assert_eq!(sanitize_path_segment("a/../b"), "a-..-b");
+    }
+
+    #[test]
+    fn test_sanitize_path_segment_reserved_characters() {
+        assert_eq!(sanitize_path_segment("fix:a|b*c?"), "fix-a-b-c");
+        assert_eq!(sanitize_path_segment("back\\slash"), "back-slash");
+        assert_eq!(sanitize_path_segment("tab\there"), "tabhere");
+    }
+
+    #[test]
+    fn test_sanitize_path_segment_empty_and_whitespace() {
+        assert_eq!(sanitize_path_segment(""), "_");
+        assert_eq!(sanitize_path_segment("   "), "_");
+        assert_eq!(sanitize_path_segment("///"), "_");
+    }
+
+    #[test]
+    fn test_sanitize_path_segment_keeps_unicode() {
+        assert_eq!(sanitize_path_segment("📅 Diaries"), "📅 Diaries");
+    }
+
+    #[test]
+    fn test_sanitize_path_segment_truncates() {
+        let long = "a".repeat(500);
+        assert_eq!(
+            sanitize_path_segment(&long).chars().count(),
+            MAX_SEGMENT_LEN
+        );
+    }
+
+    #[test]
+    fn test_repository_name_from_url() {
+        assert_eq!(
+            repository_name_from_url("https://github.com/user/repo.git"),
+            "repo"
+        );
+        assert_eq!(
+            repository_name_from_url("git@github.com:user/repo.git"),
+            "repo"
+        );
+        assert_eq!(
+            repository_name_from_url("https://git.sr.ht/~user/repo/"),
+            "repo"
+        );
+        assert_eq!(repository_name_from_url("no_url_set"), "no_url_set");
+    }
+
+    #[test]
+    fn test_expand_path_placeholders() {
+        let result = expand_path_placeholders("Commits/{repo}/{branch}.md", "repo", "feat/x");
+        assert_eq!(result, "Commits/repo/feat-x.md");
+    }
+
+    #[test]
+    fn test_expand_path_placeholders_without_placeholders() {
+        let result = expand_path_placeholders("%Y/%m-%B/%F.md", "repo", "main");
+        assert_eq!(result, "%Y/%m-%B/%F.md");
+    }
+
+    #[test]
+    fn test_expand_path_placeholders_hostile_branch_stays_inside() {
+        let hostile = [
+            "../../../../etc/passwd",
+            "..",
+            "/absolute",
+            "a/b/c",
+            "..\\..\\windows",
+            "~/home",
+            "con:",
+            "\u{0}nul",
+        ];
+
+        for branch in hostile {
+            let result = expand_path_placeholders("Commits/{branch}", "repo", branch);
+            let segment = result.strip_prefix("Commits/").unwrap();
+            assert!(
+                is_single_normal_segment(segment),
+                "{branch:?} produced {result:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_flat_date_path_template_keeps_the_file_name() {
+        assert_eq!(flat_date_path_template("%Y/%m-%B/%F.md"), "%F.md");
+        assert_eq!(flat_date_path_template("{repo}/%F.md"), "%F.md");
+        assert_eq!(flat_date_path_template("%F.md"), "%F.md");
+    }
+
+    #[test]
+    fn test_normalize_nfc_composes_decomposed_text() {
+        let decomposed = "📅 Diaries/2025/03-Ma\u{308}rz/cafe\u{301}";
+
+        assert_eq!(
+            normalize_nfc(decomposed),
+            "📅 Diaries/2025/03-M\u{e4}rz/caf\u{e9}"
+        );
+        assert_eq!(normalize_nfc("already/composed"), "already/composed");
+    }
+
+    proptest! {
+        #[test]
+        fn prop_sanitized_segment_is_single_normal_component(raw in any::<String>()) {
+            let segment = sanitize_path_segment(&raw);
+            prop_assert!(is_single_normal_segment(&segment), "{:?} -> {:?}", raw, segment);
+        }
+
+        #[test]
+        fn prop_sanitized_segment_has_no_separators(raw in "[a-z./\\\\:~ -]{0,40}") {
+            let segment = sanitize_path_segment(&raw);
+            prop_assert!(!segment.contains('/'));
+            prop_assert!(!segment.contains('\\'));
+            prop_assert!(segment != "." && segment != "..");
+        }
+
+        #[test]
+        fn prop_expanded_branch_never_escapes_commit_path(branch in any::<String>()) {
+            let result = expand_path_placeholders("Commits/{branch}/%F.md", "repo", &branch);
+            let components: Vec<_> = Path::new(&result).components().collect();
+            prop_assert_eq!(components.len(), 3);
+            prop_assert!(components.iter().all(|c| matches!(c, Component::Normal(_))));
+        }
+
+        #[test]
+        fn prop_normalize_nfc_is_idempotent_and_nfc(raw in any::<String>()) {
+            let once = normalize_nfc(&raw);
+            prop_assert!(is_nfc(&once));
+            prop_assert_eq!(normalize_nfc(&once), once.clone());
+        }
+
+        #[test]
+        fn prop_sanitize_is_idempotent(raw in any::<String>()) {
+            let once = sanitize_path_segment(&raw);
+            prop_assert_eq!(sanitize_path_segment(&once), once.clone());
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use std::path::PathBuf;
+
+use crate::vim_commit::CommitSaver;
+use crate::vim_commit::EntryKind;
+
+/// Separator between the fields of a porcelain record.
+const FIELD_SEPARATOR: char = '\t';
+
+/// Escapes a field so a record always stays on one line.
+///
+/// Backslashes, tabs, line feeds and carriage returns are written as `\\`,
+/// `\t`, `\n` and `\r`; everything else is kept as is.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::porcelain::escape_field;
+///
+/// assert_eq!(escape_field("C:\\vault\tnotes"), "C:\\\\vault\\tnotes");
+/// ```
+#[must_use]
+pub fn escape_field(field: &str) -> String {
+    let mut escaped = String::with_capacity(field.len());
+    for character in field.chars() {
+        match character {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+/// Joins escaped fields into one tab-separated record, ending with a newline.
+///
+/// The first field is the record type (e.g. `save`), so one stream can carry
+/// several kinds of records; the field order of each type never changes.
+/// Empty fields are kept, so columns can be read by position.
+#[must_use]
+pub fn porcelain_line(fields: &[&str]) -> String {
+    let mut line = fields
+        .iter()
+        .map(|field| escape_field(field))
+        .collect::<Vec<_>>()
+        .join(&FIELD_SEPARATOR.to_string());
+    line.push('\n');
+    line
+}
+
+/// What happened to an entry handed to the diary writer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveStatus {
+    /// The row was written to the diary file.
+    Logged,
+    /// The commit is already in its diary file, or in the logged-commits
+    /// index (`save --stdin`).
+    AlreadyLogged,
+    /// The commit asked to be skipped (`Rcs-Skip` trailer or `RCS_SKIP`), or
+    /// matches a `[filters]` rule (branch, message or bot).
+    Skipped,
+    /// The commit is a vault auto-commit, which is never logged.
+    VaultSync,
+    /// The entry was only previewed (`save --dry-run`), nothing was written.
+    DryRun,
+    /// The repository has no commit yet, so there was nothing to log.
+    NoCommit,
+}
+
+impl SaveStatus {
+    /// Returns the status as written in porcelain output.
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            SaveStatus::Logged => "logged",
+            SaveStatus::AlreadyLogged => "already-logged",
+            SaveStatus::Skipped => "skipped",
+            SaveStatus::VaultSync => "vault-sync",
+            SaveStatus::DryRun => "dry-run",
+            SaveStatus::NoCommit => "no-commit",
+        }
+    }
+}
+
+/// The outcome of saving one entry, as reported by `save --porcelain`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveReport {
+    pub status: SaveStatus,
+    pub entry_kind: EntryKind,
+    pub commit_hash: String,
+    pub repository_url: String,
+    pub branch: String,
+    /// The diary file written to, only set for [`SaveStatus::Logged`] and
+    /// [`SaveStatus::DryRun`] (the file that would be written to).
+    pub diary_path: Option<PathBuf>,
+    /// What would have been written, only set for [`SaveStatus::DryRun`].
+    pub preview: Option<String>,
+}
+
+impl SaveReport {
+    /// Builds the report of `commit_saver_struct` with the given outcome.
+    #[must_use]
+    pub fn new(
+        status: SaveStatus,
+        commit_saver_struct: &CommitSaver,
+        diary_path: Option<PathBuf>,
+    ) -> Self {
+        SaveReport {
+            status,
+            entry_kind: commit_saver_struct.details.entry_kind,
+            commit_hash: commit_saver_struct.entry.hash.clone(),
+            repository_url: commit_saver_struct.entry.repository_url.clone(),
+            branch: commit_saver_struct.entry.branch.clone(),
+            diary_path,
+            preview: None,
+        }
+    }
+
+    /// Renders the report as a porcelain `save` record.
+    ///
+    /// # Format
+    ///
+    /// Tab-separated fields, in this order, escaped with [`escape_field()`]:
+    ///
+    /// ```text
+    /// save <status> <event> <hash> <repository url> <branch> <diary path>
+    /// ```
+    ///
+    /// - `status` - `logged`, `already-logged`, `skipped`, `vault-sync`, `dry-run`
+    ///   or `no-commit`
+    /// - `event` - The `EVENT` label of the row (`commit`, `wip`, `checkout`, ...)
+    /// - `diary path` - Empty unless the row was logged, or would be with `dry-run`
+    #[must_use]
+    pub fn to_porcelain(&self) -> String {
+        let diary_path = self
+            .diary_path
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        porcelain_line(&[
+            "save",
+            self.status.label(),
+            self.entry_kind.label(),
+            &self.commit_hash,
+            &self.repository_url,
+            &self.branch,
+            &diary_path,
+        ])
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod porcelain_tests {
+    use super::*;
+
+    #[test]
+    fn test_porcelain_line_escapes_fields() {
+        assert_eq!(
+            porcelain_line(&["save", "a\tb", "line\nbreak\r", "C:\\vault", ""]),
+            "save\ta\\tb\tline\\nbreak\\r\tC:\\\\vault\t\n"
+        );
+    }
+
+    #[test]
+    fn test_save_report_to_porcelain() {
+        let report = SaveReport {
+            status: SaveStatus::Logged,
+            entry_kind: EntryKind::StashPush,
+            commit_hash: "abc123".to_string(),
+            repository_url: "git@github.com:acme/api.git".to_string(),
+            branch: "main".to_string(),
+            diary_path: Some(PathBuf::from("/vault/Commits/2025-01-14.md")),
+            preview: None,
+        };
+        assert_eq!(
+            report.to_porcelain(),
+            "save\tlogged\tstash push\tabc123\tgit@github.com:acme/api.git\tmain\t/vault/Commits/2025-01-14.md\n"
+        );
+
+        let skipped = SaveReport {
+            status: SaveStatus::Skipped,
+            diary_path: None,
+            ..report
+        };
+        assert!(skipped.to_porcelain().starts_with("save\tskipped\t"));
+        assert!(skipped.to_porcelain().ends_with("\tmain\t\n"));
+    }
+}
@@ -0,0 +1,306 @@
+use std::error::Error;
+
+use chrono::Utc;
+use git2::Oid;
+use git2::Repository;
+use log::info;
+
+use crate::commit_entry::CommitEntry;
+use crate::vim_commit::origin_url;
+use crate::vim_commit::CommitSaver;
+use crate::vim_commit::EntryKind;
+
+/// Hash Git uses in the `pre-push` and `post-receive` input for a ref that
+/// does not exist on one side.
+pub const ZERO_HASH: &str = "0000000000000000000000000000000000000000";
+
+/// One ref being pushed, as read from the `pre-push` hook input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushedRef {
+    pub local_ref: String,
+    pub local_hash: String,
+    pub remote_ref: String,
+    pub remote_hash: String,
+}
+
+impl PushedRef {
+    /// Returns `true` if the push deletes the remote ref.
+    #[must_use]
+    pub fn is_deletion(&self) -> bool {
+        self.local_hash == ZERO_HASH
+    }
+
+    /// Returns `true` if the remote ref does not exist yet.
+    #[must_use]
+    pub fn is_new(&self) -> bool {
+        self.remote_hash == ZERO_HASH
+    }
+}
+
+/// Parses the lines Git writes to the `pre-push` hook's stdin.
+///
+/// Each line reads `<local ref> <local hash> <remote ref> <remote hash>`;
+/// lines with another number of fields are skipped.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::push::parse_pre_push_input;
+///
+/// let pushed = parse_pre_push_input("refs/heads/main def456 refs/heads/main abc123\n");
+/// assert_eq!(pushed[0].remote_hash, "abc123");
+/// ```
+#[must_use]
+pub fn parse_pre_push_input(input: &str) -> Vec<PushedRef> {
+    input
+        .lines()
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            match fields[..] {
+                [local_ref, local_hash, remote_ref, remote_hash] => Some(PushedRef {
+                    local_ref: local_ref.to_string(),
+                    local_hash: local_hash.to_string(),
+                    remote_ref: remote_ref.to_string(),
+                    remote_hash: remote_hash.to_string(),
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Counts the commits a pushed ref sends to the remote.
+///
+/// For an existing remote ref this is `<remote hash>..<local hash>`. For a new
+/// ref, or when the remote hash is unknown locally (someone else pushed), the
+/// commits already on a remote-tracking branch of `remote` are left out.
+///
+/// # Errors
+///
+/// Returns an error if the local hash is not a commit of the repository.
+pub fn count_pushed_commits(
+    git_repo: &Repository,
+    pushed_ref: &PushedRef,
+    remote: &str,
+) -> Result<usize, Box<dyn Error>> {
+    let mut revwalk = git_repo.revwalk()?;
+    revwalk.push(Oid::from_str(&pushed_ref.local_hash)?)?;
+
+    let remote_commit = Oid::from_str(&pushed_ref.remote_hash)
+        .ok()
+        .filter(|_| !pushed_ref.is_new())
+        .filter(|oid| git_repo.find_commit(*oid).is_ok());
+    match remote_commit {
+        Some(oid) => revwalk.hide(oid)?,
+        None => revwalk.hide_glob(&format!("refs/remotes/{remote:}/*"))?,
+    }
+
+    Ok(revwalk.flatten().count())
+}
+
+/// Summarizes a pushed ref in one line, e.g. `main: 5 commits, abc1234..def5678`.
+///
+/// The remote branch is named too when it differs from the local one
+/// (`main → release`). Tags are summarized as `tag v1.0`.
+#[must_use]
+pub fn push_summary(pushed_ref: &PushedRef, commits: usize) -> String {
+    let short = |hash: &str| hash.chars().take(7).collect::<String>();
+    if let Some(tag) = pushed_ref.local_ref.strip_prefix("refs/tags/") {
+        return format!("tag {tag:} ({:})", short(&pushed_ref.local_hash));
+    }
+
+    let local_name = short_ref_name(&pushed_ref.local_ref);
+    let remote_name = short_ref_name(&pushed_ref.remote_ref);
+    let name = if local_name == remote_name {
+        local_name.to_string()
+    } else {
+        format!("{local_name:} → {remote_name:}")
+    };
+    let plural = if commits == 1 { "" } else { "s" };
+
+    if pushed_ref.is_new() {
+        format!(
+            "{name:} (new): {commits:} commit{plural:}, up to {:}",
+            short(&pushed_ref.local_hash)
+        )
+    } else {
+        format!(
+            "{name:}: {commits:} commit{plural:}, {:}..{:}",
+            short(&pushed_ref.remote_hash),
+            short(&pushed_ref.local_hash)
+        )
+    }
+}
+
+/// Builds one push row per ref of the `pre-push` hook input.
+///
+/// Deleted remote refs are skipped. Rows are timestamped with the current
+/// time, their hash column holds the pushed commit and their branch column
+/// the local branch (or tag).
+///
+/// # Message Format
+///
+/// ```text
+/// 🚀 Pushed to origin: main: 5 commits, abc1234..def5678
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if a pushed hash is not a commit of the repository.
+pub fn push_entries(
+    git_repo: &Repository,
+    remote: &str,
+    pushed_refs: &[PushedRef],
+) -> Result<Vec<CommitSaver>, Box<dyn Error>> {
+    let mut entries = Vec::new();
+    for pushed_ref in pushed_refs {
+        if pushed_ref.is_deletion() {
+            info!(
+                "[push_entries()]: Deletion of {:}, skipping.",
+                pushed_ref.remote_ref
+            );
+            continue;
+        }
+
+        let commits = count_pushed_commits(git_repo, pushed_ref, remote)?;
+        entries.push(
+            CommitSaver::with_kind(
+                CommitEntry {
+                    repository_url: origin_url(git_repo),
+                    branch: short_ref_name(&pushed_ref.local_ref).replace('"', ""),
+                    hash: pushed_ref.local_hash.clone(),
+                    message: format!(
+                        "🚀 Pushed to {remote:}: {:}",
+                        push_summary(pushed_ref, commits)
+                    ),
+                    datetime: Utc::now(),
+                    author: None,
+                    author_email: None,
+                    stats: None,
+                    parents: Vec::new(),
+                },
+                EntryKind::Push,
+            )
+            .in_repository(git_repo),
+        );
+    }
+    Ok(entries)
+}
+
+/// Strips the `refs/heads/` or `refs/tags/` prefix of a ref name.
+fn short_ref_name(ref_name: &str) -> &str {
+    ref_name
+        .strip_prefix("refs/heads/")
+        .or_else(|| ref_name.strip_prefix("refs/tags/"))
+        .unwrap_or(ref_name)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod push_tests {
+    use super::*;
+    use git2::Signature;
+    use git2::Time;
+    use tempfile::tempdir;
+
+    fn commit(git_repo: &Repository, message: &str) -> String {
+        let signature =
+            Signature::new("Test", "test@example.com", &Time::new(1_736_848_800, 0)).unwrap();
+        let tree_id = git_repo.index().unwrap().write_tree().unwrap();
+        let tree = git_repo.find_tree(tree_id).unwrap();
+        let parent = git_repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents = parent.iter().collect::<Vec<_>>();
+
+        git_repo
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parents,
+            )
+            .unwrap()
+            .to_string()
+    }
+
+    fn pushed(local_hash: &str, remote_hash: &str) -> PushedRef {
+        PushedRef {
+            local_ref: "refs/heads/main".to_string(),
+            local_hash: local_hash.to_string(),
+            remote_ref: "refs/heads/main".to_string(),
+            remote_hash: remote_hash.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_pre_push_input() {
+        let input = format!(
+            "refs/heads/main def456 refs/heads/main abc123\nnot a line\n(delete) {ZERO_HASH:} refs/heads/old abc123\n"
+        );
+
+        let pushed_refs = parse_pre_push_input(&input);
+
+        assert_eq!(pushed_refs.len(), 2);
+        assert_eq!(pushed_refs[0], pushed("def456", "abc123"));
+        assert!(pushed_refs[1].is_deletion());
+        assert!(!pushed_refs[0].is_new());
+    }
+
+    #[test]
+    fn test_push_summary() {
+        let hash = |c: char| c.to_string().repeat(40);
+        assert_eq!(
+            push_summary(&pushed(&hash('d'), &hash('a')), 5),
+            "main: 5 commits, aaaaaaa..ddddddd"
+        );
+
+        let mut renamed = pushed(&hash('d'), ZERO_HASH);
+        renamed.remote_ref = "refs/heads/release".to_string();
+        assert_eq!(
+            push_summary(&renamed, 1),
+            "main → release (new): 1 commit, up to ddddddd"
+        );
+
+        let tag = PushedRef {
+            local_ref: "refs/tags/v1.0".to_string(),
+            remote_ref: "refs/tags/v1.0".to_string(),
+            ..pushed(&hash('d'), ZERO_HASH)
+        };
+        assert_eq!(push_summary(&tag, 0), "tag v1.0 (ddddddd)");
+    }
+
+    #[test]
+    fn test_push_entries_count_the_pushed_range() {
+        let temp_dir = tempdir().unwrap();
+        let git_repo = Repository::init(temp_dir.path()).unwrap();
+        let first = commit(&git_repo, "first");
+        commit(&git_repo, "second");
+        let third = commit(&git_repo, "third");
+
+        let entries = push_entries(
+            &git_repo,
+            "origin",
+            &[
+                pushed(&third, &first),
+                pushed(&third, ZERO_HASH),
+                pushed(ZERO_HASH, &first),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].details.entry_kind, EntryKind::Push);
+        assert_eq!(entries[0].entry.branch, "main");
+        assert_eq!(entries[0].entry.hash, third);
+        assert_eq!(
+            entries[0].entry.message,
+            format!(
+                "🚀 Pushed to origin: main: 2 commits, {:}..{:}",
+                &first[..7],
+                &third[..7]
+            )
+        );
+        assert!(entries[1].entry.message.contains("main (new): 3 commits"));
+    }
+}
@@ -0,0 +1,161 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::DateTime;
+use chrono::Utc;
+use log::warn;
+
+/// Tells why the content of an existing diary file cannot be appended to.
+///
+/// - Content that is not UTF-8, or contains NUL bytes, is binary (e.g. a file
+///   overwritten by a sync conflict or a crashed editor)
+/// - A frontmatter block opened with `---` but never closed would swallow the
+///   rows appended below it
+///
+/// # Returns
+///
+/// A short description of the problem, or `None` if the file can be used.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::quarantine::diary_file_problem;
+///
+/// assert_eq!(diary_file_problem(b"---\ntags:\n---\n| a |\n"), None);
+/// assert!(diary_file_problem(b"---\ntags:\n- daily\n").is_some());
+/// assert!(diary_file_problem(&[0xff, 0xfe, 0x00]).is_some());
+/// ```
+#[must_use]
+pub fn diary_file_problem(content: &[u8]) -> Option<&'static str> {
+    let Ok(text) = std::str::from_utf8(content) else {
+        return Some("the file is not valid UTF-8");
+    };
+    if text.contains('\0') {
+        return Some("the file contains binary data");
+    }
+
+    let mut lines = text.lines();
+    if lines.next().map(str::trim_end) == Some("---") && !lines.any(|line| line.trim_end() == "---")
+    {
+        return Some("the frontmatter block is never closed");
+    }
+    None
+}
+
+/// Returns the path a malformed diary file is moved to: `<name>.bak-<timestamp>`.
+///
+/// The timestamp is `YYYYMMDDTHHMMSS` in UTC, so several broken copies of the
+/// same day sort in order. The `.bak-` suffix hides the copy from Obsidian,
+/// which only indexes `.md` files.
+///
+/// # Examples
+///
+/// ```ignore
+/// use chrono::{TimeZone, Utc};
+/// use rusty_commit_saver::quarantine::quarantine_destination;
+/// use std::path::Path;
+///
+/// let now = Utc.with_ymd_and_hms(2025, 1, 14, 10, 30, 0).unwrap();
+/// assert_eq!(
+///     quarantine_destination(Path::new("/vault/2025-01-14.md"), now),
+///     Path::new("/vault/2025-01-14.md.bak-20250114T103000")
+/// );
+/// ```
+#[must_use]
+pub fn quarantine_destination(file: &Path, now: DateTime<Utc>) -> PathBuf {
+    let name = file
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    file.with_file_name(format!("{name:}.bak-{:}", now.format("%Y%m%dT%H%M%S")))
+}
+
+/// Moves a malformed diary file aside so a fresh one can be created.
+///
+/// A broken note would otherwise make every commit of that day fail in the
+/// hook. The file is renamed next to itself (see [`quarantine_destination()`])
+/// rather than deleted, so nothing already logged is lost.
+///
+/// # Returns
+///
+/// - `Ok(Some(path))` - The file was malformed and moved to `path`
+/// - `Ok(None)` - The file is fine, or does not exist
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or renamed.
+pub fn quarantine_if_malformed(
+    file: &Path,
+    now: DateTime<Utc>,
+) -> Result<Option<PathBuf>, Box<dyn Error>> {
+    if !file.is_file() {
+        return Ok(None);
+    }
+    let Some(problem) = diary_file_problem(&fs::read(file)?) else {
+        return Ok(None);
+    };
+
+    let destination = quarantine_destination(file, now);
+    warn!(
+        "[quarantine_if_malformed()]: Cannot use {:} ({problem:}), moving it to: {:}",
+        file.display(),
+        destination.display()
+    );
+    fs::rename(file, &destination)?;
+    Ok(Some(destination))
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod quarantine_tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_diary_file_problem() {
+        assert_eq!(diary_file_problem(b""), None);
+        assert_eq!(diary_file_problem(b"# Notes\n---\n"), None);
+        assert_eq!(
+            diary_file_problem(b"---\ntags:\n- '#diary/commits'\n---\n| a |\n"),
+            None
+        );
+        assert_eq!(
+            diary_file_problem(b"---\ntags:\n- '#diary/commits'\n| a |\n"),
+            Some("the frontmatter block is never closed")
+        );
+        assert_eq!(
+            diary_file_problem(&[0x89, b'P', b'N', b'G']),
+            Some("the file is not valid UTF-8")
+        );
+        assert_eq!(
+            diary_file_problem(b"---\n\0\0\0"),
+            Some("the file contains binary data")
+        );
+    }
+
+    #[test]
+    fn test_quarantine_if_malformed() {
+        let temp_dir = tempdir().unwrap();
+        let now = Utc.with_ymd_and_hms(2025, 1, 14, 10, 30, 0).unwrap();
+        let diary = temp_dir.path().join("2025-01-14.md");
+
+        assert_eq!(quarantine_if_malformed(&diary, now).unwrap(), None);
+
+        fs::write(&diary, "---\ntags:\n---\n").unwrap();
+        assert_eq!(quarantine_if_malformed(&diary, now).unwrap(), None);
+        assert!(diary.exists());
+
+        fs::write(&diary, [0xff, 0x00, 0xfe]).unwrap();
+        let moved = quarantine_if_malformed(&diary, now).unwrap().unwrap();
+        assert_eq!(
+            moved,
+            temp_dir.path().join("2025-01-14.md.bak-20250114T103000")
+        );
+        assert!(!diary.exists());
+        assert_eq!(fs::read(moved).unwrap(), [0xff, 0x00, 0xfe]);
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::DateTime;
+use chrono::TimeDelta;
+use chrono::Utc;
+use git2::Repository;
+use git2::Sort;
+use log::info;
+
+use crate::state::LoggedEntry;
+use crate::vim_commit::origin_url;
+
+/// How far back `log-recent` looks in repositories with no logged commit yet, in days.
+pub const DEFAULT_LOOKBACK_DAYS: i64 = 7;
+
+/// How many directory levels below the projects directory are scanned for repositories.
+const MAX_SCAN_DEPTH: usize = 3;
+
+/// Finds the Git repositories under a projects directory.
+///
+/// Looks up to three levels deep (e.g. `~/Projects/<forge>/<owner>/<repo>`)
+/// for directories holding a `.git` directory or file (worktrees and
+/// submodules use a file). Repositories are not descended into, and hidden
+/// directories and symbolic links are skipped.
+///
+/// # Returns
+///
+/// The repository directories, sorted.
+///
+/// # Errors
+///
+/// Returns an error if the projects directory cannot be read.
+pub fn find_repositories(projects_dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut repositories = Vec::new();
+    let mut pending = vec![(projects_dir.to_path_buf(), 0)];
+
+    while let Some((directory, depth)) = pending.pop() {
+        if directory.join(".git").exists() {
+            repositories.push(directory);
+            continue;
+        }
+        if depth == MAX_SCAN_DEPTH {
+            continue;
+        }
+
+        let entries = match fs::read_dir(&directory) {
+            Ok(entries) => entries,
+            Err(e) if depth == 0 => return Err(e.into()),
+            Err(_) => continue,
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let is_hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if !is_hidden && entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+                pending.push((entry.path(), depth + 1));
+            }
+        }
+    }
+
+    repositories.sort();
+    info!(
+        "[find_repositories()]: Found {:} repositories in: {:}",
+        repositories.len(),
+        projects_dir.display()
+    );
+    Ok(repositories)
+}
+
+/// Lists the commits of a repository that were made since its last logged one.
+///
+/// Walks the history of `HEAD`, newest first, back to the last commit of this
+/// repository (same `origin` URL) in the logged-commits index, and never
+/// further back than `lookback` from `now`. Commits already in the index are
+/// left out, and so are commits by other authors when `user.email` is set,
+/// since the post-commit hook would only have logged the user's own commits.
+///
+/// # Returns
+///
+/// The hashes of the commits to log, newest first (the `git rev-list` order
+/// [`collect_commit_batch()`](crate::batch::collect_commit_batch) expects).
+///
+/// # Errors
+///
+/// Returns an error if the history of `HEAD` cannot be walked.
+pub fn recent_unlogged_commits(
+    git_repo: &Repository,
+    logged_entries: &[LoggedEntry],
+    now: DateTime<Utc>,
+    lookback: TimeDelta,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let repository_url = origin_url(git_repo);
+    let last_logged = logged_entries
+        .iter()
+        .filter(|entry| entry.repository_url == repository_url)
+        .map(|entry| entry.commit_datetime)
+        .max();
+    let since = last_logged.map_or(now - lookback, |last| last.max(now - lookback));
+    let logged_hashes = logged_entries
+        .iter()
+        .map(|entry| entry.commit_hash.as_str())
+        .collect::<HashSet<_>>();
+    let author_email = git_repo
+        .config()
+        .and_then(|config| config.get_string("user.email"))
+        .ok();
+
+    info!("[recent_unlogged_commits()]: Looking for commits of {repository_url:} since {since:}");
+    let mut revwalk = git_repo.revwalk()?;
+    if revwalk.push_head().is_err() {
+        return Ok(Vec::new());
+    }
+    revwalk.set_sorting(Sort::TIME)?;
+
+    let mut commits = Vec::new();
+    for oid in revwalk {
+        let commit = git_repo.find_commit(oid?)?;
+        if commit.time().seconds() <= since.timestamp() {
+            break;
+        }
+        let hash = commit.id().to_string();
+        let by_user = author_email
+            .as_deref()
+            .is_none_or(|email| commit.author().email().ok() == Some(email));
+        if by_user && !logged_hashes.contains(hash.as_str()) {
+            commits.push(hash);
+        }
+    }
+    Ok(commits)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod recent_tests {
+    use super::*;
+    use crate::testing::FakeRepo;
+    use crate::testing::LoggedEntryBuilder;
+    use chrono::TimeZone;
+    use tempfile::tempdir;
+
+    const REPO_URL: &str = "https://github.com/user/repo.git";
+
+    fn commit_by(repo: &FakeRepo, email: &str, seconds: i64) -> Result<String, Box<dyn Error>> {
+        let datetime = Utc.timestamp_opt(seconds, 0).unwrap();
+        Ok(repo.commit_by("work", email, datetime)?.to_string())
+    }
+
+    #[test]
+    fn test_find_repositories() {
+        let projects = tempdir().unwrap();
+        for repository in [
+            "api",
+            "github.com/acme/web",
+            ".cache/hidden",
+            "a/b/c/too-deep",
+        ] {
+            fs::create_dir_all(projects.path().join(repository).join(".git")).unwrap();
+        }
+        fs::create_dir_all(projects.path().join("api/vendor/dep/.git")).unwrap();
+        fs::create_dir_all(projects.path().join("notes")).unwrap();
+        fs::create_dir_all(projects.path().join("worktree")).unwrap();
+        fs::write(projects.path().join("worktree/.git"), "gitdir: ../api/.git").unwrap();
+
+        assert_eq!(
+            find_repositories(projects.path()).unwrap(),
+            vec![
+                projects.path().join("api"),
+                projects.path().join("github.com/acme/web"),
+                projects.path().join("worktree"),
+            ]
+        );
+        assert!(find_repositories(&projects.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_recent_unlogged_commits_stops_at_the_last_logged_commit() -> Result<(), Box<dyn Error>>
+    {
+        let repo = FakeRepo::new()?.with_origin(REPO_URL)?;
+        repo.repo()
+            .config()?
+            .set_str("user.email", "me@example.com")?;
+        let now = Utc.with_ymd_and_hms(2025, 1, 20, 12, 0, 0).unwrap();
+        let hour = 3_600;
+        let base = now.timestamp() - 48 * hour;
+
+        let old = commit_by(&repo, "me@example.com", base)?;
+        let logged_commit = commit_by(&repo, "me@example.com", base + hour)?;
+        let newer = commit_by(&repo, "me@example.com", base + 2 * hour)?;
+        commit_by(&repo, "someone@example.com", base + 3 * hour)?;
+        let newest = commit_by(&repo, "me@example.com", base + 4 * hour)?;
+
+        let entries = [LoggedEntryBuilder::new(&logged_commit)
+            .at(Utc.timestamp_opt(base + hour, 0).unwrap())
+            .build()];
+        assert_eq!(
+            recent_unlogged_commits(repo.repo(), &entries, now, TimeDelta::days(7))?,
+            vec![newest.clone(), newer]
+        );
+
+        let lookback = TimeDelta::hours(45);
+        assert_eq!(
+            recent_unlogged_commits(repo.repo(), &[], now, lookback)?,
+            vec![newest]
+        );
+        assert_eq!(
+            recent_unlogged_commits(repo.repo(), &[], now, TimeDelta::days(7))?.last(),
+            Some(&old)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_recent_unlogged_commits_of_an_empty_repository() -> Result<(), Box<dyn Error>> {
+        let repo = FakeRepo::new()?;
+
+        assert!(
+            recent_unlogged_commits(repo.repo(), &[], Utc::now(), TimeDelta::days(7))?.is_empty()
+        );
+        Ok(())
+    }
+}
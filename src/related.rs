@@ -0,0 +1,254 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use chrono::DateTime;
+use chrono::Utc;
+use log::info;
+
+use crate::atomic_write::write_atomically;
+use crate::hydration::ensure_hydrated;
+use crate::hydration::HYDRATION_ATTEMPTS;
+use crate::hydration::HYDRATION_RETRY_DELAY;
+use crate::paths::repository_name_from_url;
+use crate::state::LoggedEntry;
+
+/// Default path of the related repositories note, relative to the vault root.
+pub const DEFAULT_RELATED_REPOS_NOTE: &str = "Related Repos.md";
+
+/// How often two repositories were worked on together.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CoOccurrence {
+    /// Days with logged commits in both repositories.
+    pub days: usize,
+    /// Clock hours (UTC) with logged commits in both repositories.
+    pub hours: usize,
+}
+
+/// Co-occurrences of the repositories in the logged-commits index.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RelatedRepos {
+    /// Co-occurrence of each pair of repository names, the pair sorted by name.
+    pub pairs: BTreeMap<(String, String), CoOccurrence>,
+    /// Days with at least one logged commit.
+    pub active_days: usize,
+    /// Days with logged commits in more than one repository.
+    pub multi_repo_days: usize,
+}
+
+impl RelatedRepos {
+    /// Counts which repositories were worked on the same day and the same hour.
+    ///
+    /// Repositories are named with [`repository_name_from_url()`], and days and
+    /// hours follow the UTC dates the diary files are named after.
+    #[must_use]
+    pub fn from_entries(entries: &[LoggedEntry]) -> Self {
+        let mut days: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let mut hours: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for entry in entries {
+            let name = repository_name_from_url(&entry.repository_url);
+            days.entry(entry.commit_datetime.format("%F").to_string())
+                .or_default()
+                .insert(name.clone());
+            hours
+                .entry(entry.commit_datetime.format("%F %H").to_string())
+                .or_default()
+                .insert(name);
+        }
+
+        let mut pairs: BTreeMap<(String, String), CoOccurrence> = BTreeMap::new();
+        for repositories in days.values() {
+            for pair in name_pairs(repositories) {
+                pairs.entry(pair).or_default().days += 1;
+            }
+        }
+        for repositories in hours.values() {
+            for pair in name_pairs(repositories) {
+                pairs.entry(pair).or_default().hours += 1;
+            }
+        }
+
+        RelatedRepos {
+            pairs,
+            active_days: days.len(),
+            multi_repo_days: days
+                .values()
+                .filter(|repositories| repositories.len() > 1)
+                .count(),
+        }
+    }
+
+    /// Renders the related repositories note.
+    ///
+    /// Pairs are listed by the number of shared days, then shared hours. The
+    /// repository names are wikilinks, so the pairs also show up in
+    /// Obsidian's graph view when the repositories have notes of their own.
+    ///
+    /// # Output Format
+    ///
+    /// ```text
+    /// ---
+    /// updated: 2025-01-14T18:00:00+00:00
+    /// ---
+    /// # Related Repos
+    ///
+    /// 12 of 40 days with logged commits touched more than one repository.
+    ///
+    /// | REPOSITORY | REPOSITORY | DAYS | HOURS |
+    /// |------------|------------|------|-------|
+    /// | [[api]] | [[web]] | 9 | 4 |
+    /// ```
+    #[must_use]
+    pub fn render_note(&self, now: DateTime<Utc>) -> String {
+        let mut note = format!(
+            "---\nupdated: {:}\n---\n# Related Repos\n\n{:} of {:} days with logged commits touched more than one repository.\n\n",
+            now.to_rfc3339(),
+            self.multi_repo_days,
+            self.active_days
+        );
+        if self.pairs.is_empty() {
+            note.push_str("No repositories were worked on together yet.\n");
+            return note;
+        }
+
+        let mut pairs = self.pairs.iter().collect::<Vec<_>>();
+        pairs.sort_by(|(a_names, a), (b_names, b)| {
+            b.days
+                .cmp(&a.days)
+                .then_with(|| b.hours.cmp(&a.hours))
+                .then_with(|| a_names.cmp(b_names))
+        });
+        note.push_str("| REPOSITORY | REPOSITORY | DAYS | HOURS |\n");
+        note.push_str("|------------|------------|------|-------|\n");
+        for ((first, second), co_occurrence) in pairs {
+            let _ = writeln!(
+                note,
+                "| [[{first:}]] | [[{second:}]] | {:} | {:} |",
+                co_occurrence.days, co_occurrence.hours
+            );
+        }
+        note
+    }
+}
+
+/// Rewrites the related repositories note from the logged-commits index.
+///
+/// The note is generated as a whole on every save, so it should not be
+/// edited by hand. Its directories are created when missing.
+///
+/// # Errors
+///
+/// Returns an error if the note is a cloud placeholder that cannot be
+/// hydrated, or if it cannot be written.
+pub fn update_related_repos_note(
+    note_path: &Path,
+    entries: &[LoggedEntry],
+    now: DateTime<Utc>,
+) -> Result<RelatedRepos, Box<dyn Error>> {
+    ensure_hydrated(note_path, HYDRATION_ATTEMPTS, HYDRATION_RETRY_DELAY)?;
+    let related = RelatedRepos::from_entries(entries);
+    if let Some(parent) = note_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    info!(
+        "[update_related_repos_note()]: Writing {:} repository pair(s) to: {:}",
+        related.pairs.len(),
+        note_path.display()
+    );
+    write_atomically(note_path, related.render_note(now))?;
+    Ok(related)
+}
+
+/// Lists every pair of names in a sorted set, each pair sorted too.
+fn name_pairs(names: &BTreeSet<String>) -> Vec<(String, String)> {
+    let names = names.iter().collect::<Vec<_>>();
+    let mut pairs = Vec::new();
+    for (index, first) in names.iter().enumerate() {
+        for second in &names[index + 1..] {
+            pairs.push(((*first).clone(), (*second).clone()));
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod related_tests {
+    use super::*;
+    use crate::testing::LoggedEntryBuilder;
+    use chrono::TimeZone;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_from_entries_counts_shared_days_and_hours() {
+        let entries = [
+            ("api", 14, 9),
+            ("web", 14, 9),
+            ("docs", 14, 16),
+            ("api", 15, 10),
+            ("web", 15, 14),
+            ("api", 16, 10),
+        ]
+        .map(|(repository, day, hour)| {
+            LoggedEntryBuilder::new(&format!("{repository:}{day:}{hour:}"))
+                .in_repository(&format!("https://github.com/user/{repository:}.git"))
+                .at(Utc.with_ymd_and_hms(2025, 1, day, hour, 15, 0).unwrap())
+                .build()
+        });
+
+        let related = RelatedRepos::from_entries(&entries);
+
+        let pair = |a: &str, b: &str| (a.to_string(), b.to_string());
+        assert_eq!(
+            related.pairs[&pair("api", "web")],
+            CoOccurrence { days: 2, hours: 1 }
+        );
+        assert_eq!(
+            related.pairs[&pair("api", "docs")],
+            CoOccurrence { days: 1, hours: 0 }
+        );
+        assert_eq!(related.pairs.len(), 3);
+        assert_eq!(related.active_days, 3);
+        assert_eq!(related.multi_repo_days, 2);
+    }
+
+    #[test]
+    fn test_update_related_repos_note() {
+        let temp_dir = tempdir().unwrap();
+        let note_path = temp_dir.path().join("Rollups/Related Repos.md");
+        let now = Utc.with_ymd_and_hms(2025, 1, 16, 18, 0, 0).unwrap();
+        let entries =
+            [("api", 14, 9), ("web", 14, 9), ("docs", 14, 16)].map(|(repository, day, hour)| {
+                LoggedEntryBuilder::new(&format!("{repository:}{day:}{hour:}"))
+                    .in_repository(&format!("https://github.com/user/{repository:}.git"))
+                    .at(Utc.with_ymd_and_hms(2025, 1, day, hour, 15, 0).unwrap())
+                    .build()
+            });
+
+        update_related_repos_note(&note_path, &entries, now).unwrap();
+
+        let note = fs::read_to_string(&note_path).unwrap();
+        assert!(note.starts_with("---\nupdated: 2025-01-16T18:00:00+00:00\n---\n"));
+        assert!(note.contains("1 of 1 days with logged commits touched more than one repository."));
+        assert!(note.contains(
+            "|------------|------------|------|-------|\n| [[api]] | [[web]] | 1 | 1 |\n| [[api]] | [[docs]] | 1 | 0 |\n"
+        ));
+    }
+
+    #[test]
+    fn test_render_note_without_pairs() {
+        let now = Utc.with_ymd_and_hms(2025, 1, 16, 18, 0, 0).unwrap();
+
+        let note = RelatedRepos::from_entries(&[LoggedEntryBuilder::new("api149")
+            .in_repository("https://github.com/user/api.git")
+            .at(Utc.with_ymd_and_hms(2025, 1, 14, 9, 15, 0).unwrap())
+            .build()])
+        .render_note(now);
+
+        assert!(note.ends_with("No repositories were worked on together yet.\n"));
+    }
+}
@@ -0,0 +1,318 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::Datelike;
+use chrono::NaiveDate;
+use log::info;
+
+use crate::diary_parser::parse_diary_table;
+use crate::diary_parser::read_diary_snapshot;
+use crate::same_day::diary_files_by_date;
+
+/// How many of the largest days `report --vault` lists.
+pub const LARGEST_DAYS_SHOWN: usize = 5;
+
+/// Years older than this many years before the current one are suggested for archiving.
+pub const ARCHIVE_AFTER_YEARS: i32 = 1;
+
+/// Size and entry count of one diary file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiaryFileUsage {
+    pub path: PathBuf,
+    /// The frontmatter `date` of the diary file.
+    pub date: String,
+    pub bytes: u64,
+    /// Table rows, or fenced YAML blocks for `entry_format = yaml`.
+    pub entries: usize,
+}
+
+/// Totals of a group of diary files (the whole diary, or one year).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UsageTotals {
+    pub files: usize,
+    pub bytes: u64,
+    pub entries: usize,
+}
+
+impl UsageTotals {
+    fn add(&mut self, file: &DiaryFileUsage) {
+        self.files += 1;
+        self.bytes += file.bytes;
+        self.entries += file.entries;
+    }
+}
+
+/// How much of the vault the commit diary occupies.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VaultReport {
+    /// Every diary file found, sorted by date.
+    pub files: Vec<DiaryFileUsage>,
+}
+
+impl VaultReport {
+    /// Totals over every diary file.
+    #[must_use]
+    pub fn totals(&self) -> UsageTotals {
+        let mut totals = UsageTotals::default();
+        for file in &self.files {
+            totals.add(file);
+        }
+        totals
+    }
+
+    /// Totals per year, keyed by the year of the diary file's `date`.
+    #[must_use]
+    pub fn years(&self) -> BTreeMap<String, UsageTotals> {
+        let mut years: BTreeMap<String, UsageTotals> = BTreeMap::new();
+        for file in &self.files {
+            let year = file.date.get(..4).unwrap_or(&file.date).to_string();
+            years.entry(year).or_default().add(file);
+        }
+        years
+    }
+
+    /// The `count` largest diary files, largest first.
+    #[must_use]
+    pub fn largest_days(&self, count: usize) -> Vec<&DiaryFileUsage> {
+        let mut files = self.files.iter().collect::<Vec<_>>();
+        files.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.date.cmp(&b.date)));
+        files.truncate(count);
+        files
+    }
+
+    /// The years that could be moved out of the vault: every year older than
+    /// [`ARCHIVE_AFTER_YEARS`] before `today`'s.
+    #[must_use]
+    pub fn archive_candidates(&self, today: NaiveDate) -> Vec<(String, UsageTotals)> {
+        let newest_kept = today.year() - ARCHIVE_AFTER_YEARS;
+        self.years()
+            .into_iter()
+            .filter(|(year, _)| year.parse::<i32>().is_ok_and(|year| year < newest_kept))
+            .collect()
+    }
+}
+
+/// Measures the diary files under the given commits roots.
+///
+/// Diary files are found like in [`diary_files_by_date()`] (by their
+/// frontmatter `date`, so every `commit_date_path` layout is covered) and
+/// their entries are counted with [`parse_diary_table()`].
+///
+/// # Errors
+///
+/// Returns an error if a commits root or a diary file cannot be read.
+pub fn collect_vault_report(commits_roots: &[PathBuf]) -> Result<VaultReport, Box<dyn Error>> {
+    let mut files = Vec::new();
+    for commits_root in commits_roots {
+        for (date, paths) in diary_files_by_date(commits_root)? {
+            for path in paths {
+                let content = read_diary_snapshot(&path)?;
+                files.push(DiaryFileUsage {
+                    bytes: content.len() as u64,
+                    entries: count_entries(&content),
+                    date: date.clone(),
+                    path,
+                });
+            }
+        }
+    }
+    files.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.path.cmp(&b.path)));
+
+    info!(
+        "[collect_vault_report()]: Measured {:} diary file(s).",
+        files.len()
+    );
+    Ok(VaultReport { files })
+}
+
+/// Renders the `report --vault` output.
+///
+/// Paths are shown relative to the vault root.
+///
+/// # Output Format
+///
+/// ```text
+/// Commit diary: 412 files, 3.4 MiB, 5120 entries
+///
+/// By year:
+///   2024   180 files    1.5 MiB   2210 entries
+///   2025   232 files    1.9 MiB   2910 entries
+///
+/// Largest days:
+///   2025-03-14   48.2 KiB    310 entries  Diaries/Commits/2025/2025-03-14.md
+///
+/// Archive candidates (older than 2025):
+///   2024: 180 files, 1.5 MiB
+/// ```
+#[must_use]
+pub fn render_vault_report(
+    report: &VaultReport,
+    obsidian_root_path_dir: &Path,
+    today: NaiveDate,
+) -> String {
+    let totals = report.totals();
+    if totals.files == 0 {
+        return "No diary files in the vault yet.\n".to_string();
+    }
+
+    let mut output = format!(
+        "Commit diary: {:} files, {:}, {:} entries\n\nBy year:\n",
+        totals.files,
+        format_size(totals.bytes),
+        totals.entries
+    );
+    for (year, year_totals) in report.years() {
+        let _ = writeln!(
+            output,
+            "  {year:<5} {:>5} files  {:>9}  {:>5} entries",
+            year_totals.files,
+            format_size(year_totals.bytes),
+            year_totals.entries
+        );
+    }
+
+    output.push_str("\nLargest days:\n");
+    for file in report.largest_days(LARGEST_DAYS_SHOWN) {
+        let path = file
+            .path
+            .strip_prefix(obsidian_root_path_dir)
+            .unwrap_or(&file.path);
+        let _ = writeln!(
+            output,
+            "  {:<10}  {:>9}  {:>5} entries  {:}",
+            file.date,
+            format_size(file.bytes),
+            file.entries,
+            path.display()
+        );
+    }
+
+    let candidates = report.archive_candidates(today);
+    let _ = writeln!(
+        output,
+        "\nArchive candidates (older than {:}):",
+        today.year() - ARCHIVE_AFTER_YEARS
+    );
+    if candidates.is_empty() {
+        output.push_str("  none\n");
+    }
+    for (year, year_totals) in candidates {
+        let _ = writeln!(
+            output,
+            "  {year:}: {:} files, {:}",
+            year_totals.files,
+            format_size(year_totals.bytes)
+        );
+    }
+    output
+}
+
+/// Counts the entries of a diary file: its table rows, or its fenced YAML
+/// blocks when it holds no table.
+fn count_entries(content: &str) -> usize {
+    parse_diary_table(content).map_or_else(
+        || {
+            content
+                .lines()
+                .filter(|line| line.trim_end() == "```yaml")
+                .count()
+        },
+        |table| table.rows.len(),
+    )
+}
+
+/// Formats a byte count with binary units and one decimal, e.g. `48.2 KiB`.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 3] = ["KiB", "MiB", "GiB"];
+    if bytes < 1024 {
+        return format!("{bytes:} B");
+    }
+
+    let mut divisor = 1024;
+    let mut unit = 0;
+    while bytes >= divisor * 1024 && unit < UNITS.len() - 1 {
+        divisor *= 1024;
+        unit += 1;
+    }
+    let tenths = (bytes * 10 + divisor / 2) / divisor;
+    format!("{:}.{:} {:}", tenths / 10, tenths % 10, UNITS[unit])
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod report_tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn write_diary(root: &Path, date: &str, rows: usize) -> PathBuf {
+        let path = root.join(format!("{:}/{date:}.md", &date[..4]));
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let mut content = format!("---\ndate: {date:}\n---\n| TIME | COMMIT HASH |\n|---|---|\n");
+        for row in 0..rows {
+            let _ = writeln!(content, "| 10:00:00 | abc{row:} |");
+        }
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(49_357), "48.2 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MiB");
+    }
+
+    #[test]
+    fn test_count_entries_of_tables_and_yaml_blocks() {
+        assert_eq!(count_entries("| A |\n|---|\n| 1 |\n| 2 |\n"), 2);
+        assert_eq!(
+            count_entries("```yaml\na: 1\n```\n\n```yaml\na: 2\n```\n"),
+            2
+        );
+        assert_eq!(count_entries("---\ndate: 2025-01-14\n---\n"), 0);
+    }
+
+    #[test]
+    fn test_collect_vault_report_totals_years_and_largest_days() {
+        let vault = tempdir().unwrap();
+        let root = vault.path().join("Commits");
+        write_diary(&root, "2023-05-01", 1);
+        write_diary(&root, "2024-02-10", 2);
+        let largest = write_diary(&root, "2025-01-14", 30);
+        write_diary(&root, "2025-01-15", 3);
+
+        let report = collect_vault_report(&[root.clone(), vault.path().join("Missing")]).unwrap();
+
+        assert_eq!(report.files.len(), 4);
+        assert_eq!(report.totals().entries, 36);
+        let years = report.years();
+        assert_eq!(years.keys().collect::<Vec<_>>(), ["2023", "2024", "2025"]);
+        assert_eq!(years["2025"].files, 2);
+        assert_eq!(report.largest_days(1)[0].path, largest);
+
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let candidates = report.archive_candidates(today);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].0, "2023");
+
+        let output = render_vault_report(&report, vault.path(), today);
+        assert!(output.starts_with("Commit diary: 4 files, "));
+        assert!(output.contains("36 entries\n"));
+        assert!(output.contains("Commits/2025/2025-01-14.md\n"));
+        assert!(output.contains("Archive candidates (older than 2024):\n  2023: 1 files, "));
+    }
+
+    #[test]
+    fn test_render_vault_report_of_an_empty_vault() {
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+
+        assert_eq!(
+            render_vault_report(&VaultReport::default(), Path::new("/vault"), today),
+            "No diary files in the vault yet.\n"
+        );
+    }
+}
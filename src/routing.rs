@@ -0,0 +1,166 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use log::info;
+
+use crate::category::wildcard_match;
+
+/// Separator between the URL pattern and the commit path of a route.
+const ROUTE_SEPARATOR: &str = "->";
+
+/// A route sending the commits of matching repositories to another commit path.
+///
+/// # Configuration
+///
+/// Each key of the `[routing]` section names a route, its value is a remote
+/// URL pattern and a commit path (relative to the vault root) separated by `->`:
+///
+/// ```text
+/// [routing]
+/// work = github.com/acme/* -> Work/Commits
+/// personal = github.com/me/* -> Personal/Commits
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    pub pattern: String,
+    pub commit_path: PathBuf,
+}
+
+/// Parses a `pattern -> commit_path` route value.
+///
+/// # Returns
+///
+/// `None` if the separator is missing or either side is empty.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::routing::parse_route;
+///
+/// let route = parse_route("github.com/acme/* -> Work/Commits").unwrap();
+/// assert_eq!(route.pattern, "github.com/acme/*");
+/// ```
+#[must_use]
+pub fn parse_route(value: &str) -> Option<Route> {
+    let (pattern, commit_path) = value.split_once(ROUTE_SEPARATOR)?;
+    let (pattern, commit_path) = (pattern.trim(), commit_path.trim());
+    if pattern.is_empty() || commit_path.is_empty() {
+        return None;
+    }
+
+    Some(Route {
+        pattern: pattern.to_string(),
+        commit_path: PathBuf::from(commit_path),
+    })
+}
+
+/// Returns the `host/path` part of a remote URL.
+///
+/// Drops the scheme and user (`https://`, `ssh://git@`, `git@`) and turns the
+/// `host:path` form of SSH remotes into `host/path`, so one pattern like
+/// `github.com/acme/*` covers HTTPS and SSH remotes alike.
+fn remote_host_path(url: &str) -> String {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let without_user = without_scheme
+        .split_once('@')
+        .map_or(without_scheme, |(_, rest)| rest);
+
+    if url.contains("://") {
+        without_user.to_string()
+    } else {
+        without_user.replacen(':', "/", 1)
+    }
+}
+
+/// Finds the commit path for a repository from the routing table.
+///
+/// Patterns are matched against the full remote URL and against its
+/// `host/path` form. When several routes match, the longest pattern wins, as
+/// it is the most specific one; ties go to the route listed first.
+///
+/// # Returns
+///
+/// The routed commit path, or `None` to keep `[obsidian] commit_path`.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::routing::{parse_route, route_commit_path};
+///
+/// let routes = vec![parse_route("github.com/acme/* -> Work/Commits").unwrap()];
+/// let commit_path = route_commit_path(&routes, "git@github.com:acme/api.git");
+/// assert_eq!(commit_path.unwrap(), std::path::Path::new("Work/Commits"));
+/// ```
+#[must_use]
+pub fn route_commit_path<'a>(routes: &'a [Route], repository_url: &str) -> Option<&'a Path> {
+    let host_path = remote_host_path(repository_url);
+    let mut best: Option<&Route> = None;
+    for route in routes {
+        let matches = wildcard_match(&route.pattern, repository_url)
+            || wildcard_match(&route.pattern, &host_path);
+        let more_specific = best.is_none_or(|best| route.pattern.len() > best.pattern.len());
+        if matches && more_specific {
+            best = Some(route);
+        }
+    }
+
+    let commit_path = best.map(|route| route.commit_path.as_path());
+    info!("[route_commit_path()]: Route for {repository_url:}: {commit_path:?}");
+    commit_path
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod routing_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_route() {
+        assert_eq!(
+            parse_route(" github.com/acme/*  ->  Work/Commits "),
+            Some(Route {
+                pattern: "github.com/acme/*".to_string(),
+                commit_path: PathBuf::from("Work/Commits"),
+            })
+        );
+        assert_eq!(parse_route("github.com/acme/* Work/Commits"), None);
+        assert_eq!(parse_route("-> Work/Commits"), None);
+    }
+
+    #[test]
+    fn test_remote_host_path() {
+        assert_eq!(
+            remote_host_path("https://github.com/acme/api.git"),
+            "github.com/acme/api.git"
+        );
+        assert_eq!(
+            remote_host_path("git@github.com:acme/api.git"),
+            "github.com/acme/api.git"
+        );
+        assert_eq!(
+            remote_host_path("ssh://git@gitlab.com:2222/acme/api.git"),
+            "gitlab.com:2222/acme/api.git"
+        );
+    }
+
+    #[test]
+    fn test_route_commit_path_prefers_most_specific_route() {
+        let routes = vec![
+            parse_route("github.com/* -> Personal/Commits").unwrap(),
+            parse_route("github.com/acme/* -> Work/Commits").unwrap(),
+        ];
+
+        assert_eq!(
+            route_commit_path(&routes, "git@github.com:acme/api.git"),
+            Some(Path::new("Work/Commits"))
+        );
+        assert_eq!(
+            route_commit_path(&routes, "https://github.com/me/tool.git"),
+            Some(Path::new("Personal/Commits"))
+        );
+        assert_eq!(
+            route_commit_path(&routes, "https://gitlab.com/me/tool.git"),
+            None
+        );
+    }
+}
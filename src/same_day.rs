@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use log::info;
+
+use crate::frontmatter::frontmatter_property;
+
+/// Collects the diary files under a commits root, grouped by their frontmatter `date`.
+///
+/// Every `.md` file in the directory tree is read, so files written with an
+/// older `commit_date_path` template are found too. Hidden directories (such
+/// as the vault `.trash`) and symbolic links are skipped, and so are files
+/// without a `date` property (overflow notes, unrelated notes). A missing
+/// commits root holds no diary files yet.
+///
+/// # Returns
+///
+/// The diary files of each date, sorted.
+///
+/// # Errors
+///
+/// Returns an error if the commits root or one of its directories cannot be read.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::same_day::diary_files_by_date;
+/// use std::path::Path;
+///
+/// let files = diary_files_by_date(Path::new("/vault/Diaries/Commits"))?;
+/// for (date, paths) in files.iter().filter(|(_, paths)| paths.len() > 1) {
+///     println!("{date}: {paths:?}");
+/// }
+/// ```
+pub fn diary_files_by_date(
+    commits_root: &Path,
+) -> Result<BTreeMap<String, Vec<PathBuf>>, Box<dyn Error>> {
+    let mut files_by_date: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    if !commits_root.is_dir() {
+        return Ok(files_by_date);
+    }
+
+    let mut pending = vec![commits_root.to_path_buf()];
+    while let Some(directory) = pending.pop() {
+        for entry in fs::read_dir(&directory)? {
+            let entry = entry?;
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let file_type = entry.file_type()?;
+            let path = entry.path();
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file() && path.extension().is_some_and(|ext| ext == "md") {
+                let Ok(content) = fs::read_to_string(&path) else {
+                    continue;
+                };
+                if let Some(date) = frontmatter_property(&content, "date") {
+                    files_by_date
+                        .entry(date.trim().to_string())
+                        .or_default()
+                        .push(path);
+                }
+            }
+        }
+    }
+
+    for paths in files_by_date.values_mut() {
+        paths.sort();
+    }
+    Ok(files_by_date)
+}
+
+/// Lists the diary files for `date` under a commits root, other than `diary_file`.
+///
+/// Used before creating a new diary file: a non-empty result means the day
+/// already has a file at another path, typically because `commit_date_path`
+/// changed since it was created.
+///
+/// # Errors
+///
+/// Returns an error if the commits root cannot be read, see [`diary_files_by_date()`].
+pub fn other_diary_files_for_day(
+    commits_root: &Path,
+    date: &str,
+    diary_file: &Path,
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let others = diary_files_by_date(commits_root)?
+        .remove(date)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|path| path != diary_file)
+        .collect::<Vec<_>>();
+
+    info!(
+        "[other_diary_files_for_day()]: Found {:} other diary file(s) for {date:}.",
+        others.len()
+    );
+    Ok(others)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod same_day_tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write_diary(root: &Path, relative_path: &str, date: &str) -> PathBuf {
+        let path = root.join(relative_path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, format!("---\ncategory: diary\ndate: {date:}\n---\n")).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_diary_files_by_date_groups_files_across_layouts() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        let old = write_diary(root, "2025/01-January/2025-01-14.md", "2025-01-14");
+        let new = write_diary(root, "2025/2025-01-14.md", "2025-01-14");
+        let other_day = write_diary(root, "2025/2025-01-15.md", "2025-01-15");
+        write_diary(root, ".trash/2025-01-14.md", "2025-01-14");
+        fs::write(root.join("2025/2025-01-14.overflow.md"), "## abc123\n").unwrap();
+
+        let files_by_date = diary_files_by_date(root).unwrap();
+
+        assert_eq!(files_by_date.len(), 2);
+        assert_eq!(files_by_date["2025-01-14"], vec![old.clone(), new.clone()]);
+        assert_eq!(files_by_date["2025-01-15"], vec![other_day]);
+
+        assert_eq!(
+            other_diary_files_for_day(root, "2025-01-14", &new).unwrap(),
+            vec![old]
+        );
+        assert!(other_diary_files_for_day(root, "2025-01-16", &new)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_diary_files_by_date_of_a_missing_root() {
+        let temp_dir = tempdir().unwrap();
+
+        assert!(diary_files_by_date(&temp_dir.path().join("missing"))
+            .unwrap()
+            .is_empty());
+    }
+}
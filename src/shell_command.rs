@@ -0,0 +1,112 @@
+use std::error::Error;
+use std::io::Read;
+use std::process::Command;
+use std::process::Output;
+use std::process::Stdio;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+use log::warn;
+
+/// Interval between checks whether a command has exited.
+const COMMAND_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Runs a shell command (`sh -c`) and returns its exit status and standard
+/// output, or `None` if it does not finish within `timeout` (it is killed
+/// then).
+///
+/// Standard output is read on a separate thread while the command runs. A
+/// pipe only holds a few kilobytes, so a command printing more than that
+/// would otherwise block on its write and never exit. Standard error is
+/// discarded.
+///
+/// # Errors
+///
+/// Returns an error if the command cannot be spawned or waited for.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::shell_command::run_with_timeout;
+///
+/// let output = run_with_timeout("echo client-a", Duration::from_secs(2))?;
+/// assert_eq!(output.unwrap().stdout, b"client-a\n");
+/// ```
+pub fn run_with_timeout(
+    command: &str,
+    timeout: Duration,
+) -> Result<Option<Output>, Box<dyn Error>> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    let mut stdout_pipe = child
+        .stdout
+        .take()
+        .ok_or("Command has no standard output")?;
+    let reader = thread::spawn(move || {
+        let mut stdout = Vec::new();
+        stdout_pipe.read_to_end(&mut stdout).map(|_| stdout)
+    });
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            let stdout = reader
+                .join()
+                .map_err(|_| "Could not read the command output")??;
+            return Ok(Some(Output {
+                status,
+                stdout,
+                stderr: Vec::new(),
+            }));
+        }
+        if Instant::now() >= deadline {
+            // The reader is left behind: a process started by the command
+            // may still hold the pipe open.
+            warn!("[run_with_timeout()]: Command timed out, killing it: {command:}");
+            child.kill()?;
+            child.wait()?;
+            return Ok(None);
+        }
+        thread::sleep(COMMAND_POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod shell_command_tests {
+    use super::*;
+
+    #[test]
+    fn test_run_with_timeout_returns_the_status_and_output() {
+        let output = run_with_timeout("echo done; exit 3", Duration::from_secs(5))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(output.status.code(), Some(3));
+        assert_eq!(output.stdout, b"done\n");
+    }
+
+    #[test]
+    fn test_run_with_timeout_drains_output_larger_than_the_pipe() {
+        let output = run_with_timeout("head -c 1000000 /dev/zero", Duration::from_secs(5))
+            .unwrap()
+            .unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(output.stdout.len(), 1_000_000);
+    }
+
+    #[test]
+    fn test_run_with_timeout_kills_slow_commands() {
+        assert!(run_with_timeout("sleep 5", Duration::from_millis(100))
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,474 @@
+use std::error::Error;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use log::info;
+use log::warn;
+
+use crate::commit_entry::CommitEntry;
+use crate::commit_entry::JsonRenderer;
+use crate::commit_entry::Renderer;
+
+/// An output commits are written to (`[output] sinks`).
+///
+/// # Variants
+///
+/// - `ObsidianMarkdown` - The diary files of the Obsidian vaults, the
+///   default
+/// - `Jsonl` - One JSON object per commit, appended to a file
+/// - `Sqlite` - A `commits` table of a `SQLite` database (`sqlite` feature)
+/// - `Stdout` - One JSON object per commit, printed on standard output
+///
+/// # Configuration
+///
+/// ```text
+/// [output]
+/// sinks = markdown, jsonl, sqlite
+/// jsonl_path = ~/Journal/commits.jsonl
+/// sqlite_path = ~/Journal/commits.db
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SinkKind {
+    ObsidianMarkdown,
+    Jsonl(PathBuf),
+    Sqlite(PathBuf),
+    Stdout,
+}
+
+impl SinkKind {
+    /// The name of the sink in `[output] sinks`.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            SinkKind::ObsidianMarkdown => "markdown",
+            SinkKind::Jsonl(_) => "jsonl",
+            SinkKind::Sqlite(_) => "sqlite",
+            SinkKind::Stdout => "stdout",
+        }
+    }
+}
+
+/// A destination for logged commits, fed with the [`CommitEntry`] of each
+/// commit.
+///
+/// The Obsidian diary is one too, built by the caller of [`open_sinks()`]
+/// around the [`CommitSaver`](crate::vim_commit::CommitSaver) it writes.
+///
+/// A commit is staged in every sink before it is written to any, so a sink
+/// that cannot take it stops the save while nothing is written yet.
+pub trait EntrySink {
+    /// The name of the sink, for logs.
+    fn name(&self) -> &'static str;
+
+    /// Prepares the write of one commit without writing it: renders it and
+    /// opens what it goes to. A commit that is staged but never written
+    /// leaves the sink as it was.
+    ///
+    /// Does nothing by default; [`write_entry()`](EntrySink::write_entry)
+    /// then does all the work.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the commit cannot be written.
+    fn stage_entry(&mut self, _entry: &CommitEntry) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Writes one commit, the one staged last if any.
+    ///
+    /// # Returns
+    ///
+    /// Whether the commit was written. A sink may pass on a commit (the
+    /// diary on a dry run or for a commit it already has), which then goes to
+    /// no later sink.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the commit cannot be written.
+    fn write_entry(&mut self, entry: &CommitEntry) -> Result<bool, Box<dyn Error>>;
+}
+
+impl<S: EntrySink + ?Sized> EntrySink for &mut S {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn stage_entry(&mut self, entry: &CommitEntry) -> Result<(), Box<dyn Error>> {
+        (**self).stage_entry(entry)
+    }
+
+    fn write_entry(&mut self, entry: &CommitEntry) -> Result<bool, Box<dyn Error>> {
+        (**self).write_entry(entry)
+    }
+}
+
+/// Appends each commit as a JSON line to a file, created with its
+/// directories when missing.
+///
+/// Staging renders the line and opens the file, so an unwritable file stops
+/// the save before the diary is written.
+#[derive(Debug)]
+pub struct JsonlSink {
+    pub path: PathBuf,
+    staged: Option<(fs::File, String)>,
+}
+
+impl JsonlSink {
+    /// A sink appending to `path`.
+    #[must_use]
+    pub fn new(path: PathBuf) -> Self {
+        JsonlSink { path, staged: None }
+    }
+}
+
+impl EntrySink for JsonlSink {
+    fn name(&self) -> &'static str {
+        "jsonl"
+    }
+
+    fn stage_entry(&mut self, entry: &CommitEntry) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let line = JsonRenderer.render(entry)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.staged = Some((file, line));
+        Ok(())
+    }
+
+    fn write_entry(&mut self, entry: &CommitEntry) -> Result<bool, Box<dyn Error>> {
+        if self.staged.is_none() {
+            self.stage_entry(entry)?;
+        }
+        if let Some((mut file, line)) = self.staged.take() {
+            file.write_all(line.as_bytes())?;
+        }
+        Ok(true)
+    }
+}
+
+/// Prints each commit as a JSON line on standard output, e.g. for `jq`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StdoutSink {
+    staged: Option<String>,
+}
+
+impl EntrySink for StdoutSink {
+    fn name(&self) -> &'static str {
+        "stdout"
+    }
+
+    fn stage_entry(&mut self, entry: &CommitEntry) -> Result<(), Box<dyn Error>> {
+        self.staged = Some(JsonRenderer.render(entry)?);
+        Ok(())
+    }
+
+    fn write_entry(&mut self, entry: &CommitEntry) -> Result<bool, Box<dyn Error>> {
+        let line = match self.staged.take() {
+            Some(line) => line,
+            None => JsonRenderer.render(entry)?,
+        };
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(line.as_bytes())?;
+        stdout.flush()?;
+        Ok(true)
+    }
+}
+
+/// Stores each commit in the `commits` table of a `SQLite` database, created
+/// with its table when missing. A commit logged again on the same branch
+/// replaces its row.
+///
+/// Staging inserts the row in a transaction that writing commits; a staged
+/// row that is never written is rolled back when the sink is dropped.
+///
+/// Only built with the `sqlite` feature.
+#[cfg(feature = "sqlite")]
+pub struct SqliteSink {
+    connection: rusqlite::Connection,
+    staged: bool,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteSink {
+    /// Opens the database, creating it and its `commits` table if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be opened or created.
+    pub fn open(path: &std::path::Path) -> Result<Self, Box<dyn Error>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let connection = rusqlite::Connection::open(path)?;
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS commits (
+                hash TEXT NOT NULL,
+                branch TEXT NOT NULL,
+                repository_url TEXT NOT NULL,
+                message TEXT NOT NULL,
+                datetime TEXT NOT NULL,
+                author TEXT,
+                author_email TEXT,
+                files_changed INTEGER,
+                insertions INTEGER,
+                deletions INTEGER,
+                parents TEXT NOT NULL,
+                PRIMARY KEY (hash, branch)
+            );",
+        )?;
+        Ok(SqliteSink {
+            connection,
+            staged: false,
+        })
+    }
+
+    /// Inserts or replaces the row of `entry`.
+    fn insert(&self, entry: &CommitEntry) -> Result<(), Box<dyn Error>> {
+        let stat = |field: fn(&crate::git_stats::DiffStats) -> usize| {
+            entry
+                .stats
+                .as_ref()
+                .map(|stats| i64::try_from(field(stats)))
+                .transpose()
+        };
+        self.connection.execute(
+            "INSERT OR REPLACE INTO commits (hash, branch, repository_url, message, datetime, author, author_email, files_changed, insertions, deletions, parents)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            rusqlite::params![
+                entry.hash,
+                entry.branch,
+                entry.repository_url,
+                entry.message,
+                entry.datetime.to_rfc3339(),
+                entry.author,
+                entry.author_email,
+                stat(|stats| stats.files_changed)?,
+                stat(|stats| stats.insertions)?,
+                stat(|stats| stats.deletions)?,
+                entry.parents.join(" "),
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl EntrySink for SqliteSink {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn stage_entry(&mut self, entry: &CommitEntry) -> Result<(), Box<dyn Error>> {
+        if self.staged {
+            self.connection.execute_batch("ROLLBACK")?;
+            self.staged = false;
+        }
+        self.connection.execute_batch("BEGIN IMMEDIATE")?;
+        if let Err(e) = self.insert(entry) {
+            self.connection.execute_batch("ROLLBACK")?;
+            return Err(e);
+        }
+        self.staged = true;
+        Ok(())
+    }
+
+    fn write_entry(&mut self, entry: &CommitEntry) -> Result<bool, Box<dyn Error>> {
+        if !self.staged {
+            self.stage_entry(entry)?;
+        }
+        self.staged = false;
+        self.connection.execute_batch("COMMIT")?;
+        Ok(true)
+    }
+}
+
+/// Opens the sinks of `kinds`, `diary` standing for
+/// [`SinkKind::ObsidianMarkdown`].
+///
+/// The diary comes first whatever the order of `kinds`, so a commit it does
+/// not log goes to no other sink. A sink that cannot be opened only logs a
+/// warning.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::sinks::open_sinks;
+///
+/// for mut sink in open_sinks(&save_settings.sinks, Box::new(&mut diary)) {
+///     sink.write_entry(&entry)?;
+/// }
+/// ```
+pub fn open_sinks<'a>(
+    kinds: &[SinkKind],
+    diary: Box<dyn EntrySink + 'a>,
+) -> Vec<Box<dyn EntrySink + 'a>> {
+    let mut diary = Some(diary);
+    let mut sinks: Vec<Box<dyn EntrySink + 'a>> = Vec::new();
+    for kind in kinds {
+        info!("[open_sinks()]: Opening the {:} sink.", kind.name());
+        let opened: Result<Box<dyn EntrySink + 'a>, Box<dyn Error>> = match kind {
+            SinkKind::ObsidianMarkdown => match diary.take() {
+                Some(diary) => {
+                    sinks.insert(0, diary);
+                    continue;
+                }
+                None => continue,
+            },
+            SinkKind::Jsonl(path) => Ok(Box::new(JsonlSink::new(path.clone()))),
+            SinkKind::Stdout => Ok(Box::new(StdoutSink::default())),
+            #[cfg(feature = "sqlite")]
+            SinkKind::Sqlite(path) => SqliteSink::open(path).map(|sink| Box::new(sink) as _),
+            #[cfg(not(feature = "sqlite"))]
+            SinkKind::Sqlite(_) => Err(
+                "the sqlite sink needs rusty-commit-saver built with the 'sqlite' feature".into(),
+            ),
+        };
+        match opened {
+            Ok(sink) => sinks.push(sink),
+            Err(e) => warn!(
+                "[open_sinks()]: Could not open the {:} sink: {e:}",
+                kind.name()
+            ),
+        }
+    }
+    sinks
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod sinks_tests {
+    use super::*;
+    use chrono::TimeZone;
+    use chrono::Utc;
+    use tempfile::tempdir;
+
+    fn entry(hash: &str) -> CommitEntry {
+        CommitEntry {
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: hash.to_string(),
+            message: "feat: add feature".to_string(),
+            datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 30, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_jsonl_sink_appends_one_line_per_entry() -> Result<(), Box<dyn Error>> {
+        let temp_dir = tempdir()?;
+        let path = temp_dir.path().join("journal/commits.jsonl");
+        let mut sink = JsonlSink::new(path.clone());
+
+        assert!(sink.write_entry(&entry("abc123"))?);
+        assert!(sink.write_entry(&entry("def456"))?);
+
+        let lines = fs::read_to_string(&path)?
+            .lines()
+            .map(serde_json::from_str::<CommitEntry>)
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(lines, vec![entry("abc123"), entry("def456")]);
+        Ok(())
+    }
+
+    /// Records the commits it gets, passing on those of `skip`.
+    struct FakeDiary {
+        written: Vec<String>,
+        skip: &'static str,
+    }
+
+    impl EntrySink for FakeDiary {
+        fn name(&self) -> &'static str {
+            "markdown"
+        }
+
+        fn write_entry(&mut self, entry: &CommitEntry) -> Result<bool, Box<dyn Error>> {
+            if entry.hash == self.skip {
+                return Ok(false);
+            }
+            self.written.push(entry.hash.clone());
+            Ok(true)
+        }
+    }
+
+    #[test]
+    fn test_open_sinks_puts_the_diary_first() -> Result<(), Box<dyn Error>> {
+        let temp_dir = tempdir()?;
+        let mut diary = FakeDiary {
+            written: Vec::new(),
+            skip: "def456",
+        };
+        let mut sinks = open_sinks(
+            &[
+                SinkKind::Jsonl(temp_dir.path().join("commits.jsonl")),
+                SinkKind::ObsidianMarkdown,
+                SinkKind::Stdout,
+            ],
+            Box::new(&mut diary),
+        );
+
+        assert_eq!(
+            sinks.iter().map(|sink| sink.name()).collect::<Vec<_>>(),
+            vec!["markdown", "jsonl", "stdout"]
+        );
+        assert!(sinks[0].write_entry(&entry("abc123"))?);
+        assert!(!sinks[0].write_entry(&entry("def456"))?);
+        drop(sinks);
+        assert_eq!(diary.written, vec!["abc123"]);
+        assert_eq!(
+            open_sinks(&[SinkKind::Stdout], Box::new(&mut diary)).len(),
+            1
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_sqlite_sink_replaces_a_commit_logged_again() -> Result<(), Box<dyn Error>> {
+        let temp_dir = tempdir()?;
+        let path = temp_dir.path().join("commits.db");
+        let mut sink = SqliteSink::open(&path)?;
+
+        sink.write_entry(&entry("abc123"))?;
+        sink.write_entry(&CommitEntry {
+            message: "feat: add feature, amended".to_string(),
+            ..entry("abc123")
+        })?;
+        sink.write_entry(&entry("def456"))?;
+
+        let connection = rusqlite::Connection::open(&path)?;
+        let messages = connection
+            .prepare("SELECT message FROM commits ORDER BY hash")?
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            messages,
+            vec!["feat: add feature, amended", "feat: add feature"]
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_sqlite_sink_drops_a_staged_entry_never_written() -> Result<(), Box<dyn Error>> {
+        let temp_dir = tempdir()?;
+        let path = temp_dir.path().join("commits.db");
+        let mut sink = SqliteSink::open(&path)?;
+        sink.stage_entry(&entry("abc123"))?;
+        drop(sink);
+
+        let connection = rusqlite::Connection::open(&path)?;
+        let count: i64 =
+            connection.query_row("SELECT COUNT(*) FROM commits", [], |row| row.get(0))?;
+        assert_eq!(count, 0);
+        Ok(())
+    }
+}
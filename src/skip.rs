@@ -0,0 +1,202 @@
+use log::info;
+
+use crate::category::wildcard_match;
+use crate::hygiene::MessagePattern;
+use crate::trailers::commit_trailers;
+
+/// Commit trailer asking the hook not to log a commit, e.g. `Rcs-Skip: true`.
+pub const SKIP_TRAILER: &str = "Rcs-Skip";
+
+/// Dependency update bots whose commits `[filters] ignore_bots` skips, found
+/// in the author name or email (e.g. `dependabot[bot]`, `renovate-bot`).
+pub const BOT_AUTHORS: [&str; 2] = ["dependabot", "renovate"];
+
+/// Environment variable asking the hook not to log the current commit.
+///
+/// Handy for a one-off: `RCS_SKIP=1 git commit -m "..."`.
+pub const SKIP_ENV_VAR: &str = "RCS_SKIP";
+
+/// Returns `true` if a trailer or environment value asks for the skip.
+///
+/// `true`, `yes`, `on` and `1` are accepted, in any case.
+fn is_truthy(value: &str) -> bool {
+    matches!(
+        value.trim().to_lowercase().as_str(),
+        "true" | "yes" | "on" | "1"
+    )
+}
+
+/// Returns `true` if the commit message carries an `Rcs-Skip: true` trailer.
+///
+/// Works on the message as stored in [`CommitSaver`](crate::vim_commit::CommitSaver),
+/// whose lines are joined with `<br/>`. The trailers are the `Key: value`
+/// lines at the end of the message, after the subject line (see
+/// [`commit_trailers()`]), so a message merely mentioning `Rcs-Skip: true` in its subject or body is still logged.
+/// The trailer key is matched case-insensitively, like Git does.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::skip::has_skip_trailer;
+///
+/// assert!(has_skip_trailer("Rotate secrets<br/>Rcs-Skip: true"));
+/// assert!(!has_skip_trailer("Explain how Rcs-Skip: true works"));
+/// ```
+#[must_use]
+pub fn has_skip_trailer(commit_msg: &str) -> bool {
+    commit_trailers(commit_msg)
+        .into_iter()
+        .any(|(key, value)| key.eq_ignore_ascii_case(SKIP_TRAILER) && is_truthy(value))
+}
+
+/// Decides whether a commit must be left out of the diary.
+///
+/// A commit is skipped when [`SKIP_ENV_VAR`] is set to a truthy value, or
+/// when its message has an [`SKIP_TRAILER`] trailer. Skips are only written
+/// to the log, never to the vault.
+///
+/// # Arguments
+///
+/// * `commit_msg` - The full commit message
+/// * `env_value` - The value of [`SKIP_ENV_VAR`], if set
+#[must_use]
+pub fn should_skip_commit(commit_msg: &str, env_value: Option<&str>) -> bool {
+    if env_value.is_some_and(is_truthy) {
+        info!("[should_skip_commit()]: {SKIP_ENV_VAR:} is set, skipping the commit.");
+        return true;
+    }
+    if has_skip_trailer(commit_msg) {
+        info!("[should_skip_commit()]: {SKIP_TRAILER:} trailer found, skipping the commit.");
+        return true;
+    }
+    false
+}
+
+/// Returns `true` if `branch` matches one of the `[filters] ignore_branches`
+/// patterns, where `*` stands for any run of characters (`/` included).
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::skip::is_ignored_branch;
+///
+/// let patterns = vec!["wip/*".to_string(), "dependabot/*".to_string()];
+/// assert!(is_ignored_branch(&patterns, "dependabot/cargo/serde-1.0.228"));
+/// assert!(!is_ignored_branch(&patterns, "main"));
+/// ```
+#[must_use]
+pub fn is_ignored_branch(patterns: &[String], branch: &str) -> bool {
+    let ignored = patterns
+        .iter()
+        .find(|pattern| wildcard_match(pattern, branch));
+    if let Some(pattern) = ignored {
+        info!("[is_ignored_branch()]: Branch {branch:} matches {pattern:}, skipping the commit.");
+    }
+    ignored.is_some()
+}
+
+/// Returns `true` if the commit message matches one of the `[filters]
+/// ignore_message_patterns`.
+///
+/// Like for `[message_hygiene]`, only the subject is checked, see
+/// [`MessagePattern::is_match()`].
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::hygiene::MessagePattern;
+/// use rusty_commit_saver::skip::is_ignored_message;
+///
+/// let patterns = vec![MessagePattern::new("^fixup!").unwrap()];
+/// assert!(is_ignored_message(&patterns, "fixup! feat: login"));
+/// ```
+#[must_use]
+pub fn is_ignored_message(patterns: &[MessagePattern], commit_msg: &str) -> bool {
+    let ignored = patterns.iter().find(|pattern| pattern.is_match(commit_msg));
+    if let Some(pattern) = ignored {
+        info!("[is_ignored_message()]: Message matches {pattern:}, skipping the commit.");
+    }
+    ignored.is_some()
+}
+
+/// Returns `true` if the commit was authored by one of the [`BOT_AUTHORS`],
+/// matched case-insensitively in the author name or email.
+#[must_use]
+pub fn is_bot_commit(author: Option<&str>, author_email: Option<&str>) -> bool {
+    let bot = [author, author_email]
+        .into_iter()
+        .flatten()
+        .map(str::to_lowercase)
+        .find_map(|identity| BOT_AUTHORS.into_iter().find(|bot| identity.contains(bot)));
+    if let Some(bot) = bot {
+        info!("[is_bot_commit()]: Commit made by {bot:}, skipping it.");
+    }
+    bot.is_some()
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod skip_tests {
+    use super::*;
+
+    #[test]
+    fn test_has_skip_trailer() {
+        assert!(has_skip_trailer("Rotate secrets<br/>Rcs-Skip: true"));
+        assert!(has_skip_trailer(
+            "Bump lockfile<br/>Signed-off-by: Me <me@example.com><br/>rcs-skip: YES"
+        ));
+        assert!(!has_skip_trailer("Rotate secrets<br/>Rcs-Skip: false"));
+        assert!(!has_skip_trailer("Rcs-Skip: true"));
+        assert!(!has_skip_trailer(
+            "Docs<br/>Rcs-Skip: true is a trailer.<br/>See the README.<br/>Reviewed-by: Someone"
+        ));
+    }
+
+    #[test]
+    fn test_should_skip_commit_from_env() {
+        assert!(should_skip_commit("Regular commit", Some("1")));
+        assert!(!should_skip_commit("Regular commit", Some("0")));
+        assert!(!should_skip_commit("Regular commit", None));
+        assert!(should_skip_commit("Noise<br/>Rcs-Skip: on", None));
+    }
+
+    #[test]
+    fn test_is_ignored_branch() {
+        let patterns = ["wip/*", "tmp/*", "dependabot/*"].map(String::from);
+        assert!(is_ignored_branch(&patterns, "wip/login"));
+        assert!(is_ignored_branch(
+            &patterns,
+            "dependabot/cargo/serde-1.0.228"
+        ));
+        assert!(!is_ignored_branch(&patterns, "main"));
+        assert!(!is_ignored_branch(&patterns, "feature/wip/login"));
+        assert!(!is_ignored_branch(&[], "wip/login"));
+    }
+
+    #[test]
+    fn test_is_ignored_message() {
+        let patterns = ["^fixup!", "^squash!", "^Merge branch"]
+            .map(|pattern| MessagePattern::new(pattern).unwrap());
+        assert!(is_ignored_message(&patterns, "fixup! feat: login"));
+        assert!(is_ignored_message(
+            &patterns,
+            "Merge branch 'main'<br/>Conflicts: src/lib.rs"
+        ));
+        assert!(!is_ignored_message(
+            &patterns,
+            "feat: login<br/>fixup! later"
+        ));
+        assert!(!is_ignored_message(&[], "fixup! feat: login"));
+    }
+
+    #[test]
+    fn test_is_bot_commit() {
+        assert!(is_bot_commit(Some("dependabot[bot]"), None));
+        assert!(is_bot_commit(
+            Some("Bot"),
+            Some("29139614+Renovate-Bot@users.noreply.github.com")
+        ));
+        assert!(!is_bot_commit(Some("Jane"), Some("jane@example.com")));
+        assert!(!is_bot_commit(None, None));
+    }
+}
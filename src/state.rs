@@ -0,0 +1,436 @@
+use chrono::DateTime;
+use chrono::Utc;
+
+use std::error::Error;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use log::info;
+use log::warn;
+
+use crate::atomic_write::write_atomically;
+use crate::paths::repository_name_from_url;
+
+/// Name of the application directory inside the platform state directory.
+const STATE_DIR_NAME: &str = "rusty-commit-saver";
+
+/// File name of the logged-commits index inside the state directory.
+pub(crate) const INDEX_FILE_NAME: &str = "index.tsv";
+
+/// One commit that has been written to the diary.
+///
+/// Stored as a tab-separated line in the state index, so later runs can look
+/// back at what was already logged (e.g. to compute the time spent since the
+/// previous commit of the same repository).
+///
+/// # Line Format
+///
+/// Five tab-separated fields, in this order:
+///
+/// ```text
+/// <commit datetime, RFC 3339> <repository url> <branch> <commit hash> <diary path>
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoggedEntry {
+    /// When the commit was created.
+    pub commit_datetime: DateTime<Utc>,
+
+    /// The remote origin URL of the repository (`no_url_set` without remote).
+    pub repository_url: String,
+
+    /// The branch the commit was made on.
+    pub branch: String,
+
+    /// The full commit hash.
+    pub commit_hash: String,
+
+    /// The diary file the row was appended to.
+    pub diary_path: String,
+}
+
+impl LoggedEntry {
+    /// Serializes the entry as one index line (without the trailing newline).
+    ///
+    /// Tabs and line breaks inside fields are replaced by spaces so a value
+    /// can never split the line into extra columns or records.
+    #[must_use]
+    pub fn to_index_line(&self) -> String {
+        [
+            self.commit_datetime.to_rfc3339(),
+            self.repository_url.clone(),
+            self.branch.clone(),
+            self.commit_hash.clone(),
+            self.diary_path.clone(),
+        ]
+        .iter()
+        .map(|field| field.replace(['\t', '\n', '\r'], " "))
+        .collect::<Vec<_>>()
+        .join("\t")
+    }
+
+    /// Parses one index line written by [`to_index_line()`](Self::to_index_line).
+    ///
+    /// # Returns
+    ///
+    /// `None` if the line does not have exactly five fields or the timestamp
+    /// is not valid RFC 3339.
+    #[must_use]
+    pub fn from_index_line(line: &str) -> Option<Self> {
+        let fields = line.split('\t').collect::<Vec<_>>();
+        let [datetime, repository_url, branch, commit_hash, diary_path] = fields[..] else {
+            return None;
+        };
+
+        Some(LoggedEntry {
+            commit_datetime: DateTime::parse_from_rfc3339(datetime)
+                .ok()?
+                .with_timezone(&Utc),
+            repository_url: repository_url.to_string(),
+            branch: branch.to_string(),
+            commit_hash: commit_hash.to_string(),
+            diary_path: diary_path.to_string(),
+        })
+    }
+}
+
+/// Restricts a command to the logged commits of one repository and/or
+/// branch (`--repo`, `--branch`).
+///
+/// Repositories are matched by name, see [`repository_name_from_url()`], like
+/// the shell completions offer them. An empty filter matches everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EntryFilter {
+    /// Repository name, e.g. `api` for `git@github.com:acme/api.git`.
+    pub repository: Option<String>,
+
+    /// Branch name.
+    pub branch: Option<String>,
+}
+
+impl EntryFilter {
+    /// Whether the repository named `repository` passes the filter.
+    #[must_use]
+    pub fn matches_repository(&self, repository: &str) -> bool {
+        self.repository
+            .as_deref()
+            .is_none_or(|name| name == repository)
+    }
+
+    /// Whether the logged `entry` passes the filter.
+    #[must_use]
+    pub fn matches(&self, entry: &LoggedEntry) -> bool {
+        self.matches_repository(&repository_name_from_url(&entry.repository_url))
+            && self
+                .branch
+                .as_deref()
+                .is_none_or(|branch| branch == entry.branch)
+    }
+}
+
+/// Append-only index of the commits logged to the diary.
+///
+/// The index lives in the state directory (see [`default_state_dir()`]), not
+/// in the vault: it is bookkeeping for the tool, not something to read in
+/// Obsidian.
+///
+/// Reads never take a lock: each entry is appended with a single write, and
+/// [`entries()`](Self::entries) reads a snapshot of the complete lines, so
+/// read-only commands can run while the daemon or a hook is writing.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::state::{default_state_dir, StateIndex};
+///
+/// let index = StateIndex::new(&default_state_dir().unwrap());
+/// let previous = index.last_entry_for_repository("https://github.com/user/repo.git")?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct StateIndex {
+    index_path: PathBuf,
+}
+
+impl StateIndex {
+    /// Creates an index handle stored inside `state_dir`.
+    ///
+    /// Nothing is created on disk until the first [`append()`](Self::append).
+    #[must_use]
+    pub fn new(state_dir: &Path) -> Self {
+        StateIndex {
+            index_path: state_dir.join(INDEX_FILE_NAME),
+        }
+    }
+
+    /// Returns the path of the index file.
+    #[must_use]
+    pub fn index_path(&self) -> &Path {
+        &self.index_path
+    }
+
+    /// Reads every entry of the index, oldest first.
+    ///
+    /// A missing index file is an empty index. Lines that cannot be parsed
+    /// are skipped with a warning instead of failing the whole read, and a
+    /// last line without its newline (an append still in progress) is left
+    /// out of the snapshot.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the index file exists but cannot be read.
+    pub fn entries(&self) -> Result<Vec<LoggedEntry>, Box<dyn Error>> {
+        if !self.index_path.exists() {
+            info!(
+                "[StateIndex::entries()]: No index yet at: {:}",
+                self.index_path.display()
+            );
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&self.index_path)?;
+        let complete = match content.rfind('\n') {
+            Some(last_newline) => &content[..=last_newline],
+            None => "",
+        };
+        if complete.len() < content.len() {
+            info!("[StateIndex::entries()]: Skipping the line still being appended.");
+        }
+        Ok(complete
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| {
+                let entry = LoggedEntry::from_index_line(line);
+                if entry.is_none() {
+                    warn!("[StateIndex::entries()]: Skipping malformed index line: {line:}");
+                }
+                entry
+            })
+            .collect())
+    }
+
+    /// Returns the most recently logged entry of the given repository.
+    ///
+    /// "Most recent" is by commit time, so entries logged out of order (e.g.
+    /// backfilled) do not confuse the lookup.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the index cannot be read.
+    pub fn last_entry_for_repository(
+        &self,
+        repository_url: &str,
+    ) -> Result<Option<LoggedEntry>, Box<dyn Error>> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .filter(|entry| entry.repository_url == repository_url)
+            .max_by_key(|entry| entry.commit_datetime))
+    }
+
+    /// Appends an entry to the index, creating the state directory if needed.
+    ///
+    /// The line and its newline go out in one write, so concurrent readers
+    /// see either the whole entry or none of it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state directory cannot be created or the index
+    /// file cannot be opened or written.
+    pub fn append(&self, entry: &LoggedEntry) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = self.index_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        info!(
+            "[StateIndex::append()]: Recording {:} in: {:}",
+            entry.commit_hash,
+            self.index_path.display()
+        );
+        let mut file_ref = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.index_path)?;
+        file_ref.write_all(format!("{:}\n", entry.to_index_line()).as_bytes())?;
+
+        Ok(())
+    }
+
+    /// Replaces the whole index with `entries`, e.g. after pruning stale ones.
+    ///
+    /// The new content is written with [`write_atomically()`], so an
+    /// interrupted run never leaves a truncated index.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state directory cannot be created or the index
+    /// file cannot be written or replaced.
+    pub fn replace_entries(&self, entries: &[LoggedEntry]) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = self.index_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        info!(
+            "[StateIndex::replace_entries()]: Rewriting {:} entries in: {:}",
+            entries.len(),
+            self.index_path.display()
+        );
+        let mut content = String::new();
+        for entry in entries {
+            content.push_str(&entry.to_index_line());
+            content.push('\n');
+        }
+        write_atomically(&self.index_path, content)?;
+
+        Ok(())
+    }
+}
+
+/// Returns the default directory for the tool's own bookkeeping files.
+///
+/// Uses the platform state directory (`$XDG_STATE_HOME`, usually
+/// `~/.local/state`) and falls back to the local data directory on platforms
+/// without one (macOS, Windows).
+///
+/// # Returns
+///
+/// `None` if neither directory can be determined (no home directory).
+#[must_use]
+pub fn default_state_dir() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .map(|dir| dir.join(STATE_DIR_NAME))
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod state_tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::tempdir;
+
+    fn logged_entry(repository_url: &str, hour: u32) -> LoggedEntry {
+        LoggedEntry {
+            commit_datetime: Utc.with_ymd_and_hms(2025, 1, 14, hour, 0, 0).unwrap(),
+            repository_url: repository_url.to_string(),
+            branch: "main".to_string(),
+            commit_hash: format!("hash{hour:}"),
+            diary_path: "/vault/2025-01-14.md".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_index_line_round_trip() {
+        let entry = logged_entry("https://github.com/user/repo.git", 10);
+
+        let line = entry.to_index_line();
+
+        assert_eq!(line.split('\t').count(), 5);
+        assert_eq!(LoggedEntry::from_index_line(&line), Some(entry));
+    }
+
+    #[test]
+    fn test_entry_filter_matches_repository_names_and_branches() {
+        let entry = logged_entry("git@github.com:acme/api.git", 10);
+        let filter = |repository: Option<&str>, branch: Option<&str>| EntryFilter {
+            repository: repository.map(str::to_string),
+            branch: branch.map(str::to_string),
+        };
+
+        assert!(EntryFilter::default().matches(&entry));
+        assert!(filter(Some("api"), Some("main")).matches(&entry));
+        assert!(!filter(Some("web"), None).matches(&entry));
+        assert!(!filter(None, Some("feature")).matches(&entry));
+        assert!(filter(Some("api"), None).matches_repository("api"));
+    }
+
+    #[test]
+    fn test_index_line_escapes_tabs_and_newlines() {
+        let mut entry = logged_entry("repo", 10);
+        entry.diary_path = "/vault/odd\tname\n.md".to_string();
+
+        let line = entry.to_index_line();
+
+        assert!(!line.contains('\n'));
+        assert_eq!(
+            LoggedEntry::from_index_line(&line).unwrap().diary_path,
+            "/vault/odd name .md"
+        );
+    }
+
+    #[test]
+    fn test_from_index_line_rejects_malformed_lines() {
+        assert_eq!(LoggedEntry::from_index_line("only\ttwo"), None);
+        assert_eq!(LoggedEntry::from_index_line("not-a-date\ta\tb\tc\td"), None);
+    }
+
+    #[test]
+    fn test_entries_missing_index_is_empty() {
+        let temp_dir = tempdir().unwrap();
+        let index = StateIndex::new(&temp_dir.path().join("state"));
+
+        assert!(index.entries().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_append_creates_dir_and_last_entry_for_repository() {
+        let temp_dir = tempdir().unwrap();
+        let index = StateIndex::new(&temp_dir.path().join("nested").join("state"));
+
+        index.append(&logged_entry("repo-a", 11)).unwrap();
+        index.append(&logged_entry("repo-b", 12)).unwrap();
+        index.append(&logged_entry("repo-a", 9)).unwrap();
+
+        let last = index.last_entry_for_repository("repo-a").unwrap().unwrap();
+        assert_eq!(last.commit_hash, "hash11");
+        assert!(index.last_entry_for_repository("repo-c").unwrap().is_none());
+        assert_eq!(index.entries().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_replace_entries_overwrites_index() {
+        let temp_dir = tempdir().unwrap();
+        let index = StateIndex::new(temp_dir.path());
+        index.append(&logged_entry("repo", 10)).unwrap();
+        index.append(&logged_entry("repo", 11)).unwrap();
+
+        index.replace_entries(&[logged_entry("repo", 12)]).unwrap();
+
+        let entries = index.entries().unwrap();
+        assert_eq!(entries, vec![logged_entry("repo", 12)]);
+        assert!(!index.index_path().with_extension("tsv.tmp").exists());
+    }
+
+    #[test]
+    fn test_entries_skips_malformed_lines() {
+        let temp_dir = tempdir().unwrap();
+        let index = StateIndex::new(temp_dir.path());
+        index.append(&logged_entry("repo", 10)).unwrap();
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(index.index_path())
+            .unwrap();
+        writeln!(file, "garbage line").unwrap();
+
+        assert_eq!(index.entries().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_entries_leaves_out_a_line_still_being_appended() {
+        let temp_dir = tempdir().unwrap();
+        let index = StateIndex::new(temp_dir.path());
+        index.append(&logged_entry("repo", 10)).unwrap();
+        let partial = logged_entry("repo", 11).to_index_line();
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(index.index_path())
+            .unwrap();
+        write!(file, "{:}", &partial[..partial.len() - 3]).unwrap();
+
+        assert_eq!(index.entries().unwrap(), vec![logged_entry("repo", 10)]);
+
+        writeln!(file, "{:}", &partial[partial.len() - 3..]).unwrap();
+        assert_eq!(index.entries().unwrap().len(), 2);
+    }
+}
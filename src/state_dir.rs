@@ -0,0 +1,280 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use log::info;
+use log::warn;
+
+use crate::atomic_write::write_atomically;
+use crate::diary_lock::DiaryLock;
+use crate::diary_lock::LOCKS_DIR_NAME;
+use crate::diary_lock::LOCK_FILE_NAME;
+use crate::diary_lock::LOCK_TIMEOUT;
+use crate::diary_moves::MOVES_FILE_NAME;
+use crate::state::INDEX_FILE_NAME;
+
+/// Version of the state directory layout this build reads and writes.
+///
+/// # Versions
+///
+/// - `1` - The index, moved-diaries record, spool, usage counters and lock
+///   file, without a version file (up to this release)
+/// - `2` - The same files, with torn lines of interrupted appends and
+///   temporary files of interrupted writes cleaned up, and a version file
+/// - `3` - One lock file per note in a `locks` directory (see
+///   [`lock_path()`](crate::diary_lock::lock_path)) instead of one per
+///   directory next to the diaries; an older release would not see these
+///   locks and could interleave its rows with this one's
+pub const STATE_SCHEMA_VERSION: u32 = 3;
+
+/// File name of the schema version inside the state directory.
+const SCHEMA_VERSION_FILE_NAME: &str = "schema-version";
+
+/// One step of [`MIGRATIONS`], upgrading the state directory by one version.
+type Migration = fn(&Path) -> Result<(), Box<dyn Error>>;
+
+/// The migrations, in order: the first one upgrades version 1 to 2.
+///
+/// Each step must be safe to run again: a run interrupted before the new
+/// version is written redoes the step.
+const MIGRATIONS: [Migration; (STATE_SCHEMA_VERSION - 1) as usize] =
+    [finish_interrupted_writes, remove_directory_lock];
+
+/// The state directory was written by a newer version of the tool, whose
+/// data this one might misread or overwrite.
+#[derive(Debug)]
+pub struct NewerStateSchema {
+    /// The state directory.
+    pub state_dir: PathBuf,
+
+    /// Its schema version.
+    pub version: u32,
+}
+
+impl fmt::Display for NewerStateSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The state directory {:} has schema version {:}, newer than the {STATE_SCHEMA_VERSION:} this version of rusty-commit-saver reads; upgrade rusty-commit-saver.",
+            self.state_dir.display(),
+            self.version
+        )
+    }
+}
+
+impl Error for NewerStateSchema {}
+
+/// Reads the schema version of `state_dir`.
+///
+/// A directory without a version file is at version 1 when it already holds
+/// state files, and at [`STATE_SCHEMA_VERSION`] when it is missing or empty
+/// (nothing to migrate).
+///
+/// # Errors
+///
+/// Returns an error if the directory or the version file cannot be read, or
+/// if the version file does not hold a number.
+pub fn schema_version(state_dir: &Path) -> Result<u32, Box<dyn Error>> {
+    let version_path = state_dir.join(SCHEMA_VERSION_FILE_NAME);
+    if version_path.exists() {
+        let version = fs::read_to_string(&version_path)?;
+        return version.trim().parse().map_err(|e| {
+            format!(
+                "Could not read the schema version in {:}: {e:}",
+                version_path.display()
+            )
+            .into()
+        });
+    }
+    if !state_dir.exists() {
+        return Ok(STATE_SCHEMA_VERSION);
+    }
+
+    let holds_state = fs::read_dir(state_dir)?
+        .filter_map(Result::ok)
+        .any(|entry| entry.file_name() != LOCK_FILE_NAME && entry.file_name() != LOCKS_DIR_NAME);
+    Ok(if holds_state { 1 } else { STATE_SCHEMA_VERSION })
+}
+
+/// Brings `state_dir` to [`STATE_SCHEMA_VERSION`], running the migrations
+/// it is missing.
+///
+/// The version is checked without a lock first, so an up-to-date directory
+/// costs one small read. Migrations run under the lock of the version file
+/// (concurrent hooks wait, then find it migrated), and the version
+/// file is rewritten atomically after each step: a run killed halfway
+/// resumes from the last completed step.
+///
+/// # Returns
+///
+/// The version the directory was migrated from, or `None` if it was already
+/// up to date.
+///
+/// # Errors
+///
+/// Returns a [`NewerStateSchema`] if the directory was written by a newer
+/// version, and another error if it cannot be created, locked, read or
+/// migrated.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::state::default_state_dir;
+/// use rusty_commit_saver::state_dir::upgrade_state_dir;
+///
+/// if let Some(from) = upgrade_state_dir(&default_state_dir().unwrap())? {
+///     println!("Migrated the state directory from version {from}");
+/// }
+/// ```
+pub fn upgrade_state_dir(state_dir: &Path) -> Result<Option<u32>, Box<dyn Error>> {
+    let version = schema_version(state_dir)?;
+    if version > STATE_SCHEMA_VERSION {
+        return Err(Box::new(NewerStateSchema {
+            state_dir: state_dir.to_path_buf(),
+            version,
+        }));
+    }
+    let version_path = state_dir.join(SCHEMA_VERSION_FILE_NAME);
+    if version == STATE_SCHEMA_VERSION && version_path.exists() {
+        return Ok(None);
+    }
+
+    let _lock = DiaryLock::acquire(Some(state_dir), &version_path, LOCK_TIMEOUT)?;
+    let from = schema_version(state_dir)?;
+    for version in from..STATE_SCHEMA_VERSION {
+        info!(
+            "[upgrade_state_dir()]: Migrating {:} from version {version:} to {:}.",
+            state_dir.display(),
+            version + 1
+        );
+        MIGRATIONS[(version - 1) as usize](state_dir)?;
+        write_atomically(&version_path, format!("{:}\n", version + 1))?;
+    }
+    if !version_path.exists() {
+        write_atomically(&version_path, format!("{STATE_SCHEMA_VERSION:}\n"))?;
+    }
+    Ok((from < STATE_SCHEMA_VERSION).then_some(from))
+}
+
+/// Migration from version 1 to 2: cleans up after runs killed mid-write.
+///
+/// - The last line of the append-only files (index, moved-diaries record)
+///   is dropped when it has no newline: it is a torn append, and the next
+///   append would otherwise be glued to it and lost as well
+/// - Temporary files left by interrupted atomic writes are removed
+fn finish_interrupted_writes(state_dir: &Path) -> Result<(), Box<dyn Error>> {
+    for file_name in [INDEX_FILE_NAME, MOVES_FILE_NAME] {
+        let path = state_dir.join(file_name);
+        if !path.exists() {
+            continue;
+        }
+        let content = fs::read_to_string(&path)?;
+        if content.is_empty() || content.ends_with('\n') {
+            continue;
+        }
+        let complete = content.rfind('\n').map_or("", |index| &content[..=index]);
+        warn!(
+            "[finish_interrupted_writes()]: Dropping the torn last line of: {:}",
+            path.display()
+        );
+        write_atomically(&path, complete)?;
+    }
+
+    for entry in fs::read_dir(state_dir)? {
+        let path = entry?.path();
+        let hidden = path
+            .file_name()
+            .is_some_and(|file_name| file_name.to_string_lossy().starts_with('.'));
+        if hidden && path.extension().is_some_and(|extension| extension == "tmp") {
+            info!(
+                "[finish_interrupted_writes()]: Removing the leftover: {:}",
+                path.display()
+            );
+            fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Migration from version 2 to 3: removes the lock file of the state
+/// directory itself, replaced by the per-file locks of the `locks`
+/// directory.
+fn remove_directory_lock(state_dir: &Path) -> Result<(), Box<dyn Error>> {
+    let path = state_dir.join(LOCK_FILE_NAME);
+    if path.exists() {
+        info!(
+            "[remove_directory_lock()]: Removing the old lock file: {:}",
+            path.display()
+        );
+        fs::remove_file(&path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod state_dir_tests {
+    use super::*;
+    use crate::state::StateIndex;
+    use tempfile::tempdir;
+
+    const ENTRY: &str = "2025-01-14T10:00:00+00:00\thttps://github.com/user/repo.git\tmain\tabc123\t/vault/2025-01-14.md\n";
+
+    #[test]
+    fn test_upgrade_state_dir_migrates_version_1() {
+        let state_dir = tempdir().unwrap();
+        fs::write(
+            state_dir.path().join(INDEX_FILE_NAME),
+            format!("{ENTRY:}2025-01-14T11:00:00+00:00\thttps://git"),
+        )
+        .unwrap();
+        fs::write(state_dir.path().join(".index.tsv.4242.tmp"), "half").unwrap();
+        fs::write(state_dir.path().join(LOCK_FILE_NAME), "").unwrap();
+        assert_eq!(schema_version(state_dir.path()).unwrap(), 1);
+
+        assert_eq!(upgrade_state_dir(state_dir.path()).unwrap(), Some(1));
+
+        assert_eq!(
+            fs::read_to_string(state_dir.path().join(INDEX_FILE_NAME)).unwrap(),
+            ENTRY
+        );
+        assert!(!state_dir.path().join(".index.tsv.4242.tmp").exists());
+        assert!(!state_dir.path().join(LOCK_FILE_NAME).exists());
+        assert_eq!(
+            schema_version(state_dir.path()).unwrap(),
+            STATE_SCHEMA_VERSION
+        );
+        assert_eq!(
+            StateIndex::new(state_dir.path()).entries().unwrap().len(),
+            1
+        );
+        assert_eq!(upgrade_state_dir(state_dir.path()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_upgrade_state_dir_starts_new_directories_at_the_current_version() {
+        let parent = tempdir().unwrap();
+        let state_dir = parent.path().join("rusty-commit-saver");
+
+        assert_eq!(upgrade_state_dir(&state_dir).unwrap(), None);
+        assert_eq!(
+            fs::read_to_string(state_dir.join(SCHEMA_VERSION_FILE_NAME)).unwrap(),
+            format!("{STATE_SCHEMA_VERSION:}\n")
+        );
+    }
+
+    #[test]
+    fn test_upgrade_state_dir_refuses_newer_versions() {
+        let state_dir = tempdir().unwrap();
+        fs::write(state_dir.path().join(SCHEMA_VERSION_FILE_NAME), "99\n").unwrap();
+
+        let error = upgrade_state_dir(state_dir.path()).unwrap_err();
+        assert!(error.is::<NewerStateSchema>());
+        assert!(error.to_string().contains("schema version 99"));
+
+        fs::write(state_dir.path().join(SCHEMA_VERSION_FILE_NAME), "two").unwrap();
+        assert!(upgrade_state_dir(state_dir.path()).is_err());
+    }
+}
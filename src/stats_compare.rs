@@ -0,0 +1,419 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use chrono::Datelike;
+use chrono::Days;
+use chrono::Months;
+use chrono::NaiveDate;
+
+use crate::porcelain::porcelain_line;
+use crate::vault_stats::DiaryCommit;
+
+/// Name under which commits without a Conventional Commits type are counted.
+pub const UNTYPED_COMMITS: &str = "(none)";
+
+/// A range of days compared by `stats --compare`, both ends included.
+///
+/// # Names
+///
+/// - `today`, `yesterday`
+/// - `this-week`, `last-week` - ISO weeks, Monday to Sunday
+/// - `this-month`, `last-month`, `this-year`, `last-year`
+/// - `2025-01` - A month
+/// - `2025-01-14` - A day
+/// - `2025-01-06..2025-01-19` - Any range of days
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Period {
+    /// The name the period was given on the command line.
+    pub name: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl Period {
+    /// Resolves a period name (see [`Period`]) relative to `today`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is unknown, or if a range ends before it
+    /// starts.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rusty_commit_saver::stats_compare::Period;
+    ///
+    /// // 2025-01-16 is a Thursday
+    /// let period = Period::parse("last-week", today)?;
+    /// assert_eq!(period.start.to_string(), "2025-01-06");
+    /// assert_eq!(period.end.to_string(), "2025-01-12");
+    /// ```
+    pub fn parse(name: &str, today: NaiveDate) -> Result<Self, String> {
+        let (start, end) = period_bounds(name.trim(), today)
+            .ok_or_else(|| format!("Unknown period '{name:}', e.g. last-week, this-month, 2025-01 or 2025-01-06..2025-01-19"))?;
+        if end < start {
+            return Err(format!("The period '{name:}' ends before it starts"));
+        }
+        Ok(Period {
+            name: name.trim().to_string(),
+            start,
+            end,
+        })
+    }
+
+    /// Whether `date` falls within the period.
+    #[must_use]
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+}
+
+/// Returns the first and last day of a period name.
+fn period_bounds(name: &str, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+    let week_start = today - Days::new(u64::from(today.weekday().num_days_from_monday()));
+    let month_start = today.with_day(1)?;
+    let year_start = today.with_ordinal(1)?;
+    let month_end = |start: NaiveDate| start.checked_add_months(Months::new(1))?.pred_opt();
+
+    match name {
+        "today" => Some((today, today)),
+        "yesterday" => today.pred_opt().map(|day| (day, day)),
+        "this-week" => Some((week_start, week_start + Days::new(6))),
+        "last-week" => Some((week_start - Days::new(7), week_start - Days::new(1))),
+        "this-month" => Some((month_start, month_end(month_start)?)),
+        "last-month" => {
+            let start = month_start.checked_sub_months(Months::new(1))?;
+            Some((start, month_end(start)?))
+        }
+        "this-year" => Some((year_start, year_start.with_month(12)?.with_day(31)?)),
+        "last-year" => {
+            let start = year_start.with_year(year_start.year() - 1)?;
+            Some((start, year_start.pred_opt()?))
+        }
+        _ => {
+            if let Some((start, end)) = name.split_once("..") {
+                return Some((parse_day(start)?, parse_day(end)?));
+            }
+            if let Some(day) = parse_day(name) {
+                return Some((day, day));
+            }
+            let start = parse_day(&format!("{name:}-01"))?;
+            Some((start, month_end(start)?))
+        }
+    }
+}
+
+/// Parses a `YYYY-MM-DD` day.
+fn parse_day(day: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(day.trim(), "%Y-%m-%d").ok()
+}
+
+/// Commits logged in the two compared periods.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CommitCounts {
+    pub before: usize,
+    pub after: usize,
+}
+
+impl CommitCounts {
+    /// Change from the first period to the second one.
+    #[must_use]
+    pub fn delta(&self) -> i64 {
+        i64::try_from(self.after).unwrap_or(i64::MAX)
+            - i64::try_from(self.before).unwrap_or(i64::MAX)
+    }
+}
+
+/// Commit counts of two periods side by side, for personal retrospectives
+/// (`stats --compare last-week this-week`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeriodComparison {
+    pub before: Period,
+    pub after: Period,
+    pub total: CommitCounts,
+
+    /// Counts per repository name.
+    pub per_repository: BTreeMap<String, CommitCounts>,
+
+    /// Counts per Conventional Commits type, see [`DiaryCommit::commit_type()`];
+    /// commits without one are counted as [`UNTYPED_COMMITS`].
+    pub per_type: BTreeMap<String, CommitCounts>,
+}
+
+impl PeriodComparison {
+    /// Counts the `commits` of each period. A commit in both periods (when
+    /// they overlap) counts in both.
+    #[must_use]
+    pub fn new(commits: &[DiaryCommit], before: Period, after: Period) -> Self {
+        let mut comparison = PeriodComparison {
+            before,
+            after,
+            total: CommitCounts::default(),
+            per_repository: BTreeMap::new(),
+            per_type: BTreeMap::new(),
+        };
+        for commit in commits {
+            let commit_type = commit
+                .commit_type()
+                .unwrap_or_else(|| UNTYPED_COMMITS.to_string());
+            for (within, is_after) in [
+                (comparison.before.contains(commit.date), false),
+                (comparison.after.contains(commit.date), true),
+            ] {
+                if !within {
+                    continue;
+                }
+                for counts in [
+                    &mut comparison.total,
+                    comparison
+                        .per_repository
+                        .entry(commit.repository.clone())
+                        .or_default(),
+                    comparison.per_type.entry(commit_type.clone()).or_default(),
+                ] {
+                    if is_after {
+                        counts.after += 1;
+                    } else {
+                        counts.before += 1;
+                    }
+                }
+            }
+        }
+        comparison
+    }
+
+    /// Renders the comparison as Markdown, like the `stats` output.
+    ///
+    /// Rows are sorted by their count in the second period, then the first.
+    ///
+    /// # Output Format
+    ///
+    /// ```text
+    /// # Commit Stats: last-week vs this-week
+    ///
+    /// - last-week (2025-01-06 to 2025-01-12): 12 commits
+    /// - this-week (2025-01-13 to 2025-01-19): 18 commits (+6)
+    ///
+    /// | REPOSITORY | LAST-WEEK | THIS-WEEK | DELTA |
+    /// |------------|-----------|-----------|-------|
+    /// | api | 5 | 9 | +4 |
+    ///
+    /// | TYPE | LAST-WEEK | THIS-WEEK | DELTA |
+    /// |------|-----------|-----------|-------|
+    /// | feat | 3 | 7 | +4 |
+    /// ```
+    #[must_use]
+    pub fn render(&self) -> String {
+        let mut output = format!(
+            "# Commit Stats: {:} vs {:}\n\n",
+            self.before.name, self.after.name
+        );
+        let _ = writeln!(
+            output,
+            "- {:} ({:} to {:}): {:} commits",
+            self.before.name, self.before.start, self.before.end, self.total.before
+        );
+        let _ = writeln!(
+            output,
+            "- {:} ({:} to {:}): {:} commits ({:+})",
+            self.after.name,
+            self.after.start,
+            self.after.end,
+            self.total.after,
+            self.total.delta()
+        );
+
+        for (title, counts) in [
+            ("REPOSITORY", &self.per_repository),
+            ("TYPE", &self.per_type),
+        ] {
+            output.push('\n');
+            self.render_table(&mut output, title, counts);
+        }
+        output
+    }
+
+    /// Appends the delta table of `counts`, keyed by `title`.
+    fn render_table(
+        &self,
+        output: &mut String,
+        title: &str,
+        counts: &BTreeMap<String, CommitCounts>,
+    ) {
+        let headers = [
+            title.to_string(),
+            self.before.name.to_uppercase(),
+            self.after.name.to_uppercase(),
+            "DELTA".to_string(),
+        ];
+        let _ = writeln!(output, "| {:} |", headers.join(" | "));
+        let separators = headers
+            .iter()
+            .map(|header| "-".repeat(header.chars().count() + 2))
+            .collect::<Vec<_>>();
+        let _ = writeln!(output, "|{:}|", separators.join("|"));
+
+        for (name, counts) in sorted_rows(counts) {
+            let _ = writeln!(
+                output,
+                "| {name:} | {:} | {:} | {:+} |",
+                counts.before,
+                counts.after,
+                counts.delta()
+            );
+        }
+    }
+
+    /// Renders the comparison as porcelain records (`stats --compare
+    /// --porcelain`), in the order of [`render()`](Self::render).
+    ///
+    /// # Format
+    ///
+    /// Tab-separated fields, escaped with [`escape_field()`](crate::porcelain::escape_field):
+    ///
+    /// ```text
+    /// period <before|after> <name> <start> <end> <commits>
+    /// repository-delta <name> <before commits> <after commits> <delta>
+    /// type-delta <type> <before commits> <after commits> <delta>
+    /// ```
+    #[must_use]
+    pub fn to_porcelain(&self) -> String {
+        let mut output = String::new();
+        for (side, period, commits) in [
+            ("before", &self.before, self.total.before),
+            ("after", &self.after, self.total.after),
+        ] {
+            output.push_str(&porcelain_line(&[
+                "period",
+                side,
+                &period.name,
+                &period.start.to_string(),
+                &period.end.to_string(),
+                &commits.to_string(),
+            ]));
+        }
+        for (record, counts) in [
+            ("repository-delta", &self.per_repository),
+            ("type-delta", &self.per_type),
+        ] {
+            for (name, counts) in sorted_rows(counts) {
+                output.push_str(&porcelain_line(&[
+                    record,
+                    name,
+                    &counts.before.to_string(),
+                    &counts.after.to_string(),
+                    &format!("{:+}", counts.delta()),
+                ]));
+            }
+        }
+        output
+    }
+}
+
+/// Returns the rows of `counts` by their count in the second period, then
+/// the first.
+fn sorted_rows(counts: &BTreeMap<String, CommitCounts>) -> Vec<(&String, &CommitCounts)> {
+    let mut rows = counts.iter().collect::<Vec<_>>();
+    rows.sort_by(|(a_name, a), (b_name, b)| {
+        (b.after, b.before)
+            .cmp(&(a.after, a.before))
+            .then_with(|| a_name.cmp(b_name))
+    });
+    rows
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod stats_compare_tests {
+    use super::*;
+
+    fn date(day: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(day, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_period_parse() {
+        // A Thursday
+        let today = date("2025-01-16");
+        let bounds = |name| {
+            let period = Period::parse(name, today).unwrap();
+            (period.start.to_string(), period.end.to_string())
+        };
+        let range = |start: &str, end: &str| (start.to_string(), end.to_string());
+
+        assert_eq!(bounds("today"), range("2025-01-16", "2025-01-16"));
+        assert_eq!(bounds("yesterday"), range("2025-01-15", "2025-01-15"));
+        assert_eq!(bounds("this-week"), range("2025-01-13", "2025-01-19"));
+        assert_eq!(bounds("last-week"), range("2025-01-06", "2025-01-12"));
+        assert_eq!(bounds("this-month"), range("2025-01-01", "2025-01-31"));
+        assert_eq!(bounds("last-month"), range("2024-12-01", "2024-12-31"));
+        assert_eq!(bounds("this-year"), range("2025-01-01", "2025-12-31"));
+        assert_eq!(bounds("last-year"), range("2024-01-01", "2024-12-31"));
+        assert_eq!(bounds("2024-02"), range("2024-02-01", "2024-02-29"));
+        assert_eq!(bounds("2024-02-10"), range("2024-02-10", "2024-02-10"));
+        assert_eq!(
+            bounds("2024-02-10..2024-03-01"),
+            range("2024-02-10", "2024-03-01")
+        );
+        assert!(Period::parse("fortnight", today)
+            .unwrap_err()
+            .starts_with("Unknown period 'fortnight'"));
+        assert!(Period::parse("2024-03-01..2024-02-10", today)
+            .unwrap_err()
+            .ends_with("ends before it starts"));
+    }
+
+    #[test]
+    fn test_period_comparison() {
+        let today = date("2025-01-16");
+        let commit =
+            |day, repository_url, message| DiaryCommit::new(date(day), repository_url, message);
+        let commits = [
+            commit("2025-01-07", "git@github.com:user/api.git", "feat: a"),
+            commit("2025-01-08", "git@github.com:user/api.git", "fix: b"),
+            commit("2025-01-13", "git@github.com:user/api.git", "feat: c"),
+            commit("2025-01-14", "git@github.com:user/web.git", "feat(ui): d"),
+            commit("2025-01-15", "git@github.com:user/web.git", "Update README"),
+            commit("2024-12-31", "git@github.com:user/old.git", "feat: e"),
+        ];
+
+        let comparison = PeriodComparison::new(
+            &commits,
+            Period::parse("last-week", today).unwrap(),
+            Period::parse("this-week", today).unwrap(),
+        );
+
+        assert_eq!(
+            comparison.total,
+            CommitCounts {
+                before: 2,
+                after: 3
+            }
+        );
+        assert_eq!(
+            comparison.per_repository["web"],
+            CommitCounts {
+                before: 0,
+                after: 2
+            }
+        );
+        assert!(!comparison.per_repository.contains_key("old"));
+        assert_eq!(comparison.per_type["fix"].delta(), -1);
+
+        let output = comparison.render();
+        assert!(output.starts_with(
+            "# Commit Stats: last-week vs this-week\n\n- last-week (2025-01-06 to 2025-01-12): 2 commits\n- this-week (2025-01-13 to 2025-01-19): 3 commits (+1)\n"
+        ));
+        assert!(output.contains(
+            "| REPOSITORY | LAST-WEEK | THIS-WEEK | DELTA |\n|------------|-----------|-----------|-------|\n| web | 0 | 2 | +2 |\n| api | 2 | 1 | -1 |\n"
+        ));
+        assert!(output
+            .contains("| feat | 1 | 2 | +1 |\n| (none) | 0 | 1 | +1 |\n| fix | 1 | 0 | -1 |\n"));
+
+        let porcelain = comparison.to_porcelain();
+        assert!(porcelain.starts_with(
+            "period\tbefore\tlast-week\t2025-01-06\t2025-01-12\t2\nperiod\tafter\tthis-week\t2025-01-13\t2025-01-19\t3\nrepository-delta\tweb\t0\t2\t+2\nrepository-delta\tapi\t2\t1\t-1\n"
+        ));
+        assert!(porcelain.ends_with("type-delta\tfix\t1\t0\t-1\n"));
+    }
+}
@@ -0,0 +1,328 @@
+use std::error::Error;
+use std::path::Path;
+
+use chrono::DateTime;
+use chrono::FixedOffset;
+use handlebars::no_escape;
+use handlebars::Handlebars;
+use log::info;
+use serde_json::json;
+
+use crate::atomic_write::write_atomically;
+use crate::frontmatter::merge_frontmatter_list;
+use crate::vim_commit::CommitSaver;
+
+/// Substitutes the common template variables of Obsidian daily-note templates.
+///
+/// - `{{date}}` - The diary date, `YYYY-MM-DD`
+/// - `{{time}}` - The entry time, `HH:mm`
+/// - `{{title}}` - The note title (the diary file name without `.md`)
+///
+/// Date and time are those of the entry, in its timezone like the diary file
+/// names (see [`CommitSaver::local_datetime()`]).
+/// Any other `{{...}}` or Templater `<% ... %>` expression is left as is.
+///
+/// # Examples
+///
+/// ```ignore
+/// use chrono::{TimeZone, Utc};
+/// use rusty_commit_saver::templater::render_template_variables;
+///
+/// let datetime = Utc.with_ymd_and_hms(2025, 1, 14, 9, 5, 0).unwrap().fixed_offset();
+/// let rendered = render_template_variables("# {{title}} ({{date}} {{time}})", datetime, "Tuesday");
+/// assert_eq!(rendered, "# Tuesday (2025-01-14 09:05)");
+/// ```
+#[must_use]
+pub fn render_template_variables(
+    template: &str,
+    datetime: DateTime<FixedOffset>,
+    title: &str,
+) -> String {
+    template
+        .replace("{{date}}", &datetime.format("%Y-%m-%d").to_string())
+        .replace("{{time}}", &datetime.format("%H:%M").to_string())
+        .replace("{{title}}", title)
+}
+
+/// Creates a new diary file from the user's daily-note template.
+///
+/// Used instead of [`create_diary_file()`](crate::vim_commit::create_diary_file)
+/// when `[templates] diary_template` is set. The template variables are
+/// substituted (see [`render_template_variables()`]), the entry tags and
+/// category are merged into the template frontmatter if it has one, and the
+/// commit table header is added at the end so rows are appended below it.
+///
+/// # Errors
+///
+/// Returns an error if the diary file cannot be written.
+pub fn create_diary_file_from_template(
+    full_diary_file_path: &Path,
+    commit_saver_struct: &mut CommitSaver,
+    template: &str,
+) -> Result<(), Box<dyn Error>> {
+    let content =
+        render_diary_file_from_template(full_diary_file_path, commit_saver_struct, template);
+
+    info!(
+        "[create_diary_file_from_template()]: Writing the diary file: {:}",
+        full_diary_file_path.display()
+    );
+    write_atomically(full_diary_file_path, content)?;
+    Ok(())
+}
+
+/// Renders the diary file that [`create_diary_file_from_template()`] writes,
+/// without touching the filesystem.
+#[must_use]
+pub fn render_diary_file_from_template(
+    full_diary_file_path: &Path,
+    commit_saver_struct: &mut CommitSaver,
+    template: &str,
+) -> String {
+    let title = full_diary_file_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut content =
+        render_template_variables(template, commit_saver_struct.local_datetime(), &title);
+
+    info!("[render_diary_file_from_template()]: Merging the entry tags into the frontmatter.");
+    let tags = commit_saver_struct.prepare_frontmatter_tags();
+    if let Some(merged) = merge_frontmatter_list(&content, "tags", &tags) {
+        content = merged;
+    }
+    let categories = commit_saver_struct
+        .details
+        .category
+        .iter()
+        .cloned()
+        .collect::<Vec<_>>();
+    if let Some(merged) = merge_frontmatter_list(&content, "categories", &categories) {
+        content = merged;
+    }
+
+    if !content.is_empty() {
+        if !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push('\n');
+    }
+    content.push_str(
+        &commit_saver_struct
+            .settings
+            .entry_format
+            .header(&commit_saver_struct.settings.table_columns),
+    );
+    content
+}
+
+/// Renders a new diary file from a Handlebars template
+/// (`[templates] diary_file`), replacing the built-in diary file layout.
+///
+/// The template gets these variables:
+///
+/// - `date` - The diary date, `YYYY-MM-DD`
+/// - `time` - The entry time, `HH:mm`
+/// - `title` - The note title (the diary file name without `.md`)
+/// - `week_number` - The week of the year, like the `#datetime/week/` tag
+/// - `tags` - The frontmatter tags of the entry, for `{{#each tags}}`
+/// - `categories` - The entry category, if any, for `{{#each categories}}`
+/// - `table_header` - The commit table header (empty for YAML entries)
+///
+/// Values are inserted as is, without HTML escaping. When the template does
+/// not place `{{table_header}}`, the header is added at the end so rows are
+/// appended below it.
+///
+/// # Examples
+///
+/// ```ignore
+/// ---
+/// tags:
+/// {{#each tags}}
+///   - '{{this}}'
+/// {{/each}}
+/// ---
+/// # {{date}} (week {{week_number}})
+///
+/// {{table_header}}
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the template is not valid Handlebars or fails to
+/// render (e.g. an unknown helper).
+pub fn render_diary_file_from_handlebars(
+    full_diary_file_path: &Path,
+    commit_saver_struct: &mut CommitSaver,
+    template: &str,
+) -> Result<String, Box<dyn Error>> {
+    let title = full_diary_file_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let table_header = commit_saver_struct
+        .settings
+        .entry_format
+        .header(&commit_saver_struct.settings.table_columns);
+    let datetime = commit_saver_struct.local_datetime();
+    let variables = json!({
+        "date": datetime.format("%Y-%m-%d").to_string(),
+        "time": datetime.format("%H:%M").to_string(),
+        "title": title,
+        "week_number": datetime.format("%W").to_string(),
+        "tags": commit_saver_struct.prepare_frontmatter_tags(),
+        "categories": commit_saver_struct.details.category.iter().collect::<Vec<_>>(),
+        "table_header": table_header,
+    });
+
+    info!("[render_diary_file_from_handlebars()]: Rendering the diary file template.");
+    let mut handlebars = Handlebars::new();
+    handlebars.register_escape_fn(no_escape);
+    let mut content = handlebars.render_template(template, &variables)?;
+
+    if !content.contains(table_header.trim_end()) {
+        info!("[render_diary_file_from_handlebars()]: Adding the table header at the end.");
+        if !content.is_empty() {
+            if !content.ends_with('\n') {
+                content.push('\n');
+            }
+            content.push('\n');
+        }
+        content.push_str(&table_header);
+    }
+    Ok(content)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod templater_tests {
+    use super::*;
+    use crate::commit_entry::CommitEntry;
+    use crate::vim_commit::render_table_header;
+    use crate::vim_commit::EntryDetails;
+    use crate::vim_commit::DEFAULT_TABLE_COLUMNS;
+    use chrono::TimeZone;
+    use chrono::Utc;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_render_template_variables() {
+        let datetime = Utc
+            .with_ymd_and_hms(2025, 1, 14, 9, 5, 0)
+            .unwrap()
+            .fixed_offset();
+
+        assert_eq!(
+            render_template_variables(
+                "{{title}}: {{date}} at {{time}}, {{date:YYYY}} <% tp.file.title %>",
+                datetime,
+                "2025-01-14"
+            ),
+            "2025-01-14: 2025-01-14 at 09:05, {{date:YYYY}} <% tp.file.title %>"
+        );
+    }
+
+    #[test]
+    fn test_create_diary_file_from_template() {
+        let temp_dir = tempdir().unwrap();
+        let diary = temp_dir.path().join("2025-01-14.md");
+        let mut commit_saver = CommitSaver {
+            details: EntryDetails {
+                category: Some("work".to_string()),
+                ..EntryDetails::default()
+            },
+            ..CommitSaver::from(CommitEntry {
+                repository_url: "https://github.com/test/repo.git".to_string(),
+                branch: "main".to_string(),
+                hash: "abc123".to_string(),
+                message: "work".to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, 14, 9, 5, 0).unwrap(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            })
+        };
+
+        create_diary_file_from_template(
+            &diary,
+            &mut commit_saver,
+            "---\ntags:\n- daily\ncreated: {{date}}\n---\n# {{title}}\n\n## Log",
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&diary).unwrap();
+        assert!(content.starts_with(
+            "---\ntags:\n- daily\n- '#datetime/week/02'\n- '#datetime/days/Tuesday'\n- '#diary/commits'\n- '#category/work'\ncreated: 2025-01-14\ncategories:\n- 'work'\n---\n# 2025-01-14\n"
+        ));
+        assert!(content.ends_with(&format!(
+            "## Log\n\n{:}",
+            render_table_header(&DEFAULT_TABLE_COLUMNS)
+        )));
+    }
+
+    fn work_commit() -> CommitSaver {
+        CommitSaver {
+            details: EntryDetails {
+                category: Some("work".to_string()),
+                ..EntryDetails::default()
+            },
+            ..CommitSaver::from(CommitEntry {
+                repository_url: "https://github.com/test/repo.git".to_string(),
+                branch: "main".to_string(),
+                hash: "abc123".to_string(),
+                message: "work".to_string(),
+                datetime: Utc.with_ymd_and_hms(2025, 1, 14, 9, 5, 0).unwrap(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_render_diary_file_from_handlebars() {
+        let template = "---\ntags:\n{{#each tags}}\n  - '{{this}}'\n{{/each}}\n---\n# {{title}} & week {{week_number}}\n\n{{table_header}}";
+
+        let content = render_diary_file_from_handlebars(
+            Path::new("/vault/2025-01-14.md"),
+            &mut work_commit(),
+            template,
+        )
+        .unwrap();
+
+        assert_eq!(
+            content,
+            format!(
+                "---\ntags:\n  - '#datetime/week/02'\n  - '#datetime/days/Tuesday'\n  - '#diary/commits'\n  - '#category/work'\n---\n# 2025-01-14 & week 02\n\n{:}",
+                render_table_header(&DEFAULT_TABLE_COLUMNS)
+            )
+        );
+    }
+
+    #[test]
+    fn test_render_diary_file_from_handlebars_adds_a_missing_header() {
+        let content = render_diary_file_from_handlebars(
+            Path::new("/vault/2025-01-14.md"),
+            &mut work_commit(),
+            "# {{date}} {{time}}",
+        )
+        .unwrap();
+
+        assert_eq!(
+            content,
+            format!(
+                "# 2025-01-14 09:05\n\n{:}",
+                render_table_header(&DEFAULT_TABLE_COLUMNS)
+            )
+        );
+        assert!(render_diary_file_from_handlebars(
+            Path::new("/vault/2025-01-14.md"),
+            &mut work_commit(),
+            "{{#each tags}}",
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,562 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::DateTime;
+use chrono::TimeZone;
+use chrono::Utc;
+use git2::Oid;
+use git2::Repository;
+use git2::Signature;
+use git2::Time;
+use log::info;
+use tempfile::TempDir;
+
+use crate::state::LoggedEntry;
+use crate::vim_commit::CommitSaver;
+
+/// Author and committer name of the commits made by [`FakeRepo`].
+pub const FAKE_AUTHOR_NAME: &str = "Test User";
+
+/// Author and committer email of the commits made by [`FakeRepo`].
+pub const FAKE_AUTHOR_EMAIL: &str = "test@example.com";
+
+/// Commit path of a [`TempVault`], relative to its root.
+pub const TEMP_VAULT_COMMIT_PATH: &str = "Diaries/Commits";
+
+/// Repository URL of the entries built by [`LoggedEntryBuilder`].
+pub const FAKE_REPOSITORY_URL: &str = "https://github.com/user/repo.git";
+
+/// Diary path of the entries built by [`LoggedEntryBuilder`].
+pub const FAKE_DIARY_PATH: &str = "/vault/diary.md";
+
+/// A throwaway Git repository in a temporary directory, removed on drop.
+///
+/// Replaces tests that only run when the working directory happens to be a
+/// repository: every test gets its own repository with known commits, so
+/// nothing depends on (or changes) the process's current directory.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::testing::FakeRepo;
+///
+/// let repo = FakeRepo::new()?.with_origin("git@github.com:user/repo.git")?;
+/// repo.commit("feat: add feature")?;
+/// let saver = repo.commit_saver()?;
+/// assert_eq!(saver.entry.message, "feat: add feature");
+/// ```
+pub struct FakeRepo {
+    dir: TempDir,
+    repo: Repository,
+}
+
+impl FakeRepo {
+    /// Creates an empty repository (no commits, no remote).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the temporary directory or the repository cannot be
+    /// created.
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        let dir = TempDir::new()?;
+        let repo = Repository::init(dir.path())?;
+        info!(
+            "[FakeRepo::new()]: Initialized a fake repository in: {:}",
+            dir.path().display()
+        );
+        Ok(FakeRepo { dir, repo })
+    }
+
+    /// Adds an `origin` remote pointing at `url`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the remote cannot be created.
+    pub fn with_origin(self, url: &str) -> Result<Self, Box<dyn Error>> {
+        self.repo.remote("origin", url)?;
+        Ok(self)
+    }
+
+    /// Returns the working directory of the repository.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Returns the `git2` handle of the repository.
+    #[must_use]
+    pub fn repo(&self) -> &Repository {
+        &self.repo
+    }
+
+    /// Commits a change to `tracked.txt` on `HEAD`, authored now.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written or the commit cannot be
+    /// created.
+    pub fn commit(&self, message: &str) -> Result<Oid, Box<dyn Error>> {
+        let count = self.commit_count();
+        self.commit_file("tracked.txt", &format!("change {count:}\n"), message)
+    }
+
+    /// Commits a change to `tracked.txt` on `HEAD`, authored at `datetime`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written or the commit cannot be
+    /// created.
+    pub fn commit_at(&self, message: &str, datetime: DateTime<Utc>) -> Result<Oid, Box<dyn Error>> {
+        self.commit_by(message, FAKE_AUTHOR_EMAIL, datetime)
+    }
+
+    /// Commits a change to `tracked.txt` on `HEAD`, authored by
+    /// `author_email` at `datetime`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written or the commit cannot be
+    /// created.
+    pub fn commit_by(
+        &self,
+        message: &str,
+        author_email: &str,
+        datetime: DateTime<Utc>,
+    ) -> Result<Oid, Box<dyn Error>> {
+        let count = self.commit_count();
+        let signature = Signature::new(
+            FAKE_AUTHOR_NAME,
+            author_email,
+            &Time::new(datetime.timestamp(), 0),
+        )?;
+        self.write_and_commit(
+            "HEAD",
+            "tracked.txt",
+            &format!("change {count:}\n"),
+            message,
+            &signature,
+        )
+    }
+
+    /// Commits a change to `tracked.txt` on `update_ref` (e.g.
+    /// `refs/heads/feature`), authored at `datetime`, without moving `HEAD`.
+    ///
+    /// The commit's parent is the commit `update_ref` points to, if any, so
+    /// branches can grow apart, as in a repository receiving pushes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written or the commit cannot be
+    /// created.
+    pub fn commit_on(
+        &self,
+        update_ref: &str,
+        message: &str,
+        datetime: DateTime<Utc>,
+    ) -> Result<Oid, Box<dyn Error>> {
+        let count = self.commit_count();
+        let signature = Signature::new(
+            FAKE_AUTHOR_NAME,
+            FAKE_AUTHOR_EMAIL,
+            &Time::new(datetime.timestamp(), 0),
+        )?;
+        self.write_and_commit(
+            update_ref,
+            "tracked.txt",
+            &format!("{update_ref:} change {count:}\n"),
+            message,
+            &signature,
+        )
+    }
+
+    /// Writes `content` to `relative_path` and commits it on `HEAD`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written or the commit cannot be
+    /// created.
+    pub fn commit_file(
+        &self,
+        relative_path: &str,
+        content: &str,
+        message: &str,
+    ) -> Result<Oid, Box<dyn Error>> {
+        let signature = Signature::now(FAKE_AUTHOR_NAME, FAKE_AUTHOR_EMAIL)?;
+        self.write_and_commit("HEAD", relative_path, content, message, &signature)
+    }
+
+    /// Creates `branch` at `HEAD` and checks it out.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the repository has no commit yet, or if the branch
+    /// cannot be created or checked out.
+    pub fn checkout_new_branch(&self, branch: &str) -> Result<(), Box<dyn Error>> {
+        let head = self.repo.head()?.peel_to_commit()?;
+        self.repo.branch(branch, &head, false)?;
+        self.repo.set_head(&format!("refs/heads/{branch:}"))?;
+        Ok(())
+    }
+
+    /// Checks out `branch`, which must exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the branch cannot be found or checked out.
+    pub fn checkout(&self, branch: &str) -> Result<(), Box<dyn Error>> {
+        let reference = format!("refs/heads/{branch:}");
+        let tree = self.repo.revparse_single(&reference)?;
+        self.repo
+            .checkout_tree(&tree, Some(git2::build::CheckoutBuilder::new().force()))?;
+        self.repo.set_head(&reference)?;
+        Ok(())
+    }
+
+    /// Merges `branch` into `HEAD` with a merge commit whose tree is the one
+    /// of `HEAD` (as `git merge -s ours` would).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `HEAD` or the branch has no commit, or if the
+    /// merge commit cannot be created.
+    pub fn merge(&self, branch: &str, message: &str) -> Result<Oid, Box<dyn Error>> {
+        let head = self.repo.head()?.peel_to_commit()?;
+        let merged = self
+            .repo
+            .revparse_single(&format!("refs/heads/{branch:}"))?
+            .peel_to_commit()?;
+        let signature = Signature::now(FAKE_AUTHOR_NAME, FAKE_AUTHOR_EMAIL)?;
+        let oid = self.repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &head.tree()?,
+            &[&head, &merged],
+        )?;
+        info!("[FakeRepo::merge()]: Merged {branch:} in {oid:}");
+        Ok(oid)
+    }
+
+    /// Creates the annotated tag `name` at `HEAD`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the repository has no commit yet, or if the tag
+    /// cannot be created.
+    pub fn tag(&self, name: &str) -> Result<Oid, Box<dyn Error>> {
+        let head = self.repo.head()?.peel(git2::ObjectType::Commit)?;
+        let signature = Signature::now(FAKE_AUTHOR_NAME, FAKE_AUTHOR_EMAIL)?;
+        Ok(self
+            .repo
+            .tag(name, &head, &signature, &format!("Release {name:}"), false)?)
+    }
+
+    /// Creates the lightweight tag `name` at `HEAD`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the repository has no commit yet, or if the tag
+    /// cannot be created.
+    pub fn lightweight_tag(&self, name: &str) -> Result<Oid, Box<dyn Error>> {
+        let head = self.repo.head()?.peel(git2::ObjectType::Commit)?;
+        Ok(self.repo.tag_lightweight(name, &head, false)?)
+    }
+
+    /// Builds the [`CommitSaver`] of the `HEAD` commit, see
+    /// [`CommitSaver::from_repo()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the repository has no commit yet.
+    pub fn commit_saver(&self) -> Result<CommitSaver, Box<dyn Error>> {
+        Ok(CommitSaver::from_repo(&self.repo)?)
+    }
+
+    fn commit_count(&self) -> usize {
+        self.repo
+            .revwalk()
+            .and_then(|mut walk| {
+                walk.push_head()?;
+                Ok(walk.count())
+            })
+            .unwrap_or(0)
+    }
+
+    fn write_and_commit(
+        &self,
+        update_ref: &str,
+        relative_path: &str,
+        content: &str,
+        message: &str,
+        signature: &Signature,
+    ) -> Result<Oid, Box<dyn Error>> {
+        let file_path = self.path().join(relative_path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&file_path, content)?;
+
+        let mut index = self.repo.index()?;
+        index.add_path(Path::new(relative_path))?;
+        index.write()?;
+        let tree = self.repo.find_tree(index.write_tree()?)?;
+        // An unborn `HEAD` (or a new ref) has no commit: a root commit then.
+        let parents = self
+            .repo
+            .find_reference(update_ref)
+            .and_then(|reference| reference.peel_to_commit())
+            .ok()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let parents = parents.iter().collect::<Vec<_>>();
+
+        let oid = self.repo.commit(
+            Some(update_ref),
+            signature,
+            signature,
+            message,
+            &tree,
+            &parents,
+        )?;
+        info!("[FakeRepo::write_and_commit()]: Committed {oid:}: {message:}");
+        Ok(oid)
+    }
+}
+
+/// A throwaway Obsidian vault in a temporary directory, removed on drop.
+///
+/// The vault has an `.obsidian` directory (so `[safety] require_vault` is
+/// satisfied) and an empty [`TEMP_VAULT_COMMIT_PATH`].
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::testing::TempVault;
+///
+/// let vault = TempVault::new()?;
+/// vault.write_note("Weekly/2025-W03.md", "# Week 3\n")?;
+/// assert!(vault.diary_files()?.is_empty());
+/// ```
+pub struct TempVault {
+    dir: TempDir,
+}
+
+impl TempVault {
+    /// Creates the vault with its `.obsidian` directory and commit path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directories cannot be created.
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        let dir = TempDir::new()?;
+        fs::create_dir_all(dir.path().join(".obsidian"))?;
+        fs::create_dir_all(dir.path().join(TEMP_VAULT_COMMIT_PATH))?;
+        Ok(TempVault { dir })
+    }
+
+    /// Returns the vault root (`[obsidian] root_path_dir`).
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Returns the commit path relative to the root (`[obsidian] commit_path`).
+    #[must_use]
+    pub fn commit_path(&self) -> PathBuf {
+        PathBuf::from(TEMP_VAULT_COMMIT_PATH)
+    }
+
+    /// Returns the directory diary files are written to.
+    #[must_use]
+    pub fn commits_dir(&self) -> PathBuf {
+        self.root().join(TEMP_VAULT_COMMIT_PATH)
+    }
+
+    /// Returns an INI configuration pointing at this vault, with the given
+    /// `commit_date_path` template and any extra sections appended.
+    #[must_use]
+    pub fn config_ini(&self, commit_date_path: &str, extra_sections: &str) -> String {
+        format!(
+            "[obsidian]\nroot_path_dir = {:}\ncommit_path = {TEMP_VAULT_COMMIT_PATH:}\n\n[templates]\ncommit_date_path = {commit_date_path:}\ncommit_datetime = %H:%M:%S\n\n{extra_sections:}",
+            self.root().display()
+        )
+    }
+
+    /// Writes a note at `relative_path`, creating its directories.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the note cannot be written.
+    pub fn write_note(
+        &self,
+        relative_path: &str,
+        content: &str,
+    ) -> Result<PathBuf, Box<dyn Error>> {
+        let note = self.root().join(relative_path);
+        if let Some(parent) = note.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&note, content)?;
+        Ok(note)
+    }
+
+    /// Reads the note at `relative_path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the note cannot be read.
+    pub fn read_note(&self, relative_path: &str) -> Result<String, Box<dyn Error>> {
+        Ok(fs::read_to_string(self.root().join(relative_path))?)
+    }
+
+    /// Returns every Markdown file under the commit path, sorted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a directory cannot be read.
+    pub fn diary_files(&self) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+        let mut files = Vec::new();
+        let mut pending = vec![self.commits_dir()];
+        while let Some(dir) = pending.pop() {
+            for entry in fs::read_dir(dir)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    pending.push(path);
+                } else if path.extension().is_some_and(|extension| extension == "md") {
+                    files.push(path);
+                }
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+}
+
+/// Builds the [`LoggedEntry`] records of the logged-commits index, with
+/// defaults for every field a test does not care about.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::testing::LoggedEntryBuilder;
+///
+/// let entry = LoggedEntryBuilder::new("abc123")
+///     .on_branch("feature/login")
+///     .in_diary(Path::new("/vault/2025-01-15.md"))
+///     .build();
+/// assert_eq!(entry.repository_url, "https://github.com/user/repo.git");
+/// ```
+#[derive(Debug, Clone)]
+pub struct LoggedEntryBuilder {
+    entry: LoggedEntry,
+}
+
+impl LoggedEntryBuilder {
+    /// Starts an entry for `commit_hash`, made on 2025-01-14 at 10:00 UTC on
+    /// the `main` branch of [`FAKE_REPOSITORY_URL`] and logged in
+    /// [`FAKE_DIARY_PATH`].
+    #[must_use]
+    pub fn new(commit_hash: &str) -> Self {
+        LoggedEntryBuilder {
+            entry: LoggedEntry {
+                commit_datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+                repository_url: FAKE_REPOSITORY_URL.to_string(),
+                branch: "main".to_string(),
+                commit_hash: commit_hash.to_string(),
+                diary_path: FAKE_DIARY_PATH.to_string(),
+            },
+        }
+    }
+
+    /// Sets when the commit was made.
+    #[must_use]
+    pub fn at(mut self, commit_datetime: DateTime<Utc>) -> Self {
+        self.entry.commit_datetime = commit_datetime;
+        self
+    }
+
+    /// Sets the repository URL of the commit.
+    #[must_use]
+    pub fn in_repository(mut self, repository_url: &str) -> Self {
+        self.entry.repository_url = repository_url.to_string();
+        self
+    }
+
+    /// Sets the branch the commit was made on.
+    #[must_use]
+    pub fn on_branch(mut self, branch: &str) -> Self {
+        self.entry.branch = branch.to_string();
+        self
+    }
+
+    /// Sets the diary file the commit was logged in.
+    #[must_use]
+    pub fn in_diary(mut self, diary_path: &Path) -> Self {
+        self.entry.diary_path = diary_path.display().to_string();
+        self
+    }
+
+    /// Returns the entry.
+    #[must_use]
+    pub fn build(self) -> LoggedEntry {
+        self.entry
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod testing_tests {
+    use super::*;
+
+    #[test]
+    fn test_fake_repo_commits_and_branches() -> Result<(), Box<dyn Error>> {
+        let repo = FakeRepo::new()?.with_origin("git@github.com:user/repo.git")?;
+        repo.commit("Initial commit")?;
+        repo.checkout_new_branch("feature/x")?;
+        let datetime = Utc.with_ymd_and_hms(2025, 1, 14, 10, 30, 0).unwrap();
+        let oid = repo.commit_at("feat: second", datetime)?;
+
+        let saver = repo.commit_saver()?;
+        assert_eq!(saver.entry.hash, oid.to_string());
+        assert_eq!(saver.entry.message, "feat: second");
+        assert_eq!(saver.entry.branch, "feature/x");
+        assert_eq!(saver.entry.repository_url, "git@github.com:user/repo.git");
+        assert_eq!(saver.entry.datetime, datetime);
+        assert_eq!(saver.entry.author.as_deref(), Some(FAKE_AUTHOR_NAME));
+        assert_eq!(saver.entry.author_email.as_deref(), Some(FAKE_AUTHOR_EMAIL));
+
+        let head = repo.commit_by("feat: third", "someone@example.com", datetime)?;
+        let saver = repo.commit_saver()?;
+        assert_eq!(
+            saver.entry.author_email.as_deref(),
+            Some("someone@example.com")
+        );
+
+        let other = repo.commit_on("refs/heads/other", "feat: elsewhere", datetime)?;
+        assert_eq!(repo.repo().find_commit(other)?.parent_count(), 0);
+        let more = repo.commit_on("refs/heads/other", "feat: more", datetime)?;
+        assert_eq!(repo.repo().find_commit(more)?.parent_id(0)?, other);
+        assert_eq!(repo.repo().head()?.target(), Some(head));
+        Ok(())
+    }
+
+    #[test]
+    fn test_temp_vault_notes_and_config() -> Result<(), Box<dyn Error>> {
+        let vault = TempVault::new()?;
+        assert!(vault.root().join(".obsidian").is_dir());
+        assert!(vault.diary_files()?.is_empty());
+
+        let diary = vault.write_note("Diaries/Commits/2025/2025-01-14.md", "# Day\n")?;
+        vault.write_note("Weekly/2025-W03.md", "# Week\n")?;
+        assert_eq!(vault.diary_files()?, vec![diary]);
+        assert_eq!(vault.read_note("Weekly/2025-W03.md")?, "# Week\n");
+
+        let config = vault.config_ini("%F.md", "[table]\nforge_links = true\n");
+        assert!(config.contains("commit_path = Diaries/Commits\n"));
+        assert!(config.ends_with("[table]\nforge_links = true\n"));
+        Ok(())
+    }
+}
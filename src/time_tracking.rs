@@ -0,0 +1,174 @@
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use log::info;
+
+use crate::shell_command::run_with_timeout;
+
+/// How long a time-tracking command may run before it is killed.
+///
+/// The query runs inside a Git hook, so a hanging tracker must never block
+/// the commit for long.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Where the currently tracked project/tag is read from at save time.
+///
+/// # Variants
+///
+/// - `Command` - A shell command whose first output line is the activity
+///   (e.g. `timew get dom.active.tag.1`)
+/// - `File` - A file whose first line is the activity (e.g. a Toggl export
+///   written by a script)
+///
+/// # Configuration
+///
+/// ```text
+/// [time_tracking]
+/// command = timew get dom.active.tag.1
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimeTrackingSource {
+    Command(String),
+    File(PathBuf),
+}
+
+/// Queries the configured source for the currently tracked activity.
+///
+/// Only the first non-empty line is used. It is trimmed and pipes are escaped
+/// so the value is safe inside the diary table.
+///
+/// # Returns
+///
+/// - `Ok(Some(activity))` - The tracker reported an activity
+/// - `Ok(None)` - Nothing is being tracked (empty output or file)
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The command cannot be started, exits with a failure status, or runs
+///   longer than 2 seconds
+/// - The file cannot be read
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::time_tracking::{query_current_activity, TimeTrackingSource};
+///
+/// let source = TimeTrackingSource::Command("echo client-a".to_string());
+/// assert_eq!(query_current_activity(&source)?, Some("client-a".to_string()));
+/// ```
+pub fn query_current_activity(
+    source: &TimeTrackingSource,
+) -> Result<Option<String>, Box<dyn Error>> {
+    let output = match source {
+        TimeTrackingSource::Command(command) => {
+            info!("[query_current_activity()]: Running time-tracking command: {command:}");
+            run_command(command)?
+        }
+        TimeTrackingSource::File(path) => {
+            info!(
+                "[query_current_activity()]: Reading time-tracking file: {:}",
+                path.display()
+            );
+            fs::read_to_string(path)?
+        }
+    };
+
+    Ok(output
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(|line| line.replace('|', "\\|")))
+}
+
+/// Runs a shell command and returns its standard output.
+///
+/// # Errors
+///
+/// Returns an error if the command cannot be spawned, exits with a failure
+/// status, or does not finish within [`COMMAND_TIMEOUT`] (it is killed then).
+fn run_command(command: &str) -> Result<String, Box<dyn Error>> {
+    let output = run_with_timeout(command, COMMAND_TIMEOUT)?
+        .ok_or_else(|| format!("time-tracking command timed out: {command:}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "time-tracking command failed with {:}: {command:}",
+            output.status
+        )
+        .into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod time_tracking_tests {
+    use super::*;
+    use std::time::Instant;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_query_current_activity_from_command() {
+        let source =
+            TimeTrackingSource::Command("printf '\\n  client-a | api  \\nother\\n'".into());
+
+        assert_eq!(
+            query_current_activity(&source).unwrap(),
+            Some("client-a \\| api".to_string())
+        );
+    }
+
+    #[test]
+    fn test_query_current_activity_from_command_with_long_output() {
+        let source = TimeTrackingSource::Command("echo client-a; seq 1 100000".into());
+
+        assert_eq!(
+            query_current_activity(&source).unwrap(),
+            Some("client-a".to_string())
+        );
+    }
+
+    #[test]
+    fn test_query_current_activity_from_file() {
+        let temp_dir = tempdir().unwrap();
+        let file = temp_dir.path().join("current.txt");
+        fs::write(&file, "project-x\n").unwrap();
+
+        let source = TimeTrackingSource::File(file);
+
+        assert_eq!(
+            query_current_activity(&source).unwrap(),
+            Some("project-x".to_string())
+        );
+    }
+
+    #[test]
+    fn test_query_current_activity_empty_output_is_none() {
+        let source = TimeTrackingSource::Command("true".into());
+
+        assert_eq!(query_current_activity(&source).unwrap(), None);
+    }
+
+    #[test]
+    fn test_query_current_activity_errors() {
+        let failing = TimeTrackingSource::Command("exit 3".into());
+        let missing = TimeTrackingSource::File(PathBuf::from("/nonexistent/tracking.txt"));
+
+        assert!(query_current_activity(&failing).is_err());
+        assert!(query_current_activity(&missing).is_err());
+    }
+
+    #[test]
+    fn test_query_current_activity_times_out() {
+        let source = TimeTrackingSource::Command("sleep 5".into());
+        let started = Instant::now();
+
+        let result = query_current_activity(&source);
+
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_secs(4));
+    }
+}
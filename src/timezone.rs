@@ -0,0 +1,269 @@
+use std::env;
+
+use chrono::DateTime;
+use chrono::FixedOffset;
+use chrono::Local;
+use chrono::Offset;
+use chrono::TimeZone;
+use chrono::Utc;
+use chrono_tz::Tz;
+use log::info;
+
+/// How the timezone of an entry is written next to its `TIME` and in the
+/// `timezone:` frontmatter field.
+///
+/// # Variants
+///
+/// - `Offset` - The UTC offset, e.g. `+01:00`
+/// - `Abbreviation` - The zone abbreviation, e.g. `CET`; falls back to the
+///   offset when the system timezone is not a known IANA zone
+///
+/// # Configuration
+///
+/// ```text
+/// [table]
+/// time_zone = abbreviation
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimezoneStyle {
+    Offset,
+    Abbreviation,
+}
+
+impl TimezoneStyle {
+    /// Parses the `[table] time_zone` value (`offset` or `abbreviation`, any case).
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "offset" => Some(TimezoneStyle::Offset),
+            "abbreviation" => Some(TimezoneStyle::Abbreviation),
+            _ => None,
+        }
+    }
+}
+
+/// The timezone diary paths, dates and times are written in
+/// (`[templates] timezone`).
+///
+/// # Variants
+///
+/// - `Local` - The timezone of the system, see [`system_timezone()`]
+/// - `Utc` - UTC, as when the option is not set
+/// - `Named` - An IANA timezone, e.g. `Europe/Zurich`
+///
+/// # Configuration
+///
+/// ```text
+/// [templates]
+/// timezone = Australia/Sydney
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiaryTimezone {
+    Local,
+    Utc,
+    Named(Tz),
+}
+
+impl DiaryTimezone {
+    /// Parses the `[templates] timezone` value: `local`, `UTC` (any case) or
+    /// an IANA timezone name.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        match name.to_lowercase().as_str() {
+            "local" => Some(DiaryTimezone::Local),
+            "utc" => Some(DiaryTimezone::Utc),
+            _ => name.parse().ok().map(DiaryTimezone::Named),
+        }
+    }
+
+    /// Returns the IANA timezone, `None` for `Local` when the system timezone
+    /// is not a known one (the local offset is then used).
+    #[must_use]
+    pub fn zone(self) -> Option<Tz> {
+        match self {
+            DiaryTimezone::Local => system_timezone(),
+            DiaryTimezone::Utc => Some(Tz::UTC),
+            DiaryTimezone::Named(zone) => Some(zone),
+        }
+    }
+}
+
+/// The timezone an entry was logged in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryTimezone {
+    /// Offset of the local time from UTC when the entry was logged.
+    pub offset: FixedOffset,
+    /// What is written after the time, e.g. `CET` or `+01:00`; empty when
+    /// times are written without their zone.
+    pub label: String,
+}
+
+impl EntryTimezone {
+    /// Formats a UTC timestamp with `time_format` as local time in this
+    /// timezone, followed by the label if any, e.g. `14:30:45 CET`, or
+    /// `14:30:45` without a label.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use chrono::{FixedOffset, TimeZone, Utc};
+    /// use rusty_commit_saver::timezone::EntryTimezone;
+    ///
+    /// let timezone = EntryTimezone {
+    ///     offset: FixedOffset::east_opt(3_600).unwrap(),
+    ///     label: "CET".to_string(),
+    /// };
+    /// let datetime = Utc.with_ymd_and_hms(2025, 1, 14, 13, 30, 45).unwrap();
+    /// assert_eq!(timezone.format_time(datetime, "%H:%M"), "14:30 CET");
+    /// ```
+    #[must_use]
+    pub fn format_time(&self, datetime: DateTime<Utc>, time_format: &str) -> String {
+        format!(
+            "{:} {:}",
+            datetime.with_timezone(&self.offset).format(time_format),
+            self.label
+        )
+        .trim_end()
+        .to_string()
+    }
+}
+
+/// Returns the IANA timezone of the system, if it is a known one.
+///
+/// The `TZ` environment variable wins over the system setting, like it does
+/// for the rest of the tool's local times.
+#[must_use]
+pub fn system_timezone() -> Option<Tz> {
+    let name = env::var("TZ")
+        .ok()
+        .map(|tz| tz.trim_start_matches(':').to_string())
+        .filter(|tz| !tz.is_empty())
+        .or_else(|| iana_time_zone::get_timezone().ok())?;
+
+    info!("[system_timezone()]: System timezone: {name:}");
+    name.parse().ok()
+}
+
+/// Resolves the timezone of an entry logged at `datetime`.
+///
+/// With a known IANA `zone`, the offset and abbreviation in effect at
+/// `datetime` are used, so daylight saving time is accounted for. Without
+/// one, the local offset is used and the label is always the offset.
+///
+/// # Arguments
+///
+/// * `style` - Whether the label is the offset or the abbreviation; `None`
+///   leaves the label empty
+/// * `zone` - The timezone of the entry (`[templates] timezone`, see
+///   [`DiaryTimezone::zone()`]) or of the system, see [`system_timezone()`]
+/// * `datetime` - When the entry was made
+///
+/// # Examples
+///
+/// ```ignore
+/// use chrono::{TimeZone, Utc};
+/// use rusty_commit_saver::timezone::{entry_timezone, TimezoneStyle};
+///
+/// let summer = Utc.with_ymd_and_hms(2025, 7, 14, 12, 0, 0).unwrap();
+/// let zone = Some(chrono_tz::Europe::Zurich);
+/// assert_eq!(entry_timezone(Some(TimezoneStyle::Abbreviation), zone, summer).label, "CEST");
+/// assert_eq!(entry_timezone(Some(TimezoneStyle::Offset), zone, summer).label, "+02:00");
+/// assert_eq!(entry_timezone(None, zone, summer).label, "");
+/// ```
+#[must_use]
+pub fn entry_timezone(
+    style: Option<TimezoneStyle>,
+    zone: Option<Tz>,
+    datetime: DateTime<Utc>,
+) -> EntryTimezone {
+    let Some(zone) = zone else {
+        let offset = Local.offset_from_utc_datetime(&datetime.naive_utc()).fix();
+        return EntryTimezone {
+            offset,
+            label: style.map(|_| offset.to_string()).unwrap_or_default(),
+        };
+    };
+
+    let local = datetime.with_timezone(&zone);
+    let offset = local.offset().fix();
+    let label = match style {
+        Some(TimezoneStyle::Offset) => offset.to_string(),
+        Some(TimezoneStyle::Abbreviation) => local.format("%Z").to_string(),
+        None => String::new(),
+    };
+    EntryTimezone { offset, label }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod timezone_tests {
+    use super::*;
+
+    #[test]
+    fn test_timezone_style_from_name() {
+        assert_eq!(
+            TimezoneStyle::from_name("offset"),
+            Some(TimezoneStyle::Offset)
+        );
+        assert_eq!(
+            TimezoneStyle::from_name(" Abbreviation "),
+            Some(TimezoneStyle::Abbreviation)
+        );
+        assert_eq!(TimezoneStyle::from_name("utc"), None);
+    }
+
+    #[test]
+    fn test_entry_timezone_follows_daylight_saving_time() {
+        let zone = Some(chrono_tz::Europe::Zurich);
+        let winter = Utc.with_ymd_and_hms(2025, 1, 14, 13, 30, 45).unwrap();
+        let summer = Utc.with_ymd_and_hms(2025, 7, 14, 12, 0, 0).unwrap();
+
+        let timezone = entry_timezone(Some(TimezoneStyle::Abbreviation), zone, winter);
+        assert_eq!(timezone.offset, FixedOffset::east_opt(3_600).unwrap());
+        assert_eq!(timezone.format_time(winter, "%H:%M:%S"), "14:30:45 CET");
+
+        assert_eq!(
+            entry_timezone(Some(TimezoneStyle::Abbreviation), zone, summer).label,
+            "CEST"
+        );
+        assert_eq!(
+            entry_timezone(Some(TimezoneStyle::Offset), zone, summer)
+                .format_time(summer, "%H:%M:%S"),
+            "14:00:00 +02:00"
+        );
+    }
+
+    #[test]
+    fn test_entry_timezone_without_a_known_zone_uses_the_offset() {
+        let datetime = Utc.with_ymd_and_hms(2025, 1, 14, 13, 30, 45).unwrap();
+
+        let timezone = entry_timezone(Some(TimezoneStyle::Abbreviation), None, datetime);
+
+        assert_eq!(timezone.label, timezone.offset.to_string());
+    }
+
+    #[test]
+    fn test_diary_timezone_from_name() {
+        assert_eq!(
+            DiaryTimezone::from_name(" Local "),
+            Some(DiaryTimezone::Local)
+        );
+        assert_eq!(DiaryTimezone::from_name("UTC"), Some(DiaryTimezone::Utc));
+        assert_eq!(
+            DiaryTimezone::from_name("Europe/Zurich"),
+            Some(DiaryTimezone::Named(chrono_tz::Europe::Zurich))
+        );
+        assert_eq!(DiaryTimezone::from_name("Mars/Olympus"), None);
+        assert_eq!(DiaryTimezone::Utc.zone(), Some(Tz::UTC));
+    }
+
+    #[test]
+    fn test_entry_timezone_without_a_style_has_no_label() {
+        let datetime = Utc.with_ymd_and_hms(2025, 1, 14, 13, 30, 45).unwrap();
+
+        let timezone = entry_timezone(None, Some(chrono_tz::Australia::Sydney), datetime);
+        assert_eq!(timezone.offset, FixedOffset::east_opt(39_600).unwrap());
+        assert_eq!(timezone.format_time(datetime, "%-I:%M %p"), "12:30 AM");
+    }
+}
@@ -0,0 +1,306 @@
+use std::error::Error;
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use chrono::NaiveTime;
+use git2::Repository;
+use log::info;
+
+use crate::commit_entry::CommitEntry;
+use crate::config::SaveSettings;
+use crate::diary_parser::link_text;
+use crate::diary_parser::DiaryTable;
+use crate::paths::repository_name_from_url;
+use crate::routing::route_commit_path;
+use crate::state::StateIndex;
+use crate::vim_commit::head_branch_name;
+use crate::vim_commit::origin_url;
+use crate::vim_commit::CommitSaver;
+use crate::vim_commit::TableColumn;
+
+/// Number of hash characters shown in the terminal table.
+const SHORT_HASH_LENGTH: usize = 7;
+
+/// Finds the diary files holding the entries of `date`.
+///
+/// Looks in two places, so routed and per-repository diaries are found too:
+///
+/// 1. The diary path the configuration gives for `date`, with the `[routing]`
+///    table and the `{repo}`/`{branch}` placeholders resolved for `git_repo`
+///    (the repository the command runs in, if any)
+/// 2. The diary files of the commits logged on `date` in the state index
+///
+/// # Returns
+///
+/// The existing diary files, without duplicates.
+///
+/// # Errors
+///
+/// Returns an error if the state index exists but cannot be read.
+pub fn day_diary_paths(
+    date: NaiveDate,
+    git_repo: Option<&Repository>,
+    obsidian_root_path_dir: &Path,
+    obsidian_commit_path: &Path,
+    template_commit_date_path: &str,
+    save_settings: &SaveSettings,
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut day_saver = CommitSaver::from(CommitEntry {
+        repository_url: git_repo.map_or_else(|| "no_url_set".to_string(), origin_url),
+        branch: git_repo.map(head_branch_name).unwrap_or_default(),
+        hash: String::new(),
+        message: String::new(),
+        datetime: date.and_time(NaiveTime::MIN).and_utc(),
+        author: None,
+        author_email: None,
+        stats: None,
+        parents: Vec::new(),
+    });
+    let commit_path = route_commit_path(&save_settings.routes, &day_saver.entry.repository_url)
+        .unwrap_or(obsidian_commit_path);
+    let diary_entry_path =
+        day_saver.prepare_path_for_commit(commit_path, template_commit_date_path);
+
+    let mut configured = obsidian_root_path_dir.to_path_buf();
+    for directory in diary_entry_path.split('/') {
+        configured.push(directory);
+    }
+    let mut candidates = vec![configured];
+
+    if let Some(state_dir) = save_settings.state_dir.as_deref() {
+        candidates.extend(
+            StateIndex::new(state_dir)
+                .entries()?
+                .into_iter()
+                .filter(|entry| entry.commit_datetime.date_naive() == date)
+                .map(|entry| PathBuf::from(entry.diary_path)),
+        );
+    }
+
+    let mut paths = Vec::new();
+    for candidate in candidates {
+        if candidate.is_file() && !paths.contains(&candidate) {
+            paths.push(candidate);
+        }
+    }
+    info!(
+        "[day_diary_paths()]: Found {:} diary file(s) for {date:}.",
+        paths.len()
+    );
+    Ok(paths)
+}
+
+/// Renders a diary table for the terminal.
+///
+/// Columns are aligned, the `FOLDER` column is left out, repository URLs are
+/// shortened to the repository name, hashes to 7 characters, and commit
+/// messages to their first line. With `color`, titles are bold and a few
+/// columns get their own ANSI color.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::today::render_day_table;
+///
+/// print!("{}", render_day_table(&table, std::io::stdout().is_terminal()));
+/// ```
+#[must_use]
+pub fn render_day_table(table: &DiaryTable, color: bool) -> String {
+    let shown = table
+        .headers
+        .iter()
+        .enumerate()
+        .filter(|(_, header)| header.as_str() != TableColumn::Folder.header())
+        .collect::<Vec<_>>();
+
+    let titles = shown
+        .iter()
+        .map(|(_, header)| display_title(header).to_string())
+        .collect::<Vec<_>>();
+    let rows = table
+        .rows
+        .iter()
+        .map(|row| {
+            shown
+                .iter()
+                .map(|(index, header)| {
+                    display_cell(header, row.cells.get(*index).map_or("", String::as_str))
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let widths = titles
+        .iter()
+        .enumerate()
+        .map(|(index, title)| {
+            rows.iter()
+                .map(|cells| cells[index].chars().count())
+                .chain([title.chars().count()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    let mut rendered = render_line(&titles, &widths, |_| color.then_some("1"));
+    for cells in &rows {
+        rendered.push_str(&render_line(cells, &widths, |index| {
+            color.then(|| ansi_code(shown[index].1)).flatten()
+        }));
+    }
+    rendered
+}
+
+/// Pads and colors one line of cells; the last cell is not padded.
+fn render_line(
+    cells: &[String],
+    widths: &[usize],
+    color_of: impl Fn(usize) -> Option<&'static str>,
+) -> String {
+    let mut line = cells
+        .iter()
+        .enumerate()
+        .map(|(index, cell)| {
+            let padded = if index + 1 == cells.len() {
+                cell.clone()
+            } else {
+                format!("{cell:<width$}", width = widths[index])
+            };
+            match color_of(index) {
+                Some(code) => format!("\x1b[{code:}m{padded:}\x1b[0m"),
+                None => padded,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("  ");
+    line.truncate(line.trim_end().len());
+    line.push('\n');
+    line
+}
+
+/// Returns the title shown for a diary table header.
+fn display_title(header: &str) -> &str {
+    if header == TableColumn::RepositoryUrl.header() {
+        "REPOSITORY"
+    } else if header == TableColumn::CommitHash.header() {
+        "HASH"
+    } else {
+        header
+    }
+}
+
+/// Returns the terminal form of a diary table cell.
+fn display_cell(header: &str, cell: &str) -> String {
+    if header == TableColumn::RepositoryUrl.header() {
+        repository_name_from_url(cell)
+    } else if header == TableColumn::CommitHash.header() {
+        link_text(cell).chars().take(SHORT_HASH_LENGTH).collect()
+    } else if header == TableColumn::CommitMessage.header() {
+        cell.split("<br/>")
+            .next()
+            .unwrap_or_default()
+            .replace("\\|", "|")
+    } else {
+        cell.to_string()
+    }
+}
+
+/// Returns the ANSI color code of a column, if it has one.
+fn ansi_code(header: &str) -> Option<&'static str> {
+    [
+        (TableColumn::Time, "2"),
+        (TableColumn::RepositoryUrl, "36"),
+        (TableColumn::Branch, "32"),
+        (TableColumn::CommitHash, "33"),
+        (TableColumn::Event, "35"),
+    ]
+    .into_iter()
+    .find(|(column, _)| column.header() == header)
+    .map(|(_, code)| code)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod today_tests {
+    use super::*;
+    use crate::diary_parser::parse_diary_table;
+    use crate::state::LoggedEntry;
+    use crate::vim_commit::render_table_header;
+    use crate::vim_commit::DEFAULT_TABLE_COLUMNS;
+    use chrono::TimeZone;
+    use chrono::Utc;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn table() -> DiaryTable {
+        parse_diary_table(&format!(
+            "{:}| /src/api | 09:05:00 | fix: a \\| b<br/>details | https://github.com/acme/api.git | main | 0123456789abcdef |\n| /src/web | 10:30:00 | feat: landing page | git@github.com:acme/web.git | feature/x | fedcba9876543210 |\n",
+            render_table_header(&DEFAULT_TABLE_COLUMNS)
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_render_day_table_aligns_columns() {
+        let rendered = render_day_table(&table(), false);
+
+        assert_eq!(
+            rendered,
+            "TIME      COMMIT MESSAGE      REPOSITORY  BRANCH     HASH\n\
+             09:05:00  fix: a | b          api         main       0123456\n\
+             10:30:00  feat: landing page  web         feature/x  fedcba9\n"
+        );
+    }
+
+    #[test]
+    fn test_render_day_table_colors_columns() {
+        let rendered = render_day_table(&table(), true);
+
+        assert!(rendered.starts_with("\x1b[1mTIME    \x1b[0m"));
+        assert!(rendered.contains("\x1b[33mfedcba9\x1b[0m\n"));
+        assert!(rendered.contains("\x1b[32mmain     \x1b[0m"));
+    }
+
+    #[test]
+    fn test_day_diary_paths_finds_configured_and_indexed_files() {
+        let vault = tempdir().unwrap();
+        let state_dir = tempdir().unwrap();
+        let configured = vault.path().join("Commits/2025-01-14.md");
+        let routed = vault.path().join("Work/2025-01-14.md");
+        fs::create_dir_all(configured.parent().unwrap()).unwrap();
+        fs::create_dir_all(routed.parent().unwrap()).unwrap();
+        fs::write(&configured, "").unwrap();
+        fs::write(&routed, "").unwrap();
+
+        let settings = SaveSettings {
+            state_dir: Some(state_dir.path().to_path_buf()),
+            ..SaveSettings::default()
+        };
+        let index = StateIndex::new(state_dir.path());
+        for (day, diary_path) in [(14, &routed), (14, &configured), (15, &routed)] {
+            index
+                .append(&LoggedEntry {
+                    commit_datetime: Utc.with_ymd_and_hms(2025, 1, day, 10, 0, 0).unwrap(),
+                    repository_url: "https://github.com/acme/api.git".to_string(),
+                    branch: "main".to_string(),
+                    commit_hash: format!("abc{day:}"),
+                    diary_path: diary_path.display().to_string(),
+                })
+                .unwrap();
+        }
+
+        let date = NaiveDate::from_ymd_opt(2025, 1, 14).unwrap();
+        let paths = day_diary_paths(
+            date,
+            None,
+            vault.path(),
+            Path::new("Commits"),
+            "%F.md",
+            &settings,
+        )
+        .unwrap();
+
+        assert_eq!(paths, vec![configured, routed]);
+    }
+}
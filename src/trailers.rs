@@ -0,0 +1,205 @@
+/// Commit trailer listing Obsidian tags for the entry, e.g.
+/// `Obsidian-Tags: project-x, deep-work`.
+pub const TAGS_TRAILER: &str = "Obsidian-Tags";
+
+/// Where the tags of the [`TAGS_TRAILER`] trailer are written
+/// (`[trailers] tags`).
+///
+/// # Variants
+///
+/// - `Row` - At the end of the `COMMIT MESSAGE` cell, as inline tags
+/// - `Frontmatter` - To the `tags` list of the diary frontmatter
+/// - `Both` - Both of the above
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagPlacement {
+    Row,
+    Frontmatter,
+    Both,
+}
+
+impl TagPlacement {
+    /// Parses a `[trailers] tags` value: `row`, `frontmatter` or `both`, in
+    /// any case.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "row" => Some(TagPlacement::Row),
+            "frontmatter" => Some(TagPlacement::Frontmatter),
+            "both" => Some(TagPlacement::Both),
+            _ => None,
+        }
+    }
+
+    /// Whether the tags go to the diary row.
+    #[must_use]
+    pub fn in_row(self) -> bool {
+        matches!(self, TagPlacement::Row | TagPlacement::Both)
+    }
+
+    /// Whether the tags go to the diary frontmatter.
+    #[must_use]
+    pub fn in_frontmatter(self) -> bool {
+        matches!(self, TagPlacement::Frontmatter | TagPlacement::Both)
+    }
+}
+
+/// The tags read from the trailers of a commit, and where they go.
+///
+/// # Fields
+///
+/// - `tags` - The tags, `#`-prefixed, in trailer order
+/// - `placement` - Where they are written, see [`TagPlacement`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrailerTags {
+    pub tags: Vec<String>,
+    pub placement: TagPlacement,
+}
+
+/// Returns the trailers of a commit message, as `(key, value)` pairs in
+/// message order.
+///
+/// Works on the message as stored in [`CommitSaver`](crate::vim_commit::CommitSaver),
+/// whose lines are joined with `<br/>`. The trailers are the `Key: value`
+/// lines at the end of the message, after the subject line; the value keeps
+/// its surrounding whitespace.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::trailers::commit_trailers;
+///
+/// let trailers = commit_trailers("Fix login<br/>Reviewed-by: Ana<br/>Rcs-Skip: true");
+/// assert_eq!(trailers, vec![("Reviewed-by", " Ana"), ("Rcs-Skip", " true")]);
+/// ```
+#[must_use]
+pub fn commit_trailers(commit_msg: &str) -> Vec<(&str, &str)> {
+    let lines = commit_msg.split("<br/>").collect::<Vec<_>>();
+    let mut trailers = lines
+        .iter()
+        .skip(1)
+        .rev()
+        .map_while(|line| parse_trailer(line))
+        .collect::<Vec<_>>();
+    trailers.reverse();
+    trailers
+}
+
+/// Splits a `Key: value` trailer line, or returns `None` for any other line.
+fn parse_trailer(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once(':')?;
+    let is_token = !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    is_token.then_some((key, value))
+}
+
+/// Reads the tags of the [`TAGS_TRAILER`] trailers of a commit message.
+///
+/// Tags are separated by commas or spaces, and get a `#` prefix when they
+/// have none. The trailer key is matched case-insensitively, and a tag given
+/// twice is kept once.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::trailers::trailer_tags;
+///
+/// assert_eq!(
+///     trailer_tags("Refactor auth<br/>Obsidian-Tags: project-x, #deep-work"),
+///     vec!["#project-x", "#deep-work"]
+/// );
+/// ```
+#[must_use]
+pub fn trailer_tags(commit_msg: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    let values = commit_trailers(commit_msg)
+        .into_iter()
+        .filter(|(key, _)| key.eq_ignore_ascii_case(TAGS_TRAILER))
+        .map(|(_, value)| value);
+    for tag in values.flat_map(|value| value.split(|c: char| c == ',' || c.is_whitespace())) {
+        let tag = tag.trim_start_matches('#');
+        if tag.is_empty() {
+            continue;
+        }
+        let tag = format!("#{tag:}");
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Removes the [`TAGS_TRAILER`] trailers from a commit message, once their
+/// tags are written to the row.
+///
+/// Other lines, trailers included, are kept in order.
+#[must_use]
+pub fn strip_tags_trailers(commit_msg: &str) -> String {
+    let lines = commit_msg.split("<br/>").collect::<Vec<_>>();
+    let trailers = commit_trailers(commit_msg).len();
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(index, line)| {
+            *index + trailers < lines.len()
+                || !parse_trailer(line)
+                    .is_some_and(|(key, _)| key.eq_ignore_ascii_case(TAGS_TRAILER))
+        })
+        .map(|(_, line)| *line)
+        .collect::<Vec<_>>()
+        .join("<br/>")
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod trailers_tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_trailers() {
+        assert_eq!(
+            commit_trailers("Fix login<br/>Some details<br/>Reviewed-by: Ana<br/>Rcs-Skip: true"),
+            vec![("Reviewed-by", " Ana"), ("Rcs-Skip", " true")]
+        );
+        assert!(commit_trailers("Obsidian-Tags: project-x").is_empty());
+        assert!(commit_trailers("Fix login<br/>See: the docs.<br/>Thanks").is_empty());
+    }
+
+    #[test]
+    fn test_trailer_tags() {
+        assert_eq!(
+            trailer_tags(
+                "Refactor auth<br/>obsidian-tags: project-x, #deep-work<br/>Obsidian-Tags: focus project-x"
+            ),
+            vec!["#project-x", "#deep-work", "#focus"]
+        );
+        assert!(trailer_tags("Refactor auth<br/>Obsidian-Tags: ,").is_empty());
+        assert!(trailer_tags("Obsidian-Tags: project-x").is_empty());
+    }
+
+    #[test]
+    fn test_strip_tags_trailers() {
+        assert_eq!(
+            strip_tags_trailers(
+                "Refactor auth<br/>Obsidian-Tags: a<br/>Reviewed-by: Ana<br/>obsidian-tags: b"
+            ),
+            "Refactor auth<br/>Reviewed-by: Ana"
+        );
+        assert_eq!(
+            strip_tags_trailers("Obsidian-Tags: in the subject"),
+            "Obsidian-Tags: in the subject"
+        );
+    }
+
+    #[test]
+    fn test_tag_placement_from_name() {
+        assert_eq!(TagPlacement::from_name(" Row "), Some(TagPlacement::Row));
+        assert_eq!(
+            TagPlacement::from_name("FRONTMATTER"),
+            Some(TagPlacement::Frontmatter)
+        );
+        assert_eq!(TagPlacement::from_name("both"), Some(TagPlacement::Both));
+        assert_eq!(TagPlacement::from_name("cell"), None);
+        assert!(TagPlacement::Both.in_row() && TagPlacement::Both.in_frontmatter());
+        assert!(!TagPlacement::Row.in_frontmatter());
+        assert!(!TagPlacement::Frontmatter.in_row());
+    }
+}
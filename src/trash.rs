@@ -0,0 +1,173 @@
+use std::error::Error;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+
+use log::info;
+
+/// Name of the trash folder Obsidian keeps at the vault root.
+///
+/// Files moved there show up in Obsidian's own "Trash" and can be restored
+/// like any note the user deleted from the app.
+pub const VAULT_TRASH_DIR: &str = ".trash";
+
+/// Returns a free path for `file` inside `trash_dir`.
+///
+/// Follows Obsidian's naming: when `2025-01-14.md` is already in the trash,
+/// the next copy becomes `2025-01-14 1.md`, then `2025-01-14 2.md`, ...
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::trash::trash_destination;
+/// use std::path::Path;
+///
+/// let destination = trash_destination(Path::new("/vault/.trash"), Path::new("/vault/Commits/2025-01-14.md"));
+/// assert_eq!(destination, Path::new("/vault/.trash/2025-01-14.md"));
+/// ```
+#[must_use]
+pub fn trash_destination(trash_dir: &Path, file: &Path) -> PathBuf {
+    let stem = file
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = file
+        .extension()
+        .map(|extension| format!(".{:}", extension.to_string_lossy()))
+        .unwrap_or_default();
+
+    let mut destination = trash_dir.join(format!("{stem:}{extension:}"));
+    let mut copy_number = 1;
+    while destination.exists() {
+        destination = trash_dir.join(format!("{stem:} {copy_number:}{extension:}"));
+        copy_number += 1;
+    }
+    destination
+}
+
+/// Moves `file` to the vault trash instead of deleting it.
+///
+/// Falls back to copy-and-delete when the trash is on another file system.
+///
+/// # Returns
+///
+/// The path the file was moved to.
+///
+/// # Errors
+///
+/// Returns an error if the trash folder cannot be created or the file cannot
+/// be moved.
+pub fn move_to_trash(trash_dir: &Path, file: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    fs::create_dir_all(trash_dir)?;
+    let destination = trash_destination(trash_dir, file);
+
+    info!(
+        "[move_to_trash()]: Moving {:} to: {:}",
+        file.display(),
+        destination.display()
+    );
+    match fs::rename(file, &destination) {
+        Ok(()) => (),
+        Err(e) if e.kind() == ErrorKind::CrossesDevices => {
+            fs::copy(file, &destination)?;
+            fs::remove_file(file)?;
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    Ok(destination)
+}
+
+/// Puts a copy of `file` in the vault trash before it is rewritten.
+///
+/// Rewrites that drop rows (e.g. `verify --fix`) can then be undone from
+/// Obsidian's trash.
+///
+/// # Returns
+///
+/// The path of the copy.
+///
+/// # Errors
+///
+/// Returns an error if the trash folder cannot be created or the file cannot
+/// be copied.
+pub fn keep_copy_in_trash(trash_dir: &Path, file: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    fs::create_dir_all(trash_dir)?;
+    let destination = trash_destination(trash_dir, file);
+
+    info!(
+        "[keep_copy_in_trash()]: Copying {:} to: {:}",
+        file.display(),
+        destination.display()
+    );
+    fs::copy(file, &destination)?;
+    Ok(destination)
+}
+
+/// Removes `file`, through the vault trash when `trash_dir` is set.
+///
+/// With `[safety] use_trash = false`, `trash_dir` is `None` and the file is
+/// deleted permanently.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be moved or deleted.
+pub fn remove_file_safely(file: &Path, trash_dir: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    if let Some(trash_dir) = trash_dir {
+        move_to_trash(trash_dir, file)?;
+    } else {
+        info!("[remove_file_safely()]: Deleting: {:}", file.display());
+        fs::remove_file(file)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod trash_tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_move_to_trash_numbers_copies_like_obsidian() {
+        let vault = tempdir().unwrap();
+        let trash_dir = vault.path().join(VAULT_TRASH_DIR);
+        let note = vault.path().join("2025-01-14.md");
+
+        fs::write(&note, "first").unwrap();
+        let first = move_to_trash(&trash_dir, &note).unwrap();
+        fs::write(&note, "second").unwrap();
+        let second = move_to_trash(&trash_dir, &note).unwrap();
+
+        assert!(!note.exists());
+        assert_eq!(first, trash_dir.join("2025-01-14.md"));
+        assert_eq!(second, trash_dir.join("2025-01-14 1.md"));
+        assert_eq!(fs::read_to_string(second).unwrap(), "second");
+    }
+
+    #[test]
+    fn test_keep_copy_in_trash_leaves_the_file() {
+        let vault = tempdir().unwrap();
+        let trash_dir = vault.path().join(VAULT_TRASH_DIR);
+        let note = vault.path().join("2025-01-14.md");
+        fs::write(&note, "rows").unwrap();
+
+        let copy = keep_copy_in_trash(&trash_dir, &note).unwrap();
+
+        assert_eq!(fs::read_to_string(&note).unwrap(), "rows");
+        assert_eq!(fs::read_to_string(copy).unwrap(), "rows");
+    }
+
+    #[test]
+    fn test_remove_file_safely_without_trash_deletes() {
+        let vault = tempdir().unwrap();
+        let note = vault.path().join("2025-01-14.md");
+        fs::write(&note, "rows").unwrap();
+
+        remove_file_safely(&note, None).unwrap();
+
+        assert!(!note.exists());
+        assert!(!vault.path().join(VAULT_TRASH_DIR).exists());
+    }
+}
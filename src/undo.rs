@@ -0,0 +1,442 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use log::info;
+
+use crate::annotate::find_logged_entry;
+use crate::atomic_write::write_atomically;
+use crate::diary_lock::DiaryLock;
+use crate::diary_lock::LOCK_TIMEOUT;
+use crate::diary_parser::parse_diary_table;
+use crate::diary_parser::split_block_anchor;
+use crate::diary_parser::DiaryTable;
+use crate::hydration::ensure_hydrated;
+use crate::hydration::HYDRATION_ATTEMPTS;
+use crate::hydration::HYDRATION_RETRY_DELAY;
+use crate::state::EntryFilter;
+use crate::state::LoggedEntry;
+use crate::state::StateIndex;
+use crate::trash::keep_copy_in_trash;
+use crate::vim_commit::yaml_quote;
+use crate::vim_commit::TableColumn;
+
+/// Key added to a struck-through YAML entry.
+const UNDONE_KEY: &str = "undone";
+
+/// What `undo` does to the diary entry of a commit.
+///
+/// # Variants
+///
+/// - `Remove` - Deletes the entry (default)
+/// - `Strike` - Keeps the entry, struck through (`~~...~~`), so the diary
+///   still shows that the commit was logged
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UndoMode {
+    #[default]
+    Remove,
+    Strike,
+}
+
+/// The entry `undo` removed or struck through.
+///
+/// # Fields
+///
+/// - `entry` - Its logged-commits index entry, removed from the index
+/// - `lines` - Its lines in the diary file, as they were before the undo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndoneEntry {
+    pub entry: LoggedEntry,
+    pub lines: Vec<String>,
+}
+
+/// Removes or strikes through the entry of `commit_hash` in a diary file's
+/// content.
+///
+/// The entry is found like [`annotate_entry()`](crate::annotate::annotate_entry)
+/// finds it:
+///
+/// - Table row - Removed, or its `COMMIT MESSAGE` cell struck through (every
+///   cell but the hash in tables without that column)
+/// - YAML block - Removed with the blank line after it, or marked with
+///   `undone: true`
+/// - Anything else mentioning the full hash (e.g. a templated bullet) -
+///   Removed with its nested lines, or its text struck through
+///
+/// The hash itself is never struck through, so the entry can still be
+/// found, e.g. by `annotate`.
+///
+/// # Returns
+///
+/// The rewritten content and the lines of the entry before the undo, or
+/// `None` if no entry of `commit_hash` is found.
+#[must_use]
+pub fn undo_entry(
+    content: &str,
+    commit_hash: &str,
+    mode: UndoMode,
+) -> Option<(String, Vec<String>)> {
+    let mut lines = content
+        .split_inclusive('\n')
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+
+    if let Some(table) = parse_diary_table(content) {
+        if let Some(row) = table
+            .rows
+            .iter()
+            .rev()
+            .find(|row| table.cell(row, TableColumn::CommitHash) == Some(commit_hash))
+        {
+            let undone = vec![lines[row.line_index].clone()];
+            match mode {
+                UndoMode::Remove => {
+                    lines.remove(row.line_index);
+                }
+                UndoMode::Strike => strike_table_row(&mut lines, &table, row.line_index),
+            }
+            return Some((lines.concat(), undone));
+        }
+    }
+
+    let yaml_line = format!(
+        "{:}: {:}",
+        TableColumn::CommitHash.key(),
+        yaml_quote(commit_hash)
+    );
+    if let Some(line_index) = lines.iter().rposition(|line| line.trim_end() == yaml_line) {
+        let undone = undo_yaml_block(&mut lines, line_index, mode);
+        return Some((lines.concat(), undone));
+    }
+
+    let line_index = lines.iter().rposition(|line| line.contains(commit_hash))?;
+    let line = &lines[line_index];
+    let indent = line[..line.len() - line.trim_start().len()].to_string();
+    let mut entry_end = line_index + 1;
+    while lines
+        .get(entry_end)
+        .is_some_and(|next| next.starts_with(&format!("{indent:}  ")))
+    {
+        entry_end += 1;
+    }
+    let undone = lines[line_index..entry_end].to_vec();
+    match mode {
+        UndoMode::Remove => {
+            lines.drain(line_index..entry_end);
+        }
+        UndoMode::Strike => lines[line_index] = strike_line(&lines[line_index]),
+    }
+    Some((lines.concat(), undone))
+}
+
+/// Wraps `text` in `~~`, unless it is empty or already struck through.
+fn strike(text: &str) -> String {
+    if text.is_empty() || (text.starts_with("~~") && text.ends_with("~~")) {
+        text.to_string()
+    } else {
+        format!("~~{text:}~~")
+    }
+}
+
+/// Strikes through the message of the table row at `line_index`.
+fn strike_table_row(lines: &mut [String], table: &DiaryTable, line_index: usize) {
+    let row = table
+        .rows
+        .iter()
+        .find(|row| row.line_index == line_index)
+        .expect("The undone row belongs to the table");
+    let hash_index = table.column_index(TableColumn::CommitHash);
+    let message_index = table.column_index(TableColumn::CommitMessage);
+    let cells = row
+        .cells
+        .iter()
+        .enumerate()
+        .map(|(index, cell)| match message_index {
+            Some(message_index) if index == message_index => strike(cell),
+            None if Some(index) != hash_index => strike(cell),
+            _ => cell.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    let ending = if lines[line_index].ends_with('\n') {
+        "\n"
+    } else {
+        ""
+    };
+    let (_, anchor) = split_block_anchor(&lines[line_index]);
+    lines[line_index] = format!("| {:} |{anchor:}{ending:}", cells.join(" | "));
+}
+
+/// Strikes through the text of a templated line, after its indentation and
+/// list marker.
+fn strike_line(line: &str) -> String {
+    let text = line.trim_end();
+    let body = text.trim_start();
+    let indent = &text[..text.len() - body.len()];
+    let (marker, body) = ["- [ ] ", "- [x] ", "- ", "* "]
+        .iter()
+        .find_map(|marker| body.strip_prefix(marker).map(|body| (*marker, body)))
+        .unwrap_or(("", body));
+    let ending = if line.ends_with('\n') { "\n" } else { "" };
+    format!("{indent:}{marker:}{:}{ending:}", strike(body))
+}
+
+/// Removes or marks the YAML block holding `line_index`.
+///
+/// # Returns
+///
+/// The lines of the block, fences included.
+fn undo_yaml_block(lines: &mut Vec<String>, line_index: usize, mode: UndoMode) -> Vec<String> {
+    let block_end = (line_index..lines.len())
+        .find(|index| lines[*index].trim_end() == "```")
+        .unwrap_or(lines.len() - 1);
+    let block_start = (0..line_index)
+        .rev()
+        .find(|index| lines[*index].trim_start().starts_with("```"))
+        .unwrap_or(line_index);
+    let undone = lines[block_start..=block_end].to_vec();
+
+    match mode {
+        UndoMode::Remove => {
+            let followed_by_blank = lines
+                .get(block_end + 1)
+                .is_some_and(|line| line.trim().is_empty());
+            lines.drain(block_start..=block_end + usize::from(followed_by_blank));
+        }
+        UndoMode::Strike => {
+            let key = format!("{UNDONE_KEY:}: ");
+            if !lines[block_start..block_end]
+                .iter()
+                .any(|line| line.starts_with(&key))
+            {
+                lines.insert(block_end, format!("{key:}true\n"));
+            }
+        }
+    }
+    undone
+}
+
+/// Undoes the logged commit starting with `hash_prefix`, anywhere in the
+/// history (`undo --hash`), or the last logged entry without one (`undo`).
+/// Only the entries passing `filter` (`--repo`, `--branch`) are looked at.
+///
+/// The entry is removed from (or struck through in) its diary file with
+/// [`undo_entry()`], then dropped from the logged-commits index, so the
+/// commit can be logged again and no longer counts in the rollups. The diary
+/// file is found through the index, hydrated if it is a cloud placeholder,
+/// and rewritten atomically under the diary lock; when `trash_dir` is set, a
+/// copy is kept in the vault trash first.
+///
+/// With `dry_run`, nothing is written: the returned entry shows what would
+/// be undone.
+///
+/// # Errors
+///
+/// Returns an error if the index is empty, if the hash matches no single
+/// logged commit, or if the diary file cannot be read, has no entry of the
+/// commit, or cannot be rewritten.
+pub fn undo_logged_commit(
+    index: &StateIndex,
+    hash_prefix: Option<&str>,
+    filter: &EntryFilter,
+    mode: UndoMode,
+    dry_run: bool,
+    trash_dir: Option<&Path>,
+) -> Result<UndoneEntry, Box<dyn Error>> {
+    let mut entries = index.entries()?;
+    let candidates = entries
+        .iter()
+        .filter(|entry| filter.matches(entry))
+        .cloned()
+        .collect::<Vec<_>>();
+    let entry = match hash_prefix {
+        Some(hash_prefix) => find_logged_entry(&candidates, hash_prefix)?,
+        None => candidates.last().ok_or("No logged commit to undo.")?,
+    }
+    .clone();
+    let diary_path = PathBuf::from(&entry.diary_path);
+
+    ensure_hydrated(&diary_path, HYDRATION_ATTEMPTS, HYDRATION_RETRY_DELAY)?;
+    let _lock = (!dry_run)
+        .then(|| DiaryLock::acquire(index.index_path().parent(), &diary_path, LOCK_TIMEOUT))
+        .transpose()?;
+    let content = fs::read_to_string(&diary_path)?;
+    let (undone, lines) = undo_entry(&content, &entry.commit_hash, mode).ok_or_else(|| {
+        format!(
+            "No entry of {:} in {:}; `verify --fix` prunes such index entries.",
+            entry.commit_hash,
+            diary_path.display()
+        )
+    })?;
+    if dry_run {
+        info!(
+            "[undo_logged_commit()]: Dry run, leaving {:} in: {:}",
+            entry.commit_hash,
+            diary_path.display()
+        );
+        return Ok(UndoneEntry { entry, lines });
+    }
+
+    info!(
+        "[undo_logged_commit()]: Undoing {:} ({mode:?}) in: {:}",
+        entry.commit_hash,
+        diary_path.display()
+    );
+    if let Some(trash_dir) = trash_dir {
+        keep_copy_in_trash(trash_dir, &diary_path)?;
+    }
+    write_atomically(&diary_path, undone)?;
+
+    if let Some(position) = entries.iter().rposition(|logged| *logged == entry) {
+        entries.remove(position);
+    }
+    index.replace_entries(&entries)?;
+    Ok(UndoneEntry { entry, lines })
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod undo_tests {
+    use super::*;
+    use crate::testing::LoggedEntryBuilder;
+    use tempfile::tempdir;
+
+    const TABLE: &str = "# 2025-01-14\n\n| TIME | COMMIT MESSAGE | COMMIT HASH |\n|------|----------------|-------------|\n| 10:00:00 | feat: api | abc123 |\n| 11:00:00 | fix: bug | def456 | ^rcs-k3xq9amd\n";
+
+    #[test]
+    fn test_undo_entry_in_a_table() {
+        let (removed, lines) = undo_entry(TABLE, "abc123", UndoMode::Remove).unwrap();
+        assert_eq!(
+            removed,
+            TABLE.replace("| 10:00:00 | feat: api | abc123 |\n", "")
+        );
+        assert_eq!(lines, vec!["| 10:00:00 | feat: api | abc123 |\n"]);
+
+        let (struck, _) = undo_entry(TABLE, "def456", UndoMode::Strike).unwrap();
+        assert!(struck.ends_with("| 11:00:00 | ~~fix: bug~~ | def456 | ^rcs-k3xq9amd\n"));
+        let (twice, _) = undo_entry(&struck, "def456", UndoMode::Strike).unwrap();
+        assert_eq!(twice, struck);
+
+        let linked =
+            "| EVENT | COMMIT HASH |\n|---|---|\n| push | [abc123](https://forge/abc123) |\n";
+        let (struck, _) = undo_entry(linked, "abc123", UndoMode::Strike).unwrap();
+        assert!(struck.ends_with("| ~~push~~ | [abc123](https://forge/abc123) |\n"));
+        assert_eq!(undo_entry(TABLE, "fff000", UndoMode::Remove), None);
+    }
+
+    #[test]
+    fn test_undo_entry_in_yaml_and_template_entries() {
+        let yaml =
+            "```yaml\ncommit_hash: \"abc123\"\n```\n\n```yaml\ncommit_hash: \"def456\"\n```\n\n";
+        let (removed, lines) = undo_entry(yaml, "abc123", UndoMode::Remove).unwrap();
+        assert_eq!(removed, "```yaml\ncommit_hash: \"def456\"\n```\n\n");
+        assert_eq!(lines.len(), 3);
+        let (struck, _) = undo_entry(yaml, "def456", UndoMode::Strike).unwrap();
+        assert!(struck.ends_with("```yaml\ncommit_hash: \"def456\"\nundone: true\n```\n\n"));
+
+        let bullets = "- 10:00 feat: api (abc123)\n  - note\n- 11:00 fix: bug (def456)\n";
+        let (removed, lines) = undo_entry(bullets, "abc123", UndoMode::Remove).unwrap();
+        assert_eq!(removed, "- 11:00 fix: bug (def456)\n");
+        assert_eq!(lines.len(), 2);
+        let (struck, _) = undo_entry(bullets, "abc123", UndoMode::Strike).unwrap();
+        assert!(struck.starts_with("- ~~10:00 feat: api (abc123)~~\n  - note\n"));
+    }
+
+    #[test]
+    fn test_undo_logged_commit_updates_the_diary_and_the_index() {
+        let vault = tempdir().unwrap();
+        let state_dir = tempdir().unwrap();
+        let index = StateIndex::new(state_dir.path());
+        let older = vault.path().join("2025-01-13.md");
+        let diary = vault.path().join("2025-01-14.md");
+        fs::write(&older, TABLE.replace("abc123", "aaa111")).unwrap();
+        fs::write(&diary, TABLE).unwrap();
+        for entry in [
+            LoggedEntryBuilder::new("aaa111").in_diary(&older).build(),
+            LoggedEntryBuilder::new("abc123").in_diary(&diary).build(),
+            LoggedEntryBuilder::new("def456").in_diary(&diary).build(),
+        ] {
+            index.append(&entry).unwrap();
+        }
+
+        let preview = undo_logged_commit(
+            &index,
+            Some("aaa1"),
+            &EntryFilter::default(),
+            UndoMode::Remove,
+            true,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            preview.entry,
+            LoggedEntryBuilder::new("aaa111").in_diary(&older).build()
+        );
+        assert_eq!(preview.lines, vec!["| 10:00:00 | feat: api | aaa111 |\n"]);
+        assert_eq!(index.entries().unwrap().len(), 3);
+        assert!(fs::read_to_string(&older).unwrap().contains("aaa111"));
+
+        let trash = vault.path().join(".trash");
+        undo_logged_commit(
+            &index,
+            Some("aaa1"),
+            &EntryFilter::default(),
+            UndoMode::Remove,
+            false,
+            Some(&trash),
+        )
+        .unwrap();
+        assert!(!fs::read_to_string(&older).unwrap().contains("aaa111"));
+        assert_eq!(fs::read_dir(&trash).unwrap().count(), 1);
+
+        let other_repository = EntryFilter {
+            repository: Some("web".to_string()),
+            branch: None,
+        };
+        assert!(undo_logged_commit(
+            &index,
+            None,
+            &other_repository,
+            UndoMode::Remove,
+            true,
+            None
+        )
+        .is_err());
+        let main_branch = EntryFilter {
+            repository: Some("repo".to_string()),
+            branch: Some("main".to_string()),
+        };
+        let preview =
+            undo_logged_commit(&index, None, &main_branch, UndoMode::Remove, true, None).unwrap();
+        assert_eq!(preview.entry.commit_hash, "def456");
+
+        let last = undo_logged_commit(
+            &index,
+            None,
+            &EntryFilter::default(),
+            UndoMode::Strike,
+            false,
+            None,
+        )
+        .unwrap();
+        assert_eq!(last.entry.commit_hash, "def456");
+        assert!(fs::read_to_string(&diary)
+            .unwrap()
+            .contains("| ~~fix: bug~~ | def456 |"));
+        assert_eq!(
+            index.entries().unwrap(),
+            vec![LoggedEntryBuilder::new("abc123").in_diary(&diary).build()]
+        );
+
+        assert!(undo_logged_commit(
+            &index,
+            Some("aaa1"),
+            &EntryFilter::default(),
+            UndoMode::Remove,
+            false,
+            None
+        )
+        .is_err());
+    }
+}
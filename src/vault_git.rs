@@ -0,0 +1,209 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use git2::Oid;
+use git2::Repository;
+use git2::Signature;
+use log::info;
+
+use crate::paths::repository_name_from_url;
+use crate::vim_commit::CommitSaver;
+
+/// Trailer added to every vault commit created by the tool.
+///
+/// Commits carrying it are never logged to the diary, so a post-commit hook
+/// installed in the vault repository cannot feed the auto-commit back into itself.
+pub const VAULT_COMMIT_TRAILER: &str = "Rusty-Commit-Saver: vault-sync";
+
+/// Vault commit message used when `[vault_git]` has no `message` key.
+pub const DEFAULT_VAULT_COMMIT_MESSAGE: &str = "Log {repo}@{hash} in the commit diary";
+
+/// Number of hash characters used for the `{hash}` placeholder.
+const SHORT_HASH_LENGTH: usize = 7;
+
+/// Name and email used when the vault repository has no `user.name`/`user.email`.
+const FALLBACK_SIGNATURE: (&str, &str) = ("rusty-commit-saver", "rusty-commit-saver@localhost");
+
+/// Renders the vault commit message for a saved entry.
+///
+/// # Placeholders
+///
+/// - `{repo}` - Repository name taken from the remote URL
+/// - `{branch}` - Branch of the logged entry
+/// - `{hash}` - Short (7 characters) hash of the logged commit
+/// - `{date}` - Entry date, `YYYY-MM-DD`
+///
+/// The [`VAULT_COMMIT_TRAILER`] is always appended as a Git trailer.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::vault_git::render_vault_commit_message;
+///
+/// let message = render_vault_commit_message("Log {repo} on {branch}", &saver);
+/// assert!(message.starts_with("Log my-repo on main\n\n"));
+/// ```
+#[must_use]
+pub fn render_vault_commit_message(template: &str, commit_saver: &CommitSaver) -> String {
+    let short_hash = commit_saver
+        .entry
+        .hash
+        .chars()
+        .take(SHORT_HASH_LENGTH)
+        .collect::<String>();
+    let subject = template
+        .replace(
+            "{repo}",
+            &repository_name_from_url(&commit_saver.entry.repository_url),
+        )
+        .replace("{branch}", &commit_saver.entry.branch)
+        .replace("{hash}", &short_hash)
+        .replace(
+            "{date}",
+            &commit_saver.entry.datetime.format("%F").to_string(),
+        );
+
+    format!("{:}\n\n{VAULT_COMMIT_TRAILER:}\n", subject.trim())
+}
+
+/// Returns `true` if a commit message was written by [`commit_diary_file()`].
+#[must_use]
+pub fn is_vault_sync_message(message: &str) -> bool {
+    message.contains(VAULT_COMMIT_TRAILER)
+}
+
+/// Stages a diary file and commits it in the vault repository.
+///
+/// The repository is discovered from the diary file's directory, so the vault
+/// (or any parent directory) must be a Git work tree. Only the diary file is
+/// staged; other pending changes in the vault are left alone. The commit is
+/// created through libgit2, which does not run Git hooks.
+///
+/// # Returns
+///
+/// - `Ok(Some(oid))` - The new vault commit
+/// - `Ok(None)` - The diary file was already committed as-is
+///
+/// # Errors
+///
+/// Returns an error if no repository contains the diary file, the file is
+/// outside its work tree, or staging or committing fails.
+pub fn commit_diary_file(diary_path: &Path, message: &str) -> Result<Option<Oid>, Box<dyn Error>> {
+    let diary_dir = diary_path
+        .parent()
+        .ok_or("The diary file has no parent directory")?;
+    let vault_repo = Repository::discover(diary_dir)?;
+    let workdir = vault_repo
+        .workdir()
+        .ok_or("The vault repository is bare")?
+        .canonicalize()?;
+    let relative_path = fs::canonicalize(diary_path)?
+        .strip_prefix(&workdir)?
+        .to_path_buf();
+
+    info!(
+        "[commit_diary_file()]: Staging {:} in: {:}",
+        relative_path.display(),
+        workdir.display()
+    );
+    let mut index = vault_repo.index()?;
+    index.add_path(&relative_path)?;
+    index.write()?;
+    let tree = vault_repo.find_tree(index.write_tree()?)?;
+
+    let parent = vault_repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+    if parent
+        .as_ref()
+        .is_some_and(|parent| parent.tree_id() == tree.id())
+    {
+        info!("[commit_diary_file()]: Nothing to commit in the vault.");
+        return Ok(None);
+    }
+
+    let signature = vault_repo
+        .signature()
+        .or_else(|_| Signature::now(FALLBACK_SIGNATURE.0, FALLBACK_SIGNATURE.1))?;
+    let parents = parent.iter().collect::<Vec<_>>();
+    let oid = vault_repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parents,
+    )?;
+
+    info!("[commit_diary_file()]: Created vault commit: {oid:}");
+    Ok(Some(oid))
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod vault_git_tests {
+    use super::*;
+    use crate::commit_entry::CommitEntry;
+    use chrono::TimeZone;
+    use chrono::Utc;
+    use tempfile::tempdir;
+
+    fn commit_saver() -> CommitSaver {
+        CommitSaver::from(CommitEntry {
+            repository_url: "https://github.com/user/my-repo.git".to_string(),
+            branch: "main".to_string(),
+            hash: "abc123def456".to_string(),
+            message: "work".to_string(),
+            datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn test_render_vault_commit_message() {
+        let message =
+            render_vault_commit_message("{date}: {repo} ({branch}) {hash}", &commit_saver());
+
+        assert_eq!(
+            message,
+            "2025-01-14: my-repo (main) abc123d\n\nRusty-Commit-Saver: vault-sync\n"
+        );
+        assert!(is_vault_sync_message(&message));
+        assert!(!is_vault_sync_message("Regular commit"));
+    }
+
+    #[test]
+    fn test_commit_diary_file_commits_only_the_diary() {
+        let temp_dir = tempdir().unwrap();
+        let vault_repo = Repository::init(temp_dir.path()).unwrap();
+        let diary_dir = temp_dir.path().join("Commits");
+        fs::create_dir_all(&diary_dir).unwrap();
+        let diary = diary_dir.join("2025-01-14.md");
+        fs::write(&diary, "| row |\n").unwrap();
+        fs::write(temp_dir.path().join("other.md"), "untouched").unwrap();
+
+        let message = render_vault_commit_message(DEFAULT_VAULT_COMMIT_MESSAGE, &commit_saver());
+        let oid = commit_diary_file(&diary, &message).unwrap().unwrap();
+
+        let commit = vault_repo.find_commit(oid).unwrap();
+        assert!(is_vault_sync_message(commit.message().unwrap()));
+        let tree = commit.tree().unwrap();
+        assert!(tree.get_path(Path::new("Commits/2025-01-14.md")).is_ok());
+        assert!(tree.get_path(Path::new("other.md")).is_err());
+
+        assert_eq!(commit_diary_file(&diary, &message).unwrap(), None);
+
+        fs::write(&diary, "| row |\n| second row |\n").unwrap();
+        let second = commit_diary_file(&diary, &message).unwrap().unwrap();
+        assert_eq!(
+            vault_repo
+                .find_commit(second)
+                .unwrap()
+                .parent_id(0)
+                .unwrap(),
+            oid
+        );
+    }
+}
@@ -0,0 +1,586 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::DateTime;
+use chrono::Datelike;
+use chrono::NaiveDate;
+use chrono::Utc;
+use chrono::Weekday;
+use log::info;
+
+use crate::atomic_write::write_atomically;
+use crate::diary_parser::link_text;
+use crate::diary_parser::parse_diary_table;
+use crate::diary_parser::read_diary_snapshot;
+use crate::paths::repository_name_from_url;
+use crate::porcelain::porcelain_line;
+use crate::same_day::diary_files_by_date;
+use crate::vim_commit::TableColumn;
+
+/// Name under which commits without a repository URL are counted.
+pub const UNKNOWN_REPOSITORY: &str = "(unknown)";
+
+/// Days of the week, in the order `stats` lists them.
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// A logged commit read back from a diary file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiaryCommit {
+    /// The `date` of the diary file.
+    pub date: NaiveDate,
+
+    /// Repository name, see [`repository_name_from_url()`];
+    /// [`UNKNOWN_REPOSITORY`] when the entry has no repository URL.
+    pub repository: String,
+
+    /// First line of the commit message.
+    pub subject: String,
+}
+
+impl DiaryCommit {
+    /// Creates the commit of an entry with `repository_url` and `message`
+    /// (the subject is its first line, `<br/>` being a line break in tables).
+    #[must_use]
+    pub fn new(date: NaiveDate, repository_url: &str, message: &str) -> Self {
+        let repository = repository_name_from_url(repository_url);
+        let subject = message
+            .split("<br/>")
+            .next()
+            .and_then(|first| first.lines().next())
+            .unwrap_or_default()
+            .replace("\\|", "|");
+        DiaryCommit {
+            date,
+            repository: if repository.is_empty() {
+                UNKNOWN_REPOSITORY.to_string()
+            } else {
+                repository
+            },
+            subject: subject.trim().to_string(),
+        }
+    }
+
+    /// Returns the Conventional Commits type of the subject, lowercased, e.g.
+    /// `feat` for `feat(api)!: add search`.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the subject has no `type: ` prefix.
+    #[must_use]
+    pub fn commit_type(&self) -> Option<String> {
+        let (prefix, _) = self.subject.split_once(':')?;
+        let prefix = prefix.strip_suffix('!').unwrap_or(prefix);
+        let commit_type = match prefix.split_once('(') {
+            Some((commit_type, scope)) if scope.ends_with(')') => commit_type,
+            Some(_) => return None,
+            None => prefix,
+        };
+        (!commit_type.is_empty() && commit_type.chars().all(|c| c.is_ascii_alphabetic()))
+            .then(|| commit_type.to_lowercase())
+    }
+}
+
+/// A run of consecutive days with at least one logged commit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Streak {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl Streak {
+    /// Number of days of the streak, both ends included.
+    #[must_use]
+    pub fn days(&self) -> i64 {
+        (self.end - self.start).num_days() + 1
+    }
+
+    /// The longest run of consecutive days among `days`, given in ascending
+    /// order; the earliest one on ties.
+    ///
+    /// # Returns
+    ///
+    /// `None` if there are no days.
+    #[must_use]
+    pub fn longest(days: impl IntoIterator<Item = NaiveDate>) -> Option<Streak> {
+        let mut longest: Option<Streak> = None;
+        let mut current: Option<Streak> = None;
+        for day in days {
+            let streak = match current {
+                Some(streak) if streak.end.succ_opt() == Some(day) => Streak { end: day, ..streak },
+                _ => Streak {
+                    start: day,
+                    end: day,
+                },
+            };
+            if longest.is_none_or(|longest| streak.days() > longest.days()) {
+                longest = Some(streak);
+            }
+            current = Some(streak);
+        }
+        longest
+    }
+}
+
+/// Commit totals of the whole commit diary, as shown by `stats`.
+///
+/// Only entries with a commit hash count: WIP rows and workday events are
+/// left out. Commits are dated by the `date` of their diary file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VaultStats {
+    /// Logged commits per month, keyed `YYYY-MM`.
+    pub per_month: BTreeMap<String, usize>,
+
+    /// Logged commits per repository name, see [`repository_name_from_url()`].
+    pub per_repository: BTreeMap<String, usize>,
+
+    /// Logged commits per day of the week, Monday first.
+    pub per_weekday: [usize; 7],
+
+    /// Every day with at least one logged commit.
+    pub active_days: BTreeSet<NaiveDate>,
+}
+
+impl VaultStats {
+    /// Counts one logged commit.
+    pub fn add_commit(&mut self, commit: &DiaryCommit) {
+        *self
+            .per_month
+            .entry(commit.date.format("%Y-%m").to_string())
+            .or_default() += 1;
+        *self
+            .per_repository
+            .entry(commit.repository.clone())
+            .or_default() += 1;
+        self.per_weekday[commit.date.weekday().num_days_from_monday() as usize] += 1;
+        self.active_days.insert(commit.date);
+    }
+
+    /// Total number of logged commits.
+    #[must_use]
+    pub fn commits(&self) -> usize {
+        self.per_month.values().sum()
+    }
+
+    /// The longest run of consecutive active days; the earliest one on ties.
+    ///
+    /// # Returns
+    ///
+    /// `None` if no commit was logged yet.
+    #[must_use]
+    pub fn longest_streak(&self) -> Option<Streak> {
+        Streak::longest(self.active_days.iter().copied())
+    }
+}
+
+/// Reads every logged commit of the diary files under the given commits roots.
+///
+/// Diary files are found like in [`diary_files_by_date()`], and their entries
+/// are read from the commit table (with [`parse_diary_table()`]) or, for
+/// `entry_format = yaml`, from the fenced YAML blocks. Files whose `date` is
+/// not `YYYY-MM-DD` are skipped.
+///
+/// # Errors
+///
+/// Returns an error if a commits root or a diary file cannot be read.
+pub fn collect_diary_commits(
+    commits_roots: &[PathBuf],
+) -> Result<Vec<DiaryCommit>, Box<dyn Error>> {
+    let mut commits = Vec::new();
+    for commits_root in commits_roots {
+        for (date, paths) in diary_files_by_date(commits_root)? {
+            let Ok(date) = NaiveDate::parse_from_str(&date, "%Y-%m-%d") else {
+                info!("[collect_diary_commits()]: Skipping the diary files of the date: {date:}");
+                continue;
+            };
+            for path in paths {
+                for entry in commit_entries(&read_diary_snapshot(&path)?) {
+                    commits.push(DiaryCommit::new(
+                        date,
+                        &entry.repository_url,
+                        &entry.message,
+                    ));
+                }
+            }
+        }
+    }
+    Ok(commits)
+}
+
+/// Collects the [`VaultStats`] of the diary files under the given commits
+/// roots, see [`collect_diary_commits()`].
+///
+/// # Errors
+///
+/// Returns an error if a commits root or a diary file cannot be read.
+pub fn collect_vault_stats(commits_roots: &[PathBuf]) -> Result<VaultStats, Box<dyn Error>> {
+    let mut stats = VaultStats::default();
+    for commit in collect_diary_commits(commits_roots)? {
+        stats.add_commit(&commit);
+    }
+
+    info!(
+        "[collect_vault_stats()]: Counted {:} commit(s) over {:} day(s).",
+        stats.commits(),
+        stats.active_days.len()
+    );
+    Ok(stats)
+}
+
+/// The fields of a diary entry the stats are computed from.
+#[derive(Debug, Default)]
+struct EntryFields {
+    commit_hash: String,
+    repository_url: String,
+    message: String,
+}
+
+/// Returns the fields of every commit entry of a diary file.
+///
+/// Table rows without a `COMMIT HASH` cell, and YAML blocks without a
+/// `commit_hash`, are not commits. Tables without a `COMMIT HASH` column
+/// count every row.
+fn commit_entries(content: &str) -> Vec<EntryFields> {
+    if let Some(table) = parse_diary_table(content) {
+        let has_hash_column = table.column_index(TableColumn::CommitHash).is_some();
+        let cell = |row, column| table.cell(row, column).unwrap_or_default().to_string();
+        return table
+            .rows
+            .iter()
+            .map(|row| EntryFields {
+                commit_hash: cell(row, TableColumn::CommitHash),
+                repository_url: link_text(&cell(row, TableColumn::RepositoryUrl)).to_string(),
+                message: cell(row, TableColumn::CommitMessage),
+            })
+            .filter(|entry| !has_hash_column || !entry.commit_hash.is_empty())
+            .collect();
+    }
+
+    let mut entries = Vec::new();
+    let mut block: Option<EntryFields> = None;
+    for line in content.lines() {
+        match (line.trim_end(), block.as_mut()) {
+            ("```yaml", _) => block = Some(EntryFields::default()),
+            ("```", Some(_)) => {
+                if let Some(entry) = block.take() {
+                    if !entry.commit_hash.is_empty() {
+                        entries.push(entry);
+                    }
+                }
+            }
+            (line, Some(entry)) => {
+                if let Some(value) = yaml_value(line, TableColumn::CommitHash) {
+                    entry.commit_hash = value;
+                } else if let Some(value) = yaml_value(line, TableColumn::RepositoryUrl) {
+                    entry.repository_url = value;
+                } else if let Some(value) = yaml_value(line, TableColumn::CommitMessage) {
+                    entry.message = value;
+                }
+            }
+            _ => {}
+        }
+    }
+    entries
+}
+
+/// Returns the unquoted value of a `key: "value"` line of a YAML block, if
+/// the line holds the key of `column`.
+fn yaml_value(line: &str, column: TableColumn) -> Option<String> {
+    let value = line.strip_prefix(column.key())?.strip_prefix(':')?.trim();
+    Some(serde_json::from_str::<String>(value).unwrap_or_else(|_| value.to_string()))
+}
+
+/// Renders the `stats` output as Markdown, so it reads the same in the
+/// terminal and in the stats note.
+///
+/// Repositories are listed by commit count, most active first.
+///
+/// # Output Format
+///
+/// ```text
+/// # Commit Stats
+///
+/// 1234 commits over 310 days. Longest streak: 21 days (2025-02-03 to 2025-02-23).
+///
+/// | MONTH | COMMITS |
+/// |-------|---------|
+/// | 2025-01 | 140 |
+///
+/// | REPOSITORY | COMMITS |
+/// |------------|---------|
+/// | api | 610 |
+///
+/// | WEEKDAY | COMMITS |
+/// |---------|---------|
+/// | Mon | 260 |
+/// ```
+#[must_use]
+pub fn render_vault_stats(stats: &VaultStats) -> String {
+    let mut output = String::from("# Commit Stats\n\n");
+    let Some(streak) = stats.longest_streak() else {
+        output.push_str("No commits in the diary yet.\n");
+        return output;
+    };
+
+    let _ = writeln!(
+        output,
+        "{:} commits over {:} days. Longest streak: {:} days ({:} to {:}).",
+        stats.commits(),
+        stats.active_days.len(),
+        streak.days(),
+        streak.start,
+        streak.end
+    );
+
+    output.push_str("\n| MONTH | COMMITS |\n|-------|---------|\n");
+    for (month, commits) in &stats.per_month {
+        let _ = writeln!(output, "| {month:} | {commits:} |");
+    }
+
+    output.push_str("\n| REPOSITORY | COMMITS |\n|------------|---------|\n");
+    for (repository, commits) in repositories_by_commits(stats) {
+        let _ = writeln!(output, "| {repository:} | {commits:} |");
+    }
+
+    output.push_str("\n| WEEKDAY | COMMITS |\n|---------|---------|\n");
+    for (weekday, commits) in WEEKDAYS.iter().zip(stats.per_weekday) {
+        let _ = writeln!(output, "| {weekday:} | {commits:} |");
+    }
+    output
+}
+
+/// Renders the `stats` output as porcelain records (`stats --porcelain`),
+/// in the order of [`render_vault_stats()`].
+///
+/// # Format
+///
+/// Tab-separated fields, escaped with [`escape_field()`](crate::porcelain::escape_field):
+///
+/// ```text
+/// total <commits> <active days> <longest streak days> <streak start> <streak end>
+/// month <YYYY-MM> <commits>
+/// repository <name> <commits>
+/// weekday <Mon..Sun> <commits>
+/// ```
+///
+/// The streak fields are empty when no commit was logged yet.
+#[must_use]
+pub fn render_vault_stats_porcelain(stats: &VaultStats) -> String {
+    let streak = stats.longest_streak();
+    let mut output = porcelain_line(&[
+        "total",
+        &stats.commits().to_string(),
+        &stats.active_days.len().to_string(),
+        &streak
+            .map(|streak| streak.days().to_string())
+            .unwrap_or_default(),
+        &streak
+            .map(|streak| streak.start.to_string())
+            .unwrap_or_default(),
+        &streak
+            .map(|streak| streak.end.to_string())
+            .unwrap_or_default(),
+    ]);
+    for (month, commits) in &stats.per_month {
+        output.push_str(&porcelain_line(&["month", month, &commits.to_string()]));
+    }
+    for (repository, commits) in repositories_by_commits(stats) {
+        output.push_str(&porcelain_line(&[
+            "repository",
+            repository,
+            &commits.to_string(),
+        ]));
+    }
+    for (weekday, commits) in WEEKDAYS.iter().zip(stats.per_weekday) {
+        output.push_str(&porcelain_line(&[
+            "weekday",
+            &weekday.to_string(),
+            &commits.to_string(),
+        ]));
+    }
+    output
+}
+
+/// Returns the repositories of `stats` by commit count, most active first.
+fn repositories_by_commits(stats: &VaultStats) -> Vec<(&String, &usize)> {
+    let mut repositories = stats.per_repository.iter().collect::<Vec<_>>();
+    repositories.sort_by(|(a_name, a), (b_name, b)| b.cmp(a).then_with(|| a_name.cmp(b_name)));
+    repositories
+}
+
+/// Writes rendered stats (see [`render_vault_stats()`]) to a note, with an
+/// `updated` frontmatter property. Its directories are created when missing.
+///
+/// # Errors
+///
+/// Returns an error if the note cannot be written.
+pub fn write_stats_note(
+    note_path: &Path,
+    rendered: &str,
+    now: DateTime<Utc>,
+) -> Result<(), Box<dyn Error>> {
+    if let Some(parent) = note_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    info!(
+        "[write_stats_note()]: Writing the stats to: {:}",
+        note_path.display()
+    );
+    write_atomically(
+        note_path,
+        format!("---\nupdated: {:}\n---\n{:}", now.to_rfc3339(), rendered),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod vault_stats_tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::tempdir;
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, day).unwrap()
+    }
+
+    #[test]
+    fn test_longest_streak() {
+        let mut stats = VaultStats::default();
+        assert_eq!(stats.longest_streak(), None);
+
+        for d in [3, 4, 6, 7, 8, 12, 13, 14] {
+            stats.add_commit(&DiaryCommit::new(
+                day(d),
+                "git@github.com:user/api.git",
+                "fix: bug",
+            ));
+        }
+
+        let streak = stats.longest_streak().unwrap();
+        assert_eq!(
+            streak,
+            Streak {
+                start: day(6),
+                end: day(8),
+            }
+        );
+        assert_eq!(streak.days(), 3);
+    }
+
+    #[test]
+    fn test_diary_commit_subject_and_type() {
+        let commit = |message| DiaryCommit::new(day(14), "", message);
+
+        assert_eq!(
+            commit("feat(api)!: add a \\| b<br/>Details").subject,
+            "feat(api)!: add a | b"
+        );
+        assert_eq!(
+            commit("feat(api)!: add<br/>Details").commit_type(),
+            Some("feat".to_string())
+        );
+        assert_eq!(
+            commit("Fix: bug\nbody").commit_type(),
+            Some("fix".to_string())
+        );
+        assert_eq!(commit("Merge branch 'main'").commit_type(), None);
+        assert_eq!(commit("see http://example.com").commit_type(), None);
+        assert_eq!(commit("feat(api: broken").commit_type(), None);
+        assert_eq!(commit("").repository, UNKNOWN_REPOSITORY);
+    }
+
+    #[test]
+    fn test_collect_vault_stats_of_tables_and_yaml_blocks() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().join("Commits");
+        fs::create_dir_all(root.join("2025")).unwrap();
+        fs::write(
+            root.join("2025/2025-01-13.md"),
+            "---\ndate: 2025-01-13\n---\n\
+             | TIME | REPOSITORY URL | COMMIT HASH |\n|---|---|---|\n\
+             | 10:00:00 | git@github.com:user/api.git | abc123 |\n\
+             | 11:00:00 | https://github.com/user/web | [def456](https://github.com/user/web/commit/def456) |\n\
+             | 12:00:00 | git@github.com:user/api.git |  |\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("2025/2025-02-01.md"),
+            "---\ndate: 2025-02-01\n---\n\
+             ```yaml\ncommit_hash: \"abc789\"\nrepository_url: \"git@github.com:user/api.git\"\n```\n\n\
+             ```yaml\ncommit_hash: \"\"\nrepository_url: \"git@github.com:user/api.git\"\n```\n",
+        )
+        .unwrap();
+        fs::write(root.join("2025/notes.md"), "---\ndate: someday\n---\n").unwrap();
+
+        let stats = collect_vault_stats(&[root]).unwrap();
+
+        assert_eq!(stats.commits(), 3);
+        assert_eq!(stats.per_month["2025-01"], 2);
+        assert_eq!(stats.per_month["2025-02"], 1);
+        assert_eq!(stats.per_repository["api"], 2);
+        assert_eq!(stats.per_repository["web"], 1);
+        assert_eq!(stats.per_weekday, [2, 0, 0, 0, 0, 1, 0]);
+        assert_eq!(stats.active_days.len(), 2);
+    }
+
+    #[test]
+    fn test_render_vault_stats_and_write_note() {
+        let mut stats = VaultStats::default();
+        for (d, repository_url) in [
+            (13, "git@github.com:user/web.git"),
+            (14, "git@github.com:user/api.git"),
+            (14, "git@github.com:user/api.git"),
+            (14, ""),
+        ] {
+            stats.add_commit(&DiaryCommit::new(day(d), repository_url, "feat: search"));
+        }
+
+        let output = render_vault_stats(&stats);
+        assert!(output.starts_with(
+            "# Commit Stats\n\n4 commits over 2 days. Longest streak: 2 days (2025-01-13 to 2025-01-14).\n"
+        ));
+        assert!(output.contains("| 2025-01 | 4 |\n"));
+        assert!(output.contains("| api | 2 |\n| (unknown) | 1 |\n| web | 1 |\n"));
+        assert!(output.contains("| Mon | 1 |\n| Tue | 3 |\n| Wed | 0 |\n"));
+        assert_eq!(
+            render_vault_stats(&VaultStats::default()),
+            "# Commit Stats\n\nNo commits in the diary yet.\n"
+        );
+
+        let porcelain = render_vault_stats_porcelain(&stats);
+        assert!(porcelain.starts_with(
+            "total\t4\t2\t2\t2025-01-13\t2025-01-14\nmonth\t2025-01\t4\nrepository\tapi\t2\nrepository\t(unknown)\t1\nrepository\tweb\t1\nweekday\tMon\t1\n"
+        ));
+        assert_eq!(porcelain.lines().count(), 12);
+        assert_eq!(
+            render_vault_stats_porcelain(&VaultStats::default())
+                .lines()
+                .next(),
+            Some("total\t0\t0\t\t\t")
+        );
+
+        let temp_dir = tempdir().unwrap();
+        let note_path = temp_dir.path().join("Rollups/Commit Stats.md");
+        let now = Utc.with_ymd_and_hms(2025, 1, 16, 18, 0, 0).unwrap();
+        write_stats_note(&note_path, &output, now).unwrap();
+        let note = fs::read_to_string(&note_path).unwrap();
+        assert_eq!(
+            note,
+            format!("---\nupdated: 2025-01-16T18:00:00+00:00\n---\n{output:}")
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use std::fmt;
+use std::path::Path;
+use std::path::PathBuf;
+
+use log::info;
+use regex::Regex;
+
+use crate::category::wildcard_match;
+
+/// A regular expression matched against remote URLs (`<vault>.remote`).
+#[derive(Debug, Clone)]
+pub struct RemotePattern(Regex);
+
+impl RemotePattern {
+    /// Compiles the pattern.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `pattern` is not a valid regular expression.
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Regex::new(pattern).map(RemotePattern)
+    }
+
+    /// Checks a remote URL against the pattern (unanchored, like `grep`).
+    #[must_use]
+    pub fn is_match(&self, repository_url: &str) -> bool {
+        self.0.is_match(repository_url)
+    }
+}
+
+impl PartialEq for RemotePattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Eq for RemotePattern {}
+
+impl fmt::Display for RemotePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0.as_str())
+    }
+}
+
+/// A vault commits are written to when its rules match, instead of the
+/// `[obsidian]` one.
+///
+/// # Configuration
+///
+/// Each `<name>.<key>` key of the `[vaults]` section sets one key of the
+/// vault `<name>`:
+///
+/// ```text
+/// [vaults]
+/// work.root = ~/Vaults/Work
+/// work.remote = github\.com[:/]acme/
+/// work.commit_path = Engineering/Commits
+/// oss.root = ~/Vaults/Personal
+/// oss.path = ~/src/oss
+/// oss.branch = release/*
+/// ```
+///
+/// # Fields
+///
+/// - `name` - The name of the vault in the configuration
+/// - `root_path_dir` - The vault root (`<name>.root`)
+/// - `commit_path` - Where diaries go in the vault (`<name>.commit_path`);
+///   `None` keeps `[obsidian] commit_path`
+/// - `remote` - Regular expression the remote URL must match (`<name>.remote`)
+/// - `path_prefix` - Directory the repository must be in (`<name>.path`)
+/// - `branch` - Branch name, `*` wildcards allowed (`<name>.branch`)
+///
+/// A vault matches a commit when every rule it sets matches, and it sets at
+/// least one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultTarget {
+    pub name: String,
+    pub root_path_dir: PathBuf,
+    pub commit_path: Option<PathBuf>,
+    pub remote: Option<RemotePattern>,
+    pub path_prefix: Option<PathBuf>,
+    pub branch: Option<String>,
+}
+
+impl VaultTarget {
+    /// Creates a vault without routing rules, to be set on the returned value.
+    #[must_use]
+    pub fn new(name: &str, root_path_dir: PathBuf) -> Self {
+        VaultTarget {
+            name: name.to_string(),
+            root_path_dir,
+            commit_path: None,
+            remote: None,
+            path_prefix: None,
+            branch: None,
+        }
+    }
+
+    /// Whether the vault sets at least one routing rule.
+    #[must_use]
+    pub fn has_rules(&self) -> bool {
+        self.remote.is_some() || self.path_prefix.is_some() || self.branch.is_some()
+    }
+
+    /// Checks a commit against the routing rules of the vault.
+    ///
+    /// # Arguments
+    ///
+    /// * `repository_url` - The remote URL of the repository
+    /// * `workdir` - Its working directory, `None` if unknown (bare
+    ///   repositories), which a `path` rule never matches
+    /// * `branch` - The branch the commit was made on
+    #[must_use]
+    pub fn matches(&self, repository_url: &str, workdir: Option<&Path>, branch: &str) -> bool {
+        let remote_matches = self
+            .remote
+            .as_ref()
+            .is_none_or(|remote| remote.is_match(repository_url));
+        let path_matches = self
+            .path_prefix
+            .as_ref()
+            .is_none_or(|prefix| workdir.is_some_and(|workdir| workdir.starts_with(prefix)));
+        let branch_matches = self
+            .branch
+            .as_ref()
+            .is_none_or(|pattern| wildcard_match(pattern, branch));
+        self.has_rules() && remote_matches && path_matches && branch_matches
+    }
+
+    /// The directory diaries go in inside the vault.
+    #[must_use]
+    pub fn commit_path<'a>(&'a self, obsidian_commit_path: &'a Path) -> &'a Path {
+        self.commit_path.as_deref().unwrap_or(obsidian_commit_path)
+    }
+}
+
+/// Picks the vaults a commit is written to.
+///
+/// # Returns
+///
+/// Every vault of `vaults` whose rules match, in configuration order; empty
+/// to write to the `[obsidian]` vault only.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::vaults::matching_vaults;
+///
+/// for vault in matching_vaults(&save_settings.vaults, &url, Some(workdir), "main") {
+///     println!("Writing to {}", vault.root_path_dir.display());
+/// }
+/// ```
+#[must_use]
+pub fn matching_vaults<'a>(
+    vaults: &'a [VaultTarget],
+    repository_url: &str,
+    workdir: Option<&Path>,
+    branch: &str,
+) -> Vec<&'a VaultTarget> {
+    let matching = vaults
+        .iter()
+        .filter(|vault| vault.matches(repository_url, workdir, branch))
+        .collect::<Vec<_>>();
+    info!(
+        "[matching_vaults()]: Vaults for {repository_url:} ({branch:}): {:?}",
+        matching
+            .iter()
+            .map(|vault| vault.name.as_str())
+            .collect::<Vec<_>>()
+    );
+    matching
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod vaults_tests {
+    use super::*;
+
+    fn work_vault() -> VaultTarget {
+        VaultTarget {
+            remote: Some(RemotePattern::new(r"github\.com[:/]acme/").unwrap()),
+            ..VaultTarget::new("work", PathBuf::from("/vaults/work"))
+        }
+    }
+
+    #[test]
+    fn test_vault_target_matches_every_rule_it_sets() {
+        let work = work_vault();
+        assert!(work.matches("git@github.com:acme/api.git", None, "main"));
+        assert!(!work.matches("https://github.com/me/tool.git", None, "main"));
+
+        let release = VaultTarget {
+            path_prefix: Some(PathBuf::from("/home/me/src/oss")),
+            branch: Some("release/*".to_string()),
+            ..VaultTarget::new("oss", PathBuf::from("/vaults/oss"))
+        };
+        let workdir = Path::new("/home/me/src/oss/tool");
+        assert!(release.matches("https://x", Some(workdir), "release/1.2"));
+        assert!(!release.matches("https://x", Some(workdir), "main"));
+        assert!(!release.matches(
+            "https://x",
+            Some(Path::new("/home/me/src/ossify")),
+            "release/1.2"
+        ));
+        assert!(!release.matches("https://x", None, "release/1.2"));
+
+        let no_rules = VaultTarget::new("all", PathBuf::from("/vaults/all"));
+        assert!(!no_rules.matches("https://x", Some(workdir), "main"));
+    }
+
+    #[test]
+    fn test_matching_vaults_keeps_every_match_in_order() {
+        let everything_on_main = VaultTarget {
+            branch: Some("main".to_string()),
+            commit_path: Some(PathBuf::from("Log")),
+            ..VaultTarget::new("journal", PathBuf::from("/vaults/journal"))
+        };
+        let vaults = vec![work_vault(), everything_on_main];
+
+        let matching = matching_vaults(&vaults, "git@github.com:acme/api.git", None, "main");
+        assert_eq!(
+            matching
+                .iter()
+                .map(|vault| vault.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["work", "journal"]
+        );
+        assert_eq!(
+            matching[1].commit_path(Path::new("Commits")),
+            Path::new("Log")
+        );
+        assert_eq!(
+            matching[0].commit_path(Path::new("Commits")),
+            Path::new("Commits")
+        );
+        assert!(matching_vaults(&vaults, "https://gitlab.com/me/x.git", None, "dev").is_empty());
+    }
+}
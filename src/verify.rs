@@ -0,0 +1,569 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use git2::Oid;
+use git2::Repository;
+use log::info;
+use log::warn;
+
+use crate::atomic_write::write_atomically;
+use crate::diary_parser::parse_diary_table;
+use crate::diary_parser::DiaryRow;
+use crate::diary_parser::DiaryTable;
+use crate::hydration::ensure_hydrated;
+use crate::hydration::HYDRATION_ATTEMPTS;
+use crate::hydration::HYDRATION_RETRY_DELAY;
+use crate::same_day::diary_files_by_date;
+use crate::state::StateIndex;
+use crate::trash::keep_copy_in_trash;
+use crate::vim_commit::origin_url;
+use crate::vim_commit::EntryKind;
+use crate::vim_commit::TableColumn;
+
+/// Message prefix of WIP rows, used to tell them apart in tables without an `EVENT` column.
+const WIP_MESSAGE_PREFIX: &str = "🚧 WIP";
+
+/// An inconsistency between the state index and the diary files in the vault.
+///
+/// # Variants
+///
+/// - `MissingDiaryFile` - The index points to a diary file that no longer exists
+/// - `MissingRow` - The diary file exists but has no row for the indexed commit
+/// - `MissingCommit` - A diary row references a commit that is gone from the
+///   current repository (e.g. after a rebase or force-push)
+/// - `DuplicateHash` - The same commit is logged more than once; `first_path`
+///   is where it was seen first
+/// - `DuplicateDay` - Two diary files under the same commits root have the
+///   same frontmatter `date` (e.g. after `commit_date_path` changed);
+///   `first_path` is the first one in path order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyIssue {
+    MissingDiaryFile {
+        diary_path: String,
+        commit_hash: String,
+    },
+    MissingRow {
+        diary_path: String,
+        commit_hash: String,
+    },
+    MissingCommit {
+        diary_path: String,
+        line_index: usize,
+        commit_hash: String,
+    },
+    DuplicateHash {
+        diary_path: String,
+        line_index: usize,
+        commit_hash: String,
+        first_path: String,
+    },
+    DuplicateDay {
+        diary_path: String,
+        date: String,
+        first_path: String,
+    },
+}
+
+impl VerifyIssue {
+    /// Returns `true` if [`fix_issues()`] can repair this issue.
+    ///
+    /// Rows of commits that vanished from the repository are only reported:
+    /// they still document work that was done, so deleting them is left to
+    /// the user. So are diary files sharing a day, which have to be merged by
+    /// hand.
+    #[must_use]
+    pub fn is_fixable(&self) -> bool {
+        !matches!(
+            self,
+            VerifyIssue::MissingCommit { .. } | VerifyIssue::DuplicateDay { .. }
+        )
+    }
+}
+
+impl fmt::Display for VerifyIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyIssue::MissingDiaryFile {
+                diary_path,
+                commit_hash,
+            } => write!(
+                f,
+                "missing diary file: {diary_path:} (indexed commit {commit_hash:})"
+            ),
+            VerifyIssue::MissingRow {
+                diary_path,
+                commit_hash,
+            } => write!(f, "missing row: {commit_hash:} is indexed but not in {diary_path:}"),
+            VerifyIssue::MissingCommit {
+                diary_path,
+                line_index,
+                commit_hash,
+            } => write!(
+                f,
+                "unknown commit: {diary_path:}:{:} references {commit_hash:}, which is not in the repository",
+                line_index + 1
+            ),
+            VerifyIssue::DuplicateHash {
+                diary_path,
+                line_index,
+                commit_hash,
+                first_path,
+            } => write!(
+                f,
+                "duplicate hash: {diary_path:}:{:} repeats {commit_hash:} (first logged in {first_path:})",
+                line_index + 1
+            ),
+            VerifyIssue::DuplicateDay {
+                diary_path,
+                date,
+                first_path,
+            } => write!(
+                f,
+                "duplicate day: {diary_path:} is another diary file for {date:} (see {first_path:})"
+            ),
+        }
+    }
+}
+
+/// Cross-checks the state index against the diary files it points to.
+///
+/// Every diary file referenced by the index is parsed (see
+/// [`parse_diary_table()`]) and checked for:
+///
+/// 1. Index entries whose diary file is missing, or has no row for the commit
+/// 2. Commit rows whose hash no longer exists in `git_repo` (only rows of
+///    the repository's own `origin` URL are checked, and only if a repository
+///    is given)
+/// 3. Commit hashes logged more than once, across all files
+///
+/// WIP and event rows are skipped: they repeat the hash of the commit they
+/// were recorded on by design.
+///
+/// # Errors
+///
+/// Returns an error if the index or an existing diary file cannot be read.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::state::{default_state_dir, StateIndex};
+/// use rusty_commit_saver::verify::verify_vault;
+///
+/// let index = StateIndex::new(&default_state_dir().unwrap());
+/// for issue in verify_vault(&index, None)? {
+///     println!("{issue}");
+/// }
+/// ```
+pub fn verify_vault(
+    index: &StateIndex,
+    git_repo: Option<&Repository>,
+) -> Result<Vec<VerifyIssue>, Box<dyn Error>> {
+    let entries = index.entries()?;
+    let repository_url = git_repo.map(origin_url);
+    let mut issues = Vec::new();
+
+    let mut diary_paths = Vec::new();
+    let mut seen_paths = HashSet::new();
+    for entry in &entries {
+        if seen_paths.insert(entry.diary_path.as_str()) {
+            diary_paths.push(entry.diary_path.as_str());
+        }
+    }
+
+    let mut hashes_by_path: HashMap<&str, HashSet<String>> = HashMap::new();
+    let mut first_seen: HashMap<String, String> = HashMap::new();
+    for diary_path in diary_paths {
+        if !Path::new(diary_path).is_file() {
+            continue;
+        }
+
+        info!("[verify_vault()]: Checking diary file: {diary_path:}");
+        let content = fs::read_to_string(diary_path)?;
+        let Some(table) = parse_diary_table(&content) else {
+            warn!("[verify_vault()]: No commit table in: {diary_path:}");
+            hashes_by_path.insert(diary_path, HashSet::new());
+            continue;
+        };
+
+        let mut hashes = HashSet::new();
+        for row in table.rows.iter().filter(|row| is_commit_row(&table, row)) {
+            let Some(commit_hash) = table
+                .cell(row, TableColumn::CommitHash)
+                .filter(|hash| !hash.is_empty())
+            else {
+                continue;
+            };
+            hashes.insert(commit_hash.to_string());
+
+            if let Some(first_path) = first_seen.get(commit_hash) {
+                issues.push(VerifyIssue::DuplicateHash {
+                    diary_path: diary_path.to_string(),
+                    line_index: row.line_index,
+                    commit_hash: commit_hash.to_string(),
+                    first_path: first_path.clone(),
+                });
+                continue;
+            }
+            first_seen.insert(commit_hash.to_string(), diary_path.to_string());
+
+            if let (Some(git_repo), Some(repository_url)) = (git_repo, &repository_url) {
+                let same_repository =
+                    table.cell(row, TableColumn::RepositoryUrl) == Some(repository_url.as_str());
+                if same_repository && !commit_exists(git_repo, commit_hash) {
+                    issues.push(VerifyIssue::MissingCommit {
+                        diary_path: diary_path.to_string(),
+                        line_index: row.line_index,
+                        commit_hash: commit_hash.to_string(),
+                    });
+                }
+            }
+        }
+        hashes_by_path.insert(diary_path, hashes);
+    }
+
+    for entry in &entries {
+        match hashes_by_path.get(entry.diary_path.as_str()) {
+            None => issues.push(VerifyIssue::MissingDiaryFile {
+                diary_path: entry.diary_path.clone(),
+                commit_hash: entry.commit_hash.clone(),
+            }),
+            Some(hashes) if !hashes.contains(&entry.commit_hash) => {
+                issues.push(VerifyIssue::MissingRow {
+                    diary_path: entry.diary_path.clone(),
+                    commit_hash: entry.commit_hash.clone(),
+                });
+            }
+            Some(_) => (),
+        }
+    }
+
+    info!("[verify_vault()]: Found {:} issue(s).", issues.len());
+    Ok(issues)
+}
+
+/// Finds the days that have more than one diary file under a commits root.
+///
+/// Each commits root is checked on its own, since `[routing]` deliberately
+/// gives a day one diary file per routed commit path. See
+/// [`diary_files_by_date()`] for which files are considered.
+///
+/// # Errors
+///
+/// Returns an error if a commits root cannot be read.
+pub fn find_duplicate_days(commits_roots: &[PathBuf]) -> Result<Vec<VerifyIssue>, Box<dyn Error>> {
+    let mut issues = Vec::new();
+    for commits_root in commits_roots {
+        info!(
+            "[find_duplicate_days()]: Checking the diary dates in: {:}",
+            commits_root.display()
+        );
+        for (date, paths) in diary_files_by_date(commits_root)? {
+            let Some((first_path, others)) = paths.split_first() else {
+                continue;
+            };
+            issues.extend(others.iter().map(|path| VerifyIssue::DuplicateDay {
+                diary_path: path.display().to_string(),
+                date: date.clone(),
+                first_path: first_path.display().to_string(),
+            }));
+        }
+    }
+    Ok(issues)
+}
+
+/// Repairs the fixable issues found by [`verify_vault()`].
+///
+/// - Index entries of missing diary files or missing rows are pruned
+/// - Duplicate rows are removed, keeping the first occurrence
+/// - Rows of unknown commits are left alone, see [`VerifyIssue::is_fixable()`]
+///
+/// Diary files are hydrated before they are rewritten, see [`ensure_hydrated()`].
+/// When `trash_dir` is set (`[safety] use_trash`), a copy of each diary file is
+/// kept in the vault trash before its rows are removed, see [`keep_copy_in_trash()`].
+///
+/// # Returns
+///
+/// The number of issues that were fixed.
+///
+/// # Errors
+///
+/// Returns an error if the index or a diary file cannot be read or rewritten.
+pub fn fix_issues(
+    index: &StateIndex,
+    issues: &[VerifyIssue],
+    trash_dir: Option<&Path>,
+) -> Result<usize, Box<dyn Error>> {
+    let mut stale_entries = HashSet::new();
+    let mut duplicate_rows: HashMap<&str, HashSet<usize>> = HashMap::new();
+
+    for issue in issues {
+        match issue {
+            VerifyIssue::MissingDiaryFile {
+                diary_path,
+                commit_hash,
+            }
+            | VerifyIssue::MissingRow {
+                diary_path,
+                commit_hash,
+            } => {
+                stale_entries.insert((diary_path.as_str(), commit_hash.as_str()));
+            }
+            VerifyIssue::DuplicateHash {
+                diary_path,
+                line_index,
+                ..
+            } => {
+                duplicate_rows
+                    .entry(diary_path.as_str())
+                    .or_default()
+                    .insert(*line_index);
+            }
+            VerifyIssue::MissingCommit { .. } | VerifyIssue::DuplicateDay { .. } => (),
+        }
+    }
+
+    for (diary_path, line_indexes) in &duplicate_rows {
+        info!(
+            "[fix_issues()]: Removing {:} duplicate row(s) from: {diary_path:}",
+            line_indexes.len()
+        );
+        remove_lines(Path::new(diary_path), line_indexes, trash_dir)?;
+    }
+
+    if !stale_entries.is_empty() {
+        let entries = index.entries()?;
+        let kept = entries
+            .into_iter()
+            .filter(|entry| {
+                !stale_entries.contains(&(entry.diary_path.as_str(), entry.commit_hash.as_str()))
+            })
+            .collect::<Vec<_>>();
+        index.replace_entries(&kept)?;
+    }
+
+    Ok(issues.iter().filter(|issue| issue.is_fixable()).count())
+}
+
+/// Returns `true` if the row records a regular commit.
+///
+/// Uses the `EVENT` column when present; older tables without it only hold
+/// commits and WIP rows, which are recognized by their message prefix.
+pub(crate) fn is_commit_row(table: &DiaryTable, row: &DiaryRow) -> bool {
+    match table.cell(row, TableColumn::Event) {
+        Some(event) => event == EntryKind::Commit.label(),
+        None => !table
+            .cell(row, TableColumn::CommitMessage)
+            .is_some_and(|message| message.starts_with(WIP_MESSAGE_PREFIX)),
+    }
+}
+
+/// Returns `true` if `commit_hash` names a commit of `git_repo`.
+fn commit_exists(git_repo: &Repository, commit_hash: &str) -> bool {
+    Oid::from_str(commit_hash)
+        .and_then(|oid| git_repo.find_commit(oid))
+        .is_ok()
+}
+
+/// Rewrites a diary file without the given (zero-based) lines.
+fn remove_lines(
+    diary_path: &Path,
+    line_indexes: &HashSet<usize>,
+    trash_dir: Option<&Path>,
+) -> Result<(), Box<dyn Error>> {
+    ensure_hydrated(diary_path, HYDRATION_ATTEMPTS, HYDRATION_RETRY_DELAY)?;
+    if let Some(trash_dir) = trash_dir {
+        keep_copy_in_trash(trash_dir, diary_path)?;
+    }
+
+    let content = fs::read_to_string(diary_path)?;
+    let mut kept = content
+        .lines()
+        .enumerate()
+        .filter(|(line_index, _)| !line_indexes.contains(line_index))
+        .map(|(_, line)| line)
+        .collect::<Vec<_>>()
+        .join("\n");
+    if content.ends_with('\n') {
+        kept.push('\n');
+    }
+
+    write_atomically(diary_path, kept)?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod verify_tests {
+    use super::*;
+    use crate::state::LoggedEntry;
+    use crate::vim_commit::render_table_header;
+    use crate::vim_commit::DEFAULT_TABLE_COLUMNS;
+    use chrono::TimeZone;
+    use chrono::Utc;
+    use git2::Signature;
+    use tempfile::tempdir;
+
+    const REPO_URL: &str = "https://github.com/user/repo.git";
+
+    fn row(message: &str, hash: &str) -> String {
+        format!("| /src | 10:00:00 | {message:} | {REPO_URL:} | main | {hash:} |\n")
+    }
+
+    fn write_diary(path: &Path, rows: &[String]) {
+        let mut content = format!(
+            "---\ncategory: diary\n---\n{:}",
+            render_table_header(&DEFAULT_TABLE_COLUMNS)
+        );
+        for row in rows {
+            content.push_str(row);
+        }
+        fs::write(path, content).unwrap();
+    }
+
+    fn index_entry(diary_path: &Path, hash: &str) -> LoggedEntry {
+        LoggedEntry {
+            commit_datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 0, 0).unwrap(),
+            repository_url: REPO_URL.to_string(),
+            branch: "main".to_string(),
+            commit_hash: hash.to_string(),
+            diary_path: diary_path.display().to_string(),
+        }
+    }
+
+    #[test]
+    fn test_verify_vault_consistent_vault_has_no_issues() {
+        let temp_dir = tempdir().unwrap();
+        let diary = temp_dir.path().join("2025-01-14.md");
+        write_diary(
+            &diary,
+            &[
+                row("first", "aaa"),
+                row("🚧 WIP on main: aaa", "aaa"),
+                row("second", "bbb"),
+            ],
+        );
+        let index = StateIndex::new(&temp_dir.path().join("state"));
+        index.append(&index_entry(&diary, "aaa")).unwrap();
+        index.append(&index_entry(&diary, "bbb")).unwrap();
+
+        assert!(verify_vault(&index, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_verify_vault_reports_and_fixes_issues() {
+        let temp_dir = tempdir().unwrap();
+        let monday = temp_dir.path().join("2025-01-13.md");
+        let tuesday = temp_dir.path().join("2025-01-14.md");
+        let deleted = temp_dir.path().join("2025-01-15.md");
+        write_diary(&monday, &[row("first", "aaa")]);
+        write_diary(&tuesday, &[row("second", "bbb"), row("first again", "aaa")]);
+        let index = StateIndex::new(&temp_dir.path().join("state"));
+        index.append(&index_entry(&monday, "aaa")).unwrap();
+        index.append(&index_entry(&tuesday, "bbb")).unwrap();
+        index.append(&index_entry(&tuesday, "ccc")).unwrap();
+        index.append(&index_entry(&deleted, "ddd")).unwrap();
+
+        let issues = verify_vault(&index, None).unwrap();
+
+        assert_eq!(
+            issues,
+            vec![
+                VerifyIssue::DuplicateHash {
+                    diary_path: tuesday.display().to_string(),
+                    line_index: 6,
+                    commit_hash: "aaa".to_string(),
+                    first_path: monday.display().to_string(),
+                },
+                VerifyIssue::MissingRow {
+                    diary_path: tuesday.display().to_string(),
+                    commit_hash: "ccc".to_string(),
+                },
+                VerifyIssue::MissingDiaryFile {
+                    diary_path: deleted.display().to_string(),
+                    commit_hash: "ddd".to_string(),
+                },
+            ]
+        );
+
+        let trash_dir = temp_dir.path().join(".trash");
+        assert_eq!(fix_issues(&index, &issues, Some(&trash_dir)).unwrap(), 3);
+        assert!(verify_vault(&index, None).unwrap().is_empty());
+        assert!(!fs::read_to_string(&tuesday)
+            .unwrap()
+            .contains("first again"));
+        assert!(fs::read_to_string(trash_dir.join("2025-01-14.md"))
+            .unwrap()
+            .contains("first again"));
+        assert_eq!(index.entries().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_find_duplicate_days_checks_each_commits_root() {
+        let temp_dir = tempdir().unwrap();
+        let work = temp_dir.path().join("Work");
+        let personal = temp_dir.path().join("Personal");
+        let day = |path: &Path| {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "---\ndate: 2025-01-14\n---\n").unwrap();
+        };
+        let old_layout = work.join("2025/01-January/2025-01-14.md");
+        let new_layout = work.join("2025/2025-01-14.md");
+        day(&old_layout);
+        day(&new_layout);
+        day(&personal.join("2025-01-14.md"));
+
+        let issues = find_duplicate_days(&[work, personal]).unwrap();
+
+        assert_eq!(
+            issues,
+            vec![VerifyIssue::DuplicateDay {
+                diary_path: new_layout.display().to_string(),
+                date: "2025-01-14".to_string(),
+                first_path: old_layout.display().to_string(),
+            }]
+        );
+        assert!(!issues[0].is_fixable());
+        assert!(issues[0].to_string().starts_with("duplicate day: "));
+    }
+
+    #[test]
+    fn test_verify_vault_reports_commits_missing_from_repo() {
+        let temp_dir = tempdir().unwrap();
+        let git_repo = Repository::init(temp_dir.path().join("repo")).unwrap();
+        git_repo.remote("origin", REPO_URL).unwrap();
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        let tree_id = git_repo.index().unwrap().write_tree().unwrap();
+        let tree = git_repo.find_tree(tree_id).unwrap();
+        let existing = git_repo
+            .commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+            .unwrap()
+            .to_string();
+        let rewritten = "0123456789abcdef0123456789abcdef01234567";
+
+        let diary = temp_dir.path().join("2025-01-14.md");
+        write_diary(&diary, &[row("kept", &existing), row("rebased", rewritten)]);
+        let index = StateIndex::new(&temp_dir.path().join("state"));
+        index.append(&index_entry(&diary, &existing)).unwrap();
+        index.append(&index_entry(&diary, rewritten)).unwrap();
+
+        let issues = verify_vault(&index, Some(&git_repo)).unwrap();
+
+        assert_eq!(
+            issues,
+            vec![VerifyIssue::MissingCommit {
+                diary_path: diary.display().to_string(),
+                line_index: 6,
+                commit_hash: rewritten.to_string(),
+            }]
+        );
+        assert!(!issues[0].is_fixable());
+        assert!(issues[0].to_string().contains("2025-01-14.md:7"));
+        assert_eq!(fix_issues(&index, &issues, None).unwrap(), 0);
+        assert!(fs::read_to_string(&diary).unwrap().contains("rebased"));
+    }
+}
@@ -1,25 +1,132 @@
 use chrono::DateTime;
+use chrono::FixedOffset;
+use chrono::NaiveDate;
+use chrono::NaiveDateTime;
+use chrono::NaiveTime;
+use chrono::TimeDelta;
 use chrono::Utc;
+use git2::Commit;
+use git2::ErrorCode;
 use git2::Repository;
+use git2::Status;
+use git2::StatusOptions;
+use serde::Deserialize;
+use serde::Serialize;
 
 use std::env;
 use std::error::Error;
+use std::fmt;
+use std::fmt::Write as _;
 use std::fs;
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::path::Path;
 use std::path::PathBuf;
 
+use crate::amend::find_amended_commit;
+use crate::atomic_write::write_atomically;
+use crate::branch::branch_tag;
+use crate::build_status::BuildStatus;
+use crate::commit_entry::normalize_message;
+use crate::commit_entry::plain_message;
+use crate::commit_entry::CommitEntry;
+use crate::conventional::commit_type_tag;
+use crate::conventional::ConventionalCommit;
+use crate::diary_parser::parse_diary_table;
+use crate::diary_parser::DiaryTable;
+use crate::diary_renderer::DiaryRenderer;
+use crate::diary_renderer::EntrySettings;
+use crate::existing_note::insert_row_under_heading;
+use crate::existing_note::section_table;
+use crate::git_env::hook_repository;
+use crate::git_env::hook_repository_with;
+use crate::git_stats::commit_diff_stats;
+use crate::git_stats::CommitSize;
+use crate::git_tags::NearestTag;
+use crate::hygiene::NEEDS_BETTER_MESSAGE_TAG;
+use crate::language::language_tag;
+use crate::paths::expand_path_placeholders;
+use crate::paths::normalize_nfc;
+use crate::paths::repository_name_from_url;
+use crate::timezone::EntryTimezone;
+use crate::trailers::TrailerTags;
+
 use log::debug;
 use log::error;
 use log::info;
 use log::warn;
 
-/// Stores Git commit metadata for logging to Obsidian diary entries.
+/// Prefix of the `COMMIT MESSAGE` cell of merge commits (the gitmoji of
+/// merges).
+pub const MERGE_MARKER: &str = "🔀";
+
+/// Label of the `EVENT` cell of merge commits.
+pub const MERGE_EVENT_LABEL: &str = "merge";
+
+/// Branch name recorded when `HEAD` points at a commit instead of a branch
+/// (e.g. after `git checkout <sha>` or during a rebase).
+pub const DETACHED_BRANCH_NAME: &str = "(detached)";
+
+/// Chrono format of the `TIME` cell when `[templates] commit_datetime` is not
+/// given.
+pub const DEFAULT_TIME_FORMAT: &str = "%H:%M:%S";
+
+/// Why a [`CommitSaver`] could not be built from a repository.
+///
+/// # Variants
+///
+/// - `NotARepository` - No Git repository was found from the given path
+/// - `EmptyRepository` - `HEAD` is unborn: the repository (or the checked
+///   out orphan branch) has no commit yet, so there is nothing to log
+/// - `TimestampOutOfRange` - The commit time cannot be represented
+/// - `Git` - Any other `git2` failure while reading `HEAD`
+#[derive(Debug)]
+pub enum CommitError {
+    NotARepository(git2::Error),
+    EmptyRepository,
+    TimestampOutOfRange(i64),
+    Git(git2::Error),
+}
+
+impl fmt::Display for CommitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommitError::NotARepository(e) => write!(f, "not inside a Git repository: {e:}"),
+            CommitError::EmptyRepository => write!(f, "the repository has no commit yet"),
+            CommitError::TimestampOutOfRange(seconds) => {
+                write!(f, "commit timestamp is out of range: {seconds:}")
+            }
+            CommitError::Git(e) => write!(f, "could not read HEAD: {e:}"),
+        }
+    }
+}
+
+impl Error for CommitError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CommitError::NotARepository(e) | CommitError::Git(e) => Some(e),
+            CommitError::EmptyRepository | CommitError::TimestampOutOfRange(_) => None,
+        }
+    }
+}
+
+impl From<git2::Error> for CommitError {
+    fn from(e: git2::Error) -> Self {
+        if matches!(e.code(), ErrorCode::UnbornBranch | ErrorCode::NotFound) {
+            CommitError::EmptyRepository
+        } else {
+            CommitError::Git(e)
+        }
+    }
+}
+
+/// A commit (or workday event) to log in the Obsidian diary.
 ///
-/// This struct captures all essential information about a single Git commit
-/// that will be written as a row in the daily diary table. It's automatically
-/// populated from the current Git repository's HEAD commit.
+/// Holds the entry data every output receives ([`CommitEntry`]), the
+/// [`EntryDetails`] worked out about it at save time, and the
+/// [`EntrySettings`] deciding how the diary entry is written. The entry is
+/// usually taken from the current Git repository's HEAD commit; the diary
+/// row, YAML block or templated row is rendered by [`DiaryRenderer`].
 ///
 /// # Examples
 ///
@@ -29,10 +136,10 @@ use log::warn;
 /// // Automatically populated from current Git repository
 /// let saver = CommitSaver::new();
 ///
-/// println!("Repository: {}", saver.repository_url);
-/// println!("Branch: {}", saver.commit_branch_name);
-/// println!("Hash: {}", saver.commit_hash);
-/// println!("Message: {}", saver.commit_msg);
+/// println!("Repository: {}", saver.entry.repository_url);
+/// println!("Branch: {}", saver.entry.branch);
+/// println!("Hash: {}", saver.entry.hash);
+/// println!("Message: {}", saver.entry.message);
 /// ```
 ///
 /// # See Also
@@ -41,69 +148,455 @@ use log::warn;
 /// - [`CommitSaver::append_entry_to_diary()`] - Write commit to diary file
 #[derive(Debug, Clone)]
 pub struct CommitSaver {
-    /// The Git remote origin URL.
+    /// The logged commit: remote URL, branch, hash, message, time, author,
+    /// diff stats and parents.
     ///
-    /// Retrieved from the repository's `origin` remote. Double quotes are stripped.
+    /// Rows that do not describe a commit (WIP, checkouts, stashes) have no
+    /// author, stats or parents, and the hash of the commit they were
+    /// logged on, if any.
+    pub entry: CommitEntry,
+
+    /// What was worked out about the entry at save time: its kind, duration,
+    /// category, timezone, ...
+    pub details: EntryDetails,
+
+    /// How the entry is written in the diary: columns, entry format, time
+    /// format, ...
+    pub settings: EntrySettings,
+}
+
+/// The annotations of a diary entry, worked out at save time from the
+/// configuration, the state index and the working tree.
+///
+/// The defaults describe a regular commit with no annotations.
+#[derive(Debug, Clone, Default)]
+pub struct EntryDetails {
+    /// What this row records.
     ///
-    /// # Examples
+    /// Regular commits use [`EntryKind::Commit`]; rows describing an
+    /// uncommitted working tree (`save --wip`) use [`EntryKind::Wip`], and
+    /// workday events (branch switches, stashes) use their own kinds.
+    pub entry_kind: EntryKind,
+
+    /// The size class of the commit, when `[size] enabled` is on.
     ///
-    /// - `https://github.com/user/repo.git`
-    /// - `git@github.com:user/repo.git`
-    /// - `https://git.sr.ht/~user/repo`
-    pub repository_url: String,
+    /// Shown in the `SIZE` column and written to the diary frontmatter as a
+    /// `#size/<class>` tag.
+    pub commit_size: Option<CommitSize>,
 
-    /// The current Git branch name.
+    /// The annotated tag at or shortly before the commit, when
+    /// `[release_tags] enabled` is on, shown in the `TAG` column.
+    pub release_tag: Option<NearestTag>,
+
+    /// Estimated time spent on this commit, shown in the `DURATION` column.
     ///
-    /// Retrieved from the repository's HEAD reference. Double quotes are stripped.
+    /// `None` until set with
+    /// [`set_duration_since()`](CommitSaver::set_duration_since), and for the
+    /// first logged commit of a repository.
+    pub duration: Option<TimeDelta>,
+
+    /// The project/tag reported by the time tracker at save time.
     ///
-    /// # Examples
+    /// Shown in the `TRACKING` column; `None` when no tracker is configured
+    /// or nothing is being tracked.
+    pub tracked_activity: Option<String>,
+
+    /// Whether the committed state built, checked at save time.
     ///
-    /// - `main`
-    /// - `develop`
-    /// - `feature/add-documentation`
-    pub commit_branch_name: String,
+    /// Shown in the `STATUS` column; `None` when no check is configured, the
+    /// check failed to run, or the entry was not logged from its working tree.
+    pub build_status: Option<BuildStatus>,
 
-    /// The full SHA-1 commit hash (40 characters).
+    /// The category (e.g. `work`, `oss`) matched by the `[categories]` rules.
     ///
-    /// Uniquely identifies the commit in the Git repository.
+    /// Written to the diary frontmatter as a `categories` entry and a
+    /// `#category/<name>` tag; `None` when no rule matches.
+    pub category: Option<String>,
+
+    /// The language of the commit message (ISO 639-3, e.g. `eng`), when
+    /// `[language] detect` is on and the detection is reliable.
+    ///
+    /// Written to the diary frontmatter as a `#lang/<code>` tag.
+    pub language: Option<String>,
+
+    /// The branch slug (e.g. `feature-login-page`), when `[branches] tags` is on.
+    ///
+    /// Written to the diary frontmatter as a `#branch/<slug>` tag.
+    pub branch_slug: Option<String>,
+
+    /// The timezone the entry was logged in, when `[templates] timezone` or
+    /// `[table] time_zone` is set.
+    ///
+    /// The diary path, the dates and the `TIME` column then use the local
+    /// time instead of UTC (see [`local_datetime()`](Self::local_datetime)).
+    /// With `[table] time_zone`, the time is followed by the zone
+    /// (`14:30:45 CET`) and new diary files record it in a `timezone:`
+    /// frontmatter field.
+    pub timezone: Option<EntryTimezone>,
+
+    /// The position of the entry among the rows logged in the same second,
+    /// when `[table] same_second = sequence` found earlier ones.
+    ///
+    /// The `TIME` column then gets a ` #02`, ` #03`, ... suffix, see
+    /// [`SameSecondPolicy`](crate::collision::SameSecondPolicy).
+    pub time_sequence: Option<usize>,
+
+    /// The hash of the commit this one replaced with `git commit --amend`.
+    ///
+    /// Set for `HEAD` by [`from_repo()`](CommitSaver::from_repo) with
+    /// [`find_amended_commit()`]. The row of the amended commit is then
+    /// replaced in place, see
+    /// [`replace_entry_in_diary()`](CommitSaver::replace_entry_in_diary).
+    pub amends: Option<String>,
+
+    /// Whether the commit message does not match `[message_hygiene] pattern`.
+    ///
+    /// The diary file then gets a
+    /// [`NEEDS_BETTER_MESSAGE_TAG`](crate::hygiene::NEEDS_BETTER_MESSAGE_TAG).
+    pub needs_better_message: bool,
+
+    /// Conventional Commits parts of the message, shown in the `TYPE`,
+    /// `SCOPE`, `BREAKING` and `SUBJECT` columns and tagged `#commit/<type>`.
+    ///
+    /// Only set for commits with `[conventional_commits] enabled` and a
+    /// message following the convention, see
+    /// [`parse_conventional_commit()`](crate::conventional::parse_conventional_commit).
+    pub conventional: Option<ConventionalCommit>,
+
+    /// Tags read from the `Obsidian-Tags` trailers of the message, when
+    /// `[trailers] tags` is set.
+    ///
+    /// Appended to the `COMMIT MESSAGE` cell (replacing the trailer lines)
+    /// and/or written to the diary
+    /// frontmatter, depending on their
+    /// [`TagPlacement`](crate::trailers::TagPlacement), and available as
+    /// the `tags` row template variable.
+    pub trailer_tags: Option<TrailerTags>,
+
+    /// The Git directory of the repository the entry was read from, set by
+    /// [`in_repository()`](CommitSaver::in_repository).
+    ///
+    /// Merge summaries look the commit up there rather than in the
+    /// repository of the current directory, so `daemon` and `log-recent`
+    /// save entries of several repositories from one process. `None` for
+    /// entries not read from a local repository (GitHub imports).
+    pub git_dir: Option<PathBuf>,
+
+    /// The working directory of that repository, `None` for bare
+    /// repositories and entries not read from a local one.
     ///
-    /// # Format
+    /// Written in the `FOLDER` column and matched by the path rules of
+    /// `[categories]` and `[vaults]`, see [`folder()`](CommitSaver::folder).
+    pub workdir: Option<PathBuf>,
+}
+
+impl EntryDetails {
+    /// Returns `datetime` in the entry [`timezone`](Self::timezone), or in
+    /// UTC when none is set.
     ///
-    /// Always 40 hexadecimal characters (e.g., `abc123def456...`)
-    pub commit_hash: String,
+    /// Every date and time written for the entry comes from it, so an
+    /// evening commit east of UTC goes to the diary of its local day.
+    #[must_use]
+    pub fn local_datetime(&self, datetime: DateTime<Utc>) -> DateTime<FixedOffset> {
+        match &self.timezone {
+            Some(timezone) => datetime.with_timezone(&timezone.offset),
+            None => datetime.fixed_offset(),
+        }
+    }
 
-    /// The formatted commit message for Obsidian display.
+    /// Returns the `TIME` cell of an entry made at `datetime`, without a
+    /// sequence suffix.
     ///
-    /// The message is processed for safe rendering in Markdown tables:
-    /// - Pipe characters (`|`) are escaped to `\|`
-    /// - Multiple lines are joined with `<br/>`
-    /// - Empty lines are filtered out
-    /// - Leading/trailing whitespace is trimmed
+    /// The time is formatted with `time_format` in UTC, or in local time,
+    /// followed by the zone label if any, when [`timezone`](Self::timezone)
+    /// is set.
+    #[must_use]
+    pub fn entry_time(&self, datetime: DateTime<Utc>, time_format: &str) -> String {
+        match &self.timezone {
+            Some(timezone) => timezone.format_time(datetime, time_format),
+            None => datetime.format(time_format).to_string(),
+        }
+    }
+}
+
+/// The kind of event a diary row describes.
+///
+/// # Variants
+///
+/// - `Commit` - A regular commit taken from `HEAD` (default)
+/// - `Wip` - A snapshot of the dirty working tree, logged before a commit exists
+/// - `Checkout` - A branch switch, recorded from the `post-checkout` hook
+/// - `StashPush` - Changes were stashed with `git stash push`
+/// - `StashPop` - A stash entry was applied and dropped with `git stash pop`
+/// - `Push` - A ref was pushed, recorded from the `pre-push` hook
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EntryKind {
+    #[default]
+    Commit,
+    Wip,
+    Checkout,
+    StashPush,
+    StashPop,
+    Push,
+}
+
+impl EntryKind {
+    /// Returns the label written to the `EVENT` column of the diary table.
     ///
     /// # Examples
     ///
-    /// ```text
-    /// Original: "feat: add feature\n\nWith details"
-    /// Formatted: "feat: add feature<br/>With details"
+    /// ```ignore
+    /// use rusty_commit_saver::vim_commit::EntryKind;
     ///
-    /// Original: "fix: issue | problem"
-    /// Formatted: "fix: issue \| problem"
+    /// assert_eq!(EntryKind::Commit.label(), "commit");
+    /// assert_eq!(EntryKind::StashPop.label(), "stash pop");
     /// ```
-    pub commit_msg: String,
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            EntryKind::Commit => "commit",
+            EntryKind::Wip => "wip",
+            EntryKind::Checkout => "checkout",
+            EntryKind::StashPush => "stash push",
+            EntryKind::StashPop => "stash pop",
+            EntryKind::Push => "push",
+        }
+    }
+}
+
+/// A column of the diary commit table.
+///
+/// The table header written by [`create_diary_file()`] and the rows appended by
+/// [`CommitSaver::append_entry_to_diary()`] are both rendered from the same
+/// ordered list of columns, so they always line up.
+///
+/// # Variants
+///
+/// - `Folder` - Working directory the entry was recorded from (`FOLDER`)
+/// - `Time` - Entry time, `HH:MM:SS` (`TIME`)
+/// - `CommitMessage` - Formatted commit or event message (`COMMIT MESSAGE`)
+/// - `RepositoryUrl` - Remote origin URL (`REPOSITORY URL`)
+/// - `Branch` - Branch name (`BRANCH`)
+/// - `CommitHash` - Full commit hash (`COMMIT HASH`)
+/// - `Event` - [`EntryKind`] label such as `commit` or `checkout` (`EVENT`)
+/// - `Duration` - Time since the previous logged commit of the repository (`DURATION`)
+/// - `Tracking` - Project/tag from the configured time tracker (`TRACKING`)
+/// - `BranchSlug` - Branch name usable in tags, see [`branch_slug()`] (`BRANCH SLUG`)
+/// - `Status` - Build status of the committed state, see [`BuildStatus`] (`STATUS`)
+/// - `Author` - Name of the commit author (`AUTHOR`)
+/// - `AuthorEmail` - Email of the commit author (`AUTHOR EMAIL`)
+/// - `Parents` - Short hashes of the commit's parents (`PARENTS`)
+/// - `Stats` - Lines and files changed, see [`DiffStats`] (`STATS`)
+/// - `Size` - Size class tag of the commit, see [`CommitSize`] (`SIZE`)
+/// - `Tag` - Annotated tag at or near the commit, see [`NearestTag`] (`TAG`)
+/// - `Note` - Freeform notes added afterwards with `annotate`, empty on new rows (`NOTE`)
+/// - `CommitType` - Conventional Commits type, see [`ConventionalCommit`] (`TYPE`)
+/// - `Scope` - Conventional Commits scope (`SCOPE`)
+/// - `Breaking` - `yes` for Conventional Commits breaking changes (`BREAKING`)
+/// - `Subject` - Conventional Commits description, without type and scope (`SUBJECT`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableColumn {
+    Folder,
+    Time,
+    CommitMessage,
+    RepositoryUrl,
+    Branch,
+    CommitHash,
+    Event,
+    Duration,
+    Tracking,
+    BranchSlug,
+    Status,
+    Author,
+    AuthorEmail,
+    Parents,
+    Stats,
+    Size,
+    Tag,
+    Note,
+    CommitType,
+    Scope,
+    Breaking,
+    Subject,
+}
+
+impl TableColumn {
+    /// Returns the column title used in the diary table header.
+    #[must_use]
+    pub fn header(self) -> &'static str {
+        match self {
+            TableColumn::Folder => "FOLDER",
+            TableColumn::Time => "TIME",
+            TableColumn::CommitMessage => "COMMIT MESSAGE",
+            TableColumn::RepositoryUrl => "REPOSITORY URL",
+            TableColumn::Branch => "BRANCH",
+            TableColumn::CommitHash => "COMMIT HASH",
+            TableColumn::Event => "EVENT",
+            TableColumn::Duration => "DURATION",
+            TableColumn::Tracking => "TRACKING",
+            TableColumn::BranchSlug => "BRANCH SLUG",
+            TableColumn::Status => "STATUS",
+            TableColumn::Author => "AUTHOR",
+            TableColumn::AuthorEmail => "AUTHOR EMAIL",
+            TableColumn::Parents => "PARENTS",
+            TableColumn::Stats => "STATS",
+            TableColumn::Size => "SIZE",
+            TableColumn::Tag => "TAG",
+            TableColumn::Note => "NOTE",
+            TableColumn::CommitType => "TYPE",
+            TableColumn::Scope => "SCOPE",
+            TableColumn::Breaking => "BREAKING",
+            TableColumn::Subject => "SUBJECT",
+        }
+    }
+
+    /// Returns the key of the column in YAML entry blocks.
+    #[must_use]
+    pub fn key(self) -> &'static str {
+        match self {
+            TableColumn::Folder => "folder",
+            TableColumn::Time => "datetime",
+            TableColumn::CommitMessage => "commit_message",
+            TableColumn::RepositoryUrl => "repository_url",
+            TableColumn::Branch => "branch",
+            TableColumn::CommitHash => "commit_hash",
+            TableColumn::Event => "event",
+            TableColumn::Duration => "duration",
+            TableColumn::Tracking => "tracking",
+            TableColumn::BranchSlug => "branch_slug",
+            TableColumn::Status => "status",
+            TableColumn::Author => "author",
+            TableColumn::AuthorEmail => "author_email",
+            TableColumn::Parents => "parents",
+            TableColumn::Stats => "diff_stats",
+            TableColumn::Size => "size",
+            TableColumn::Tag => "tag",
+            TableColumn::Note => "note",
+            TableColumn::CommitType => "commit_type",
+            TableColumn::Scope => "scope",
+            TableColumn::Breaking => "breaking",
+            TableColumn::Subject => "subject",
+        }
+    }
 
-    /// The UTC timestamp when the commit was created.
+    /// Parses a column name of the `[table] columns` list.
+    ///
+    /// Accepts the YAML key ([`key()`](Self::key)), the header title
+    /// (`commit message`) and the short names `time`, `message`, `repository`,
+    /// `url`, `hash`, `slug`, `email` and `type`, in any case.
     ///
-    /// Used for:
-    /// - Generating date-based directory paths
-    /// - Displaying commit time in diary entries
-    /// - Creating frontmatter tags (week number, day of week)
+    /// # Examples
     ///
-    /// # Format
+    /// ```ignore
+    /// use rusty_commit_saver::vim_commit::TableColumn;
     ///
-    /// Stored as `DateTime<Utc>` from the `chrono` crate.
-    pub commit_datetime: DateTime<Utc>,
+    /// assert_eq!(TableColumn::from_name("hash"), Some(TableColumn::CommitHash));
+    /// assert_eq!(TableColumn::from_name("Commit Message"), Some(TableColumn::CommitMessage));
+    /// ```
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase().replace([' ', '-'], "_");
+        match name.as_str() {
+            "folder" => Some(TableColumn::Folder),
+            "time" | "datetime" => Some(TableColumn::Time),
+            "message" | "commit_message" => Some(TableColumn::CommitMessage),
+            "repository" | "url" | "repository_url" => Some(TableColumn::RepositoryUrl),
+            "branch" => Some(TableColumn::Branch),
+            "hash" | "commit_hash" => Some(TableColumn::CommitHash),
+            "event" => Some(TableColumn::Event),
+            "duration" => Some(TableColumn::Duration),
+            "tracking" => Some(TableColumn::Tracking),
+            "slug" | "branch_slug" => Some(TableColumn::BranchSlug),
+            "status" | "build" => Some(TableColumn::Status),
+            "author" | "author_name" => Some(TableColumn::Author),
+            "email" | "author_email" => Some(TableColumn::AuthorEmail),
+            "parents" => Some(TableColumn::Parents),
+            "stats" | "diff" | "diff_stats" => Some(TableColumn::Stats),
+            "size" => Some(TableColumn::Size),
+            "tag" | "release" | "release_tag" => Some(TableColumn::Tag),
+            "note" | "notes" => Some(TableColumn::Note),
+            "type" | "commit_type" => Some(TableColumn::CommitType),
+            "scope" => Some(TableColumn::Scope),
+            "breaking" => Some(TableColumn::Breaking),
+            "subject" => Some(TableColumn::Subject),
+            _ => None,
+        }
+    }
+}
+
+/// How entries are written to the diary file.
+///
+/// # Variants
+///
+/// - `Table` - One Markdown table row per entry, below a table header (default)
+/// - `Yaml` - One fenced `yaml` block per entry, keyed by [`TableColumn::key()`],
+///   for vaults post-processed by scripts
+/// - `Template` - One entry rendered from `[templates] row_template` (bullet
+///   lists, callouts, ...), see [`CommitSaver::row_template`]
+/// - `Dataview` - One bullet per entry with Dataview inline fields
+///   (`- [time:: 14:30:45] [repo:: api] ... message`), for vault-wide queries
+///
+/// # Configuration
+///
+/// ```text
+/// [templates]
+/// entry_format = yaml
+/// ```
+///
+/// or, taking precedence:
+///
+/// ```text
+/// [output]
+/// style = dataview
+/// ```
+///
+/// Setting `row_template` selects `Template`, whatever `entry_format` says.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EntryFormat {
+    #[default]
+    Table,
+    Yaml,
+    Template,
+    Dataview,
+}
+
+impl EntryFormat {
+    /// Parses the `[templates] entry_format` and `[output] style` values
+    /// (`table`, `yaml` or `dataview`, any case).
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "table" => Some(EntryFormat::Table),
+            "yaml" => Some(EntryFormat::Yaml),
+            "dataview" => Some(EntryFormat::Dataview),
+            _ => None,
+        }
+    }
+
+    /// Returns what a new diary file holds above its entries: the table header
+    /// for [`EntryFormat::Table`], nothing for the other formats.
+    #[must_use]
+    pub fn header(self, columns: &[TableColumn]) -> String {
+        match self {
+            EntryFormat::Table => render_table_header(columns),
+            EntryFormat::Yaml | EntryFormat::Template | EntryFormat::Dataview => String::new(),
+        }
+    }
 }
 
+/// The columns of the diary table when nothing else is configured.
+pub const DEFAULT_TABLE_COLUMNS: [TableColumn; 6] = [
+    TableColumn::Folder,
+    TableColumn::Time,
+    TableColumn::CommitMessage,
+    TableColumn::RepositoryUrl,
+    TableColumn::Branch,
+    TableColumn::CommitHash,
+];
+
+/// Maximum number of file names listed in a WIP row before summarizing as `…`.
+const WIP_MAX_LISTED_FILES: usize = 5;
+
 /// Creates a `CommitSaver` instance with default values from the current Git repository.
 ///
 /// This implementation automatically discovers the Git repository in the current directory
@@ -152,6 +645,14 @@ impl Default for CommitSaver {
     }
 }
 
+impl From<CommitEntry> for CommitSaver {
+    /// Builds a `CommitSaver` logging the entry as a regular commit, with no
+    /// details and the default settings.
+    fn from(entry: CommitEntry) -> Self {
+        CommitSaver::with_kind(entry, EntryKind::Commit)
+    }
+}
+
 impl CommitSaver {
     /// Builds a `CommitSaver` from an explicit repository handle.
     ///
@@ -161,63 +662,334 @@ impl CommitSaver {
     /// the metadata extraction pure and testable, with no dependency on
     /// process-global state such as the current directory.
     ///
+    /// A detached `HEAD` is logged on the [`DETACHED_BRANCH_NAME`] branch.
+    ///
     /// # Errors
     ///
-    /// Returns an error if the repository has no resolvable `HEAD`, if `HEAD`
-    /// cannot be peeled to a commit, or if the commit timestamp is out of the
-    /// representable range.
-    pub fn from_repo(git_repo: &Repository) -> Result<Self, Box<dyn Error>> {
+    /// Returns [`CommitError::EmptyRepository`] if `HEAD` is unborn (no commit
+    /// yet), [`CommitError::TimestampOutOfRange`] if the commit time is out of
+    /// the representable range, and [`CommitError::Git`] if `HEAD` cannot be
+    /// peeled to a commit.
+    pub fn from_repo(git_repo: &Repository) -> Result<Self, CommitError> {
         let head = git_repo.head()?;
         let commit = head.peel_to_commit()?;
-        let commit_datetime = DateTime::from_timestamp(commit.time().seconds(), 0)
-            .ok_or("commit timestamp is out of range")?;
 
-        Ok(CommitSaver {
-            repository_url: match git_repo.find_remote("origin") {
-                Ok(remote) => remote.url().unwrap_or("no_url_set").replace('"', ""),
-                _ => "no_url_set".to_string(),
-            },
-            commit_branch_name: head.shorthand().unwrap_or("no_branch_set").replace('"', ""),
-            commit_hash: commit.id().to_string(),
-            // Preserve original lines, escape pipes, then join with <br/>
-            commit_msg: commit
-                .message()
-                .unwrap_or("")
-                .lines()
-                .map(|line| line.trim().replace('|', "\\|"))
-                .filter(|line| !line.is_empty())
-                .collect::<Vec<_>>()
-                .join("<br/>"),
-            commit_datetime,
+        let mut commit_saver =
+            CommitSaver::from_commit(git_repo, &commit, &head_branch_name(git_repo))?;
+        commit_saver.details.amends = find_amended_commit(git_repo, &commit);
+        Ok(commit_saver)
+    }
+
+    /// Builds a `CommitSaver` for any commit of the repository.
+    ///
+    /// Used by [`CommitSaver::from_repo`] for `HEAD` and by batch saves
+    /// (`save --stdin`) for every listed commit. The branch is passed in
+    /// because a commit does not know which branch it was made on.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CommitError::TimestampOutOfRange`] if the commit timestamp is
+    /// out of the representable range.
+    pub fn from_commit(
+        git_repo: &Repository,
+        commit: &Commit,
+        branch_name: &str,
+    ) -> Result<Self, CommitError> {
+        let author = commit.author();
+        let signature_field = |field: Result<&str, git2::Error>| field.ok().map(str::to_string);
+        let seconds = commit.time().seconds();
+        let datetime = DateTime::from_timestamp(seconds, 0)
+            .ok_or(CommitError::TimestampOutOfRange(seconds))?;
+
+        Ok(CommitSaver::from(CommitEntry {
+            repository_url: origin_url(git_repo),
+            branch: branch_name.replace('"', ""),
+            hash: commit.id().to_string(),
+            message: normalize_message(commit.message().unwrap_or("")),
+            datetime,
+            author: signature_field(author.name()),
+            author_email: signature_field(author.email()),
+            stats: commit_diff_stats(git_repo, commit).ok(),
+            parents: commit.parent_ids().map(|oid| oid.to_string()).collect(),
         })
+        .in_repository(git_repo))
+    }
+
+    /// Records the repository the entry was read from in the
+    /// [`git_dir`](EntryDetails::git_dir) and
+    /// [`workdir`](EntryDetails::workdir) details.
+    ///
+    /// Every constructor taking a repository calls it, so saving the entry
+    /// never looks at the current directory to find its repository.
+    #[must_use]
+    pub fn in_repository(mut self, git_repo: &Repository) -> Self {
+        self.details.git_dir = Some(git_repo.path().to_path_buf());
+        // `workdir()` ends with a separator, unlike the current directory
+        // written in the `FOLDER` column before.
+        self.details.workdir = git_repo
+            .workdir()
+            .map(|workdir| workdir.components().collect());
+        self
+    }
+
+    /// Builds a `CommitSaver` for an entry of the given kind, with no other
+    /// details and the default settings.
+    ///
+    /// Workday events (WIP rows, checkouts, stashes, pushes) are built this
+    /// way; [`CommitSaver::from()`] builds regular commits.
+    #[must_use]
+    pub fn with_kind(entry: CommitEntry, entry_kind: EntryKind) -> Self {
+        CommitSaver {
+            entry,
+            details: EntryDetails {
+                entry_kind,
+                ..EntryDetails::default()
+            },
+            settings: EntrySettings::default(),
+        }
+    }
+
+    /// Builds a WIP `CommitSaver` describing the repository's dirty working tree.
+    ///
+    /// Instead of reading the `HEAD` commit, this inspects the working tree
+    /// status and produces a stash-like summary, so progress can be journaled
+    /// before a commit exists. The row is timestamped with the current time and
+    /// its hash column holds the base `HEAD` commit (empty for unborn branches).
+    ///
+    /// # Message Format
+    ///
+    /// ```text
+    /// 🚧 WIP on main: 1a2b3c4 last commit subject<br/>2 modified, 1 new: src/lib.rs, src/main.rs, notes.md
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the working tree status cannot be read or if there
+    /// are no uncommitted changes to describe.
+    pub fn wip_from_repo(git_repo: &Repository) -> Result<Self, Box<dyn Error>> {
+        info!("[CommitSaver::wip_from_repo()]: Summarizing the working tree.");
+        let summary = summarize_working_tree(git_repo)?
+            .ok_or("working tree is clean, nothing to log as WIP")?;
+
+        let commit_branch_name = head_branch_name(git_repo);
+        let base_commit = git_repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+
+        let base_description = match &base_commit {
+            Some(commit) => format!(
+                "{:} {:}",
+                &commit.id().to_string()[..7],
+                commit.summary().ok().flatten().unwrap_or("").trim()
+            ),
+            None => "(no commits yet)".to_string(),
+        };
+
+        Ok(CommitSaver::with_kind(
+            CommitEntry {
+                repository_url: origin_url(git_repo),
+                message: format!(
+                    "🚧 WIP on {commit_branch_name:}: {base_description:}\n{summary:}"
+                ),
+                branch: commit_branch_name,
+                hash: base_commit
+                    .map(|commit| commit.id().to_string())
+                    .unwrap_or_default(),
+                datetime: Utc::now(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            },
+            EntryKind::Wip,
+        )
+        .in_repository(git_repo))
+    }
+
+    /// Opens the Git repository of the hook (see [`hook_repository()`]) and
+    /// builds a WIP `CommitSaver` from its working tree.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no Git repository can be opened or discovered from
+    /// the current directory, or if [`CommitSaver::wip_from_repo`] fails.
+    pub fn try_new_wip() -> Result<Self, Box<dyn Error>> {
+        let git_repo = hook_repository()?;
+        CommitSaver::wip_from_repo(&git_repo)
+    }
+
+    /// Builds a branch-switch event row from the last `HEAD` reflog entry.
+    ///
+    /// Meant to be called from the `post-checkout` hook. The previous and new
+    /// branch names are read from the `checkout: moving from <a> to <b>`
+    /// reflog message, so both names are available even though the hook only
+    /// receives commit hashes.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Some(saver))` - The last `HEAD` move was a switch between two refs
+    /// - `Ok(None)` - The last `HEAD` move was not a branch switch (rebase, reset,
+    ///   clone, or a checkout of the branch that was already current)
+    ///
+    /// # Message Format
+    ///
+    /// ```text
+    /// 🔀 Switched from main to feature/login
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `HEAD` reflog cannot be read or `HEAD` does not
+    /// point to a commit.
+    pub fn checkout_from_repo(git_repo: &Repository) -> Result<Option<Self>, Box<dyn Error>> {
+        info!("[CommitSaver::checkout_from_repo()]: Reading the last HEAD reflog entry.");
+        let reflog = git_repo.reflog("HEAD")?;
+        let Some(entry) = reflog.get(0) else {
+            info!("[CommitSaver::checkout_from_repo()]: HEAD reflog is empty.");
+            return Ok(None);
+        };
+
+        let message = entry.message().ok().flatten().unwrap_or("");
+        let Some((previous_ref, new_ref)) = parse_checkout_reflog_message(message) else {
+            info!("[CommitSaver::checkout_from_repo()]: Not a branch checkout: {message:}");
+            return Ok(None);
+        };
+        if previous_ref == new_ref {
+            info!("[CommitSaver::checkout_from_repo()]: Checked out the current branch again.");
+            return Ok(None);
+        }
+
+        let commit = git_repo.head()?.peel_to_commit()?;
+
+        Ok(Some(
+            CommitSaver::with_kind(
+                CommitEntry {
+                    repository_url: origin_url(git_repo),
+                    branch: new_ref.replace('"', ""),
+                    hash: commit.id().to_string(),
+                    message: format!("🔀 Switched from {previous_ref:} to {new_ref:}"),
+                    datetime: Utc::now(),
+                    author: None,
+                    author_email: None,
+                    stats: None,
+                    parents: Vec::new(),
+                },
+                EntryKind::Checkout,
+            )
+            .in_repository(git_repo),
+        ))
+    }
+
+    /// Builds a stash-push event row describing the newest stash entry.
+    ///
+    /// The message reuses the stash description Git stored in the
+    /// `refs/stash` reflog, and the hash column holds the stash commit.
+    ///
+    /// # Message Format
+    ///
+    /// ```text
+    /// 📦 Stash push: WIP on main: 1a2b3c4 last commit subject
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stash reflog cannot be read or holds no entries.
+    pub fn stash_push_from_repo(git_repo: &Repository) -> Result<Self, Box<dyn Error>> {
+        info!("[CommitSaver::stash_push_from_repo()]: Reading the newest stash entry.");
+        let reflog = git_repo.reflog("refs/stash")?;
+        let entry = reflog.get(0).ok_or("no stash entries found")?;
+        let description = entry.message().ok().flatten().unwrap_or("").trim();
+
+        Ok(CommitSaver::with_kind(
+            CommitEntry {
+                repository_url: origin_url(git_repo),
+                branch: head_branch_name(git_repo),
+                hash: entry.id_new().to_string(),
+                message: format!("📦 Stash push: {description:}"),
+                datetime: Utc::now(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            },
+            EntryKind::StashPush,
+        )
+        .in_repository(git_repo))
+    }
+
+    /// Builds a stash-pop event row.
+    ///
+    /// The popped entry is already gone when this runs, so the row records how
+    /// many stash entries remain; the hash column holds the current `HEAD`
+    /// commit (empty on an unborn branch).
+    ///
+    /// # Message Format
+    ///
+    /// ```text
+    /// 📦 Stash pop (2 left)
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stash reflog cannot be read.
+    pub fn stash_pop_from_repo(git_repo: &Repository) -> Result<Self, Box<dyn Error>> {
+        info!("[CommitSaver::stash_pop_from_repo()]: Counting the remaining stash entries.");
+        let remaining = git_repo.reflog("refs/stash")?.len();
+
+        Ok(CommitSaver::with_kind(
+            CommitEntry {
+                repository_url: origin_url(git_repo),
+                branch: head_branch_name(git_repo),
+                hash: git_repo
+                    .head()
+                    .ok()
+                    .and_then(|h| h.peel_to_commit().ok())
+                    .map(|commit| commit.id().to_string())
+                    .unwrap_or_default(),
+                message: format!("📦 Stash pop ({remaining:} left)"),
+                datetime: Utc::now(),
+                author: None,
+                author_email: None,
+                stats: None,
+                parents: Vec::new(),
+            },
+            EntryKind::StashPop,
+        )
+        .in_repository(git_repo))
     }
 
     /// Internal helper for path-injected repository discovery.
     ///
-    /// Discovers a Git repository at the given path and builds a `CommitSaver`
-    /// from its `HEAD` commit. This function enables testing of error cases
-    /// without mutating the process's current directory.
+    /// Opens the repository of `GIT_DIR` when the hook environment has one,
+    /// or discovers a Git repository at the given path (see
+    /// [`hook_repository_with()`]), and builds a `CommitSaver` from its `HEAD`
+    /// commit. This function enables testing of error cases without mutating
+    /// the process's current directory.
     ///
     /// # Errors
     ///
-    /// Returns an error if no Git repository can be discovered from the given
-    /// path, or if [`CommitSaver::from_repo`] fails for the discovered repo.
-    fn try_discover(path: &Path) -> Result<Self, Box<dyn Error>> {
-        let git_repo = Repository::discover(path)?;
+    /// Returns [`CommitError::NotARepository`] if no Git repository can be
+    /// opened or discovered from the given path, or the error of
+    /// [`CommitSaver::from_repo`] for the repo.
+    fn try_discover(path: &Path) -> Result<Self, CommitError> {
+        let git_repo =
+            hook_repository_with(env::var_os("GIT_DIR"), env::var_os("GIT_WORK_TREE"), path)
+                .map_err(CommitError::NotARepository)?;
         CommitSaver::from_repo(&git_repo)
     }
 
-    /// Discovers the Git repository in the current directory and builds a
-    /// `CommitSaver` from its `HEAD` commit.
+    /// Opens the Git repository of the hook and builds a `CommitSaver` from
+    /// its `HEAD` commit.
     ///
-    /// This is the non-panicking counterpart to [`CommitSaver::new`] /
-    /// [`CommitSaver::default`].
+    /// The repository comes from the `GIT_DIR` Git exports to hooks when set,
+    /// and is discovered from the current directory otherwise, see
+    /// [`hook_repository()`](crate::git_env::hook_repository). This is the non-panicking counterpart to
+    /// [`CommitSaver::new`] / [`CommitSaver::default`].
     ///
     /// # Errors
     ///
-    /// Returns an error if no Git repository can be discovered from the current
-    /// directory, or if [`CommitSaver::from_repo`] fails for the discovered repo.
-    pub fn try_new() -> Result<Self, Box<dyn Error>> {
+    /// Returns [`CommitError::NotARepository`] if no Git repository can be
+    /// opened or discovered, or the error of [`CommitSaver::from_repo`] for
+    /// the repo.
+    pub fn try_new() -> Result<Self, CommitError> {
         CommitSaver::try_discover(Path::new("./"))
     }
 
@@ -241,54 +1013,101 @@ impl CommitSaver {
     /// use rusty_commit_saver::CommitSaver;
     ///
     /// let saver = CommitSaver::new();
-    /// println!("Commit hash: {}", saver.commit_hash);
+    /// println!("Commit hash: {}", saver.entry.hash);
     /// ```
     #[must_use]
     pub fn new() -> Self {
         CommitSaver::default()
     }
 
-    /// Formats commit metadata as a Markdown table row for diary entry.
-    ///
-    /// Generates a single table row containing all commit information in the format
-    /// expected by the Obsidian diary template. The row includes pipe delimiters
-    /// and ends with a newline.
+    /// Returns the folder of the entry: the [`workdir`](EntryDetails::workdir)
+    /// of its repository, or the current directory for entries without one.
     ///
-    /// # Arguments
+    /// # Errors
     ///
-    /// * `path` - The current working directory where the commit was made
+    /// Returns an error if the entry has no working directory and the current
+    /// one cannot be determined.
+    pub fn folder(&self) -> std::io::Result<PathBuf> {
+        match &self.details.workdir {
+            Some(workdir) => Ok(workdir.clone()),
+            None => env::current_dir(),
+        }
+    }
+
+    /// Returns the [`DiaryRenderer`] of the entry, with its details and
+    /// settings, writing `folder` in the `FOLDER` column.
+    #[must_use]
+    pub fn renderer<'a>(&'a self, folder: &'a Path) -> DiaryRenderer<'a> {
+        DiaryRenderer {
+            settings: &self.settings,
+            details: &self.details,
+            folder,
+        }
+    }
+
+    /// Formats the entry as the diary writes it, logged from `path`, see
+    /// [`DiaryRenderer::render_entry()`].
+    fn prepare_commit_entry_as_string(&self, path: &Path) -> String {
+        self.renderer(path).render_entry(&self.entry)
+    }
+
+    /// Returns the commit timestamp in the entry timezone, or in UTC when
+    /// none is set, see [`EntryDetails::local_datetime()`].
+    #[must_use]
+    pub fn local_datetime(&self) -> DateTime<FixedOffset> {
+        self.details.local_datetime(self.entry.datetime)
+    }
+
+    /// Returns the `TIME` cell of the entry, without a sequence suffix, see
+    /// [`EntryDetails::entry_time()`].
+    #[must_use]
+    pub fn entry_time(&self) -> String {
+        self.details
+            .entry_time(self.entry.datetime, self.settings.time_format_or_default())
+    }
+
+    /// Estimates the time spent on this commit from the previous logged one.
     ///
-    /// # Returns
+    /// The estimate is the time elapsed since `previous_commit_datetime`,
+    /// capped at `max_duration` so overnight gaps or weekends do not count as
+    /// work. Only regular commits get a duration; WIP and event rows, a
+    /// missing previous commit, or a previous commit that is not older than
+    /// this one leave it unset.
     ///
-    /// A formatted string representing one table row with these columns:
-    /// 1. **FOLDER** - Current working directory path
-    /// 2. **TIME** - Commit timestamp (HH:MM:SS format)
-    /// 3. **COMMIT MESSAGE** - Escaped and formatted commit message
-    /// 4. **REPOSITORY URL** - Git remote origin URL
-    /// 5. **BRANCH** - Current branch name
-    /// 6. **COMMIT HASH** - Full SHA-1 commit hash
+    /// # Arguments
     ///
-    /// # Format
+    /// * `previous_commit_datetime` - Time of the previous logged commit in the same repository
+    /// * `max_duration` - Upper bound for the estimate (e.g., 2 hours)
     ///
-    /// ```text
-    /// | /path/to/repo | 14:30:45 | feat: add feature | https://github.com/user/repo.git | main | abc123... |
-    /// ```
+    /// # Examples
     ///
-    /// # Note
+    /// ```ignore
+    /// use chrono::TimeDelta;
     ///
-    /// This is a private helper method called by [`append_entry_to_diary()`](Self::append_entry_to_diary).
-    /// The commit message has already been formatted with escaped pipes and `<br/>` separators
-    /// during struct initialization.
-    fn prepare_commit_entry_as_string(&mut self, path: &Path) -> String {
-        format!(
-            "| {:} | {:} | {:} | {:} | {:} | {:} |\n",
-            path.display(),
-            self.commit_datetime.format("%H:%M:%S"),
-            self.commit_msg,
-            self.repository_url,
-            self.commit_branch_name,
-            self.commit_hash
-        )
+    /// // Previous commit 3 hours earlier, capped at 2 hours
+    /// saver.set_duration_since(Some(three_hours_ago), TimeDelta::hours(2));
+    /// assert_eq!(saver.details.duration, Some(TimeDelta::hours(2)));
+    /// ```
+    pub fn set_duration_since(
+        &mut self,
+        previous_commit_datetime: Option<DateTime<Utc>>,
+        max_duration: TimeDelta,
+    ) {
+        self.details.duration = match previous_commit_datetime {
+            Some(previous) if self.details.entry_kind == EntryKind::Commit => {
+                let elapsed = self.entry.datetime - previous;
+                if elapsed > TimeDelta::zero() {
+                    Some(elapsed.min(max_duration))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+        info!(
+            "[CommitSaver::set_duration_since()]: Estimated duration: {:?}",
+            self.details.duration
+        );
     }
 
     /// Generates Obsidian-style frontmatter tags based on the commit timestamp.
@@ -297,6 +1116,8 @@ impl CommitSaver {
     /// 1. **Week tag**: `#datetime/week/WW` (e.g., `#datetime/week/02` for week 2)
     /// 2. **Day tag**: `#datetime/days/DDDD` (e.g., `#datetime/days/Monday`)
     /// 3. **Category tag**: `#diary/commits` (constant)
+    /// 4. **Entry category tag**: `#category/<name>`, only when [`category`](EntryDetails::category) is set
+    /// 5. **Language tag**: `#lang/<code>`, only when [`language`](EntryDetails::language) is set
     ///
     /// These tags are used in the Obsidian diary file's YAML frontmatter to enable:
     /// - Filtering commits by week number
@@ -305,7 +1126,8 @@ impl CommitSaver {
     ///
     /// # Returns
     ///
-    /// A vector of three strings containing formatted Obsidian tags
+    /// A vector of three strings containing formatted Obsidian tags, plus the
+    /// category and language tags when they are set
     ///
     /// # Examples
     ///
@@ -313,13 +1135,17 @@ impl CommitSaver {
     /// use rusty_commit_saver::CommitSaver;
     /// use chrono::{TimeZone, Utc};
     ///
-    /// let mut saver = CommitSaver {
+    /// let mut saver = CommitSaver::from(CommitEntry {
     ///     repository_url: "https://github.com/example/repo.git".to_string(),
-    ///     commit_branch_name: "main".to_string(),
-    ///     commit_hash: "abc123".to_string(),
-    ///     commit_msg: "feat: add feature".to_string(),
-    ///     commit_datetime: Utc.with_ymd_and_hms(2025, 1, 13, 10, 30, 0).unwrap(), // Monday
-    /// };
+    ///     branch: "main".to_string(),
+    ///     hash: "abc123".to_string(),
+    ///     message: "feat: add feature".to_string(),
+    ///     datetime: Utc.with_ymd_and_hms(2025, 1, 13, 10, 30, 0).unwrap(), // Monday
+    ///     author: None,
+    ///     author_email: None,
+    ///     stats: None,
+    ///     parents: Vec::new(),
+    /// });
     ///
     /// let tags = saver.prepare_frontmatter_tags();
     /// assert_eq!(tags.len(), 3);
@@ -329,15 +1155,39 @@ impl CommitSaver {
     /// ```
     pub fn prepare_frontmatter_tags(&mut self) -> Vec<String> {
         info!("[CommitSaver::prepare_frontmatter_tags()]: Preparing the frontmatter week number.");
-        let week_number = format!("#datetime/week/{:}", self.commit_datetime.format("%W"));
+        let week_number = format!("#datetime/week/{:}", self.local_datetime().format("%W"));
 
         info!("[CommitSaver::prepare_frontmatter_tags()]: Preparing the frontmatter week day.");
-        let week_day = format!("#datetime/days/{:}", self.commit_datetime.format("%A"));
+        let week_day = format!("#datetime/days/{:}", self.local_datetime().format("%A"));
 
         info!(
             "[CommitSaver::prepare_frontmatter_tags()]: Returing the formatted vector with the frontmatter tags week number and day."
         );
-        vec![week_number, week_day, "#diary/commits".to_string()]
+        let mut tags = vec![week_number, week_day, "#diary/commits".to_string()];
+        if let Some(category) = &self.details.category {
+            tags.push(category_tag(category));
+        }
+        if let Some(language) = &self.details.language {
+            tags.push(language_tag(language));
+        }
+        if let Some(branch_slug) = &self.details.branch_slug {
+            tags.push(branch_tag(branch_slug));
+        }
+        if self.details.needs_better_message {
+            tags.push(NEEDS_BETTER_MESSAGE_TAG.to_string());
+        }
+        if let Some(commit_size) = self.details.commit_size {
+            tags.push(commit_size.tag());
+        }
+        if let Some(conventional) = &self.details.conventional {
+            tags.push(commit_type_tag(&conventional.kind));
+        }
+        if let Some(trailer_tags) = &self.details.trailer_tags {
+            if trailer_tags.placement.in_frontmatter() {
+                tags.extend(trailer_tags.tags.iter().cloned());
+            }
+        }
+        tags
     }
 
     /// Constructs the full file path for a diary entry based on the commit timestamp.
@@ -362,6 +1212,23 @@ impl CommitSaver {
     /// - `%F` - ISO 8601 date (e.g., `2025-01-14.md`)
     /// - `%d` - Day of month (e.g., `14`)
     ///
+    /// # Placeholders
+    ///
+    /// Both the commit path and the date template may contain:
+    ///
+    /// - `{repo}` - Repository name derived from the remote URL (e.g., `rusty-commit-saver`)
+    /// - `{branch}` - Current branch name (e.g., `feature/login` becomes `feature-login`)
+    ///
+    /// Values are sanitized with [`sanitize_path_segment()`](crate::paths::sanitize_path_segment)
+    /// so each placeholder always expands to a single path segment and can never
+    /// traverse outside the commit directory.
+    ///
+    /// # Unicode Normalization
+    ///
+    /// The returned path is normalized to NFC with
+    /// [`normalize_nfc()`](crate::paths::normalize_nfc), so month names, repository
+    /// names and emoji folders resolve to the same directory on macOS and Linux.
+    ///
     /// # Panics
     ///
     /// Panics if:
@@ -375,13 +1242,17 @@ impl CommitSaver {
     /// use std::path::PathBuf;
     /// use chrono::{TimeZone, Utc};
     ///
-    /// let mut saver = CommitSaver {
+    /// let mut saver = CommitSaver::from(CommitEntry {
     ///     repository_url: "https://github.com/example/repo.git".to_string(),
-    ///     commit_branch_name: "main".to_string(),
-    ///     commit_hash: "abc123".to_string(),
-    ///     commit_msg: "feat: add feature".to_string(),
-    ///     commit_datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 30, 0).unwrap(),
-    /// };
+    ///     branch: "main".to_string(),
+    ///     hash: "abc123".to_string(),
+    ///     message: "feat: add feature".to_string(),
+    ///     datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 30, 0).unwrap(),
+    ///     author: None,
+    ///     author_email: None,
+    ///     stats: None,
+    ///     parents: Vec::new(),
+    /// });
     ///
     /// let path = saver.prepare_path_for_commit(
     ///     &PathBuf::from("Diaries/Commits"),
@@ -408,10 +1279,22 @@ impl CommitSaver {
         let paths_with_dates_and_file =
             self.prepare_date_for_commit_file(template_commit_date_path);
 
+        info!(
+            "[CommitSaver::prepare_path_for_commit()]: Expanding {{repo}}/{{branch}} placeholders."
+        );
+        let repository_name = repository_name_from_url(&self.entry.repository_url);
+        let commit_path =
+            expand_path_placeholders(&commit_path, &repository_name, &self.entry.branch);
+        let paths_with_dates_and_file = expand_path_placeholders(
+            &paths_with_dates_and_file,
+            &repository_name,
+            &self.entry.branch,
+        );
+
         info!(
             "[CommitSaver::prepare_path_for_commit()]: Returning the full String of the ComitPath and File."
         );
-        format!("/{commit_path:}/{paths_with_dates_and_file:}")
+        normalize_nfc(&format!("/{commit_path:}/{paths_with_dates_and_file:}"))
     }
 
     /// Formats the commit timestamp using a Chrono date format string.
@@ -458,28 +1341,113 @@ impl CommitSaver {
         );
         // %B	July	Full month name. Also accepts corresponding abbreviation in parsing.
         // %F	2001-07-08	Year-month-day format (ISO 8601). Same as %Y-%m-%d.
-        self.commit_datetime.format(path_format).to_string()
+        self.local_datetime().format(path_format).to_string()
     }
 
-    /// Appends the current commit as a table row to an Obsidian diary file.
-    ///
-    /// This method writes a formatted commit entry to the specified diary file in append mode.
-    /// The entry includes: current directory, timestamp, commit message, repository URL, branch, and commit hash.
-    ///
-    /// # Arguments
+    /// Renders the entry as [`append_entry_to_diary()`](Self::append_entry_to_diary)
+    /// would append it: a table row, a fenced YAML block, or a templated row.
     ///
-    /// * `wiki` - Path to the diary file where the commit entry should be appended
+    /// # Errors
     ///
-    /// # Returns
+    /// Returns an error if the [`folder()`](Self::folder) of the entry cannot
+    /// be determined.
+    pub fn render_entry(&self) -> Result<String, Box<dyn Error>> {
+        let path = self.folder()?;
+        Ok(self.prepare_commit_entry_as_string(&path))
+    }
+
+    /// Renders the entry as a row of `table`, the table the row is added to,
+    /// in the columns of its header rather than the configured
+    /// [`table_columns`](EntrySettings::table_columns), see
+    /// [`DiaryRenderer::render_row()`]. A day file started before `[table]
+    /// columns` changed keeps rows that line up with its header.
     ///
-    /// - `Ok(())` - Successfully appended the commit entry to the file
-    /// - `Err(Box<dyn Error>)` - If file operations fail (file doesn't exist, permission denied, etc.)
+    /// Without a table, or for entries that are not table rows, this is
+    /// [`render_entry()`](Self::render_entry).
     ///
     /// # Errors
     ///
-    /// Returns an error if:
-    /// - The diary file cannot be opened for appending
-    /// - The current working directory cannot be determined
+    /// Returns an error if the [`folder()`](Self::folder) of the entry cannot
+    /// be determined.
+    fn render_row_in(&self, table: Option<&DiaryTable>) -> Result<String, Box<dyn Error>> {
+        match table {
+            Some(table) if self.settings.entry_format == EntryFormat::Table => {
+                let path = self.folder()?;
+                Ok(self.renderer(&path).render_row(&self.entry, &table.headers))
+            }
+            _ => self.render_entry(),
+        }
+    }
+
+    /// Checks whether the commit already has an entry in a diary file.
+    ///
+    /// Looks for the commit hash in the `COMMIT HASH` cells of the diary table
+    /// and in the `commit_hash:` keys of YAML entries, so a file written in
+    /// either format is recognized. With [`EntryFormat::Template`] and
+    /// [`EntryFormat::Dataview`], any mention of the full hash counts, since
+    /// the rows are not read back.
+    /// A missing file, an empty hash, or a table without a `COMMIT HASH`
+    /// column never match. Invalid UTF-8 is read lossily, leaving malformed
+    /// files to the quarantine.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the diary file exists but cannot be read.
+    pub fn is_logged_in(&self, wiki: &Path) -> Result<bool, Box<dyn Error>> {
+        if self.entry.hash.is_empty() {
+            return Ok(false);
+        }
+        let content = match fs::read(wiki) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e.into()),
+        };
+
+        if matches!(
+            self.settings.entry_format,
+            EntryFormat::Template | EntryFormat::Dataview
+        ) && content.contains(&self.entry.hash)
+        {
+            return Ok(true);
+        }
+
+        let in_table = parse_diary_table(&content).is_some_and(|table| {
+            table
+                .rows
+                .iter()
+                .any(|row| table.cell(row, TableColumn::CommitHash) == Some(&self.entry.hash))
+        });
+        let yaml_line = format!(
+            "{:}: {:}",
+            TableColumn::CommitHash.key(),
+            yaml_quote(&self.entry.hash)
+        );
+        Ok(in_table || content.lines().any(|line| line.trim() == yaml_line))
+    }
+
+    /// Appends the current commit as a table row to an Obsidian diary file.
+    ///
+    /// This method writes a formatted commit entry to the specified diary file in append mode.
+    /// The entry includes: current directory, timestamp, commit message, repository URL, branch, and commit hash.
+    ///
+    /// When the file already holds a table, the row gets the columns of its
+    /// header, which may predate the configured `[table] columns`, so the
+    /// table stays well-formed; see [`DiaryRenderer::render_row()`].
+    ///
+    /// # Arguments
+    ///
+    /// * `wiki` - Path to the diary file where the commit entry should be appended
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(())` - Successfully appended the commit entry to the file
+    /// - `Err(Box<dyn Error>)` - If file operations fail (file doesn't exist, permission denied, etc.)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The diary file cannot be opened for appending
+    /// - The folder of the entry cannot be determined
     /// - File write operations fail (I/O error, permission denied)
     ///
     /// # Examples
@@ -497,11 +1465,13 @@ impl CommitSaver {
     /// }
     /// ```
     pub fn append_entry_to_diary(&mut self, wiki: &PathBuf) -> Result<(), Box<dyn Error>> {
-        info!("[CommitSaver::append_entry_to_diary()]: Getting current directory.");
-        let path = env::current_dir()?;
-
         info!("[CommitSaver::append_entry_to_diary()]: Preparing the commit_entry_as_string.");
-        let new_commit_str = self.prepare_commit_entry_as_string(&path);
+        let table = if self.settings.entry_format == EntryFormat::Table {
+            parse_diary_table(&fs::read_to_string(wiki)?)
+        } else {
+            None
+        };
+        let new_commit_str = self.render_row_in(table.as_ref())?;
 
         debug!("[CommitSaver::append_entry_to_diary()]: Commit String: {new_commit_str:}");
         debug!(
@@ -514,6 +1484,522 @@ impl CommitSaver {
 
         Ok(())
     }
+
+    /// Inserts the table row of this entry before the first row logged later
+    /// in the day, or adds it after the last row when there is none.
+    ///
+    /// Backfilled, cherry-picked and rebased commits are often older than
+    /// rows already in the diary, and several backfills into the same vault
+    /// interleave their rows; inserting by `TIME` keeps each table
+    /// chronological whatever the order the rows arrive in. Rows of the same
+    /// second keep their arrival order.
+    ///
+    /// The table is the one under `insert_under` (`[obsidian] insert_under`)
+    /// when set, see [`section_table()`]. A row with no later row goes to the
+    /// end of that table with
+    /// [`insert_entry_under_heading()`](Self::insert_entry_under_heading),
+    /// or to the end of the file with
+    /// [`append_entry_to_diary()`](Self::append_entry_to_diary) when
+    /// `insert_under` is `None`. Entries that are not table rows, and tables
+    /// without a `TIME` column, are added the same way.
+    ///
+    /// The caller must hold the [`DiaryLock`](crate::diary_lock::DiaryLock)
+    /// of the diary, as the file is read then rewritten.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the diary file cannot be read or written, or if
+    /// the folder of the entry cannot be determined.
+    pub fn insert_entry_in_diary(
+        &mut self,
+        wiki: &PathBuf,
+        insert_under: Option<&str>,
+    ) -> Result<(), Box<dyn Error>> {
+        let add_last = |commit_saver: &mut CommitSaver| match insert_under {
+            Some(heading) => commit_saver.insert_entry_under_heading(wiki, heading),
+            None => commit_saver.append_entry_to_diary(wiki),
+        };
+        if self.settings.entry_format != EntryFormat::Table {
+            return add_last(self);
+        }
+        let content = fs::read_to_string(wiki)?;
+        let time_format = self.settings.time_format_or_default();
+        let entry_time = row_time(
+            &self.local_datetime().format(time_format).to_string(),
+            time_format,
+        );
+        let table = section_table(&content, insert_under);
+        let later_row = table
+            .as_ref()
+            .zip(entry_time)
+            .and_then(|((start, table), entry_time)| {
+                table
+                    .rows
+                    .iter()
+                    .find(|row| {
+                        table
+                            .cell(row, TableColumn::Time)
+                            .and_then(|time| row_time(time, time_format))
+                            .is_some_and(|time| time > entry_time)
+                    })
+                    .map(|row| start + row.line_index)
+            });
+        let Some(later_row) = later_row else {
+            return add_last(self);
+        };
+
+        let new_row = self.render_row_in(table.as_ref().map(|(_, table)| table))?;
+        let updated = content
+            .split_inclusive('\n')
+            .enumerate()
+            .map(|(index, line)| {
+                if index == later_row {
+                    format!("{new_row:}{line:}")
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect::<String>();
+
+        info!(
+            "[CommitSaver::insert_entry_in_diary()]: Inserting the row of {:} before line {:} of: {:}",
+            self.entry.hash,
+            later_row + 1,
+            wiki.display()
+        );
+        write_atomically(wiki, updated)?;
+        Ok(())
+    }
+
+    /// Inserts the table row of this entry at the end of the commit table
+    /// under `heading` (`[obsidian] insert_under`), creating the heading and
+    /// the table header when the note lacks them, see
+    /// [`insert_row_under_heading()`]. Unlike
+    /// [`append_entry_to_diary()`](Self::append_entry_to_diary), content
+    /// after the table (other sections of a daily note) stays after it.
+    ///
+    /// Entries that are not table rows are appended.
+    ///
+    /// The caller must hold the [`DiaryLock`](crate::diary_lock::DiaryLock)
+    /// of the diary, as the file is read then rewritten.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the diary file cannot be read or written, or if
+    /// the folder of the entry cannot be determined.
+    pub fn insert_entry_under_heading(
+        &mut self,
+        wiki: &PathBuf,
+        heading: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        if self.settings.entry_format != EntryFormat::Table {
+            return self.append_entry_to_diary(wiki);
+        }
+        let content = fs::read_to_string(wiki)?;
+        let table = section_table(&content, Some(heading));
+        let new_row = self.render_row_in(table.as_ref().map(|(_, table)| table))?;
+        let table_header = self
+            .settings
+            .entry_format
+            .header(&self.settings.table_columns);
+
+        info!(
+            "[CommitSaver::insert_entry_under_heading()]: Inserting the row of {:} under '{heading:}' in: {:}",
+            self.entry.hash,
+            wiki.display()
+        );
+        write_atomically(
+            wiki,
+            insert_row_under_heading(&content, heading, &table_header, &new_row),
+        )?;
+        Ok(())
+    }
+
+    /// Replaces the table row of an amended commit with the row of this one.
+    ///
+    /// The row whose `COMMIT HASH` is `stale_hash` is rewritten in place, so
+    /// an amended commit keeps a single row at its original position. When
+    /// the table has a `BRANCH` column, the row must be on the same branch.
+    /// YAML entries are never replaced.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(true)` - The row was replaced
+    /// - `Ok(false)` - No row of `stale_hash` was found, nothing was written
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the diary file cannot be read or written, or if
+    /// the folder of the entry cannot be determined.
+    pub fn replace_entry_in_diary(
+        &mut self,
+        wiki: &Path,
+        stale_hash: &str,
+    ) -> Result<bool, Box<dyn Error>> {
+        if self.settings.entry_format != EntryFormat::Table {
+            return Ok(false);
+        }
+        let content = fs::read_to_string(wiki)?;
+        let Some(table) = parse_diary_table(&content) else {
+            return Ok(false);
+        };
+        let Some(stale_row) = table.rows.iter().find(|row| {
+            table.cell(row, TableColumn::CommitHash) == Some(stale_hash)
+                && table
+                    .cell(row, TableColumn::Branch)
+                    .is_none_or(|branch| branch == self.entry.branch)
+        }) else {
+            return Ok(false);
+        };
+
+        let new_row = self.render_row_in(Some(&table))?;
+        let updated = content
+            .split_inclusive('\n')
+            .enumerate()
+            .map(|(index, line)| {
+                if index == stale_row.line_index {
+                    let ending = &line[line.trim_end_matches(['\r', '\n']).len()..];
+                    format!("{:}{ending:}", new_row.trim_end_matches('\n'))
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect::<String>();
+
+        info!(
+            "[CommitSaver::replace_entry_in_diary()]: Replacing the row of {stale_hash:} in: {:}",
+            wiki.display()
+        );
+        write_atomically(wiki, updated)?;
+        Ok(true)
+    }
+
+    /// Shortens the commit message so the row fits in `max_row_length` characters.
+    ///
+    /// Huge generated messages (changelogs, squashed merges) make the diary
+    /// table unreadable. When the row rendered from the current directory is
+    /// too long, the message is cut and ends with a link to this entry's
+    /// section in the overflow note, see [`append_overflow_note()`](Self::append_overflow_note).
+    /// The cut never splits a `<br/>` or a `\|` escape. Rows without a
+    /// `COMMIT MESSAGE` column are left alone.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_row_length` - Maximum row length in characters, pipes included
+    /// * `overflow_note_name` - Note name the link points to (e.g. `2025-01-14.overflow`)
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Some(message))` - The full message that was cut from the row
+    /// - `Ok(None)` - The row already fits
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the [`folder()`](Self::folder) of the entry cannot
+    /// be determined.
+    pub fn fit_row_length(
+        &mut self,
+        max_row_length: usize,
+        overflow_note_name: &str,
+    ) -> Result<Option<String>, Box<dyn Error>> {
+        let path = self.folder()?;
+        let row_length = self
+            .prepare_commit_entry_as_string(&path)
+            .trim_end()
+            .chars()
+            .count();
+        if row_length <= max_row_length
+            || !self
+                .settings
+                .table_columns
+                .contains(&TableColumn::CommitMessage)
+        {
+            return Ok(None);
+        }
+
+        info!(
+            "[CommitSaver::fit_row_length()]: Row has {row_length:} characters, moving the message to: {overflow_note_name:}"
+        );
+        let link = format!(
+            " … [[{overflow_note_name:}#{:}\\|more]]",
+            self.overflow_heading()
+        );
+        let table_message = self.entry.table_message();
+        let keep = (table_message.chars().count() + max_row_length)
+            .saturating_sub(row_length + link.chars().count());
+        let full_message = std::mem::replace(
+            &mut self.entry.message,
+            plain_message(
+                format!("{:}{link:}", truncate_message(&table_message, keep)).trim_start(),
+            ),
+        );
+
+        Ok(Some(full_message))
+    }
+
+    /// Appends the full message of a shortened row to the per-day overflow note.
+    ///
+    /// Each message gets its own section, titled like the link written by
+    /// [`fit_row_length()`](Self::fit_row_length). The note is created when
+    /// missing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the overflow note cannot be opened or written.
+    pub fn append_overflow_note(
+        &self,
+        overflow_path: &Path,
+        full_message: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        info!(
+            "[CommitSaver::append_overflow_note()]: Writing the full message to: {:}",
+            overflow_path.display()
+        );
+        let mut file_ref = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(overflow_path)?;
+        write!(
+            file_ref,
+            "\n## {:}\n\n{full_message:}\n",
+            self.overflow_heading()
+        )?;
+
+        Ok(())
+    }
+
+    /// Returns the overflow note section title of this entry: time and short hash.
+    ///
+    /// Dashes replace the colons of the time, which Obsidian does not allow
+    /// in heading links.
+    fn overflow_heading(&self) -> String {
+        let time = self.local_datetime().format("%H-%M-%S");
+        let short_hash = self.entry.hash.chars().take(7).collect::<String>();
+
+        format!("{time:} {short_hash:}").trim_end().to_string()
+    }
+}
+
+/// Returns the per-day overflow note next to a diary file.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::vim_commit::overflow_note_path;
+/// use std::path::Path;
+///
+/// assert_eq!(
+///     overflow_note_path(Path::new("/vault/2025-01-14.md")),
+///     Path::new("/vault/2025-01-14.overflow.md")
+/// );
+/// ```
+#[must_use]
+pub fn overflow_note_path(diary_path: &Path) -> PathBuf {
+    let stem = diary_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    diary_path.with_file_name(format!("{stem:}.overflow.md"))
+}
+
+/// Cuts a formatted commit message to at most `max_chars` characters.
+///
+/// A trailing partial `<br/>` or a dangling `\` escape is dropped as well, so
+/// the cut message still renders correctly in the table.
+fn truncate_message(message: &str, max_chars: usize) -> String {
+    let mut truncated = message.chars().take(max_chars).collect::<String>();
+
+    if let Some(tag_start) = truncated.rfind('<') {
+        if !truncated[tag_start..].contains('>') {
+            truncated.truncate(tag_start);
+        }
+    }
+    let trailing_backslashes = truncated.chars().rev().take_while(|c| *c == '\\').count();
+    if trailing_backslashes % 2 == 1 {
+        truncated.pop();
+    }
+    while truncated.ends_with("<br/>") {
+        truncated.truncate(truncated.len() - "<br/>".len());
+    }
+
+    truncated.trim_end().to_string()
+}
+
+/// Reads a `TIME` cell written with `time_format`, so cells compare in time
+/// order whatever the format (`9:15 AM` comes before `10:30 AM`).
+///
+/// The date is read too when the format has one (weekly diary files), and
+/// what follows the time, the `#02` same-second suffix or the time zone, is
+/// ignored.
+///
+/// # Returns
+///
+/// `None` if the cell does not start with a time in `time_format`.
+fn row_time(cell: &str, time_format: &str) -> Option<(Option<NaiveDate>, NaiveTime)> {
+    let cell = cell.trim();
+    if let Ok((datetime, _)) = NaiveDateTime::parse_and_remainder(cell, time_format) {
+        return Some((Some(datetime.date()), datetime.time()));
+    }
+    NaiveTime::parse_and_remainder(cell, time_format)
+        .ok()
+        .map(|(time, _)| (None, time))
+}
+
+/// Formats a duration as hours and minutes for the `DURATION` column.
+///
+/// Seconds are dropped; durations under one hour only show minutes.
+///
+/// # Examples
+///
+/// ```ignore
+/// use chrono::TimeDelta;
+/// use rusty_commit_saver::vim_commit::format_duration;
+///
+/// assert_eq!(format_duration(TimeDelta::minutes(45)), "45m");
+/// assert_eq!(format_duration(TimeDelta::minutes(125)), "2h 05m");
+/// ```
+#[must_use]
+pub fn format_duration(duration: TimeDelta) -> String {
+    let hours = duration.num_hours();
+    let minutes = duration.num_minutes() % 60;
+
+    if hours > 0 {
+        format!("{hours:}h {minutes:02}m")
+    } else {
+        format!("{minutes:}m")
+    }
+}
+
+/// Returns the frontmatter tag of an entry category, e.g. `#category/work`.
+#[must_use]
+pub fn category_tag(category: &str) -> String {
+    format!("#category/{category:}")
+}
+
+/// Returns the `origin` remote URL of the repository, or `no_url_set`.
+///
+/// Double quotes are stripped so the value is safe inside the diary table.
+pub(crate) fn origin_url(git_repo: &Repository) -> String {
+    match git_repo.find_remote("origin") {
+        Ok(remote) => remote.url().unwrap_or("no_url_set").replace('"', ""),
+        _ => "no_url_set".to_string(),
+    }
+}
+
+/// Returns the short name of the branch `HEAD` points to,
+/// [`DETACHED_BRANCH_NAME`] for a detached `HEAD`, or `no_branch_set`.
+///
+/// Unlike [`CommitSaver::from_repo`], this also works on unborn branches and
+/// never fails, which suits rows that do not describe a commit, and batch
+/// saves that log several commits on the current branch.
+#[must_use]
+pub fn head_branch_name(git_repo: &Repository) -> String {
+    if git_repo.head_detached().unwrap_or(false) {
+        return DETACHED_BRANCH_NAME.to_string();
+    }
+    git_repo
+        .head()
+        .ok()
+        .as_ref()
+        .and_then(|h| h.shorthand().ok())
+        .unwrap_or("no_branch_set")
+        .replace('"', "")
+}
+
+/// Extracts the previous and new ref names from a `HEAD` reflog message.
+///
+/// Git writes `checkout: moving from <previous> to <new>` for branch switches;
+/// any other message (rebase, reset, commit, …) yields `None`.
+///
+/// # Examples
+///
+/// ```ignore
+/// assert_eq!(
+///     parse_checkout_reflog_message("checkout: moving from main to feature/x"),
+///     Some(("main", "feature/x"))
+/// );
+/// assert_eq!(parse_checkout_reflog_message("rebase (start): checkout main"), None);
+/// ```
+fn parse_checkout_reflog_message(message: &str) -> Option<(&str, &str)> {
+    message
+        .trim()
+        .strip_prefix("checkout: moving from ")?
+        .split_once(" to ")
+}
+
+/// Summarizes uncommitted changes in the working tree, `git stash`-style.
+///
+/// Counts modified, new, deleted and renamed paths (staged or not, ignored
+/// files excluded) and lists the first few file names.
+///
+/// # Returns
+///
+/// - `Ok(Some(summary))` - e.g. `2 modified, 1 new: src/lib.rs, src/main.rs, notes.md`
+/// - `Ok(None)` - The working tree is clean
+///
+/// # Errors
+///
+/// Returns an error if the repository status cannot be read (e.g. bare repository).
+fn summarize_working_tree(git_repo: &Repository) -> Result<Option<String>, Box<dyn Error>> {
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false);
+    let statuses = git_repo.statuses(Some(&mut options))?;
+
+    let (mut modified, mut new, mut deleted, mut renamed) = (0, 0, 0, 0);
+    let mut files = Vec::new();
+
+    for entry in statuses.iter() {
+        let status = entry.status();
+        if status.intersects(Status::WT_NEW | Status::INDEX_NEW) {
+            new += 1;
+        } else if status.intersects(Status::WT_DELETED | Status::INDEX_DELETED) {
+            deleted += 1;
+        } else if status.intersects(Status::WT_RENAMED | Status::INDEX_RENAMED) {
+            renamed += 1;
+        } else if status.intersects(
+            Status::WT_MODIFIED
+                | Status::INDEX_MODIFIED
+                | Status::WT_TYPECHANGE
+                | Status::INDEX_TYPECHANGE,
+        ) {
+            modified += 1;
+        } else {
+            continue;
+        }
+        files.push(entry.path().unwrap_or("?").to_string());
+    }
+
+    if files.is_empty() {
+        return Ok(None);
+    }
+
+    let counts = [
+        (modified, "modified"),
+        (new, "new"),
+        (deleted, "deleted"),
+        (renamed, "renamed"),
+    ]
+    .iter()
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, label)| format!("{count:} {label:}"))
+    .collect::<Vec<_>>()
+    .join(", ");
+
+    let mut listed = files
+        .iter()
+        .take(WIP_MAX_LISTED_FILES)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if files.len() > WIP_MAX_LISTED_FILES {
+        listed.push_str(", …");
+    }
+
+    Ok(Some(format!("{counts:}: {listed:}")))
 }
 
 // Markup template for generating Obsidian diary file structure.
@@ -525,7 +2011,7 @@ impl CommitSaver {
 //
 // Used internally by create_diary_file().
 markup::define! {
-    DiaryFileEntry(frontmatter: Vec<String>, diary_date: String) {
+    DiaryFileEntry(frontmatter: Vec<String>, categories: Vec<String>, timezone: Option<String>, diary_date: String, table_header: String) {
 "---
 category: diary\n
 section: commits\n
@@ -533,15 +2019,77 @@ tags:\n"
 @for tag in frontmatter.iter() {
 "- '" @tag "'\n"
 }
+@if !categories.is_empty() {
+"categories:\n"
+@for category in categories.iter() {
+"- '" @category "'\n"
+}
+}
+@if let Some(timezone) = timezone {
+"timezone: '" @timezone "'\n"
+}
 "date: " @diary_date
 "\n
 ---
 \n
 # " @diary_date
 "\n
-| FOLDER | TIME | COMMIT MESSAGE | REPOSITORY URL | BRANCH | COMMIT HASH |
-|--------|------|----------------|----------------|--------|-------------|\n"
+" @table_header
+    }
+}
+
+/// Quotes a value as a double-quoted YAML string.
+///
+/// Backslashes, double quotes and control characters are escaped, so any
+/// commit message stays a single valid scalar.
+pub(crate) fn yaml_quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for character in value.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            character if character.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", u32::from(character));
+            }
+            character => quoted.push(character),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Renders the Markdown table header (title row and separator row) for the given columns.
+///
+/// Each separator cell is as wide as its padded title, matching the layout of
+/// the original hand-written header.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::vim_commit::{render_table_header, TableColumn};
+///
+/// let header = render_table_header(&[TableColumn::Time, TableColumn::Event]);
+/// assert_eq!(header, "| TIME | EVENT |\n|------|-------|\n");
+/// ```
+#[must_use]
+pub fn render_table_header(columns: &[TableColumn]) -> String {
+    let mut titles = String::from("|");
+    let mut separators = String::from("|");
+
+    for column in columns {
+        titles.push(' ');
+        titles.push_str(column.header());
+        titles.push_str(" |");
+
+        separators.push_str(&"-".repeat(column.header().chars().count() + 2));
+        separators.push('|');
     }
+
+    format!("{titles:}\n{separators:}\n")
 }
 
 /// Extracts the parent directory from a file path.
@@ -722,6 +2270,10 @@ pub fn create_directories_for_new_entry(
 /// - '#datetime/week/02'
 /// - '#datetime/days/Monday'
 /// - '#diary/commits'
+/// - '#category/work'
+/// categories:
+/// - 'work'
+/// timezone: 'CET'
 /// date: 2025-01-14
 /// ---
 ///
@@ -761,13 +2313,17 @@ pub fn create_directories_for_new_entry(
 /// use chrono::{TimeZone, Utc};
 /// use std::fs;
 ///
-/// let mut saver = CommitSaver {
+/// let mut saver = CommitSaver::from(CommitEntry {
 ///     repository_url: "https://github.com/example/repo.git".to_string(),
-///     commit_branch_name: "main".to_string(),
-///     commit_hash: "abc123def456".to_string(),
-///     commit_msg: "feat: implement feature".to_string(),
-///     commit_datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 30, 0).unwrap(),
-/// };
+///     branch: "main".to_string(),
+///     hash: "abc123def456".to_string(),
+///     message: "feat: implement feature".to_string(),
+///     datetime: Utc.with_ymd_and_hms(2025, 1, 14, 10, 30, 0).unwrap(),
+///     author: None,
+///     author_email: None,
+///     stats: None,
+///     parents: Vec::new(),
+/// });
 ///
 /// let file_path = "/home/user/diary/2025-01-14.md";
 /// create_diary_file(file_path, &mut saver).unwrap();
@@ -782,26 +2338,52 @@ pub fn create_diary_file(
     full_diary_file_path: &str,
     commit_saver_struct: &mut CommitSaver,
 ) -> Result<(), Box<dyn Error>> {
-    info!("[create_diary_file()]: Retrieving the frontmatter tags.");
+    let template = render_diary_file(commit_saver_struct);
+
+    info!("[create_diary_file()]: Writing the DiaryFileEntry.");
+    write_atomically(full_diary_file_path, template)?;
+
+    Ok(())
+}
+
+/// Renders the built-in diary file template that [`create_diary_file()`]
+/// writes: frontmatter, day heading and the empty table (or entry) header.
+#[must_use]
+pub fn render_diary_file(commit_saver_struct: &mut CommitSaver) -> String {
+    info!("[render_diary_file()]: Retrieving the frontmatter tags.");
     let frontmatter = commit_saver_struct.prepare_frontmatter_tags();
 
-    info!("[create_diary_file()]: Retrieving the date for commit.");
+    info!("[render_diary_file()]: Retrieving the date for commit.");
     let diary_date = commit_saver_struct
-        .commit_datetime
+        .local_datetime()
         .format("%Y-%m-%d")
         .to_string();
 
-    info!("[create_diary_file()]: Creating the DiaryFileEntry.");
-    let template = DiaryFileEntry {
+    info!("[render_diary_file()]: Rendering the table header.");
+    let table_header = commit_saver_struct
+        .settings
+        .entry_format
+        .header(&commit_saver_struct.settings.table_columns);
+
+    info!("[render_diary_file()]: Creating the DiaryFileEntry.");
+    DiaryFileEntry {
         frontmatter,
+        categories: commit_saver_struct
+            .details
+            .category
+            .iter()
+            .cloned()
+            .collect(),
+        timezone: commit_saver_struct
+            .details
+            .timezone
+            .as_ref()
+            .map(|timezone| timezone.label.clone())
+            .filter(|label| !label.is_empty()),
         diary_date,
+        table_header,
     }
-    .to_string();
-
-    info!("[create_diary_file()]: Writing the DiaryFileEntry.");
-    fs::write(full_diary_file_path, template)?;
-
-    Ok(())
+    .to_string()
 }
 
 // CommitSaver tests
@@ -809,6 +2391,13 @@ pub fn create_diary_file(
 #[cfg_attr(coverage_nightly, coverage(off))]
 mod commit_saver_tests {
     use super::*;
+    use crate::entry_id::EntryIdStyle;
+    use crate::forge::ForgeResolver;
+    use crate::forge::ForgeTemplates;
+    use crate::testing::FakeRepo;
+    use crate::trailers::TagPlacement;
+    use crate::wsl::FolderStyle;
+    use crate::wsl::WslPaths;
     use chrono::{TimeZone, Utc};
     use std::fs;
     use std::fs::File;
@@ -816,31 +2405,57 @@ mod commit_saver_tests {
     use tempfile::tempdir;
 
     fn create_test_commit_saver() -> CommitSaver {
-        CommitSaver {
+        CommitSaver::from(CommitEntry {
             repository_url: "https://github.com/test/repo.git".to_string(),
-            commit_branch_name: "main".to_string(),
-            commit_hash: "abc123def456".to_string(),
-            commit_msg: "Test commit message".to_string(),
-            commit_datetime: Utc.with_ymd_and_hms(2023, 12, 25, 10, 30, 0).unwrap(),
-        }
+            branch: "main".to_string(),
+            hash: "abc123def456".to_string(),
+            message: "Test commit message".to_string(),
+            datetime: Utc.with_ymd_and_hms(2023, 12, 25, 10, 30, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        })
     }
 
     #[test]
-    fn test_commit_saver_new() {
-        // This test requires being in a git repository
-        // We'll mock the behavior or skip if not in a git repo
-        if Repository::discover("./").is_ok() {
-            let commit_saver = CommitSaver::new();
+    fn test_commit_saver_new() -> Result<(), Box<dyn std::error::Error>> {
+        let repo = FakeRepo::new()?.with_origin("https://github.com/user/repo.git")?;
+        repo.commit("Initial commit")?;
 
-            assert!(!commit_saver.repository_url.is_empty());
-            assert!(!commit_saver.commit_branch_name.is_empty());
-            assert!(!commit_saver.commit_hash.is_empty());
-        }
+        let commit_saver = repo.commit_saver()?;
+
+        assert_eq!(
+            commit_saver.entry.repository_url,
+            "https://github.com/user/repo.git"
+        );
+        assert!(!commit_saver.entry.branch.is_empty());
+        assert!(!commit_saver.entry.hash.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_repo_writes_the_repository_folder() -> Result<(), Box<dyn std::error::Error>> {
+        let repo = FakeRepo::new()?;
+        repo.commit("Initial commit")?;
+
+        let commit_saver = repo.commit_saver()?;
+
+        let workdir: PathBuf = repo.path().components().collect();
+        assert_eq!(
+            commit_saver.details.git_dir.as_deref(),
+            Some(repo.repo().path())
+        );
+        assert_eq!(commit_saver.folder()?, workdir);
+        assert!(commit_saver
+            .render_entry()?
+            .contains(&format!("| {:} |", workdir.display())));
+        Ok(())
     }
 
     #[test]
     fn test_prepare_commit_entry_as_string() {
-        let mut commit_saver = create_test_commit_saver();
+        let commit_saver = create_test_commit_saver();
         let test_path = PathBuf::from("/test/path");
 
         let result = commit_saver.prepare_commit_entry_as_string(&test_path);
@@ -856,19 +2471,23 @@ mod commit_saver_tests {
 
     #[test]
     fn test_prepare_commit_entry_with_pipe_escaping() {
-        let mut commit_saver = CommitSaver {
+        let commit_saver = CommitSaver::from(CommitEntry {
             repository_url: "https://github.com/test/repo.git".to_string(),
-            commit_branch_name: "main".to_string(),
-            commit_hash: "abc123def456".to_string(),
-            commit_msg: "Test | commit | with | pipes".to_string(),
-            commit_datetime: Utc.with_ymd_and_hms(2023, 12, 25, 10, 30, 0).unwrap(),
-        };
+            branch: "main".to_string(),
+            hash: "abc123def456".to_string(),
+            message: "Test | commit | with | pipes".to_string(),
+            datetime: Utc.with_ymd_and_hms(2023, 12, 25, 10, 30, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        });
         let test_path = PathBuf::from("/test/path");
 
         let result = commit_saver.prepare_commit_entry_as_string(&test_path);
 
         // The commit message should have pipes escaped
-        assert!(result.contains("Test | commit | with | pipes"));
+        assert!(result.contains("Test \\| commit \\| with \\| pipes"));
     }
 
     #[test]
@@ -880,6 +2499,31 @@ mod commit_saver_tests {
         assert_eq!(tags.len(), 3);
         assert!(tags.contains(&"#datetime/days/Monday".to_string()));
         assert!(tags.contains(&"#diary/commits".to_string()));
+
+        commit_saver.details.category = Some("work".to_string());
+        commit_saver.details.language = Some("deu".to_string());
+        let tags = commit_saver.prepare_frontmatter_tags();
+        assert_eq!(tags[3..], ["#category/work", "#lang/deu"]);
+    }
+
+    #[test]
+    fn test_insert_entry_under_heading() -> Result<(), Box<dyn std::error::Error>> {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.settings.table_columns = vec![TableColumn::Time, TableColumn::CommitHash];
+        let temp_dir = tempdir()?;
+        let file_path = temp_dir.path().join("2025-01-13.md");
+        fs::write(
+            &file_path,
+            "# Monday\n\n## Commits\n\n| TIME | COMMIT HASH |\n|---|---|\n| 09:00:00 | fff000 |\n\n## Notes\nGym.\n",
+        )?;
+
+        commit_saver.insert_entry_under_heading(&file_path, "## Commits")?;
+
+        assert_eq!(
+            fs::read_to_string(&file_path)?,
+            "# Monday\n\n## Commits\n\n| TIME | COMMIT HASH |\n|---|---|\n| 09:00:00 | fff000 |\n| 10:30:00 | abc123def456 |\n\n## Notes\nGym.\n"
+        );
+        Ok(())
     }
 
     #[test]
@@ -904,14 +2548,229 @@ mod commit_saver_tests {
     }
 
     #[test]
-    fn test_append_entry_to_diary_file_not_exists() {
+    fn test_append_entry_to_diary_follows_the_existing_header(
+    ) -> Result<(), Box<dyn std::error::Error>> {
         let mut commit_saver = create_test_commit_saver();
-        let non_existent_path = PathBuf::from("/non/existent/file.md");
+        commit_saver.settings.table_columns = vec![TableColumn::Time, TableColumn::CommitHash];
+        let temp_dir = tempdir()?;
+        let file_path = temp_dir.path().join("2023-12-25.md");
+        let old_table = "| TIME | COMMIT MESSAGE | BRANCH | COMMIT HASH | MOOD |\n|---|---|---|---|---|\n| 09:00:00 | Earlier | main | early111 | 🙂 |\n";
+        fs::write(&file_path, old_table)?;
 
-        let result = commit_saver.append_entry_to_diary(&non_existent_path);
+        commit_saver.append_entry_to_diary(&file_path)?;
 
-        assert!(result.is_err());
-    }
+        assert_eq!(
+            fs::read_to_string(&file_path)?,
+            format!("{old_table:}| 10:30:00 | Test commit message | main | abc123def456 |  |\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_logged_in_finds_table_and_yaml_entries() -> Result<(), Box<dyn std::error::Error>> {
+        let mut commit_saver = create_test_commit_saver();
+        let temp_dir = tempdir()?;
+        let table_path = temp_dir.path().join("table.md");
+        let yaml_path = temp_dir.path().join("yaml.md");
+        fs::write(
+            &table_path,
+            "| TIME | COMMIT HASH |\n|---|---|\n| 10:00:00 | abc123def456 |\n",
+        )?;
+        fs::write(
+            &yaml_path,
+            "```yaml\ncommit_hash: \"abc123def456\"\n```\n\nabc123def4567\n",
+        )?;
+
+        assert!(commit_saver.is_logged_in(&table_path)?);
+        assert!(commit_saver.is_logged_in(&yaml_path)?);
+        assert!(!commit_saver.is_logged_in(&temp_dir.path().join("missing.md"))?);
+
+        commit_saver.entry.hash = "abc123def4567".to_string();
+        assert!(!commit_saver.is_logged_in(&table_path)?);
+        assert!(!commit_saver.is_logged_in(&yaml_path)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_entry_in_diary_rewrites_the_stale_row() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.settings.table_columns = vec![
+            TableColumn::CommitMessage,
+            TableColumn::Branch,
+            TableColumn::CommitHash,
+        ];
+        let temp_dir = tempdir()?;
+        let file_path = temp_dir.path().join("diary.md");
+        fs::write(
+            &file_path,
+            "| COMMIT MESSAGE | BRANCH | COMMIT HASH |\r\n|---|---|---|\r\n| typo | main | old111 |\r\n| later | main | next222 |\r\n",
+        )?;
+
+        assert!(!commit_saver.replace_entry_in_diary(&file_path, "missing")?);
+        assert!(commit_saver.replace_entry_in_diary(&file_path, "old111")?);
+
+        assert_eq!(
+            fs::read_to_string(&file_path)?,
+            "| COMMIT MESSAGE | BRANCH | COMMIT HASH |\r\n|---|---|---|\r\n| Test commit message | main | abc123def456 |\r\n| later | main | next222 |\r\n"
+        );
+
+        commit_saver.entry.branch = "other".to_string();
+        assert!(!commit_saver.replace_entry_in_diary(&file_path, "abc123def456")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_insert_entry_in_diary_keeps_rows_in_time_order(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.settings.table_columns = vec![TableColumn::Time, TableColumn::CommitHash];
+        let temp_dir = tempdir()?;
+        let file_path = temp_dir.path().join("diary.md");
+        fs::write(
+            &file_path,
+            "| TIME | COMMIT HASH |\n|---|---|\n| 09:00:00 | early111 |\n| 10:30:00 #02 | same222 |\n| 11:00:00 | late333 |\n",
+        )?;
+
+        commit_saver.insert_entry_in_diary(&file_path, None)?;
+        commit_saver.entry.datetime = Utc.with_ymd_and_hms(2023, 12, 25, 12, 0, 0).unwrap();
+        commit_saver.entry.hash = "last444".to_string();
+        commit_saver.insert_entry_in_diary(&file_path, None)?;
+
+        assert_eq!(
+            fs::read_to_string(&file_path)?,
+            "| TIME | COMMIT HASH |\n|---|---|\n| 09:00:00 | early111 |\n| 10:30:00 #02 | same222 |\n| 10:30:00 | abc123def456 |\n| 11:00:00 | late333 |\n| 12:00:00 | last444 |\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_entry_time_uses_the_time_format() -> Result<(), Box<dyn std::error::Error>> {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.settings.table_columns = vec![TableColumn::Time, TableColumn::CommitHash];
+        commit_saver.settings.time_format = Some("%Y-%m-%d %H:%M".to_string());
+        assert_eq!(commit_saver.entry_time(), "2023-12-25 10:30");
+
+        let temp_dir = tempdir()?;
+        let file_path = temp_dir.path().join("2023-W52.md");
+        fs::write(
+            &file_path,
+            "| TIME | COMMIT HASH |\n|---|---|\n| 2023-12-24 18:00 | early111 |\n| 2023-12-25 09:00 CET | same222 |\n| 2023-12-26 08:00 | late333 |\n",
+        )?;
+        commit_saver.insert_entry_in_diary(&file_path, None)?;
+
+        assert_eq!(
+            fs::read_to_string(&file_path)?,
+            "| TIME | COMMIT HASH |\n|---|---|\n| 2023-12-24 18:00 | early111 |\n| 2023-12-25 09:00 CET | same222 |\n| 2023-12-25 10:30 | abc123def456 |\n| 2023-12-26 08:00 | late333 |\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_insert_entry_in_diary_parses_twelve_hour_times(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.settings.table_columns = vec![TableColumn::Time, TableColumn::CommitHash];
+        commit_saver.settings.time_format = Some("%-I:%M %p".to_string());
+        let temp_dir = tempdir()?;
+        let file_path = temp_dir.path().join("2023-12-25.md");
+        fs::write(
+            &file_path,
+            "| TIME | COMMIT HASH |\n|---|---|\n| 9:15 AM | early111 |\n| 1:00 PM #02 | late222 |\n",
+        )?;
+
+        commit_saver.insert_entry_in_diary(&file_path, None)?;
+
+        assert_eq!(
+            fs::read_to_string(&file_path)?,
+            "| TIME | COMMIT HASH |\n|---|---|\n| 9:15 AM | early111 |\n| 10:30 AM | abc123def456 |\n| 1:00 PM #02 | late222 |\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_insert_entry_in_diary_under_heading() -> Result<(), Box<dyn std::error::Error>> {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.settings.table_columns = vec![TableColumn::Time, TableColumn::CommitHash];
+        let temp_dir = tempdir()?;
+        let file_path = temp_dir.path().join("2025-01-13.md");
+        fs::write(
+            &file_path,
+            "| TODO |\n|---|\n| 11:00:00 |\n\n## Commits\n\n| TIME | COMMIT HASH |\n|---|---|\n| 09:00:00 | early111 |\n| 11:00:00 | late333 |\n\n## Notes\n",
+        )?;
+
+        commit_saver.insert_entry_in_diary(&file_path, Some("## Commits"))?;
+        commit_saver.entry.datetime = Utc.with_ymd_and_hms(2023, 12, 25, 12, 0, 0).unwrap();
+        commit_saver.entry.hash = "last444".to_string();
+        commit_saver.insert_entry_in_diary(&file_path, Some("## Commits"))?;
+
+        assert_eq!(
+            fs::read_to_string(&file_path)?,
+            "| TODO |\n|---|\n| 11:00:00 |\n\n## Commits\n\n| TIME | COMMIT HASH |\n|---|---|\n| 09:00:00 | early111 |\n| 10:30:00 | abc123def456 |\n| 11:00:00 | late333 |\n| 12:00:00 | last444 |\n\n## Notes\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_fit_row_length_moves_long_message_to_overflow() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.entry.message = format!("feat: huge\n{:}", "x".repeat(2000));
+        let full_message = commit_saver.entry.message.clone();
+
+        let cut = commit_saver.fit_row_length(400, "2023-12-25.overflow")?;
+
+        assert_eq!(cut, Some(full_message));
+        assert!(commit_saver.entry.message.starts_with("feat: huge\nxxx"));
+        assert!(commit_saver
+            .entry
+            .message
+            .ends_with(" … [[2023-12-25.overflow#10-30-00 abc123d|more]]"));
+        let row = commit_saver.prepare_commit_entry_as_string(&env::current_dir()?);
+        assert_eq!(row.trim_end().chars().count(), 400);
+
+        assert_eq!(
+            commit_saver.fit_row_length(400, "2023-12-25.overflow")?,
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_truncate_message_keeps_escapes_whole() {
+        assert_eq!(truncate_message("one<br/>two", 5), "one");
+        assert_eq!(truncate_message("a \\| b", 3), "a");
+        assert_eq!(truncate_message("short", 50), "short");
+    }
+
+    #[test]
+    fn test_append_overflow_note() -> Result<(), Box<dyn std::error::Error>> {
+        let commit_saver = create_test_commit_saver();
+        let temp_dir = tempdir()?;
+        let overflow_path = overflow_note_path(&temp_dir.path().join("2023-12-25.md"));
+
+        commit_saver.append_overflow_note(&overflow_path, "line | one\nline two")?;
+
+        assert_eq!(
+            overflow_path.file_name().unwrap().to_str(),
+            Some("2023-12-25.overflow.md")
+        );
+        assert_eq!(
+            fs::read_to_string(&overflow_path)?,
+            "\n## 10-30-00 abc123d\n\nline | one\nline two\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_append_entry_to_diary_file_not_exists() {
+        let mut commit_saver = create_test_commit_saver();
+        let non_existent_path = PathBuf::from("/non/existent/file.md");
+
+        let result = commit_saver.append_entry_to_diary(&non_existent_path);
+
+        assert!(result.is_err());
+    }
 
     #[test]
     fn test_prepare_path_for_commit_integration() {
@@ -931,6 +2790,27 @@ mod commit_saver_tests {
             .is_some_and(|ext| ext.eq_ignore_ascii_case("md")));
     }
 
+    #[test]
+    fn test_prepare_path_for_commit_expands_placeholders() {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.entry.branch = "feature/../../login".to_string();
+        let obsidian_path = PathBuf::from("Commits/{repo}");
+
+        let result = commit_saver.prepare_path_for_commit(&obsidian_path, "{branch}/%F.md");
+
+        assert_eq!(result, "/Commits/repo/feature-..-..-login/2023-12-25.md");
+    }
+
+    #[test]
+    fn test_prepare_path_for_commit_hostile_branch_is_single_segment() {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.entry.branch = "../../..".to_string();
+
+        let result = commit_saver.prepare_path_for_commit(&PathBuf::from("Commits"), "{branch}.md");
+
+        assert_eq!(result, "/Commits/_.md");
+    }
+
     #[test]
     fn test_create_diary_file_error_handling() {
         let mut commit_saver = create_test_commit_saver();
@@ -959,22 +2839,25 @@ mod commit_saver_tests {
     }
 
     #[test]
-    fn test_commit_saver_default_in_git_repo() {
-        use git2::Repository;
+    fn test_commit_saver_default_in_git_repo() -> Result<(), Box<dyn std::error::Error>> {
+        let repo = FakeRepo::new()?.with_origin("git@github.com:user/repo.git")?;
+        repo.commit("Initial commit")?;
+        repo.checkout_new_branch("feature/x")?;
+        repo.commit("Second | commit")?;
 
-        // Only run if we're in a git repo
-        if Repository::discover("./").is_ok() {
-            let commit_saver = CommitSaver::default();
+        let commit_saver = repo.commit_saver()?;
 
-            // Verify all fields are populated
-            assert!(!commit_saver.repository_url.is_empty());
-            assert!(!commit_saver.commit_branch_name.is_empty());
-            assert!(!commit_saver.commit_hash.is_empty());
-            assert!(!commit_saver.commit_msg.is_empty());
+        // Verify all fields are populated
+        assert_eq!(
+            commit_saver.entry.repository_url,
+            "git@github.com:user/repo.git"
+        );
+        assert_eq!(commit_saver.entry.branch, "feature/x");
+        assert_eq!(commit_saver.entry.message, "Second | commit");
 
-            // Hash should be 40 characters (SHA-1)
-            assert_eq!(commit_saver.commit_hash.len(), 40);
-        }
+        // Hash should be 40 characters (SHA-1)
+        assert_eq!(commit_saver.entry.hash.len(), 40);
+        Ok(())
     }
 
     #[test]
@@ -991,16 +2874,20 @@ mod commit_saver_tests {
 
     #[test]
     fn test_commit_msg_with_only_whitespace_lines() {
-        let commit_saver = CommitSaver {
+        let commit_saver = CommitSaver::from(CommitEntry {
             repository_url: "test".to_string(),
-            commit_branch_name: "main".to_string(),
-            commit_hash: "abc123".to_string(),
-            commit_msg: "   \n\n   \n".to_string(), // Only whitespace
-            commit_datetime: Utc.with_ymd_and_hms(2023, 12, 25, 10, 30, 0).unwrap(),
-        };
+            branch: "main".to_string(),
+            hash: "abc123".to_string(),
+            message: "   \n\n   \n".to_string(),
+            datetime: Utc.with_ymd_and_hms(2023, 12, 25, 10, 30, 0).unwrap(),
+            author: None,
+            author_email: None,
+            stats: None,
+            parents: Vec::new(),
+        });
 
         // commit_msg should be empty or minimal after filtering
-        assert!(commit_saver.commit_msg.is_empty() || commit_saver.commit_msg.len() < 10);
+        assert!(commit_saver.entry.message.is_empty() || commit_saver.entry.message.len() < 10);
     }
 
     #[test]
@@ -1034,7 +2921,10 @@ mod commit_saver_tests {
 
         let markup = DiaryFileEntry {
             frontmatter,
+            categories: vec!["oss".to_string()],
+            timezone: None,
             diary_date,
+            table_header: render_table_header(&DEFAULT_TABLE_COLUMNS),
         };
 
         let output = markup.to_string();
@@ -1046,6 +2936,7 @@ mod commit_saver_tests {
         assert!(output.contains("#datetime/days/Saturday"));
         assert!(output.contains("2023-12-30"));
         assert!(output.contains("| FOLDER | TIME | COMMIT MESSAGE"));
+        assert!(output.contains("categories:\n- 'oss'\ndate: 2023-12-30"));
     }
 
     #[test]
@@ -1069,10 +2960,678 @@ mod commit_saver_tests {
         // The repo has no "origin" remote, so this exercises the `no_url_set` branch.
         let saver = CommitSaver::from_repo(&repo).expect("from_repo should succeed");
 
-        assert_eq!(saver.repository_url, "no_url_set");
+        assert_eq!(saver.entry.repository_url, "no_url_set");
         // Branch name depends on git config; just verify it's not empty
-        assert!(!saver.commit_branch_name.is_empty());
-        assert!(!saver.commit_hash.is_empty());
+        assert!(!saver.entry.branch.is_empty());
+        assert!(!saver.entry.hash.is_empty());
+    }
+
+    fn init_repo_with_commit(path: &std::path::Path) -> Repository {
+        use git2::Signature;
+
+        let repo = Repository::init(path).unwrap();
+        fs::write(path.join("tracked.txt"), "initial").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("tracked.txt")).unwrap();
+        index.write().unwrap();
+        let tree_id = index.write_tree().unwrap();
+        {
+            let tree = repo.find_tree(tree_id).unwrap();
+            let sig = Signature::now("Test User", "test@example.com").unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "Initial | commit", &tree, &[])
+                .unwrap();
+        }
+        repo
+    }
+
+    #[test]
+    fn test_wip_from_repo_clean_tree_errors() {
+        let temp_dir = tempdir().unwrap();
+        let repo = init_repo_with_commit(temp_dir.path());
+
+        let result = CommitSaver::wip_from_repo(&repo);
+
+        assert!(
+            result.is_err(),
+            "clean working tree should not produce a WIP row"
+        );
+    }
+
+    #[test]
+    fn test_wip_from_repo_summarizes_changes() {
+        let temp_dir = tempdir().unwrap();
+        let repo = init_repo_with_commit(temp_dir.path());
+        fs::write(temp_dir.path().join("tracked.txt"), "changed").unwrap();
+        fs::write(temp_dir.path().join("new|file.txt"), "new").unwrap();
+
+        let saver = CommitSaver::wip_from_repo(&repo).expect("dirty tree should produce WIP");
+
+        assert_eq!(saver.details.entry_kind, EntryKind::Wip);
+        assert_eq!(saver.entry.repository_url, "no_url_set");
+        assert_eq!(saver.entry.hash.len(), 40);
+        assert!(saver.entry.message.starts_with("🚧 WIP on "));
+        assert!(saver.entry.message.contains("Initial | commit"));
+        assert!(saver.entry.message.contains("1 modified, 1 new"));
+        assert!(saver.entry.message.contains("tracked.txt"));
+        assert!(saver.entry.message.contains("new|file.txt"));
+    }
+
+    #[test]
+    fn test_wip_from_repo_unborn_branch() {
+        let temp_dir = tempdir().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        fs::write(temp_dir.path().join("draft.md"), "draft").unwrap();
+
+        let saver = CommitSaver::wip_from_repo(&repo).expect("untracked file should produce WIP");
+
+        assert!(saver.entry.hash.is_empty());
+        assert!(saver.entry.message.contains("(no commits yet)"));
+        assert!(saver.entry.message.contains("1 new: draft.md"));
+    }
+
+    #[test]
+    fn test_summarize_working_tree_truncates_file_list() {
+        let temp_dir = tempdir().unwrap();
+        let repo = init_repo_with_commit(temp_dir.path());
+        for i in 0..8 {
+            fs::write(temp_dir.path().join(format!("file{i}.txt")), "x").unwrap();
+        }
+
+        let summary = summarize_working_tree(&repo).unwrap().unwrap();
+
+        assert!(summary.starts_with("8 new: "));
+        assert!(summary.ends_with(", …"));
+    }
+
+    #[test]
+    fn test_checkout_from_repo_records_branch_switch() {
+        let temp_dir = tempdir().unwrap();
+        let repo = init_repo_with_commit(temp_dir.path());
+        let head_commit = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("feature/login", &head_commit, false).unwrap();
+        repo.set_head("refs/heads/feature/login").unwrap();
+
+        let saver = CommitSaver::checkout_from_repo(&repo)
+            .unwrap()
+            .expect("switching branches should produce an event");
+
+        assert_eq!(saver.details.entry_kind, EntryKind::Checkout);
+        assert_eq!(saver.entry.branch, "feature/login");
+        assert_eq!(saver.entry.hash, head_commit.id().to_string());
+        assert!(saver.entry.message.starts_with("🔀 Switched from "));
+        assert!(saver.entry.message.ends_with(" to feature/login"));
+    }
+
+    #[test]
+    fn test_checkout_from_repo_ignores_other_head_moves() {
+        let temp_dir = tempdir().unwrap();
+        let repo = init_repo_with_commit(temp_dir.path());
+
+        // The last HEAD reflog entry is the initial commit, not a checkout.
+        assert!(CommitSaver::checkout_from_repo(&repo).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_parse_checkout_reflog_message() {
+        assert_eq!(
+            parse_checkout_reflog_message("checkout: moving from main to feature/x\n"),
+            Some(("main", "feature/x"))
+        );
+        assert_eq!(
+            parse_checkout_reflog_message("rebase (start): checkout main"),
+            None
+        );
+        assert_eq!(parse_checkout_reflog_message("commit: message"), None);
+    }
+
+    #[test]
+    fn test_stash_push_and_pop_from_repo() {
+        use git2::Signature;
+
+        let temp_dir = tempdir().unwrap();
+        let mut repo = init_repo_with_commit(temp_dir.path());
+        fs::write(temp_dir.path().join("tracked.txt"), "changed").unwrap();
+        let sig = Signature::now("Test User", "test@example.com").unwrap();
+        let stash_id = repo.stash_save(&sig, "half-done | refactor", None).unwrap();
+
+        let pushed = CommitSaver::stash_push_from_repo(&repo).unwrap();
+        assert_eq!(pushed.details.entry_kind, EntryKind::StashPush);
+        assert_eq!(pushed.entry.hash, stash_id.to_string());
+        assert!(pushed.entry.message.starts_with("📦 Stash push: "));
+        assert!(pushed.entry.message.contains("half-done | refactor"));
+
+        repo.stash_pop(0, None).unwrap();
+        let popped = CommitSaver::stash_pop_from_repo(&repo).unwrap();
+        assert_eq!(popped.details.entry_kind, EntryKind::StashPop);
+        assert_eq!(popped.entry.message, "📦 Stash pop (0 left)");
+    }
+
+    #[test]
+    fn test_stash_push_from_repo_without_stash_errors() {
+        let temp_dir = tempdir().unwrap();
+        let repo = init_repo_with_commit(temp_dir.path());
+
+        assert!(CommitSaver::stash_push_from_repo(&repo).is_err());
+    }
+
+    #[test]
+    fn test_render_table_header_default_columns() {
+        assert_eq!(
+            render_table_header(&DEFAULT_TABLE_COLUMNS),
+            "| FOLDER | TIME | COMMIT MESSAGE | REPOSITORY URL | BRANCH | COMMIT HASH |\n\
+             |--------|------|----------------|----------------|--------|-------------|\n"
+        );
+    }
+
+    #[test]
+    fn test_table_column_from_name() {
+        assert_eq!(
+            TableColumn::from_name("hash"),
+            Some(TableColumn::CommitHash)
+        );
+        assert_eq!(
+            TableColumn::from_name(" Commit Message "),
+            Some(TableColumn::CommitMessage)
+        );
+        assert_eq!(
+            TableColumn::from_name("branch-slug"),
+            Some(TableColumn::BranchSlug)
+        );
+        for column in DEFAULT_TABLE_COLUMNS {
+            assert_eq!(TableColumn::from_name(column.key()), Some(column));
+        }
+        assert_eq!(
+            TableColumn::from_name("Author Email"),
+            Some(TableColumn::AuthorEmail)
+        );
+        assert_eq!(TableColumn::from_name("committer"), None);
+    }
+
+    #[test]
+    fn test_from_repo_flags_merge_commits() -> Result<(), Box<dyn Error>> {
+        let repo = crate::testing::FakeRepo::new()?;
+        let base = repo.commit("Initial commit")?;
+        let default_branch = head_branch_name(repo.repo());
+        repo.checkout_new_branch("feature")?;
+        let feature = repo.commit("feat: add feature")?;
+        repo.checkout(&default_branch)?;
+        let head = repo.commit("fix: bug")?;
+
+        let mut commit_saver = repo.commit_saver()?;
+        assert!(!commit_saver.entry.is_merge());
+        assert_eq!(commit_saver.entry.parents, vec![base.to_string()]);
+
+        repo.merge("feature", "Merge branch 'feature'")?;
+        commit_saver = repo.commit_saver()?;
+        assert!(commit_saver.entry.is_merge());
+        assert_eq!(
+            commit_saver.entry.parents,
+            vec![head.to_string(), feature.to_string()]
+        );
+
+        commit_saver.settings.table_columns = vec![
+            TableColumn::CommitMessage,
+            TableColumn::Event,
+            TableColumn::Parents,
+        ];
+        assert_eq!(
+            commit_saver.prepare_commit_entry_as_string(Path::new("/test/path")),
+            format!(
+                "| {MERGE_MARKER:} Merge branch 'feature' | merge | {:} {:} |\n",
+                &head.to_string()[..7],
+                &feature.to_string()[..7]
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_prepare_commit_entry_with_stats_column() -> Result<(), Box<dyn Error>> {
+        let repo = crate::testing::FakeRepo::new()?;
+        repo.commit_file("notes.md", "one\ntwo\n", "docs: add notes")?;
+
+        let mut commit_saver = repo.commit_saver()?;
+        commit_saver.settings.table_columns = vec![TableColumn::CommitMessage, TableColumn::Stats];
+        assert_eq!(
+            commit_saver.prepare_commit_entry_as_string(Path::new("/test/path")),
+            "| docs: add notes | +2/\u{2212}0 (1 file) |\n"
+        );
+
+        commit_saver.entry.stats = None;
+        assert_eq!(
+            commit_saver.prepare_commit_entry_as_string(Path::new("/test/path")),
+            "| docs: add notes |  |\n"
+        );
+        assert_eq!(TableColumn::from_name("diff"), Some(TableColumn::Stats));
+        Ok(())
+    }
+
+    #[test]
+    fn test_commit_size_column_and_tag() {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.settings.table_columns = vec![TableColumn::CommitMessage, TableColumn::Size];
+        assert!(!commit_saver
+            .prepare_frontmatter_tags()
+            .iter()
+            .any(|tag| tag.starts_with("#size/")));
+
+        commit_saver.details.commit_size = Some(CommitSize::M);
+        assert_eq!(
+            commit_saver.prepare_commit_entry_as_string(Path::new("/test/path")),
+            format!("| {:} | #size/m |\n", commit_saver.entry.message)
+        );
+        assert!(commit_saver
+            .prepare_frontmatter_tags()
+            .contains(&"#size/m".to_string()));
+    }
+
+    #[test]
+    fn test_prepare_commit_entry_with_author_columns() {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.settings.table_columns = vec![TableColumn::Author, TableColumn::AuthorEmail];
+        commit_saver.entry.author = Some("Ada | Lovelace".to_string());
+        commit_saver.entry.author_email = Some("ada@example.com".to_string());
+        let path = PathBuf::from("/test/path");
+
+        assert_eq!(
+            commit_saver.prepare_commit_entry_as_string(&path),
+            "| Ada \\| Lovelace | ada@example.com |\n"
+        );
+
+        commit_saver.settings.entry_format = EntryFormat::Template;
+        commit_saver.settings.row_template = Some("- {{author}} <{{author_email}}>".to_string());
+        assert_eq!(
+            commit_saver.prepare_commit_entry_as_string(&path),
+            "- Ada | Lovelace <ada@example.com>\n"
+        );
+    }
+
+    #[test]
+    fn test_prepare_commit_entry_with_conventional_commit_template() {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.details.conventional = Some(ConventionalCommit {
+            kind: "fix".to_string(),
+            scope: Some("parser".to_string()),
+            breaking: true,
+            subject: "split on a \\| pipe".to_string(),
+        });
+        commit_saver.settings.entry_format = EntryFormat::Template;
+        commit_saver.settings.row_template = Some(
+            "- {{type}}{{#if scope}}({{scope}}){{/if}}{{#if breaking}} ⚠️{{/if}}: {{subject}}"
+                .to_string(),
+        );
+        let path = PathBuf::from("/test/path");
+
+        assert_eq!(
+            commit_saver.prepare_commit_entry_as_string(&path),
+            "- fix(parser) ⚠️: split on a | pipe\n"
+        );
+    }
+
+    #[test]
+    fn test_prepare_commit_entry_with_trailer_tags() {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.settings.table_columns = vec![TableColumn::CommitMessage];
+        commit_saver.details.trailer_tags = Some(TrailerTags {
+            tags: vec!["#project-x".to_string(), "#deep-work".to_string()],
+            placement: TagPlacement::Row,
+        });
+        let path = PathBuf::from("/test/path");
+
+        assert_eq!(
+            commit_saver.prepare_commit_entry_as_string(&path),
+            "| Test commit message #project-x #deep-work |\n"
+        );
+        assert!(!commit_saver
+            .prepare_frontmatter_tags()
+            .contains(&"#project-x".to_string()));
+
+        commit_saver.details.trailer_tags = Some(TrailerTags {
+            tags: vec!["#project-x".to_string()],
+            placement: TagPlacement::Frontmatter,
+        });
+        assert_eq!(
+            commit_saver.prepare_commit_entry_as_string(&path),
+            "| Test commit message |\n"
+        );
+        assert_eq!(
+            commit_saver.prepare_frontmatter_tags().last().unwrap(),
+            "#project-x"
+        );
+
+        commit_saver.settings.entry_format = EntryFormat::Template;
+        commit_saver.settings.row_template = Some("- {{message}} {{tags}}".to_string());
+        assert_eq!(
+            commit_saver.prepare_commit_entry_as_string(&path),
+            "- Test commit message #project-x\n"
+        );
+    }
+
+    #[test]
+    fn test_prepare_commit_entry_with_status_column() {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.settings.table_columns = vec![TableColumn::CommitHash, TableColumn::Status];
+        let path = PathBuf::from("/test/path");
+
+        assert_eq!(
+            commit_saver.prepare_commit_entry_as_string(&path),
+            "| abc123def456 |  |\n"
+        );
+        commit_saver.details.build_status = Some(BuildStatus::Pass);
+        assert_eq!(
+            commit_saver.prepare_commit_entry_as_string(&path),
+            "| abc123def456 | ✅ pass |\n"
+        );
+    }
+
+    #[test]
+    fn test_prepare_commit_entry_with_event_column() {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.details.entry_kind = EntryKind::StashPop;
+        commit_saver.settings.table_columns = vec![TableColumn::Time, TableColumn::Event];
+
+        let result = commit_saver.prepare_commit_entry_as_string(&PathBuf::from("/test/path"));
+
+        assert_eq!(result, "| 10:30:00 | stash pop |\n");
+    }
+
+    #[test]
+    fn test_timezone_annotates_time_column_and_frontmatter(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.settings.table_columns = vec![TableColumn::Time, TableColumn::CommitHash];
+        commit_saver.details.timezone = Some(EntryTimezone {
+            offset: chrono::FixedOffset::east_opt(11 * 3_600).unwrap(),
+            label: "AEDT".to_string(),
+        });
+
+        let result = commit_saver.prepare_commit_entry_as_string(&PathBuf::from("/test/path"));
+        assert_eq!(result, "| 21:30:00 AEDT | abc123def456 |\n");
+
+        let temp_dir = tempdir()?;
+        let diary = temp_dir.path().join("diary.md");
+        create_diary_file(diary.to_str().unwrap(), &mut commit_saver)?;
+        let content = fs::read_to_string(diary)?;
+        assert!(content.contains("timezone: 'AEDT'\ndate: "));
+        Ok(())
+    }
+
+    #[test]
+    fn test_prepare_commit_entry_as_yaml_block() {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.settings.entry_format = EntryFormat::Yaml;
+        commit_saver.entry.message = "feat: | \"quoted\" pipe\nC:\\path".to_string();
+        commit_saver.settings.table_columns = vec![
+            TableColumn::Time,
+            TableColumn::CommitMessage,
+            TableColumn::CommitHash,
+            TableColumn::Event,
+        ];
+
+        let result = commit_saver.prepare_commit_entry_as_string(&PathBuf::from("/test/path"));
+
+        assert_eq!(
+            result,
+            "```yaml\n\
+             datetime: \"2023-12-25T10:30:00+00:00\"\n\
+             commit_message: \"feat: | \\\"quoted\\\" pipe\\nC:\\\\path\"\n\
+             commit_hash: \"abc123def456\"\n\
+             event: \"commit\"\n\
+             ```\n\n"
+        );
+        assert_eq!(
+            EntryFormat::Yaml.header(&commit_saver.settings.table_columns),
+            ""
+        );
+        assert_eq!(EntryFormat::from_name(" Table "), Some(EntryFormat::Table));
+        assert_eq!(EntryFormat::from_name("json"), None);
+    }
+
+    #[test]
+    fn test_prepare_commit_entry_as_dataview_bullet() -> Result<(), Box<dyn std::error::Error>> {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.settings.entry_format = EntryFormat::Dataview;
+        commit_saver.entry.message = "feat: a | b\nDetails".to_string();
+        commit_saver.settings.block_refs = true;
+
+        let result = commit_saver.prepare_commit_entry_as_string(&PathBuf::from("/test/path"));
+
+        assert_eq!(
+            result,
+            "- [time:: 10:30:00] [repo:: repo] [branch:: main] [hash:: abc123def456] feat: a | b<br/>Details ^abc123d\n"
+        );
+        assert_eq!(
+            EntryFormat::Dataview.header(&commit_saver.settings.table_columns),
+            ""
+        );
+        assert_eq!(
+            EntryFormat::from_name("DataView"),
+            Some(EntryFormat::Dataview)
+        );
+
+        let temp_dir = tempdir()?;
+        let diary = temp_dir.path().join("diary.md");
+        fs::write(&diary, &result)?;
+        assert!(commit_saver.is_logged_in(&diary)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_prepare_commit_entry_from_template() -> Result<(), Box<dyn std::error::Error>> {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.settings.entry_format = EntryFormat::Template;
+        commit_saver.entry.repository_url = "git@github.com:test/repo.git".to_string();
+        commit_saver.entry.message = "feat: a | b\nDetails".to_string();
+        commit_saver.settings.row_template = Some(
+            "- {{time}} [{{message}}]({{repo_url}}/commit/{{hash}}) on {{branch}} `{{short_hash}}`"
+                .to_string(),
+        );
+
+        let row = commit_saver.prepare_commit_entry_as_string(&PathBuf::from("/test/path"));
+        assert_eq!(
+            row,
+            "- 10:30:00 [feat: a | b](https://github.com/test/repo/commit/abc123def456) on main `abc123d`\n"
+        );
+        assert_eq!(
+            EntryFormat::Template.header(&commit_saver.settings.table_columns),
+            ""
+        );
+
+        let temp_dir = tempdir()?;
+        let diary = temp_dir.path().join("2023-12-25.md");
+        fs::write(&diary, format!("# 2023-12-25\n{row:}"))?;
+        assert!(commit_saver.is_logged_in(&diary)?);
+
+        commit_saver.settings.row_template = Some("{{#if}}".to_string());
+        assert_eq!(
+            commit_saver.prepare_commit_entry_as_string(&PathBuf::from("/test/path")),
+            "- 10:30:00 feat: a | b\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_prepare_commit_entry_from_template_with_block_id() {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.settings.entry_format = EntryFormat::Template;
+        commit_saver.settings.row_template = Some("- {{message}} {{block_id}}".to_string());
+
+        let row = commit_saver.prepare_commit_entry_as_string(&PathBuf::from("/test/path"));
+        let entry_id = EntryIdStyle::Base32.entry_id("abc123def456", commit_saver.entry.datetime);
+        assert_eq!(
+            row,
+            format!("- {:} ^{entry_id:}\n", commit_saver.entry.message)
+        );
+        assert_eq!(
+            commit_saver.prepare_commit_entry_as_string(&PathBuf::from("/test/path")),
+            row
+        );
+
+        commit_saver.settings.entry_id_style = EntryIdStyle::ShortHash;
+        commit_saver.settings.row_template = Some("{{entry_id}}".to_string());
+        assert_eq!(
+            commit_saver.prepare_commit_entry_as_string(&PathBuf::from("/test/path")),
+            "rcs-abc123d\n"
+        );
+    }
+
+    #[test]
+    fn test_prepare_commit_entry_with_block_anchor() {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.settings.table_columns = vec![TableColumn::Time, TableColumn::CommitHash];
+        commit_saver.settings.block_refs = true;
+
+        let row = commit_saver.prepare_commit_entry_as_string(&PathBuf::from("/test/path"));
+        assert_eq!(row, "| 10:30:00 | abc123def456 | ^abc123d\n");
+        let table =
+            parse_diary_table(&format!("| TIME | COMMIT HASH |\n|---|---|\n{row:}")).unwrap();
+        assert_eq!(
+            table.cell(&table.rows[0], TableColumn::CommitHash),
+            Some("abc123def456")
+        );
+
+        commit_saver.entry.hash = String::new();
+        assert_eq!(
+            commit_saver
+                .renderer(Path::new("/test/path"))
+                .block_anchor(&commit_saver.entry),
+            None
+        );
+        commit_saver.settings.block_refs = false;
+        commit_saver.entry.hash = "abc123def456".to_string();
+        assert_eq!(
+            commit_saver
+                .renderer(Path::new("/test/path"))
+                .block_anchor(&commit_saver.entry),
+            None
+        );
+    }
+
+    #[test]
+    fn test_prepare_commit_entry_windows_folder_style() {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.settings.table_columns = vec![TableColumn::Folder, TableColumn::CommitHash];
+        commit_saver.settings.wsl_paths = Some(WslPaths {
+            folder_style: FolderStyle::Windows,
+            ..WslPaths::default()
+        });
+
+        assert_eq!(
+            commit_saver.prepare_commit_entry_as_string(&PathBuf::from("/mnt/c/src/api")),
+            "| C:\\src\\api | abc123def456 |\n"
+        );
+    }
+
+    #[test]
+    fn test_prepare_commit_entry_links_hash_to_forge() -> Result<(), Box<dyn std::error::Error>> {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.settings.table_columns = vec![TableColumn::CommitHash];
+        commit_saver.entry.repository_url = "git@github.com:test/repo.git".to_string();
+        commit_saver.settings.forge_links = Some(ForgeResolver::default());
+
+        let row = commit_saver.prepare_commit_entry_as_string(&PathBuf::from("/test/path"));
+        assert_eq!(
+            row,
+            "| [abc123def456](https://github.com/test/repo/commit/abc123def456) |\n"
+        );
+
+        let temp_dir = tempdir()?;
+        let diary = temp_dir.path().join("2023-12-25.md");
+        fs::write(&diary, format!("| COMMIT HASH |\n|---|\n{row:}"))?;
+        assert!(commit_saver.is_logged_in(&diary)?);
+
+        commit_saver.entry.repository_url = "https://example.org/test/repo.git".to_string();
+        assert_eq!(
+            commit_saver.prepare_commit_entry_as_string(&PathBuf::from("/test/path")),
+            "| abc123def456 |\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_row_template_gets_the_branch_and_pull_request_links() {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.entry.repository_url = "git@git.example.com:team/repo.git".to_string();
+        commit_saver.entry.branch = "feature/x".to_string();
+        commit_saver.entry.message = "fix: parse dates (#42)".to_string();
+        commit_saver.settings.entry_format = EntryFormat::Template;
+        commit_saver.settings.row_template =
+            Some("- {{branch_url}} {{pull_request_url}}".to_string());
+        let mut forges = ForgeResolver::default();
+        forges.set_host("git.example.com", ForgeTemplates::preset("gitea").unwrap());
+        commit_saver.settings.forge_links = Some(forges);
+
+        assert_eq!(
+            commit_saver.prepare_commit_entry_as_string(&PathBuf::from("/test/path")),
+            "- https://git.example.com/team/repo/src/branch/feature/x https://git.example.com/team/repo/pulls/42\n"
+        );
+
+        commit_saver.settings.forge_links = None;
+        assert_eq!(
+            commit_saver.prepare_commit_entry_as_string(&PathBuf::from("/test/path")),
+            "-  \n"
+        );
+    }
+
+    #[test]
+    fn test_set_duration_since_caps_and_skips() {
+        let mut commit_saver = create_test_commit_saver();
+        let commit_time = commit_saver.entry.datetime;
+
+        commit_saver.set_duration_since(
+            Some(commit_time - TimeDelta::minutes(50)),
+            TimeDelta::hours(2),
+        );
+        assert_eq!(commit_saver.details.duration, Some(TimeDelta::minutes(50)));
+
+        commit_saver
+            .set_duration_since(Some(commit_time - TimeDelta::days(1)), TimeDelta::hours(2));
+        assert_eq!(commit_saver.details.duration, Some(TimeDelta::hours(2)));
+
+        commit_saver
+            .set_duration_since(Some(commit_time + TimeDelta::hours(1)), TimeDelta::hours(2));
+        assert_eq!(commit_saver.details.duration, None);
+
+        commit_saver.set_duration_since(None, TimeDelta::hours(2));
+        assert_eq!(commit_saver.details.duration, None);
+
+        commit_saver.details.entry_kind = EntryKind::Wip;
+        commit_saver.set_duration_since(
+            Some(commit_time - TimeDelta::minutes(5)),
+            TimeDelta::hours(2),
+        );
+        assert_eq!(commit_saver.details.duration, None);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(TimeDelta::seconds(30)), "0m");
+        assert_eq!(format_duration(TimeDelta::minutes(45)), "45m");
+        assert_eq!(format_duration(TimeDelta::minutes(125)), "2h 05m");
+    }
+
+    #[test]
+    fn test_prepare_commit_entry_with_duration_column() {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.settings.table_columns = vec![TableColumn::CommitHash, TableColumn::Duration];
+
+        let without = commit_saver.prepare_commit_entry_as_string(&PathBuf::from("/p"));
+        commit_saver.details.duration = Some(TimeDelta::minutes(90));
+        let with = commit_saver.prepare_commit_entry_as_string(&PathBuf::from("/p"));
+
+        assert_eq!(without, "| abc123def456 |  |\n");
+        assert_eq!(with, "| abc123def456 | 1h 30m |\n");
+    }
+
+    #[test]
+    fn test_prepare_path_for_commit_normalizes_to_nfc() {
+        let mut commit_saver = create_test_commit_saver();
+        commit_saver.entry.repository_url = "https://github.com/test/cafe\u{301}.git".to_string();
+
+        let path = commit_saver
+            .prepare_path_for_commit(&PathBuf::from("📅 Diaries/Ma\u{308}rz/{repo}"), "%F.md");
+
+        assert_eq!(path, "/📅 Diaries/M\u{e4}rz/caf\u{e9}/2023-12-25.md");
     }
 
     // US-02: CommitSaver construction error branches
@@ -1084,11 +3643,13 @@ mod commit_saver_tests {
         let temp_dir = tempdir().unwrap();
         let repo = Repository::init(temp_dir.path()).unwrap();
 
-        // Repository with no commits → no HEAD
-        // from_repo should hit git_repo.head()? error arm
+        // Repository with no commits → unborn HEAD
         let result = CommitSaver::from_repo(&repo);
 
-        assert!(result.is_err(), "from_repo should error on no-HEAD repo");
+        assert!(
+            matches!(result, Err(CommitError::EmptyRepository)),
+            "from_repo should report an empty repository on an unborn HEAD"
+        );
     }
 
     #[test]
@@ -1109,15 +3670,14 @@ mod commit_saver_tests {
         // Detach HEAD by pointing directly to the commit
         repo.set_head_detached(commit_oid).unwrap();
 
-        // from_repo should succeed with detached HEAD and record "HEAD" as branch name
-        // (head.shorthand() returns Some("HEAD") for detached HEAD, not None)
         let saver =
             CommitSaver::from_repo(&repo).expect("from_repo should succeed on detached HEAD");
 
         assert_eq!(
-            saver.commit_branch_name, "HEAD",
-            "detached HEAD should record 'HEAD' as branch name"
+            saver.entry.branch, DETACHED_BRANCH_NAME,
+            "detached HEAD should record '(detached)' as branch name"
         );
+        assert_eq!(head_branch_name(&repo), DETACHED_BRANCH_NAME);
     }
 
     #[test]
@@ -1146,7 +3706,7 @@ mod commit_saver_tests {
         let result = CommitSaver::try_discover(non_repo_dir.path());
 
         assert!(
-            result.is_err(),
+            matches!(result, Err(CommitError::NotARepository(_))),
             "try_discover should fail when path is not in a git repository"
         );
     }
@@ -0,0 +1,233 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::Duration;
+
+use handlebars::Handlebars;
+use hmac::Hmac;
+use hmac::Mac;
+use log::info;
+use serde_json::json;
+use sha2::Sha256;
+
+use crate::links::vault_relative_path;
+use crate::paths::repository_name_from_url;
+use crate::vim_commit::CommitSaver;
+
+/// Header carrying the HMAC-SHA256 signature of the payload, as
+/// `sha256=<hex digest>`.
+pub const SIGNATURE_HEADER: &str = "X-Rcs-Signature-256";
+
+/// Payload sent when `[webhook]` has no `payload` template.
+pub const DEFAULT_PAYLOAD_TEMPLATE: &str = r#"{"repo": "{{repo}}", "repo_url": "{{repo_url}}", "branch": "{{branch}}", "hash": "{{hash}}", "author": "{{author}}", "author_email": "{{author_email}}", "message": "{{message}}", "datetime": "{{datetime}}", "vault_path": "{{vault_path}}"}"#;
+
+/// How long a webhook request may take before it is given up.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An outbound webhook receiving every logged commit, e.g. to start an n8n or
+/// Zapier automation.
+///
+/// The JSON payload is rendered from a Handlebars template whose values are
+/// JSON-escaped, so `"{{message}}"` is always a valid string. With a secret,
+/// the payload is signed with HMAC-SHA256 in [`SIGNATURE_HEADER`].
+///
+/// # Template Variables
+///
+/// - `repo`, `repo_url` - Repository name and remote URL
+/// - `branch`, `hash`, `short_hash` - Branch and commit hash
+/// - `author`, `author_email` - Commit author
+/// - `message` - Full commit message
+/// - `datetime` - Commit time, RFC 3339
+/// - `vault_path` - Diary note, relative to the vault root
+/// - `diary_path` - Diary note, absolute
+///
+/// # Configuration
+///
+/// ```text
+/// [webhook]
+/// url = https://n8n.example.com/webhook/commits
+/// payload = {"text": "{{repo}}: {{message}}", "note": "{{vault_path}}"}
+/// secret_env = RCS_WEBHOOK_SECRET
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct Webhook {
+    /// Where the payload is `POST`ed.
+    pub url: String,
+
+    /// Handlebars template of the JSON payload.
+    pub payload_template: String,
+
+    /// Key of the HMAC-SHA256 signature. `None` sends unsigned payloads.
+    pub secret: Option<String>,
+}
+
+impl fmt::Debug for Webhook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Webhook")
+            .field("url", &self.url)
+            .field("payload_template", &self.payload_template)
+            .field("secret", &self.secret.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+impl Webhook {
+    /// Renders the payload of a logged entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the template cannot be rendered or does not render
+    /// valid JSON.
+    pub fn render_payload(
+        &self,
+        commit_saver: &CommitSaver,
+        vault_root: &Path,
+        diary_path: &Path,
+    ) -> Result<String, Box<dyn Error>> {
+        let mut handlebars = Handlebars::new();
+        handlebars.register_escape_fn(json_escape);
+
+        let payload = handlebars.render_template(
+            &self.payload_template,
+            &json!({
+                "repo": repository_name_from_url(&commit_saver.entry.repository_url),
+                "repo_url": commit_saver.entry.repository_url,
+                "branch": commit_saver.entry.branch,
+                "author": commit_saver.entry.author.as_deref().unwrap_or_default(),
+                "author_email": commit_saver.entry.author_email.as_deref().unwrap_or_default(),
+                "hash": commit_saver.entry.hash,
+                "short_hash": commit_saver.entry.hash.get(..7).unwrap_or(&commit_saver.entry.hash),
+                "message": commit_saver.entry.message,
+                "datetime": commit_saver.entry.datetime.to_rfc3339(),
+                "vault_path": vault_relative_path(vault_root, diary_path).unwrap_or_default(),
+                "diary_path": diary_path.display().to_string(),
+            }),
+        )?;
+        serde_json::from_str::<serde_json::Value>(&payload)
+            .map_err(|e| format!("The webhook payload is not valid JSON: {e:}"))?;
+        Ok(payload)
+    }
+
+    /// `POST`s `payload` to the webhook URL, signed when a secret is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the receiver answers with an
+    /// error status.
+    pub fn send(&self, payload: &str) -> Result<(), Box<dyn Error>> {
+        info!("[Webhook::send()]: Posting the entry to: {:}", self.url);
+        let mut request = ureq::post(&self.url)
+            .config()
+            .timeout_global(Some(WEBHOOK_TIMEOUT))
+            .build()
+            .header("Content-Type", "application/json")
+            .header("User-Agent", env!("CARGO_PKG_NAME"));
+        if let Some(secret) = &self.secret {
+            request = request.header(SIGNATURE_HEADER, &sign_payload(secret, payload));
+        }
+        request.send(payload)?;
+        Ok(())
+    }
+}
+
+/// Signs `payload` with HMAC-SHA256, as `sha256=<hex digest>`, the format of
+/// [`SIGNATURE_HEADER`].
+///
+/// # Panics
+///
+/// Never in practice: HMAC accepts keys of any length.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::webhook::sign_payload;
+///
+/// assert!(sign_payload("secret", "{}").starts_with("sha256="));
+/// ```
+#[must_use]
+pub fn sign_payload(secret: &str, payload: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(payload.as_bytes());
+
+    let mut signature = String::from("sha256=");
+    for byte in mac.finalize().into_bytes() {
+        let _ = write!(signature, "{byte:02x}");
+    }
+    signature
+}
+
+/// Escapes a value for the inside of a JSON string.
+#[must_use]
+pub fn json_escape(value: &str) -> String {
+    let quoted = serde_json::Value::String(value.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod webhook_tests {
+    use super::*;
+    use crate::testing::FakeRepo;
+    use chrono::TimeZone;
+    use chrono::Utc;
+
+    fn webhook(payload_template: &str) -> Webhook {
+        Webhook {
+            url: "http://127.0.0.1:9/hook".to_string(),
+            payload_template: payload_template.to_string(),
+            secret: Some("s3cret".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_sign_payload_matches_rfc_4231() {
+        // RFC 4231, test case 2.
+        assert_eq!(
+            sign_payload("Jefe", "what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_render_payload_escapes_values() {
+        let repo = FakeRepo::new()
+            .unwrap()
+            .with_origin("git@github.com:user/api.git")
+            .unwrap();
+        repo.commit_at(
+            "fix: \"quotes\" | pipes\n\nBody",
+            Utc.with_ymd_and_hms(2025, 1, 14, 10, 30, 0).unwrap(),
+        )
+        .unwrap();
+        let commit_saver = repo.commit_saver().unwrap();
+
+        let payload = webhook(DEFAULT_PAYLOAD_TEMPLATE)
+            .render_payload(
+                &commit_saver,
+                Path::new("/vault"),
+                Path::new("/vault/Diaries/2025-01-14.md"),
+            )
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&payload).unwrap();
+
+        assert_eq!(value["repo"], "api");
+        assert_eq!(value["author"], crate::testing::FAKE_AUTHOR_NAME);
+        assert_eq!(value["message"], "fix: \"quotes\" | pipes\nBody");
+        assert_eq!(value["datetime"], "2025-01-14T10:30:00+00:00");
+        assert_eq!(value["vault_path"], "Diaries/2025-01-14.md");
+
+        assert!(webhook("{\"text\": {{message}}}")
+            .render_payload(&commit_saver, Path::new("/vault"), Path::new("/vault/a.md"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_debug_redacts_the_secret() {
+        let debug = format!("{:?}", webhook("{}"));
+
+        assert!(debug.contains("<redacted>"));
+        assert!(!debug.contains("s3cret"));
+    }
+}
@@ -0,0 +1,144 @@
+use std::error::Error;
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::DateTime;
+use chrono::Utc;
+
+use crate::fan_out::PendingAppend;
+use crate::goals::weekly_note_path;
+use crate::vim_commit::CommitSaver;
+use crate::vim_commit::EntryFormat;
+
+/// Default row of the weekly log: one compact line per commit.
+pub const DEFAULT_WEEKLY_ROW_TEMPLATE: &str =
+    "- {{date}} {{time}} **{{repo}}** {{message}} (`{{short_hash}}`)";
+
+/// A second, compact row written to the weekly note for every logged commit,
+/// next to the row of the daily diary.
+///
+/// Weekly reviews then read straight from the weekly note, without a rollup
+/// job rebuilding it from the daily files. The row is rendered from its own
+/// Handlebars template, with the variables of `[templates] row_template`.
+///
+/// # Configuration
+///
+/// ```text
+/// [weekly_log]
+/// enabled = true
+/// note = Diaries/Weekly/%G-W%V.md
+/// row_template = - {{date}} {{time}} **{{repo}}** {{message}}
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeeklyLog {
+    /// Chrono format of the weekly note path, relative to the vault root.
+    pub note_template: String,
+
+    /// Handlebars template of the weekly row.
+    pub row_template: String,
+}
+
+impl WeeklyLog {
+    /// Returns the weekly note of the ISO week `datetime` falls in.
+    #[must_use]
+    pub fn note_path(&self, obsidian_root_path_dir: &Path, datetime: DateTime<Utc>) -> PathBuf {
+        weekly_note_path(obsidian_root_path_dir, &self.note_template, datetime)
+    }
+
+    /// Renders the weekly row of an entry from [`row_template`](Self::row_template).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the folder of the entry cannot be determined.
+    pub fn render_row(&self, commit_saver: &CommitSaver) -> Result<String, Box<dyn Error>> {
+        let mut weekly_entry = commit_saver.clone();
+        weekly_entry.settings.entry_format = EntryFormat::Template;
+        weekly_entry.settings.row_template = Some(self.row_template.clone());
+        weekly_entry.render_entry()
+    }
+
+    /// Renders the weekly row of an entry without writing it, for a save
+    /// writing to several notes at once (see [`commit_appends()`](crate::fan_out::commit_appends)).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the row cannot be rendered.
+    pub fn stage_row(
+        &self,
+        obsidian_root_path_dir: &Path,
+        commit_saver: &CommitSaver,
+    ) -> Result<PendingAppend, Box<dyn Error>> {
+        let week = commit_saver.entry.datetime.format("%G-W%V");
+        Ok(PendingAppend {
+            note_path: self.note_path(obsidian_root_path_dir, commit_saver.entry.datetime),
+            header: format!("# {week:}\n\n"),
+            text: self.render_row(commit_saver)?,
+        })
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod weekly_log_tests {
+    use super::*;
+    use crate::goals::DEFAULT_WEEKLY_NOTE_TEMPLATE;
+    use crate::testing::FakeRepo;
+    use crate::testing::TempVault;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_staged_row_creates_then_appends_to_the_weekly_note() -> Result<(), Box<dyn Error>> {
+        let repo = FakeRepo::new()?.with_origin("git@github.com:user/api.git")?;
+        repo.commit_at(
+            "feat: add feature\n\nDetails",
+            Utc.with_ymd_and_hms(2025, 1, 14, 10, 30, 0).unwrap(),
+        )?;
+        let commit_saver = repo.commit_saver()?;
+        let vault = TempVault::new()?;
+        let weekly_log = WeeklyLog {
+            note_template: DEFAULT_WEEKLY_NOTE_TEMPLATE.to_string(),
+            row_template: DEFAULT_WEEKLY_ROW_TEMPLATE.to_string(),
+        };
+
+        let append = weekly_log.stage_row(vault.root(), &commit_saver)?;
+        append.write()?;
+        append.write()?;
+        let note_path = append.note_path;
+
+        assert_eq!(note_path, vault.root().join("Diaries/Weekly/2025-W03.md"));
+        let row = format!(
+            "- 2025-01-14 10:30:00 **api** feat: add feature (`{:}`)\n",
+            &commit_saver.entry.hash[..7]
+        );
+        assert_eq!(
+            vault.read_note("Diaries/Weekly/2025-W03.md")?,
+            format!("# 2025-W03\n\n{row:}{row:}")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_staged_row_keeps_an_existing_note() -> Result<(), Box<dyn Error>> {
+        let repo = FakeRepo::new()?;
+        repo.commit_at(
+            "fix: bug",
+            Utc.with_ymd_and_hms(2025, 1, 14, 10, 30, 0).unwrap(),
+        )?;
+        let vault = TempVault::new()?;
+        vault.write_note("Weekly/2025-W03.md", "---\ngoal: 20\n---\n")?;
+        let weekly_log = WeeklyLog {
+            note_template: "Weekly/%G-W%V.md".to_string(),
+            row_template: "- {{message}}".to_string(),
+        };
+
+        weekly_log
+            .stage_row(vault.root(), &repo.commit_saver()?)?
+            .write()?;
+
+        assert_eq!(
+            vault.read_note("Weekly/2025-W03.md")?,
+            "---\ngoal: 20\n---\n- fix: bug\n"
+        );
+        Ok(())
+    }
+}
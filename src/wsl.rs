@@ -0,0 +1,288 @@
+use std::env;
+use std::fs;
+
+use log::info;
+
+/// Where drive letters of the Windows side are mounted inside WSL.
+pub const DEFAULT_MOUNT_ROOT: &str = "/mnt";
+
+/// The environment paths are translated for.
+///
+/// # Variants
+///
+/// - `Wsl` - Linux inside WSL: `C:\...` becomes `/mnt/c/...`
+/// - `Windows` - Native Windows: `/mnt/c/...` becomes `C:\...`
+/// - `Other` - Anything else: paths are left alone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathEnvironment {
+    Wsl,
+    Windows,
+    Other,
+}
+
+impl PathEnvironment {
+    /// Detects the environment the binary runs in.
+    ///
+    /// WSL is recognized by the `WSL_DISTRO_NAME` variable it sets, or by the
+    /// `microsoft` kernel release of WSL 1 and 2.
+    #[must_use]
+    pub fn current() -> Self {
+        if cfg!(windows) {
+            return PathEnvironment::Windows;
+        }
+        let wsl_kernel = fs::read_to_string("/proc/sys/kernel/osrelease")
+            .is_ok_and(|release| release.to_lowercase().contains("microsoft"));
+        if env::var_os("WSL_DISTRO_NAME").is_some() || wsl_kernel {
+            PathEnvironment::Wsl
+        } else {
+            PathEnvironment::Other
+        }
+    }
+}
+
+/// How the `FOLDER` cell shows the directory an entry was logged from.
+///
+/// # Variants
+///
+/// - `Native` - As the running system sees it (default)
+/// - `Windows` - WSL mounts as Windows paths (`/mnt/c/src` → `C:\src`), so
+///   the diary reads the same from both sides
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FolderStyle {
+    #[default]
+    Native,
+    Windows,
+}
+
+impl FolderStyle {
+    /// Parses the `[wsl] folder_style` value (`native` or `windows`, any case).
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "native" => Some(FolderStyle::Native),
+            "windows" => Some(FolderStyle::Windows),
+            _ => None,
+        }
+    }
+
+    /// Renders a `FOLDER` cell in this style. Paths outside the WSL mounts of
+    /// `mount_root` are kept as they are.
+    #[must_use]
+    pub fn folder(self, folder: &str, mount_root: &str) -> String {
+        match self {
+            FolderStyle::Native => folder.to_string(),
+            FolderStyle::Windows => {
+                wsl_to_windows(folder, mount_root).unwrap_or_else(|| folder.to_string())
+            }
+        }
+    }
+}
+
+/// Translation of configured paths between WSL and Windows, so one config
+/// file works on both sides of a vault kept on the other one.
+///
+/// # Configuration
+///
+/// ```text
+/// [wsl]
+/// translate = true
+/// mount_root = /mnt
+/// folder_style = windows
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WslPaths {
+    /// Whether configured paths are translated (`[wsl] translate`).
+    pub translate: bool,
+
+    /// Where WSL mounts the Windows drives (`[wsl] mount_root`).
+    pub mount_root: String,
+
+    /// How the `FOLDER` cell is written (`[wsl] folder_style`).
+    pub folder_style: FolderStyle,
+}
+
+impl Default for WslPaths {
+    fn default() -> Self {
+        WslPaths {
+            translate: false,
+            mount_root: DEFAULT_MOUNT_ROOT.to_string(),
+            folder_style: FolderStyle::Native,
+        }
+    }
+}
+
+impl WslPaths {
+    /// Translates a configured path for `environment`, see [`translate_path()`].
+    ///
+    /// Paths are left alone when translation is off.
+    #[must_use]
+    pub fn native_path(&self, path: &str, environment: PathEnvironment) -> String {
+        if !self.translate {
+            return path.to_string();
+        }
+        let translated = translate_path(path, environment, &self.mount_root);
+        if translated != path {
+            info!("[WslPaths::native_path()]: Translated {path:} to {translated:}");
+        }
+        translated
+    }
+}
+
+/// Translates a path to the form `environment` can open.
+///
+/// Inside WSL, Windows paths (`C:\Users\me`, `C:/Users/me`) become mount
+/// paths (`/mnt/c/Users/me`); on Windows, mount paths become Windows paths.
+/// Anything else, including every path in [`PathEnvironment::Other`], is
+/// returned unchanged.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rusty_commit_saver::wsl::{translate_path, PathEnvironment};
+///
+/// assert_eq!(
+///     translate_path(r"C:\Users\me\Vault", PathEnvironment::Wsl, "/mnt"),
+///     "/mnt/c/Users/me/Vault"
+/// );
+/// ```
+#[must_use]
+pub fn translate_path(path: &str, environment: PathEnvironment, mount_root: &str) -> String {
+    let translated = match environment {
+        PathEnvironment::Wsl => windows_to_wsl(path, mount_root),
+        PathEnvironment::Windows => wsl_to_windows(path, mount_root),
+        PathEnvironment::Other => None,
+    };
+    translated.unwrap_or_else(|| path.to_string())
+}
+
+/// Converts a Windows path (`C:\Users\me` or `C:/Users/me`) to its WSL mount
+/// path (`/mnt/c/Users/me`).
+///
+/// # Returns
+///
+/// `None` if `path` does not start with a drive letter.
+#[must_use]
+pub fn windows_to_wsl(path: &str, mount_root: &str) -> Option<String> {
+    let mut chars = path.chars();
+    let drive = chars.next().filter(char::is_ascii_alphabetic)?;
+    if chars.next() != Some(':') {
+        return None;
+    }
+    let rest = chars.as_str();
+    if !(rest.is_empty() || rest.starts_with(['\\', '/'])) {
+        return None;
+    }
+
+    let mut wsl_path = format!(
+        "{:}/{:}",
+        mount_root.trim_end_matches('/'),
+        drive.to_ascii_lowercase()
+    );
+    for segment in rest
+        .split(['\\', '/'])
+        .filter(|segment| !segment.is_empty())
+    {
+        wsl_path.push('/');
+        wsl_path.push_str(segment);
+    }
+    Some(wsl_path)
+}
+
+/// Converts a WSL mount path (`/mnt/c/Users/me`) to its Windows path
+/// (`C:\Users\me`).
+///
+/// # Returns
+///
+/// `None` if `path` is not under a drive of `mount_root`.
+#[must_use]
+pub fn wsl_to_windows(path: &str, mount_root: &str) -> Option<String> {
+    let rest = path.strip_prefix(mount_root.trim_end_matches('/'))?;
+    let rest = rest.strip_prefix('/')?;
+    let (drive, rest) = rest.split_once('/').unwrap_or((rest, ""));
+    let mut drive_chars = drive.chars();
+    let letter = drive_chars.next().filter(char::is_ascii_alphabetic)?;
+    if drive_chars.next().is_some() {
+        return None;
+    }
+
+    let segments = rest
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    Some(format!(
+        "{:}:\\{:}",
+        letter.to_ascii_uppercase(),
+        segments.join("\\")
+    ))
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod wsl_tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_to_wsl() {
+        assert_eq!(
+            windows_to_wsl(r"C:\Users\me\Vault\", "/mnt").as_deref(),
+            Some("/mnt/c/Users/me/Vault")
+        );
+        assert_eq!(
+            windows_to_wsl("D:/Notes", "/mnt/").as_deref(),
+            Some("/mnt/d/Notes")
+        );
+        assert_eq!(windows_to_wsl("E:", "/").as_deref(), Some("/e"));
+        assert_eq!(windows_to_wsl("/home/me", "/mnt"), None);
+        assert_eq!(windows_to_wsl("C:relative", "/mnt"), None);
+    }
+
+    #[test]
+    fn test_wsl_to_windows() {
+        assert_eq!(
+            wsl_to_windows("/mnt/c/Users/me/Vault", "/mnt").as_deref(),
+            Some(r"C:\Users\me\Vault")
+        );
+        assert_eq!(wsl_to_windows("/mnt/d", "/mnt/").as_deref(), Some(r"D:\"));
+        assert_eq!(wsl_to_windows("/mnt/wsl/data", "/mnt"), None);
+        assert_eq!(wsl_to_windows("/mntc/Users", "/mnt"), None);
+        assert_eq!(wsl_to_windows("/home/me", "/mnt"), None);
+    }
+
+    #[test]
+    fn test_native_path_and_folder_style() {
+        let paths = WslPaths {
+            translate: true,
+            ..WslPaths::default()
+        };
+
+        assert_eq!(
+            paths.native_path(r"C:\Vault", PathEnvironment::Wsl),
+            "/mnt/c/Vault"
+        );
+        assert_eq!(
+            paths.native_path("/mnt/c/Vault", PathEnvironment::Windows),
+            r"C:\Vault"
+        );
+        assert_eq!(
+            paths.native_path(r"C:\Vault", PathEnvironment::Other),
+            r"C:\Vault"
+        );
+        assert_eq!(
+            WslPaths::default().native_path(r"C:\Vault", PathEnvironment::Wsl),
+            r"C:\Vault"
+        );
+        assert_eq!(
+            FolderStyle::Windows.folder("/mnt/c/src/api", "/mnt"),
+            r"C:\src\api"
+        );
+        assert_eq!(
+            FolderStyle::Windows.folder("/home/me/api", "/mnt"),
+            "/home/me/api"
+        );
+        assert_eq!(
+            FolderStyle::from_name(" Windows "),
+            Some(FolderStyle::Windows)
+        );
+        assert_eq!(FolderStyle::from_name("posix"), None);
+    }
+}
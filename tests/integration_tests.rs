@@ -37,3 +37,44 @@ fn test_global_vars_full_integration_workflow() {
     assert_eq!(date_path, "%Y-%m-%d.md");
     assert_eq!(datetime, "%Y-%m-%d %H:%M");
 }
+
+#[test]
+fn test_fake_repo_commit_logged_into_temp_vault() {
+    use rusty_commit_saver::diary_parser::parse_diary_table;
+    use rusty_commit_saver::testing::{FakeRepo, TempVault};
+    use rusty_commit_saver::vim_commit::{
+        create_diary_file, create_directories_for_new_entry, TableColumn,
+    };
+
+    let vault = TempVault::new().unwrap();
+    let config_content = vault.config_ini("%Y/%F.md", "");
+    let config = rusty_commit_saver::config::parse_ini_content(&config_content).unwrap();
+    let global_vars = GlobalVars::new();
+    global_vars.config.set(config).unwrap();
+    global_vars.set_obsidian_vars();
+
+    let repo = FakeRepo::new()
+        .unwrap()
+        .with_origin("git@github.com:user/repo.git")
+        .unwrap();
+    let oid = repo.commit("feat: integration").unwrap();
+    let mut saver = repo.commit_saver().unwrap();
+
+    let relative = saver.prepare_path_for_commit(
+        &global_vars.get_obsidian_commit_path(),
+        &global_vars.get_template_commit_date_path(),
+    );
+    let diary = global_vars
+        .get_obsidian_root_path_dir()
+        .join(relative.trim_start_matches('/'));
+    create_directories_for_new_entry(&diary).unwrap();
+    create_diary_file(diary.to_str().unwrap(), &mut saver).unwrap();
+    saver.append_entry_to_diary(&diary).unwrap();
+
+    assert_eq!(vault.diary_files().unwrap(), vec![diary.clone()]);
+    let table = parse_diary_table(&fs::read_to_string(&diary).unwrap()).unwrap();
+    assert_eq!(
+        table.cell(&table.rows[0], TableColumn::CommitHash),
+        Some(oid.to_string().as_str())
+    );
+}